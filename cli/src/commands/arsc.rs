@@ -0,0 +1,158 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use apk_info::ARSC;
+use apk_info::artifact::Artifact;
+use apk_info::structs::ResTableConfig;
+use colored::Colorize;
+
+use crate::commands::output::Output;
+
+/// Parses resource id in hex (`0x7f110003`) or decimal form.
+pub(crate) fn parse_resource_id(value: &str) -> Result<u32, String> {
+    let parsed = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse::<u32>(),
+    };
+
+    parsed.map_err(|e| format!("invalid resource id {:?}: {}", value, e))
+}
+
+/// Returns the resource table of the apk or the table itself.
+fn resource_table<'a>(path: &Path, artifact: &'a Artifact) -> Result<&'a ARSC> {
+    match artifact {
+        Artifact::Arsc(arsc) => Ok(arsc),
+        Artifact::Apk(apk) => match apk.get_resource_table() {
+            Some(arsc) => Ok(arsc),
+            None => bail!("{:?} doesn't contain resources.arsc", path),
+        },
+        artifact => bail!(
+            "{:?} is {}, expected resources.arsc or apk",
+            path,
+            artifact.kind()
+        ),
+    }
+}
+
+pub(crate) fn command_arsc(path: &Path, id: &u32, all_configs: &bool, pager: &bool) -> Result<()> {
+    let artifact = Artifact::new(path).with_context(|| format!("can't parse {:?}", path))?;
+    let arsc = resource_table(path, &artifact)?;
+
+    let mut out = Output::new(*pager);
+
+    let name = arsc
+        .get_resource_name(*id)
+        .unwrap_or_else(|| "-".to_string());
+
+    if !*all_configs {
+        let value = arsc
+            .get_resource_value(*id)
+            .with_context(|| format!("resource 0x{:08x} not found", id))?;
+
+        writeln!(out, "0x{:08x} {} = {}", id, name.blue(), value.green())?;
+        return Ok(());
+    }
+
+    let values = arsc.get_resource_values_all_configs(*id);
+    if values.is_empty() {
+        bail!("resource 0x{:08x} not found", id);
+    }
+
+    writeln!(out, "0x{:08x} {}", id, name.blue())?;
+    for (config, value) in values {
+        writeln!(out, "  [{}] {}", qualifiers(config).yellow(), value.green())?;
+    }
+
+    Ok(())
+}
+
+/// Dumps packages, types and entries of the table in every config, like `aapt2 dump resources`.
+pub(crate) fn command_arsc_dump(
+    path: &Path,
+    type_name: Option<&str>,
+    name: Option<&str>,
+    pager: &bool,
+) -> Result<()> {
+    let artifact = Artifact::new(path).with_context(|| format!("can't parse {:?}", path))?;
+    let arsc = resource_table(path, &artifact)?;
+
+    let mut out = Output::new(*pager);
+
+    let resources = arsc.resources();
+    for package in arsc.packages() {
+        let package_id = (package.header.id & 0xff) as u8;
+        writeln!(
+            out,
+            "Package name={} id={:02x}",
+            package.header.name().green(),
+            package_id
+        )?;
+
+        for type_id in package.type_ids() {
+            let type_str = package.type_name(type_id).unwrap_or("-");
+            if type_name.is_some_and(|type_name| type_name != type_str) {
+                continue;
+            }
+
+            // resources are sorted by id, so the type is a contiguous range
+            let type_resources = resources.iter().filter(|resource| {
+                resource.id >> 16 == (u32::from(package_id) << 8) | u32::from(type_id)
+                    && name.is_none_or(|name| {
+                        resource
+                            .name
+                            .split_once('/')
+                            .is_some_and(|(_, entry)| entry.contains(name))
+                    })
+            });
+
+            let mut type_resources = type_resources.peekable();
+            if name.is_some() && type_resources.peek().is_none() {
+                continue;
+            }
+
+            writeln!(
+                out,
+                "  type {} id={:02x} entryCount={}",
+                type_str.blue(),
+                type_id,
+                package.entry_count(type_id)
+            )?;
+
+            for resource in type_resources {
+                let public = if resource.public { " PUBLIC" } else { "" };
+                writeln!(
+                    out,
+                    "    resource 0x{:08x} {}{}",
+                    resource.id,
+                    resource.name.blue(),
+                    public
+                )?;
+
+                for (config, value) in &resource.values {
+                    writeln!(
+                        out,
+                        "      [{}] {}",
+                        qualifiers(config).yellow(),
+                        value.green()
+                    )?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Qualifiers of the config, `default` for the default one.
+fn qualifiers(config: &ResTableConfig) -> String {
+    let qualifiers = config.as_string();
+    if qualifiers.is_empty() {
+        "default".to_string()
+    } else {
+        qualifiers
+    }
+}
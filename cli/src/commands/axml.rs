@@ -1,33 +1,41 @@
-use std::io::IsTerminal;
+use std::io::Write;
 use std::path::Path;
 
-use anyhow::{Context, Result};
-use apk_info::Apk;
-use apk_info_axml::AXML;
+use anyhow::{Context, Result, bail};
+use apk_info::artifact::ArtifactKind;
+use apk_info::{AXML, Apk};
 use bat::PrettyPrinter;
 
-pub(crate) fn command_axml(path: &Path) -> Result<()> {
-    let stdout_is_tty = std::io::stdout().is_terminal();
+use crate::commands::output::{Output, colors_enabled};
 
-    let xml = match Apk::new(path) {
-        Ok(apk) => apk.get_xml_string(),
-        Err(_) => {
-            // raw axml?
-            let file = std::fs::read(path)
-                .with_context(|| format!("can't open and read file: {:?}", path))?;
-            let axml = AXML::new(&mut &file[..], None)?;
+pub(crate) fn command_axml(path: &Path, pager: &bool) -> Result<()> {
+    let file =
+        std::fs::read(path).with_context(|| format!("can't open and read file: {:?}", path))?;
 
-            axml.get_xml_string()
+    let xml = match ArtifactKind::detect(&file) {
+        Some(ArtifactKind::Apk) => Apk::from_bytes(file)
+            .with_context(|| format!("can't parse apk: {:?}", path))?
+            .get_xml_string(),
+        Some(kind @ (ArtifactKind::Dex | ArtifactKind::Arsc)) => {
+            bail!("{:?} is {}, expected binary xml or apk", path, kind)
         }
+        // the header of raw axml is often tampered with, so unknown files are parsed too
+        Some(ArtifactKind::Axml) | None => AXML::new(&mut &file[..], None)?.get_xml_string(),
     };
 
-    let mut printer = PrettyPrinter::new();
-    printer.input_from_bytes(xml.as_bytes()).language("xml");
+    let mut out = Output::new(*pager);
 
-    if stdout_is_tty {
-        printer.print().unwrap();
+    if colors_enabled() {
+        let mut highlighted = String::with_capacity(xml.len() * 2);
+        PrettyPrinter::new()
+            .input_from_bytes(xml.as_bytes())
+            .language("xml")
+            .print_with_writer(Some(&mut highlighted))
+            .map_err(|e| anyhow::anyhow!("can't highlight xml: {}", e))?;
+
+        out.write_all(highlighted.as_bytes())?;
     } else {
-        print!("{}", xml);
+        out.write_all(xml.as_bytes())?;
     }
 
     Ok(())
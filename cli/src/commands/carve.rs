@@ -0,0 +1,40 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use apk_info::carve::carve;
+use colored::Colorize;
+
+pub(crate) fn command_carve(path: &Path, output: &Option<PathBuf>) -> Result<()> {
+    let data = std::fs::read(path).with_context(|| format!("can't open file: {:?}", path))?;
+    let candidates = carve(&data);
+
+    if candidates.is_empty() {
+        println!("{:?} - {}", path, "no apk files found".red());
+        return Ok(());
+    }
+
+    // ./<file_name>.carved by default
+    let out_dir = output.clone().unwrap_or_else(|| {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".carved");
+        PathBuf::from(name)
+    });
+
+    std::fs::create_dir_all(&out_dir)
+        .with_context(|| format!("can't create output directory {:?}", out_dir))?;
+
+    for (i, candidate) in candidates.iter().enumerate() {
+        let file_path = out_dir.join(format!("{i}.apk"));
+        std::fs::write(&file_path, &candidate.data)
+            .with_context(|| format!("can't write to {:?}", file_path))?;
+
+        println!(
+            "[*] carved {} ({} bytes) -> {:?}",
+            candidate.source.green().bold(),
+            candidate.data.len(),
+            file_path
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use apk_info::{Apk, CertificateInfo, FingerprintStyle, Signature};
+use colored::Colorize;
+use serde::Serialize;
+
+/// Signing certificate with the schemes it is used in.
+#[derive(Serialize)]
+struct SignerCertificate {
+    schemes: Vec<String>,
+
+    #[serde(flatten)]
+    certificate: CertificateInfo,
+}
+
+/// Prints the signing certificates of the apk and optionally saves them as PEM/DER files.
+///
+/// The same certificate is usually shared by all schemes, so it is listed once.
+pub(crate) fn command_certs(
+    path: &Path,
+    fingerprint_style: FingerprintStyle,
+    pem: &Option<PathBuf>,
+    der: &Option<PathBuf>,
+    json: &bool,
+) -> Result<()> {
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+
+    let mut certificates: Vec<SignerCertificate> = Vec::new();
+    for signature in apk.get_signatures_ref()? {
+        let signer_certificates = match signature {
+            Signature::V1(certificates)
+            | Signature::V2 { certificates, .. }
+            | Signature::V3 { certificates, .. }
+            | Signature::V31 { certificates, .. } => certificates,
+            _ => continue,
+        };
+
+        for certificate in signer_certificates {
+            match certificates
+                .iter_mut()
+                .find(|c| c.certificate.sha256_fingerprint == certificate.sha256_fingerprint)
+            {
+                Some(known) => known.schemes.push(signature.name()),
+                None => certificates.push(SignerCertificate {
+                    schemes: vec![signature.name()],
+                    certificate: certificate.clone(),
+                }),
+            }
+        }
+    }
+
+    if certificates.is_empty() {
+        bail!("no signing certificates: {:?}", path);
+    }
+
+    // file names use hex, it is safe for the file system in any style
+    let mut saved = Vec::new();
+    for (dir, extension) in [(pem, "pem"), (der, "der")] {
+        let Some(dir) = dir else {
+            continue;
+        };
+        std::fs::create_dir_all(dir).with_context(|| format!("can't create {:?}", dir))?;
+
+        for SignerCertificate { certificate, .. } in &certificates {
+            let output = dir.join(format!("{}.{extension}", certificate.sha256_fingerprint));
+            let data = match extension {
+                "pem" => certificate.to_pem().into_bytes(),
+                _ => certificate.der.clone(),
+            };
+            std::fs::write(&output, data).with_context(|| format!("can't write {:?}", output))?;
+            saved.push(output);
+        }
+    }
+
+    for SignerCertificate { certificate, .. } in &mut certificates {
+        certificate.md5_fingerprint = certificate.fingerprint_md5_formatted(fingerprint_style);
+        certificate.sha1_fingerprint = certificate.fingerprint_sha1_formatted(fingerprint_style);
+        certificate.sha256_fingerprint =
+            certificate.fingerprint_sha256_formatted(fingerprint_style);
+    }
+
+    if *json {
+        println!("{}", serde_json::to_string(&certificates)?);
+        return Ok(());
+    }
+
+    let width = certificates
+        .iter()
+        .map(|c| c.certificate.sha256_fingerprint.len())
+        .max()
+        .unwrap_or_default();
+    let schemes_width = certificates
+        .iter()
+        .map(|c| c.schemes.join(",").len())
+        .max()
+        .unwrap_or_default()
+        .max("schemes".len());
+
+    println!(
+        "{:<width$} {:<schemes_width$} {:<10} subject",
+        "sha256", "schemes", "expires"
+    );
+    for SignerCertificate {
+        schemes,
+        certificate,
+    } in &certificates
+    {
+        // `2050-01-01T00:00:00Z` -> `2050-01-01`
+        let expires = certificate
+            .valid_until
+            .split_once('T')
+            .map_or(certificate.valid_until.as_str(), |(date, _)| date);
+
+        let mut subject = certificate.subject.normal();
+        if certificate.is_debug_cert {
+            subject = format!("{} (debug)", certificate.subject).red();
+        }
+
+        println!(
+            "{:<width$} {:<schemes_width$} {:<10} {}",
+            certificate.sha256_fingerprint.green(),
+            schemes.join(","),
+            expires,
+            subject
+        );
+    }
+
+    for output in saved {
+        println!("[*] saved certificate to {:?}", output);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use apk_info::artifact::Artifact;
+use apk_info::dex::Dex;
+use colored::Colorize;
+use regex::Regex;
+use serde::Serialize;
+
+/// Items of dex files printed instead of the header.
+#[derive(Clone, Copy)]
+pub(crate) enum DexListing {
+    Strings,
+    Classes,
+    Methods,
+}
+
+impl DexListing {
+    fn items(self, dex: &Dex) -> Vec<String> {
+        match self {
+            DexListing::Strings => dex.strings().collect(),
+            DexListing::Classes => dex
+                .classes()
+                .iter()
+                .filter_map(|class| dex.get_class_name(class.class_idx))
+                .collect(),
+            DexListing::Methods => (0..dex.method_ids().len() as u32)
+                .filter_map(|idx| dex.get_method_signature(idx))
+                .collect(),
+        }
+    }
+}
+
+/// Header values and table sizes of a single dex file
+#[derive(Serialize)]
+struct DexInfo {
+    name: String,
+    version: Option<u32>,
+    file_size: u32,
+    checksum: String,
+    checksum_valid: bool,
+    signature: String,
+    signature_valid: bool,
+    strings: usize,
+    types: usize,
+    protos: usize,
+    fields: usize,
+    methods: usize,
+    classes: usize,
+}
+
+impl DexInfo {
+    fn new(name: &str, dex: &Dex) -> DexInfo {
+        let header = dex.header();
+        let verification = dex.verify();
+
+        DexInfo {
+            name: name.to_string(),
+            version: header.version(),
+            file_size: header.file_size,
+            checksum: format!("{:08x}", header.checksum),
+            checksum_valid: verification.is_checksum_valid(),
+            signature: header
+                .signature
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            signature_valid: verification.is_signature_valid(),
+            strings: dex.strings_count(),
+            types: dex.types_count(),
+            protos: dex.proto_ids().len(),
+            fields: dex.field_ids().len(),
+            methods: dex.method_ids().len(),
+            classes: dex.classes().len(),
+        }
+    }
+}
+
+/// Shows the header and counts of a standalone dex file or all dex files in the apk.
+///
+/// With `listing` prints the items of all dex files instead, duplicates across
+/// multidex files are printed once.
+pub(crate) fn command_dex(
+    path: &Path,
+    listing: Option<DexListing>,
+    filter: Option<&str>,
+    json: &bool,
+) -> Result<()> {
+    let dexes = match Artifact::new(path).with_context(|| format!("can't parse {:?}", path))? {
+        Artifact::Dex(dex) => {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            vec![(name.to_string(), dex)]
+        }
+        Artifact::Apk(apk) => apk
+            .dex_names()
+            .into_iter()
+            .map(|name| Ok((name.to_string(), apk.read_dex(name)?)))
+            .collect::<Result<Vec<_>>>()?,
+        artifact => bail!("{:?} is {}, expected dex or apk", path, artifact.kind()),
+    };
+
+    if let Some(listing) = listing {
+        return print_listing(&dexes, listing, filter, json);
+    }
+
+    let infos: Vec<DexInfo> = dexes
+        .iter()
+        .map(|(name, dex)| DexInfo::new(name, dex))
+        .collect();
+
+    if *json {
+        println!("{}", serde_json::to_string(&infos)?);
+        return Ok(());
+    }
+
+    for (i, info) in infos.iter().enumerate() {
+        println!("{}:", info.name.blue().bold());
+        println!(
+            "  Version: {}",
+            info.version
+                .map_or_else(|| "-".to_string(), |v| format!("{v:03}"))
+                .green()
+        );
+        println!("  File size: {}", info.file_size.to_string().green());
+        if info.checksum_valid {
+            println!("  Checksum: {}", info.checksum.green());
+        } else {
+            println!("  Checksum: {} {}", info.checksum.red(), "(mismatch)".red());
+        }
+        if info.signature_valid {
+            println!("  Signature: {}", info.signature.green());
+        } else {
+            println!(
+                "  Signature: {} {}",
+                info.signature.red(),
+                "(mismatch)".red()
+            );
+        }
+        println!("  Strings: {}", info.strings.to_string().green());
+        println!("  Types: {}", info.types.to_string().green());
+        println!("  Protos: {}", info.protos.to_string().green());
+        println!("  Fields: {}", info.fields.to_string().green());
+        println!("  Methods: {}", info.methods.to_string().green());
+        println!("  Classes: {}", info.classes.to_string().green());
+
+        if i != infos.len() - 1 {
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+fn print_listing(
+    dexes: &[(String, Dex)],
+    listing: DexListing,
+    filter: Option<&str>,
+    json: &bool,
+) -> Result<()> {
+    let filter = filter
+        .map(|filter| Regex::new(filter).with_context(|| format!("invalid regex: {:?}", filter)))
+        .transpose()?;
+
+    let mut seen = HashSet::new();
+    let items: Vec<String> = dexes
+        .iter()
+        .flat_map(|(_, dex)| listing.items(dex))
+        .filter(|item| filter.as_ref().is_none_or(|filter| filter.is_match(item)))
+        .filter(|item| seen.insert(item.clone()))
+        .collect();
+
+    if *json {
+        println!("{}", serde_json::to_string(&items)?);
+    } else {
+        for item in items {
+            println!("{item}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use apk_info::Apk;
+use apk_info::diff::{ApkDiffer, Change, ComponentDigest};
+use colored::Colorize;
+
+/// Compares two apks: manifest, signers and optionally files and classes.
+pub(crate) fn command_diff(
+    old_path: &Path,
+    new_path: &Path,
+    show_files: &bool,
+    show_classes: &bool,
+    json: &bool,
+) -> Result<()> {
+    let old = Apk::new(old_path).with_context(|| format!("can't parse apk: {:?}", old_path))?;
+    let new = Apk::new(new_path).with_context(|| format!("can't parse apk: {:?}", new_path))?;
+    let diff = ApkDiffer::compare(&old, &new)?;
+
+    if *json {
+        println!("{}", serde_json::to_string(&diff)?);
+        return Ok(());
+    }
+
+    if diff.is_empty() {
+        println!("{}", "no differences".green());
+        return Ok(());
+    }
+
+    print_change("Package Name", &diff.package_name, format_option);
+    print_change("Version Code", &diff.version_code, format_option);
+    print_change("Version Name", &diff.version_name, format_option);
+    print_change("Min SDK Version", &diff.min_sdk_version, format_option);
+    print_change(
+        "Target SDK Version",
+        &diff.target_sdk_version,
+        u32::to_string,
+    );
+
+    if let Some(change) = &diff.certificates {
+        println!("{}:", "Certificates".blue().bold());
+        print_list("-", &change.old, "red");
+        print_list("+", &change.new, "green");
+    }
+
+    print_section(
+        "Permissions",
+        &diff.added_permissions,
+        &diff.removed_permissions,
+        &[],
+    );
+
+    let changed_components: Vec<String> = diff
+        .changed_components
+        .iter()
+        .map(|(name, change)| {
+            format!(
+                "{}: {} -> {}",
+                name,
+                format_component(&change.old),
+                format_component(&change.new)
+            )
+        })
+        .collect();
+    print_section(
+        "Components",
+        &diff.added_components,
+        &diff.removed_components,
+        &changed_components,
+    );
+
+    if *show_files {
+        print_section(
+            "Files",
+            &diff.added_entries,
+            &diff.removed_entries,
+            &diff.changed_entries,
+        );
+    }
+
+    if *show_classes {
+        print_section("Classes", &diff.added_classes, &diff.removed_classes, &[]);
+    }
+
+    Ok(())
+}
+
+fn print_change<T>(label: &str, change: &Option<Change<T>>, format: impl Fn(&T) -> String) {
+    if let Some(change) = change {
+        println!(
+            "{}: {} -> {}",
+            label,
+            format(&change.old).red(),
+            format(&change.new).green()
+        );
+    }
+}
+
+fn print_section(label: &str, added: &[String], removed: &[String], changed: &[String]) {
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        return;
+    }
+
+    println!("{}:", label.blue().bold());
+    print_list("+", added, "green");
+    print_list("-", removed, "red");
+    print_list("~", changed, "yellow");
+}
+
+fn print_list(marker: &str, values: &[impl Display], color: &str) {
+    for value in values {
+        println!("  {} {}", marker, value.to_string().color(color));
+    }
+}
+
+fn format_option(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+fn format_component(component: &ComponentDigest) -> String {
+    let mut parts = vec![component.kind.tag().to_string()];
+    if component.exported {
+        parts.push("exported".to_string());
+    }
+    if let Some(permission) = &component.permission {
+        parts.push(format!("permission {permission}"));
+    }
+
+    parts.join(", ")
+}
@@ -2,24 +2,38 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use apk_info::FileCompressionType;
-use apk_info_zip::ZipEntry;
+use apk_info::{FileCompressionType, FileType, ZipEntry, extract_paths};
 use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
 use log::warn;
 use regex::Regex;
 
 use crate::commands::path_helpers::get_all_files;
 
+/// Parses the name of the file type for `--magic`.
+pub(crate) fn parse_file_type(value: &str) -> Result<FileType, String> {
+    FileType::from_name(value).ok_or_else(|| {
+        let names: Vec<&str> = FileType::ALL.iter().map(FileType::name).collect();
+        format!(
+            "unknown file type {:?}, expected one of: {}",
+            value,
+            names.join(", ")
+        )
+    })
+}
+
 pub(crate) fn command_extract(
     paths: &[PathBuf],
+    max_file_size: Option<u64>,
     output: &Option<PathBuf>,
     files: &[String],
+    magic: &[FileType],
 ) -> Result<()> {
-    let all_files = get_all_files(paths);
+    let all_files = get_all_files(paths, max_file_size);
 
     all_files.into_iter().try_for_each(|path| {
         let out_dir = make_output_dir(&path, output);
-        extract(&path, &out_dir, files)
+        extract(&path, &out_dir, files, magic)
     })
 }
 
@@ -47,15 +61,7 @@ fn make_output_dir(path: &Path, output: &Option<PathBuf>) -> PathBuf {
     }
 }
 
-#[inline]
-fn is_bad_filename(file_name: &str) -> bool {
-    file_name.ends_with('/')
-        || file_name.is_empty()
-        || file_name.starts_with("..")
-        || file_name.starts_with("/")
-}
-
-fn extract(path: &PathBuf, out_dir: &PathBuf, files: &[String]) -> Result<()> {
+fn extract(path: &PathBuf, out_dir: &PathBuf, files: &[String], magic: &[FileType]) -> Result<()> {
     let buf = std::fs::read(path).with_context(|| format!("can't open file: {:?}", path))?;
     let zip = ZipEntry::new(buf)?;
 
@@ -67,17 +73,49 @@ fn extract(path: &PathBuf, out_dir: &PathBuf, files: &[String]) -> Result<()> {
         .map(|file| Regex::new(file).with_context(|| format!("invalid regex: {:?}", file)))
         .collect::<Result<Vec<_>>>()?;
 
-    for file_name in zip.namelist() {
-        if is_bad_filename(file_name) {
-            warn!("got bad filename: {:?}, skipped", file_name);
-            continue;
-        }
+    let file_names: Vec<&str> = zip
+        .namelist()
+        .filter(|file_name| {
+            if file_name.is_empty() {
+                warn!("got empty filename, skipped");
+                return false;
+            }
+
+            regexes.is_empty() || regexes.iter().any(|re| re.is_match(file_name))
+        })
+        .filter(|file_name| {
+            magic.is_empty()
+                || zip
+                    .entry_info(file_name)
+                    .is_ok_and(|info| info.file_type.is_some_and(|t| magic.contains(&t)))
+        })
+        .collect();
+
+    // backslashes, absolute paths, reserved windows names, etc. are normalized
+    let files = extract_paths(file_names);
 
-        if !regexes.is_empty() && !regexes.iter().any(|re| re.is_match(file_name)) {
-            continue;
+    // drawn only if stderr is a terminal
+    let progress = ProgressBar::new(files.len() as u64).with_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} {wide_msg}")
+            .expect("valid progress template"),
+    );
+
+    for file in &files {
+        let file_name = file.name.as_str();
+        progress.inc(1);
+        progress.set_message(file_name.to_string());
+
+        if file.is_renamed() {
+            progress.suspend(|| {
+                println!(
+                    "[!] renamed \"{}\" -> \"{}\"",
+                    file_name,
+                    file.path.yellow()
+                )
+            });
         }
 
-        let file_path = out_dir.join(file_name);
+        let file_path = out_dir.join(&file.path);
 
         if let Some(parent) = file_path.parent() {
             std::fs::create_dir_all(parent)
@@ -85,42 +123,48 @@ fn extract(path: &PathBuf, out_dir: &PathBuf, files: &[String]) -> Result<()> {
         }
 
         let (data, compression) = zip
-            .read(file_name)
+            .read_borrowed(file_name)
             .with_context(|| format!("can't read file {:?} from archive", file_name))?;
 
         let mut f = match std::fs::File::create(&file_path) {
             Ok(v) => v,
             Err(e) => {
-                println!(
-                    "[-] can't create file - {:?} - {}",
-                    file_name,
-                    e.to_string().red()
-                );
+                progress.suspend(|| {
+                    println!(
+                        "[-] can't create file - {:?} - {}",
+                        file_name,
+                        e.to_string().red()
+                    )
+                });
                 continue;
             }
         };
 
-        f.write_all(data.as_slice())
+        f.write_all(&data)
             .with_context(|| format!("can't write to {:?}", file_path))?;
 
-        // highligt interesting files
-        if file_name == "AndroidManifest.xml" || file_name == "resources.arsc" {
-            print!("[*] extracted \"{}\" ", file_name.green().bold());
-        } else if file_name.ends_with(".so") {
-            print!("[*] extracted \"{}\" ", file_name.magenta().bold());
-        } else {
-            print!("[~] extracted \"{}\" ", file_name);
-        }
-
-        match compression {
-            FileCompressionType::StoredTampered | FileCompressionType::DeflatedTampered => {
-                println!("({})", format!("{:?}", compression).bold().red());
+        progress.suspend(|| {
+            // highligt interesting files
+            if file_name == "AndroidManifest.xml" || file_name == "resources.arsc" {
+                print!("[*] extracted \"{}\" ", file_name.green().bold());
+            } else if file_name.ends_with(".so") {
+                print!("[*] extracted \"{}\" ", file_name.magenta().bold());
+            } else {
+                print!("[~] extracted \"{}\" ", file_name);
             }
-            _ => {
-                println!("({:?})", compression);
+
+            match compression {
+                FileCompressionType::StoredTampered | FileCompressionType::DeflatedTampered => {
+                    println!("({})", format!("{:?}", compression).bold().red());
+                }
+                _ => {
+                    println!("({:?})", compression);
+                }
             }
-        }
+        });
     }
 
+    progress.finish_and_clear();
+
     Ok(())
 }
@@ -0,0 +1,202 @@
+use std::io::Write;
+
+use anyhow::{Result, bail};
+use apk_info::batch::BatchItem;
+use colored::Colorize;
+use serde_json::{Map, Value, json};
+
+use crate::commands::output::ShowFormat;
+use crate::commands::serve::{FIELDS, collect_fields, validate_fields};
+
+/// Path of the file, accepted in addition to [FIELDS].
+const PATH_FIELD: &str = "path";
+
+/// Selected fields printed by `show` instead of the full report.
+pub(crate) enum Projection {
+    /// `--fields`: tab-separated values in text formats, objects in json formats
+    Fields(Vec<String>),
+
+    /// `--template`: one rendered line per file
+    Template(Vec<Segment>),
+}
+
+pub(crate) enum Segment {
+    Text(String),
+    Field(String),
+}
+
+impl Projection {
+    /// Checks the field names, `path` is accepted too.
+    pub(crate) fn fields(fields: &[String]) -> Result<Projection> {
+        validate(fields)?;
+        Ok(Projection::Fields(fields.to_vec()))
+    }
+
+    /// Parses the template: `{{package_name}} {{version_code}}`.
+    pub(crate) fn template(template: &str) -> Result<Projection> {
+        let mut segments = Vec::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Text(rest[..start].to_string()));
+            }
+
+            let Some(end) = rest[start..].find("}}") else {
+                bail!("unclosed {{{{ in template: {:?}", template);
+            };
+            let field = rest[start + 2..start + end].trim();
+            segments.push(Segment::Field(field.to_string()));
+            rest = &rest[start + end + 2..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Text(rest.to_string()));
+        }
+
+        let fields: Vec<String> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Field(field) => Some(field.clone()),
+                Segment::Text(_) => None,
+            })
+            .collect();
+        validate(&fields)?;
+
+        Ok(Projection::Template(segments))
+    }
+
+    /// Names of the fields to collect from the apk, without `path`.
+    fn apk_fields(&self) -> Vec<&str> {
+        let fields: Vec<&str> = match self {
+            Projection::Fields(fields) => fields.iter().map(String::as_str).collect(),
+            Projection::Template(segments) => segments
+                .iter()
+                .filter_map(|segment| match segment {
+                    Segment::Field(field) => Some(field.as_str()),
+                    Segment::Text(_) => None,
+                })
+                .collect(),
+        };
+
+        fields
+            .into_iter()
+            .filter(|field| *field != PATH_FIELD)
+            .collect()
+    }
+}
+
+fn validate(fields: &[String]) -> Result<()> {
+    if fields.is_empty() {
+        bail!(
+            "no fields, expected one of: {}, {}",
+            PATH_FIELD,
+            FIELDS.join(", ")
+        );
+    }
+
+    let fields: Vec<String> = fields
+        .iter()
+        .filter(|field| *field != PATH_FIELD)
+        .cloned()
+        .collect();
+    validate_fields(&fields)
+}
+
+/// Prints the selected fields of every file.
+///
+/// Files that can't be parsed are reported to stderr in text formats, so the output
+/// stays machine-readable.
+pub(crate) fn print_projection(
+    out: &mut impl Write,
+    items: impl Iterator<Item = BatchItem>,
+    projection: &Projection,
+    format: ShowFormat,
+) -> Result<()> {
+    if matches!(format, ShowFormat::Stix | ShowFormat::Table) {
+        bail!("--fields and --template only work with text, json and ndjson formats");
+    }
+
+    let fields = projection.apk_fields();
+    let mut objects = Vec::new();
+
+    for item in items {
+        let path = item.path.display().to_string();
+        let values = item
+            .result
+            .map_err(anyhow::Error::from)
+            .and_then(|apk| collect_fields(&apk, &fields));
+
+        let mut values = match values {
+            Ok(values) => values,
+            Err(e) if matches!(format, ShowFormat::Json | ShowFormat::Ndjson) => {
+                let error = json!({ "path": path, "error": format!("{e:#}") });
+                match format {
+                    ShowFormat::Json => objects.push(error),
+                    _ => writeln!(out, "{}", error)?,
+                }
+                continue;
+            }
+            Err(e) => {
+                eprintln!("{:?} - {}", item.path, format!("{e:#}").red());
+                continue;
+            }
+        };
+        values.insert(PATH_FIELD.to_string(), json!(path));
+
+        match (projection, format) {
+            (Projection::Template(segments), _) => {
+                let line: String = segments
+                    .iter()
+                    .map(|segment| match segment {
+                        Segment::Text(text) => text.clone(),
+                        Segment::Field(field) => to_text(&values[field.as_str()]),
+                    })
+                    .collect();
+                writeln!(out, "{line}")?;
+            }
+            (Projection::Fields(names), ShowFormat::Json | ShowFormat::Ndjson) => {
+                let mut object = Map::new();
+                object.insert(PATH_FIELD.to_string(), json!(path));
+                // cloned, the same field may be selected twice
+                for name in names {
+                    object.insert(name.clone(), values[name.as_str()].clone());
+                }
+
+                match format {
+                    ShowFormat::Json => objects.push(Value::Object(object)),
+                    _ => writeln!(out, "{}", Value::Object(object))?,
+                }
+            }
+            (Projection::Fields(names), _) => {
+                let line: Vec<String> = names
+                    .iter()
+                    .map(|name| to_text(&values[name.as_str()]))
+                    .collect();
+                writeln!(out, "{}", line.join("\t"))?;
+            }
+        }
+    }
+
+    if format == ShowFormat::Json {
+        writeln!(out, "{}", serde_json::to_string_pretty(&objects)?)?;
+    }
+
+    Ok(())
+}
+
+/// Strings are printed as is, lists of strings and numbers are joined with commas,
+/// everything else is compact json.
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        Value::Array(values)
+            if values
+                .iter()
+                .all(|value| value.is_string() || value.is_number()) =>
+        {
+            values.iter().map(to_text).collect::<Vec<_>>().join(",")
+        }
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use apk_info::artifact::ArtifactKind;
+use apk_info::dex::Dex;
+use apk_info::xml::Element;
+use apk_info::{AXML, Apk};
+use colored::Colorize;
+use log::warn;
+use regex::RegexBuilder;
+use serde::Serialize;
+
+/// Where the matched string is stored.
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum Source {
+    Dex,
+    Resource,
+    Xml,
+    Asset,
+}
+
+impl Source {
+    fn name(self) -> &'static str {
+        match self {
+            Source::Dex => "dex",
+            Source::Resource => "resource",
+            Source::Xml => "xml",
+            Source::Asset => "asset",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Hit {
+    source: Source,
+
+    /// File inside the apk
+    file: String,
+
+    /// Class loading the string, resource name, `element@attribute` or offset in the asset
+    location: Option<String>,
+
+    value: String,
+}
+
+/// Searches strings of dex files, resource values and attributes of binary xml files,
+/// with `assets` also the raw content of `assets/`.
+///
+/// The pattern and the expressions from the rule files are combined, a string matching
+/// any of them is reported.
+pub(crate) fn command_grep(
+    path: &Path,
+    pattern: Option<&str>,
+    rules: &[PathBuf],
+    ignore_case: &bool,
+    assets: &bool,
+    json: &bool,
+) -> Result<()> {
+    let mut patterns: Vec<String> = pattern.map(str::to_string).into_iter().collect();
+    for rule in rules {
+        patterns.extend(load_rules(rule)?);
+    }
+    let pattern = match patterns.as_slice() {
+        [] => bail!("no pattern given, use a pattern, --rules or rules in apk-info.toml"),
+        [pattern] => pattern.clone(),
+        patterns => patterns
+            .iter()
+            .map(|pattern| format!("(?:{pattern})"))
+            .collect::<Vec<_>>()
+            .join("|"),
+    };
+    let pattern = pattern.as_str();
+
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(*ignore_case)
+        .build()
+        .with_context(|| format!("invalid regex: {:?}", pattern))?;
+
+    let mut hits = Vec::new();
+
+    for name in apk.dex_names() {
+        match apk.read_dex(name) {
+            Ok(dex) => grep_dex(name, &dex, &regex, &mut hits),
+            Err(e) => warn!("{name}: skipped, {}", e.display_chain()),
+        }
+    }
+
+    if let Some(arsc) = apk.get_resource_table() {
+        for resource in arsc.resources() {
+            // values are usually the same in every config
+            let values: BTreeSet<&str> = resource
+                .values
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .filter(|value| regex.is_match(value))
+                .collect();
+
+            hits.extend(values.into_iter().map(|value| Hit {
+                source: Source::Resource,
+                file: "resources.arsc".to_string(),
+                location: Some(resource.name.clone()),
+                value: value.to_string(),
+            }));
+        }
+    }
+
+    grep_xml(
+        "AndroidManifest.xml",
+        apk.get_manifest_root(),
+        &regex,
+        &mut hits,
+    );
+    for name in apk.namelist() {
+        if !name.ends_with(".xml") || name == "AndroidManifest.xml" {
+            continue;
+        }
+        let Ok((data, _)) = apk.read(name) else {
+            continue;
+        };
+        if ArtifactKind::detect(&data) != Some(ArtifactKind::Axml) {
+            continue;
+        }
+
+        match AXML::new(&mut &data[..], apk.get_resource_table()) {
+            Ok(axml) => grep_xml(name, &axml.root, &regex, &mut hits),
+            Err(e) => warn!("{name}: skipped, {e}"),
+        }
+    }
+
+    if *assets {
+        let regex = regex::bytes::RegexBuilder::new(pattern)
+            .case_insensitive(*ignore_case)
+            .build()?;
+
+        for name in apk.namelist().filter(|name| name.starts_with("assets/")) {
+            let Ok((data, _)) = apk.read(name) else {
+                warn!("{name}: skipped, can't read");
+                continue;
+            };
+
+            hits.extend(regex.find_iter(&data).map(|m| Hit {
+                source: Source::Asset,
+                file: name.to_string(),
+                location: Some(format!("0x{:x}", m.start())),
+                value: String::from_utf8_lossy(m.as_bytes()).into_owned(),
+            }));
+        }
+    }
+
+    if *json {
+        println!("{}", serde_json::to_string(&hits)?);
+        return Ok(());
+    }
+
+    for hit in hits {
+        let location = hit
+            .location
+            .map(|location| format!(" {}", location.yellow()))
+            .unwrap_or_default();
+
+        println!(
+            "{} {}{}: {}",
+            format!("[{}]", hit.source.name()).dimmed(),
+            hit.file.blue(),
+            location,
+            hit.value.green()
+        );
+    }
+
+    Ok(())
+}
+
+/// Reads regular expressions from the rule file, skipping empty lines and `#` comments.
+fn load_rules(path: &Path) -> Result<Vec<String>> {
+    let data =
+        std::fs::read_to_string(path).with_context(|| format!("can't read rules: {:?}", path))?;
+
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            regex::Regex::new(line)
+                .map(|_| line.to_string())
+                .with_context(|| format!("invalid regex in {:?}: {:?}", path, line))
+        })
+        .collect()
+}
+
+/// Reports matched strings once per class that loads them, or once without a location
+/// if the code doesn't use them (names of types, fields and methods).
+fn grep_dex(name: &str, dex: &Dex, regex: &regex::Regex, hits: &mut Vec<Hit>) {
+    let matched: Vec<(u32, String)> = (0..dex.strings_count() as u32)
+        .filter_map(|idx| Some((idx, dex.get_string(idx)?)))
+        .filter(|(_, value)| regex.is_match(value))
+        .collect();
+    if matched.is_empty() {
+        return;
+    }
+
+    // the whole bytecode is scanned, so only when there is something to look up
+    let references = dex.string_references().unwrap_or_else(|e| {
+        warn!("{name}: can't find string references, {e}");
+        Default::default()
+    });
+
+    for (idx, value) in matched {
+        let classes: BTreeSet<String> = references
+            .get(&idx)
+            .into_iter()
+            .flatten()
+            .filter_map(|&method_idx| dex.method_ids().get(method_idx as usize))
+            .filter_map(|method| dex.get_class_name(u32::from(method.class_idx)))
+            .collect();
+
+        if classes.is_empty() {
+            hits.push(Hit {
+                source: Source::Dex,
+                file: name.to_string(),
+                location: None,
+                value,
+            });
+            continue;
+        }
+
+        hits.extend(classes.into_iter().map(|class| Hit {
+            source: Source::Dex,
+            file: name.to_string(),
+            location: Some(class),
+            value: value.clone(),
+        }));
+    }
+}
+
+fn grep_xml(name: &str, root: &Element, regex: &regex::Regex, hits: &mut Vec<Hit>) {
+    for element in std::iter::once(root).chain(root.descendants()) {
+        for attribute in element.attributes() {
+            if !regex.is_match(attribute.value()) {
+                continue;
+            }
+
+            let attribute_name = match attribute.prefix() {
+                Some(prefix) => format!("{prefix}:{}", attribute.name()),
+                None => attribute.name().to_string(),
+            };
+
+            hits.push(Hit {
+                source: Source::Xml,
+                file: name.to_string(),
+                location: Some(format!("{}@{attribute_name}", element.name())),
+                value: attribute.value().to_string(),
+            });
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use apk_info::{Apk, HashAlgorithm};
+use colored::Colorize;
+
+pub(crate) fn command_hashes(path: &Path, algorithm: &HashAlgorithm, json: &bool) -> Result<()> {
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+    let hashes = apk.get_file_hashes();
+
+    if *json {
+        println!("{}", serde_json::to_string(&hashes)?);
+        return Ok(());
+    }
+
+    // same layout as sha256sum, so the output can be checked with it after extraction
+    for entry in hashes {
+        println!("{}  {}", entry.get(*algorithm).green(), entry.name);
+    }
+
+    Ok(())
+}
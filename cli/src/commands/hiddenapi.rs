@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use apk_info::{Apk, HiddenApiDatabase};
+use colored::Colorize;
+
+pub(crate) fn command_hiddenapi(path: &Path, flags: &Path, json: &bool) -> Result<()> {
+    let database = HiddenApiDatabase::load(flags)
+        .with_context(|| format!("can't load hiddenapi flags: {:?}", flags))?;
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+    let usages = apk
+        .restricted_api_usage(&database)
+        .with_context(|| format!("can't parse dex files: {:?}", path))?;
+
+    if *json {
+        println!("{}", serde_json::to_string(&usages)?);
+        return Ok(());
+    }
+
+    let width = usages
+        .iter()
+        .map(|u| u.restriction.len())
+        .max()
+        .unwrap_or_default()
+        .max("restriction".len());
+
+    println!("{:<width$} {:<6} signature", "restriction", "kind");
+
+    for usage in usages {
+        let restriction = if usage.restriction == "blocked" {
+            usage.restriction.red()
+        } else {
+            usage.restriction.yellow()
+        };
+
+        println!(
+            "{:<width$} {:<6} {} {}",
+            restriction,
+            usage.kind,
+            usage.signature.blue(),
+            format!("({})", usage.dex_files.join(", ")).dimmed()
+        );
+    }
+
+    Ok(())
+}
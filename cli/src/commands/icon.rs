@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use apk_info::Apk;
+use apk_info::models::IconKind;
+use apk_info::structs::Density;
+use colored::Colorize;
+
+/// Densities by qualifier name, as in the names of resource directories.
+const DENSITIES: &[(&str, Density)] = &[
+    ("ldpi", Density::Low),
+    ("mdpi", Density::Medium),
+    ("tvdpi", Density::TV),
+    ("hdpi", Density::High),
+    ("xhdpi", Density::XHigh),
+    ("xxhdpi", Density::XXHigh),
+    ("xxxhdpi", Density::XXXHigh),
+];
+
+/// Parses the density qualifier (`xxhdpi`) or dpi value (`480`).
+pub(crate) fn parse_density(value: &str) -> Result<Density, String> {
+    if let Some((_, density)) = DENSITIES.iter().find(|(name, _)| *name == value) {
+        return Ok(*density);
+    }
+
+    match value.parse::<u16>() {
+        Ok(dpi) if dpi > 0 => Ok(Density::from(dpi)),
+        _ => Err(format!(
+            "invalid density {:?}, expected dpi or one of: ldpi, mdpi, tvdpi, hdpi, xhdpi, xxhdpi, xxxhdpi",
+            value
+        )),
+    }
+}
+
+/// Saves the application icon, the extension of the output is taken from the format by default.
+pub(crate) fn command_icon(
+    path: &Path,
+    output: &Option<PathBuf>,
+    density: &Option<Density>,
+) -> Result<()> {
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+
+    let Some((data, kind)) = apk.get_icon_bytes(*density) else {
+        bail!(
+            "can't find a bitmap icon in {:?}: {}",
+            path,
+            apk.get_application_icon()
+                .unwrap_or_else(|| "no android:icon".to_string())
+        );
+    };
+
+    let output = output.clone().unwrap_or_else(|| {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        PathBuf::from(format!("{stem}.icon.{}", kind.format()))
+    });
+
+    std::fs::write(&output, &data).with_context(|| format!("can't write {:?}", output))?;
+
+    if let IconKind::AdaptiveForeground(_) = kind {
+        println!(
+            "[!] {}",
+            "adaptive icon, only the foreground layer is saved".yellow()
+        );
+    }
+    println!(
+        "[*] saved {} icon ({} bytes) to {:?}",
+        kind.format(),
+        data.len(),
+        output
+    );
+
+    Ok(())
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use apk_info::{Apk, FileType, Signature};
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use rusqlite::{Connection, Transaction, params};
+use sha2::{Digest, Sha256};
+
+use crate::commands::path_helpers::get_all_files;
+
+/// Tables are normalized, so the corpus can be queried with plain SQL:
+///
+/// ```sql
+/// SELECT DISTINCT a.path FROM apks a
+/// JOIN certs c ON c.apk_id = a.id
+/// JOIN permissions p ON p.apk_id = a.id
+/// WHERE c.sha256 = '...' AND p.name LIKE '%SMS%';
+/// ```
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS apks (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL UNIQUE,
+    sha256 TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    package_name TEXT,
+    version_name TEXT,
+    version_code TEXT,
+    min_sdk_version TEXT,
+    target_sdk_version INTEGER,
+    max_sdk_version TEXT,
+    application_label TEXT,
+    main_activity TEXT,
+    error TEXT
+);
+CREATE INDEX IF NOT EXISTS apks_sha256 ON apks(sha256);
+CREATE INDEX IF NOT EXISTS apks_package_name ON apks(package_name);
+
+CREATE TABLE IF NOT EXISTS permissions (
+    apk_id INTEGER NOT NULL REFERENCES apks(id) ON DELETE CASCADE,
+    name TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS permissions_name ON permissions(name);
+
+CREATE TABLE IF NOT EXISTS components (
+    apk_id INTEGER NOT NULL REFERENCES apks(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    name TEXT,
+    exported TEXT,
+    enabled TEXT,
+    permission TEXT
+);
+CREATE INDEX IF NOT EXISTS components_name ON components(name);
+
+CREATE TABLE IF NOT EXISTS certs (
+    apk_id INTEGER NOT NULL REFERENCES apks(id) ON DELETE CASCADE,
+    scheme TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    sha1 TEXT NOT NULL,
+    md5 TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    issuer TEXT NOT NULL,
+    serial_number TEXT NOT NULL,
+    valid_from TEXT NOT NULL,
+    valid_until TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS certs_sha256 ON certs(sha256);
+
+CREATE TABLE IF NOT EXISTS files (
+    apk_id INTEGER NOT NULL REFERENCES apks(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    compressed_size INTEGER,
+    uncompressed_size INTEGER,
+    file_type TEXT
+);
+CREATE INDEX IF NOT EXISTS files_name ON files(name);
+
+CREATE TABLE IF NOT EXISTS anomalies (
+    apk_id INTEGER NOT NULL REFERENCES apks(id) ON DELETE CASCADE,
+    source TEXT NOT NULL,
+    message TEXT NOT NULL
+);
+"#;
+
+pub(crate) fn command_index(
+    paths: &[PathBuf],
+    max_file_size: Option<u64>,
+    db: &Path,
+) -> Result<()> {
+    let files = get_all_files(paths, max_file_size);
+
+    let mut conn =
+        Connection::open(db).with_context(|| format!("can't open database: {:?}", db))?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    conn.execute_batch(SCHEMA)
+        .context("can't create database schema")?;
+
+    let progress = ProgressBar::new(files.len() as u64).with_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} {wide_msg}")
+            .expect("valid progress template"),
+    );
+
+    let mut tx = conn.transaction()?;
+    let mut failed = 0;
+
+    for path in &files {
+        progress.set_message(path.display().to_string());
+
+        if let Err(e) = index_one(&mut tx, path) {
+            failed += 1;
+            progress.suspend(|| {
+                eprintln!("{:?} - {}", path, format!("{e:#}").red());
+            });
+        }
+
+        progress.inc(1);
+    }
+
+    tx.commit().context("can't write database")?;
+    progress.finish_and_clear();
+
+    println!(
+        "Indexed {} files into {:?} ({} errors)",
+        files.len().to_string().green(),
+        db,
+        failed.to_string().red()
+    );
+
+    Ok(())
+}
+
+/// Indexes the file in its own savepoint, so a failure in the middle doesn't leave
+/// partial rows or remove the previous data of the file.
+fn index_one(tx: &mut Transaction, path: &Path) -> Result<()> {
+    let savepoint = tx.savepoint()?;
+    // dropping the savepoint on error rolls it back
+    index_file(&savepoint, path)?;
+    savepoint.commit()?;
+
+    Ok(())
+}
+
+fn index_file(tx: &Connection, path: &Path) -> Result<()> {
+    let (sha256, size) = hash_file(path)?;
+    let path_str = path.to_string_lossy();
+
+    // reindexing replaces the previous data, related rows are removed by cascade
+    tx.execute("DELETE FROM apks WHERE path = ?1", params![path_str])?;
+
+    let apk = match Apk::new(path) {
+        Ok(apk) => apk,
+        Err(e) => {
+            // keep broken samples, they are also part of the corpus
+            tx.execute(
+                "INSERT INTO apks (path, sha256, size, error) VALUES (?1, ?2, ?3, ?4)",
+                params![path_str, sha256, size, e.display_chain()],
+            )?;
+            return Ok(());
+        }
+    };
+
+    tx.execute(
+        "INSERT INTO apks (path, sha256, size, package_name, version_name, version_code,
+            min_sdk_version, target_sdk_version, max_sdk_version, application_label, main_activity)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            path_str,
+            sha256,
+            size,
+            apk.get_package_name(),
+            apk.get_version_name(),
+            apk.get_version_code(),
+            apk.get_min_sdk_version(),
+            apk.get_target_sdk_version(),
+            apk.get_max_sdk_version(),
+            apk.get_application_label(),
+            apk.get_main_activity(),
+        ],
+    )?;
+    let apk_id = tx.last_insert_rowid();
+
+    let mut stmt = tx.prepare_cached("INSERT INTO permissions (apk_id, name) VALUES (?1, ?2)")?;
+    for permission in apk.get_permissions() {
+        stmt.execute(params![apk_id, permission])?;
+    }
+
+    let mut stmt = tx.prepare_cached(
+        "INSERT INTO components (apk_id, kind, name, exported, enabled, permission)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+    for c in apk.get_activities() {
+        stmt.execute(params![
+            apk_id,
+            "activity",
+            c.name,
+            c.exported,
+            c.enabled,
+            c.permission
+        ])?;
+    }
+    for c in apk.get_activity_aliases() {
+        stmt.execute(params![
+            apk_id,
+            "activity-alias",
+            c.name,
+            c.exported,
+            c.enabled,
+            c.permission
+        ])?;
+    }
+    for c in apk.get_services() {
+        stmt.execute(params![
+            apk_id,
+            "service",
+            c.name,
+            c.exported,
+            c.enabled,
+            c.permission
+        ])?;
+    }
+    for c in apk.get_receivers() {
+        stmt.execute(params![
+            apk_id,
+            "receiver",
+            c.name,
+            c.exported,
+            c.enabled,
+            c.permission
+        ])?;
+    }
+    for c in apk.get_providers() {
+        stmt.execute(params![
+            apk_id,
+            "provider",
+            c.name,
+            c.exported,
+            c.enabled,
+            c.permission
+        ])?;
+    }
+
+    let mut stmt = tx.prepare_cached(
+        "INSERT INTO files (apk_id, name, compressed_size, uncompressed_size, file_type)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    let file_types: HashMap<String, FileType> = apk
+        .get_file_infos()
+        .into_iter()
+        .filter_map(|info| Some((info.name, info.file_type?)))
+        .collect();
+    for name in apk.namelist() {
+        let sizes = apk.get_file_sizes(name);
+        stmt.execute(params![
+            apk_id,
+            name,
+            sizes.map(|(compressed, _)| compressed),
+            sizes.map(|(_, uncompressed)| uncompressed),
+            file_types.get(name).map(FileType::name)
+        ])?;
+    }
+
+    let mut stmt =
+        tx.prepare_cached("INSERT INTO anomalies (apk_id, source, message) VALUES (?1, ?2, ?3)")?;
+    for diagnostic in apk.get_manifest_diagnostics() {
+        stmt.execute(params![apk_id, "manifest", diagnostic.to_string()])?;
+    }
+    for anomaly in apk.get_archive_anomalies() {
+        stmt.execute(params![apk_id, "zip", anomaly.to_string()])?;
+    }
+
+    let signatures = match apk.get_signatures_ref() {
+        Ok(signatures) => signatures,
+        Err(e) => {
+            stmt.execute(params![apk_id, "signature", e.display_chain()])?;
+            return Ok(());
+        }
+    };
+
+    let mut stmt = tx.prepare_cached(
+        "INSERT INTO certs (apk_id, scheme, sha256, sha1, md5, subject, issuer, serial_number,
+            valid_from, valid_until)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+    )?;
+    for signature in signatures {
+        let certificates = match signature {
+            Signature::V1(certificates)
+            | Signature::V2 { certificates, .. }
+            | Signature::V3 { certificates, .. }
+            | Signature::V31 { certificates, .. } => certificates.as_slice(),
+            Signature::StampBlockV1(certificate) | Signature::StampBlockV2(certificate) => {
+                std::slice::from_ref(certificate)
+            }
+            _ => continue,
+        };
+
+        for cert in certificates {
+            stmt.execute(params![
+                apk_id,
+                signature.name(),
+                cert.sha256_fingerprint,
+                cert.sha1_fingerprint,
+                cert.md5_fingerprint,
+                cert.subject,
+                cert.issuer,
+                cert.serial_number,
+                cert.valid_from,
+                cert.valid_until,
+            ])?;
+        }
+    }
+
+    Ok(())
+}
+
+fn hash_file(path: &Path) -> Result<(String, u64)> {
+    let mut file = File::open(path).with_context(|| format!("can't open file: {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher)?;
+
+    let hash = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    Ok((hash, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "foreign_keys", true).unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        conn
+    }
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_failed_file_is_rolled_back() {
+        let path = std::env::temp_dir().join(format!("apk-info-index-{}.apk", std::process::id()));
+        std::fs::write(&path, b"not an apk").unwrap();
+
+        let mut conn = database();
+        let mut tx = conn.transaction().unwrap();
+        let indexed = index_one(&mut tx, &path);
+
+        // the second run fails after the previous row is deleted
+        tx.execute_batch(
+            "CREATE TRIGGER fail BEFORE INSERT ON apks BEGIN SELECT RAISE(ABORT, 'fail'); END;",
+        )
+        .unwrap();
+        let reindexed = index_one(&mut tx, &path);
+        tx.commit().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(indexed.is_ok());
+        assert!(reindexed.is_err());
+        assert_eq!(count(&conn, "apks"), 1);
+        let error: Option<String> = conn
+            .query_row("SELECT error FROM apks", [], |row| row.get(0))
+            .unwrap();
+        assert!(error.is_some());
+    }
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use apk_info::Apk;
+use apk_info::indicators::Indicators;
+use colored::Colorize;
+
+use crate::commands::output::IocFormat;
+
+/// Prints urls, ip addresses, domains, emails and wallets found in the apk.
+pub(crate) fn command_iocs(path: &Path, format: IocFormat) -> Result<()> {
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+    let mut indicators = Indicators::collect(&apk)?;
+    indicators.file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+
+    match format {
+        IocFormat::Json => println!("{}", serde_json::to_string(&indicators)?),
+        IocFormat::Stix => println!("{}", indicators.to_stix_bundle()),
+        IocFormat::Text => {
+            let wallets: Vec<String> = indicators
+                .wallets
+                .iter()
+                .map(|wallet| format!("{} ({:?})", wallet.address, wallet.currency))
+                .collect();
+
+            for (title, values) in [
+                ("Urls", &indicators.urls),
+                ("IPs", &indicators.ips),
+                ("Domains", &indicators.domains),
+                ("Emails", &indicators.emails),
+                ("Wallets", &wallets),
+            ] {
+                if values.is_empty() {
+                    continue;
+                }
+
+                println!("{}:", title.blue().bold());
+                for value in values {
+                    println!("  {}", value.green());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use apk_info::Apk;
+
+/// Saves the decoded `AndroidManifest.xml` of the apk or xapk, with `raw` the binary one.
+///
+/// The manifest of xapk is taken from the inner base apk, of app bundles it is protobuf.
+pub(crate) fn command_manifest(path: &Path, output: &Option<PathBuf>, raw: &bool) -> Result<()> {
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+
+    let (data, source) = if *raw {
+        let (data, info) = apk
+            .raw_manifest()
+            .with_context(|| format!("can't read manifest: {:?}", path))?;
+        (data, info.name)
+    } else {
+        (
+            apk.get_xml_string().into_bytes(),
+            "decoded AndroidManifest.xml".to_string(),
+        )
+    };
+
+    let output = output.clone().unwrap_or_else(|| {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        PathBuf::from(format!("{stem}.AndroidManifest.xml"))
+    });
+
+    std::fs::write(&output, &data).with_context(|| format!("can't write {:?}", output))?;
+
+    println!(
+        "[*] saved {} ({} bytes) to {:?}",
+        source,
+        data.len(),
+        output
+    );
+
+    Ok(())
+}
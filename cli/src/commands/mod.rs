@@ -1,8 +1,47 @@
+pub(crate) mod arsc;
 pub(crate) mod axml;
+pub(crate) mod carve;
+pub(crate) mod certs;
+pub(crate) mod dex;
+pub(crate) mod diff;
 pub(crate) mod extract;
-mod path_helpers;
+pub(crate) mod fields;
+pub(crate) mod grep;
+pub(crate) mod hashes;
+pub(crate) mod hiddenapi;
+pub(crate) mod icon;
+pub(crate) mod index;
+pub(crate) mod iocs;
+pub(crate) mod manifest;
+pub(crate) mod output;
+pub(crate) mod path_helpers;
+pub(crate) mod profile;
+pub(crate) mod serve;
+#[cfg(feature = "server")]
+pub(crate) mod server;
 pub(crate) mod show;
+mod trusted_certs;
+pub(crate) mod verify;
+pub(crate) mod xapk;
 
+pub(crate) use arsc::{command_arsc, command_arsc_dump, parse_resource_id};
 pub(crate) use axml::command_axml;
-pub(crate) use extract::command_extract;
+pub(crate) use carve::command_carve;
+pub(crate) use certs::command_certs;
+pub(crate) use dex::{DexListing, command_dex};
+pub(crate) use diff::command_diff;
+pub(crate) use extract::{command_extract, parse_file_type};
+pub(crate) use grep::command_grep;
+pub(crate) use hashes::command_hashes;
+pub(crate) use hiddenapi::command_hiddenapi;
+pub(crate) use icon::{command_icon, parse_density};
+pub(crate) use index::command_index;
+pub(crate) use iocs::command_iocs;
+pub(crate) use manifest::command_manifest;
+pub(crate) use profile::command_profile;
+pub(crate) use serve::command_serve;
+#[cfg(feature = "server")]
+pub(crate) use server::command_server;
 pub(crate) use show::command_show;
+pub(crate) use verify::command_verify;
+pub(crate) use xapk::command_xapk;
@@ -0,0 +1,197 @@
+use std::io::{self, IsTerminal, StdoutLock, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use apk_info::FingerprintStyle;
+use apk_info::progress::{ProgressSink, Stage};
+use clap::ValueEnum;
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use indicatif::{ProgressBar, ProgressStyle};
+use log::warn;
+use serde::Deserialize;
+
+/// When to use colors in the output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ColorChoice {
+    /// Use colors if stdout is a terminal and `NO_COLOR` is not set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Format of the `show` output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ShowFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// One json document with the full report, an array for several files
+    Json,
+    /// Full report of every file as one json object per line
+    Ndjson,
+    /// One STIX 2.1 bundle of indicators per file
+    Stix,
+    /// One row per file: package, version, min sdk, signer sha256 and flags
+    Table,
+}
+
+/// Format of the `iocs` output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum IocFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// All indicators as one json object
+    Json,
+    /// STIX 2.1 bundle, wallets aren't included
+    Stix,
+}
+
+/// Parses `--fingerprint-format` into the library style, listing the names in the help.
+pub(crate) fn fingerprint_style_parser() -> impl TypedValueParser<Value = FingerprintStyle> {
+    PossibleValuesParser::new(FingerprintStyle::NAMES)
+        .try_map(|name| name.parse::<FingerprintStyle>())
+}
+
+/// Configures colors globally for all commands.
+pub(crate) fn setup_colors(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        // https://no-color.org: any non-empty value disables colors
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && io::stdout().is_terminal()
+        }
+    };
+
+    colored::control::set_override(enabled);
+}
+
+/// Whether the output should be colorized, see [setup_colors].
+pub(crate) fn colors_enabled() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// Destination of the command output: stdout or a pager process.
+///
+/// Commands write everything through it instead of `println!`, so the same code
+/// works for both cases.
+pub(crate) enum Output {
+    Stdout(StdoutLock<'static>),
+    Pager {
+        child: Child,
+        stdin: Option<ChildStdin>,
+    },
+}
+
+impl Output {
+    /// Creates the output, the pager is started only if stdout is a terminal.
+    ///
+    /// The pager is taken from `$PAGER`, `less` is used by default.
+    pub(crate) fn new(pager: bool) -> Output {
+        if pager
+            && io::stdout().is_terminal()
+            && let Some(output) = Self::spawn_pager()
+        {
+            return output;
+        }
+
+        Output::Stdout(io::stdout().lock())
+    }
+
+    fn spawn_pager() -> Option<Output> {
+        let pager = std::env::var("PAGER")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| "less".to_string());
+
+        let mut args = pager.split_whitespace();
+        let program = args.next()?;
+
+        let mut command = Command::new(program);
+        command.args(args).stdin(Stdio::piped());
+
+        // keep colors, quit if the output fits on one screen, don't clear the screen
+        if program == "less" && std::env::var_os("LESS").is_none() {
+            command.env("LESS", "-FRX");
+        }
+
+        match command.spawn() {
+            Ok(mut child) => {
+                let stdin = child.stdin.take();
+                Some(Output::Pager { child, stdin })
+            }
+            Err(e) => {
+                warn!("can't start pager {:?}: {}", pager, e);
+                None
+            }
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Stdout(stdout) => stdout.write(buf),
+            Output::Pager { stdin, .. } => match stdin.as_mut().map(|s| s.write(buf)) {
+                // the user closed the pager, nothing to do
+                Some(Err(e)) if e.kind() == io::ErrorKind::BrokenPipe => {
+                    stdin.take();
+                    Ok(buf.len())
+                }
+                Some(result) => result,
+                None => Ok(buf.len()),
+            },
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Stdout(stdout) => stdout.flush(),
+            Output::Pager { stdin, .. } => match stdin.as_mut().map(|s| s.flush()) {
+                Some(Err(e)) if e.kind() != io::ErrorKind::BrokenPipe => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+impl Drop for Output {
+    fn drop(&mut self) {
+        let _ = self.flush();
+
+        if let Output::Pager { child, stdin } = self {
+            // close stdin, so the pager sees the end of the input
+            stdin.take();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Progress bar showing the stage reported by the library, drawn only if stderr is a terminal.
+#[derive(Clone)]
+pub(crate) struct StageProgress(ProgressBar);
+
+impl StageProgress {
+    pub(crate) fn new() -> StageProgress {
+        StageProgress(
+            ProgressBar::no_length().with_style(
+                ProgressStyle::with_template("{msg:10} {bar:40} {pos}/{len}")
+                    .expect("valid progress template"),
+            ),
+        )
+    }
+
+    pub(crate) fn finish(&self) {
+        self.0.finish_and_clear();
+    }
+}
+
+impl ProgressSink for StageProgress {
+    fn report(&self, stage: Stage, current: u64, total: u64) {
+        self.0.set_message(stage.to_string());
+        self.0.set_length(total);
+        self.0.set_position(current);
+    }
+}
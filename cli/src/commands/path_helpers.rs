@@ -1,13 +1,73 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use log::warn;
 use walkdir::WalkDir;
 
-pub(crate) fn get_all_files(paths: &[PathBuf]) -> Vec<PathBuf> {
+/// Which files are taken from the given directories.
+pub(crate) struct WalkOptions {
+    /// Descend into subdirectories, otherwise only files directly in the directory are taken
+    pub recursive: bool,
+
+    /// Files larger than it are skipped, directories and explicitly given files alike
+    pub max_size: Option<u64>,
+
+    /// Filters files found in directories, explicitly given files are always taken
+    pub filter: GlobFilter,
+}
+
+/// Globs matched against the path relative to the walked directory, `!` excludes files.
+///
+/// example: `*.apk`, `!**/test/**`
+#[derive(Default)]
+pub(crate) struct GlobFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl GlobFilter {
+    pub(crate) fn new(globs: &[String]) -> Result<GlobFilter> {
+        let mut include = GlobSetBuilder::new();
+        let mut exclude = GlobSetBuilder::new();
+        let (mut has_include, mut has_exclude) = (false, false);
+
+        for glob in globs {
+            let (builder, pattern) = match glob.strip_prefix('!') {
+                Some(pattern) => {
+                    has_exclude = true;
+                    (&mut exclude, pattern)
+                }
+                None => {
+                    has_include = true;
+                    (&mut include, glob.as_str())
+                }
+            };
+            builder.add(Glob::new(pattern).with_context(|| format!("invalid glob: {:?}", glob))?);
+        }
+
+        Ok(GlobFilter {
+            include: has_include.then(|| include.build()).transpose()?,
+            exclude: has_exclude.then(|| exclude.build()).transpose()?,
+        })
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        self.include.as_ref().is_none_or(|set| set.is_match(path))
+            && !self.exclude.as_ref().is_some_and(|set| set.is_match(path))
+    }
+}
+
+/// Expands directories into the files inside them, hidden files and directories are skipped.
+pub(crate) fn walk_files(paths: &[PathBuf], options: &WalkOptions) -> Vec<PathBuf> {
     paths
         .iter()
         .flat_map(move |path| {
             if path.is_dir() {
+                let max_depth = if options.recursive { usize::MAX } else { 1 };
+
                 WalkDir::new(path)
+                    .max_depth(max_depth)
                     .into_iter()
                     .filter_entry(|e| {
                         e.file_name()
@@ -17,6 +77,10 @@ pub(crate) fn get_all_files(paths: &[PathBuf]) -> Vec<PathBuf> {
                     })
                     .filter_map(Result::ok)
                     .filter(|e| e.path().is_file())
+                    .filter(|e| {
+                        let relative = e.path().strip_prefix(path).unwrap_or(e.path());
+                        options.filter.is_match(relative)
+                    })
                     .map(|e| e.path().to_path_buf())
                     .collect::<Vec<_>>()
             } else if path.is_file() {
@@ -25,5 +89,76 @@ pub(crate) fn get_all_files(paths: &[PathBuf]) -> Vec<PathBuf> {
                 Vec::new()
             }
         })
+        .filter(|path| match (options.max_size, path.metadata()) {
+            (Some(max_size), Ok(metadata)) if metadata.len() > max_size => {
+                warn!(
+                    "skipping {:?}: file is larger than {} bytes",
+                    path, max_size
+                );
+                false
+            }
+            _ => true,
+        })
         .collect()
 }
+
+/// Expands directories recursively into the files inside them, files larger than `max_size`
+/// are skipped.
+pub(crate) fn get_all_files(paths: &[PathBuf], max_size: Option<u64>) -> Vec<PathBuf> {
+    let options = WalkOptions {
+        recursive: true,
+        max_size,
+        filter: GlobFilter::default(),
+    };
+
+    walk_files(paths, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob_filter(globs: &[&str]) -> GlobFilter {
+        GlobFilter::new(
+            &globs
+                .iter()
+                .map(|glob| glob.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_glob_filter_default() {
+        assert!(GlobFilter::default().is_match(Path::new("app.apk")));
+        assert!(GlobFilter::default().is_match(Path::new("nested/readme.txt")));
+    }
+
+    #[test]
+    fn test_glob_filter_include() {
+        let filter = glob_filter(&["*.apk", "*.xapk"]);
+        assert!(filter.is_match(Path::new("app.apk")));
+        assert!(filter.is_match(Path::new("bundle.xapk")));
+        assert!(!filter.is_match(Path::new("readme.txt")));
+    }
+
+    #[test]
+    fn test_glob_filter_exclude() {
+        // only excludes, everything else is taken
+        let filter = glob_filter(&["!**/test/**"]);
+        assert!(filter.is_match(Path::new("app.apk")));
+        assert!(!filter.is_match(Path::new("samples/test/app.apk")));
+
+        // excludes win over includes
+        let filter = glob_filter(&["*.apk", "!**/test/**"]);
+        assert!(filter.is_match(Path::new("samples/app.apk")));
+        assert!(!filter.is_match(Path::new("samples/test/app.apk")));
+        assert!(!filter.is_match(Path::new("samples/readme.txt")));
+    }
+
+    #[test]
+    fn test_glob_filter_invalid() {
+        let error = GlobFilter::new(&["!a[".to_string()]).err().unwrap();
+        assert!(error.to_string().contains("\"!a[\""));
+    }
+}
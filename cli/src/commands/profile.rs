@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use apk_info::Apk;
+use colored::Colorize;
+
+use crate::commands::output::StageProgress;
+
+pub(crate) fn command_profile(path: &Path, json: &bool) -> Result<()> {
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+
+    let progress = StageProgress::new();
+    let profiles = apk
+        .package_profile(Some(&progress), None)
+        .with_context(|| format!("can't parse dex files: {:?}", path))?;
+    progress.finish();
+
+    if *json {
+        println!("{}", serde_json::to_string(&profiles)?);
+        return Ok(());
+    }
+
+    let width = profiles
+        .iter()
+        .map(|p| p.prefix.len())
+        .max()
+        .unwrap_or_default()
+        .max("(default)".len());
+
+    println!(
+        "{:<width$} {:>8} {:>8} {:>8} {:>8}",
+        "package", "classes", "methods", "fields", "avg len"
+    );
+
+    for profile in profiles {
+        let prefix = if profile.prefix.is_empty() {
+            "(default)"
+        } else {
+            &profile.prefix
+        };
+
+        println!(
+            "{:<width$} {:>8} {:>8} {:>8} {:>8}",
+            prefix.blue(),
+            profile.classes,
+            profile.methods,
+            profile.fields,
+            format!("{:.1}", profile.average_identifier_length).yellow()
+        );
+    }
+
+    Ok(())
+}
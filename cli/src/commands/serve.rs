@@ -0,0 +1,244 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use apk_info::audit::audit;
+use apk_info::{Apk, Signature};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Fields returned when the request doesn't list any.
+const DEFAULT_FIELDS: &[&str] = &[
+    "package_name",
+    "version_name",
+    "version_code",
+    "main_activity",
+    "min_sdk_version",
+    "max_sdk_version",
+    "target_sdk_version",
+    "application_label",
+];
+
+/// All supported fields, in the order they are listed in errors.
+pub(crate) const FIELDS: &[&str] = &[
+    "sha256",
+    "package_name",
+    "version_name",
+    "version_code",
+    "main_activity",
+    "main_activities",
+    "min_sdk_version",
+    "max_sdk_version",
+    "target_sdk_version",
+    "application_label",
+    "permissions",
+    "declared_permissions",
+    "declared_permissions_detailed",
+    "permissions_by_protection",
+    "features",
+    "features_detailed",
+    "libraries",
+    "native_codes",
+    "supports_screens",
+    "compatible_screens",
+    "min_supported_screens",
+    "activities",
+    "activity_aliases",
+    "services",
+    "receivers",
+    "providers",
+    "exported_components",
+    "instrumentations",
+    "is_test_artifact",
+    "signatures",
+    "signer_sha256",
+    "files",
+    "files_normalized",
+    "file_infos",
+    "size_estimate",
+    "manifest_diagnostics",
+    "archive_anomalies",
+    "anomalies",
+    "unreferenced_files",
+    "audit",
+    "frameworks",
+    "packers",
+    "perf_stats",
+];
+
+/// A single request line: `{"id": 1, "path": "./file.apk", "fields": ["package_name"]}`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Request {
+    /// Opaque value echoed in the response to match it with the request
+    #[serde(default)]
+    id: Option<Value>,
+    path: PathBuf,
+    #[serde(default)]
+    fields: Vec<String>,
+}
+
+/// A single response line, either `result` or `error` is set
+#[derive(Serialize)]
+struct Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<serde_json::Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Answers requests from stdin line by line until EOF.
+///
+/// Errors of a single request are reported in its response, so the process stays
+/// alive for the next ones.
+pub(crate) fn command_serve() -> Result<()> {
+    let stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+
+    for line in stdin.lines() {
+        let line = line.context("can't read request")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => handle(request),
+            Err(e) => Response {
+                id: None,
+                path: None,
+                result: None,
+                error: Some(format!("invalid request: {e}")),
+            },
+        };
+
+        serde_json::to_writer(&mut stdout, &response)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn handle(request: Request) -> Response {
+    let result = collect(&request);
+
+    Response {
+        id: request.id,
+        path: Some(request.path),
+        error: result.as_ref().err().map(|e| format!("{e:#}")),
+        result: result.ok(),
+    }
+}
+
+fn collect(request: &Request) -> Result<serde_json::Map<String, Value>> {
+    // validate before parsing, a typo shouldn't cost a full parse
+    validate_fields(&request.fields)?;
+
+    let apk =
+        Apk::new(&request.path).with_context(|| format!("can't parse apk: {:?}", request.path))?;
+
+    if request.fields.is_empty() {
+        collect_fields(&apk, DEFAULT_FIELDS)
+    } else {
+        let fields: Vec<&str> = request.fields.iter().map(String::as_str).collect();
+        collect_fields(&apk, &fields)
+    }
+}
+
+/// Checks that all fields are known.
+pub(crate) fn validate_fields(fields: &[String]) -> Result<()> {
+    match fields
+        .iter()
+        .find(|field| !FIELDS.contains(&field.as_str()))
+    {
+        Some(field) => bail!(
+            "unknown field {:?}, expected one of: {}",
+            field,
+            FIELDS.join(", ")
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Collects values of the fields into a json object.
+pub(crate) fn collect_fields(apk: &Apk, fields: &[&str]) -> Result<serde_json::Map<String, Value>> {
+    fields
+        .iter()
+        .map(|field| Ok((field.to_string(), field_value(apk, field)?)))
+        .collect()
+}
+
+fn field_value(apk: &Apk, field: &str) -> Result<Value> {
+    let value = match field {
+        "sha256" => json!(apk.get_sha256()),
+        "package_name" => json!(apk.get_package_name()),
+        "version_name" => json!(apk.get_version_name()),
+        "version_code" => json!(apk.get_version_code()),
+        "main_activity" => json!(apk.get_main_activity()),
+        "main_activities" => json!(apk.get_main_activities().collect::<Vec<_>>()),
+        "min_sdk_version" => json!(apk.get_min_sdk_version()),
+        "max_sdk_version" => json!(apk.get_max_sdk_version()),
+        "target_sdk_version" => json!(apk.get_target_sdk_version()),
+        "application_label" => json!(apk.get_application_label()),
+        "permissions" => json!(apk.get_permissions().collect::<Vec<_>>()),
+        "declared_permissions" => json!(apk.get_declared_permissions().collect::<Vec<_>>()),
+        "declared_permissions_detailed" => {
+            json!(apk.get_declared_permissions_detailed().collect::<Vec<_>>())
+        }
+        "permissions_by_protection" => json!(apk.get_permissions_by_protection()),
+        "features" => json!(apk.get_features().collect::<Vec<_>>()),
+        "features_detailed" => json!(apk.get_features_detailed().collect::<Vec<_>>()),
+        "libraries" => json!(apk.get_libraries().collect::<Vec<_>>()),
+        "native_codes" => json!(apk.get_native_codes()),
+        "supports_screens" => json!(apk.get_supports_screens()),
+        "compatible_screens" => json!(apk.get_compatible_screens().collect::<Vec<_>>()),
+        "min_supported_screens" => json!(apk.get_min_supported_screens()),
+        "activities" => json!(apk.get_activities().collect::<Vec<_>>()),
+        "activity_aliases" => json!(apk.get_activity_aliases().collect::<Vec<_>>()),
+        "services" => json!(apk.get_services().collect::<Vec<_>>()),
+        "receivers" => json!(apk.get_receivers().collect::<Vec<_>>()),
+        "providers" => json!(apk.get_providers().collect::<Vec<_>>()),
+        "exported_components" => json!(apk.get_exported_components()),
+        "instrumentations" => json!(apk.get_instrumentations().collect::<Vec<_>>()),
+        "is_test_artifact" => json!(apk.is_test_artifact()),
+        "signatures" => json!(
+            apk.get_signatures_ref()?
+                .iter()
+                .filter(|s| !matches!(s, Signature::Unknown))
+                .collect::<Vec<_>>()
+        ),
+        "signer_sha256" => json!(
+            apk.get_signer_certificate()
+                .map(|certificate| &certificate.sha256_fingerprint)
+        ),
+        "files" => json!(apk.namelist().collect::<Vec<_>>()),
+        "files_normalized" => json!(apk.get_files_normalized()),
+        "file_infos" => json!(apk.get_file_infos()),
+        "size_estimate" => json!(apk.estimated_install_size()),
+        "manifest_diagnostics" => json!(
+            apk.get_manifest_diagnostics()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        ),
+        "archive_anomalies" => json!(
+            apk.get_archive_anomalies()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        ),
+        "anomalies" => json!(apk.get_anomalies()),
+        "unreferenced_files" => json!(apk.get_unreferenced_files()?),
+        "audit" => json!(audit(apk)),
+        "frameworks" => json!(apk.detect_frameworks()),
+        "packers" => json!(apk.detect_packers()),
+        "perf_stats" => json!(apk.perf_stats()),
+        _ => bail!("unknown field {:?}", field),
+    };
+
+    Ok(value)
+}
@@ -0,0 +1,326 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use apk_info::{AXML, Apk, ZipEntry};
+use axum::extract::{DefaultBodyLimit, Multipart, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::{Map, Value, json};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::commands::serve::{FIELDS, collect_fields, validate_fields};
+
+/// Default limit of the uploaded apk size (256 MiB)
+pub(crate) const DEFAULT_MAX_SIZE: u64 = 256 * 1024 * 1024;
+
+#[derive(Clone)]
+struct AppState {
+    /// Limits the number of apk files read and parsed at the same time, uploads wait
+    /// for a permit before they are read, so at most `jobs` files are held in memory
+    permits: Arc<Semaphore>,
+
+    /// Maximum size of the uploaded apk
+    max_size: u64,
+}
+
+/// Error returned to the client as `{"error": "..."}`
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// An apk from the multipart form: `file` with the data and optional `fields` (comma separated).
+struct Upload {
+    data: Vec<u8>,
+    fields: Vec<String>,
+}
+
+/// Serves the analysis over http:
+///
+/// - `POST /analyze` - multipart upload, responds with the requested (or all) fields;
+/// - `POST /probe` - multipart upload, responds with package name and version only.
+pub(crate) fn command_server(addr: SocketAddr, jobs: usize, max_size: u64) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .context("can't start async runtime")?;
+
+    runtime.block_on(async move {
+        let app = router(AppState {
+            permits: Arc::new(Semaphore::new(jobs.max(1))),
+            max_size,
+        });
+
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("can't listen on {addr}"))?;
+        eprintln!("listening on http://{}", listener.local_addr()?);
+
+        axum::serve(listener, app).await.context("server failed")
+    })
+}
+
+fn router(state: AppState) -> Router {
+    let max_size = state.max_size;
+
+    Router::new()
+        .route("/analyze", post(analyze))
+        .route("/probe", post(probe))
+        // some room for the multipart framing
+        .layer(DefaultBodyLimit::max(
+            usize::try_from(max_size)
+                .unwrap_or(usize::MAX)
+                .saturating_add(64 * 1024),
+        ))
+        .with_state(state)
+}
+
+async fn analyze(
+    State(state): State<AppState>,
+    multipart: Multipart,
+) -> Result<Json<Map<String, Value>>, ApiError> {
+    let _permit = acquire(&state).await?;
+    let upload = read_upload(&state, multipart).await?;
+    validate_fields(&upload.fields)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    run_blocking(move || {
+        let apk = Apk::from_bytes(upload.data).context("can't parse apk")?;
+
+        if upload.fields.is_empty() {
+            collect_fields(&apk, FIELDS)
+        } else {
+            let fields: Vec<&str> = upload.fields.iter().map(String::as_str).collect();
+            collect_fields(&apk, &fields)
+        }
+    })
+    .await
+    .map(Json)
+}
+
+async fn probe(
+    State(state): State<AppState>,
+    multipart: Multipart,
+) -> Result<Json<Value>, ApiError> {
+    let _permit = acquire(&state).await?;
+    let upload = read_upload(&state, multipart).await?;
+
+    run_blocking(move || probe_apk(upload.data)).await.map(Json)
+}
+
+/// Reads only the manifest, resources and signatures are skipped.
+///
+/// Xapk files don't have the manifest in the root, they are parsed completely.
+fn probe_apk(data: Vec<u8>) -> Result<Value> {
+    let zip = ZipEntry::new(data).context("can't parse zip archive")?;
+
+    let Ok((manifest, _)) = zip.read("AndroidManifest.xml") else {
+        let apk = Apk::from_bytes(zip.into_data()).context("can't parse apk")?;
+
+        return Ok(json!({
+            "package_name": apk.get_package_name(),
+            "version_code": apk.get_version_code(),
+            "version_name": apk.get_version_name(),
+        }));
+    };
+
+    let axml = AXML::new(&mut &manifest[..], None).context("can't parse AndroidManifest.xml")?;
+
+    Ok(json!({
+        "package_name": axml.get_attribute_value("manifest", "package", None),
+        "version_code": axml.get_attribute_value("manifest", "versionCode", None),
+        "version_name": axml.get_attribute_value("manifest", "versionName", None),
+    }))
+}
+
+async fn read_upload(state: &AppState, mut multipart: Multipart) -> Result<Upload, ApiError> {
+    let mut data = None;
+    let mut fields = Vec::new();
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError(e.status(), e.body_text()))?
+    {
+        match field.name() {
+            Some("file") => {
+                let mut bytes = Vec::new();
+                while let Some(chunk) = field
+                    .chunk()
+                    .await
+                    .map_err(|e| ApiError(e.status(), e.body_text()))?
+                {
+                    if (bytes.len() + chunk.len()) as u64 > state.max_size {
+                        return Err(ApiError(
+                            StatusCode::PAYLOAD_TOO_LARGE,
+                            format!("file is larger than {} bytes", state.max_size),
+                        ));
+                    }
+                    bytes.extend_from_slice(&chunk);
+                }
+                data = Some(bytes);
+            }
+            Some("fields") => {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|e| ApiError(e.status(), e.body_text()))?;
+                fields.extend(
+                    text.split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    match data {
+        Some(data) => Ok(Upload { data, fields }),
+        None => Err(ApiError(
+            StatusCode::BAD_REQUEST,
+            "missing multipart field \"file\"".to_string(),
+        )),
+    }
+}
+
+/// Waits for a permit, it must be held from reading the upload to the end of the analysis.
+async fn acquire(state: &AppState) -> Result<SemaphorePermit<'_>, ApiError> {
+    state.permits.acquire().await.map_err(|_| {
+        ApiError(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "server is shutting down".to_string(),
+        )
+    })
+}
+
+/// Runs the blocking analysis on the thread pool.
+async fn run_blocking<T, F>(f: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| ApiError(StatusCode::UNPROCESSABLE_ENTITY, format!("{e:#}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Starts the server on a free port, the state is returned to take the permits.
+    fn start(jobs: usize, max_size: u64) -> (SocketAddr, AppState) {
+        let state = AppState {
+            permits: Arc::new(Semaphore::new(jobs)),
+            max_size,
+        };
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let listener = runtime
+            .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = router(state.clone());
+        std::thread::spawn(move || runtime.block_on(async { axum::serve(listener, app).await }));
+
+        (addr, state)
+    }
+
+    /// Sends the multipart form, returns the status and the body of the response.
+    fn post(addr: SocketAddr, path: &str, parts: &[(&str, &[u8])]) -> (u16, String) {
+        const BOUNDARY: &str = "apk-info-test";
+
+        let mut body = Vec::new();
+        for (name, data) in parts {
+            write!(
+                body,
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n"
+            )
+            .unwrap();
+            body.extend(*data);
+            body.extend(b"\r\n");
+        }
+        write!(body, "--{BOUNDARY}--\r\n").unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(30)))
+            .unwrap();
+        write!(
+            stream,
+            "POST {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+             Content-Type: multipart/form-data; boundary={BOUNDARY}\r\n\
+             Content-Length: {}\r\n\r\n",
+            body.len()
+        )
+        .unwrap();
+        stream.write_all(&body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+
+        (status, body.to_string())
+    }
+
+    #[test]
+    fn test_bad_requests() {
+        let (addr, _) = start(2, 16);
+
+        let (status, body) = post(addr, "/probe", &[("fields", b"package_name")]);
+        assert_eq!(status, 400);
+        assert!(body.contains("missing multipart field"), "{body}");
+
+        let (status, _) = post(addr, "/analyze", &[("file", &[0; 17])]);
+        assert_eq!(status, 413);
+
+        let (status, body) = post(
+            addr,
+            "/analyze",
+            &[("file", b"PK"), ("fields", b"package_name,unknown")],
+        );
+        assert_eq!(status, 400);
+        assert!(body.contains("unknown"), "{body}");
+
+        let (status, body) = post(addr, "/probe", &[("file", b"not an apk")]);
+        assert_eq!(status, 422);
+        assert!(body.contains("can't parse zip archive"), "{body}");
+    }
+
+    #[test]
+    fn test_uploads_wait_for_permit() {
+        let (addr, state) = start(1, 16);
+        let permit = state.permits.try_acquire().unwrap();
+
+        // even a rejected upload is not read while all permits are taken
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            sender
+                .send(post(addr, "/probe", &[("file", &[0; 64])]))
+                .unwrap()
+        });
+        assert!(receiver.recv_timeout(Duration::from_millis(300)).is_err());
+
+        drop(permit);
+        let (status, _) = receiver.recv_timeout(Duration::from_secs(30)).unwrap();
+        assert_eq!(status, 413);
+    }
+}
@@ -1,48 +1,355 @@
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::Result;
-use apk_info::Apk;
-use apk_info_zip::{CertificateInfo, Signature};
+use apk_info::anomaly::AnomalyReport;
+use apk_info::batch::{self, BatchOptions};
+use apk_info::detection::FrameworkHit;
+use apk_info::indicators::Indicators;
+use apk_info::models::{Activity, ExportReason, Provider, Receiver, Service, SupportedAbis};
+use apk_info::progress::ProgressSink;
+use apk_info::{APKError, Apk, CertificateInfo, EntryInfo, FingerprintStyle, Signature};
 use colored::Colorize;
 use serde::Serialize;
+use serde_json::{Value, json};
 
-use crate::commands::path_helpers::get_all_files;
+use crate::commands::fields::{Projection, print_projection};
+use crate::commands::output::{Output, ShowFormat, StageProgress};
+use crate::commands::path_helpers::{WalkOptions, walk_files};
+use crate::commands::trusted_certs::TrustedCerts;
 
-pub(crate) fn command_show(paths: &[PathBuf], show_signatures: &bool, jsonl: &bool) -> Result<()> {
-    let files = get_all_files(paths);
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn command_show(
+    paths: &[PathBuf],
+    walk: &WalkOptions,
+    show_signatures: &bool,
+    show_exported: &bool,
+    show_frameworks: &bool,
+    show_anomalies: &bool,
+    format: ShowFormat,
+    trusted_certs: &Option<PathBuf>,
+    fingerprint_style: FingerprintStyle,
+    projection: Option<&Projection>,
+    pager: &bool,
+    jobs: usize,
+    mmap: &bool,
+) -> Result<()> {
+    let files = walk_files(paths, walk);
+    let trusted_certs = trusted_certs
+        .as_deref()
+        .map(TrustedCerts::load)
+        .transpose()?;
 
-    for (i, path) in files.iter().enumerate() {
-        show(path, show_signatures, jsonl)?;
+    let mut out = Output::new(*pager);
 
-        // Add a newline between APKs except after the last one
-        if i != files.len() - 1 {
-            println!();
+    // files are parsed on the thread pool, the output keeps the order of the paths
+    let options = BatchOptions {
+        jobs,
+        ordered: true,
+    };
+
+    // json and table outputs are printed after the whole batch, so progress is shown meanwhile
+    let progress = (projection.is_none() && matches!(format, ShowFormat::Json | ShowFormat::Table))
+        .then(StageProgress::new);
+    let progress_sink = progress
+        .as_ref()
+        .map(|progress| Arc::new(progress.clone()) as Arc<dyn ProgressSink + Send + Sync>);
+    let items = match (progress_sink, mmap) {
+        // SAFETY: the user promised the files are not modified while the command runs, see `--mmap`
+        (Some(sink), true) => unsafe {
+            batch::process_mmap_with_progress(&files, &options, Some(sink), None)?
+        },
+        (Some(sink), false) => batch::process_with_progress(&files, &options, Some(sink), None)?,
+        // SAFETY: same as above
+        (None, true) => unsafe { batch::process_mmap(&files, &options)? },
+        (None, false) => batch::process(&files, &options)?,
+    };
+
+    if let Some(projection) = projection {
+        return print_projection(&mut out, items, projection, format);
+    }
+
+    if format == ShowFormat::Json {
+        let mut reports = items
+            .map(|item| {
+                report(
+                    &item.path,
+                    item.result,
+                    show_exported,
+                    show_frameworks,
+                    show_anomalies,
+                    trusted_certs.as_ref(),
+                    fingerprint_style,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(progress) = &progress {
+            progress.finish();
+        }
+
+        let document = match reports.len() {
+            1 => reports.remove(0),
+            _ => Value::Array(reports),
+        };
+        writeln!(out, "{}", serde_json::to_string_pretty(&document)?)?;
+
+        return Ok(());
+    }
+
+    if format == ShowFormat::Table {
+        return print_table(&mut out, items, fingerprint_style, progress.as_ref());
+    }
+
+    for (i, item) in items.enumerate() {
+        show(
+            &mut out,
+            &item.path,
+            item.result,
+            show_signatures,
+            show_exported,
+            show_frameworks,
+            show_anomalies,
+            format,
+            trusted_certs.as_ref(),
+            fingerprint_style,
+        )?;
+
+        // Add an empty line between APKs in the text output
+        if format == ShowFormat::Text && i != files.len() - 1 {
+            writeln!(out)?;
         }
     }
 
     Ok(())
 }
 
-fn show(path: &Path, show_signatures: &bool, jsonl: &bool) -> Result<()> {
-    let info = match collect_apk_info(path, show_signatures) {
-        Ok(v) => v,
-        Err(e) => {
-            println!("{:?} - {}", path, e.to_string().red());
-            return Ok(());
+#[allow(clippy::too_many_arguments)]
+fn show(
+    out: &mut impl Write,
+    path: &Path,
+    apk: Result<Apk, APKError>,
+    show_signatures: &bool,
+    show_exported: &bool,
+    show_frameworks: &bool,
+    show_anomalies: &bool,
+    format: ShowFormat,
+    trusted_certs: Option<&TrustedCerts>,
+    fingerprint_style: FingerprintStyle,
+) -> Result<()> {
+    match format {
+        ShowFormat::Stix => match apk
+            .map_err(anyhow::Error::from)
+            .and_then(|apk| collect_indicators(path, &apk))
+        {
+            Ok(indicators) => Ok(writeln!(out, "{}", indicators.to_stix_bundle())?),
+            Err(e) => Ok(writeln!(out, "{:?} - {}", path, format!("{e:#}").red())?),
+        },
+        ShowFormat::Json | ShowFormat::Ndjson => {
+            let report = report(
+                path,
+                apk,
+                show_exported,
+                show_frameworks,
+                show_anomalies,
+                trusted_certs,
+                fingerprint_style,
+            )?;
+            // one record per line, including the last one
+            Ok(writeln!(out, "{}", serde_json::to_string(&report)?)?)
         }
-    };
+        ShowFormat::Table => unreachable!("the table is printed by print_table"),
+        ShowFormat::Text => {
+            match apk.map_err(anyhow::Error::from).and_then(|apk| {
+                collect_from_apk(
+                    &apk,
+                    show_signatures,
+                    show_exported,
+                    show_frameworks,
+                    show_anomalies,
+                    trusted_certs,
+                )
+            }) {
+                Ok(info) => pretty_print(out, &info, trusted_certs, fingerprint_style),
+                Err(e) => Ok(writeln!(out, "{:?} - {}", path, format!("{e:#}").red())?),
+            }
+        }
+    }
+}
 
-    if *jsonl {
-        print!("{}", serde_json::to_string(&info)?);
-    } else {
-        pretty_print(&info);
+/// Prints one row per file: package, version, min sdk, signer and flags.
+///
+/// Widths of the columns depend on all rows, so the whole batch is collected first.
+fn print_table(
+    out: &mut impl Write,
+    items: impl Iterator<Item = batch::BatchItem>,
+    fingerprint_style: FingerprintStyle,
+    progress: Option<&StageProgress>,
+) -> Result<()> {
+    const HEADER: [&str; 6] = ["path", "package", "version", "min sdk", "signer", "flags"];
+
+    let rows: Vec<(String, Result<[String; 5]>)> = items
+        .map(|item| {
+            let row = item
+                .result
+                .map_err(anyhow::Error::from)
+                .map(|apk| summary_row(&apk, fingerprint_style));
+            (item.path.display().to_string(), row)
+        })
+        .collect();
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+
+    let mut widths = HEADER.map(str::len);
+    for (path, row) in &rows {
+        widths[0] = widths[0].max(path.len());
+        if let Ok(row) = row {
+            for (width, value) in widths[1..].iter_mut().zip(row) {
+                *width = (*width).max(value.len());
+            }
+        }
+    }
+
+    let header: Vec<String> = HEADER
+        .iter()
+        .zip(widths)
+        .map(|(title, width)| format!("{title:<width$}"))
+        .collect();
+    writeln!(out, "{}", header.join(" ").trim_end().bold())?;
+
+    for (path, row) in rows {
+        let path = format!("{path:<width$}", width = widths[0]);
+        match row {
+            Ok([package, version, min_sdk, signer, flags]) => writeln!(
+                out,
+                "{} {} {:<version_width$} {:<min_sdk_width$} {} {}",
+                path,
+                format!("{package:<width$}", width = widths[1]).green(),
+                version,
+                min_sdk,
+                format!("{signer:<width$}", width = widths[4]).blue(),
+                flags.red(),
+                version_width = widths[2],
+                min_sdk_width = widths[3],
+            )?,
+            Err(e) => writeln!(out, "{} {}", path, format!("{e:#}").red())?,
+        }
     }
 
     Ok(())
 }
 
+/// Package, version, min sdk, sha256 of the signer certificate and flags of the apk.
+fn summary_row(apk: &Apk, fingerprint_style: FingerprintStyle) -> [String; 5] {
+    let signer = apk.get_signer_certificate();
+
+    let mut flags = Vec::new();
+    if apk.is_debuggable() == Some(true) {
+        flags.push("debuggable");
+    }
+    if apk.get_application_test_only().as_deref() == Some("true") {
+        flags.push("test-only");
+    }
+    // broken signatures are not the same as missing ones
+    if apk.get_signatures_ref().is_err() {
+        flags.push("sig-error");
+    } else if signer.is_none() {
+        flags.push("unsigned");
+    }
+    if signer.is_some_and(|certificate| certificate.is_debug_cert) {
+        flags.push("debug-cert");
+    }
+
+    let version = match (apk.get_version_name(), apk.get_version_code()) {
+        (Some(name), Some(code)) => format!("{name} ({code})"),
+        (name, code) => name.or(code).unwrap_or_else(|| "-".to_string()),
+    };
+
+    [
+        apk.get_package_name().unwrap_or_else(|| "-".to_string()),
+        version,
+        apk.get_min_sdk_version().unwrap_or_else(|| "-".to_string()),
+        signer.map_or_else(
+            || "-".to_string(),
+            |certificate| certificate.fingerprint_sha256_formatted(fingerprint_style),
+        ),
+        flags.join(","),
+    ]
+}
+
+/// Builds the full report of the file for the json formats.
+///
+/// Files that can't be parsed are reported as `{"path": ..., "error": ...}`,
+/// so one broken file doesn't break the whole pipeline.
+fn report(
+    path: &Path,
+    apk: Result<Apk, APKError>,
+    show_exported: &bool,
+    show_frameworks: &bool,
+    show_anomalies: &bool,
+    trusted_certs: Option<&TrustedCerts>,
+    fingerprint_style: FingerprintStyle,
+) -> Result<Value> {
+    let result = apk.map_err(anyhow::Error::from).and_then(|apk| {
+        // signatures are always a part of the report
+        let mut info = collect_from_apk(
+            &apk,
+            &true,
+            show_exported,
+            show_frameworks,
+            show_anomalies,
+            trusted_certs,
+        )?;
+
+        // trusted certificates are already resolved, fingerprints can be rewritten
+        for certificate in info
+            .signatures
+            .iter_mut()
+            .flatten()
+            .flat_map(certificates_mut)
+        {
+            certificate.md5_fingerprint = certificate.fingerprint_md5_formatted(fingerprint_style);
+            certificate.sha1_fingerprint =
+                certificate.fingerprint_sha1_formatted(fingerprint_style);
+            certificate.sha256_fingerprint =
+                certificate.fingerprint_sha256_formatted(fingerprint_style);
+        }
+
+        let report = ApkReport {
+            path,
+            info,
+            permissions: apk.get_permissions().collect(),
+            activities: apk.get_activities().collect(),
+            services: apk.get_services().collect(),
+            receivers: apk.get_receivers().collect(),
+            providers: apk.get_providers().collect(),
+            files: apk.get_file_infos(),
+        };
+
+        Ok(serde_json::to_value(report)?)
+    });
+
+    Ok(result.unwrap_or_else(|e| json!({ "path": path, "error": format!("{e:#}") })))
+}
+
+/// Everything `show` knows about the apk, for the json formats
+#[derive(Serialize)]
+struct ApkReport<'a> {
+    path: &'a Path,
+    #[serde(flatten)]
+    info: ApkInfo,
+    permissions: Vec<&'a str>,
+    activities: Vec<Activity<'a>>,
+    services: Vec<Service<'a>>,
+    receivers: Vec<Receiver<'a>>,
+    providers: Vec<Provider<'a>>,
+    files: Vec<EntryInfo>,
+}
+
 #[derive(Serialize)]
-struct ApkInfo {
+pub(crate) struct ApkInfo {
     pub package_name: String,
     pub version_name: String,
     pub version_code: String,
@@ -51,13 +358,69 @@ struct ApkInfo {
     pub max_sdk_version: String,
     pub target_sdk_version: String,
     pub application_label: String,
+    pub supported_abis: SupportedAbis,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signatures: Option<Vec<Signature>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signer: Option<SignerStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exported_components: Option<Vec<ExportedComponentInfo>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frameworks: Option<Vec<FrameworkHit>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anomalies: Option<AnomalyReport>,
 }
 
-fn collect_apk_info(path: &Path, show_signatures: &bool) -> Result<ApkInfo> {
-    let apk = Apk::new(path)?;
+/// Owned copy of [apk_info::models::ExportedComponent]
+#[derive(Serialize)]
+pub(crate) struct ExportedComponentInfo {
+    pub kind: &'static str,
+    pub name: String,
+    pub reason: ExportReason,
+    pub permission: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_permission: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_permission: Option<String>,
+    pub enabled: bool,
+    pub protected: bool,
+}
 
+/// Verdict about the apk signer based on the list of trusted certificates
+#[derive(Serialize)]
+#[serde(tag = "status", content = "label", rename_all = "snake_case")]
+pub(crate) enum SignerStatus {
+    Trusted(String),
+    Unknown,
+}
+
+impl std::fmt::Display for SignerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignerStatus::Trusted(label) => write!(f, "TRUSTED ({})", label),
+            SignerStatus::Unknown => write!(f, "UNKNOWN SIGNER"),
+        }
+    }
+}
+
+fn collect_indicators(path: &Path, apk: &Apk) -> Result<Indicators> {
+    let mut indicators = Indicators::collect(apk)?;
+    indicators.file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+
+    Ok(indicators)
+}
+
+/// Collects the same information as `show` for the already parsed apk.
+pub(crate) fn collect_from_apk(
+    apk: &Apk,
+    show_signatures: &bool,
+    show_exported: &bool,
+    show_frameworks: &bool,
+    show_anomalies: &bool,
+    trusted_certs: Option<&TrustedCerts>,
+) -> Result<ApkInfo> {
     let signatures = if *show_signatures {
         Some(
             apk.get_signatures()?
@@ -69,6 +432,33 @@ fn collect_apk_info(path: &Path, show_signatures: &bool) -> Result<ApkInfo> {
         None
     };
 
+    let signer =
+        trusted_certs
+            .zip(signatures.as_deref())
+            .map(|(trusted, signatures)| match trusted.verify(signatures) {
+                Some(label) => SignerStatus::Trusted(label.to_string()),
+                None => SignerStatus::Unknown,
+            });
+
+    let exported_components = show_exported.then(|| {
+        apk.get_exported_components()
+            .into_iter()
+            .map(|component| ExportedComponentInfo {
+                kind: component.kind.tag(),
+                protected: component.is_protected(),
+                name: component.name.unwrap_or("-").to_string(),
+                reason: component.reason,
+                permission: component.permission.map(String::from),
+                read_permission: component.read_permission.map(String::from),
+                write_permission: component.write_permission.map(String::from),
+                enabled: component.enabled,
+            })
+            .collect()
+    });
+
+    let frameworks = show_frameworks.then(|| apk.detect_frameworks());
+    let anomalies = show_anomalies.then(|| apk.get_anomalies());
+
     Ok(ApkInfo {
         package_name: apk.get_package_name().unwrap_or_else(|| "-".to_string()),
         version_name: apk.get_version_name().unwrap_or_else(|| "-".to_string()),
@@ -84,45 +474,198 @@ fn collect_apk_info(path: &Path, show_signatures: &bool) -> Result<ApkInfo> {
         application_label: apk
             .get_application_label()
             .unwrap_or_else(|| "-".to_string()),
+        supported_abis: apk.get_supported_abis(),
         signatures,
+        signer,
+        exported_components,
+        frameworks,
+        anomalies,
     })
 }
 
-fn pretty_print(info: &ApkInfo) {
-    println!("Package Name: {}", info.package_name.green(),);
-    println!("Main Activity: {}", info.main_activity.green(),);
-    println!("Min SDK Version: {}", info.min_sdk_version.green(),);
-    println!("Max SDK Version: {}", info.max_sdk_version.green(),);
-    println!("Target SDK Version: {}", info.target_sdk_version.green(),);
-    println!("Application Label: {}", info.application_label.green(),);
-    println!("Version Name: {}", info.version_name.green(),);
-    println!("Version Code: {}", info.version_code.green(),);
+pub(crate) fn pretty_print(
+    out: &mut impl Write,
+    info: &ApkInfo,
+    trusted_certs: Option<&TrustedCerts>,
+    fingerprint_style: FingerprintStyle,
+) -> Result<()> {
+    writeln!(out, "Package Name: {}", info.package_name.green())?;
+    writeln!(out, "Main Activity: {}", info.main_activity.green())?;
+    writeln!(out, "Min SDK Version: {}", info.min_sdk_version.green())?;
+    writeln!(out, "Max SDK Version: {}", info.max_sdk_version.green())?;
+    writeln!(
+        out,
+        "Target SDK Version: {}",
+        info.target_sdk_version.green(),
+    )?;
+    writeln!(out, "Application Label: {}", info.application_label.green())?;
+    writeln!(out, "Version Name: {}", info.version_name.green())?;
+    writeln!(out, "Version Code: {}", info.version_code.green())?;
+
+    let abis = &info.supported_abis;
+    if abis.has_native_code() {
+        let mut flags = Vec::new();
+        if !abis.extract_native_libs {
+            flags.push("not extracted");
+        }
+        if abis.multi_arch {
+            flags.push("multiArch");
+        }
+        if !abis.supports_64bit() {
+            flags.push("32-bit only");
+        }
+
+        let flags = if flags.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", flags.join(", "))
+        };
+        writeln!(
+            out,
+            "Native ABIs: {}{}",
+            abis.abis.join(", ").green(),
+            flags
+        )?;
+    } else {
+        writeln!(out, "Native ABIs: {}", "-".green())?;
+    }
+
+    match &info.signer {
+        Some(signer @ SignerStatus::Trusted(_)) => {
+            writeln!(out, "Signer: {}", signer.to_string().green().bold())?
+        }
+        Some(signer @ SignerStatus::Unknown) => {
+            writeln!(out, "Signer: {}", signer.to_string().red().bold())?
+        }
+        None => {}
+    }
+
+    if let Some(components) = &info.exported_components {
+        writeln!(out, "{}:", "Exported Components".blue().bold())?;
+
+        for component in components {
+            let reason = match component.reason {
+                ExportReason::Explicit => "",
+                ExportReason::IntentFilters => " (by intent filters)",
+                ExportReason::LegacyProviderDefault => " (by default for sdk <= 16)",
+            };
+            let disabled = if component.enabled { "" } else { " [disabled]" };
+            writeln!(
+                out,
+                "  {} {}{}{}",
+                component.kind,
+                component.name.green(),
+                reason,
+                disabled
+            )?;
+
+            let permissions = [
+                ("Permission", &component.permission),
+                ("Read permission", &component.read_permission),
+                ("Write permission", &component.write_permission),
+            ];
+            for (label, permission) in permissions {
+                if let Some(permission) = permission {
+                    writeln!(out, "    {}: {}", label, permission.green())?;
+                }
+            }
+            if !component.protected {
+                writeln!(out, "    {}", "not protected by a permission".red())?;
+            }
+        }
+    }
+
+    if let Some(frameworks) = &info.frameworks {
+        writeln!(out, "{}:", "Frameworks".blue().bold())?;
+
+        for hit in frameworks {
+            let version = hit
+                .version
+                .as_deref()
+                .map(|version| format!(" {version}"))
+                .unwrap_or_default();
+            writeln!(out, "  {}{}", hit.framework.to_string().green(), version)?;
+            writeln!(out, "    Evidence: {}", hit.evidence.join(", "))?;
+        }
+    }
+
+    if let Some(anomalies) = &info.anomalies {
+        writeln!(out, "{}:", "Anomalies".blue().bold())?;
+
+        if anomalies.is_empty() {
+            writeln!(out, "  {}", "none".green())?;
+        }
+        for anomaly in anomalies {
+            writeln!(out, "  {}", anomaly.to_string().red())?;
+        }
+    }
 
     if let Some(signatures) = &info.signatures {
-        println!("{}:", "APK Signature block".blue().bold());
+        writeln!(out, "{}:", "APK Signature block".blue().bold())?;
 
         for (i, signature) in signatures.iter().enumerate() {
             match signature {
                 Signature::V1(certificates)
-                | Signature::V2(certificates)
-                | Signature::V3(certificates)
-                | Signature::V31(certificates) => {
-                    println!("  Type: {}", signature.name().green());
+                | Signature::V2 { certificates, .. }
+                | Signature::V3 { certificates, .. }
+                | Signature::V31 { certificates, .. } => {
+                    writeln!(out, "  Type: {}", signature.name().green())?;
 
                     for (j, certificate) in certificates.iter().enumerate() {
-                        print_certificate(certificate);
+                        print_certificate(out, certificate, fingerprint_style)?;
+                        if let Some(trusted) = trusted_certs {
+                            match trusted.label(certificate) {
+                                Some(label) => writeln!(out, "  Trusted: {}", label.green())?,
+                                None => writeln!(out, "  Trusted: {}", "no".red())?,
+                            }
+                        }
                         if j != certificates.len() - 1 {
-                            println!();
+                            writeln!(out)?;
+                        }
+                    }
+
+                    if let Signature::V2 { algorithms, .. }
+                    | Signature::V3 { algorithms, .. }
+                    | Signature::V31 { algorithms, .. } = signature
+                        && !algorithms.is_empty()
+                    {
+                        let algorithms: Vec<String> = algorithms
+                            .iter()
+                            .map(|algorithm| {
+                                if algorithm.is_weak() {
+                                    algorithm.name().red().to_string()
+                                } else {
+                                    algorithm.name().green().to_string()
+                                }
+                            })
+                            .collect();
+                        writeln!(out, "  Algorithms: {}", algorithms.join(", "))?;
+                    }
+
+                    if let Signature::V3 { lineage, .. } | Signature::V31 { lineage, .. } =
+                        signature
+                        && !lineage.is_empty()
+                    {
+                        writeln!(out, "  Lineage:")?;
+                        for node in lineage {
+                            writeln!(
+                                out,
+                                "    {} ({}): {}",
+                                node.certificate.subject.green(),
+                                node.certificate
+                                    .fingerprint_sha256_formatted(fingerprint_style),
+                                node.capabilities().join(", ")
+                            )?;
                         }
                     }
                 }
                 Signature::StampBlockV1(certificate) | Signature::StampBlockV2(certificate) => {
-                    println!("  Type: {}", signature.name().green());
-                    print_certificate(certificate);
+                    writeln!(out, "  Type: {}", signature.name().green())?;
+                    print_certificate(out, certificate, fingerprint_style)?;
                 }
                 Signature::ApkChannelBlock(channel) => {
-                    println!("  Type: {}", signature.name().green());
-                    println!("  Channel: {}", channel.green());
+                    writeln!(out, "  Type: {}", signature.name().green())?;
+                    writeln!(out, "  Channel: {}", channel.green())?;
                 }
                 Signature::PackerNextGenV2(data) => {
                     let hex_string = data
@@ -131,41 +674,144 @@ fn pretty_print(info: &ApkInfo) {
                         .collect::<Vec<_>>()
                         .join("");
 
-                    println!("  Type: {}", signature.name().green());
-                    println!("  Value: {}", hex_string.green());
+                    writeln!(out, "  Type: {}", signature.name().green())?;
+                    writeln!(out, "  Value: {}", hex_string.green())?;
                 }
-                Signature::GooglePlayFrosting => {
-                    println!("  Type: {}", signature.name().green());
-                    println!("  Info: {}", "Metadata exist".green());
+                Signature::Frosting(frosting) => {
+                    writeln!(out, "  Type: {}", signature.name().green())?;
+                    writeln!(
+                        out,
+                        "  Metadata: {} bytes, {} fields",
+                        frosting.metadata.len(),
+                        frosting.fields.len()
+                    )?;
+                    writeln!(out, "  Signature: {} bytes", frosting.signature_data.len())?;
                 }
                 Signature::VasDollyV2(channel) => {
-                    println!("  Type: {}", signature.name().green());
-                    println!("  Channel: {}", channel.green());
+                    writeln!(out, "  Type: {}", signature.name().green())?;
+                    writeln!(out, "  Channel: {}", channel.green())?;
+                }
+                Signature::UnknownBlock { data, .. } => {
+                    writeln!(out, "  Type: {}", signature.name().yellow())?;
+                    writeln!(out, "  Size: {}", data.len())?;
                 }
                 _ => continue,
             }
 
             if i != signatures.len() - 1 {
-                println!();
+                writeln!(out)?;
             }
         }
     }
+
+    Ok(())
 }
 
-fn print_certificate(certificate: &CertificateInfo) {
-    println!("  Serial Number: {}", certificate.serial_number.green());
-    println!("  Subject: {}", certificate.subject.green());
-    println!("  Issuer: {}", certificate.issuer.green());
-    println!("  Valid from: {}", certificate.valid_from.green());
-    println!("  Valid until: {}", certificate.valid_until.green());
-    println!("  Signature type: {}", certificate.signature_type.green());
-    println!("  MD5 fingerprint: {}", certificate.md5_fingerprint.green());
-    println!(
+/// Certificates of the signature and its lineage, if it has any.
+fn certificates_mut(signature: &mut Signature) -> Vec<&mut CertificateInfo> {
+    match signature {
+        Signature::V1(certificates) | Signature::V2 { certificates, .. } => {
+            certificates.iter_mut().collect()
+        }
+        Signature::V3 {
+            certificates,
+            lineage,
+            ..
+        }
+        | Signature::V31 {
+            certificates,
+            lineage,
+            ..
+        } => certificates
+            .iter_mut()
+            .chain(lineage.iter_mut().map(|node| &mut node.certificate))
+            .collect(),
+        Signature::StampBlockV1(certificate) | Signature::StampBlockV2(certificate) => {
+            vec![certificate]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn print_certificate(
+    out: &mut impl Write,
+    certificate: &CertificateInfo,
+    fingerprint_style: FingerprintStyle,
+) -> Result<()> {
+    writeln!(
+        out,
+        "  Serial Number: {}",
+        certificate.serial_number.green()
+    )?;
+    writeln!(out, "  Subject: {}", certificate.subject.green())?;
+    writeln!(out, "  Issuer: {}", certificate.issuer.green())?;
+    writeln!(
+        out,
+        "  Version: {}",
+        certificate.version.to_string().green()
+    )?;
+    let key = match certificate.key_size {
+        Some(size) => format!("{} {size}", certificate.key_algorithm),
+        None => certificate.key_algorithm.clone(),
+    };
+    writeln!(out, "  Key: {}", key.green())?;
+    if !certificate.extensions.is_empty() {
+        writeln!(
+            out,
+            "  Extensions: {}",
+            certificate.extensions.join(", ").green()
+        )?;
+    }
+    writeln!(out, "  Valid from: {}", certificate.valid_from.green())?;
+    writeln!(out, "  Valid until: {}", certificate.valid_until.green())?;
+    writeln!(
+        out,
+        "  Signature type: {}",
+        certificate.signature_type.green()
+    )?;
+    writeln!(
+        out,
+        "  MD5 fingerprint: {}",
+        certificate
+            .fingerprint_md5_formatted(fingerprint_style)
+            .green()
+    )?;
+    writeln!(
+        out,
         "  SHA1 fingerprint: {}",
-        certificate.sha1_fingerprint.green()
-    );
-    println!(
+        certificate
+            .fingerprint_sha1_formatted(fingerprint_style)
+            .green()
+    )?;
+    writeln!(
+        out,
         "  SHA256 fingerprint: {}",
-        certificate.sha256_fingerprint.green()
-    );
+        certificate
+            .fingerprint_sha256_formatted(fingerprint_style)
+            .green()
+    )?;
+    writeln!(
+        out,
+        "  Public key SHA256: {}",
+        fingerprint_style
+            .format(&certificate.public_key_sha256)
+            .unwrap_or_else(|| certificate.public_key_sha256.clone())
+            .green()
+    )?;
+    if certificate.is_debug_cert {
+        writeln!(
+            out,
+            "  {}",
+            "Debug certificate, anyone can sign with it".red()
+        )?;
+    }
+    if certificate.has_public_key_mismatch() {
+        writeln!(
+            out,
+            "  {}",
+            "Signer public key doesn't match the certificate".red()
+        )?;
+    }
+
+    Ok(())
 }
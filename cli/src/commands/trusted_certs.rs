@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use apk_info::{CertificateInfo, Signature};
+
+/// Mapping of known signer certificates (sha256 fingerprint -> label)
+///
+/// Expected file format:
+///
+/// ```json
+/// {
+///     "a40da80a59d170caa950cf15c18c454d47a39b26989d8b640ecd745ba71bf5dc": "Google LLC"
+/// }
+/// ```
+pub(crate) struct TrustedCerts(HashMap<String, String>);
+
+impl TrustedCerts {
+    pub(crate) fn load(path: &Path) -> Result<TrustedCerts> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("can't read trusted certificates: {:?}", path))?;
+        let certs: HashMap<String, String> = serde_json::from_slice(&data)
+            .with_context(|| format!("invalid trusted certificates file: {:?}", path))?;
+
+        // fingerprints are often copied from keytool output, e.g. "A4:0D:A8:..."
+        Ok(TrustedCerts(
+            certs
+                .into_iter()
+                .map(|(fingerprint, label)| (normalize_fingerprint(&fingerprint), label))
+                .collect(),
+        ))
+    }
+
+    /// Returns the label of the certificate, if it is known.
+    pub(crate) fn label(&self, certificate: &CertificateInfo) -> Option<&str> {
+        self.0
+            .get(&certificate.sha256_fingerprint)
+            .map(String::as_str)
+    }
+
+    /// Returns the label of the signer if every signer of the newest scheme is known.
+    ///
+    /// Android verifies only the newest scheme it supports, so an older scheme signed by a trusted
+    /// key proves nothing. Certificates that aren't signers, like extra certificates stuffed into
+    /// the PKCS#7 bag of v1, are ignored.
+    pub(crate) fn verify(&self, signatures: &[Signature]) -> Option<&str> {
+        let (_, certificates) = signatures
+            .iter()
+            .filter_map(|signature| match signature {
+                Signature::V31 { certificates, .. } => Some((0, certificates)),
+                Signature::V3 { certificates, .. } => Some((1, certificates)),
+                Signature::V2 { certificates, .. } => Some((2, certificates)),
+                Signature::V1(certificates) => Some((3, certificates)),
+                _ => None,
+            })
+            .min_by_key(|(priority, _)| *priority)?;
+
+        let mut labels = certificates
+            .iter()
+            .filter(|certificate| certificate.is_signer)
+            .map(|certificate| self.label(certificate));
+        let label = labels.next()??;
+
+        labels.all(|label| label.is_some()).then_some(label)
+    }
+}
+
+fn normalize_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRUSTED: &str = "a40da80a59d170caa950cf15c18c454d47a39b26989d8b640ecd745ba71bf5dc";
+
+    fn trusted() -> TrustedCerts {
+        TrustedCerts(HashMap::from([(TRUSTED.to_string(), "Bank".to_string())]))
+    }
+
+    fn certificate(sha256: &str, is_signer: bool) -> CertificateInfo {
+        serde_json::from_value(serde_json::json!({
+            "serial_number": "01",
+            "subject": "CN=test",
+            "issuer": "CN=test",
+            "valid_from": "",
+            "valid_until": "",
+            "signature_type": "",
+            "md5_fingerprint": "",
+            "sha1_fingerprint": "",
+            "sha256_fingerprint": sha256,
+            "public_key_sha256": "",
+            "is_signer": is_signer,
+        }))
+        .unwrap()
+    }
+
+    fn v2(certificates: Vec<CertificateInfo>) -> Signature {
+        Signature::V2 {
+            certificates,
+            algorithms: Vec::new(),
+            digests: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_fingerprint() {
+        assert_eq!(normalize_fingerprint("A4:0D:a8"), "a40da8");
+    }
+
+    #[test]
+    fn test_verify_signer() {
+        let trusted = trusted();
+        let signatures = [v2(vec![certificate(TRUSTED, true)])];
+        assert_eq!(trusted.verify(&signatures), Some("Bank"));
+        assert_eq!(trusted.verify(&[]), None);
+    }
+
+    #[test]
+    fn test_verify_ignores_bag_certificates() {
+        // a clone signed by its own key with the official certificate added to the bag
+        let trusted = trusted();
+        let signatures = [Signature::V1(vec![
+            certificate("00", true),
+            certificate(TRUSTED, false),
+        ])];
+        assert_eq!(trusted.verify(&signatures), None);
+    }
+
+    #[test]
+    fn test_verify_newest_scheme() {
+        // v1 of the official app, but Android checks only the v2 signed by another key
+        let trusted = trusted();
+        let signatures = [
+            Signature::V1(vec![certificate(TRUSTED, true)]),
+            v2(vec![certificate("00", true)]),
+        ];
+        assert_eq!(trusted.verify(&signatures), None);
+
+        // every signer must be known
+        let signatures = [v2(vec![
+            certificate(TRUSTED, true),
+            certificate("00", true),
+        ])];
+        assert_eq!(trusted.verify(&signatures), None);
+    }
+}
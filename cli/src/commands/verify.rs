@@ -0,0 +1,223 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use apk_info::{Apk, DigestCheck, Signature, StructureIssue, V1Verification};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::commands::output::StageProgress;
+
+const V1_CHECK: &str = "v1 digests";
+const STRUCTURE_CHECK: &str = "zip structure";
+
+/// Outcome of a single check.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Pass,
+    Fail,
+    Skip,
+}
+
+impl Status {
+    fn colored(self) -> colored::ColoredString {
+        match self {
+            Status::Pass => "PASS".green().bold(),
+            Status::Fail => "FAIL".red().bold(),
+            Status::Skip => "SKIP".yellow().bold(),
+        }
+    }
+}
+
+/// Result of a check with the reasons of the failure.
+#[derive(Serialize)]
+struct Check {
+    name: String,
+    status: Status,
+    details: Vec<String>,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, failures: Vec<String>) -> Check {
+        let status = if failures.is_empty() {
+            Status::Pass
+        } else {
+            Status::Fail
+        };
+
+        Check {
+            name: name.into(),
+            status,
+            details: failures,
+        }
+    }
+
+    fn skip(name: impl Into<String>, reason: &str) -> Check {
+        Check {
+            name: name.into(),
+            status: Status::Skip,
+            details: vec![reason.to_string()],
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct VerifyReport<'a> {
+    path: &'a Path,
+    passed: bool,
+    checks: Vec<Check>,
+    v1: Option<V1Verification>,
+    content_digests: Vec<DigestCheck>,
+    structure: Vec<StructureIssue>,
+}
+
+/// Checks digests and integrity of the apk, fails if any check fails.
+///
+/// The signatures themselves are not verified cryptographically: the v1 digests,
+/// the content digests of v2+ signers and the consistency of the zip are checked.
+/// Anyone who patches the apk can rewrite the digests, so the checks are named after
+/// the digests and never report a signature scheme as passed.
+pub(crate) fn command_verify(path: &Path, json: &bool) -> Result<()> {
+    let apk = Apk::new(path).with_context(|| format!("can't parse apk: {:?}", path))?;
+
+    let progress = StageProgress::new();
+    let v1 = apk.verify_signature_v1_with_progress(Some(&progress), None)?;
+    progress.finish();
+    let content_digests = apk
+        .verify_content_digests()
+        .context("can't parse the signing block")?;
+    let structure = apk.verify_structure();
+
+    // the same scheme can have several signers
+    let mut schemes: Vec<String> = apk
+        .get_signatures_ref()?
+        .iter()
+        .filter(|signature| {
+            matches!(
+                signature,
+                Signature::V2 { .. } | Signature::V3 { .. } | Signature::V31 { .. }
+            )
+        })
+        .map(Signature::name)
+        .collect();
+    schemes.sort_unstable();
+    schemes.dedup();
+
+    let mut checks = Vec::new();
+    checks.push(check_v1(v1.as_ref(), &schemes));
+    checks.extend(
+        schemes
+            .iter()
+            .map(|scheme| check_content_digests(scheme, &content_digests)),
+    );
+    checks.push(Check::new(
+        STRUCTURE_CHECK,
+        structure.iter().map(ToString::to_string).collect(),
+    ));
+
+    let signed = checks
+        .iter()
+        .any(|check| check.name != STRUCTURE_CHECK && check.status != Status::Skip);
+    if !signed {
+        checks.push(Check::new("signed", vec!["no signatures to verify".into()]));
+    }
+
+    let passed = checks.iter().all(|check| check.status != Status::Fail);
+
+    if *json {
+        let report = VerifyReport {
+            path,
+            passed,
+            checks,
+            v1,
+            content_digests,
+            structure,
+        };
+        println!("{}", serde_json::to_string(&report)?);
+    } else {
+        for check in &checks {
+            println!("{}: {}", check.name.blue().bold(), check.status.colored());
+            for detail in &check.details {
+                println!("  {}", detail);
+            }
+        }
+    }
+
+    if !passed {
+        bail!("verification failed: {:?}", path);
+    }
+
+    Ok(())
+}
+
+fn check_v1(v1: Option<&V1Verification>, schemes: &[String]) -> Check {
+    let Some(v1) = v1 else {
+        return Check::skip(V1_CHECK, "no META-INF/MANIFEST.MF");
+    };
+    if v1.signature_files.is_empty() {
+        return Check::new(V1_CHECK, vec!["no .SF files".into()]);
+    }
+
+    let mut failures = Vec::new();
+    let mut add = |reason: &str, names: &[String]| {
+        failures.extend(names.iter().map(|name| format!("{reason}: {name}")));
+    };
+    add("modified", &v1.modified);
+    add("missing", &v1.missing);
+    add("not in manifest", &v1.extra);
+    add("unsupported digest", &v1.unverified);
+
+    for file in &v1.signature_files {
+        if !file.is_valid() {
+            failures.push(format!("{} doesn't match MANIFEST.MF", file.name));
+        }
+        failures.extend(
+            file.unsigned_sections
+                .iter()
+                .map(|name| format!("not signed by {}: {name}", file.name)),
+        );
+
+        // Android rejects apks whose newer signatures were stripped
+        let declared = file.apk_signed.as_deref().unwrap_or_default();
+        for id in declared.split(',').map(str::trim) {
+            let scheme = match id {
+                "2" => "v2",
+                "3" => "v3",
+                _ => continue,
+            };
+            if !schemes.iter().any(|name| name == scheme) {
+                failures.push(format!(
+                    "{} declares a {scheme} signature, but the apk has none",
+                    file.name
+                ));
+            }
+        }
+    }
+
+    Check::new(V1_CHECK, failures)
+}
+
+/// Compares the content digests stored in the signed data of the scheme with the apk,
+/// the signature over the signed data isn't verified.
+fn check_content_digests(scheme: &str, content_digests: &[DigestCheck]) -> Check {
+    let name = format!("{scheme} content digest");
+    let checks: Vec<&DigestCheck> = content_digests
+        .iter()
+        .filter(|check| check.scheme == scheme)
+        .collect();
+
+    if checks.is_empty() {
+        return Check::new(name, vec!["signer has no content digests".into()]);
+    }
+    if checks.iter().all(|check| check.is_valid().is_none()) {
+        return Check::skip(name, "only verity digests, not supported");
+    }
+
+    let failures = checks
+        .iter()
+        .filter(|check| check.is_valid() == Some(false))
+        .map(|check| format!("{} content digest doesn't match", check.algorithm))
+        .collect();
+
+    Check::new(name, failures)
+}
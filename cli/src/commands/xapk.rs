@@ -0,0 +1,157 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use apk_info::FingerprintStyle;
+use apk_info::models::{XapkExpansion, XapkManifest};
+use apk_info::xapk::{Xapk, XapkMember};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::commands::output::Output;
+use crate::commands::show::{ApkInfo, collect_from_apk, pretty_print};
+
+/// Structure of the bundle, inner apks are analyzed only with `--analyze-all`
+#[derive(Serialize)]
+struct XapkInfo {
+    manifest: XapkManifest,
+    members: Vec<XapkMember>,
+    expansions: Vec<XapkExpansion>,
+    icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    apks: Option<Vec<InnerApk>>,
+}
+
+#[derive(Serialize)]
+struct InnerApk {
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    info: Option<ApkInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Lists inner apks, expansion files and the store manifest of the xapk bundle.
+pub(crate) fn command_xapk(
+    path: &Path,
+    analyze_all: &bool,
+    json: &bool,
+    pager: &bool,
+) -> Result<()> {
+    let xapk = Xapk::new(path).with_context(|| format!("can't parse xapk: {:?}", path))?;
+    let members = xapk.members();
+
+    let apks = analyze_all.then(|| {
+        members
+            .iter()
+            .map(|member| {
+                let info = xapk
+                    .read_apk(&member.file)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|apk| collect_from_apk(&apk, &false, &false, &false, &false, None));
+
+                InnerApk {
+                    file: member.file.clone(),
+                    error: info.as_ref().err().map(|e| format!("{e:#}")),
+                    info: info.ok(),
+                }
+            })
+            .collect()
+    });
+
+    let info = XapkInfo {
+        manifest: xapk.manifest().clone(),
+        members,
+        expansions: xapk.expansions(),
+        icon: xapk.icon().map(String::from),
+        apks,
+    };
+
+    let mut out = Output::new(*pager);
+
+    if *json {
+        writeln!(out, "{}", serde_json::to_string(&info)?)?;
+        return Ok(());
+    }
+
+    pretty_print_xapk(&mut out, &info)
+}
+
+fn pretty_print_xapk(out: &mut impl Write, info: &XapkInfo) -> Result<()> {
+    let manifest = &info.manifest;
+    let or_dash = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+
+    writeln!(out, "Package Name: {}", manifest.package_name.green())?;
+    writeln!(out, "Name: {}", or_dash(&manifest.name).green())?;
+    writeln!(
+        out,
+        "Version Name: {}",
+        or_dash(&manifest.version_name).green()
+    )?;
+    writeln!(
+        out,
+        "Version Code: {}",
+        or_dash(&manifest.version_code).green()
+    )?;
+    writeln!(
+        out,
+        "Min SDK Version: {}",
+        or_dash(&manifest.min_sdk_version).green()
+    )?;
+    writeln!(
+        out,
+        "Target SDK Version: {}",
+        or_dash(&manifest.target_sdk_version).green()
+    )?;
+    writeln!(
+        out,
+        "Xapk Version: {}",
+        or_dash(&manifest.xapk_version).green()
+    )?;
+    writeln!(out, "Icon: {}", or_dash(&info.icon).green())?;
+    writeln!(
+        out,
+        "Permissions: {}",
+        manifest.permissions.len().to_string().green()
+    )?;
+
+    writeln!(out, "{}:", "Apks".blue().bold())?;
+    for member in &info.members {
+        writeln!(
+            out,
+            "  {}: {}, {} bytes",
+            member.file,
+            member.role.to_string().green(),
+            member.size
+        )?;
+    }
+
+    if !info.expansions.is_empty() {
+        writeln!(out, "{}:", "Expansions".blue().bold())?;
+        for expansion in &info.expansions {
+            match (&expansion.install_location, &expansion.install_path) {
+                (Some(location), Some(install_path)) => writeln!(
+                    out,
+                    "  {}: {} {}",
+                    expansion.file,
+                    location.green(),
+                    install_path.green()
+                )?,
+                _ => writeln!(out, "  {}", expansion.file)?,
+            }
+        }
+    }
+
+    for apk in info.apks.iter().flatten() {
+        writeln!(out)?;
+        writeln!(out, "{}:", apk.file.blue().bold())?;
+
+        match (&apk.info, &apk.error) {
+            (Some(info), _) => pretty_print(out, info, None, FingerprintStyle::default())?,
+            (None, Some(error)) => writeln!(out, "{}", error.red())?,
+            (None, None) => {}
+        }
+    }
+
+    Ok(())
+}
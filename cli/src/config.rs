@@ -0,0 +1,248 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use apk_info::FingerprintStyle;
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::commands::output::ColorChoice;
+
+/// Name of the configuration file looked up in the current directory.
+const CONFIG_NAME: &str = "apk-info.toml";
+
+/// Defaults for command-line options, so teams can share the same behavior
+/// without long command lines.
+///
+/// Files are layered: `$XDG_CONFIG_HOME/apk-info/config.toml` (or `~/.config/...`),
+/// then `./apk-info.toml`, then command-line flags. Later layers override earlier ones.
+///
+/// ```toml
+/// color = "never"
+/// json = true
+/// pager = false
+/// analyzers = ["signatures", "exported"]
+/// fingerprint_format = "colon"
+/// trusted_certs = "trusted-certs.json"
+/// hiddenapi_flags = "/opt/android/hiddenapi-flags.csv"
+/// max_file_size = 209715200
+/// rules = ["rules/secrets.txt"]
+/// ```
+///
+/// Relative paths are resolved against the directory of the file that defines them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    /// When to use colors
+    pub(crate) color: Option<ColorChoice>,
+
+    /// Use json output in commands that support it
+    pub(crate) json: Option<bool>,
+
+    /// Send output to a pager in commands that support it
+    pub(crate) pager: Option<bool>,
+
+    /// Reports enabled in `show` in addition to the basic information
+    pub(crate) analyzers: Option<Vec<Analyzer>>,
+
+    /// Encoding of certificate fingerprints in `show` and `certs`
+    pub(crate) fingerprint_format: Option<FingerprintStyle>,
+
+    /// JSON file with known signer certificates
+    pub(crate) trusted_certs: Option<PathBuf>,
+
+    /// Hiddenapi flags for `hiddenapi`
+    pub(crate) hiddenapi_flags: Option<PathBuf>,
+
+    /// Files larger than this size (in bytes) are skipped when scanning paths
+    pub(crate) max_file_size: Option<u64>,
+
+    /// Files with regular expressions for `grep`, one per line
+    pub(crate) rules: Option<Vec<PathBuf>>,
+}
+
+/// Optional report of `show`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Analyzer {
+    /// Signatures and certificates
+    Signatures,
+
+    /// Components available to other applications
+    Exported,
+
+    /// Frameworks the app is built with
+    Frameworks,
+
+    /// Tampering and anomalies
+    Anomalies,
+}
+
+impl Config {
+    /// Loads the given file only, or all discovered layers if there is none.
+    pub(crate) fn load(path: Option<&Path>) -> Result<Config> {
+        if let Some(path) = path {
+            return Config::from_file(path);
+        }
+
+        let mut config = Config::default();
+        let user = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .map(|dir| dir.join("apk-info").join("config.toml"));
+
+        for path in user.into_iter().chain([PathBuf::from(CONFIG_NAME)]) {
+            if path.is_file() {
+                config = config.merge(Config::from_file(&path)?);
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Config> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("can't read config: {:?}", path))?;
+        let mut config: Config =
+            toml::from_str(&data).with_context(|| format!("invalid config: {:?}", path))?;
+
+        let base = path.parent().unwrap_or(Path::new(""));
+        for value in [&mut config.trusted_certs, &mut config.hiddenapi_flags]
+            .into_iter()
+            .flatten()
+        {
+            *value = base.join(&value);
+        }
+        for value in config.rules.iter_mut().flatten() {
+            *value = base.join(&value);
+        }
+
+        Ok(config)
+    }
+
+    /// Values from `other` take precedence.
+    fn merge(self, other: Config) -> Config {
+        Config {
+            color: other.color.or(self.color),
+            json: other.json.or(self.json),
+            pager: other.pager.or(self.pager),
+            analyzers: other.analyzers.or(self.analyzers),
+            fingerprint_format: other.fingerprint_format.or(self.fingerprint_format),
+            trusted_certs: other.trusted_certs.or(self.trusted_certs),
+            hiddenapi_flags: other.hiddenapi_flags.or(self.hiddenapi_flags),
+            max_file_size: other.max_file_size.or(self.max_file_size),
+            rules: other.rules.or(self.rules),
+        }
+    }
+
+    /// Whether the analyzer is enabled by the config; the list given on the command line
+    /// replaces the one from the config.
+    pub(crate) fn analyzer(&self, overridden: Option<&[Analyzer]>, analyzer: Analyzer) -> bool {
+        overridden
+            .or(self.analyzers.as_deref())
+            .is_some_and(|analyzers| analyzers.contains(&analyzer))
+    }
+}
+
+/// Resolves a boolean option: `--flag` wins, then `--no-flag`, then the config value.
+pub(crate) fn flag(enabled: bool, disabled: bool, config: Option<bool>) -> bool {
+    enabled || (!disabled && config.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_precedence() {
+        let user: Config = toml::from_str(
+            r#"
+            color = "never"
+            json = true
+            analyzers = ["signatures"]
+            max_file_size = 100
+            "#,
+        )
+        .unwrap();
+        let local: Config = toml::from_str(
+            r#"
+            json = false
+            analyzers = ["exported", "anomalies"]
+            "#,
+        )
+        .unwrap();
+
+        let config = user.merge(local);
+        assert_eq!(config.color, Some(ColorChoice::Never));
+        assert_eq!(config.json, Some(false));
+        assert_eq!(config.max_file_size, Some(100));
+        assert_eq!(
+            config.analyzers,
+            Some(vec![Analyzer::Exported, Analyzer::Anomalies])
+        );
+    }
+
+    #[test]
+    fn test_command_line_precedence() {
+        let config: Config = toml::from_str(
+            r#"
+            pager = true
+            analyzers = ["signatures", "frameworks"]
+            "#,
+        )
+        .unwrap();
+
+        assert!(flag(false, false, config.pager));
+        assert!(!flag(false, true, config.pager));
+        assert!(flag(true, true, config.pager));
+        assert!(!flag(false, false, config.json));
+
+        assert!(config.analyzer(None, Analyzer::Signatures));
+        assert!(!config.analyzer(None, Analyzer::Exported));
+        assert!(!config.analyzer(Some(&[]), Analyzer::Signatures));
+        assert!(config.analyzer(Some(&[Analyzer::Exported]), Analyzer::Exported));
+        assert!(!Config::default().analyzer(None, Analyzer::Signatures));
+    }
+
+    #[test]
+    fn test_relative_paths() {
+        let dir = std::env::temp_dir().join(format!("apk-info-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_NAME);
+        std::fs::write(
+            &path,
+            r#"
+            trusted_certs = "certs/trusted.json"
+            hiddenapi_flags = "/opt/hiddenapi-flags.csv"
+            rules = ["secrets.txt", "/etc/apk-info/urls.txt"]
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::load(Some(&path));
+        std::fs::remove_dir_all(&dir).unwrap();
+        let config = config.unwrap();
+
+        assert_eq!(
+            config.trusted_certs,
+            Some(dir.join("certs").join("trusted.json"))
+        );
+        assert_eq!(
+            config.hiddenapi_flags,
+            Some(PathBuf::from("/opt/hiddenapi-flags.csv"))
+        );
+        assert_eq!(
+            config.rules,
+            Some(vec![
+                dir.join("secrets.txt"),
+                PathBuf::from("/etc/apk-info/urls.txt")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        assert!(toml::from_str::<Config>("signatures = true").is_err());
+        assert!(toml::from_str::<Config>(r#"analyzers = ["unknown"]"#).is_err());
+    }
+}
@@ -1,27 +1,66 @@
 use std::path::PathBuf;
 
+use apk_info::{FingerprintStyle, HashAlgorithm};
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{Shell, generate};
 
-use crate::commands::{command_axml, command_extract, command_show};
+use crate::commands::fields::Projection;
+use crate::commands::output::{
+    ColorChoice, IocFormat, ShowFormat, fingerprint_style_parser, setup_colors,
+};
+use crate::commands::path_helpers::{GlobFilter, WalkOptions};
+use crate::commands::{
+    DexListing, command_arsc, command_arsc_dump, command_axml, command_carve, command_certs,
+    command_dex, command_diff, command_extract, command_grep, command_hashes, command_hiddenapi,
+    command_icon, command_index, command_iocs, command_manifest, command_profile, command_serve,
+    command_show, command_verify, command_xapk, parse_density, parse_file_type, parse_resource_id,
+};
+use crate::config::{Analyzer, Config, flag};
 
 mod commands;
+mod config;
 
 #[derive(Parser)]
 #[command(version, about, arg_required_else_help(true))]
 struct Cli {
     #[command(subcommand)]
     commands: Option<Commands>,
+
+    /// When to use colors (NO_COLOR environment variable is respected in auto mode) [default: auto]
+    #[arg(long, value_enum, global = true)]
+    color: Option<ColorChoice>,
+
+    /// Configuration file with default options (default: ./apk-info.toml, ~/.config/apk-info/config.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Don't use json output even if the config enables it
+    #[arg(long, global = true)]
+    no_json: bool,
+
+    /// Don't send output to a pager even if the config enables it
+    #[arg(long, global = true)]
+    no_pager: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Show basic information about apk file
     Show {
-        /// One or more paths to APK files to inspect
+        /// One or more paths to APK files or directories to inspect
         #[arg(required = true)]
         paths: Vec<PathBuf>,
 
+        /// Descend into subdirectories of the given directories
+        #[arg(short, long, default_value_t = false)]
+        recursive: bool,
+
+        /// Take only files of the directories matching the glob, `!` excludes files
+        ///
+        /// example: --glob '*.apk' --glob '!**/test/**'
+        #[arg(long)]
+        glob: Vec<String>,
+
         /// Show information about signatures
         #[arg(
             short,
@@ -31,8 +70,66 @@ enum Commands {
         )]
         sigs: bool,
 
+        /// Show components available to other applications and their permissions
+        #[arg(long, default_value_t = false)]
+        exported: bool,
+
+        /// Detect frameworks the app is built with: Flutter, React Native, Unity, etc.
+        #[arg(long, default_value_t = false)]
+        frameworks: bool,
+
+        /// Show tampering and anomalies: fake compression, manifest tricks, patched dex files
+        #[arg(long, default_value_t = false)]
+        anomalies: bool,
+
+        /// Reports enabled instead of `analyzers` from the config, empty disables all of them
+        ///
+        /// example: --analyzers signatures,exported
+        #[arg(long, value_enum, value_delimiter = ',', num_args = 0..)]
+        analyzers: Option<Vec<Analyzer>>,
+
         #[arg(short, long, default_value_t = false, help = "Show output as jsonl")]
         json: bool,
+
+        /// Number of files parsed in parallel (default: number of cpus)
+        #[arg(long)]
+        jobs: Option<usize>,
+
+        /// Map the files into memory instead of reading them, only the entries that are read
+        /// are loaded from disk. The files must not be modified while the command runs
+        #[arg(long, default_value_t = false)]
+        mmap: bool,
+
+        /// Output format, `-j` is a shortcut for `--format ndjson`
+        #[arg(long, value_enum, conflicts_with = "json")]
+        format: Option<ShowFormat>,
+
+        /// JSON file with known signer certificates (sha256 fingerprint -> label)
+        ///
+        /// example: {"a40da80a59d1...": "Official bank app signer"}
+        #[arg(long)]
+        trusted_certs: Option<PathBuf>,
+
+        /// Encoding of certificate fingerprints: lowercase hex, uppercase hex separated by colons
+        /// (keytool, Play Console) or base64 of the digest (certificate pinning)
+        #[arg(long, value_parser = fingerprint_style_parser())]
+        fingerprint_format: Option<FingerprintStyle>,
+
+        /// Print only the fields: tab-separated in text, objects in json formats
+        ///
+        /// example: --fields path,package_name,version_code,signer_sha256
+        #[arg(long, value_delimiter = ',', conflicts_with = "template")]
+        fields: Vec<String>,
+
+        /// Print a line per file rendered from the template with the same fields as --fields
+        ///
+        /// example: --template '{{package_name}} {{version_code}}'
+        #[arg(long, conflicts_with_all = ["format", "json"])]
+        template: Option<String>,
+
+        /// Send output to a pager ($PAGER or less)
+        #[arg(long, default_value_t = false)]
+        pager: bool,
     },
     /// Unpack apk files as zip archive
     #[command(visible_alias = "x")]
@@ -50,12 +147,288 @@ enum Commands {
         /// example: -f AndroidManifest.xml -f classes\d+.dex
         #[arg(short, long)]
         files: Vec<String>,
+
+        /// Extract only files with the given content type, detected by magic bytes
+        ///
+        /// example: --magic elf --magic dex
+        #[arg(long, value_parser = parse_file_type)]
+        magic: Vec<apk_info::FileType>,
     },
     /// Read and pretty-print binary AndroidManifest.xml
     Axml {
         /// Path to the AndroidManifest.xml file or APK containing it
         #[arg(required = true)]
         path: PathBuf,
+
+        /// Send output to a pager ($PAGER or less)
+        #[arg(long, default_value_t = false)]
+        pager: bool,
+    },
+    /// Save AndroidManifest.xml of apk or xapk to a file, decoded or binary
+    Manifest {
+        /// Path to the APK or XAPK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Output file (default: ./<filename>.AndroidManifest.xml)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Save the binary manifest as it is stored in the archive
+        #[arg(long, default_value_t = false)]
+        raw: bool,
+    },
+    /// Inspect values from resources.arsc, without --id dumps the whole table
+    Arsc {
+        /// Path to the APK or resources.arsc file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Resource id in hex or decimal form (example: 0x7f110003)
+        #[arg(long, value_parser = parse_resource_id)]
+        id: Option<u32>,
+
+        /// Print value for every config in which resource is defined
+        #[arg(long, default_value_t = false, requires = "id")]
+        all_configs: bool,
+
+        /// Dump only resources of the type (example: string)
+        #[arg(long = "type", value_name = "TYPE", conflicts_with = "id")]
+        type_name: Option<String>,
+
+        /// Dump only resources whose name contains the text
+        #[arg(long, conflicts_with = "id")]
+        name: Option<String>,
+
+        /// Send output to a pager ($PAGER or less)
+        #[arg(long, default_value_t = false)]
+        pager: bool,
+    },
+    /// Show header and counts of dex files or list their strings, classes and methods
+    Dex {
+        /// Path to the dex file or APK containing them
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// List strings of all dex files instead of the header
+        #[arg(long, group = "listing")]
+        strings: bool,
+
+        /// List java names of the defined classes instead of the header
+        #[arg(long, group = "listing")]
+        classes: bool,
+
+        /// List signatures of the defined and called methods instead of the header
+        #[arg(long, group = "listing")]
+        methods: bool,
+
+        /// Show only items matching the regex (example: --filter '^https?://')
+        #[arg(long, requires = "listing")]
+        filter: Option<String>,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Show the structure of xapk bundle: inner apks, expansion files and manifest.json
+    Xapk {
+        /// Path to the xapk file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Run `show` on every inner apk
+        #[arg(long, default_value_t = false)]
+        analyze_all: bool,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+
+        /// Send output to a pager ($PAGER or less)
+        #[arg(long, default_value_t = false)]
+        pager: bool,
+    },
+    /// Save the application icon as png/webp
+    Icon {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Output file (default: ./<filename>.icon.<format>)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Preferred density: xxhdpi, 480, ... (default: the largest one)
+        #[arg(long, value_parser = parse_density)]
+        density: Option<apk_info::structs::Density>,
+    },
+    /// Find apk files hidden inside other containers (tar/gz, 7z, zip, base64 in emails)
+    Carve {
+        /// Path to the container file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Output folder (default: ./<filename>.carved)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write metadata of apk files into sqlite database for corpus querying
+    Index {
+        /// One or more paths to APK files or directories with them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Path to the sqlite database, created if it does not exist
+        #[arg(long, default_value = "corpus.sqlite")]
+        db: PathBuf,
+    },
+    /// Show code statistics grouped by package prefix (obfuscation profiling)
+    Profile {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Show references to restricted (non-SDK) Android apis
+    Hiddenapi {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Hiddenapi flags: hiddenapi-flags.csv, framework dex or jar with them
+        #[arg(long)]
+        flags: Option<PathBuf>,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Compare two apks: permissions, components, versions and signers
+    Diff {
+        /// Path to the old APK file
+        #[arg(required = true)]
+        old: PathBuf,
+
+        /// Path to the new APK file
+        #[arg(required = true)]
+        new: PathBuf,
+
+        /// Show added, removed and changed files
+        #[arg(long, default_value_t = false)]
+        files: bool,
+
+        /// Show added and removed classes
+        #[arg(long, default_value_t = false)]
+        classes: bool,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Compute md5, sha1 and sha256 of every file in the apk
+    Hashes {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Digest shown in the text output, json output has all of them
+        #[arg(long, default_value = "sha256")]
+        algorithm: HashAlgorithm,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Search strings of dex files, resources and xml attributes for a regex
+    Grep {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Regular expression (example: 'https?://')
+        pattern: Option<String>,
+
+        /// File with regular expressions, one per line, `#` starts a comment
+        /// (default: rules from config)
+        #[arg(long)]
+        rules: Vec<PathBuf>,
+
+        /// Case-insensitive matching
+        #[arg(short, long, default_value_t = false)]
+        ignore_case: bool,
+
+        /// Also search the raw content of files in assets/
+        #[arg(long, default_value_t = false)]
+        assets: bool,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Extract urls, ip addresses, domains, emails and crypto wallets from dex, native libraries and assets
+    Iocs {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = IocFormat::Text)]
+        format: IocFormat,
+    },
+    /// Show signing certificates and save them as PEM/DER files
+    Certs {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        /// Encoding of certificate fingerprints: lowercase hex, uppercase hex separated by colons
+        /// (keytool, Play Console) or base64 of the digest (certificate pinning)
+        #[arg(long, value_parser = fingerprint_style_parser())]
+        fingerprint_format: Option<FingerprintStyle>,
+
+        /// Save certificates as `<sha256>.pem` to the folder
+        #[arg(long, value_name = "DIR")]
+        pem: Option<PathBuf>,
+
+        /// Save certificates as `<sha256>.der` to the folder
+        #[arg(long, value_name = "DIR")]
+        der: Option<PathBuf>,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Check v1 digests, v2/v3 content digests and zip consistency, exits with 1 on failure
+    ///
+    /// Signatures are not verified cryptographically: whoever patches the apk can rewrite
+    /// the digests, so a passing run doesn't prove who signed it.
+    Verify {
+        /// Path to the APK file
+        #[arg(required = true)]
+        path: PathBuf,
+
+        #[arg(short, long, default_value_t = false, help = "Show output as json")]
+        json: bool,
+    },
+    /// Answer JSON requests line by line, keeping the process warm between apk files
+    ///
+    /// request: {"id": 1, "path": "./file.apk", "fields": ["package_name", "permissions"]}
+    ///
+    /// response: {"id": 1, "path": "./file.apk", "result": {...}} or {..., "error": "..."}
+    #[command(group(clap::ArgGroup::new("mode").required(true)))]
+    Serve {
+        /// Read requests from stdin and write responses to stdout
+        #[arg(long, group = "mode")]
+        stdio: bool,
+
+        /// Listen for http requests: POST /analyze and /probe with multipart "file" upload
+        #[cfg(feature = "server")]
+        #[arg(long, group = "mode", value_name = "ADDR")]
+        http: Option<std::net::SocketAddr>,
+
+        /// Maximum number of apk files parsed at the same time (default: number of cpus)
+        #[cfg(feature = "server")]
+        #[arg(long, requires = "http")]
+        jobs: Option<usize>,
+
+        /// Maximum size of the uploaded apk in bytes (default: max_file_size from config or 256 MiB)
+        #[cfg(feature = "server")]
+        #[arg(long, requires = "http")]
+        max_size: Option<u64>,
     },
     /// Generate shell completion
     Completion {
@@ -69,15 +442,225 @@ fn main() {
     env_logger::init();
 
     let cli = Cli::parse();
+    let config = match Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{:#}", err);
+            std::process::exit(1);
+        }
+    };
+    setup_colors(cli.color.or(config.color).unwrap_or_default());
+
+    let json = |enabled: &bool| flag(*enabled, cli.no_json, config.json);
+    let pager = |enabled: &bool| flag(*enabled, cli.no_pager, config.pager);
 
     let result = match &cli.commands {
-        Some(Commands::Show { paths, sigs, json }) => command_show(paths, sigs, json),
+        Some(Commands::Show {
+            paths,
+            recursive,
+            glob,
+            sigs,
+            exported,
+            frameworks,
+            anomalies,
+            analyzers,
+            json: jsonl,
+            jobs,
+            mmap,
+            format,
+            trusted_certs,
+            fingerprint_format,
+            fields,
+            template,
+            pager: use_pager,
+        }) => {
+            let format = format.unwrap_or(if json(jsonl) {
+                ShowFormat::Ndjson
+            } else {
+                ShowFormat::Text
+            });
+            let analyzer = |enabled: &bool, analyzer| {
+                *enabled || config.analyzer(analyzers.as_deref(), analyzer)
+            };
+            let sigs = analyzer(sigs, Analyzer::Signatures);
+            let exported = analyzer(exported, Analyzer::Exported);
+            let frameworks = analyzer(frameworks, Analyzer::Frameworks);
+            let anomalies = analyzer(anomalies, Analyzer::Anomalies);
+            let trusted_certs = trusted_certs.clone().or(config.trusted_certs.clone());
+            let fingerprint_format = fingerprint_format
+                .or(config.fingerprint_format)
+                .unwrap_or_default();
+            GlobFilter::new(glob).and_then(|filter| {
+                let walk = WalkOptions {
+                    recursive: *recursive,
+                    max_size: config.max_file_size,
+                    filter,
+                };
+                let projection = match template {
+                    Some(template) => Some(Projection::template(template)?),
+                    None if !fields.is_empty() => Some(Projection::fields(fields)?),
+                    None => None,
+                };
+                command_show(
+                    paths,
+                    &walk,
+                    &sigs,
+                    &exported,
+                    &frameworks,
+                    &anomalies,
+                    format,
+                    &trusted_certs,
+                    fingerprint_format,
+                    projection.as_ref(),
+                    &pager(use_pager),
+                    jobs.unwrap_or_default(),
+                    mmap,
+                )
+            })
+        }
         Some(Commands::Extract {
             paths,
             output,
             files,
-        }) => command_extract(paths, output, files),
-        Some(Commands::Axml { path }) => command_axml(path),
+            magic,
+        }) => command_extract(paths, config.max_file_size, output, files, magic),
+        Some(Commands::Axml {
+            path,
+            pager: use_pager,
+        }) => command_axml(path, &pager(use_pager)),
+        Some(Commands::Arsc {
+            path,
+            id: Some(id),
+            all_configs,
+            pager: use_pager,
+            ..
+        }) => command_arsc(path, id, all_configs, &pager(use_pager)),
+        Some(Commands::Arsc {
+            path,
+            id: None,
+            type_name,
+            name,
+            pager: use_pager,
+            ..
+        }) => command_arsc_dump(
+            path,
+            type_name.as_deref(),
+            name.as_deref(),
+            &pager(use_pager),
+        ),
+        Some(Commands::Manifest { path, output, raw }) => command_manifest(path, output, raw),
+        Some(Commands::Dex {
+            path,
+            strings,
+            classes,
+            methods,
+            filter,
+            json: use_json,
+        }) => {
+            let listing = if *strings {
+                Some(DexListing::Strings)
+            } else if *classes {
+                Some(DexListing::Classes)
+            } else if *methods {
+                Some(DexListing::Methods)
+            } else {
+                None
+            };
+            command_dex(path, listing, filter.as_deref(), &json(use_json))
+        }
+        Some(Commands::Xapk {
+            path,
+            analyze_all,
+            json: use_json,
+            pager: use_pager,
+        }) => command_xapk(path, analyze_all, &json(use_json), &pager(use_pager)),
+        Some(Commands::Icon {
+            path,
+            output,
+            density,
+        }) => command_icon(path, output, density),
+        Some(Commands::Carve { path, output }) => command_carve(path, output),
+        Some(Commands::Index { paths, db }) => command_index(paths, config.max_file_size, db),
+        Some(Commands::Profile {
+            path,
+            json: use_json,
+        }) => command_profile(path, &json(use_json)),
+        Some(Commands::Hiddenapi {
+            path,
+            flags,
+            json: use_json,
+        }) => match flags.as_ref().or(config.hiddenapi_flags.as_ref()) {
+            Some(flags) => command_hiddenapi(path, flags, &json(use_json)),
+            None => Err(anyhow::anyhow!(
+                "hiddenapi flags are not set, use --flags or hiddenapi_flags in apk-info.toml"
+            )),
+        },
+        Some(Commands::Diff {
+            old,
+            new,
+            files,
+            classes,
+            json: use_json,
+        }) => command_diff(old, new, files, classes, &json(use_json)),
+        Some(Commands::Hashes {
+            path,
+            algorithm,
+            json: use_json,
+        }) => command_hashes(path, algorithm, &json(use_json)),
+        Some(Commands::Grep {
+            path,
+            pattern,
+            ignore_case,
+            rules,
+            assets,
+            json: use_json,
+        }) => {
+            let rules = match rules.as_slice() {
+                [] => config.rules.as_deref().unwrap_or_default(),
+                rules => rules,
+            };
+            command_grep(
+                path,
+                pattern.as_deref(),
+                rules,
+                ignore_case,
+                assets,
+                &json(use_json),
+            )
+        }
+        Some(Commands::Iocs { path, format }) => command_iocs(path, *format),
+        Some(Commands::Certs {
+            path,
+            fingerprint_format,
+            pem,
+            der,
+            json: use_json,
+        }) => {
+            let fingerprint_format = fingerprint_format
+                .or(config.fingerprint_format)
+                .unwrap_or_default();
+            command_certs(path, fingerprint_format, pem, der, &json(use_json))
+        }
+        Some(Commands::Verify {
+            path,
+            json: use_json,
+        }) => command_verify(path, &json(use_json)),
+        #[cfg(feature = "server")]
+        Some(Commands::Serve {
+            http: Some(addr),
+            jobs,
+            max_size,
+            ..
+        }) => commands::command_server(
+            *addr,
+            jobs.unwrap_or_else(|| {
+                std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+            }),
+            max_size
+                .or(config.max_file_size)
+                .unwrap_or(commands::server::DEFAULT_MAX_SIZE),
+        ),
+        Some(Commands::Serve { .. }) => command_serve(),
         Some(Commands::Completion { shell }) => {
             let mut cmd = Cli::command();
             let name = cmd.get_name().to_string();
@@ -89,5 +672,6 @@ fn main() {
 
     if let Err(err) = result {
         eprintln!("{:#}", err);
+        std::process::exit(1);
     }
 }
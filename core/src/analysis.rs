@@ -0,0 +1,425 @@
+//! Capabilities of the app derived from the framework apis it calls.
+//!
+//! The manifest tells what the app may do, method references of the dex files tell what it
+//! actually does. Calls like `SmsManager.sendTextMessage` are mapped to the permissions they
+//! require and to capability categories, calls that need a permission the app doesn't request
+//! are reported too: such code is dead or the permission is obtained by other means.
+//!
+//! Only direct references are found, apis called through reflection or from native code
+//! are not visible here.
+//!
+//! ```ignore
+//! let report = apk.get_capability_report()?;
+//! for usage in &report.capabilities {
+//!     println!("{:?}: {}", usage.capability, usage.apis.join(", "));
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use apk_info_dex::Dex;
+use serde::Serialize;
+
+use crate::apk::Apk;
+use crate::errors::APKError;
+
+/// Category of sensitive behaviour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Sending or intercepting sms
+    Sms,
+
+    /// Placing or ending phone calls
+    PhoneCalls,
+
+    /// Reading device and sim identifiers: imei, imsi, phone number
+    DeviceIdentifiers,
+
+    /// Tracking the location of the device
+    Location,
+
+    /// Taking photos or videos
+    Camera,
+
+    /// Recording audio
+    Microphone,
+
+    /// Capturing the screen with `MediaProjection`
+    ScreenCapture,
+
+    /// Reading the screen and acting on behalf of the user with an accessibility service
+    Accessibility,
+
+    /// Drawing over other apps
+    Overlay,
+
+    /// Locking or wiping the device as a device administrator
+    DeviceAdmin,
+
+    /// Installing other apps
+    InstallPackages,
+
+    /// Loading code at runtime, usual for droppers and packers
+    DynamicCode,
+
+    /// Running shell commands
+    ShellCommands,
+}
+
+/// Framework method and what calling it means.
+struct Rule {
+    /// Type descriptor of the class: `Landroid/telephony/SmsManager;`
+    class: &'static str,
+
+    method: &'static str,
+
+    capability: Capability,
+
+    /// Permission required to call the method
+    permission: Option<&'static str>,
+}
+
+const fn rule(
+    class: &'static str,
+    method: &'static str,
+    capability: Capability,
+    permission: Option<&'static str>,
+) -> Rule {
+    Rule {
+        class,
+        method,
+        capability,
+        permission,
+    }
+}
+
+const SEND_SMS: Option<&str> = Some("android.permission.SEND_SMS");
+const RECEIVE_SMS: Option<&str> = Some("android.permission.RECEIVE_SMS");
+const CALL_PHONE: Option<&str> = Some("android.permission.CALL_PHONE");
+const READ_PHONE_STATE: Option<&str> = Some("android.permission.READ_PHONE_STATE");
+const FINE_LOCATION: Option<&str> = Some("android.permission.ACCESS_FINE_LOCATION");
+const CAMERA: Option<&str> = Some("android.permission.CAMERA");
+const RECORD_AUDIO: Option<&str> = Some("android.permission.RECORD_AUDIO");
+const INSTALL_PACKAGES: Option<&str> = Some("android.permission.REQUEST_INSTALL_PACKAGES");
+
+const RULES: &[Rule] = &[
+    rule(
+        "Landroid/telephony/SmsManager;",
+        "sendTextMessage",
+        Capability::Sms,
+        SEND_SMS,
+    ),
+    rule(
+        "Landroid/telephony/SmsManager;",
+        "sendMultipartTextMessage",
+        Capability::Sms,
+        SEND_SMS,
+    ),
+    rule(
+        "Landroid/telephony/SmsManager;",
+        "sendDataMessage",
+        Capability::Sms,
+        SEND_SMS,
+    ),
+    rule(
+        "Landroid/telephony/gsm/SmsManager;",
+        "sendTextMessage",
+        Capability::Sms,
+        SEND_SMS,
+    ),
+    rule(
+        "Landroid/telephony/SmsMessage;",
+        "createFromPdu",
+        Capability::Sms,
+        RECEIVE_SMS,
+    ),
+    rule(
+        "Landroid/provider/Telephony$Sms$Intents;",
+        "getMessagesFromIntent",
+        Capability::Sms,
+        RECEIVE_SMS,
+    ),
+    rule(
+        "Landroid/telecom/TelecomManager;",
+        "placeCall",
+        Capability::PhoneCalls,
+        CALL_PHONE,
+    ),
+    rule(
+        "Landroid/telecom/TelecomManager;",
+        "endCall",
+        Capability::PhoneCalls,
+        Some("android.permission.ANSWER_PHONE_CALLS"),
+    ),
+    rule(
+        "Landroid/telephony/TelephonyManager;",
+        "getDeviceId",
+        Capability::DeviceIdentifiers,
+        READ_PHONE_STATE,
+    ),
+    rule(
+        "Landroid/telephony/TelephonyManager;",
+        "getImei",
+        Capability::DeviceIdentifiers,
+        READ_PHONE_STATE,
+    ),
+    rule(
+        "Landroid/telephony/TelephonyManager;",
+        "getSubscriberId",
+        Capability::DeviceIdentifiers,
+        READ_PHONE_STATE,
+    ),
+    rule(
+        "Landroid/telephony/TelephonyManager;",
+        "getSimSerialNumber",
+        Capability::DeviceIdentifiers,
+        READ_PHONE_STATE,
+    ),
+    rule(
+        "Landroid/telephony/TelephonyManager;",
+        "getLine1Number",
+        Capability::DeviceIdentifiers,
+        READ_PHONE_STATE,
+    ),
+    rule(
+        "Landroid/location/LocationManager;",
+        "getLastKnownLocation",
+        Capability::Location,
+        FINE_LOCATION,
+    ),
+    rule(
+        "Landroid/location/LocationManager;",
+        "requestLocationUpdates",
+        Capability::Location,
+        FINE_LOCATION,
+    ),
+    rule(
+        "Landroid/location/LocationManager;",
+        "requestSingleUpdate",
+        Capability::Location,
+        FINE_LOCATION,
+    ),
+    rule(
+        "Lcom/google/android/gms/location/FusedLocationProviderClient;",
+        "getLastLocation",
+        Capability::Location,
+        FINE_LOCATION,
+    ),
+    rule(
+        "Lcom/google/android/gms/location/FusedLocationProviderClient;",
+        "requestLocationUpdates",
+        Capability::Location,
+        FINE_LOCATION,
+    ),
+    rule(
+        "Landroid/hardware/Camera;",
+        "open",
+        Capability::Camera,
+        CAMERA,
+    ),
+    rule(
+        "Landroid/hardware/camera2/CameraManager;",
+        "openCamera",
+        Capability::Camera,
+        CAMERA,
+    ),
+    rule(
+        "Landroid/media/AudioRecord;",
+        "<init>",
+        Capability::Microphone,
+        RECORD_AUDIO,
+    ),
+    rule(
+        "Landroid/media/MediaRecorder;",
+        "setAudioSource",
+        Capability::Microphone,
+        RECORD_AUDIO,
+    ),
+    rule(
+        "Landroid/media/projection/MediaProjectionManager;",
+        "getMediaProjection",
+        Capability::ScreenCapture,
+        None,
+    ),
+    rule(
+        "Landroid/accessibilityservice/AccessibilityService;",
+        "performGlobalAction",
+        Capability::Accessibility,
+        None,
+    ),
+    rule(
+        "Landroid/accessibilityservice/AccessibilityService;",
+        "dispatchGesture",
+        Capability::Accessibility,
+        None,
+    ),
+    rule(
+        "Landroid/view/accessibility/AccessibilityNodeInfo;",
+        "performAction",
+        Capability::Accessibility,
+        None,
+    ),
+    rule(
+        "Landroid/provider/Settings;",
+        "canDrawOverlays",
+        Capability::Overlay,
+        Some("android.permission.SYSTEM_ALERT_WINDOW"),
+    ),
+    rule(
+        "Landroid/app/admin/DevicePolicyManager;",
+        "lockNow",
+        Capability::DeviceAdmin,
+        None,
+    ),
+    rule(
+        "Landroid/app/admin/DevicePolicyManager;",
+        "wipeData",
+        Capability::DeviceAdmin,
+        None,
+    ),
+    rule(
+        "Landroid/app/admin/DevicePolicyManager;",
+        "resetPassword",
+        Capability::DeviceAdmin,
+        None,
+    ),
+    rule(
+        "Landroid/content/pm/PackageInstaller;",
+        "createSession",
+        Capability::InstallPackages,
+        INSTALL_PACKAGES,
+    ),
+    rule(
+        "Ldalvik/system/DexClassLoader;",
+        "<init>",
+        Capability::DynamicCode,
+        None,
+    ),
+    rule(
+        "Ldalvik/system/InMemoryDexClassLoader;",
+        "<init>",
+        Capability::DynamicCode,
+        None,
+    ),
+    rule(
+        "Ldalvik/system/DexFile;",
+        "loadDex",
+        Capability::DynamicCode,
+        None,
+    ),
+    rule(
+        "Ljava/lang/Runtime;",
+        "exec",
+        Capability::ShellCommands,
+        None,
+    ),
+    rule(
+        "Ljava/lang/ProcessBuilder;",
+        "start",
+        Capability::ShellCommands,
+        None,
+    ),
+];
+
+/// Capability found in the code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CapabilityUsage {
+    pub capability: Capability,
+
+    /// Signatures of the called apis: `Landroid/telephony/SmsManager;->sendTextMessage(...)V`
+    pub apis: Vec<String>,
+
+    /// Permissions required by the called apis
+    pub permissions: Vec<&'static str>,
+
+    /// Required permissions the app doesn't request in the manifest
+    pub missing_permissions: Vec<&'static str>,
+}
+
+/// Result of [Apk::get_capability_report](crate::Apk::get_capability_report).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct CapabilityReport {
+    /// Found capabilities, sorted
+    pub capabilities: Vec<CapabilityUsage>,
+}
+
+impl CapabilityReport {
+    /// Checks whether the app calls apis of the capability.
+    pub fn has(&self, capability: Capability) -> bool {
+        self.capabilities
+            .iter()
+            .any(|usage| usage.capability == capability)
+    }
+}
+
+/// Returns indexes of the matched [RULES] with the signatures of the called methods.
+fn scan(dex: &Dex) -> Vec<(usize, String)> {
+    let classes: HashSet<&str> = RULES.iter().map(|rule| rule.class).collect();
+
+    let mut found = Vec::new();
+    for (idx, method) in dex.method_ids().iter().enumerate() {
+        // resolving the class first is enough to skip almost all methods
+        let Some(class) = dex.get_type(u32::from(method.class_idx)) else {
+            continue;
+        };
+        if !classes.contains(class.as_str()) {
+            continue;
+        }
+
+        let Some(name) = dex.get_string(method.name_idx) else {
+            continue;
+        };
+        let Some(rule) = RULES
+            .iter()
+            .position(|rule| rule.class == class && rule.method == name)
+        else {
+            continue;
+        };
+
+        if let Some(signature) = dex.get_method_signature(idx as u32) {
+            found.push((rule, signature));
+        }
+    }
+
+    found
+}
+
+pub(crate) fn capability_report(apk: &Apk) -> Result<CapabilityReport, APKError> {
+    let per_dex = apk.map_dex(|name| -> Result<_, APKError> { Ok(scan(&apk.read_dex(name)?)) });
+
+    let mut found: BTreeMap<Capability, (BTreeSet<String>, BTreeSet<&'static str>)> =
+        BTreeMap::new();
+    for result in per_dex {
+        for (rule, signature) in result? {
+            let rule = &RULES[rule];
+            let (apis, permissions) = found.entry(rule.capability).or_default();
+            apis.insert(signature);
+            permissions.extend(rule.permission);
+        }
+    }
+
+    let requested: HashSet<&str> = apk.get_permissions().collect();
+    let capabilities = found
+        .into_iter()
+        .map(|(capability, (apis, permissions))| CapabilityUsage {
+            capability,
+            apis: apis.into_iter().collect(),
+            missing_permissions: permissions
+                .iter()
+                .filter(|permission| !is_requested(permission, &requested))
+                .copied()
+                .collect(),
+            permissions: permissions.into_iter().collect(),
+        })
+        .collect();
+
+    Ok(CapabilityReport { capabilities })
+}
+
+fn is_requested(permission: &str, requested: &HashSet<&str>) -> bool {
+    requested.contains(permission)
+        // location apis work with the approximate location too
+        || (permission == "android.permission.ACCESS_FINE_LOCATION"
+            && requested.contains("android.permission.ACCESS_COARSE_LOCATION"))
+}
@@ -0,0 +1,477 @@
+//! Tampering and anomalies found while parsing the apk.
+//!
+//! The parsers recover from the tricks malware uses against analysis tools: fake compression
+//! methods, chunks with garbage sizes, duplicate manifest attributes. Android recovers from them
+//! too, so such apks install and run fine, but a clean build never has them.
+//! [Apk::get_anomalies](crate::Apk::get_anomalies) collects all of them in one report.
+//!
+//! ```ignore
+//! let report = apk.get_anomalies();
+//! for anomaly in &report {
+//!     println!("{anomaly}");
+//! }
+//! ```
+
+use std::fmt;
+
+use apk_info_axml::diagnostics::XmlDiagnostic;
+use apk_info_zip::{FileCompressionType, ZipAnomaly};
+
+use crate::apk::Apk;
+use crate::logging::warn;
+
+/// A single anomaly, grouped by the part of the apk it was found in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "source", rename_all = "snake_case")
+)]
+pub enum Anomaly {
+    /// Found while parsing `AndroidManifest.xml`
+    Manifest(XmlDiagnostic),
+
+    /// Anomaly of the zip container as a whole
+    Archive(ZipAnomaly),
+
+    /// Anomaly of the headers of a single entry
+    Entry(EntryAnomaly),
+
+    /// Mismatch between the header of the dex file and its content
+    Dex(DexAnomaly),
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Anomaly::Manifest(diagnostic) => write!(f, "manifest: {diagnostic}"),
+            Anomaly::Archive(anomaly) => write!(f, "archive: {anomaly}"),
+            Anomaly::Entry(anomaly) => write!(f, "entry: {anomaly}"),
+            Anomaly::Dex(anomaly) => write!(f, "dex: {anomaly}"),
+        }
+    }
+}
+
+/// Anomaly of the headers of a zip entry, see BadPack.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum EntryAnomaly {
+    /// The local header declares a compression method that is not the real one,
+    /// see [FileCompressionType::is_tampered].
+    TamperedCompression {
+        /// Name of the entry.
+        name: String,
+
+        /// Offset of the local file header.
+        offset: u64,
+
+        /// Compression method declared in the local header.
+        declared_method: u16,
+
+        /// How the data was actually read.
+        compression: FileCompressionType,
+    },
+
+    /// The central directory and the local header declare different compression methods.
+    CompressionMethodMismatch {
+        /// Name of the entry.
+        name: String,
+
+        /// Offset of the local file header.
+        offset: u64,
+
+        /// Compression method declared in the central directory.
+        central_method: u16,
+
+        /// Compression method declared in the local header.
+        local_method: u16,
+    },
+}
+
+impl fmt::Display for EntryAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryAnomaly::TamperedCompression {
+                name,
+                offset,
+                declared_method,
+                compression,
+            } => {
+                let actual = match compression {
+                    FileCompressionType::Deflated | FileCompressionType::DeflatedTampered => {
+                        "deflated"
+                    }
+                    FileCompressionType::Stored | FileCompressionType::StoredTampered => "stored",
+                };
+                write!(
+                    f,
+                    "{name} at 0x{offset:x} declares compression method {declared_method}, but is {actual}"
+                )
+            }
+            EntryAnomaly::CompressionMethodMismatch {
+                name,
+                offset,
+                central_method,
+                local_method,
+            } => write!(
+                f,
+                "{name} at 0x{offset:x} has compression method {central_method} in the central directory and {local_method} in the local header"
+            ),
+        }
+    }
+}
+
+/// Header values of the dex file that don't match its content.
+///
+/// `d8` always writes valid values, so the file was patched after the build.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum DexAnomaly {
+    /// The adler32 checksum doesn't match.
+    ChecksumMismatch {
+        /// Name of the dex file.
+        name: String,
+
+        /// Checksum from the header.
+        expected: u32,
+
+        /// Recomputed checksum, [None] if the header points outside of the file.
+        actual: Option<u32>,
+    },
+
+    /// The SHA-1 signature doesn't match.
+    SignatureMismatch {
+        /// Name of the dex file.
+        name: String,
+    },
+
+    /// `file_size` from the header doesn't match the size of the entry.
+    SizeMismatch {
+        /// Name of the dex file.
+        name: String,
+
+        /// Size from the header.
+        declared: u32,
+
+        /// Size of the entry.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for DexAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DexAnomaly::ChecksumMismatch {
+                name,
+                expected,
+                actual,
+            } => match actual {
+                Some(actual) => write!(
+                    f,
+                    "{name} checksum is {expected:08x}, but the content has {actual:08x}"
+                ),
+                None => write!(
+                    f,
+                    "{name} checksum can't be computed, header is out of bounds"
+                ),
+            },
+            DexAnomaly::SignatureMismatch { name } => {
+                write!(f, "{name} signature doesn't match the content")
+            }
+            DexAnomaly::SizeMismatch {
+                name,
+                declared,
+                actual,
+            } => write!(f, "{name} declares {declared} bytes, but has {actual}"),
+        }
+    }
+}
+
+/// All anomalies of the apk, see [Apk::get_anomalies](crate::Apk::get_anomalies).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct AnomalyReport {
+    anomalies: Vec<Anomaly>,
+}
+
+impl AnomalyReport {
+    /// Checks if nothing was found.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    /// Returns the number of anomalies.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.anomalies.len()
+    }
+
+    /// Returns the anomalies: manifest first, then the archive, entries and dex files.
+    #[inline]
+    pub fn anomalies(&self) -> &[Anomaly] {
+        &self.anomalies
+    }
+
+    /// Iterates over the anomalies.
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, Anomaly> {
+        self.anomalies.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AnomalyReport {
+    type Item = &'a Anomaly;
+    type IntoIter = std::slice::Iter<'a, Anomaly>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.anomalies.iter()
+    }
+}
+
+impl IntoIterator for AnomalyReport {
+    type Item = Anomaly;
+    type IntoIter = std::vec::IntoIter<Anomaly>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.anomalies.into_iter()
+    }
+}
+
+pub(crate) fn collect(apk: &Apk) -> AnomalyReport {
+    let mut anomalies: Vec<Anomaly> = apk
+        .get_manifest_diagnostics()
+        .iter()
+        .cloned()
+        .map(Anomaly::Manifest)
+        .collect();
+
+    anomalies.extend(
+        apk.get_archive_anomalies()
+            .into_iter()
+            .map(Anomaly::Archive),
+    );
+
+    for info in apk.get_file_infos() {
+        if info.central_compression_method != info.local_compression_method {
+            anomalies.push(Anomaly::Entry(EntryAnomaly::CompressionMethodMismatch {
+                name: info.name.clone(),
+                offset: info.local_header_offset,
+                central_method: info.central_compression_method,
+                local_method: info.local_compression_method,
+            }));
+        }
+
+        if info.compression.is_tampered() {
+            anomalies.push(Anomaly::Entry(EntryAnomaly::TamperedCompression {
+                name: info.name,
+                offset: info.local_header_offset,
+                declared_method: info.local_compression_method,
+                compression: info.compression,
+            }));
+        }
+    }
+
+    let dex_anomalies = apk.map_dex(|name| {
+        let dex = match apk.read_dex(name) {
+            Ok(dex) => dex,
+            Err(e) => {
+                warn!("{name}: skipped, {}", e.display_chain());
+                return Vec::new();
+            }
+        };
+
+        let verification = dex.verify();
+        let mut found = Vec::new();
+        if !verification.is_checksum_valid() {
+            found.push(DexAnomaly::ChecksumMismatch {
+                name: name.to_string(),
+                expected: verification.expected_checksum,
+                actual: verification.actual_checksum,
+            });
+        }
+        if !verification.is_signature_valid() {
+            found.push(DexAnomaly::SignatureMismatch {
+                name: name.to_string(),
+            });
+        }
+        if !verification.is_size_valid() {
+            found.push(DexAnomaly::SizeMismatch {
+                name: name.to_string(),
+                declared: verification.declared_size,
+                actual: verification.actual_size,
+            });
+        }
+        found
+    });
+    anomalies.extend(dex_anomalies.into_iter().flatten().map(Anomaly::Dex));
+
+    AnomalyReport { anomalies }
+}
+
+#[cfg(test)]
+mod tests {
+    use apk_info_axml::diagnostics::XmlDiagnostic;
+
+    use super::*;
+    use crate::testing::*;
+
+    fn manifest_file(attrs: &[(&str, Value)]) -> ZipFile {
+        let mut manifest_attrs = vec![("package", s("com.example.tests"))];
+        manifest_attrs.extend_from_slice(attrs);
+
+        ZipFile::deflated(
+            "AndroidManifest.xml",
+            build_axml(
+                &el("manifest", &manifest_attrs, vec![]),
+                AxmlOptions::default(),
+            ),
+        )
+    }
+
+    fn dex_file(data: Vec<u8>) -> ZipFile {
+        ZipFile::stored("classes.dex", data)
+    }
+
+    fn valid_dex() -> Vec<u8> {
+        seal_dex(build_dex(&["com.example.tests.MainActivity"]))
+    }
+
+    fn anomalies(files: &[ZipFile], options: ZipOptions) -> Vec<Anomaly> {
+        let apk = Apk::from_bytes(build_zip(files, options)).unwrap();
+        apk.get_anomalies().anomalies().to_vec()
+    }
+
+    #[test]
+    fn test_clean() {
+        let files = [manifest_file(&[]), dex_file(valid_dex())];
+        assert_eq!(anomalies(&files, ZipOptions::default()), []);
+    }
+
+    #[test]
+    fn test_manifest_diagnostic() {
+        let files = [
+            manifest_file(&[
+                ("android:versionCode", Value::Int(1)),
+                ("android:versionCode", Value::Int(2)),
+            ]),
+            dex_file(valid_dex()),
+        ];
+
+        assert!(matches!(
+            anomalies(&files, ZipOptions::default())[..],
+            [Anomaly::Manifest(XmlDiagnostic::DuplicateAttribute { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_archive() {
+        let files = [manifest_file(&[]), dex_file(valid_dex())];
+        let options = ZipOptions {
+            prepended: b"MZ stub",
+            ..Default::default()
+        };
+
+        assert_eq!(
+            anomalies(&files, options),
+            [Anomaly::Archive(ZipAnomaly::PrependedData { size: 7 })]
+        );
+    }
+
+    #[test]
+    fn test_compression_method_mismatch() {
+        // the data is deflated, as the central directory says
+        let files = [
+            manifest_file(&[]),
+            ZipFile {
+                local_method: Some(0),
+                ..ZipFile::deflated("classes.dex", valid_dex())
+            },
+        ];
+
+        assert!(matches!(
+            &anomalies(&files, ZipOptions::default())[..],
+            [Anomaly::Entry(EntryAnomaly::CompressionMethodMismatch {
+                name,
+                central_method: 8,
+                local_method: 0,
+                ..
+            })] if name == "classes.dex"
+        ));
+    }
+
+    #[test]
+    fn test_tampered_compression() {
+        let files = [
+            manifest_file(&[]),
+            ZipFile {
+                method: Some(0x1337),
+                ..dex_file(valid_dex())
+            },
+        ];
+
+        assert!(matches!(
+            &anomalies(&files, ZipOptions::default())[..],
+            [Anomaly::Entry(EntryAnomaly::TamperedCompression {
+                name,
+                declared_method: 0x1337,
+                compression: FileCompressionType::StoredTampered,
+                ..
+            })] if name == "classes.dex"
+        ));
+    }
+
+    #[test]
+    fn test_dex_checksum_mismatch() {
+        let mut dex = valid_dex();
+        dex[8] ^= 1;
+        let files = [manifest_file(&[]), dex_file(dex)];
+
+        assert!(matches!(
+            &anomalies(&files, ZipOptions::default())[..],
+            [Anomaly::Dex(DexAnomaly::ChecksumMismatch {
+                actual: Some(_),
+                ..
+            })]
+        ));
+    }
+
+    #[test]
+    fn test_dex_signature_mismatch() {
+        let mut dex = valid_dex();
+        dex[12] ^= 1;
+        // the checksum covers the signature, so it is kept valid
+        let checksum = dex_checksum(&dex);
+        dex[8..12].copy_from_slice(&checksum.to_le_bytes());
+        let files = [manifest_file(&[]), dex_file(dex)];
+
+        assert!(matches!(
+            &anomalies(&files, ZipOptions::default())[..],
+            [Anomaly::Dex(DexAnomaly::SignatureMismatch { .. })]
+        ));
+    }
+
+    #[test]
+    fn test_dex_size_mismatch() {
+        let mut dex = valid_dex();
+        dex.extend_from_slice(b"payload");
+        let files = [manifest_file(&[]), dex_file(dex)];
+
+        assert_eq!(
+            anomalies(&files, ZipOptions::default()),
+            [Anomaly::Dex(DexAnomaly::SizeMismatch {
+                name: "classes.dex".to_string(),
+                declared: valid_dex().len() as u32,
+                actual: valid_dex().len() + 7,
+            })]
+        );
+    }
+}
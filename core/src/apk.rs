@@ -1,19 +1,51 @@
 //! The main structure that represents the `apk` file.
 
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::{self, BufReader, Read};
 use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
 
+use apk_info_axml::diagnostics::XmlDiagnostic;
+use apk_info_axml::structs::{Density, ResConfigQuery};
 use apk_info_axml::{ARSC, AXML};
+use apk_info_dex::Dex;
 use apk_info_xml::Element;
-use apk_info_zip::{FileCompressionType, Signature, ZipEntry, ZipError};
-
+#[cfg(feature = "experimental")]
+use apk_info_zip::extract_paths;
+use apk_info_zip::{
+    CertificateInfo, DigestCheck, EntryHashes, EntryInfo, FileCompressionType, FileType,
+    HashAlgorithm, JarManifest, Signature, StructureIssue, V1Verification, ZipAnomaly, ZipData,
+    ZipEntry, ZipError,
+};
+#[cfg(feature = "experimental")]
+use regex::Regex;
+
+#[cfg(feature = "experimental")]
+use crate::analysis::{self, CapabilityReport};
+use crate::anomaly::{self, AnomalyReport};
+use crate::baseline::{self, Baseline, BaselineCheck};
+#[cfg(feature = "experimental")]
+use crate::detection::{self, FrameworkHit, PackerHit};
 use crate::errors::APKError;
+#[cfg(feature = "experimental")]
+use crate::hiddenapi::HiddenApiDatabase;
+use crate::logging::warn;
 use crate::models::{
-    Activity, ActivityAlias, Attribution, IntentFilter, Permission, Provider, Receiver, Service,
-    XAPKManifest,
+    Activity, ActivityAlias, Attribution, CompatibleScreen, ComponentKind, DeclaredPermission,
+    ExportReason, ExportedComponent, Feature, IconKind, Instrumentation, IntentData, IntentFilter,
+    NormalizedFile, Permission, PermissionsByProtection, Provider, Receiver, Service, SizeEstimate,
+    SupportedAbis, SupportsScreens, XapkManifest,
 };
+#[cfg(feature = "experimental")]
+use crate::models::{PackageProfile, RestrictedApiUsage, StringUsage};
+#[cfg(feature = "experimental")]
+use crate::native::{self, NativeAbi, NativeLibrary};
+use crate::perf::{Measure, PerfStats, PhaseStats};
+use crate::permissions::ProtectionLevel;
+#[cfg(feature = "experimental")]
+use crate::progress::{CancellationToken, ProgressSink, Stage};
 
 /// The name of the manifest to be searched for in the zip archive.
 const ANDROID_MANIFEST_PATH: &str = "AndroidManifest.xml";
@@ -21,48 +53,85 @@ const ANDROID_MANIFEST_PATH: &str = "AndroidManifest.xml";
 /// The name of the resource to be searched in the zip archive.
 const RESOURCE_TABLE_PATH: &str = "resources.arsc";
 
+/// Protobuf manifest of the base module in the app bundle (aab).
+const BUNDLE_MANIFEST_PATH: &str = "base/manifest/AndroidManifest.xml";
+
+/// Protobuf resource table of the base module in the app bundle (aab).
+const BUNDLE_RESOURCE_TABLE_PATH: &str = "base/resources.pb";
+
+/// Packages of the test frameworks that are bundled only into instrumentation test apks.
+const TEST_CLASS_PREFIXES: &[&str] = &["androidx.test.", "android.support.test."];
+
 /// The main structure that represents the `apk` file.
 #[derive(Debug)]
 pub struct Apk {
     zip: ZipEntry,
     axml: AXML,
     arsc: Option<ARSC>,
+
+    /// Name of the inner apk with the manifest, if the file is xapk
+    manifest_container: Option<String>,
+
+    /// Whether the file is an app bundle with protobuf manifest and resources
+    bundle: bool,
+
+    /// Parsed signatures, filled on first access
+    signatures: OnceLock<Vec<Signature>>,
+
+    /// Parse statistics, updated by lazily parsed parts
+    perf: Mutex<PerfStats>,
 }
 
 /// Implementation of internal methods
 impl Apk {
     /// Helper function for reading apk files
-    fn init(p: &Path) -> Result<(ZipEntry, AXML, Option<ARSC>), APKError> {
+    fn read_file(p: &Path) -> Result<ZipData, APKError> {
         let file = File::open(p).map_err(APKError::IoError)?;
         let mut reader = BufReader::with_capacity(1024 * 1024, file);
         let mut input = Vec::new();
         reader.read_to_end(&mut input).map_err(APKError::IoError)?;
 
+        Ok(ZipData::Owned(input))
+    }
+
+    /// Helper function for parsing apk data, `measure` covers reading of the data
+    fn init(input: ZipData, measure: Measure) -> Result<Apk, APKError> {
+        let mut perf = PerfStats::default();
+
         if input.is_empty() {
             return Err(APKError::InvalidInput("got empty file"));
         }
 
-        let zip = ZipEntry::new(input).map_err(APKError::ZipError)?;
+        let input_size = input.len();
+        let zip = ZipEntry::from_data(input).map_err(APKError::ZipError)?;
+        perf.zip = measure.finish(input_size);
 
+        let measure = Measure::start();
         match zip.read(ANDROID_MANIFEST_PATH) {
             Ok((manifest, _)) => {
+                perf.axml = measure.finish(0);
+
                 if manifest.is_empty() {
                     return Err(APKError::InvalidInput(
                         "AndroidManifest.xml is empty, not a valid apk",
                     ));
                 }
 
-                let arsc = match zip.read(RESOURCE_TABLE_PATH) {
-                    Ok((resource_data, _)) => {
-                        Some(ARSC::new(&mut &resource_data[..]).map_err(APKError::ResourceError)?)
-                    }
-                    Err(_) => None,
-                };
+                let arsc = Self::init_arsc(&zip, &mut perf)?;
 
-                let axml = AXML::new(&mut &manifest[..], arsc.as_ref())
-                    .map_err(APKError::ManifestError)?;
+                let measure = Measure::start();
+                let axml = AXML::new(&mut &manifest[..], arsc.as_ref()).map_err(|source| {
+                    APKError::ManifestError {
+                        entry: ANDROID_MANIFEST_PATH.to_string(),
+                        source,
+                    }
+                })?;
+                perf.axml.add(measure.finish(manifest.len()));
 
-                Ok((zip, axml, arsc))
+                Ok(Apk::from_parts(zip, axml, arsc, None, perf))
+            }
+            Err(_) if zip.namelist().any(|name| name == BUNDLE_MANIFEST_PATH) => {
+                Self::init_bundle(zip, measure, perf)
             }
             Err(_) => {
                 // maybe this is xapk?
@@ -72,18 +141,22 @@ impl Apk {
                     )
                 })?;
 
-                let manifest_json: XAPKManifest = serde_json::from_slice(&manifest_json_data)
+                let manifest_json: XapkManifest = serde_json::from_slice(&manifest_json_data)
                     .map_err(APKError::XAPKManifestError)?;
 
                 let package_name = format!("{}.apk", manifest_json.package_name);
                 let (inner_apk_data, _) = zip.read(&package_name).map_err(APKError::ZipError)?;
 
+                let inner_apk_size = inner_apk_data.len();
                 let inner_apk = ZipEntry::new(inner_apk_data).map_err(APKError::ZipError)?;
+                perf.zip.add(measure.finish(inner_apk_size));
 
                 // try again read AndroidManifest.xml from inner apk
+                let measure = Measure::start();
                 let (inner_manifest, _) = inner_apk
                     .read(ANDROID_MANIFEST_PATH)
                     .map_err(APKError::ZipError)?;
+                perf.axml = measure.finish(0);
 
                 if inner_manifest.is_empty() {
                     return Err(APKError::InvalidInput(
@@ -91,26 +164,103 @@ impl Apk {
                     ));
                 }
 
-                let arsc = match zip.read(RESOURCE_TABLE_PATH) {
-                    Ok((resource_data, _)) => {
-                        Some(ARSC::new(&mut &resource_data[..]).map_err(APKError::ResourceError)?)
-                    }
-                    Err(_) => None,
-                };
+                let arsc = Self::init_arsc(&zip, &mut perf)?;
+
+                let measure = Measure::start();
+                let axml =
+                    AXML::new(&mut &inner_manifest[..], arsc.as_ref()).map_err(|source| {
+                        APKError::ManifestError {
+                            entry: format!("{package_name}/{ANDROID_MANIFEST_PATH}"),
+                            source,
+                        }
+                    })?;
+                perf.axml.add(measure.finish(inner_manifest.len()));
 
-                let axml = AXML::new(&mut &inner_manifest[..], arsc.as_ref())
-                    .map_err(APKError::ManifestError)?;
+                Ok(Apk::from_parts(zip, axml, arsc, Some(package_name), perf))
+            }
+        }
+    }
 
-                Ok((zip, axml, arsc))
+    /// Parses the base module of the app bundle, `measure` covers reading of the manifest.
+    fn init_bundle(zip: ZipEntry, measure: Measure, mut perf: PerfStats) -> Result<Apk, APKError> {
+        let (manifest, _) = zip.read(BUNDLE_MANIFEST_PATH)?;
+        perf.axml = measure.finish(0);
+
+        let measure = Measure::start();
+        let arsc = match zip.read(BUNDLE_RESOURCE_TABLE_PATH) {
+            Ok((resource_data, _)) => {
+                let arsc =
+                    ARSC::from_proto(&resource_data).map_err(|source| APKError::ResourceError {
+                        entry: BUNDLE_RESOURCE_TABLE_PATH.to_string(),
+                        source,
+                    })?;
+                perf.arsc = Some(measure.finish(resource_data.len()));
+                Some(arsc)
+            }
+            Err(_) => None,
+        };
+
+        let measure = Measure::start();
+        let axml = AXML::from_proto(&manifest, arsc.as_ref()).map_err(|source| {
+            APKError::ManifestError {
+                entry: BUNDLE_MANIFEST_PATH.to_string(),
+                source,
             }
+        })?;
+        perf.axml.add(measure.finish(manifest.len()));
+
+        Ok(Apk {
+            bundle: true,
+            ..Apk::from_parts(zip, axml, arsc, None, perf)
+        })
+    }
+
+    fn from_parts(
+        zip: ZipEntry,
+        axml: AXML,
+        arsc: Option<ARSC>,
+        manifest_container: Option<String>,
+        perf: PerfStats,
+    ) -> Apk {
+        Apk {
+            zip,
+            axml,
+            arsc,
+            manifest_container,
+            bundle: false,
+            signatures: OnceLock::new(),
+            perf: Mutex::new(perf),
         }
     }
+
+    /// Reads and parses `resources.arsc`, if the archive has one.
+    fn init_arsc(zip: &ZipEntry, perf: &mut PerfStats) -> Result<Option<ARSC>, APKError> {
+        let measure = Measure::start();
+        let Ok((resource_data, _)) = zip.read_borrowed(RESOURCE_TABLE_PATH) else {
+            return Ok(None);
+        };
+
+        let arsc =
+            ARSC::new(&mut &resource_data[..]).map_err(|source| APKError::ResourceError {
+                entry: RESOURCE_TABLE_PATH.to_string(),
+                source,
+            })?;
+        perf.arsc = Some(measure.finish(resource_data.len()));
+
+        Ok(Some(arsc))
+    }
+
+    /// Adds statistics of a lazily parsed part.
+    fn record_perf(&self, f: impl FnOnce(&mut PerfStats)) {
+        f(&mut self.perf.lock().unwrap_or_else(PoisonError::into_inner));
+    }
 }
 
 impl Apk {
     /// Creates a new [Apk] object.
     ///
-    /// Upon initialization, the apk file will be read and analyzed.
+    /// Upon initialization, the apk file will be read and analyzed, see [Apk::open_mmap]
+    /// for large files. Besides apk, xapk and app bundles (aab) are accepted.
     ///
     /// ```ignore
     /// let apk = Apk::new("./file.apk").expect("can't analyze apk file");
@@ -126,9 +276,67 @@ impl Apk {
             )));
         }
 
-        let (zip, axml, arsc) = Self::init(path)?;
+        let measure = Measure::start();
+        let input = Self::read_file(path)?;
+
+        Self::init(input, measure)
+    }
+
+    /// Creates a new [Apk] object from the memory-mapped file.
+    ///
+    /// Entries are loaded from disk only when they are read, see [ZipData].
+    /// Files that can't be mapped are read into memory as in [Apk::new].
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the [Apk] is alive, see [ZipData::map].
+    ///
+    /// ```ignore
+    /// // SAFETY: the file is a private copy nobody else writes to
+    /// let apk = unsafe { Apk::open_mmap("./file.apk") }.expect("can't analyze apk file");
+    /// ```
+    pub unsafe fn open_mmap<P: AsRef<Path>>(path: P) -> Result<Apk, APKError> {
+        let path = path.as_ref();
+
+        let measure = Measure::start();
+        // SAFETY: forwarded to the caller
+        let input = match unsafe { ZipData::map(path) } {
+            Ok(data) => data,
+            Err(_) => Self::read_file(path)?,
+        };
+
+        Self::init(input, measure)
+    }
+
+    /// Creates a new [Apk] object from the data already in memory.
+    ///
+    /// Useful when the apk doesn't come from disk, for example from network or object storage.
+    ///
+    /// ```ignore
+    /// let apk = Apk::from_bytes(std::fs::read("./file.apk")?)?;
+    /// ```
+    pub fn from_bytes(data: Vec<u8>) -> Result<Apk, APKError> {
+        Self::init(ZipData::Owned(data), Measure::start())
+    }
 
-        Ok(Apk { zip, axml, arsc })
+    /// Returns parse timing and memory statistics per subsystem.
+    ///
+    /// Lazily parsed parts (signatures, dex files) are included once they are parsed,
+    /// see [crate::perf] for allocation tracking.
+    ///
+    /// ```ignore
+    /// let apk = Apk::new("./file.apk")?;
+    /// apk.get_signatures_ref()?;
+    ///
+    /// let stats = apk.perf_stats();
+    /// println!("manifest: {:?}, total: {:?}", stats.axml.duration, stats.total_duration());
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn perf_stats(&self) -> PerfStats {
+        self.perf
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
     }
 
     /// Reads data from `apk` file.
@@ -142,6 +350,20 @@ impl Apk {
         self.zip.read(filename)
     }
 
+    /// Same as [Apk::read], but stored entries are borrowed without a copy.
+    ///
+    /// ```ignore
+    /// let (data, _) = apk.read_borrowed("lib/arm64-v8a/libnative.so")?;
+    /// let digest = Sha256::digest(&data);
+    /// ```
+    #[inline]
+    pub fn read_borrowed(
+        &self,
+        filename: &str,
+    ) -> Result<(Cow<'_, [u8]>, FileCompressionType), ZipError> {
+        self.zip.read_borrowed(filename)
+    }
+
     /// Retrieves the list of files that are specified in the central directory (zip).
     ///
     /// ```ignore
@@ -155,28 +377,576 @@ impl Apk {
         self.zip.namelist()
     }
 
+    /// Returns a sorted and de-duplicated file listing with all parent directories.
+    ///
+    /// Unlike [Apk::namelist], the order is stable, directories missing from the archive are
+    /// synthesized and every entry carries the number of central directory records with its name.
+    ///
+    /// ```ignore
+    /// for file in apk.get_files_normalized() {
+    ///     if file.count > 1 {
+    ///         println!("{} is duplicated {} times", file.name, file.count);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_files_normalized(&self) -> Vec<NormalizedFile> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for name in self.zip.raw_namelist() {
+            *counts.entry(name).or_default() += 1;
+        }
+
+        let mut directories: BTreeSet<&str> = BTreeSet::new();
+        for name in counts.keys() {
+            let parents = name
+                .trim_end_matches('/')
+                .match_indices('/')
+                .map(|(i, _)| &name[..=i]);
+            directories.extend(parents.filter(|parent| !counts.contains_key(parent)));
+        }
+
+        let mut files: Vec<NormalizedFile> = counts
+            .into_iter()
+            .map(|(name, count)| NormalizedFile {
+                name: name.to_string(),
+                is_directory: name.ends_with('/'),
+                synthesized: false,
+                count,
+            })
+            .chain(directories.into_iter().map(|name| NormalizedFile {
+                name: name.to_string(),
+                is_directory: true,
+                synthesized: true,
+                count: 0,
+            }))
+            .collect();
+        files.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        files
+    }
+
+    /// Returns raw bytes of `AndroidManifest.xml` together with its zip metadata.
+    ///
+    /// The data is read the same tamper-aware way as for parsing. For xapk files the
+    /// manifest is taken from the inner apk, so offsets in [EntryInfo] are relative to it
+    /// and the name is prefixed with the inner apk name. For bundles the protobuf
+    /// manifest of the base module is returned.
+    ///
+    /// ```ignore
+    /// let (data, info) = apk.raw_manifest()?;
+    /// std::fs::write("AndroidManifest.xml", data)?;
+    /// println!("{:?}", info.compression);
+    /// ```
+    pub fn raw_manifest(&self) -> Result<(Vec<u8>, EntryInfo), APKError> {
+        if self.bundle {
+            return Ok(self.zip.read_with_info(BUNDLE_MANIFEST_PATH)?);
+        }
+
+        let Some(container) = &self.manifest_container else {
+            return Ok(self.zip.read_with_info(ANDROID_MANIFEST_PATH)?);
+        };
+
+        let (inner_apk_data, _) = self.zip.read(container)?;
+        let inner_apk = ZipEntry::new(inner_apk_data)?;
+        let (data, mut info) = inner_apk.read_with_info(ANDROID_MANIFEST_PATH)?;
+        info.name = format!("{container}/{ANDROID_MANIFEST_PATH}");
+
+        Ok((data, info))
+    }
+
+    /// Returns raw bytes of `resources.arsc` together with its zip metadata.
+    ///
+    /// Fails with [ZipError::FileNotFound] if the apk has no resource table.
+    /// For bundles `base/resources.pb` is returned.
+    pub fn raw_resources(&self) -> Result<(Vec<u8>, EntryInfo), APKError> {
+        if self.bundle {
+            return Ok(self.zip.read_with_info(BUNDLE_RESOURCE_TABLE_PATH)?);
+        }
+
+        Ok(self.zip.read_with_info(RESOURCE_TABLE_PATH)?)
+    }
+
+    /// Returns compressed and uncompressed sizes of the file from the central directory.
+    #[inline]
+    pub fn get_file_sizes(&self, filename: &str) -> Option<(u64, u64)> {
+        self.zip.get_file_sizes(filename)
+    }
+
+    /// Returns metadata of all files in the archive, sorted by name, directories are skipped.
+    ///
+    /// Only the first bytes of every entry are decompressed to detect [EntryInfo::file_type],
+    /// entries that can't be read are skipped.
+    ///
+    /// ```ignore
+    /// for info in apk.get_file_infos() {
+    ///     if info.file_type == Some(FileType::Elf) && !info.name.starts_with("lib/") {
+    ///         println!("native code outside of lib/: {}", info.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_file_infos(&self) -> Vec<EntryInfo> {
+        let mut infos: Vec<EntryInfo> = self
+            .zip
+            .namelist()
+            .filter(|name| !name.ends_with('/'))
+            .filter_map(|name| match self.zip.entry_info(name) {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    warn!("can't read {name}, skipped: {e}");
+                    None
+                }
+            })
+            .collect();
+        infos.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        infos
+    }
+
+    /// Returns sha256 of the apk file, lowercase hex, as VirusTotal and most feeds identify it.
+    ///
+    /// ```ignore
+    /// println!("https://www.virustotal.com/gui/file/{}", apk.get_sha256());
+    /// ```
+    #[inline]
+    pub fn get_sha256(&self) -> String {
+        self.zip.hash(HashAlgorithm::Sha256)
+    }
+
+    /// Returns sha1 of the apk file, lowercase hex.
+    #[inline]
+    pub fn get_sha1(&self) -> String {
+        self.zip.hash(HashAlgorithm::Sha1)
+    }
+
+    /// Returns md5 of the apk file, lowercase hex.
+    #[inline]
+    pub fn get_md5(&self) -> String {
+        self.zip.hash(HashAlgorithm::Md5)
+    }
+
+    /// Computes md5, sha1 and sha256 of the uncompressed data of all files, sorted by name,
+    /// directories are skipped.
+    ///
+    /// Files are hashed as they are decompressed, one at a time per thread, so large apks
+    /// are not loaded into memory. Entries that can't be read are skipped.
+    ///
+    /// ```ignore
+    /// for hashes in apk.get_file_hashes() {
+    ///     println!("{}  {}", hashes.sha256, hashes.name);
+    /// }
+    /// ```
+    pub fn get_file_hashes(&self) -> Vec<EntryHashes> {
+        let names: Vec<&str> = self
+            .zip
+            .namelist()
+            .filter(|name| !name.ends_with('/'))
+            .collect();
+
+        let hash = |name: &str| match self.zip.hash_entry_all(name) {
+            Ok(hashes) => Some(hashes),
+            Err(e) => {
+                warn!("can't read {name}, skipped: {e}");
+                None
+            }
+        };
+
+        #[cfg(feature = "parallel")]
+        let mut hashes: Vec<EntryHashes> = {
+            use rayon::prelude::*;
+
+            names.into_par_iter().filter_map(hash).collect()
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let mut hashes: Vec<EntryHashes> = names.into_iter().filter_map(hash).collect();
+
+        hashes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        hashes
+    }
+
+    /// Computes the digest of the uncompressed data of the file, see [ZipEntry::hash_entry].
+    #[inline]
+    pub fn hash_file(&self, filename: &str, algorithm: HashAlgorithm) -> Result<String, ZipError> {
+        self.zip.hash_entry(filename, algorithm)
+    }
+
+    /// Returns metadata of the file, only the first bytes of the data are decompressed.
+    ///
+    /// ```ignore
+    /// let info = apk.get_file_info("classes.dex")?;
+    /// println!("{} bytes, {:?}", info.uncompressed_size, info.compression);
+    /// ```
+    #[inline]
+    pub fn get_file_info(&self, filename: &str) -> Result<EntryInfo, APKError> {
+        Ok(self.zip.entry_info(filename)?)
+    }
+
+    /// Returns the CRC-32 of the file declared in the central directory.
+    #[inline]
+    pub fn get_file_crc32(&self, filename: &str) -> Option<u32> {
+        self.zip.get_file_crc32(filename)
+    }
+
     /// Converts the internal xml representation of the `AndroidManifest.xml` to a human readable format.
     #[inline]
     pub fn get_xml_string(&self) -> String {
         self.axml.get_xml_string()
     }
 
+    /// Returns the root `<manifest>` element of the decoded `AndroidManifest.xml`.
+    ///
+    /// Resource references are already resolved, the same as in [Apk::get_xml_string].
+    #[inline]
+    pub fn get_manifest_root(&self) -> &Element {
+        &self.axml.root
+    }
+
     /// Checks if the APK has multiple `classes.dex` files or not.
     pub fn is_multidex(&self) -> bool {
-        self.zip
+        self.dex_names().len() > 1
+    }
+
+    /// Checks if the file is an Android App Bundle (aab) rather than apk.
+    ///
+    /// Bundles keep the manifest and resources in protobuf format, they are parsed into
+    /// the same structures, so the getters work the same way. Only the base module is analyzed.
+    #[inline]
+    pub fn is_bundle(&self) -> bool {
+        self.bundle
+    }
+
+    /// Returns the path of the file as it would be in the apk.
+    ///
+    /// The base module of the bundle is kept under `base/` with dex files in `base/dex/`,
+    /// files of other modules are not a part of the app and [None] is returned for them.
+    pub(crate) fn module_path<'a>(&self, name: &'a str) -> Option<&'a str> {
+        if !self.bundle {
+            return Some(name);
+        }
+
+        let name = name.strip_prefix("base/")?;
+        Some(
+            name.strip_prefix("dex/")
+                .or_else(|| name.strip_prefix("root/"))
+                .unwrap_or(name),
+        )
+    }
+
+    /// Returns names of the `classes*.dex` files in the order they are loaded by the runtime.
+    pub fn dex_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .zip
             .namelist()
-            .filter(|name| {
-                // don't use regexes, i think it's overengineering for this task
-                if !name.starts_with("classes") || !name.ends_with(".dex") {
-                    return false;
-                }
+            .filter(|name| self.module_path(name).is_some_and(is_dex_name))
+            .collect();
+
+        // classes.dex, classes2.dex, ..., classes10.dex
+        names.sort_by_key(|name| (name.len(), *name));
+        names
+    }
+
+    /// Reads and parses the dex file from the archive.
+    pub fn read_dex(&self, name: &str) -> Result<Dex, APKError> {
+        let measure = Measure::start();
+        let (data, _) = self.zip.read(name)?;
+        let size = data.len();
+
+        let dex = Dex::new(data).map_err(|source| APKError::DexError {
+            entry: name.to_string(),
+            source,
+        })?;
+
+        let stats = measure.finish(size);
+        self.record_perf(|perf| perf.dex.get_or_insert_with(PhaseStats::default).add(stats));
+
+        Ok(dex)
+    }
+
+    /// Returns strings of all `classes*.dex` files.
+    ///
+    /// Dex files are parsed lazily, one per thread with the `parallel` feature, those that
+    /// can't be parsed are skipped.
+    ///
+    /// ```ignore
+    /// let urls = apk.get_dex_strings().filter(|s| s.starts_with("http"));
+    /// ```
+    pub fn get_dex_strings(&self) -> impl Iterator<Item = String> + '_ {
+        self.flat_map_dex(|dex| dex.strings().collect())
+    }
+
+    /// Returns java names of the classes defined in all `classes*.dex` files:
+    /// `com.example.MainActivity`.
+    ///
+    /// Dex files are parsed lazily, one per thread with the `parallel` feature, those that
+    /// can't be parsed are skipped.
+    pub fn get_dex_classes(&self) -> impl Iterator<Item = String> + '_ {
+        self.flat_map_dex(|dex| {
+            dex.classes()
+                .iter()
+                .filter_map(|class| dex.get_class_name(class.class_idx))
+                .collect()
+        })
+    }
+
+    /// Returns signatures of the methods defined or called in all `classes*.dex` files:
+    /// `Landroid/app/Activity;->finish()V`.
+    ///
+    /// Dex files are parsed lazily, one per thread with the `parallel` feature, those that
+    /// can't be parsed are skipped.
+    /// Type and prototype descriptors are decoded once and shared between the dex files.
+    pub fn get_dex_methods(&self) -> impl Iterator<Item = String> + '_ {
+        let interner = Interner::default();
+
+        self.flat_map_dex(move |dex| {
+            let types: Vec<Option<Arc<str>>> = (0..dex.types_count() as u32)
+                .map(|idx| dex.get_type(idx).map(|t| interner.intern(t)))
+                .collect();
+            let protos: Vec<Option<Arc<str>>> = (0..dex.proto_ids().len() as u32)
+                .map(|idx| dex.get_proto_descriptor(idx).map(|p| interner.intern(p)))
+                .collect();
+
+            dex.method_ids()
+                .iter()
+                .filter_map(|method| {
+                    let class = types.get(method.class_idx as usize)?.as_deref()?;
+                    let proto = protos.get(method.proto_idx as usize)?.as_deref()?;
+                    Some(format!(
+                        "{class}->{}{proto}",
+                        dex.get_string(method.name_idx)?
+                    ))
+                })
+                .collect()
+        })
+    }
+
+    /// Reads the dex files in the order of [Apk::dex_names] on demand and flattens the items
+    /// collected by `f`.
+    ///
+    /// With the `parallel` feature the files are processed in chunks of one file per thread
+    /// on the rayon thread pool, the order of the items is the same.
+    fn flat_map_dex<T, F>(&self, f: F) -> impl Iterator<Item = T> + '_
+    where
+        T: Send + 'static,
+        F: Fn(&Dex) -> Vec<T> + Send + Sync + 'static,
+    {
+        let collect = move |name: &str| match self.read_dex(name) {
+            Ok(dex) => f(&dex),
+            Err(e) => {
+                warn!("{name}: skipped, {}", e.display_chain());
+                Vec::new()
+            }
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let names = self.dex_names();
+            let chunks: Vec<Vec<&str>> = names
+                .chunks(rayon::current_num_threads())
+                .map(<[&str]>::to_vec)
+                .collect();
+
+            chunks.into_iter().flat_map(move |chunk| {
+                chunk
+                    .into_par_iter()
+                    .map(&collect)
+                    .collect::<Vec<Vec<T>>>()
+                    .into_iter()
+                    .flatten()
+            })
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        self.dex_names().into_iter().flat_map(collect)
+    }
+
+    /// Runs `f` for every `classes*.dex` file, results are in the order of [Apk::dex_names].
+    ///
+    /// With the `parallel` feature the files are processed on the rayon thread pool,
+    /// `f` is expected to read and parse the dex file itself.
+    pub(crate) fn map_dex<T, F>(&self, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&str) -> T + Send + Sync,
+    {
+        let names = self.dex_names();
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            names.into_par_iter().map(f).collect()
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        names.into_iter().map(f).collect()
+    }
+
+    /// Same as [Apk::map_dex], but `f` gets the parsed dex file. Files that can't be parsed
+    /// or processed are skipped with a warning, so one broken dex doesn't hide the others.
+    pub(crate) fn try_map_dex<T, F>(&self, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&str, &Dex) -> Result<T, APKError> + Send + Sync,
+    {
+        self.map_dex(|name| {
+            self.read_dex(name)
+                .and_then(|dex| f(name, &dex))
+                .inspect_err(|e| warn!("{name}: skipped, {}", e.display_chain()))
+                .ok()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Same as [Apk::try_map_dex], the progress is reported per dex file and the remaining
+    /// files are skipped with [APKError::Cancelled] as soon as `cancel` is triggered.
+    #[cfg(feature = "experimental")]
+    fn scan_dex<T, F>(
+        &self,
+        progress: Option<&(dyn ProgressSink + Sync)>,
+        cancel: Option<&CancellationToken>,
+        f: F,
+    ) -> Result<Vec<T>, APKError>
+    where
+        T: Send,
+        F: Fn(&str, &Dex) -> Result<T, APKError> + Send + Sync,
+    {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let total = self.dex_names().len() as u64;
+        let done = AtomicU64::new(0);
+
+        let results = self.map_dex(|name| {
+            if cancel.is_some_and(CancellationToken::is_cancelled) {
+                return None;
+            }
+
+            let result = self
+                .read_dex(name)
+                .and_then(|dex| f(name, &dex))
+                .inspect_err(|e| warn!("{name}: skipped, {}", e.display_chain()))
+                .ok();
 
-                let middle = &name["classes".len()..name.len() - ".dex".len()];
+            if let Some(progress) = progress {
+                progress.report(Stage::Dex, done.fetch_add(1, Ordering::Relaxed) + 1, total);
+            }
+            result
+        });
+
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(APKError::Cancelled);
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Returns the parsed `resources.arsc`, if the apk has one.
+    #[inline]
+    pub fn get_resource_table(&self) -> Option<&ARSC> {
+        self.arsc.as_ref()
+    }
 
-                middle.is_empty() || middle.chars().all(|c| c.is_ascii_digit())
+    /// Returns anomalies found while parsing `AndroidManifest.xml`.
+    ///
+    /// For example, duplicate attributes (Android takes the first one) or attributes
+    /// declared with unexpected namespaces.
+    #[inline]
+    pub fn get_manifest_diagnostics(&self) -> &[XmlDiagnostic] {
+        self.axml.diagnostics()
+    }
+
+    /// Returns anomalies of the zip container.
+    ///
+    /// For example, data before the first local header or a non-empty archive comment.
+    ///
+    /// Signer public keys that don't match their certificates are reported too, if the
+    /// signatures can be parsed, as well as entries whose content doesn't match the extension.
+    pub fn get_archive_anomalies(&self) -> Vec<ZipAnomaly> {
+        let mut anomalies = self.zip.anomalies();
+
+        anomalies.extend(self.get_file_infos().into_iter().filter_map(|info| {
+            let file_type = info.file_type?;
+            (!file_type.matches_extension(&info.name)).then_some(ZipAnomaly::ExtensionMismatch {
+                name: info.name,
+                file_type,
             })
-            .count()
-            > 1
+        }));
+
+        let Ok(signatures) = self.get_signatures_ref() else {
+            return anomalies;
+        };
+
+        for signature in signatures {
+            let (Signature::V2 { certificates, .. }
+            | Signature::V3 { certificates, .. }
+            | Signature::V31 { certificates, .. }) = signature
+            else {
+                continue;
+            };
+
+            anomalies.extend(
+                certificates
+                    .iter()
+                    .filter(|certificate| certificate.has_public_key_mismatch())
+                    .map(|certificate| ZipAnomaly::PublicKeyMismatch {
+                        scheme: signature.name(),
+                        certificate: certificate.sha256_fingerprint.clone(),
+                    }),
+            );
+        }
+
+        anomalies
+    }
+
+    /// Returns all tampering and anomalies found in the apk: manifest diagnostics,
+    /// archive anomalies, tampered entry headers and dex files that don't match their headers.
+    ///
+    /// Parses all dex files, so it is slower than the other getters.
+    ///
+    /// ```ignore
+    /// for anomaly in &apk.get_anomalies() {
+    ///     println!("{anomaly}");
+    /// }
+    /// ```
+    pub fn get_anomalies(&self) -> AnomalyReport {
+        anomaly::collect(self)
+    }
+
+    /// Compares the package name, signer certificates and label with the known-good apps.
+    ///
+    /// A known package name signed by a foreign certificate is a repackaged app, a known
+    /// label or a lookalike package name under another name is an impersonation,
+    /// see [crate::baseline].
+    ///
+    /// ```ignore
+    /// let baseline = Baseline::from_json(&std::fs::read("baseline.json")?)?;
+    /// let check = apk.check_against_baseline(&baseline);
+    /// for finding in &check.findings {
+    ///     println!("{}: {finding}", check.verdict);
+    /// }
+    /// ```
+    pub fn check_against_baseline(&self, baseline: &Baseline) -> BaselineCheck {
+        baseline::check(self, baseline)
+    }
+
+    /// Returns the raw data of the file.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        self.zip.data()
+    }
+
+    /// Returns the data placed before the first local header of the archive.
+    #[inline]
+    pub fn prepended_data(&self) -> &[u8] {
+        self.zip.prepended_data()
+    }
+
+    /// Returns the comment of the archive (End of Central Directory).
+    #[inline]
+    pub fn archive_comment(&self) -> &[u8] {
+        self.zip.archive_comment()
     }
 
     /// An auxiliary method that allows you to get a value from a reference to a resource.
@@ -276,6 +1046,29 @@ impl Apk {
             .get_attribute_value("manifest", "versionCode", self.arsc.as_ref())
     }
 
+    /// Retrieves the version code as a number, `versionCodeMajor` makes up the upper 32 bits.
+    ///
+    /// Decimal and hex values are accepted, values that aren't numbers give `None`.
+    ///
+    /// See: <https://developer.android.com/reference/android/content/pm/PackageInfo#getLongVersionCode()>
+    ///
+    /// ```ignore
+    /// apk.get_version_code_u64() // Some(2025101912)
+    /// ```
+    pub fn get_version_code_u64(&self) -> Option<u64> {
+        // decimal values are stored as i32, large version codes come out negative
+        let version_code = self
+            .get_version_code()
+            .and_then(|value| parse_resource_int(&value))? as u32;
+        let major = self
+            .axml
+            .get_attribute_value("manifest", "versionCodeMajor", self.arsc.as_ref())
+            .and_then(|value| parse_resource_int(&value))
+            .map_or(0, |major| major as u32);
+
+        Some((u64::from(major) << 32) | u64::from(version_code))
+    }
+
     /// Retrieves the human-readable application version name.
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/manifest-element#vname>
@@ -387,6 +1180,24 @@ impl Apk {
             .get_attribute_value("application", "debuggable", self.arsc.as_ref())
     }
 
+    /// Parses the `android:debuggable` attribute from `<application>`.
+    ///
+    /// Accepts `true`/`false` in any case and `1`/`0`, other values give `None`.
+    #[inline]
+    pub fn is_debuggable(&self) -> Option<bool> {
+        self.get_application_debuggable()
+            .and_then(|value| parse_resource_bool(&value))
+    }
+
+    /// Extracts the `android:testOnly` attribute from `<application>`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/application-element#testOnly>
+    #[inline]
+    pub fn get_application_test_only(&self) -> Option<String> {
+        self.axml
+            .get_attribute_value("application", "testOnly", self.arsc.as_ref())
+    }
+
     /// Extracts and resolve the `android:description` attribute from `<application>`.
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/application-element#desc>
@@ -406,16 +1217,117 @@ impl Apk {
             .get_attribute_value("application", "icon", self.arsc.as_ref())
     }
 
-    /// Extracts and resolves the `android:label` attribute from `<application>`.
+    /// Returns the bitmap of the application icon.
     ///
-    /// See: <https://developer.android.com/guide/topics/manifest/application-element#label>
-    #[inline]
-    pub fn get_application_label(&self) -> Option<String> {
-        self.axml
-            .get_attribute_value("application", "label", self.arsc.as_ref())
-    }
-
-    /// Extracts and resolves the `android:logo` attribute from `<application>`.
+    /// The icon is resolved through the resource table and the bitmap closest to the `density`
+    /// is picked: the smallest one that is not lower, otherwise the largest available, as Android does.
+    /// Without `density` the largest bitmap is returned.
+    ///
+    /// Adaptive icons (`mipmap-anydpi-v26`) are xml, so the legacy bitmap of the same resource
+    /// is preferred, if there is none, the bitmap of the foreground layer is returned.
+    /// Vector drawables can't be rendered and give [None].
+    ///
+    /// ```ignore
+    /// if let Some((data, kind)) = apk.get_icon_bytes(Some(Density::XXHigh)) {
+    ///     std::fs::write(format!("icon.{}", kind.format()), data)?;
+    /// }
+    /// ```
+    pub fn get_icon_bytes(&self, density: Option<Density>) -> Option<(Vec<u8>, IconKind)> {
+        let arsc = self.arsc.as_ref()?;
+        let icon = self.resource_id(
+            self.axml
+                .get_all_attribute_values("application", "icon")
+                .next()?,
+        )?;
+
+        if let Some((data, format)) = self.icon_bitmap(arsc, icon, density) {
+            return Some((data, IconKind::Bitmap(format)));
+        }
+
+        let foreground = self.adaptive_icon_foreground(arsc, icon)?;
+        let (data, format) = self.icon_bitmap(arsc, foreground, density)?;
+
+        Some((data, IconKind::AdaptiveForeground(format)))
+    }
+
+    /// Extracts and resolves the `android:label` attribute from `<application>`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/application-element#label>
+    #[inline]
+    pub fn get_application_label(&self) -> Option<String> {
+        self.axml
+            .get_attribute_value("application", "label", self.arsc.as_ref())
+    }
+
+    /// Resolves the `android:label` attribute from `<application>` for the locale:
+    /// `ru`, `ru-RU` or `ru-rRU`.
+    ///
+    /// Falls back to the default value if the label is not translated, literal labels are
+    /// returned as is.
+    ///
+    /// ```ignore
+    /// assert_eq!(apk.get_application_label_for_locale("ru-RU").as_deref(), Some("Пример"));
+    /// ```
+    pub fn get_application_label_for_locale(&self, locale: &str) -> Option<String> {
+        let label = self
+            .axml
+            .get_all_attribute_values("application", "label")
+            .next()?;
+        if !label.starts_with('@') {
+            return Some(label.to_string());
+        }
+
+        self.arsc.as_ref()?.get_resource_value_with_config(
+            self.resource_id(label)?,
+            &ResConfigQuery::from_locale(locale),
+        )
+    }
+
+    /// Returns every translation of the application label, the key is the locale (`ru`, `ru-RU`)
+    /// or an empty string for the default value.
+    ///
+    /// ```ignore
+    /// for (locale, label) in apk.get_application_labels() {
+    ///     println!("{locale}: {label}");
+    /// }
+    /// ```
+    pub fn get_application_labels(&self) -> BTreeMap<String, String> {
+        let Some(label) = self
+            .axml
+            .get_all_attribute_values("application", "label")
+            .next()
+        else {
+            return BTreeMap::new();
+        };
+        if !label.starts_with('@') {
+            return BTreeMap::from([(String::new(), label.to_string())]);
+        }
+
+        let (Some(arsc), Some(id)) = (self.arsc.as_ref(), self.resource_id(label)) else {
+            return BTreeMap::new();
+        };
+
+        // the label can be defined for other qualifiers too (`ru-rRU-v26`), one value per locale
+        let locales: BTreeSet<String> = arsc
+            .get_resource_values_all_configs(id)
+            .into_iter()
+            .map(|(config, _)| match config.get_language_region() {
+                (language, region) if region.is_empty() => language,
+                (language, region) => format!("{language}-{region}"),
+            })
+            .collect();
+
+        locales
+            .into_iter()
+            .filter_map(|locale| {
+                let label =
+                    arsc.get_resource_value_with_config(id, &ResConfigQuery::from_locale(&locale))?;
+                Some((locale, label))
+            })
+            .collect()
+    }
+
+    /// Extracts and resolves the `android:logo` attribute from `<application>`.
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/application-element#logo>
     #[inline]
@@ -445,6 +1357,21 @@ impl Apk {
             })
     }
 
+    /// Retrieves all `<instrumentation>` declarations.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/instrumentation-element>
+    pub fn get_instrumentations(&self) -> impl Iterator<Item = Instrumentation<'_>> {
+        self.axml
+            .root
+            .childrens()
+            .filter(|el| el.name() == "instrumentation")
+            .map(|el| Instrumentation {
+                name: el.attr("name"),
+                target_package: el.attr("targetPackage"),
+                handle_profiling: el.attr("handleProfiling"),
+            })
+    }
+
     /// Retrieves all declared permissions from `<uses-permission android:name="...">`.
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/uses-permission-element>
@@ -472,6 +1399,20 @@ impl Apk {
             .get_attribute_value("uses-sdk", "minSdkVersion", self.arsc.as_ref())
     }
 
+    /// Parses the minimum supported SDK version (`minSdkVersion`) as a number.
+    ///
+    /// Codenames of preview releases (`"Tiramisu"`) give `None`.
+    ///
+    /// ```ignore
+    /// apk.get_min_sdk() // Some(26)
+    /// ```
+    #[inline]
+    pub fn get_min_sdk(&self) -> Option<u32> {
+        self.get_min_sdk_version()
+            .and_then(|value| parse_resource_int(&value))
+            .and_then(|sdk| u32::try_from(sdk).ok())
+    }
+
     /// Extracts the target SDK version (`targetSdkVersion`) from the `<uses-sdk>` element.
     ///
     /// Determines the version based on the following algorithm:
@@ -485,7 +1426,8 @@ impl Apk {
         self.axml
             .get_attribute_value("uses-sdk", "targetSdkVersion", self.arsc.as_ref())
             .or_else(|| self.get_min_sdk_version())
-            .and_then(|sdk| sdk.parse::<u32>().ok())
+            .and_then(|sdk| parse_resource_int(&sdk))
+            .and_then(|sdk| u32::try_from(sdk).ok())
             .unwrap_or(1)
     }
 
@@ -523,6 +1465,24 @@ impl Apk {
         self.axml.get_root_attribute_values("uses-feature", "name")
     }
 
+    /// Retrieves all `<uses-feature>` declarations with their `required`, `glEsVersion` and `version`.
+    ///
+    /// Google Play hides the application from devices without the required features.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/uses-feature-element>
+    pub fn get_features_detailed(&self) -> impl Iterator<Item = Feature<'_>> {
+        self.axml
+            .root
+            .childrens()
+            .filter(|el| el.name() == "uses-feature")
+            .map(|el| Feature {
+                name: el.attr("name"),
+                required: el.attr("required"),
+                gl_es_version: el.attr("glEsVersion"),
+                version: el.attr("version"),
+            })
+    }
+
     /// Checks whether the app is designed to display its user interface on multiple screens inside the vehicle.
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features>
@@ -558,8 +1518,122 @@ impl Apk {
         self.get_features().any(|x| x == "android.hardware.type.pc")
     }
 
+    /// Checks whether the apk looks like a test build that should not be shipped.
+    ///
+    /// Any of the following is enough:
+    /// - `android:testOnly="true"` in `<application>`, set by Android Studio for run configurations;
+    /// - `<instrumentation>` declarations or the `.test` package suffix of `androidTest` apks;
+    /// - classes of `androidx.test` or `android.support.test` in the dex files.
+    ///
+    /// Dex files that can't be parsed are skipped.
+    pub fn is_test_artifact(&self) -> bool {
+        if self.get_application_test_only().as_deref() == Some("true")
+            || self.get_instrumentations().next().is_some()
+            || self
+                .get_package_name()
+                .is_some_and(|package| package.ends_with(".test"))
+        {
+            return true;
+        }
+
+        self.map_dex(|name| match self.read_dex(name) {
+            Ok(dex) => dex.classes().iter().any(|class| {
+                dex.get_class_name(class.class_idx)
+                    .is_some_and(|class_name| {
+                        TEST_CLASS_PREFIXES
+                            .iter()
+                            .any(|prefix| class_name.starts_with(prefix))
+                    })
+            }),
+            Err(e) => {
+                warn!(
+                    "{name}: skipped while looking for test classes: {}",
+                    e.display_chain()
+                );
+                false
+            }
+        })
+        .into_iter()
+        .any(|found| found)
+    }
+
+    /// Retrieves `<supports-screens>`, if the manifest has one.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element>
+    pub fn get_supports_screens(&self) -> Option<SupportsScreens<'_>> {
+        self.axml
+            .root
+            .childrens()
+            .find(|el| el.name() == "supports-screens")
+            .map(|el| SupportsScreens {
+                resizeable: el.attr("resizeable"),
+                small_screens: el.attr("smallScreens"),
+                normal_screens: el.attr("normalScreens"),
+                large_screens: el.attr("largeScreens"),
+                xlarge_screens: el.attr("xlargeScreens"),
+                any_density: el.attr("anyDensity"),
+                requires_smallest_width_dp: el.attr("requiresSmallestWidthDp"),
+                compatible_width_limit_dp: el.attr("compatibleWidthLimitDp"),
+                largest_width_limit_dp: el.attr("largestWidthLimitDp"),
+            })
+    }
+
+    /// Retrieves screens listed in `<compatible-screens>`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/compatible-screens-element>
+    pub fn get_compatible_screens(&self) -> impl Iterator<Item = CompatibleScreen<'_>> {
+        self.axml
+            .root
+            .childrens()
+            .filter(|el| el.name() == "compatible-screens")
+            .flat_map(|el| el.childrens())
+            .filter(|el| el.name() == "screen")
+            .map(|el| CompatibleScreen {
+                screen_size: el.attr("screenSize"),
+                screen_density: el.attr("screenDensity"),
+            })
+    }
+
+    /// Returns the smallest screen size the application can be installed on:
+    /// `small`, `normal`, `large` or `xlarge`.
+    ///
+    /// `<compatible-screens>` takes precedence, since Google Play filters devices by it,
+    /// otherwise `<supports-screens>` flags are used (small and normal screens are supported by default).
+    /// Returns `None` if every screen size is excluded.
+    pub fn get_min_supported_screens(&self) -> Option<&'static str> {
+        const SIZES: [&str; 4] = ["small", "normal", "large", "xlarge"];
+
+        let compatible: Vec<CompatibleScreen> = self.get_compatible_screens().collect();
+        if !compatible.is_empty() {
+            return SIZES.into_iter().find(|size| {
+                compatible
+                    .iter()
+                    .any(|screen| screen.screen_size == Some(*size))
+            });
+        }
+
+        let Some(supports) = self.get_supports_screens() else {
+            return Some("small");
+        };
+
+        // large and xlarge defaults depend on the target sdk, unset values are treated as supported
+        [
+            supports.small_screens,
+            supports.normal_screens,
+            supports.large_screens,
+            supports.xlarge_screens,
+        ]
+        .into_iter()
+        .zip(SIZES)
+        .find(|(value, _)| *value != Some("false"))
+        .map(|(_, size)| size)
+    }
+
     /// Retrieves all user defines permissions.
     ///
+    /// Attributes are returned as is, see [Apk::get_declared_permissions_detailed] for resolved
+    /// labels and the parsed protection level.
+    ///
     /// See: <https://developer.android.com/guide/topics/manifest/permission-element>
     #[inline]
     pub fn get_declared_permissions(&self) -> impl Iterator<Item = Permission<'_>> {
@@ -578,6 +1652,81 @@ impl Apk {
             })
     }
 
+    /// Retrieves all user defined permissions with the label and description resolved from
+    /// `resources.arsc` and the parsed protection level.
+    ///
+    /// Helps to audit custom `dangerous` and `signature` permissions, references that can't be
+    /// resolved are returned as is.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/permission-element>
+    pub fn get_declared_permissions_detailed(
+        &self,
+    ) -> impl Iterator<Item = DeclaredPermission<'_>> {
+        let resolve = |value: &str| {
+            self.get_resource_value(value)
+                .unwrap_or_else(|| value.to_string())
+        };
+
+        self.get_declared_permissions()
+            .map(move |permission| DeclaredPermission {
+                name: permission.name,
+                label: permission.label.map(resolve),
+                description: permission.description.map(resolve),
+                icon: permission.icon,
+                permission_group: permission.permission_group,
+                protection_level: permission
+                    .protection_level
+                    .map_or(ProtectionLevel::Normal, ProtectionLevel::from_attribute),
+                protection_flags: permission
+                    .protection_level
+                    .map(|value| ProtectionLevel::flags(value).collect())
+                    .unwrap_or_default(),
+            })
+    }
+
+    /// Groups requested permissions (`<uses-permission>` and `<uses-permission-sdk-23>`)
+    /// by protection level.
+    ///
+    /// Permissions declared by the apk itself take the level from their `<permission>` element,
+    /// others are looked up in the bundled table of framework permissions. Lists are sorted and
+    /// de-duplicated.
+    pub fn get_permissions_by_protection(&self) -> PermissionsByProtection<'_> {
+        let declared: HashMap<&str, ProtectionLevel> = self
+            .get_declared_permissions()
+            .filter_map(|permission| {
+                let level = permission
+                    .protection_level
+                    .map_or(ProtectionLevel::Normal, ProtectionLevel::from_attribute);
+
+                Some((permission.name?, level))
+            })
+            .collect();
+
+        let mut permissions: Vec<&str> = self
+            .get_permissions()
+            .chain(self.get_permissions_sdk23())
+            .collect();
+        permissions.sort_unstable();
+        permissions.dedup();
+
+        let mut result = PermissionsByProtection::default();
+        for permission in permissions {
+            let level = declared
+                .get(permission)
+                .copied()
+                .unwrap_or_else(|| ProtectionLevel::of_framework_permission(permission));
+
+            match level {
+                ProtectionLevel::Normal => result.normal.push(permission),
+                ProtectionLevel::Dangerous => result.dangerous.push(permission),
+                ProtectionLevel::Signature => result.signature.push(permission),
+                ProtectionLevel::Unknown => result.unknown.push(permission),
+            }
+        }
+
+        result
+    }
+
     /// Retrieves first main (launchable) activity defined in the manifest.
     ///
     /// A main activity is typically one that has an intent filter with actions `MAIN` and categories `LAUNCHER` or `INFO`.
@@ -587,19 +1736,183 @@ impl Apk {
     /// Resolve logic: <https://xrefandroid.com/android-16.0.0_r2/xref/frameworks/base/core/java/android/app/ApplicationPackageManager.java#310>
     #[inline]
     pub fn get_main_activity(&self) -> Option<&str> {
-        self.axml.get_main_activities().next()
+        self.get_main_activities().next()
     }
 
     /// Retrieves all main (launchable) activities defined in the manifest.
     ///
     /// A main activity is typically one that has an intent filter with actions `MAIN` and categories `LAUNCHER` or `INFO`.
     ///
+    /// `<activity-alias>` is resolved to its `android:targetActivity`, so only names of the
+    /// declared activities are returned, each one once. Disabled components (including
+    /// `android:enabled` pointing to a resource) are skipped.
+    ///
     /// See: <https://developer.android.com/guide/topics/manifest/activity-element>
     ///
     /// Resolve logic: <https://xrefandroid.com/android-16.0.0_r2/xref/frameworks/base/core/java/android/app/ApplicationPackageManager.java#310>
-    #[inline]
     pub fn get_main_activities(&self) -> impl Iterator<Item = &str> {
-        self.axml.get_main_activities()
+        let application_enabled = self
+            .axml
+            .root
+            .childrens()
+            .find(|el| el.name() == "application")
+            .is_none_or(|app| self.is_enabled(app));
+
+        let mut seen = HashSet::new();
+
+        self.axml
+            .get_launcher_elements()
+            .filter(move |_| application_enabled)
+            .filter(|el| self.is_enabled(el))
+            .filter_map(|el| {
+                if el.name() != "activity-alias" {
+                    return el.attr("name");
+                }
+
+                // the system refuses aliases pointing to undeclared activities
+                let target = el.attr("targetActivity")?;
+                let activity = self.axml.root.descendants().find(|activity| {
+                    activity.name() == "activity"
+                        && activity
+                            .attr("name")
+                            .is_some_and(|name| self.is_same_class(name, target))
+                })?;
+
+                if !self.is_enabled(activity) {
+                    return None;
+                }
+
+                activity.attr("name")
+            })
+            .filter(move |name| seen.insert(*name))
+    }
+
+    /// Checks `android:enabled` of the element, the value may be a reference to a boolean resource.
+    ///
+    /// Unresolved values are treated as enabled, since it is the default.
+    fn is_enabled(&self, element: &Element) -> bool {
+        self.bool_attr(element, "enabled") != Some("false")
+    }
+
+    /// Returns the boolean attribute of the element, references to boolean resources
+    /// (`@bool/enable_feature`, often toggled per build) are resolved to `"true"` or `"false"`.
+    ///
+    /// References that can't be resolved are returned as is.
+    fn bool_attr<'a>(&self, element: &'a Element, name: &str) -> Option<&'a str> {
+        let value = element.attr(name)?;
+        if !value.starts_with('@') {
+            return Some(value);
+        }
+
+        let resolved = self.resource_id(value).and_then(|id| {
+            self.arsc
+                .as_ref()
+                .and_then(|arsc| arsc.get_resource_value(id))
+        });
+
+        match resolved.as_deref() {
+            Some("true") => Some("true"),
+            Some("false") => Some("false"),
+            _ => Some(value),
+        }
+    }
+
+    /// Returns the id of the resource referenced by the attribute value.
+    fn resource_id(&self, value: &str) -> Option<u32> {
+        match value.strip_prefix('@')? {
+            // unresolved references are kept as hex resource ids
+            id if id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit()) => {
+                u32::from_str_radix(id, 16).ok()
+            }
+            name => self.arsc.as_ref()?.get_resource_id(name),
+        }
+    }
+
+    /// Picks the bitmap of the drawable resource for the density, see [Apk::get_icon_bytes].
+    fn icon_bitmap(
+        &self,
+        arsc: &ARSC,
+        id: u32,
+        density: Option<Density>,
+    ) -> Option<(Vec<u8>, FileType)> {
+        // `nodpi` and `anydpi` bitmaps are not scaled, so they are the last resort
+        let dpi = |density: u16| match density {
+            0 => u16::from(Density::Medium),
+            0xfffe | 0xffff => 0,
+            density => density,
+        };
+
+        let candidates = arsc
+            .get_resource_values_all_configs(id)
+            .into_iter()
+            .filter_map(|(config, path)| {
+                let format = self.zip.entry_info(&path).ok()?.file_type?;
+                if !matches!(format, FileType::Png | FileType::Webp | FileType::Jpeg) {
+                    return None;
+                }
+
+                let (_, _, density) = config.get_orientation_touchscreen_density();
+                Some((dpi(density), path, format))
+            });
+
+        let (_, path, format) = match density {
+            Some(density) => {
+                let target = dpi(density.into());
+                candidates.min_by_key(|(dpi, _, _)| match *dpi >= target {
+                    true => (false, *dpi),
+                    false => (true, u16::MAX - *dpi),
+                })?
+            }
+            None => candidates.max_by_key(|(dpi, _, _)| *dpi)?,
+        };
+
+        let (data, _) = self.zip.read(&path).ok()?;
+        Some((data, format))
+    }
+
+    /// Returns the drawable of the foreground layer of the adaptive icon resource.
+    ///
+    /// The layer may be wrapped: `<foreground><inset android:drawable="..."/></foreground>`.
+    fn adaptive_icon_foreground(&self, arsc: &ARSC, id: u32) -> Option<u32> {
+        arsc.get_resource_values_all_configs(id)
+            .into_iter()
+            .find_map(|(_, path)| {
+                let (data, _) = self.zip.read(&path).ok()?;
+                let axml = AXML::new(&mut &data[..], Some(arsc)).ok()?;
+                if axml.root.name() != "adaptive-icon" {
+                    return None;
+                }
+
+                let foreground = axml
+                    .root
+                    .childrens()
+                    .find(|element| element.name() == "foreground")?;
+                let drawable = std::iter::once(foreground)
+                    .chain(foreground.descendants())
+                    .find_map(|element| element.attr("drawable"))?;
+
+                self.resource_id(drawable)
+            })
+    }
+
+    /// Compares class names from the manifest, which may be relative to the package (`.MainActivity`).
+    fn is_same_class(&self, a: &str, b: &str) -> bool {
+        if a == b {
+            return true;
+        }
+
+        let package = self.axml.root.attr("package").unwrap_or_default();
+        let full_name = |name: &'_ str| -> String {
+            if name.starts_with('.') {
+                format!("{package}{name}")
+            } else if !name.contains('.') {
+                format!("{package}.{name}")
+            } else {
+                name.to_string()
+            }
+        };
+
+        full_name(a) == full_name(b)
     }
 
     #[inline]
@@ -613,6 +1926,7 @@ impl Apk {
             .map(|intent| {
                 let mut actions = Vec::new();
                 let mut categories = Vec::new();
+                let mut data = Vec::new();
 
                 // only one iteration
                 for child in intent.childrens() {
@@ -627,6 +1941,15 @@ impl Apk {
                                 categories.push(name);
                             }
                         }
+                        "data" => data.push(IntentData {
+                            scheme: child.attr("scheme"),
+                            host: child.attr("host"),
+                            port: child.attr("port"),
+                            path: child.attr("path"),
+                            path_prefix: child.attr("pathPrefix"),
+                            path_pattern: child.attr("pathPattern"),
+                            mime_type: child.attr("mimeType"),
+                        }),
                         _ => {}
                     }
                 }
@@ -634,6 +1957,8 @@ impl Apk {
                 IntentFilter {
                     actions,
                     categories,
+                    data,
+                    auto_verify: self.bool_attr(intent, "autoVerify"),
                 }
             })
     }
@@ -648,14 +1973,27 @@ impl Apk {
             .descendants()
             .filter(|&el| el.name() == "activity")
             .map(|el| Activity {
-                enabled: el.attr("enabled"),
-                exported: el.attr("exported"),
+                enabled: self.bool_attr(el, "enabled"),
+                exported: self.bool_attr(el, "exported"),
                 icon: el.attr("icon"),
                 label: el.attr("label"),
                 name: el.attr("name"),
-                parent_activity_name: el.attr("parent_activity_name"),
+                parent_activity_name: el.attr("parentActivityName"),
                 permission: el.attr("permission"),
                 process: el.attr("process"),
+                allow_task_reparenting: el.attr("allowTaskReparenting"),
+                config_changes: el.attr("configChanges"),
+                direct_boot_aware: el.attr("directBootAware"),
+                document_launch_mode: el.attr("documentLaunchMode"),
+                exclude_from_recents: el.attr("excludeFromRecents"),
+                launch_mode: el.attr("launchMode"),
+                no_history: el.attr("noHistory"),
+                resizeable_activity: el.attr("resizeableActivity"),
+                screen_orientation: el.attr("screenOrientation"),
+                show_when_locked: el.attr("showWhenLocked"),
+                task_affinity: el.attr("taskAffinity"),
+                theme: el.attr("theme"),
+                window_soft_input_mode: el.attr("windowSoftInputMode"),
                 intent_filters: self.get_intent_filters(el).collect(),
             })
     }
@@ -670,8 +2008,8 @@ impl Apk {
             .descendants()
             .filter(|&el| el.name() == "activity-alias")
             .map(|el| ActivityAlias {
-                enabled: el.attr("enabled"),
-                exported: el.attr("exported"),
+                enabled: self.bool_attr(el, "enabled"),
+                exported: self.bool_attr(el, "exported"),
                 icon: el.attr("icon"),
                 label: el.attr("label"),
                 name: el.attr("name"),
@@ -692,17 +2030,18 @@ impl Apk {
             .filter(|&el| el.name() == "service")
             .map(|el| Service {
                 description: el.attr("description"),
-                direct_boot_aware: el.attr("direct_boot_aware"),
-                enabled: el.attr("enabled"),
-                exported: el.attr("exported"),
-                foreground_service_type: el.attr("foreground_service_type"),
+                direct_boot_aware: el.attr("directBootAware"),
+                enabled: self.bool_attr(el, "enabled"),
+                exported: self.bool_attr(el, "exported"),
+                foreground_service_type: el.attr("foregroundServiceType"),
                 icon: el.attr("icon"),
-                isolated_process: el.attr("isolated_process"),
+                isolated_process: el.attr("isolatedProcess"),
                 label: el.attr("label"),
                 name: el.attr("name"),
                 permission: el.attr("permission"),
                 process: el.attr("process"),
-                stop_with_task: el.attr("stop_with_task"),
+                stop_with_task: el.attr("stopWithTask"),
+                intent_filters: self.get_intent_filters(el).collect(),
             })
     }
 
@@ -716,14 +2055,15 @@ impl Apk {
             .descendants()
             .filter(|&el| el.name() == "receiver")
             .map(|el| Receiver {
-                direct_boot_aware: el.attr("direct_boot_aware"),
-                enabled: el.attr("enabled"),
-                exported: el.attr("exported"),
+                direct_boot_aware: el.attr("directBootAware"),
+                enabled: self.bool_attr(el, "enabled"),
+                exported: self.bool_attr(el, "exported"),
                 icon: el.attr("icon"),
                 label: el.attr("label"),
                 name: el.attr("name"),
                 permission: el.attr("permission"),
                 process: el.attr("process"),
+                intent_filters: self.get_intent_filters(el).collect(),
             })
     }
 
@@ -738,27 +2078,253 @@ impl Apk {
             .filter(|&el| el.name() == "provider")
             .map(|el| Provider {
                 authorities: el.attr("authorities"),
-                enabled: el.attr("enabled"),
-                direct_boot_aware: el.attr("direct_boot_aware"),
-                exported: el.attr("exported"),
-                grant_uri_permissions: el.attr("grant_uri_permissions"),
+                enabled: self.bool_attr(el, "enabled"),
+                direct_boot_aware: el.attr("directBootAware"),
+                exported: self.bool_attr(el, "exported"),
+                grant_uri_permissions: el.attr("grantUriPermissions"),
                 icon: el.attr("icon"),
-                init_order: el.attr("init_order"),
+                init_order: el.attr("initOrder"),
                 label: el.attr("label"),
                 multiprocess: el.attr("multiprocess"),
                 name: el.attr("name"),
                 permission: el.attr("permission"),
                 process: el.attr("process"),
-                read_permission: el.attr("read_permission"),
+                read_permission: el.attr("readPermission"),
                 syncable: el.attr("syncable"),
-                write_permission: el.attr("write_permission"),
+                write_permission: el.attr("writePermission"),
+                intent_filters: self.get_intent_filters(el).collect(),
+            })
+    }
+
+    /// Returns components that other applications can start, bind to or query: activities,
+    /// activity aliases, services, receivers and providers, in this order.
+    ///
+    /// The effective value of `android:exported` is computed as the system does:
+    /// - the explicit value wins, references to boolean resources are resolved;
+    /// - activities, services and receivers without it are exported when they declare intent filters;
+    /// - providers without it are exported when `minSdkVersion` or `targetSdkVersion` is 16 or lower.
+    ///
+    /// Disabled components are reported too, the app can enable them at runtime.
+    ///
+    /// ```ignore
+    /// for component in apk.get_exported_components() {
+    ///     if !component.is_protected() {
+    ///         println!("{} {:?} is open to any app", component.kind.tag(), component.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_exported_components<'a>(&'a self) -> Vec<ExportedComponent<'a>> {
+        let application_permission = self
+            .axml
+            .root
+            .childrens()
+            .find(|el| el.name() == "application")
+            .and_then(|el| el.attr("permission"));
+
+        let reason = |exported: Option<&str>, filters: &[IntentFilter]| match exported {
+            Some("true") => Some(ExportReason::Explicit),
+            Some(_) => None,
+            None if !filters.is_empty() => Some(ExportReason::IntentFilters),
+            None => None,
+        };
+
+        let component = |kind, name, reason, permission: Option<&'a str>, enabled| {
+            Some(ExportedComponent {
+                kind,
+                name,
+                reason,
+                permission: permission.or(application_permission),
+                read_permission: None,
+                write_permission: None,
+                enabled,
+            })
+        };
+
+        let activities = self.get_activities().filter_map(|activity| {
+            component(
+                ComponentKind::Activity,
+                activity.name,
+                reason(activity.exported, &activity.intent_filters)?,
+                activity.permission,
+                activity.is_enabled(),
+            )
+        });
+        let aliases = self.get_activity_aliases().filter_map(|alias| {
+            component(
+                ComponentKind::ActivityAlias,
+                alias.name,
+                reason(alias.exported, &alias.intent_filters)?,
+                alias.permission,
+                alias.is_enabled(),
+            )
+        });
+        let services = self.get_services().filter_map(|service| {
+            component(
+                ComponentKind::Service,
+                service.name,
+                reason(service.exported, &service.intent_filters)?,
+                service.permission,
+                service.is_enabled(),
+            )
+        });
+        let receivers = self.get_receivers().filter_map(|receiver| {
+            component(
+                ComponentKind::Receiver,
+                receiver.name,
+                reason(receiver.exported, &receiver.intent_filters)?,
+                receiver.permission,
+                receiver.is_enabled(),
+            )
+        });
+
+        let legacy_providers =
+            self.get_target_sdk_version() <= 16 || self.get_min_sdk().is_some_and(|sdk| sdk <= 16);
+        let providers = self.get_providers().filter_map(|provider| {
+            let reason = match provider.exported {
+                Some("true") => ExportReason::Explicit,
+                None if legacy_providers => ExportReason::LegacyProviderDefault,
+                _ => return None,
+            };
+
+            Some(ExportedComponent {
+                read_permission: provider.read_permission,
+                write_permission: provider.write_permission,
+                ..component(
+                    ComponentKind::Provider,
+                    provider.name,
+                    reason,
+                    provider.permission,
+                    provider.is_enabled(),
+                )?
             })
+        });
+
+        activities
+            .chain(aliases)
+            .chain(services)
+            .chain(receivers)
+            .chain(providers)
+            .collect()
     }
 
     /// Retrieves all APK signing signatures (v1, v2, v3, v3.1, etc).
     ///
     /// Combines results from multiple signature blocks within the APK file.
+    ///
+    /// Signatures are parsed only once, subsequent calls return a copy of the cached result.
+    /// Use [Apk::get_signatures_ref] to avoid copying.
     pub fn get_signatures(&self) -> Result<Vec<Signature>, APKError> {
+        self.get_signatures_ref().map(<[Signature]>::to_vec)
+    }
+
+    /// Same as [Apk::get_signatures], but returns a reference to the cached signatures.
+    pub fn get_signatures_ref(&self) -> Result<&[Signature], APKError> {
+        if let Some(signatures) = self.signatures.get() {
+            return Ok(signatures);
+        }
+
+        // errors are not cached, so the next call will try again
+        let measure = Measure::start();
+        let signatures = self.parse_signatures()?;
+        let stats = measure.finish(0);
+        self.record_perf(|perf| perf.signatures = Some(stats));
+
+        Ok(self.signatures.get_or_init(|| signatures))
+    }
+
+    /// Returns the certificate the apk is currently signed with: the first certificate
+    /// of the newest scheme (v3.1, v3, v2, v1), which follows key rotation.
+    ///
+    /// Returns [None] if the apk is unsigned or the signatures can't be parsed.
+    pub fn get_signer_certificate(&self) -> Option<&CertificateInfo> {
+        self.get_signatures_ref()
+            .ok()?
+            .iter()
+            .filter_map(|signature| match signature {
+                Signature::V31 { certificates, .. } => Some((0, certificates)),
+                Signature::V3 { certificates, .. } => Some((1, certificates)),
+                Signature::V2 { certificates, .. } => Some((2, certificates)),
+                Signature::V1(certificates) => Some((3, certificates)),
+                _ => None,
+            })
+            .filter_map(|(priority, certificates)| Some((priority, certificates.first()?)))
+            .min_by_key(|(priority, _)| *priority)
+            .map(|(_, certificate)| certificate)
+    }
+
+    /// Returns the raw ID-value pairs of the APK Signing Block,
+    /// see [ZipEntry::get_signing_block_entries].
+    pub fn get_signing_block_entries(&self) -> Vec<(u32, &[u8])> {
+        self.zip.get_signing_block_entries()
+    }
+
+    /// Parses `META-INF/MANIFEST.MF` of the v1 signature.
+    ///
+    /// Returns [None] if the apk has no manifest.
+    pub fn get_jar_manifest(&self) -> Option<JarManifest> {
+        self.zip.get_jar_manifest()
+    }
+
+    /// Verifies the digests of the v1 signature, see [ZipEntry::verify_signature_v1].
+    ///
+    /// Android 7.0+ ignores v1 when the apk has a v2+ signature, so the entries modified
+    /// after signing are only relevant for older devices and for the apks signed with v1 only.
+    ///
+    /// ```ignore
+    /// if let Some(verification) = apk.verify_signature_v1() {
+    ///     for name in &verification.modified {
+    ///         println!("{name} was modified after signing");
+    ///     }
+    /// }
+    /// ```
+    pub fn verify_signature_v1(&self) -> Option<V1Verification> {
+        self.zip.verify_signature_v1()
+    }
+
+    /// Same as [Apk::verify_signature_v1], the progress is reported per entry of
+    /// `MANIFEST.MF`, the check stops with [APKError::Cancelled] as soon as `cancel` is triggered.
+    #[cfg(feature = "experimental")]
+    pub fn verify_signature_v1_with_progress(
+        &self,
+        progress: Option<&dyn ProgressSink>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Option<V1Verification>, APKError> {
+        let verification = self.zip.verify_signature_v1_with(|current, total| {
+            if let Some(progress) = progress {
+                progress.report(Stage::Signatures, current as u64, total as u64);
+            }
+
+            !cancel.is_some_and(CancellationToken::is_cancelled)
+        });
+
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            return Err(APKError::Cancelled);
+        }
+
+        Ok(verification)
+    }
+
+    /// Checks the content digests of the v2, v3 and v3.1 signers against the apk,
+    /// see [ZipEntry::verify_content_digests].
+    ///
+    /// ```ignore
+    /// let modified = apk
+    ///     .verify_content_digests()?
+    ///     .iter()
+    ///     .any(|check| check.is_valid() == Some(false));
+    /// ```
+    pub fn verify_content_digests(&self) -> Result<Vec<DigestCheck>, APKError> {
+        Ok(self.zip.verify_content_digests()?)
+    }
+
+    /// Checks that the local headers match the central directory and the data matches
+    /// the declared CRC-32, see [ZipEntry::verify_structure].
+    #[inline]
+    pub fn verify_structure(&self) -> Vec<StructureIssue> {
+        self.zip.verify_structure()
+    }
+
+    fn parse_signatures(&self) -> Result<Vec<Signature>, APKError> {
         let mut signatures = Vec::new();
         if let Ok(v1_sig) = self.zip.get_signature_v1() {
             signatures.push(v1_sig);
@@ -778,7 +2344,11 @@ impl Apk {
     pub fn get_native_codes(&self) -> Vec<String> {
         let mut native_codes_set = HashSet::new();
 
-        for filename in self.zip.namelist() {
+        for filename in self
+            .zip
+            .namelist()
+            .filter_map(|name| self.module_path(name))
+        {
             if let Some(rest) = filename.strip_prefix("lib/")
                 && let Some((abi, lib)) = rest.split_once('/')
                 && lib.ends_with(".so")
@@ -792,4 +2362,1560 @@ impl Apk {
         native_codes.sort();
         native_codes
     }
+
+    /// Parses the native libraries (`lib/<abi>/*.so`) and groups them by ABI,
+    /// see [native](crate::native).
+    ///
+    /// Not to be confused with [Apk::get_native_libraries], that returns libraries
+    /// of the system declared in the manifest.
+    ///
+    /// ```ignore
+    /// for abi in apk.get_native_libraries_by_abi() {
+    ///     for library in &abi.libraries {
+    ///         if library.elf.as_ref().is_some_and(|elf| elf.has_jni_onload) {
+    ///             println!("{} has JNI_OnLoad", library.path);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn get_native_libraries_by_abi(&self) -> Vec<NativeAbi> {
+        let mut abis: BTreeMap<&str, Vec<NativeLibrary>> = BTreeMap::new();
+
+        for name in self.zip.namelist() {
+            let Some(path) = self.module_path(name) else {
+                continue;
+            };
+            let Some((abi, library)) = path
+                .strip_prefix("lib/")
+                .and_then(|rest| rest.split_once('/'))
+            else {
+                continue;
+            };
+            if abi.is_empty() || !library.ends_with(".so") {
+                continue;
+            }
+
+            let (data, _) = match self.zip.read_borrowed(name) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("can't read {name}, skipped: {e}");
+                    continue;
+                }
+            };
+
+            abis.entry(abi).or_default().push(NativeLibrary {
+                path: path.to_string(),
+                size: data.len() as u64,
+                elf: native::parse_elf(&data),
+            });
+        }
+
+        abis.into_iter()
+            .map(|(abi, mut libraries)| {
+                libraries.sort_by(|a, b| a.path.cmp(&b.path));
+                NativeAbi {
+                    abi: abi.to_string(),
+                    libraries,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the ABIs of the native libraries together with the manifest attributes
+    /// that affect how they are installed.
+    ///
+    /// ```ignore
+    /// let abis = apk.get_supported_abis();
+    /// if !abis.supports_64bit() {
+    ///     println!("32-bit only: {:?}", abis.abis);
+    /// }
+    /// ```
+    pub fn get_supported_abis(&self) -> SupportedAbis {
+        SupportedAbis {
+            abis: self.get_native_codes(),
+            // default value is "true"
+            extract_native_libs: self
+                .get_attribute_value("application", "extractNativeLibs")
+                .is_none_or(|v| v != "false"),
+            multi_arch: self
+                .get_attribute_value("application", "multiArch")
+                .is_some_and(|v| v == "true"),
+        }
+    }
+
+    /// Estimates install and download size of the apk, similar to Play Console.
+    ///
+    /// Native libraries are accounted only for the largest ABI, since the device
+    /// installs only one of them.
+    ///
+    /// ```ignore
+    /// let size = apk.estimated_install_size();
+    /// println!("download: {}, install: {}", size.download_size, size.install_size);
+    /// ```
+    pub fn estimated_install_size(&self) -> SizeEstimate {
+        let mut estimate = SizeEstimate {
+            // default value is "true"
+            extract_native_libs: self
+                .get_attribute_value("application", "extractNativeLibs")
+                .is_none_or(|v| v != "false"),
+            ..Default::default()
+        };
+
+        let mut compressed_dex_size = 0;
+        let mut abi_sizes: HashMap<&str, u64> = HashMap::new();
+
+        for filename in self.zip.namelist() {
+            let Some((compressed, uncompressed)) = self.zip.get_file_sizes(filename) else {
+                continue;
+            };
+
+            estimate.download_size += compressed;
+
+            if filename.starts_with("classes") && filename.ends_with(".dex") {
+                estimate.dex_size += uncompressed;
+                if compressed != uncompressed {
+                    compressed_dex_size += uncompressed;
+                }
+            } else if let Some(rest) = filename.strip_prefix("lib/")
+                && let Some((abi, lib)) = rest.split_once('/')
+                && lib.ends_with(".so")
+            {
+                *abi_sizes.entry(abi).or_default() += uncompressed;
+            } else if filename == RESOURCE_TABLE_PATH
+                || filename.starts_with("res/")
+                || filename.starts_with("assets/")
+            {
+                estimate.resources_size += uncompressed;
+            }
+        }
+
+        // the archive is downloaded with its local headers and central directory
+        estimate.download_size += self.zip.get_headers_size();
+
+        estimate.native_libs_size = abi_sizes.into_values().max().unwrap_or_default();
+        estimate.install_size = estimate.download_size + compressed_dex_size;
+        if estimate.extract_native_libs {
+            estimate.install_size += estimate.native_libs_size;
+        }
+
+        estimate
+    }
+
+    /// Aggregates code statistics of all `classes*.dex` files by package prefix.
+    ///
+    /// Classes are grouped by the first two segments of the package (`com.google.*`,
+    /// `a.b.*`), so third-party libraries and obfuscated code stand out. The result is
+    /// sorted by the number of classes, dex files that can't be parsed are skipped.
+    ///
+    /// ```ignore
+    /// for profile in apk.package_profile(None, None)? {
+    ///     println!("{}: {} classes, {:.1}", profile.prefix, profile.classes, profile.average_identifier_length);
+    /// }
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn package_profile(
+        &self,
+        progress: Option<&(dyn ProgressSink + Sync)>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Vec<PackageProfile>, APKError> {
+        #[derive(Default)]
+        struct Counter {
+            profile: PackageProfile,
+            identifiers: usize,
+            identifiers_length: usize,
+        }
+
+        impl Counter {
+            fn add_identifier(&mut self, name: &str) {
+                self.identifiers += 1;
+                self.identifiers_length += name.chars().count();
+            }
+
+            fn merge(&mut self, other: Counter) {
+                self.profile.classes += other.profile.classes;
+                self.profile.methods += other.profile.methods;
+                self.profile.fields += other.profile.fields;
+                self.identifiers += other.identifiers;
+                self.identifiers_length += other.identifiers_length;
+            }
+        }
+
+        let per_dex = self.scan_dex(
+            progress,
+            cancel,
+            |name, dex| -> Result<HashMap<String, Counter>, APKError> {
+                let mut groups: HashMap<String, Counter> = HashMap::new();
+
+                for class in dex.classes() {
+                    let Some(class_name) = dex.get_class_name(class.class_idx) else {
+                        continue;
+                    };
+
+                    let (package, simple_name) =
+                        class_name.rsplit_once('.').unwrap_or(("", &class_name));
+                    let prefix = package.splitn(3, '.').take(2).collect::<Vec<_>>().join(".");
+
+                    let counter = groups.entry(prefix).or_default();
+                    counter.profile.classes += 1;
+
+                    // inner classes: Outer$Inner -> Inner
+                    counter.add_identifier(simple_name.rsplit('$').next().unwrap_or(simple_name));
+
+                    let class_data = match dex.get_class_data(class) {
+                        Ok(Some(class_data)) => class_data,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            warn!("{name}: skipped class data of {class_name}: {e}");
+                            continue;
+                        }
+                    };
+
+                    for field in class_data.fields() {
+                        counter.profile.fields += 1;
+                        if let Some(field_name) = dex.get_field_name(field.field_idx) {
+                            counter.add_identifier(&field_name);
+                        }
+                    }
+
+                    for method in class_data.methods() {
+                        counter.profile.methods += 1;
+                        if let Some(method_name) = dex.get_method_name(method.method_idx)
+                            && !method_name.starts_with('<')
+                        {
+                            counter.add_identifier(&method_name);
+                        }
+                    }
+                }
+
+                Ok(groups)
+            },
+        )?;
+
+        let mut groups: HashMap<String, Counter> = HashMap::new();
+        for dex_groups in per_dex {
+            for (prefix, counter) in dex_groups {
+                groups.entry(prefix).or_default().merge(counter);
+            }
+        }
+
+        let mut profiles: Vec<PackageProfile> = groups
+            .into_iter()
+            .map(|(prefix, counter)| PackageProfile {
+                prefix,
+                average_identifier_length: if counter.identifiers == 0 {
+                    0.0
+                } else {
+                    counter.identifiers_length as f64 / counter.identifiers as f64
+                },
+                ..counter.profile
+            })
+            .collect();
+
+        profiles.sort_by(|a, b| {
+            b.classes
+                .cmp(&a.classes)
+                .then_with(|| a.prefix.cmp(&b.prefix))
+        });
+
+        Ok(profiles)
+    }
+
+    /// Reports references to restricted (non-SDK) apis from all `classes*.dex` files.
+    ///
+    /// Only direct method and field references are found, apis accessed through
+    /// reflection are visible only as strings. The result is sorted by signature,
+    /// dex files that can't be parsed are skipped.
+    ///
+    /// ```ignore
+    /// let database = HiddenApiDatabase::load("./hiddenapi-flags.csv")?;
+    /// for usage in apk.restricted_api_usage(&database)? {
+    ///     println!("{} ({})", usage.signature, usage.restriction);
+    /// }
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn restricted_api_usage(
+        &self,
+        database: &HiddenApiDatabase,
+    ) -> Result<Vec<RestrictedApiUsage>, APKError> {
+        let per_dex = self.try_map_dex(|name, dex| -> Result<_, APKError> {
+            let methods = (0..dex.method_ids().len() as u32)
+                .filter_map(|idx| Some(("method", dex.get_method_signature(idx)?)));
+            let fields = (0..dex.field_ids().len() as u32)
+                .filter_map(|idx| Some(("field", dex.get_field_signature(idx)?)));
+
+            let restricted: Vec<(&'static str, String, String)> = methods
+                .chain(fields)
+                .filter_map(|(kind, signature)| {
+                    let restriction = database.get(&signature).filter(|r| r.is_restricted())?;
+                    Some((kind, signature, restriction.to_string()))
+                })
+                .collect();
+
+            Ok((name.to_string(), restricted))
+        });
+
+        let mut usages: BTreeMap<String, RestrictedApiUsage> = BTreeMap::new();
+        for (name, restricted) in per_dex {
+            for (kind, signature, restriction) in restricted {
+                usages
+                    .entry(signature)
+                    .or_insert_with_key(|signature| RestrictedApiUsage {
+                        signature: signature.clone(),
+                        kind,
+                        restriction,
+                        dex_files: Vec::new(),
+                    })
+                    .dex_files
+                    .push(name.clone());
+            }
+        }
+
+        Ok(usages.into_values().collect())
+    }
+
+    /// Maps framework apis called from all `classes*.dex` files to capabilities and
+    /// the permissions they require, see [analysis](crate::analysis).
+    ///
+    /// ```ignore
+    /// let report = apk.get_capability_report()?;
+    /// if report.has(Capability::Sms) {
+    ///     println!("sends or intercepts sms");
+    /// }
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn get_capability_report(&self) -> Result<CapabilityReport, APKError> {
+        analysis::capability_report(self)
+    }
+
+    /// Detects frameworks and sdks the app is built with: Flutter, React Native, Unity, etc.
+    /// See [detection](crate::detection).
+    ///
+    /// Dex files that can't be parsed are skipped, only files of the apk are checked for them.
+    ///
+    /// ```ignore
+    /// for hit in apk.detect_frameworks() {
+    ///     println!("{} {:?}", hit.framework, hit.version);
+    /// }
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn detect_frameworks(&self) -> Vec<FrameworkHit> {
+        detection::detect_frameworks(self)
+    }
+
+    /// Detects commercial packers and protectors: Jiagu, Bangcle, Tencent Legu, etc.
+    /// See [detection](crate::detection).
+    ///
+    /// Hits are sorted by confidence, the most likely packer first.
+    ///
+    /// ```ignore
+    /// if let Some(hit) = apk.detect_packers().first() {
+    ///     println!("packed with {} ({}%)", hit.packer, hit.confidence);
+    /// }
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn detect_packers(&self) -> Vec<PackerHit> {
+        detection::detect_packers(self)
+    }
+
+    /// Finds strings matching the regular expression in all `classes*.dex` files
+    /// and the methods that load them.
+    ///
+    /// The result is in the order of dex files and strings, dex files that can't be parsed
+    /// are skipped.
+    ///
+    /// ```ignore
+    /// for usage in apk.find_string_usages("https?://", None, None)? {
+    ///     println!("{} <- {:?}", usage.string, usage.methods);
+    /// }
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn find_string_usages(
+        &self,
+        pattern: &str,
+        progress: Option<&(dyn ProgressSink + Sync)>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Vec<StringUsage>, APKError> {
+        let regex = Regex::new(pattern)?;
+
+        let per_dex = self.scan_dex(
+            progress,
+            cancel,
+            |name, dex| -> Result<Vec<StringUsage>, APKError> {
+                let mut usages = Vec::new();
+
+                let matched: Vec<(u32, String)> = (0..dex.strings_count() as u32)
+                    .filter_map(|idx| Some((idx, dex.get_string(idx)?)))
+                    .filter(|(_, string)| regex.is_match(string))
+                    .collect();
+
+                if matched.is_empty() {
+                    return Ok(usages);
+                }
+
+                let mut references =
+                    dex.string_references()
+                        .map_err(|source| APKError::DexError {
+                            entry: name.to_string(),
+                            source,
+                        })?;
+
+                for (idx, string) in matched {
+                    let methods = references
+                        .remove(&idx)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter_map(|method_idx| dex.get_method_signature(method_idx))
+                        .collect();
+
+                    usages.push(StringUsage {
+                        string,
+                        dex_file: name.to_string(),
+                        methods,
+                    });
+                }
+
+                Ok(usages)
+            },
+        )?;
+
+        Ok(per_dex.into_iter().flatten().collect())
+    }
+
+    /// Returns files of the archive that nothing points to: they are not resources from
+    /// `resources.arsc`, not mentioned in the manifest and not path literals in the dex strings.
+    ///
+    /// Such files are read only by code that builds the path at runtime (decrypted
+    /// strings, names from the server), which is typical for second-stage payloads
+    /// hidden in `assets/` and `res/raw/`.
+    ///
+    /// Paths are matched leniently to avoid noise: a file is referenced by its full path,
+    /// by the path inside `assets/` or just by the file name. The manifest, resource table,
+    /// dex files and `META-INF/` are never reported, native libraries are referenced by
+    /// the name passed to `System.loadLibrary`.
+    ///
+    /// Dex files that can't be parsed are skipped with a warning, files referenced only
+    /// from them are reported as unreferenced.
+    pub fn get_unreferenced_files(&self) -> Result<Vec<&str>, APKError> {
+        let mut references: HashSet<String> = HashSet::new();
+        let mut add_reference = |value: &str| {
+            for key in reference_keys(value) {
+                if !references.contains(key) {
+                    references.insert(key.to_string());
+                }
+            }
+        };
+
+        if let Some(arsc) = &self.arsc {
+            arsc.strings().for_each(&mut add_reference);
+        }
+
+        for element in std::iter::once(&self.axml.root).chain(self.axml.root.descendants()) {
+            element
+                .attributes()
+                .for_each(|attribute| add_reference(attribute.value()));
+        }
+
+        let per_dex = self.try_map_dex(|_, dex| -> Result<HashSet<String>, APKError> {
+            let mut references = HashSet::new();
+            for string in dex.strings() {
+                references.extend(reference_keys(&string).map(String::from));
+            }
+
+            Ok(references)
+        });
+        references.extend(per_dex.into_iter().flatten());
+
+        let dex_names = self.dex_names();
+
+        let mut files: Vec<&str> = self
+            .namelist()
+            .filter(|name| {
+                !name.ends_with('/')
+                    && *name != ANDROID_MANIFEST_PATH
+                    && *name != RESOURCE_TABLE_PATH
+                    && !name.starts_with("META-INF/")
+                    && !dex_names.contains(name)
+            })
+            .filter(|name| {
+                !reference_keys(name)
+                    .chain(native_library_name(name))
+                    .any(|key| references.contains(key))
+            })
+            .collect();
+        files.sort_unstable();
+
+        Ok(files)
+    }
+
+    /// Extracts files from the apk into the `output` directory.
+    ///
+    /// Only files accepted by `filter` are extracted. Entry names are turned into safe
+    /// relative paths with [normalize_entry_name](apk_info_zip::normalize_entry_name),
+    /// renames are logged. Returns the number of extracted files.
+    ///
+    /// Progress is reported per file to `progress`, the operation stops with
+    /// [APKError::Cancelled] as soon as `cancel` is triggered.
+    ///
+    /// ```ignore
+    /// let count = apk.extract_to("./out", |name| name.ends_with(".dex"), None, None)?;
+    /// ```
+    #[cfg(feature = "experimental")]
+    pub fn extract_to<P, F>(
+        &self,
+        output: P,
+        filter: F,
+        progress: Option<&dyn ProgressSink>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<usize, APKError>
+    where
+        P: AsRef<Path>,
+        F: Fn(&str) -> bool,
+    {
+        let output = output.as_ref();
+
+        let mut names: Vec<&str> = self.zip.namelist().filter(|name| filter(name)).collect();
+        names.sort_unstable();
+        let files = extract_paths(names);
+
+        let total = files.len() as u64;
+        for (i, file) in files.iter().enumerate() {
+            if cancel.is_some_and(|token| token.is_cancelled()) {
+                return Err(APKError::Cancelled);
+            }
+
+            if file.is_renamed() {
+                warn!("{:?} is extracted as {:?}", file.name, file.path);
+            }
+
+            let (data, _) = self.zip.read_borrowed(&file.name)?;
+            let path = output.join(&file.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, data)?;
+
+            if let Some(progress) = progress {
+                progress.report(Stage::Extract, i as u64 + 1, total);
+            }
+        }
+
+        Ok(files.len())
+    }
+}
+
+/// Checks that the name looks like `classes.dex`, `classes2.dex`, etc.
+fn is_dex_name(name: &str) -> bool {
+    // don't use regexes, i think it's overengineering for this task
+    if !name.starts_with("classes") || !name.ends_with(".dex") {
+        return false;
+    }
+
+    let middle = &name["classes".len()..name.len() - ".dex".len()];
+
+    middle.is_empty() || middle.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Forms of the path that code or resources may use to point to the file:
+/// the path itself, the path inside `assets/` and the file name.
+fn reference_keys(value: &str) -> impl Iterator<Item = &str> {
+    let value = value.trim();
+    let asset = ["file:///android_asset/", "/android_asset/", "assets/"]
+        .iter()
+        .find_map(|prefix| value.strip_prefix(prefix));
+    let file_name = value.rsplit('/').next();
+
+    [Some(value), asset, file_name]
+        .into_iter()
+        .flatten()
+        .filter(|key| !key.is_empty())
+}
+
+/// Name for `System.loadLibrary`: `lib/arm64-v8a/libfoo.so` -> `foo`.
+fn native_library_name(name: &str) -> Option<&str> {
+    name.strip_prefix("lib/")?
+        .rsplit('/')
+        .next()?
+        .strip_prefix("lib")?
+        .strip_suffix(".so")
+}
+
+/// Integer attribute as the manifest stores it: decimal or `0x`-prefixed hex.
+pub(crate) fn parse_resource_int(value: &str) -> Option<i64> {
+    let value = value.trim();
+
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Boolean attribute: `true`/`false` in any case or `1`/`0`.
+fn parse_resource_bool(value: &str) -> Option<bool> {
+    let value = value.trim();
+
+    if value.eq_ignore_ascii_case("true") || value == "1" {
+        Some(true)
+    } else if value.eq_ignore_ascii_case("false") || value == "0" {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Deduplicated strings shared between the threads, each distinct value is stored once.
+#[derive(Debug, Default)]
+struct Interner {
+    strings: Mutex<HashSet<Arc<str>>>,
+}
+
+impl Interner {
+    fn intern(&self, value: String) -> Arc<str> {
+        let mut strings = self.strings.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(interned) = strings.get(value.as_str()) {
+            return Arc::clone(interned);
+        }
+
+        let interned: Arc<str> = value.into();
+        strings.insert(Arc::clone(&interned));
+        interned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn test_mmap_matches_owned() {
+        let manifest = el(
+            "manifest",
+            &[("package", s("com.example.mmap"))],
+            vec![el(
+                "application",
+                &[("android:label", s("Mmap"))],
+                vec![el(
+                    "activity",
+                    &[("android:name", s("com.example.mmap.MainActivity"))],
+                    vec![],
+                )],
+            )],
+        );
+        let data = build_zip(
+            &[
+                ZipFile::deflated(
+                    "AndroidManifest.xml",
+                    build_axml(&manifest, AxmlOptions::default()),
+                ),
+                ZipFile::stored(
+                    "classes.dex",
+                    build_dex(&["Lcom/example/mmap/MainActivity;"]),
+                ),
+                ZipFile::deflated("assets/data.bin", vec![7; 4096]),
+            ],
+            ZipOptions::default(),
+        );
+
+        let path = std::env::temp_dir().join(format!("apk-info-mmap-{}.apk", std::process::id()));
+        std::fs::write(&path, &data).unwrap();
+
+        let owned = Apk::new(&path).unwrap();
+        // SAFETY: the file is private to the test and isn't modified until both are dropped
+        let mapped = unsafe { Apk::open_mmap(&path) }.unwrap();
+        let (owned_report, mapped_report) = (report(&owned), report(&mapped));
+        drop((owned, mapped));
+        let _ = std::fs::remove_file(&path);
+
+        assert!(mapped_report.contains("com.example.mmap.MainActivity"));
+        assert_eq!(owned_report, mapped_report);
+    }
+
+    fn multidex_apk() -> Apk {
+        let manifest = el(
+            "manifest",
+            &[("package", s("com.example.multidex"))],
+            vec![],
+        );
+        let mut files = vec![ZipFile::deflated(
+            "AndroidManifest.xml",
+            build_axml(&manifest, AxmlOptions::default()),
+        )];
+        #[cfg(feature = "parallel")]
+        let threads = rayon::current_num_threads();
+        #[cfg(not(feature = "parallel"))]
+        let threads = 1;
+
+        // more files than threads, so there are several chunks
+        for i in 1..=threads * 2 + 1 {
+            let name = if i == 1 {
+                "classes.dex".to_string()
+            } else {
+                format!("classes{i}.dex")
+            };
+            let class = format!("com.example.C{i}");
+            files.push(ZipFile::stored(
+                &name,
+                build_dex_with_methods(
+                    &[&class],
+                    &[
+                        (&class, "<init>", "()V"),
+                        (&class, "run", "(Ljava/lang/String;I)Z"),
+                        ("android.app.Activity", "finish", "()V"),
+                    ],
+                ),
+            ));
+        }
+
+        Apk::from_bytes(build_zip(&files, ZipOptions::default())).unwrap()
+    }
+
+    #[test]
+    fn test_dex_items_order() {
+        let apk = multidex_apk();
+        let serial = |f: fn(&Dex) -> Vec<String>| -> Vec<String> {
+            apk.dex_names()
+                .into_iter()
+                .flat_map(|name| f(&apk.read_dex(name).unwrap()))
+                .collect()
+        };
+
+        let methods = serial(|dex| {
+            (0..dex.method_ids().len() as u32)
+                .filter_map(|idx| dex.get_method_signature(idx))
+                .collect()
+        });
+        assert_eq!(methods.len(), apk.dex_names().len() * 3);
+        assert_eq!(
+            methods[..3],
+            [
+                "Lcom/example/C1;-><init>()V",
+                "Lcom/example/C1;->run(Ljava/lang/String;I)Z",
+                "Landroid/app/Activity;->finish()V",
+            ]
+        );
+        assert_eq!(apk.get_dex_methods().collect::<Vec<_>>(), methods);
+
+        assert_eq!(
+            apk.get_dex_strings().collect::<Vec<_>>(),
+            serial(|dex| dex.strings().collect())
+        );
+        assert_eq!(
+            apk.get_dex_classes().collect::<Vec<_>>(),
+            serial(|dex| {
+                dex.classes()
+                    .iter()
+                    .filter_map(|class| dex.get_class_name(class.class_idx))
+                    .collect()
+            })
+        );
+    }
+
+    #[test]
+    fn test_interner() {
+        let interner = Interner::default();
+        let a = interner.intern("Ljava/lang/String;".to_string());
+        let b = interner.intern("Ljava/lang/String;".to_string());
+        let c = interner.intern("I".to_string());
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(&*c, "I");
+    }
+
+    fn sdk_manifest(min_sdk: u32, target_sdk: u32, application: Vec<Node>) -> Node {
+        el(
+            "manifest",
+            &[("package", s("com.example.tests"))],
+            vec![
+                el(
+                    "uses-sdk",
+                    &[
+                        ("android:minSdkVersion", Value::Int(min_sdk)),
+                        ("android:targetSdkVersion", Value::Int(target_sdk)),
+                    ],
+                    vec![],
+                ),
+                el("application", &[], application),
+            ],
+        )
+    }
+
+    fn action_filter(action: &str) -> Node {
+        el(
+            "intent-filter",
+            &[],
+            vec![el("action", &[("android:name", s(action))], vec![])],
+        )
+    }
+
+    fn exported(apk: &Apk) -> Vec<(ComponentKind, &str, ExportReason, bool)> {
+        apk.get_exported_components()
+            .into_iter()
+            .map(|c| (c.kind, c.name.unwrap_or_default(), c.reason, c.enabled))
+            .collect()
+    }
+
+    #[test]
+    fn test_exported_explicit() {
+        let manifest = sdk_manifest(
+            21,
+            34,
+            vec![
+                el(
+                    "activity",
+                    &[
+                        ("android:name", s("Open")),
+                        ("android:exported", Value::Bool(true)),
+                    ],
+                    vec![],
+                ),
+                // intent filters don't export the component when it's declared as private
+                el(
+                    "activity",
+                    &[
+                        ("android:name", s("Private")),
+                        ("android:exported", Value::Bool(false)),
+                    ],
+                    vec![action_filter("com.example.OPEN")],
+                ),
+                el(
+                    "service",
+                    &[
+                        ("android:name", s("Sync")),
+                        ("android:exported", Value::Bool(true)),
+                        ("android:permission", s("com.example.SYNC")),
+                    ],
+                    vec![],
+                ),
+            ],
+        );
+        let apk = apk_from_manifest(&manifest, None);
+
+        assert_eq!(
+            exported(&apk),
+            [
+                (
+                    ComponentKind::Activity,
+                    "Open",
+                    ExportReason::Explicit,
+                    true
+                ),
+                (ComponentKind::Service, "Sync", ExportReason::Explicit, true),
+            ]
+        );
+        let components = apk.get_exported_components();
+        assert!(!components[0].is_protected());
+        assert_eq!(components[1].permission, Some("com.example.SYNC"));
+    }
+
+    #[test]
+    fn test_exported_intent_filters() {
+        const FEATURE_OFF: u32 = 0x7f010000;
+
+        let manifest = sdk_manifest(
+            21,
+            30,
+            vec![
+                el(
+                    "receiver",
+                    &[("android:name", s("Boot"))],
+                    vec![action_filter("android.intent.action.BOOT_COMPLETED")],
+                ),
+                el("service", &[("android:name", s("Internal"))], vec![]),
+                // the enabled flags of all kinds go through the same resolution of references
+                el(
+                    "service",
+                    &[
+                        ("android:name", s("Feature")),
+                        ("android:enabled", Value::Typed(0x01, FEATURE_OFF)),
+                    ],
+                    vec![action_filter("com.example.FEATURE")],
+                ),
+                el(
+                    "activity-alias",
+                    &[
+                        ("android:name", s("Alias")),
+                        ("android:targetActivity", s("Main")),
+                        ("android:enabled", Value::Bool(false)),
+                    ],
+                    vec![action_filter("android.intent.action.VIEW")],
+                ),
+            ],
+        );
+        let resources = build_arsc(
+            &[ResType {
+                name: "bool",
+                keys: vec!["feature"],
+                values: vec![Some("false".to_string())],
+            }],
+            ArscOptions::default(),
+        );
+        let apk = apk_from_manifest(&manifest, Some(resources));
+
+        assert_eq!(
+            exported(&apk),
+            [
+                (
+                    ComponentKind::ActivityAlias,
+                    "Alias",
+                    ExportReason::IntentFilters,
+                    false
+                ),
+                (
+                    ComponentKind::Service,
+                    "Feature",
+                    ExportReason::IntentFilters,
+                    false
+                ),
+                (
+                    ComponentKind::Receiver,
+                    "Boot",
+                    ExportReason::IntentFilters,
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exported_legacy_providers() {
+        let providers = || {
+            vec![
+                el(
+                    "provider",
+                    &[
+                        ("android:name", s("Default")),
+                        ("android:authorities", s("com.example.default")),
+                    ],
+                    vec![],
+                ),
+                el(
+                    "provider",
+                    &[
+                        ("android:name", s("Private")),
+                        ("android:authorities", s("com.example.private")),
+                        ("android:exported", Value::Bool(false)),
+                    ],
+                    vec![],
+                ),
+            ]
+        };
+        let legacy = [(
+            ComponentKind::Provider,
+            "Default",
+            ExportReason::LegacyProviderDefault,
+            true,
+        )];
+
+        // targetSdkVersion 16 or lower
+        let apk = apk_from_manifest(&sdk_manifest(14, 16, providers()), None);
+        assert_eq!(exported(&apk), legacy);
+
+        // minSdkVersion 16 or lower, the app still runs on the old releases
+        let apk = apk_from_manifest(&sdk_manifest(16, 30, providers()), None);
+        assert_eq!(exported(&apk), legacy);
+
+        let apk = apk_from_manifest(&sdk_manifest(17, 30, providers()), None);
+        assert!(exported(&apk).is_empty());
+    }
+
+    #[test]
+    fn test_parse_resource_int() {
+        assert_eq!(parse_resource_int("42"), Some(42));
+        assert_eq!(parse_resource_int(" 0x1F "), Some(31));
+        assert_eq!(parse_resource_int("0XfF"), Some(255));
+        assert_eq!(parse_resource_int("-5"), Some(-5));
+        assert_eq!(parse_resource_int("0x"), None);
+        assert_eq!(parse_resource_int("Tiramisu"), None);
+        assert_eq!(parse_resource_int("@integer/version"), None);
+    }
+
+    #[test]
+    fn test_parse_resource_bool() {
+        assert_eq!(parse_resource_bool("TRUE"), Some(true));
+        assert_eq!(parse_resource_bool("1"), Some(true));
+        assert_eq!(parse_resource_bool("False"), Some(false));
+        assert_eq!(parse_resource_bool(" 0 "), Some(false));
+        assert_eq!(parse_resource_bool("yes"), None);
+        assert_eq!(parse_resource_bool("@bool/enabled"), None);
+    }
+
+    #[test]
+    fn test_version_code_u64() {
+        const VERSION: u32 = 0x7f010000;
+        const MISSING: u32 = 0x7f0100ff;
+
+        let version_code = |attrs: &[(&str, Value)]| {
+            let mut manifest_attrs = vec![("package", s("com.example.tests"))];
+            manifest_attrs.extend_from_slice(attrs);
+            let resources = build_arsc(
+                &[ResType {
+                    name: "integer",
+                    keys: vec!["version"],
+                    values: vec![Some("123".to_string())],
+                }],
+                ArscOptions::default(),
+            );
+            apk_from_manifest(&el("manifest", &manifest_attrs, vec![]), Some(resources))
+                .get_version_code_u64()
+        };
+
+        assert_eq!(
+            version_code(&[("android:versionCode", Value::Int(7))]),
+            Some(7)
+        );
+        // TYPE_INT_HEX
+        assert_eq!(
+            version_code(&[("android:versionCode", Value::Typed(0x11, 0x10))]),
+            Some(16)
+        );
+        assert_eq!(
+            version_code(&[("android:versionCode", s("0x20"))]),
+            Some(32)
+        );
+        // decimal values are signed, codes above i32::MAX are negative in the manifest
+        assert_eq!(
+            version_code(&[("android:versionCode", Value::Int(0xfffffffe))]),
+            Some(0xfffffffe)
+        );
+        assert_eq!(
+            version_code(&[
+                ("android:versionCode", Value::Int(5)),
+                ("android:versionCodeMajor", Value::Int(1)),
+            ]),
+            Some((1 << 32) | 5)
+        );
+        assert_eq!(
+            version_code(&[("android:versionCode", Value::Typed(0x01, VERSION))]),
+            Some(123)
+        );
+        assert_eq!(
+            version_code(&[("android:versionCode", Value::Typed(0x01, MISSING))]),
+            None
+        );
+        assert_eq!(version_code(&[]), None);
+    }
+
+    fn launcher(tag: &str, attrs: &[(&str, Value)]) -> Node {
+        el(
+            tag,
+            attrs,
+            vec![el(
+                "intent-filter",
+                &[],
+                vec![
+                    el(
+                        "action",
+                        &[("android:name", s("android.intent.action.MAIN"))],
+                        vec![],
+                    ),
+                    el(
+                        "category",
+                        &[("android:name", s("android.intent.category.LAUNCHER"))],
+                        vec![],
+                    ),
+                ],
+            )],
+        )
+    }
+
+    fn main_activities(application: Vec<Node>) -> Vec<String> {
+        let apk = apk_from_manifest(&sdk_manifest(21, 34, application), None);
+        apk.get_main_activities().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_main_activity_alias_target() {
+        let activity = |name: &str, enabled: bool| {
+            el(
+                "activity",
+                &[
+                    ("android:name", s(name)),
+                    ("android:enabled", Value::Bool(enabled)),
+                ],
+                vec![],
+            )
+        };
+
+        // relative target, the activity itself isn't a launcher
+        assert_eq!(
+            main_activities(vec![
+                activity("com.example.tests.Main", true),
+                launcher(
+                    "activity-alias",
+                    &[
+                        ("android:name", s("com.example.tests.Launcher")),
+                        ("android:targetActivity", s(".Main")),
+                    ],
+                ),
+            ]),
+            ["com.example.tests.Main"]
+        );
+
+        // the alias and its target are returned once
+        assert_eq!(
+            main_activities(vec![
+                launcher("activity", &[("android:name", s("com.example.tests.Main"))]),
+                launcher(
+                    "activity-alias",
+                    &[
+                        ("android:name", s("com.example.tests.Launcher")),
+                        ("android:targetActivity", s("com.example.tests.Main")),
+                    ],
+                ),
+            ]),
+            ["com.example.tests.Main"]
+        );
+
+        // the target is disabled
+        assert!(
+            main_activities(vec![
+                activity("com.example.tests.Main", false),
+                launcher(
+                    "activity-alias",
+                    &[
+                        ("android:name", s("com.example.tests.Launcher")),
+                        ("android:targetActivity", s("com.example.tests.Main")),
+                    ],
+                ),
+            ])
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_main_activity_disabled_alias() {
+        let alias = |name: &str, target: &str, enabled: bool| {
+            launcher(
+                "activity-alias",
+                &[
+                    ("android:name", s(name)),
+                    ("android:targetActivity", s(target)),
+                    ("android:enabled", Value::Bool(enabled)),
+                ],
+            )
+        };
+
+        // icon switching: only the enabled alias is shown in the launcher
+        assert_eq!(
+            main_activities(vec![
+                el(
+                    "activity",
+                    &[("android:name", s("com.example.tests.Dark"))],
+                    vec![]
+                ),
+                el(
+                    "activity",
+                    &[("android:name", s("com.example.tests.Light"))],
+                    vec![]
+                ),
+                alias(
+                    "com.example.tests.DarkIcon",
+                    "com.example.tests.Dark",
+                    false
+                ),
+                alias(
+                    "com.example.tests.LightIcon",
+                    "com.example.tests.Light",
+                    true
+                ),
+            ]),
+            ["com.example.tests.Light"]
+        );
+    }
+
+    #[test]
+    fn test_main_activity_alias_without_target() {
+        assert!(
+            main_activities(vec![
+                el(
+                    "activity",
+                    &[("android:name", s("com.example.tests.Main"))],
+                    vec![]
+                ),
+                launcher(
+                    "activity-alias",
+                    &[("android:name", s("com.example.tests.NoTarget"))],
+                ),
+                // the system refuses aliases pointing to undeclared activities
+                launcher(
+                    "activity-alias",
+                    &[
+                        ("android:name", s("com.example.tests.Dangling")),
+                        ("android:targetActivity", s("com.example.tests.Missing")),
+                    ],
+                ),
+            ])
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_permissions_by_protection() {
+        let uses = |name: &str| el("uses-permission", &[("android:name", s(name))], vec![]);
+        let manifest = el(
+            "manifest",
+            &[("package", s("com.example.tests"))],
+            vec![
+                el(
+                    "permission",
+                    &[
+                        ("android:name", s("com.example.tests.C2D")),
+                        ("android:protectionLevel", s("signature")),
+                    ],
+                    vec![],
+                ),
+                uses("com.example.tests.C2D"),
+                uses("android.permission.READ_SMS"),
+                uses("android.permission.INTERNET"),
+                uses("android.permission.INTERNET"),
+                el(
+                    "uses-permission-sdk-23",
+                    &[("android:name", s("android.permission.CAMERA"))],
+                    vec![],
+                ),
+                uses("com.other.permission.READ"),
+            ],
+        );
+        let apk = apk_from_manifest(&manifest, None);
+        let permissions = apk.get_permissions_by_protection();
+
+        assert_eq!(
+            permissions.dangerous,
+            ["android.permission.CAMERA", "android.permission.READ_SMS"]
+        );
+        assert_eq!(permissions.normal, ["android.permission.INTERNET"]);
+        // declared by the apk, the level comes from its `<permission>`
+        assert_eq!(permissions.signature, ["com.example.tests.C2D"]);
+        assert_eq!(permissions.unknown, ["com.other.permission.READ"]);
+    }
+
+    #[test]
+    fn test_min_supported_screens() {
+        let min_screens = |children: Vec<Node>| {
+            let manifest = el("manifest", &[("package", s("com.example.tests"))], children);
+            apk_from_manifest(&manifest, None).get_min_supported_screens()
+        };
+        let supports = |flags: &[(&str, bool)]| {
+            let attrs: Vec<_> = flags
+                .iter()
+                .map(|(name, value)| (*name, Value::Bool(*value)))
+                .collect();
+            el("supports-screens", &attrs, vec![])
+        };
+        // values of the `screenSize` enum
+        let compatible = |sizes: &[u32]| {
+            let screens = sizes
+                .iter()
+                .map(|size| {
+                    el(
+                        "screen",
+                        &[("android:screenSize", Value::Int(*size))],
+                        vec![],
+                    )
+                })
+                .collect();
+            el("compatible-screens", &[], screens)
+        };
+
+        // small and normal screens are supported by default
+        assert_eq!(min_screens(vec![]), Some("small"));
+        assert_eq!(
+            min_screens(vec![supports(&[("android:largeScreens", true)])]),
+            Some("small")
+        );
+        assert_eq!(
+            min_screens(vec![supports(&[
+                ("android:smallScreens", false),
+                ("android:normalScreens", false),
+            ])]),
+            Some("large")
+        );
+        assert_eq!(
+            min_screens(vec![supports(&[
+                ("android:smallScreens", false),
+                ("android:normalScreens", false),
+                ("android:largeScreens", false),
+                ("android:xlargeScreens", false),
+            ])]),
+            None
+        );
+
+        // `<compatible-screens>` wins over `<supports-screens>`
+        assert_eq!(
+            min_screens(vec![
+                supports(&[("android:smallScreens", true)]),
+                compatible(&[500, 300]),
+            ]),
+            Some("normal")
+        );
+        assert_eq!(min_screens(vec![compatible(&[500])]), Some("xlarge"));
+    }
+
+    #[test]
+    fn test_bool_resource_attributes() {
+        const FEATURE_ON: u32 = 0x7f010000;
+        const FEATURE_OFF: u32 = 0x7f010001;
+        const MISSING: u32 = 0x7f0100ff;
+
+        let reference = |id: u32| Value::Typed(0x01, id);
+        let service = |name: &str, enabled: Value, exported: Value| {
+            el(
+                "service",
+                &[
+                    ("android:name", s(name)),
+                    ("android:enabled", enabled),
+                    ("android:exported", exported),
+                ],
+                vec![],
+            )
+        };
+        let manifest = sdk_manifest(
+            21,
+            34,
+            vec![
+                service("On", reference(FEATURE_ON), reference(FEATURE_OFF)),
+                service("Off", reference(FEATURE_OFF), Value::Bool(true)),
+                service("Missing", reference(MISSING), Value::Bool(false)),
+            ],
+        );
+        let resources = build_arsc(
+            &[ResType {
+                name: "bool",
+                keys: vec!["feature_on", "feature_off"],
+                values: vec![Some("true".to_string()), Some("false".to_string())],
+            }],
+            ArscOptions::default(),
+        );
+        let apk = apk_from_manifest(&manifest, Some(resources));
+
+        let services: Vec<_> = apk
+            .get_services()
+            .map(|service| (service.name, service.enabled, service.exported))
+            .collect();
+        assert_eq!(services[0], (Some("On"), Some("true"), Some("false")));
+        assert_eq!(services[1], (Some("Off"), Some("false"), Some("true")));
+
+        // unresolved references are kept as is
+        let (name, enabled, exported) = services[2];
+        assert_eq!(name, Some("Missing"));
+        assert!(enabled.is_some_and(|value| value.starts_with('@')));
+        assert_eq!(exported, Some("false"));
+    }
+
+    fn data(attrs: &[(&str, &str)]) -> Node {
+        let attrs: Vec<(&str, Value)> = attrs
+            .iter()
+            .map(|(name, value)| (*name, s(value)))
+            .collect();
+        el("data", &attrs, vec![])
+    }
+
+    #[test]
+    fn test_intent_filter_data() {
+        let filter = el(
+            "intent-filter",
+            &[("android:autoVerify", Value::Bool(true))],
+            vec![
+                el(
+                    "action",
+                    &[("android:name", s("android.intent.action.VIEW"))],
+                    vec![],
+                ),
+                el(
+                    "category",
+                    &[("android:name", s("android.intent.category.DEFAULT"))],
+                    vec![],
+                ),
+                el(
+                    "category",
+                    &[("android:name", s("android.intent.category.BROWSABLE"))],
+                    vec![],
+                ),
+                // no name, skipped
+                el("action", &[], vec![]),
+                data(&[("android:scheme", "https")]),
+                data(&[("android:scheme", "http")]),
+                data(&[("android:host", "example.com"), ("android:port", "8443")]),
+                data(&[("android:host", "*.example.org")]),
+                data(&[
+                    ("android:path", "/exact"),
+                    ("android:pathPrefix", "/prefix"),
+                ]),
+                data(&[("android:pathPattern", "/item/.*")]),
+            ],
+        );
+        let manifest = sdk_manifest(
+            21,
+            34,
+            vec![el(
+                "activity",
+                &[("android:name", s("Links"))],
+                vec![filter, action_filter("com.example.OPEN")],
+            )],
+        );
+        let apk = apk_from_manifest(&manifest, None);
+
+        let activity = apk.get_activities().next().unwrap();
+        assert_eq!(activity.intent_filters.len(), 2);
+        assert_eq!(activity.intent_filters[1].actions, ["com.example.OPEN"]);
+
+        let filter = &activity.intent_filters[0];
+        assert_eq!(filter.actions, ["android.intent.action.VIEW"]);
+        assert_eq!(
+            filter.categories,
+            [
+                "android.intent.category.DEFAULT",
+                "android.intent.category.BROWSABLE"
+            ]
+        );
+        assert_eq!(filter.auto_verify, Some("true"));
+        assert_eq!(filter.data.len(), 6);
+        assert_eq!(
+            filter.data[2],
+            IntentData {
+                scheme: None,
+                host: Some("example.com"),
+                port: Some("8443"),
+                path: None,
+                path_prefix: None,
+                path_pattern: None,
+                mime_type: None,
+            }
+        );
+
+        // every scheme with every host and every path, whatever <data> they are declared in
+        assert_eq!(
+            filter.uris(),
+            [
+                "https://example.com:8443/exact",
+                "https://example.com:8443/prefix",
+                "https://example.com:8443/item/.*",
+                "https://*.example.org/exact",
+                "https://*.example.org/prefix",
+                "https://*.example.org/item/.*",
+                "http://example.com:8443/exact",
+                "http://example.com:8443/prefix",
+                "http://example.com:8443/item/.*",
+                "http://*.example.org/exact",
+                "http://*.example.org/prefix",
+                "http://*.example.org/item/.*",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_intent_filter_uris() {
+        let uris = |elements: Vec<Node>| {
+            let manifest = sdk_manifest(
+                21,
+                34,
+                vec![el(
+                    "activity",
+                    &[("android:name", s("Links"))],
+                    vec![el("intent-filter", &[], elements)],
+                )],
+            );
+            let apk = apk_from_manifest(&manifest, None);
+            let activity = apk.get_activities().next().unwrap();
+            activity.intent_filters[0].uris()
+        };
+
+        assert_eq!(uris(vec![data(&[("android:scheme", "myapp")])]), ["myapp:"]);
+        assert_eq!(
+            uris(vec![data(&[
+                ("android:scheme", "myapp"),
+                ("android:host", "open")
+            ])]),
+            ["myapp://open"]
+        );
+        // paths without a host are ignored with the scheme
+        assert_eq!(
+            uris(vec![data(&[
+                ("android:scheme", "myapp"),
+                ("android:path", "/ignored")
+            ])]),
+            ["myapp:"]
+        );
+        // hosts, paths and mime types without a scheme match no uri
+        assert!(
+            uris(vec![
+                data(&[("android:host", "example.com")]),
+                data(&[("android:path", "/path")]),
+                data(&[("android:mimeType", "image/*")]),
+            ])
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_intent_filters_on_all_components() {
+        let component = |tag: &str, name: &str, action: &str| {
+            el(
+                tag,
+                &[("android:name", s(name))],
+                vec![action_filter(action)],
+            )
+        };
+        let manifest = sdk_manifest(
+            21,
+            34,
+            vec![
+                component("activity", "Main", "com.example.MAIN"),
+                el(
+                    "activity-alias",
+                    &[
+                        ("android:name", s("Alias")),
+                        ("android:targetActivity", s("Main")),
+                    ],
+                    vec![
+                        action_filter("com.example.ALIAS"),
+                        el(
+                            "intent-filter",
+                            &[],
+                            vec![data(&[
+                                ("android:scheme", "alias"),
+                                ("android:host", "open"),
+                            ])],
+                        ),
+                    ],
+                ),
+                component("service", "Sync", "com.example.SYNC"),
+                component("receiver", "Boot", "android.intent.action.BOOT_COMPLETED"),
+                component("provider", "Files", "com.example.FILES"),
+            ],
+        );
+        let apk = apk_from_manifest(&manifest, None);
+
+        let alias = apk.get_activity_aliases().next().unwrap();
+        assert_eq!(alias.name, Some("Alias"));
+        assert_eq!(alias.intent_filters.len(), 2);
+        assert_eq!(alias.intent_filters[0].actions, ["com.example.ALIAS"]);
+        assert_eq!(alias.intent_filters[1].uris(), ["alias://open"]);
+        // filters of the alias aren't merged into the target
+        let activity = apk.get_activities().next().unwrap();
+        assert_eq!(activity.intent_filters.len(), 1);
+
+        let actions = |filters: &[IntentFilter]| -> Vec<String> {
+            filters
+                .iter()
+                .flat_map(|filter| filter.actions.iter().map(|action| action.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            actions(&apk.get_services().next().unwrap().intent_filters),
+            ["com.example.SYNC"]
+        );
+        assert_eq!(
+            actions(&apk.get_receivers().next().unwrap().intent_filters),
+            ["android.intent.action.BOOT_COMPLETED"]
+        );
+        assert_eq!(
+            actions(&apk.get_providers().next().unwrap().intent_filters),
+            ["com.example.FILES"]
+        );
+    }
 }
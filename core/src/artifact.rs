@@ -0,0 +1,224 @@
+//! Standalone android artifacts: apk, dex, arsc and binary xml.
+//!
+//! Files extracted from the apk (or pulled from the device) can be parsed without
+//! knowing their type upfront, it is detected by the magic bytes:
+//!
+//! ```ignore
+//! match Artifact::new("./classes.dex")? {
+//!     Artifact::Dex(dex) => println!("{} classes", dex.classes().len()),
+//!     Artifact::Apk(apk) => println!("{:?}", apk.get_package_name()),
+//!     _ => {}
+//! }
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use apk_info_axml::{ARSC, AXML};
+use apk_info_dex::Dex;
+use memchr::memmem;
+
+use crate::apk::Apk;
+use crate::errors::APKError;
+
+/// Type of the artifact, see [ArtifactKind::detect].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArtifactKind {
+    /// Zip archive: apk, xapk, jar
+    Apk,
+
+    /// Dalvik executable, compact dex included
+    Dex,
+
+    /// Resource table (`resources.arsc`)
+    Arsc,
+
+    /// Binary xml (`AndroidManifest.xml`, compiled layouts)
+    Axml,
+}
+
+impl ArtifactKind {
+    /// Local file header of the zip archive.
+    const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+    /// End of central directory of the zip archive.
+    const EOCD_MAGIC: &[u8] = b"PK\x05\x06";
+
+    /// Maximum distance of the end of central directory from the end of the file:
+    /// record itself and the longest comment.
+    const EOCD_SEARCH_SIZE: usize = 22 + u16::MAX as usize;
+
+    /// Prefix of the dex magic, followed by the version.
+    const DEX_MAGIC: &[u8] = b"dex\n";
+
+    /// Prefix of the compact dex magic, such files are extracted from devices.
+    const COMPACT_DEX_MAGIC: &[u8] = b"cdex";
+
+    /// `RES_TABLE_TYPE` chunk type.
+    const ARSC_CHUNK_TYPE: [u8; 2] = [0x02, 0x00];
+
+    /// `RES_XML_TYPE` chunk type.
+    const AXML_CHUNK_TYPE: [u8; 2] = [0x03, 0x00];
+
+    /// Detects the type of the artifact by the magic bytes.
+    ///
+    /// Zip archives with tampered local headers (BadPack) are detected by the end of central directory.
+    pub fn detect(data: &[u8]) -> Option<ArtifactKind> {
+        if data.starts_with(Self::DEX_MAGIC) || data.starts_with(Self::COMPACT_DEX_MAGIC) {
+            return Some(ArtifactKind::Dex);
+        }
+
+        if data.starts_with(Self::ZIP_MAGIC) {
+            return Some(ArtifactKind::Apk);
+        }
+
+        // only the chunk type is checked, header sizes are often tampered with
+        if data.starts_with(&Self::ARSC_CHUNK_TYPE) {
+            return Some(ArtifactKind::Arsc);
+        }
+
+        if data.starts_with(&Self::AXML_CHUNK_TYPE) {
+            return Some(ArtifactKind::Axml);
+        }
+
+        let tail = &data[data.len().saturating_sub(Self::EOCD_SEARCH_SIZE)..];
+        if memmem::rfind(tail, Self::EOCD_MAGIC).is_some() {
+            return Some(ArtifactKind::Apk);
+        }
+
+        None
+    }
+}
+
+impl fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ArtifactKind::Apk => "apk",
+            ArtifactKind::Dex => "dex",
+            ArtifactKind::Arsc => "arsc",
+            ArtifactKind::Axml => "axml",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Parsed artifact of the detected type.
+pub enum Artifact {
+    Apk(Box<Apk>),
+    Dex(Dex),
+    Arsc(ARSC),
+    Axml(AXML),
+}
+
+impl Artifact {
+    /// Reads and parses the file, see the [module](self) docs.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Artifact, APKError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self::parse(data, name)
+    }
+
+    /// Parses the artifact from the given bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Artifact, APKError> {
+        Self::parse(data, "input".to_string())
+    }
+
+    fn parse(data: Vec<u8>, entry: String) -> Result<Artifact, APKError> {
+        if data.is_empty() {
+            return Err(APKError::InvalidInput("got empty file"));
+        }
+
+        let Some(kind) = ArtifactKind::detect(&data) else {
+            return Err(APKError::InvalidInput(
+                "unknown file type, expected apk, dex, arsc or binary xml",
+            ));
+        };
+
+        let artifact = match kind {
+            ArtifactKind::Apk => Artifact::Apk(Box::new(Apk::from_bytes(data)?)),
+            ArtifactKind::Dex => Artifact::Dex(
+                Dex::new(data).map_err(|source| APKError::DexError { entry, source })?,
+            ),
+            ArtifactKind::Arsc => Artifact::Arsc(
+                ARSC::new(&mut &data[..])
+                    .map_err(|source| APKError::ResourceError { entry, source })?,
+            ),
+            ArtifactKind::Axml => Artifact::Axml(
+                AXML::new(&mut &data[..], None)
+                    .map_err(|source| APKError::ManifestError { entry, source })?,
+            ),
+        };
+
+        Ok(artifact)
+    }
+
+    /// Returns the type of the artifact.
+    pub fn kind(&self) -> ArtifactKind {
+        match self {
+            Artifact::Apk(_) => ArtifactKind::Apk,
+            Artifact::Dex(_) => ArtifactKind::Dex,
+            Artifact::Arsc(_) => ArtifactKind::Arsc,
+            Artifact::Axml(_) => ArtifactKind::Axml,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn test_artifact_kind() {
+        let manifest = build_axml(
+            &el("manifest", &[("package", s("com.example.tests"))], vec![]),
+            AxmlOptions::default(),
+        );
+        let files = [ZipFile::deflated("AndroidManifest.xml", manifest.clone())];
+        let apk = build_zip(&files, ZipOptions::default());
+        let resources = build_arsc(
+            &[ResType {
+                name: "string",
+                keys: vec!["app_name"],
+                values: vec![Some("Tests".to_string())],
+            }],
+            ArscOptions::default(),
+        );
+
+        assert_eq!(ArtifactKind::detect(&apk), Some(ArtifactKind::Apk));
+        assert_eq!(
+            ArtifactKind::detect(&build_dex(&[])),
+            Some(ArtifactKind::Dex)
+        );
+        assert_eq!(ArtifactKind::detect(b"cdex001\0"), Some(ArtifactKind::Dex));
+        assert_eq!(ArtifactKind::detect(&resources), Some(ArtifactKind::Arsc));
+        assert_eq!(ArtifactKind::detect(&manifest), Some(ArtifactKind::Axml));
+
+        // BadPack: the local header magic is wiped, the end of central directory is still there
+        let mut tampered = apk.clone();
+        tampered[..4].copy_from_slice(b"\0\0\0\0");
+        assert_eq!(ArtifactKind::detect(&tampered), Some(ArtifactKind::Apk));
+
+        // archive comment of the maximum size doesn't hide the end of central directory
+        let comment = vec![b'c'; u16::MAX as usize];
+        let mut tampered = build_zip(
+            &files,
+            ZipOptions {
+                comment: &comment,
+                ..Default::default()
+            },
+        );
+        tampered[..4].copy_from_slice(b"\0\0\0\0");
+        assert_eq!(ArtifactKind::detect(&tampered), Some(ArtifactKind::Apk));
+
+        assert_eq!(ArtifactKind::detect(b""), None);
+        assert_eq!(ArtifactKind::detect(b"\x89PNG\r\n\x1a\n"), None);
+        assert_eq!(ArtifactKind::detect(b"de"), None);
+    }
+}
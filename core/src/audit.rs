@@ -0,0 +1,228 @@
+//! Heuristic audit of the manifest.
+//!
+//! Single permissions say little: overlays, accessibility services and sms access all have
+//! legitimate uses. Banking trojans and droppers need them together, so detectors here score
+//! the co-occurrence of such signals and report the evidence behind every finding.
+//!
+//! ```ignore
+//! for finding in audit(&apk) {
+//!     println!("{} ({:?}, score {})", finding.title, finding.likelihood, finding.score);
+//!     for evidence in &finding.evidence {
+//!         println!("  - {}", evidence.detail);
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+
+use crate::apk::Apk;
+
+/// Findings with a lower score are not reported.
+const MIN_SCORE: u32 = 40;
+
+/// Weights of the signals for a kind of malware, they sum up to 100.
+struct Profile {
+    id: &'static str,
+    title: &'static str,
+    weights: &'static [(Signal, u32)],
+}
+
+const PROFILES: &[Profile] = &[
+    Profile {
+        id: "banker",
+        title: "Banking trojan: overlays or accessibility abuse with sms access",
+        weights: &[
+            (Signal::Overlay, 25),
+            (Signal::Accessibility, 30),
+            (Signal::ReadSms, 20),
+            (Signal::QueryAllPackages, 10),
+            (Signal::HiddenLauncherIcon, 15),
+        ],
+    },
+    Profile {
+        id: "dropper",
+        title: "Dropper: installs other apps and hides itself",
+        weights: &[
+            (Signal::InstallPackages, 35),
+            (Signal::Accessibility, 20),
+            (Signal::HiddenLauncherIcon, 25),
+            (Signal::QueryAllPackages, 10),
+            (Signal::Overlay, 10),
+        ],
+    },
+];
+
+/// Likelihood of the finding, derived from its score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Likelihood {
+    Low,
+    Medium,
+    High,
+}
+
+impl Likelihood {
+    fn from_score(score: u32) -> Likelihood {
+        match score {
+            0..60 => Likelihood::Low,
+            60..80 => Likelihood::Medium,
+            _ => Likelihood::High,
+        }
+    }
+}
+
+/// Capability of the app that contributes to a finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Signal {
+    /// Drawing over other apps (`SYSTEM_ALERT_WINDOW`), used for fake login screens
+    Overlay,
+
+    /// Accessibility service, reads the screen and clicks on behalf of the user
+    Accessibility,
+
+    /// Installing other apps (`REQUEST_INSTALL_PACKAGES`)
+    InstallPackages,
+
+    /// Reading sms (`READ_SMS`, `RECEIVE_SMS`), used to steal one-time codes
+    ReadSms,
+
+    /// Listing all installed apps (`QUERY_ALL_PACKAGES`), used to find targets
+    QueryAllPackages,
+
+    /// No launcher icon or a disabled launcher `<activity-alias>` to hide it later
+    HiddenLauncherIcon,
+}
+
+/// Signal found in the apk.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Evidence {
+    pub signal: Signal,
+
+    /// What exactly was found: `android.permission.READ_SMS`
+    pub detail: String,
+}
+
+/// Result of a detector.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Finding {
+    /// Stable identifier of the finding: `banker`, `dropper`
+    pub id: &'static str,
+
+    /// Human-readable description
+    pub title: &'static str,
+
+    /// Sum of the weights of the found signals, from 0 to 100
+    pub score: u32,
+
+    pub likelihood: Likelihood,
+
+    /// Signals that contributed to the score
+    pub evidence: Vec<Evidence>,
+}
+
+/// Runs all detectors, findings are sorted by score.
+pub fn audit(apk: &Apk) -> Vec<Finding> {
+    let mut findings = dangerous_combinations(apk);
+    findings.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.id.cmp(b.id)));
+
+    findings
+}
+
+/// Scores co-occurrence of overlay, accessibility, package installation, sms access and
+/// launcher icon hiding as banker and dropper profiles.
+pub fn dangerous_combinations(apk: &Apk) -> Vec<Finding> {
+    let evidence = collect_evidence(apk);
+
+    let mut findings = Vec::new();
+    for profile in PROFILES {
+        let mut score = 0;
+        let mut found = Vec::new();
+        for (signal, weight) in profile.weights {
+            let matched: Vec<Evidence> = evidence
+                .iter()
+                .filter(|e| e.signal == *signal)
+                .cloned()
+                .collect();
+            if !matched.is_empty() {
+                score += weight;
+                found.extend(matched);
+            }
+        }
+
+        if score >= MIN_SCORE {
+            findings.push(Finding {
+                id: profile.id,
+                title: profile.title,
+                score,
+                likelihood: Likelihood::from_score(score),
+                evidence: found,
+            });
+        }
+    }
+
+    findings
+}
+
+fn collect_evidence(apk: &Apk) -> Vec<Evidence> {
+    let mut evidence = Vec::new();
+    let mut push = |signal, detail: &str| {
+        evidence.push(Evidence {
+            signal,
+            detail: detail.to_string(),
+        })
+    };
+
+    let permissions: BTreeSet<&str> = apk.get_permissions().collect();
+    for permission in permissions {
+        let signal = match permission {
+            "android.permission.SYSTEM_ALERT_WINDOW" => Signal::Overlay,
+            "android.permission.BIND_ACCESSIBILITY_SERVICE" => Signal::Accessibility,
+            "android.permission.REQUEST_INSTALL_PACKAGES"
+            | "android.permission.INSTALL_PACKAGES" => Signal::InstallPackages,
+            "android.permission.READ_SMS" | "android.permission.RECEIVE_SMS" => Signal::ReadSms,
+            "android.permission.QUERY_ALL_PACKAGES" => Signal::QueryAllPackages,
+            _ => continue,
+        };
+        push(signal, permission);
+    }
+
+    // the permission protects the service, the app itself doesn't request it
+    for service in apk.get_services() {
+        if service.permission == Some("android.permission.BIND_ACCESSIBILITY_SERVICE") {
+            push(
+                Signal::Accessibility,
+                &format!(
+                    "accessibility service {}",
+                    service.name.unwrap_or("<unnamed>")
+                ),
+            );
+        }
+    }
+
+    for alias in apk.get_activity_aliases() {
+        let is_launcher = alias.intent_filters.iter().any(|filter| {
+            filter.actions.contains(&"android.intent.action.MAIN")
+                && filter
+                    .categories
+                    .contains(&"android.intent.category.LAUNCHER")
+        });
+        if is_launcher && !alias.is_enabled() {
+            push(
+                Signal::HiddenLauncherIcon,
+                &format!(
+                    "disabled launcher activity-alias {}",
+                    alias.name.unwrap_or("<unnamed>")
+                ),
+            );
+        }
+    }
+
+    if apk.get_main_activities().next().is_none() {
+        push(Signal::HiddenLauncherIcon, "no launcher activity");
+    }
+
+    evidence
+}
@@ -0,0 +1,455 @@
+//! Repackaging detection against known-good apps.
+//!
+//! Repackaged apps keep the package name and the label of the original, but can't keep its
+//! signature. Phishing clones change the package name too and rely on the label and the icon.
+//! The [Baseline] lists the package names, labels and signer certificates of the genuine apps,
+//! [Apk::check_against_baseline](crate::Apk::check_against_baseline) reports what doesn't fit.
+//!
+//! Expected file format:
+//!
+//! ```json
+//! {
+//!     "apps": [
+//!         {
+//!             "package_name": "com.example.bank",
+//!             "labels": ["Example Bank"],
+//!             "certificates": ["a40da80a59d170caa950cf15c18c454d47a39b26989d8b640ecd745ba71bf5dc"]
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! ```ignore
+//! let baseline = Baseline::from_json(&std::fs::read("baseline.json")?)?;
+//! let check = apk.check_against_baseline(&baseline);
+//! if check.verdict == BaselineVerdict::Repackaged {
+//!     println!("{:?} is signed by a foreign key", check.matched_package);
+//! }
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::apk::Apk;
+use crate::diff;
+use crate::errors::APKError;
+
+/// Package names within this edit distance from a known one are reported as lookalikes,
+/// typosquatting usually replaces or inserts a single character.
+const MAX_LOOKALIKE_DISTANCE: usize = 1;
+
+/// Identity of a genuine app.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct BaselineApp {
+    pub package_name: String,
+
+    /// Known application labels, compared case-insensitively
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// Sha256 fingerprints of the signer certificates, `A4:0D:...` from keytool is accepted
+    pub certificates: Vec<String>,
+}
+
+/// Known-good apps, see the [module](self) docs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Baseline {
+    pub apps: Vec<BaselineApp>,
+}
+
+impl Baseline {
+    /// Creates the baseline, fingerprints are normalized to lowercase hex.
+    ///
+    /// Fails with [APKError::BaselineError] if a fingerprint isn't a sha256 digest:
+    /// 64 hex characters, separators of keytool (`A4:0D:...`) are accepted.
+    pub fn new(apps: Vec<BaselineApp>) -> Result<Baseline, APKError> {
+        let apps = apps
+            .into_iter()
+            .map(|app| {
+                let certificates = app
+                    .certificates
+                    .iter()
+                    .map(|fingerprint| {
+                        normalize_fingerprint(fingerprint).ok_or_else(|| APKError::BaselineError {
+                            source: serde::de::Error::custom(format!(
+                                "{}: {fingerprint:?} is not a sha256 fingerprint",
+                                app.package_name
+                            )),
+                        })
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(BaselineApp {
+                    certificates,
+                    ..app
+                })
+            })
+            .collect::<Result<_, APKError>>()?;
+
+        Ok(Baseline { apps })
+    }
+
+    /// Parses the baseline from json, see [Baseline::new].
+    pub fn from_json(data: &[u8]) -> Result<Baseline, APKError> {
+        let baseline: Baseline =
+            serde_json::from_slice(data).map_err(|source| APKError::BaselineError { source })?;
+
+        Baseline::new(baseline.apps)
+    }
+
+    fn find_package(&self, package_name: &str) -> Option<&BaselineApp> {
+        self.apps
+            .iter()
+            .find(|app| app.package_name == package_name)
+    }
+}
+
+/// Overall result of [Apk::check_against_baseline](crate::Apk::check_against_baseline).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaselineVerdict {
+    /// Package name of a known app, signed by one of its certificates
+    Genuine,
+
+    /// Package name of a known app, signed by a foreign certificate
+    Repackaged,
+
+    /// Other package name that pretends to be a known app: the same label or a lookalike name
+    Impersonation,
+
+    /// Nothing in common with the known apps
+    Unknown,
+}
+
+impl fmt::Display for BaselineVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BaselineVerdict::Genuine => "genuine",
+            BaselineVerdict::Repackaged => "repackaged",
+            BaselineVerdict::Impersonation => "impersonation",
+            BaselineVerdict::Unknown => "unknown",
+        })
+    }
+}
+
+/// A single mismatch with the baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BaselineFinding {
+    /// The package name is known, but none of the signer certificates are.
+    ForeignSigner {
+        package_name: String,
+
+        /// Sha256 fingerprints of the signers, empty if the apk isn't signed
+        certificates: Vec<String>,
+    },
+
+    /// The package name and the signer are known, but the label is not.
+    ///
+    /// Usually a new release, but resigned apps with a stolen key look the same.
+    UnknownLabel {
+        package_name: String,
+        label: Option<String>,
+    },
+
+    /// The label of a known app under another package name.
+    LabelImpersonation {
+        label: String,
+
+        /// Package name of the known app
+        impersonated: String,
+    },
+
+    /// The package name differs from a known one by a few characters: `com.examp1e.bank`.
+    LookalikePackage {
+        package_name: String,
+
+        /// Package name of the known app
+        impersonated: String,
+    },
+}
+
+impl fmt::Display for BaselineFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BaselineFinding::ForeignSigner {
+                package_name,
+                certificates,
+            } if certificates.is_empty() => write!(f, "{package_name} is not signed"),
+            BaselineFinding::ForeignSigner {
+                package_name,
+                certificates,
+            } => write!(
+                f,
+                "{package_name} is signed by unknown certificates: {}",
+                certificates.join(", ")
+            ),
+            BaselineFinding::UnknownLabel {
+                package_name,
+                label,
+            } => write!(
+                f,
+                "{package_name} has unknown label {:?}",
+                label.as_deref().unwrap_or_default()
+            ),
+            BaselineFinding::LabelImpersonation {
+                label,
+                impersonated,
+            } => write!(f, "label {label:?} belongs to {impersonated}"),
+            BaselineFinding::LookalikePackage {
+                package_name,
+                impersonated,
+            } => write!(f, "{package_name} looks like {impersonated}"),
+        }
+    }
+}
+
+/// Result of [Apk::check_against_baseline](crate::Apk::check_against_baseline).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct BaselineCheck {
+    pub verdict: BaselineVerdict,
+
+    /// Package name of the known app the apk was matched with
+    pub matched_package: Option<String>,
+
+    pub findings: Vec<BaselineFinding>,
+}
+
+pub(crate) fn check(apk: &Apk, baseline: &Baseline) -> BaselineCheck {
+    let package_name = apk.get_package_name().unwrap_or_default();
+    let label = apk.get_application_label();
+    let certificates = diff::certificates(apk);
+
+    if let Some(app) = baseline.find_package(&package_name) {
+        let signed = certificates
+            .iter()
+            .any(|certificate| app.certificates.contains(certificate));
+
+        let mut findings = Vec::new();
+        if !signed {
+            findings.push(BaselineFinding::ForeignSigner {
+                package_name: package_name.clone(),
+                certificates,
+            });
+        } else if !app.labels.is_empty()
+            && !label
+                .as_deref()
+                .is_some_and(|label| app.labels.iter().any(|known| same_label(known, label)))
+        {
+            findings.push(BaselineFinding::UnknownLabel {
+                package_name: package_name.clone(),
+                label,
+            });
+        }
+
+        return BaselineCheck {
+            verdict: if signed {
+                BaselineVerdict::Genuine
+            } else {
+                BaselineVerdict::Repackaged
+            },
+            matched_package: Some(package_name),
+            findings,
+        };
+    }
+
+    let mut findings = Vec::new();
+    let mut matched_package = None;
+
+    for app in &baseline.apps {
+        // another app of the same developer
+        if certificates
+            .iter()
+            .any(|certificate| app.certificates.contains(certificate))
+        {
+            continue;
+        }
+
+        if let Some(label) = label
+            .as_deref()
+            .filter(|label| app.labels.iter().any(|known| same_label(known, label)))
+        {
+            findings.push(BaselineFinding::LabelImpersonation {
+                label: label.to_string(),
+                impersonated: app.package_name.clone(),
+            });
+            matched_package.get_or_insert_with(|| app.package_name.clone());
+        }
+
+        if !package_name.is_empty()
+            && edit_distance(&package_name, &app.package_name) <= MAX_LOOKALIKE_DISTANCE
+        {
+            findings.push(BaselineFinding::LookalikePackage {
+                package_name: package_name.clone(),
+                impersonated: app.package_name.clone(),
+            });
+            matched_package.get_or_insert_with(|| app.package_name.clone());
+        }
+    }
+
+    BaselineCheck {
+        verdict: if findings.is_empty() {
+            BaselineVerdict::Unknown
+        } else {
+            BaselineVerdict::Impersonation
+        },
+        matched_package,
+        findings,
+    }
+}
+
+fn same_label(known: &str, label: &str) -> bool {
+    known.trim().to_lowercase() == label.trim().to_lowercase()
+}
+
+/// Lowercase hex of the sha256 fingerprint, `None` if it isn't one.
+fn normalize_fingerprint(fingerprint: &str) -> Option<String> {
+    let hex: String = fingerprint.trim().chars().filter(|c| *c != ':').collect();
+
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| hex.to_ascii_lowercase())
+}
+
+/// Levenshtein distance over chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    const FINGERPRINT: &str = "a40da80a59d170caa950cf15c18c454d47a39b26989d8b640ecd745ba71bf5dc";
+
+    fn bank() -> BaselineApp {
+        BaselineApp {
+            package_name: "com.example.bank".to_string(),
+            labels: vec!["Example Bank".to_string()],
+            certificates: vec![FINGERPRINT.to_string()],
+        }
+    }
+
+    fn unsigned_apk(package_name: &str, label: &str) -> Apk {
+        let manifest = el(
+            "manifest",
+            &[("package", s(package_name))],
+            vec![el("application", &[("android:label", s(label))], vec![])],
+        );
+        apk_from_manifest(&manifest, None)
+    }
+
+    #[test]
+    fn test_normalize_fingerprint() {
+        let keytool = FINGERPRINT
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| std::str::from_utf8(pair).unwrap().to_ascii_uppercase())
+            .collect::<Vec<_>>()
+            .join(":");
+
+        assert_eq!(
+            normalize_fingerprint(&keytool).as_deref(),
+            Some(FINGERPRINT)
+        );
+        assert_eq!(
+            normalize_fingerprint(&format!(" {FINGERPRINT}\n")).as_deref(),
+            Some(FINGERPRINT)
+        );
+        // sha1 of keytool
+        assert_eq!(normalize_fingerprint(&FINGERPRINT[..40]), None);
+        // not hex, used to be stripped down to a different digest
+        assert_eq!(
+            normalize_fingerprint(&format!("{}zz", &FINGERPRINT[..62])),
+            None
+        );
+        assert_eq!(
+            normalize_fingerprint(&format!("sha256:{FINGERPRINT}")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_fingerprint() {
+        let app = BaselineApp {
+            certificates: vec!["not a fingerprint".to_string()],
+            ..bank()
+        };
+        assert!(matches!(
+            Baseline::new(vec![app]),
+            Err(APKError::BaselineError { .. })
+        ));
+
+        let json =
+            br#"{"apps": [{"package_name": "com.example.bank", "certificates": ["a4:0d"]}]}"#;
+        let error = Baseline::from_json(json).unwrap_err();
+        assert!(error.display_chain().contains("com.example.bank"));
+
+        let baseline = Baseline::new(vec![bank()]).unwrap();
+        assert_eq!(baseline.apps[0].certificates, [FINGERPRINT]);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("com.example.bank", "com.example.bank"), 0);
+        assert_eq!(edit_distance("com.examp1e.bank", "com.example.bank"), 1);
+        assert_eq!(edit_distance("com.example.bankk", "com.example.bank"), 1);
+        assert_eq!(edit_distance("com.exampe.bank", "com.example.bank"), 1);
+        assert_eq!(edit_distance("com.exmaple.bank", "com.example.bank"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("банк", "банки"), 1);
+    }
+
+    #[test]
+    fn test_impersonation() {
+        let baseline = Baseline::new(vec![bank()]).unwrap();
+
+        let result = check(&unsigned_apk("com.fake.app", " example bank "), &baseline);
+        assert_eq!(result.verdict, BaselineVerdict::Impersonation);
+        assert_eq!(result.matched_package.as_deref(), Some("com.example.bank"));
+        assert_eq!(
+            result.findings,
+            [BaselineFinding::LabelImpersonation {
+                label: " example bank ".to_string(),
+                impersonated: "com.example.bank".to_string(),
+            }]
+        );
+
+        let result = check(&unsigned_apk("com.examp1e.bank", "Bank"), &baseline);
+        assert_eq!(result.verdict, BaselineVerdict::Impersonation);
+        assert_eq!(
+            result.findings,
+            [BaselineFinding::LookalikePackage {
+                package_name: "com.examp1e.bank".to_string(),
+                impersonated: "com.example.bank".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unknown() {
+        let baseline = Baseline::new(vec![bank()]).unwrap();
+
+        let result = check(&unsigned_apk("com.example.notes", "Notes"), &baseline);
+        assert_eq!(result.verdict, BaselineVerdict::Unknown);
+        assert_eq!(result.matched_package, None);
+        assert!(result.findings.is_empty());
+
+        // a known package signed by a foreign key is not unknown
+        let result = check(&unsigned_apk("com.example.bank", "Example Bank"), &baseline);
+        assert_eq!(result.verdict, BaselineVerdict::Repackaged);
+    }
+}
@@ -0,0 +1,382 @@
+//! Parsing of many apk files on the rayon thread pool.
+//!
+//! [process] parses the files in parallel and yields them as soon as they are ready,
+//! the caller analyzes each [Apk] and drops it, so only a few of them are in memory at a time.
+//! With [BatchOptions::ordered] the workers don't run further than a few files per thread
+//! ahead of the first file that isn't yielded yet. [process_mmap] maps the files instead
+//! of reading them, so only the entries that are read are loaded from disk.
+//!
+//! ```ignore
+//! let options = BatchOptions { jobs: 4, ..Default::default() };
+//! for item in batch::process(&paths, &options) {
+//!     match item.result {
+//!         Ok(apk) => println!("{:?}: {:?}", item.path, apk.get_package_name()),
+//!         Err(e) => eprintln!("{:?}: {e}", item.path),
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "experimental")]
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+use crate::apk::Apk;
+use crate::errors::APKError;
+#[cfg(feature = "experimental")]
+use crate::progress::{CancellationToken, ProgressSink, Stage};
+
+/// Options of [process].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchOptions {
+    /// Number of worker threads, `0` means the number of cpus
+    pub jobs: usize,
+
+    /// Yield results in the order of the given paths instead of the completion order.
+    ///
+    /// Results that are ready ahead of their turn are kept in memory until then,
+    /// at most [WINDOW_PER_THREAD] per worker thread.
+    pub ordered: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            jobs: 0,
+            ordered: true,
+        }
+    }
+}
+
+/// How many files per worker thread can be parsed ahead of the first file that isn't
+/// yielded yet, see [BatchOptions::ordered].
+pub const WINDOW_PER_THREAD: usize = 4;
+
+/// Parsed file of the batch.
+#[derive(Debug)]
+pub struct BatchItem {
+    /// Position of the file in the given paths
+    pub index: usize,
+
+    pub path: PathBuf,
+
+    pub result: Result<Apk, APKError>,
+}
+
+/// Iterator over the parsed files, see [process].
+///
+/// Dropping it stops the workers after the files they are parsing.
+#[derive(Debug)]
+pub struct Batch {
+    receiver: Receiver<BatchItem>,
+
+    /// Out of order results, used with [BatchOptions::ordered]
+    pending: Option<BTreeMap<usize, BatchItem>>,
+    next_index: usize,
+
+    /// Limits how far the workers run ahead of [Batch::next_index]
+    window: Arc<Window>,
+
+    // keeps the workers alive until the iterator is dropped
+    _pool: ThreadPool,
+}
+
+/// Files the workers are allowed to start: indices below `start + size`.
+#[derive(Debug)]
+struct Window {
+    /// Index of the first file that isn't yielded yet, [usize::MAX] once the batch is dropped
+    start: Mutex<usize>,
+    moved: Condvar,
+    size: usize,
+}
+
+impl Window {
+    /// Blocks until the file can be started, returns `false` if the batch is dropped.
+    fn wait(&self, index: usize) -> bool {
+        let mut start = self.start.lock().unwrap_or_else(|e| e.into_inner());
+        while index >= start.saturating_add(self.size) {
+            start = self.moved.wait(start).unwrap_or_else(|e| e.into_inner());
+        }
+
+        *start != usize::MAX
+    }
+
+    fn advance(&self, start: usize) {
+        *self.start.lock().unwrap_or_else(|e| e.into_inner()) = start;
+        self.moved.notify_all();
+    }
+}
+
+/// Parses the files in parallel, see the [module](self) docs.
+///
+/// Fails only if the thread pool can't be created, errors of the files are in [BatchItem::result].
+pub fn process<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    options: &BatchOptions,
+) -> Result<Batch, APKError> {
+    process_inner(paths, options, |path| Apk::new(path))
+}
+
+/// Same as [process], the files are opened with [Apk::open_mmap].
+///
+/// # Safety
+///
+/// The files must not be modified or truncated while the [Batch] and the parsed [Apk]s are alive.
+pub unsafe fn process_mmap<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    options: &BatchOptions,
+) -> Result<Batch, APKError> {
+    // SAFETY: forwarded to the caller
+    process_inner(paths, options, |path| unsafe { Apk::open_mmap(path) })
+}
+
+/// Same as [process], [Stage::Parse] is reported to `progress` after every parsed file.
+///
+/// Once `cancel` is triggered, the files that are not started yet are yielded
+/// with [APKError::Cancelled].
+#[cfg(feature = "experimental")]
+pub fn process_with_progress<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    options: &BatchOptions,
+    progress: Option<Arc<dyn ProgressSink + Send + Sync>>,
+    cancel: Option<CancellationToken>,
+) -> Result<Batch, APKError> {
+    process_inner_with_progress(paths, options, progress, cancel, |path| Apk::new(path))
+}
+
+/// Same as [process_with_progress], the files are opened with [Apk::open_mmap].
+///
+/// # Safety
+///
+/// The files must not be modified or truncated while the [Batch] and the parsed [Apk]s are alive.
+#[cfg(feature = "experimental")]
+pub unsafe fn process_mmap_with_progress<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    options: &BatchOptions,
+    progress: Option<Arc<dyn ProgressSink + Send + Sync>>,
+    cancel: Option<CancellationToken>,
+) -> Result<Batch, APKError> {
+    // SAFETY: forwarded to the caller
+    process_inner_with_progress(paths, options, progress, cancel, |path| unsafe {
+        Apk::open_mmap(path)
+    })
+}
+
+#[cfg(feature = "experimental")]
+fn process_inner_with_progress<P: AsRef<Path>>(
+    paths: impl IntoIterator<Item = P>,
+    options: &BatchOptions,
+    progress: Option<Arc<dyn ProgressSink + Send + Sync>>,
+    cancel: Option<CancellationToken>,
+    open: fn(&Path) -> Result<Apk, APKError>,
+) -> Result<Batch, APKError> {
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .map(|path| path.as_ref().to_path_buf())
+        .collect();
+    let total = paths.len() as u64;
+    let done = AtomicU64::new(0);
+
+    process_inner(paths, options, move |path| {
+        if cancel.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(APKError::Cancelled);
+        }
+
+        let result = open(path);
+        if let Some(progress) = &progress {
+            progress.report(
+                Stage::Parse,
+                done.fetch_add(1, Ordering::Relaxed) + 1,
+                total,
+            );
+        }
+        result
+    })
+}
+
+fn process_inner<P, F>(
+    paths: impl IntoIterator<Item = P>,
+    options: &BatchOptions,
+    parse: F,
+) -> Result<Batch, APKError>
+where
+    P: AsRef<Path>,
+    F: Fn(&Path) -> Result<Apk, APKError> + Send + Sync + 'static,
+{
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .map(|path| path.as_ref().to_path_buf())
+        .collect();
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(options.jobs)
+        .thread_name(|i| format!("apk-info-batch-{i}"))
+        .build()
+        .map_err(|e| APKError::IoError(std::io::Error::other(e)))?;
+
+    let threads = pool.current_num_threads();
+    let window = Arc::new(Window {
+        start: Mutex::new(0),
+        moved: Condvar::new(),
+        // the channel is enough to keep the workers close to the consumer
+        size: if options.ordered {
+            threads * WINDOW_PER_THREAD
+        } else {
+            usize::MAX
+        },
+    });
+
+    // files are handed out in order, so the next file to yield is always being parsed
+    let paths = Arc::new(paths);
+    let parse = Arc::new(parse);
+    let next = Arc::new(AtomicUsize::new(0));
+
+    // bounded, so workers don't run far ahead of a slow consumer
+    let (sender, receiver) = mpsc::sync_channel(threads);
+    for _ in 0..threads {
+        let (paths, parse, next, window) = (
+            Arc::clone(&paths),
+            Arc::clone(&parse),
+            Arc::clone(&next),
+            Arc::clone(&window),
+        );
+        let sender = sender.clone();
+
+        pool.spawn(move || {
+            loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                if !window.wait(index) {
+                    break;
+                }
+
+                let item = BatchItem {
+                    index,
+                    path: path.clone(),
+                    result: parse(path),
+                };
+                // the receiver is gone, nobody needs the rest
+                if sender.send(item).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    Ok(Batch {
+        receiver,
+        pending: options.ordered.then(BTreeMap::new),
+        next_index: 0,
+        window,
+        _pool: pool,
+    })
+}
+
+impl Iterator for Batch {
+    type Item = BatchItem;
+
+    fn next(&mut self) -> Option<BatchItem> {
+        let Some(pending) = &mut self.pending else {
+            return self.receiver.recv().ok();
+        };
+
+        loop {
+            if let Some(item) = pending.remove(&self.next_index) {
+                self.next_index += 1;
+                self.window.advance(self.next_index);
+                return Some(item);
+            }
+
+            match self.receiver.recv() {
+                Ok(item) => {
+                    pending.insert(item.index, item);
+                }
+                // all workers are done, a gap can only be left by a panicked worker
+                Err(_) => {
+                    let (&index, _) = pending.first_key_value()?;
+                    self.next_index = index;
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        // wakes up the workers waiting for their turn
+        self.window.advance(usize::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_ordered_window() {
+        let paths: Vec<PathBuf> = (0..200).map(|i| PathBuf::from(i.to_string())).collect();
+        let options = BatchOptions {
+            jobs: 2,
+            ordered: true,
+        };
+
+        // the first file is the slowest, the others are ready long before it
+        let mut batch = process_inner(&paths, &options, |path| {
+            if path == Path::new("0") {
+                std::thread::sleep(Duration::from_millis(200));
+            }
+            Err(APKError::InvalidInput("not an apk"))
+        })
+        .unwrap();
+
+        let mut indices = Vec::new();
+        let mut max_pending = 0;
+        while let Some(item) = batch.next() {
+            indices.push(item.index);
+            max_pending = max_pending.max(batch.pending.as_ref().unwrap().len());
+        }
+
+        assert_eq!(indices, (0..200).collect::<Vec<_>>());
+        assert!(max_pending <= 2 * WINDOW_PER_THREAD, "{max_pending}");
+    }
+
+    #[test]
+    fn test_unordered() {
+        let paths: Vec<PathBuf> = (0..50).map(|i| PathBuf::from(i.to_string())).collect();
+        let options = BatchOptions {
+            jobs: 3,
+            ordered: false,
+        };
+
+        let batch = process_inner(&paths, &options, |_| {
+            Err(APKError::InvalidInput("not an apk"))
+        })
+        .unwrap();
+        let mut indices: Vec<usize> = batch.map(|item| item.index).collect();
+        indices.sort_unstable();
+
+        assert_eq!(indices, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_drop_early() {
+        let paths: Vec<PathBuf> = (0..100).map(|i| PathBuf::from(i.to_string())).collect();
+        let mut batch = process_inner(&paths, &BatchOptions::default(), |_| {
+            Err(APKError::InvalidInput("not an apk"))
+        })
+        .unwrap();
+
+        assert_eq!(batch.next().map(|item| item.index), Some(0));
+        drop(batch);
+    }
+}
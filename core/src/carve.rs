@@ -0,0 +1,335 @@
+//! Locating apk files embedded into other containers.
+//!
+//! Malware is often delivered wrapped into something else: a `.tar.gz`, a password
+//! protected `.7z`, a base64 attachment inside an email, a zip archive with the apk
+//! inside, or the apk appended to some other file.
+//!
+//! [carve] walks such wrappers recursively and returns all found candidates:
+//!
+//! ```no_run
+//! use apk_info::carve::carve;
+//!
+//! let data = std::fs::read("./mail.eml").expect("can't read file");
+//! for candidate in carve(&data) {
+//!     println!("{} - {} bytes", candidate.source, candidate.data.len());
+//! }
+//! ```
+
+use std::io::Read;
+
+use apk_info_zip::ZipEntry;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::read::MultiGzDecoder;
+use memchr::memmem;
+
+use crate::logging::debug;
+
+/// Maximum depth of nested containers.
+const MAX_DEPTH: usize = 4;
+
+/// Maximum size of a single unpacked object (512 MiB).
+const MAX_OBJECT_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Maximum size of all unpacked objects and candidates together (2 GiB), nested archives
+/// multiply the data, so the per-object limit alone is not enough.
+const MAX_TOTAL_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Maximum number of embedded zip signatures checked in a single object.
+const MAX_EMBEDDED_ZIPS: usize = 16;
+
+/// Local file header magic.
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Base64 encoding of the [ZIP_MAGIC] prefix (`PK\x03\x04`).
+const ZIP_BASE64_MAGIC: &[u8] = b"UEsDB";
+
+/// The apk found inside a container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CarvedApk {
+    /// Human readable path to the apk, for example `gzip > tar:files/app.apk`.
+    pub source: String,
+
+    /// Raw bytes of the apk.
+    pub data: Vec<u8>,
+}
+
+/// Locates apk files in the given bytes.
+///
+/// Supported wrappers:
+/// - plain apk (returned as is);
+/// - zip archives containing apk files;
+/// - zip archives appended to other data;
+/// - gzip and tar;
+/// - 7z (requires `sevenz` feature, tries empty password and `infected`);
+/// - base64 encoded apk inside text (emails, scripts, html);
+///
+/// Unpacking stops once 2 GiB are unpacked in total, objects bigger than 512 MiB are skipped.
+pub fn carve(input: &[u8]) -> Vec<CarvedApk> {
+    let mut carver = Carver {
+        found: Vec::new(),
+        remaining: MAX_TOTAL_SIZE,
+    };
+    carver.carve(input, "", 0);
+    carver.found
+}
+
+/// Found candidates and the budget of unpacked bytes shared by all nesting levels.
+struct Carver {
+    found: Vec<CarvedApk>,
+    remaining: u64,
+}
+
+impl Carver {
+    /// Maximum size of the next unpacked object.
+    fn limit(&self) -> u64 {
+        MAX_OBJECT_SIZE.min(self.remaining)
+    }
+
+    /// Takes `size` bytes from the budget, `false` if there is not enough left.
+    fn reserve(&mut self, size: u64, source: &str) -> bool {
+        if size > self.limit() {
+            debug!("{source:?} is too big ({size} bytes), skipped");
+            return false;
+        }
+
+        self.remaining -= size;
+        true
+    }
+
+    /// Reads the whole stream, but not more than [Carver::limit].
+    fn read<R: Read>(&mut self, reader: R, source: &str) -> Option<Vec<u8>> {
+        let mut data = Vec::new();
+        // one byte more to tell truncated objects from the ones of exactly the limit size
+        let limit = self.limit();
+        match reader.take(limit + 1).read_to_end(&mut data) {
+            Ok(_) if data.len() as u64 > limit => {
+                debug!("{source:?} is bigger than {limit} bytes, skipped");
+                None
+            }
+            Ok(_) if !data.is_empty() => {
+                self.remaining -= data.len() as u64;
+                Some(data)
+            }
+            _ => None,
+        }
+    }
+
+    fn carve(&mut self, input: &[u8], source: &str, depth: usize) {
+        if depth > MAX_DEPTH {
+            debug!("max depth reached at {source:?}, skipped");
+            return;
+        }
+
+        let next_source = |kind: &str| {
+            if source.is_empty() {
+                kind.to_string()
+            } else {
+                format!("{source} > {kind}")
+            }
+        };
+
+        if input.starts_with(ZIP_MAGIC) {
+            self.carve_zip(input, source, depth);
+            return;
+        }
+
+        if input.starts_with(b"\x1f\x8b") {
+            let source = next_source("gzip");
+            if let Some(data) = self.read(MultiGzDecoder::new(input), &source) {
+                self.carve(&data, &source, depth + 1);
+            }
+            return;
+        }
+
+        if input.get(257..262) == Some(b"ustar") {
+            self.carve_tar(input, &next_source("tar"), depth);
+            return;
+        }
+
+        #[cfg(feature = "sevenz")]
+        if input.starts_with(b"7z\xbc\xaf\x27\x1c") {
+            self.carve_7z(input, &next_source("7z"), depth);
+            return;
+        }
+
+        // zip appended to some other data (images, scripts, self-extracting archives)
+        for offset in memmem::find_iter(input, ZIP_MAGIC).take(MAX_EMBEDDED_ZIPS) {
+            if offset == 0 {
+                continue;
+            }
+
+            let before = self.found.len();
+            self.carve_zip(
+                &input[offset..],
+                &next_source(&format!("offset:0x{offset:x}")),
+                depth + 1,
+            );
+
+            // the first valid archive consumes the rest of data
+            if self.found.len() != before {
+                break;
+            }
+        }
+
+        // base64 encoded archive inside text, every run is decoded once: the magic
+        // repeated inside a run would make the scan quadratic otherwise
+        let mut run_end = 0;
+        for offset in memmem::find_iter(input, ZIP_BASE64_MAGIC) {
+            if offset < run_end {
+                continue;
+            }
+
+            let source = next_source(&format!("base64:0x{offset:x}"));
+            let (data, length) = decode_base64_run(&input[offset..], self.limit());
+            run_end = offset + length;
+
+            let Some(data) = data else {
+                continue;
+            };
+            if self.reserve(data.len() as u64, &source) {
+                self.carve(&data, &source, depth + 1);
+            }
+        }
+    }
+
+    fn carve_zip(&mut self, input: &[u8], source: &str, depth: usize) {
+        let Ok(zip) = ZipEntry::new(input.to_vec()) else {
+            return;
+        };
+
+        let mut names: Vec<&str> = zip.namelist().collect();
+        if names.contains(&"AndroidManifest.xml") {
+            let source = if source.is_empty() {
+                "apk".to_string()
+            } else {
+                source.to_string()
+            };
+            if self.reserve(input.len() as u64, &source) {
+                self.found.push(CarvedApk {
+                    source,
+                    data: input.to_vec(),
+                });
+            }
+            return;
+        }
+
+        // zip with something inside (xapk, apks, archive with samples)
+        names.sort_unstable();
+        for name in names {
+            if name.ends_with('/') {
+                continue;
+            }
+
+            let kind = format!("zip:{name}");
+            let next = if source.is_empty() {
+                kind
+            } else {
+                format!("{source} > {kind}")
+            };
+
+            // the declared size is the upper bound of the decompressed data
+            let Ok(info) = zip.entry_info(name) else {
+                continue;
+            };
+            if !self.reserve(info.uncompressed_size, &next) {
+                continue;
+            }
+
+            let Ok((data, _)) = zip.read_borrowed(name) else {
+                continue;
+            };
+            self.carve(&data, &next, depth + 1);
+        }
+    }
+
+    fn carve_tar(&mut self, input: &[u8], source: &str, depth: usize) {
+        let mut archive = tar::Archive::new(input);
+        let Ok(entries) = archive.entries() else {
+            return;
+        };
+
+        for entry in entries {
+            let Ok(entry) = entry else {
+                break;
+            };
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let name = entry
+                .path()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            let source = format!("{source}:{name}");
+            if let Some(data) = self.read(entry, &source) {
+                self.carve(&data, &source, depth + 1);
+            }
+        }
+    }
+
+    #[cfg(feature = "sevenz")]
+    fn carve_7z(&mut self, input: &[u8], source: &str, depth: usize) {
+        use std::io::Cursor;
+
+        use sevenz_rust::{Password, SevenZReader};
+
+        // "infected" is a de-facto standard password for malware samples
+        for password in [Password::empty(), Password::from("infected")] {
+            let Ok(mut archive) =
+                SevenZReader::new(Cursor::new(input), input.len() as u64, password)
+            else {
+                continue;
+            };
+
+            // sizes in the headers are not trusted, buffers grow with the data actually read
+            let mut files = Vec::new();
+            let result = archive.for_each_entries(|entry, reader| {
+                if !entry.is_directory() {
+                    let name = format!("{source}:{}", entry.name());
+                    if let Some(data) = self.read(reader, &name) {
+                        files.push((name, data));
+                    }
+                }
+                Ok(true)
+            });
+
+            if result.is_err() {
+                debug!("can't unpack {source:?}: {result:?}");
+                continue;
+            }
+
+            for (name, data) in files {
+                self.carve(&data, &name, depth + 1);
+            }
+            return;
+        }
+    }
+}
+
+/// Decodes the longest base64 run at the start of input, line breaks are allowed.
+///
+/// Runs longer than needed for `limit` decoded bytes are cut. Returns the decoded data
+/// and the length of the whole run in the input.
+fn decode_base64_run(input: &[u8], limit: u64) -> (Option<Vec<u8>>, usize) {
+    let length = input
+        .iter()
+        .position(|&c| {
+            !(c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'=' | b'\r' | b'\n'))
+        })
+        .unwrap_or(input.len());
+
+    let max_encoded = usize::try_from(limit / 3 * 4).unwrap_or(usize::MAX);
+    let encoded: Vec<u8> = input[..length]
+        .iter()
+        .filter(|&&c| c != b'\r' && c != b'\n')
+        .take(max_encoded)
+        .copied()
+        .collect();
+
+    // drop trailing garbage that doesn't form a full quantum
+    let len = encoded.len() - encoded.len() % 4;
+    (STANDARD.decode(&encoded[..len]).ok(), length)
+}
@@ -0,0 +1,818 @@
+//! Detection of the frameworks, sdks and packers the app is built with.
+//!
+//! Cross-platform frameworks leave characteristic files in the apk (`libflutter.so`,
+//! `assets/index.android.bundle`) and their java glue in the dex files. Knowing the framework
+//! tells where the actual logic is: a flutter app keeps it in `libapp.so`, a react native app
+//! in the javascript bundle, so the dex files alone say little about them.
+//!
+//! Packers replace the application class with a stub that decrypts the original dex files
+//! from a native library or an asset at runtime, the dex files of a packed apk contain only
+//! the stub.
+//!
+//! ```ignore
+//! for hit in apk.detect_frameworks() {
+//!     println!("{}: {}", hit.framework, hit.evidence.join(", "));
+//! }
+//! for hit in apk.detect_packers() {
+//!     println!("{} ({}%)", hit.packer, hit.confidence);
+//! }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::apk::Apk;
+use crate::logging::warn;
+
+/// Framework or sdk the app is built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Framework {
+    /// Flutter, dart code is compiled to `libapp.so`
+    Flutter,
+
+    /// React Native, javascript or hermes bytecode in `assets/index.android.bundle`
+    ReactNative,
+
+    /// Unity, game logic in `libil2cpp.so` or mono assemblies
+    Unity,
+
+    /// Apache Cordova and derivatives (Ionic, PhoneGap), web app in `assets/www/`
+    Cordova,
+
+    /// Xamarin and .NET for Android, .NET assemblies in `assemblies/`
+    Xamarin,
+
+    /// Kotlin
+    Kotlin,
+
+    /// Jetpack Compose
+    Compose,
+}
+
+impl fmt::Display for Framework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Framework::Flutter => "Flutter",
+            Framework::ReactNative => "React Native",
+            Framework::Unity => "Unity",
+            Framework::Cordova => "Cordova",
+            Framework::Xamarin => "Xamarin",
+            Framework::Kotlin => "Kotlin",
+            Framework::Compose => "Jetpack Compose",
+        })
+    }
+}
+
+/// Where a file of the rule is expected to be.
+enum FilePattern {
+    /// Exact path: `assets/index.android.bundle`
+    Path(&'static str),
+
+    /// Directory or the beginning of the path: `assets/flutter_assets/`, `assets/dp.`
+    Prefix(&'static str),
+
+    /// Native library of any abi: `libflutter.so`
+    Library(&'static str),
+
+    /// Native library of any abi with the versioned name: `libshella-` for `libshella-2.10.so`
+    LibraryPrefix(&'static str),
+
+    /// File name in the directory: `META-INF/` and `.kotlin_module`
+    Suffix(&'static str, &'static str),
+}
+
+impl FilePattern {
+    fn matches(&self, path: &str) -> bool {
+        match self {
+            FilePattern::Path(expected) => path == *expected,
+            FilePattern::Prefix(prefix) => path.starts_with(prefix),
+            FilePattern::Library(library) => library_name(path) == Some(library),
+            FilePattern::LibraryPrefix(prefix) => library_name(path)
+                .is_some_and(|name| name.starts_with(prefix) && name.ends_with(".so")),
+            FilePattern::Suffix(directory, suffix) => path
+                .strip_prefix(directory)
+                .is_some_and(|name| !name.contains('/') && name.ends_with(suffix)),
+        }
+    }
+
+    /// Evidence for the matched path, directories and groups of files are reported once,
+    /// not with every file in them.
+    fn evidence(&self, path: &str) -> String {
+        match self {
+            FilePattern::Prefix(prefix) if prefix.ends_with('/') => prefix.to_string(),
+            FilePattern::Suffix(directory, suffix) => format!("{directory}*{suffix}"),
+            _ => path.to_string(),
+        }
+    }
+
+    fn is_library(&self) -> bool {
+        matches!(
+            self,
+            FilePattern::Library(_) | FilePattern::LibraryPrefix(_)
+        )
+    }
+}
+
+/// Returns the name of the native library: `libflutter.so` for `lib/arm64-v8a/libflutter.so`.
+fn library_name(path: &str) -> Option<&str> {
+    path.strip_prefix("lib/")?
+        .split_once('/')
+        .map(|(_, name)| name)
+        .filter(|name| !name.contains('/'))
+}
+
+/// Characteristic files and packages of the framework.
+struct Rule {
+    framework: Framework,
+
+    files: &'static [FilePattern],
+
+    /// Type descriptor prefixes of the java part: `Lio/flutter/`
+    packages: &'static [&'static str],
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        framework: Framework::Flutter,
+        files: &[
+            FilePattern::Library("libflutter.so"),
+            FilePattern::Prefix("assets/flutter_assets/"),
+        ],
+        packages: &["Lio/flutter/embedding/", "Lio/flutter/plugin/"],
+    },
+    Rule {
+        framework: Framework::ReactNative,
+        files: &[
+            FilePattern::Path("assets/index.android.bundle"),
+            FilePattern::Library("libreactnativejni.so"),
+            FilePattern::Library("libreactnative.so"),
+        ],
+        packages: &["Lcom/facebook/react/"],
+    },
+    Rule {
+        framework: Framework::Unity,
+        files: &[
+            FilePattern::Library("libunity.so"),
+            FilePattern::Library("libil2cpp.so"),
+            FilePattern::Prefix("assets/bin/Data/"),
+        ],
+        packages: &["Lcom/unity3d/player/"],
+    },
+    Rule {
+        framework: Framework::Cordova,
+        files: &[
+            FilePattern::Path("assets/www/cordova.js"),
+            FilePattern::Path("assets/www/cordova_plugins.js"),
+        ],
+        packages: &["Lorg/apache/cordova/"],
+    },
+    Rule {
+        framework: Framework::Xamarin,
+        files: &[
+            FilePattern::Library("libmonodroid.so"),
+            FilePattern::Library("libxamarin-app.so"),
+            FilePattern::Prefix("assemblies/"),
+        ],
+        packages: &["Lmono/android/"],
+    },
+    Rule {
+        framework: Framework::Kotlin,
+        files: &[
+            FilePattern::Prefix("kotlin/"),
+            FilePattern::Suffix("META-INF/", ".kotlin_module"),
+        ],
+        packages: &["Lkotlin/"],
+    },
+    Rule {
+        framework: Framework::Compose,
+        files: &[FilePattern::Path(COMPOSE_VERSION_FILE)],
+        packages: &["Landroidx/compose/runtime/"],
+    },
+];
+
+/// Version file of the compose runtime, the other compose libraries have their own.
+const COMPOSE_VERSION_FILE: &str = "META-INF/androidx.compose.runtime_runtime.version";
+
+/// Framework found in the apk, see [Apk::detect_frameworks](crate::Apk::detect_frameworks).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct FrameworkHit {
+    pub framework: Framework,
+
+    /// Version, if the framework records it in the apk
+    pub version: Option<String>,
+
+    /// Matched files and packages: `lib/arm64-v8a/libflutter.so`, `Lio/flutter/embedding/`
+    pub evidence: Vec<String>,
+}
+
+impl FrameworkHit {
+    /// Checks whether both the files and the dex files point to the framework.
+    ///
+    /// Hits based on the packages only are usually libraries that bundle a part of
+    /// the framework, not the framework the app is built with.
+    pub fn is_confirmed(&self) -> bool {
+        let from_dex = |evidence: &String| evidence.starts_with('L');
+        self.evidence.iter().any(from_dex) && !self.evidence.iter().all(from_dex)
+    }
+}
+
+/// Returns the prefixes of the type descriptors referenced in the dex file.
+fn scan(dex: &apk_info_dex::Dex, prefixes: &[&'static str]) -> Vec<&'static str> {
+    let mut found = BTreeSet::new();
+
+    for idx in 0..dex.types_count() as u32 {
+        let Some(descriptor) = dex.get_type(idx) else {
+            continue;
+        };
+
+        found.extend(
+            prefixes
+                .iter()
+                .filter(|prefix| descriptor.starts_with(*prefix)),
+        );
+        if found.len() == prefixes.len() {
+            break;
+        }
+    }
+
+    found.into_iter().collect()
+}
+
+/// Finds the prefixes in all dex files, those that can't be parsed are skipped.
+fn scan_dex(apk: &Apk, prefixes: &[&'static str]) -> BTreeSet<&'static str> {
+    apk.map_dex(|name| match apk.read_dex(name) {
+        Ok(dex) => scan(&dex, prefixes),
+        Err(e) => {
+            warn!("{name}: skipped, {}", e.display_chain());
+            Vec::new()
+        }
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+pub(crate) fn detect_frameworks(apk: &Apk) -> Vec<FrameworkHit> {
+    let mut evidence: BTreeMap<usize, BTreeSet<String>> = BTreeMap::new();
+    let mut compose_version_file = None;
+
+    for name in apk.namelist() {
+        let Some(path) = apk.module_path(name) else {
+            continue;
+        };
+        if path == COMPOSE_VERSION_FILE {
+            compose_version_file = Some(name);
+        }
+
+        for (rule_idx, rule) in RULES.iter().enumerate() {
+            if let Some(pattern) = rule.files.iter().find(|pattern| pattern.matches(path)) {
+                evidence
+                    .entry(rule_idx)
+                    .or_default()
+                    .insert(pattern.evidence(path));
+            }
+        }
+    }
+
+    let prefixes: Vec<&'static str> = RULES
+        .iter()
+        .flat_map(|rule| rule.packages)
+        .copied()
+        .collect();
+    for prefix in scan_dex(apk, &prefixes) {
+        if let Some(rule_idx) = RULES
+            .iter()
+            .position(|rule| rule.packages.contains(&prefix))
+        {
+            evidence
+                .entry(rule_idx)
+                .or_default()
+                .insert(prefix.to_string());
+        }
+    }
+
+    evidence
+        .into_iter()
+        .map(|(rule_idx, evidence)| {
+            let framework = RULES[rule_idx].framework;
+            let version = match framework {
+                Framework::Compose => compose_version_file.and_then(|name| read_version(apk, name)),
+                _ => None,
+            };
+
+            FrameworkHit {
+                framework,
+                version,
+                evidence: evidence.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+/// Reads the version from the `.version` file of the androidx library: `1.6.8`.
+fn read_version(apk: &Apk, name: &str) -> Option<String> {
+    let (data, _) = apk.read(name).ok()?;
+    let version = String::from_utf8_lossy(&data).trim().to_string();
+    (!version.is_empty() && version.len() < 64).then_some(version)
+}
+
+/// Commercial packer or protector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Packer {
+    /// Qihoo 360 Jiagu
+    Jiagu,
+
+    /// Bangcle (SecNeo)
+    Bangcle,
+
+    /// Tencent Legu
+    TencentLegu,
+
+    /// DexProtector by Licel
+    DexProtector,
+
+    /// Appdome
+    Appdome,
+
+    /// Ijiami
+    Ijiami,
+
+    /// Baidu app protection
+    Baidu,
+}
+
+impl fmt::Display for Packer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Packer::Jiagu => "360 Jiagu",
+            Packer::Bangcle => "Bangcle",
+            Packer::TencentLegu => "Tencent Legu",
+            Packer::DexProtector => "DexProtector",
+            Packer::Appdome => "Appdome",
+            Packer::Ijiami => "Ijiami",
+            Packer::Baidu => "Baidu Protect",
+        })
+    }
+}
+
+/// Indicators of the packer.
+struct PackerRule {
+    packer: Packer,
+
+    /// Native libraries and encrypted payloads
+    files: &'static [FilePattern],
+
+    /// Stub set as `android:name` or `android:appComponentFactory` of `<application>`
+    application_classes: &'static [&'static str],
+
+    /// Type descriptor prefixes of the stub: `Lcom/stub/StubApp;`
+    packages: &'static [&'static str],
+}
+
+const PACKER_RULES: &[PackerRule] = &[
+    PackerRule {
+        packer: Packer::Jiagu,
+        files: &[
+            FilePattern::LibraryPrefix("libjiagu"),
+            FilePattern::Prefix("assets/libjiagu"),
+        ],
+        application_classes: &["com.stub.StubApp"],
+        packages: &["Lcom/stub/StubApp;", "Lcom/qihoo/util/"],
+    },
+    PackerRule {
+        packer: Packer::Bangcle,
+        files: &[
+            FilePattern::LibraryPrefix("libsecexe"),
+            FilePattern::LibraryPrefix("libsecmain"),
+            FilePattern::LibraryPrefix("libSecShell"),
+            FilePattern::Path("assets/secData0.jar"),
+            FilePattern::Prefix("assets/bangcle"),
+        ],
+        application_classes: &[
+            "com.secneo.apkwrapper.ApplicationWrapper",
+            "com.SecShell.SecShell.ApplicationWrapper",
+        ],
+        packages: &["Lcom/secneo/apkwrapper/", "Lcom/SecShell/SecShell/"],
+    },
+    PackerRule {
+        packer: Packer::TencentLegu,
+        files: &[
+            FilePattern::LibraryPrefix("libshella-"),
+            FilePattern::LibraryPrefix("libshellx-"),
+            FilePattern::Path("tencent_stub"),
+            FilePattern::Path("assets/0OO00l111l1l"),
+        ],
+        application_classes: &["com.tencent.StubShell.TxAppEntry"],
+        packages: &["Lcom/tencent/StubShell/"],
+    },
+    PackerRule {
+        packer: Packer::DexProtector,
+        files: &[
+            FilePattern::Library("libdexprotector.so"),
+            FilePattern::Prefix("assets/dp."),
+        ],
+        application_classes: &[],
+        packages: &["Lcom/licel/dexprotector/"],
+    },
+    PackerRule {
+        packer: Packer::Appdome,
+        files: &[
+            FilePattern::LibraryPrefix("libappdome"),
+            FilePattern::Prefix("assets/appdome"),
+        ],
+        application_classes: &[],
+        packages: &["Lcom/appdome/"],
+    },
+    PackerRule {
+        packer: Packer::Ijiami,
+        files: &[
+            FilePattern::Library("libexec.so"),
+            FilePattern::Library("libexecmain.so"),
+            FilePattern::Path("assets/ijiami.dat"),
+            FilePattern::Prefix("assets/ijm_lib/"),
+        ],
+        application_classes: &["com.shell.SuperApplication"],
+        packages: &["Lcom/shell/SuperApplication;"],
+    },
+    PackerRule {
+        packer: Packer::Baidu,
+        files: &[
+            FilePattern::Library("libbaiduprotect.so"),
+            FilePattern::Prefix("assets/baiduprotect"),
+        ],
+        application_classes: &["com.baidu.protect.StubApplication"],
+        packages: &["Lcom/baidu/protect/"],
+    },
+];
+
+/// Weights of the kinds of indicators, the confidence is their sum.
+///
+/// The stub application class is the strongest one, names of the libraries and assets
+/// are easy to copy, so one of them alone is not a proof.
+const APPLICATION_CLASS_WEIGHT: u8 = 60;
+const LIBRARY_WEIGHT: u8 = 45;
+const DEX_PACKAGE_WEIGHT: u8 = 35;
+const FILE_WEIGHT: u8 = 25;
+
+/// Packer found in the apk, see [Apk::detect_packers](crate::Apk::detect_packers).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct PackerHit {
+    pub packer: Packer,
+
+    /// Confidence of the detection in percent, `1..=100`
+    pub confidence: u8,
+
+    /// Matched indicators: `lib/arm64-v8a/libjiagu_a64.so`, `application: com.stub.StubApp`
+    pub evidence: Vec<String>,
+}
+
+/// Kinds of indicators matched for the packer and their evidence.
+#[derive(Default)]
+struct PackerMatch {
+    application_class: bool,
+    library: bool,
+    dex_package: bool,
+    file: bool,
+    evidence: BTreeSet<String>,
+}
+
+impl PackerMatch {
+    fn confidence(&self) -> u8 {
+        let weights = [
+            (self.application_class, APPLICATION_CLASS_WEIGHT),
+            (self.library, LIBRARY_WEIGHT),
+            (self.dex_package, DEX_PACKAGE_WEIGHT),
+            (self.file, FILE_WEIGHT),
+        ];
+
+        weights
+            .iter()
+            .filter(|(matched, _)| *matched)
+            .map(|(_, weight)| *weight)
+            .fold(0u8, u8::saturating_add)
+            .min(100)
+    }
+}
+
+pub(crate) fn detect_packers(apk: &Apk) -> Vec<PackerHit> {
+    let mut matches: BTreeMap<usize, PackerMatch> = BTreeMap::new();
+
+    for name in apk.namelist() {
+        let Some(path) = apk.module_path(name) else {
+            continue;
+        };
+
+        for (rule_idx, rule) in PACKER_RULES.iter().enumerate() {
+            if let Some(pattern) = rule.files.iter().find(|pattern| pattern.matches(path)) {
+                let found = matches.entry(rule_idx).or_default();
+                if pattern.is_library() {
+                    found.library = true;
+                } else {
+                    found.file = true;
+                }
+                found.evidence.insert(pattern.evidence(path));
+            }
+        }
+    }
+
+    for attribute in ["name", "appComponentFactory"] {
+        let Some(class) = apk.get_attribute_value("application", attribute) else {
+            continue;
+        };
+
+        for (rule_idx, rule) in PACKER_RULES.iter().enumerate() {
+            if rule.application_classes.contains(&class.as_str()) {
+                let found = matches.entry(rule_idx).or_default();
+                found.application_class = true;
+                found.evidence.insert(format!("application: {class}"));
+            }
+        }
+    }
+
+    let prefixes: Vec<&'static str> = PACKER_RULES
+        .iter()
+        .flat_map(|rule| rule.packages)
+        .copied()
+        .collect();
+    for prefix in scan_dex(apk, &prefixes) {
+        if let Some(rule_idx) = PACKER_RULES
+            .iter()
+            .position(|rule| rule.packages.contains(&prefix))
+        {
+            let found = matches.entry(rule_idx).or_default();
+            found.dex_package = true;
+            found.evidence.insert(prefix.to_string());
+        }
+    }
+
+    let mut hits: Vec<PackerHit> = matches
+        .into_iter()
+        .map(|(rule_idx, found)| PackerHit {
+            packer: PACKER_RULES[rule_idx].packer,
+            confidence: found.confidence(),
+            evidence: found.evidence.into_iter().collect(),
+        })
+        .collect();
+
+    // the most likely packer first
+    hits.sort_by(|a, b| {
+        b.confidence
+            .cmp(&a.confidence)
+            .then(a.packer.cmp(&b.packer))
+    });
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn apk(application: &[(&str, Value)], files: Vec<ZipFile>) -> Apk {
+        let manifest = el(
+            "manifest",
+            &[("package", s("com.example.tests"))],
+            vec![el("application", application, vec![])],
+        );
+        let mut apk_files = vec![ZipFile::deflated(
+            "AndroidManifest.xml",
+            build_axml(&manifest, AxmlOptions::default()),
+        )];
+        apk_files.extend(files);
+
+        Apk::from_bytes(build_zip(&apk_files, ZipOptions::default())).unwrap()
+    }
+
+    fn file(name: &str) -> ZipFile {
+        ZipFile::stored(name, b"\x7fELF".to_vec())
+    }
+
+    fn dex(classes: &[&str]) -> ZipFile {
+        ZipFile::stored("classes.dex", build_dex(classes))
+    }
+
+    fn frameworks(apk: &Apk) -> Vec<(Framework, Vec<String>)> {
+        apk.detect_frameworks()
+            .into_iter()
+            .map(|hit| (hit.framework, hit.evidence))
+            .collect()
+    }
+
+    fn packers(apk: &Apk) -> Vec<(Packer, u8)> {
+        apk.detect_packers()
+            .into_iter()
+            .map(|hit| (hit.packer, hit.confidence))
+            .collect()
+    }
+
+    #[test]
+    fn test_library_name() {
+        assert_eq!(
+            library_name("lib/arm64-v8a/libflutter.so"),
+            Some("libflutter.so")
+        );
+        assert_eq!(library_name("lib/libflutter.so"), None);
+        assert_eq!(library_name("lib/x86/nested/libflutter.so"), None);
+        assert_eq!(library_name("assets/lib/x86/libflutter.so"), None);
+    }
+
+    #[test]
+    fn test_file_patterns() {
+        assert!(FilePattern::Library("libflutter.so").matches("lib/x86_64/libflutter.so"));
+        assert!(!FilePattern::Library("libflutter.so").matches("lib/x86_64/libflutter.so.1"));
+        assert!(FilePattern::LibraryPrefix("libshella-").matches("lib/armeabi/libshella-2.10.so"));
+        assert!(
+            !FilePattern::LibraryPrefix("libshella-").matches("lib/armeabi/libshella-2.10.txt")
+        );
+        assert!(
+            FilePattern::Suffix("META-INF/", ".kotlin_module")
+                .matches("META-INF/app_release.kotlin_module")
+        );
+        assert!(
+            !FilePattern::Suffix("META-INF/", ".kotlin_module")
+                .matches("META-INF/nested/app.kotlin_module")
+        );
+
+        // directories are reported once, not with every file in them
+        assert_eq!(
+            FilePattern::Prefix("assets/flutter_assets/").evidence("assets/flutter_assets/a.json"),
+            "assets/flutter_assets/"
+        );
+        assert_eq!(
+            FilePattern::Prefix("assets/dp.").evidence("assets/dp.arm64.so"),
+            "assets/dp.arm64.so"
+        );
+    }
+
+    #[test]
+    fn test_frameworks() {
+        let apk = apk(
+            &[],
+            vec![
+                file("lib/arm64-v8a/libflutter.so"),
+                file("lib/armeabi-v7a/libflutter.so"),
+                file("assets/flutter_assets/AssetManifest.json"),
+                file("assets/flutter_assets/fonts/MaterialIcons-Regular.otf"),
+                file("META-INF/app_release.kotlin_module"),
+                dex(&[
+                    "io.flutter.embedding.engine.FlutterEngine",
+                    "com.example.MainActivity",
+                ]),
+            ],
+        );
+
+        assert_eq!(
+            frameworks(&apk),
+            [
+                (
+                    Framework::Flutter,
+                    vec![
+                        "Lio/flutter/embedding/".to_string(),
+                        "assets/flutter_assets/".to_string(),
+                        "lib/arm64-v8a/libflutter.so".to_string(),
+                        "lib/armeabi-v7a/libflutter.so".to_string(),
+                    ]
+                ),
+                (
+                    Framework::Kotlin,
+                    vec!["META-INF/*.kotlin_module".to_string()]
+                ),
+            ]
+        );
+
+        let hits = apk.detect_frameworks();
+        assert!(hits[0].is_confirmed());
+        assert!(!hits[1].is_confirmed());
+    }
+
+    #[test]
+    fn test_framework_from_packages_only() {
+        let apk = apk(&[], vec![dex(&["com.facebook.react.ReactActivity"])]);
+
+        let hits = apk.detect_frameworks();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].framework, Framework::ReactNative);
+        // a library bundling a part of react native, not an app built with it
+        assert!(!hits[0].is_confirmed());
+    }
+
+    #[test]
+    fn test_compose_version() {
+        let apk = apk(
+            &[],
+            vec![ZipFile::stored(COMPOSE_VERSION_FILE, b"1.6.8\n".to_vec())],
+        );
+
+        let hits = apk.detect_frameworks();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].framework, Framework::Compose);
+        assert_eq!(hits[0].version.as_deref(), Some("1.6.8"));
+    }
+
+    #[test]
+    fn test_no_frameworks() {
+        let apk = apk(
+            &[],
+            vec![
+                // not in an abi directory
+                file("lib/libflutter.so"),
+                file("assets/www/index.html"),
+                file("assets/index.android.bundle.map"),
+                dex(&[
+                    "com.facebook.reactor.Reactor",
+                    "io.flutter.Log",
+                    "com.example.kotlin.Helper",
+                ]),
+            ],
+        );
+
+        assert!(apk.detect_frameworks().is_empty());
+    }
+
+    #[test]
+    fn test_packer_confidence() {
+        let apk = apk(
+            &[("android:name", s("com.stub.StubApp"))],
+            vec![
+                file("lib/arm64-v8a/libjiagu_a64.so"),
+                file("assets/libjiagu.so"),
+                dex(&["com.stub.StubApp"]),
+            ],
+        );
+
+        let hits = apk.detect_packers();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].packer, Packer::Jiagu);
+        // 60 + 45 + 35 + 25, capped
+        assert_eq!(hits[0].confidence, 100);
+        assert_eq!(
+            hits[0].evidence,
+            [
+                "Lcom/stub/StubApp;",
+                "application: com.stub.StubApp",
+                "assets/libjiagu.so",
+                "lib/arm64-v8a/libjiagu_a64.so",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_packer_indicators() {
+        let library = apk(&[], vec![file("lib/armeabi-v7a/libshella-2.10.so")]);
+        assert_eq!(packers(&library), [(Packer::TencentLegu, LIBRARY_WEIGHT)]);
+
+        let asset = apk(&[], vec![file("assets/dp.arm64-v8a.so")]);
+        assert_eq!(packers(&asset), [(Packer::DexProtector, FILE_WEIGHT)]);
+
+        let factory = apk(
+            &[(
+                "android:appComponentFactory",
+                s("com.secneo.apkwrapper.ApplicationWrapper"),
+            )],
+            vec![],
+        );
+        assert_eq!(
+            packers(&factory),
+            [(Packer::Bangcle, APPLICATION_CLASS_WEIGHT)]
+        );
+    }
+
+    #[test]
+    fn test_packers_order() {
+        let apk = apk(
+            &[("android:name", s("com.baidu.protect.StubApplication"))],
+            vec![
+                file("lib/x86/libbaiduprotect.so"),
+                file("lib/x86/libappdome_core.so"),
+                file("assets/ijiami.dat"),
+            ],
+        );
+
+        assert_eq!(
+            packers(&apk),
+            [
+                (Packer::Baidu, 100),
+                (Packer::Appdome, LIBRARY_WEIGHT),
+                (Packer::Ijiami, FILE_WEIGHT),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_packers() {
+        let apk = apk(
+            &[("android:name", s("com.stub.StubApplication"))],
+            vec![
+                file("lib/arm64-v8a/libshell.so"),
+                file("assets/jiagu.txt"),
+                dex(&["com.stub.StubAppHelper", "com.tencent.mm.Main"]),
+            ],
+        );
+
+        assert!(apk.detect_packers().is_empty());
+    }
+}
@@ -0,0 +1,625 @@
+//! Incremental analysis of app updates.
+//!
+//! The [ApkReport] of the previous version is kept between runs (it is serializable),
+//! and the next version is analyzed against it: dex files with the same CRC-32 and sizes
+//! are not parsed again, their summaries are taken from the old report. Parsing the dex files
+//! is the expensive part, the manifest and the signatures are always read from the new apk.
+//!
+//! ```ignore
+//! let old: ApkReport = serde_json::from_str(&std::fs::read_to_string("report.json")?)?;
+//! let apk = Apk::new("./update.apk")?;
+//!
+//! let analysis = ApkDiffer::analyze_incremental(&old, &apk)?;
+//! println!("new classes: {:?}", analysis.diff.added_classes);
+//! std::fs::write("report.json", serde_json::to_string(&analysis.report)?)?;
+//! ```
+//!
+//! Values declared in the central directory are trusted, so the cache is meant for
+//! vendors tracking their own apps, not for hostile samples that can forge CRC-32.
+//!
+//! Two apks are compared directly with [ApkDiffer::compare], for example to vet an update
+//! or to spot a repackaged app: a changed signer with new permissions and components.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use apk_info_zip::Signature;
+use serde::{Deserialize, Serialize};
+
+use crate::apk::Apk;
+use crate::errors::APKError;
+use crate::models::ComponentKind;
+
+/// Identity of the zip entry from the central directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryDigest {
+    /// Declared CRC-32 of the uncompressed data.
+    pub crc32: u32,
+
+    /// Compressed size.
+    pub compressed_size: u64,
+
+    /// Uncompressed size.
+    pub uncompressed_size: u64,
+}
+
+/// Results of parsing a single `classes*.dex` file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DexSummary {
+    /// Names of the defined classes: `com.example.MainActivity`.
+    pub classes: Vec<String>,
+
+    /// Number of method references.
+    pub methods: usize,
+
+    /// Number of field references.
+    pub fields: usize,
+
+    /// Number of strings.
+    pub strings: usize,
+}
+
+/// Manifest component as it is compared between versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentDigest {
+    pub kind: ComponentKind,
+
+    /// Whether the component is available to other applications.
+    pub exported: bool,
+
+    /// Permission required from callers of the exported component.
+    pub permission: Option<String>,
+}
+
+/// Analysis results of the apk that can be reused by the next analysis.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApkReport {
+    /// Package name from the manifest.
+    pub package_name: Option<String>,
+
+    /// Version code from the manifest.
+    pub version_code: Option<String>,
+
+    /// Version name from the manifest.
+    pub version_name: Option<String>,
+
+    /// `minSdkVersion` from the manifest.
+    #[serde(default)]
+    pub min_sdk_version: Option<String>,
+
+    /// `targetSdkVersion` from the manifest.
+    #[serde(default)]
+    pub target_sdk_version: u32,
+
+    /// Requested permissions, sorted.
+    pub permissions: Vec<String>,
+
+    /// Sha256 fingerprints of the signer certificates (v1, v2, v3, v3.1), sorted.
+    pub certificates: Vec<String>,
+
+    /// Components of the manifest by the class name.
+    #[serde(default)]
+    pub components: BTreeMap<String, ComponentDigest>,
+
+    /// All entries of the archive.
+    pub entries: BTreeMap<String, EntryDigest>,
+
+    /// Parsed `classes*.dex` files.
+    pub dex: BTreeMap<String, DexSummary>,
+}
+
+/// Old and new value of the changed field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change<T> {
+    /// Value in the old report.
+    pub old: T,
+
+    /// Value in the new report.
+    pub new: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn between(old: T, new: T) -> Option<Change<T>> {
+        (old != new).then_some(Change { old, new })
+    }
+}
+
+/// Difference between two [ApkReport].
+///
+/// Changed scalar values are `Some`, lists contain sorted names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApkDiff {
+    pub package_name: Option<Change<Option<String>>>,
+    pub version_code: Option<Change<Option<String>>>,
+    pub version_name: Option<Change<Option<String>>>,
+    #[serde(default)]
+    pub min_sdk_version: Option<Change<Option<String>>>,
+    #[serde(default)]
+    pub target_sdk_version: Option<Change<u32>>,
+    pub certificates: Option<Change<Vec<String>>>,
+    pub added_permissions: Vec<String>,
+    pub removed_permissions: Vec<String>,
+    #[serde(default)]
+    pub added_components: Vec<String>,
+    #[serde(default)]
+    pub removed_components: Vec<String>,
+    /// Components whose kind, export status or permission changed.
+    #[serde(default)]
+    pub changed_components: BTreeMap<String, Change<ComponentDigest>>,
+    pub added_entries: Vec<String>,
+    pub removed_entries: Vec<String>,
+    pub changed_entries: Vec<String>,
+    pub added_classes: Vec<String>,
+    pub removed_classes: Vec<String>,
+}
+
+impl ApkDiff {
+    /// Returns `true` if the reports are the same.
+    pub fn is_empty(&self) -> bool {
+        *self == ApkDiff::default()
+    }
+}
+
+/// Result of [ApkDiffer::analyze_incremental].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncrementalAnalysis {
+    /// Fresh report of the new apk.
+    pub report: ApkReport,
+
+    /// Difference from the old report.
+    pub diff: ApkDiff,
+
+    /// Dex files whose summaries were taken from the old report.
+    pub reused_entries: Vec<String>,
+}
+
+/// Builds [ApkReport] and compares them, see the [module](self) docs.
+pub struct ApkDiffer;
+
+impl ApkDiffer {
+    /// Analyzes the apk completely.
+    pub fn analyze(apk: &Apk) -> Result<ApkReport, APKError> {
+        Self::analyze_with_cache(apk, None).map(|(report, _)| report)
+    }
+
+    /// Analyzes both apks and compares them.
+    pub fn compare(old_apk: &Apk, new_apk: &Apk) -> Result<ApkDiff, APKError> {
+        Ok(Self::diff(
+            &Self::analyze(old_apk)?,
+            &Self::analyze(new_apk)?,
+        ))
+    }
+
+    /// Analyzes the new apk, reusing summaries of unchanged dex files from the old report.
+    pub fn analyze_incremental(
+        old_report: &ApkReport,
+        new_apk: &Apk,
+    ) -> Result<IncrementalAnalysis, APKError> {
+        let (report, reused_entries) = Self::analyze_with_cache(new_apk, Some(old_report))?;
+        let diff = Self::diff(old_report, &report);
+
+        Ok(IncrementalAnalysis {
+            report,
+            diff,
+            reused_entries,
+        })
+    }
+
+    /// Compares two reports.
+    pub fn diff(old: &ApkReport, new: &ApkReport) -> ApkDiff {
+        let (added_permissions, removed_permissions) =
+            added_removed(old.permissions.iter(), new.permissions.iter());
+        let (added_entries, removed_entries) =
+            added_removed(old.entries.keys(), new.entries.keys());
+        let (added_classes, removed_classes) = added_removed(
+            old.dex.values().flat_map(|dex| &dex.classes),
+            new.dex.values().flat_map(|dex| &dex.classes),
+        );
+        let (added_components, removed_components) =
+            added_removed(old.components.keys(), new.components.keys());
+
+        let changed_components = new
+            .components
+            .iter()
+            .filter_map(|(name, component)| {
+                let old = old.components.get(name)?;
+                let change = Change::between(old.clone(), component.clone())?;
+                Some((name.clone(), change))
+            })
+            .collect();
+
+        let changed_entries = new
+            .entries
+            .iter()
+            .filter(|(name, digest)| old.entries.get(*name).is_some_and(|old| old != *digest))
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        ApkDiff {
+            package_name: Change::between(old.package_name.clone(), new.package_name.clone()),
+            version_code: Change::between(old.version_code.clone(), new.version_code.clone()),
+            version_name: Change::between(old.version_name.clone(), new.version_name.clone()),
+            min_sdk_version: Change::between(
+                old.min_sdk_version.clone(),
+                new.min_sdk_version.clone(),
+            ),
+            target_sdk_version: Change::between(old.target_sdk_version, new.target_sdk_version),
+            certificates: Change::between(old.certificates.clone(), new.certificates.clone()),
+            added_permissions,
+            removed_permissions,
+            added_components,
+            removed_components,
+            changed_components,
+            added_entries,
+            removed_entries,
+            changed_entries,
+            added_classes,
+            removed_classes,
+        }
+    }
+
+    fn analyze_with_cache(
+        apk: &Apk,
+        cache: Option<&ApkReport>,
+    ) -> Result<(ApkReport, Vec<String>), APKError> {
+        let entries: BTreeMap<String, EntryDigest> = apk
+            .namelist()
+            .filter_map(|name| {
+                let (compressed_size, uncompressed_size) = apk.get_file_sizes(name)?;
+                let digest = EntryDigest {
+                    crc32: apk.get_file_crc32(name)?,
+                    compressed_size,
+                    uncompressed_size,
+                };
+                Some((name.to_string(), digest))
+            })
+            .collect();
+
+        let mut dex = BTreeMap::new();
+        let mut reused_entries = Vec::new();
+
+        for name in apk.dex_names() {
+            let cached = cache
+                .filter(|cache| cache.entries.get(name) == entries.get(name))
+                .and_then(|cache| cache.dex.get(name));
+
+            let summary = match cached {
+                Some(summary) => {
+                    reused_entries.push(name.to_string());
+                    summary.clone()
+                }
+                None => {
+                    let parsed = apk.read_dex(name)?;
+                    DexSummary {
+                        classes: parsed
+                            .classes()
+                            .iter()
+                            .filter_map(|class| parsed.get_class_name(class.class_idx))
+                            .collect(),
+                        methods: parsed.method_ids().len(),
+                        fields: parsed.field_ids().len(),
+                        strings: parsed.strings_count(),
+                    }
+                }
+            };
+
+            dex.insert(name.to_string(), summary);
+        }
+
+        let mut permissions: Vec<String> = apk.get_permissions().map(String::from).collect();
+        permissions.sort_unstable();
+        permissions.dedup();
+
+        let report = ApkReport {
+            package_name: apk.get_package_name(),
+            version_code: apk.get_version_code(),
+            version_name: apk.get_version_name(),
+            min_sdk_version: apk.get_min_sdk_version(),
+            target_sdk_version: apk.get_target_sdk_version(),
+            permissions,
+            certificates: certificates(apk),
+            components: components(apk),
+            entries,
+            dex,
+        };
+
+        Ok((report, reused_entries))
+    }
+}
+
+/// Sha256 fingerprints of the signer certificates, broken signatures give an empty list.
+pub(crate) fn certificates(apk: &Apk) -> Vec<String> {
+    let Ok(signatures) = apk.get_signatures_ref() else {
+        return Vec::new();
+    };
+
+    signatures
+        .iter()
+        .filter_map(|signature| match signature {
+            Signature::V1(certificates)
+            | Signature::V2 { certificates, .. }
+            | Signature::V3 { certificates, .. }
+            | Signature::V31 { certificates, .. } => Some(certificates),
+            _ => None,
+        })
+        .flatten()
+        .map(|certificate| certificate.sha256_fingerprint.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Components of the manifest, the export status is resolved as Android does it.
+fn components(apk: &Apk) -> BTreeMap<String, ComponentDigest> {
+    let declared = apk
+        .get_activities()
+        .map(|activity| (ComponentKind::Activity, activity.name))
+        .chain(
+            apk.get_activity_aliases()
+                .map(|alias| (ComponentKind::ActivityAlias, alias.name)),
+        )
+        .chain(
+            apk.get_services()
+                .map(|service| (ComponentKind::Service, service.name)),
+        )
+        .chain(
+            apk.get_receivers()
+                .map(|receiver| (ComponentKind::Receiver, receiver.name)),
+        )
+        .chain(
+            apk.get_providers()
+                .map(|provider| (ComponentKind::Provider, provider.name)),
+        );
+
+    let mut components: BTreeMap<String, ComponentDigest> = declared
+        .filter_map(|(kind, name)| {
+            let digest = ComponentDigest {
+                kind,
+                exported: false,
+                permission: None,
+            };
+            Some((name?.to_string(), digest))
+        })
+        .collect();
+
+    for component in apk.get_exported_components() {
+        let Some(name) = component.name else {
+            continue;
+        };
+        components.insert(
+            name.to_string(),
+            ComponentDigest {
+                kind: component.kind,
+                exported: true,
+                permission: component.permission.map(String::from),
+            },
+        );
+    }
+
+    components
+}
+
+/// Returns sorted values only in `new` and only in `old`.
+fn added_removed<'a>(
+    old: impl Iterator<Item = &'a String>,
+    new: impl Iterator<Item = &'a String>,
+) -> (Vec<String>, Vec<String>) {
+    let old: BTreeSet<&String> = old.collect();
+    let new: BTreeSet<&String> = new.collect();
+
+    (
+        new.difference(&old).map(|s| s.to_string()).collect(),
+        old.difference(&new).map(|s| s.to_string()).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use apk_info_zip::ZipEntry;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::testing::*;
+
+    fn manifest(
+        version: (u32, &str),
+        sdk: (u32, u32),
+        permissions: &[&str],
+        application: Vec<Node>,
+    ) -> Node {
+        let mut children = vec![el(
+            "uses-sdk",
+            &[
+                ("android:minSdkVersion", Value::Int(sdk.0)),
+                ("android:targetSdkVersion", Value::Int(sdk.1)),
+            ],
+            vec![],
+        )];
+        children.extend(
+            permissions
+                .iter()
+                .map(|name| el("uses-permission", &[("android:name", s(name))], vec![])),
+        );
+        children.push(el("application", &[], application));
+
+        el(
+            "manifest",
+            &[
+                ("package", s("com.example.tests")),
+                ("android:versionCode", Value::Int(version.0)),
+                ("android:versionName", s(version.1)),
+            ],
+            children,
+        )
+    }
+
+    fn signed(manifest: &Node, certificate: &[u8]) -> Apk {
+        let block = signing_block(&[(
+            ZipEntry::SIGNATURE_SCHEME_V2_BLOCK_ID,
+            signers(&[signer_v2(&[certificate])]),
+        )]);
+        let files = [ZipFile::deflated(
+            "AndroidManifest.xml",
+            build_axml(manifest, AxmlOptions::default()),
+        )];
+        let options = ZipOptions {
+            signing_block: Some(&block),
+            ..Default::default()
+        };
+
+        Apk::from_bytes(build_zip(&files, options)).unwrap()
+    }
+
+    fn component(tag: &str, name: &str, attrs: &[(&str, Value)]) -> Node {
+        let mut all = vec![("android:name", s(name))];
+        all.extend_from_slice(attrs);
+        el(tag, &all, vec![])
+    }
+
+    #[test]
+    fn test_diff_versions() {
+        let old = apk_from_manifest(&manifest((1, "1.0"), (21, 30), &[], vec![]), None);
+        let new = apk_from_manifest(&manifest((2, "2.0"), (23, 34), &[], vec![]), None);
+
+        let diff = ApkDiffer::compare(&old, &new).unwrap();
+
+        let change = |old: &str, new: &str| {
+            Some(Change {
+                old: Some(old.to_string()),
+                new: Some(new.to_string()),
+            })
+        };
+        assert_eq!(diff.version_code, change("1", "2"));
+        assert_eq!(diff.version_name, change("1.0", "2.0"));
+        assert_eq!(diff.min_sdk_version, change("21", "23"));
+        assert_eq!(diff.target_sdk_version, Some(Change { old: 30, new: 34 }));
+        assert_eq!(diff.package_name, None);
+        assert!(diff.added_permissions.is_empty() && diff.changed_components.is_empty());
+    }
+
+    #[test]
+    fn test_diff_permissions() {
+        let old = manifest(
+            (1, "1.0"),
+            (21, 34),
+            &["android.permission.INTERNET", "android.permission.READ_SMS"],
+            vec![],
+        );
+        let new = manifest(
+            (1, "1.0"),
+            (21, 34),
+            &[
+                "android.permission.INTERNET",
+                "android.permission.CAMERA",
+                "android.permission.CAMERA",
+            ],
+            vec![],
+        );
+
+        let diff = ApkDiffer::compare(
+            &apk_from_manifest(&old, None),
+            &apk_from_manifest(&new, None),
+        )
+        .unwrap();
+
+        assert_eq!(diff.added_permissions, ["android.permission.CAMERA"]);
+        assert_eq!(diff.removed_permissions, ["android.permission.READ_SMS"]);
+        assert_eq!(diff.version_code, None);
+    }
+
+    #[test]
+    fn test_diff_components() {
+        let old = manifest(
+            (1, "1.0"),
+            (21, 34),
+            &[],
+            vec![
+                component(
+                    "activity",
+                    "Main",
+                    &[("android:exported", Value::Bool(true))],
+                ),
+                component("service", "Sync", &[]),
+                component("receiver", "Removed", &[]),
+            ],
+        );
+        let new = manifest(
+            (1, "1.0"),
+            (21, 34),
+            &[],
+            vec![
+                component(
+                    "activity",
+                    "Main",
+                    &[("android:exported", Value::Bool(true))],
+                ),
+                component(
+                    "service",
+                    "Sync",
+                    &[
+                        ("android:exported", Value::Bool(true)),
+                        ("android:permission", s("com.example.SYNC")),
+                    ],
+                ),
+                component("provider", "Added", &[]),
+            ],
+        );
+
+        let diff = ApkDiffer::compare(
+            &apk_from_manifest(&old, None),
+            &apk_from_manifest(&new, None),
+        )
+        .unwrap();
+
+        assert_eq!(diff.added_components, ["Added"]);
+        assert_eq!(diff.removed_components, ["Removed"]);
+        assert_eq!(diff.changed_components.len(), 1);
+        assert_eq!(
+            diff.changed_components["Sync"],
+            Change {
+                old: ComponentDigest {
+                    kind: ComponentKind::Service,
+                    exported: false,
+                    permission: None,
+                },
+                new: ComponentDigest {
+                    kind: ComponentKind::Service,
+                    exported: true,
+                    permission: Some("com.example.SYNC".to_string()),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_certificates() {
+        let manifest = manifest((1, "1.0"), (21, 34), &[], vec![]);
+        let fingerprint = |certificate: &[u8]| format!("{:x}", Sha256::digest(certificate));
+
+        let diff =
+            ApkDiffer::compare(&signed(&manifest, SIGNER_1), &signed(&manifest, SIGNER_2)).unwrap();
+        assert_eq!(
+            diff.certificates,
+            Some(Change {
+                old: vec![fingerprint(SIGNER_1)],
+                new: vec![fingerprint(SIGNER_2)],
+            })
+        );
+
+        let diff = ApkDiffer::compare(
+            &apk_from_manifest(&manifest, None),
+            &signed(&manifest, SIGNER_1),
+        )
+        .unwrap();
+        assert_eq!(
+            diff.certificates,
+            Some(Change {
+                old: vec![],
+                new: vec![fingerprint(SIGNER_1)],
+            })
+        );
+
+        let diff =
+            ApkDiffer::compare(&signed(&manifest, SIGNER_1), &signed(&manifest, SIGNER_1)).unwrap();
+        assert!(diff.is_empty());
+    }
+}
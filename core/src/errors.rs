@@ -5,10 +5,15 @@
 use std::io;
 
 use apk_info_axml::errors::{ARCSError, AXMLError};
+use apk_info_dex::errors::DexError;
 use apk_info_zip::{CertificateError, ZipError};
 use thiserror::Error;
 
 /// Possible `APK` errors
+///
+/// Every variant that wraps an error from the underlying parsers exposes it via
+/// [std::error::Error::source], so the full context chain can be printed with
+/// `{:#}` (anyhow) or [APKError::display_chain]. I/O errors are shown as they are.
 #[derive(Error, Debug)]
 pub enum APKError {
     /// Generic I/O error while trying to read or write data
@@ -20,20 +25,82 @@ pub enum APKError {
     InvalidInput(&'static str),
 
     /// Error occurred while parsing `AndroidManifest.xml`
-    #[error("got error while parsing AndroidManifest.xml: {0}")]
-    ManifestError(#[from] AXMLError),
+    #[error("AXML parse error in {entry}")]
+    ManifestError {
+        /// Path of the manifest inside the archive.
+        entry: String,
+
+        /// The underlying parsing error.
+        #[source]
+        source: AXMLError,
+    },
 
     /// Error occured while parsing `resources.arsc`
-    #[error("got error while parsing resources.arsc: {0}")]
-    ResourceError(#[from] ARCSError),
+    #[error("ARSC parse error in {entry}")]
+    ResourceError {
+        /// Path of the resource table inside the archive.
+        entry: String,
+
+        /// The underlying parsing error.
+        #[source]
+        source: ARCSError,
+    },
+
+    /// Error occurred while parsing `classes*.dex`
+    #[error("DEX parse error in {entry}")]
+    DexError {
+        /// Path of the dex file inside the archive.
+        entry: String,
 
-    #[error("got error while parsing manifest.json inside xapk: {0}")]
+        /// The underlying parsing error.
+        #[source]
+        source: DexError,
+    },
+
+    #[error("got error while parsing manifest.json inside xapk")]
     XAPKManifestError(#[from] serde_json::error::Error),
 
     /// Error occurred while parsing apk as zip archive
-    #[error("got error while parsing apk archive: {0}")]
+    #[error("got error while parsing apk archive")]
     ZipError(#[from] ZipError),
 
-    #[error("got error while parsing certificates: {0}")]
+    #[error("got error while parsing certificates")]
     CertificateError(#[from] CertificateError),
+
+    /// The baseline of known apps is not valid json or has invalid fingerprints,
+    /// see [crate::baseline::Baseline::new]
+    #[error("invalid baseline")]
+    BaselineError {
+        #[source]
+        source: serde_json::error::Error,
+    },
+
+    /// The search pattern is not a valid regular expression
+    #[error("invalid regular expression")]
+    InvalidPattern(#[from] regex::Error),
+
+    /// The operation was cancelled via `progress::CancellationToken` (`experimental` feature)
+    #[error("operation was cancelled")]
+    Cancelled,
+}
+
+impl APKError {
+    /// Formats the error together with all of its sources.
+    ///
+    /// ```ignore
+    /// // AXML parse error in AndroidManifest.xml: failed to parse XML tree at chunk 0x102 offset 0x1a2f
+    /// eprintln!("{}", err.display_chain());
+    /// ```
+    pub fn display_chain(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = std::error::Error::source(self);
+
+        while let Some(err) = source {
+            message.push_str(": ");
+            message.push_str(&err.to_string());
+            source = err.source();
+        }
+
+        message
+    }
 }
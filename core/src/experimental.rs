@@ -0,0 +1,14 @@
+//! Experimental api, enabled by the `experimental` feature.
+//!
+//! Analysis passes and parser internals that are still being shaped. They may change
+//! or disappear in any minor release, pin the exact version of the crate when
+//! depending on them.
+//!
+//! ```ignore
+//! use apk_info::experimental::carve::carve;
+//! ```
+
+pub use apk_info_dex as dex;
+
+pub use crate::models::{PackageProfile, RestrictedApiUsage, StringUsage};
+pub use crate::{analysis, audit, carve, detection, hiddenapi, native, perf, progress};
@@ -0,0 +1,120 @@
+//! Database of restricted (non-SDK) Android apis.
+//!
+//! Android doesn't ship the restrictions with the apk, they are built into the platform libraries.
+//! The database can be loaded either from `hiddenapi-flags.csv` produced by the platform build,
+//! or from the platform dex files themselves (`framework.jar`, `core-oj.jar` pulled from a device),
+//! which carry the flags in `hiddenapi_class_data_item`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use apk_info_dex::Dex;
+use apk_info_dex::structs::HiddenApiRestriction;
+use apk_info_zip::ZipEntry;
+
+use crate::errors::APKError;
+use crate::logging::warn;
+
+/// Maps member signatures (`Landroid/app/Activity;->mResumed:Z`) to their restrictions.
+#[derive(Debug, Default, Clone)]
+pub struct HiddenApiDatabase {
+    members: HashMap<String, HiddenApiRestriction>,
+}
+
+impl HiddenApiDatabase {
+    /// Loads the database from a file, the format is detected by the content:
+    /// dex file, jar/apk with dex files or `hiddenapi-flags.csv`.
+    ///
+    /// ```ignore
+    /// let database = HiddenApiDatabase::load("./hiddenapi-flags.csv")?;
+    /// ```
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<HiddenApiDatabase, APKError> {
+        let path = path.as_ref();
+        let data = std::fs::read(path).map_err(APKError::IoError)?;
+        let mut database = HiddenApiDatabase::default();
+
+        if data.starts_with(b"dex\n") {
+            let dex = Dex::new(data).map_err(|source| APKError::DexError {
+                entry: path.display().to_string(),
+                source,
+            })?;
+            database.add_dex(&dex)?;
+        } else if data.starts_with(b"PK\x03\x04") {
+            let zip = ZipEntry::new(data)?;
+            let mut names: Vec<&str> = zip
+                .namelist()
+                .filter(|name| name.ends_with(".dex"))
+                .collect();
+            names.sort_unstable();
+
+            for name in names {
+                let (data, _) = zip.read(name)?;
+                let dex = Dex::new(data).map_err(|source| APKError::DexError {
+                    entry: name.to_string(),
+                    source,
+                })?;
+                database.add_dex(&dex)?;
+            }
+        } else {
+            database.add_csv(&String::from_utf8_lossy(&data));
+        }
+
+        Ok(database)
+    }
+
+    /// Adds members from `hiddenapi-flags.csv`.
+    ///
+    /// Each line is a signature followed by flags: `Landroid/app/Activity;->mResumed:Z,unsupported`.
+    /// Lines without a known restriction are skipped.
+    pub fn add_csv(&mut self, data: &str) {
+        for line in data.lines() {
+            let mut parts = line.trim().split(',');
+            let Some(signature) = parts.next().filter(|s| !s.is_empty()) else {
+                continue;
+            };
+
+            match parts.find_map(HiddenApiRestriction::from_name) {
+                Some(restriction) => {
+                    self.members.insert(signature.to_string(), restriction);
+                }
+                None => warn!("no known hidden api restriction for {signature}"),
+            }
+        }
+    }
+
+    /// Adds members from the dex file with `hiddenapi_class_data_item`.
+    ///
+    /// Dex files without the section (for example, regular application code) add nothing.
+    pub fn add_dex(&mut self, dex: &Dex) -> Result<(), APKError> {
+        let members = dex
+            .hiddenapi_members()
+            .map_err(|source| APKError::DexError {
+                entry: "hiddenapi".to_string(),
+                source,
+            })?;
+
+        for (signature, flags) in members {
+            self.members.insert(signature, flags.restriction());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the restriction of the member by its signature.
+    #[inline]
+    pub fn get(&self, signature: &str) -> Option<HiddenApiRestriction> {
+        self.members.get(signature).copied()
+    }
+
+    /// Returns the number of known members.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns `true` if the database has no members.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
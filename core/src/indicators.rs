@@ -0,0 +1,749 @@
+//! Indicators extracted from the apk and their export to STIX 2.1.
+//!
+//! Network indicators, emails and wallets are searched in the strings of dex files and
+//! resources, and in printable strings of native libraries and assets.
+//!
+//! Only cyber-observable objects are emitted (`file`, `software`, `url`, `ipv4-addr`,
+//! `domain-name`, `email-addr`, `x509-certificate`), they don't carry timestamps, and their ids are deterministic as the specification
+//! recommends, so the same apk always produces the same bundle.
+//!
+//! ```ignore
+//! let indicators = Indicators::collect(&apk)?;
+//! println!("{}", indicators.to_stix_bundle());
+//! ```
+//!
+//! See: <https://docs.oasis-open.org/cti/stix/v2.1/stix-v2.1.html>
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::net::Ipv4Addr;
+use std::sync::LazyLock;
+
+use apk_info_zip::Signature;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+use crate::apk::Apk;
+use crate::errors::APKError;
+use crate::logging::warn;
+
+/// Namespace for deterministic ids of cyber-observable objects, defined by the specification.
+const STIX_NAMESPACE: [u8; 16] = [
+    0x00, 0xab, 0xed, 0xb4, 0xaa, 0x42, 0x46, 0x6c, 0x9c, 0x01, 0xfe, 0xd2, 0x33, 0x15, 0xa9, 0xb7,
+];
+
+const APK_MIME_TYPE: &str = "application/vnd.android.package-archive";
+
+static URL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\b(?:https?|wss?|ftp)://[^\s"'<>\\]+"#).expect("valid url regex")
+});
+
+/// Octets are 0-255 without leading zeros, the surrounding characters are checked
+/// in [Extracted::scan] since the regex crate has no lookaround.
+static IPV4_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b",
+    )
+    .expect("valid ipv4 regex")
+});
+
+static EMAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b")
+        .expect("valid email regex")
+});
+
+/// Patterns of wallet addresses, base58 ones are checked with [is_base58check] afterwards.
+static WALLET_REGEXES: LazyLock<Vec<(Currency, Regex)>> = LazyLock::new(|| {
+    [
+        (Currency::Bitcoin, r"\b[13][1-9A-HJ-NP-Za-km-z]{25,34}\b"),
+        (
+            Currency::Bitcoin,
+            r"\bbc1[qpzry9x8gf2tvdw0s3jn54khce6mua7l]{39,59}\b",
+        ),
+        (Currency::Ethereum, r"\b0x[0-9a-fA-F]{40}\b"),
+        (Currency::Tron, r"\bT[1-9A-HJ-NP-Za-km-z]{33}\b"),
+        (Currency::Monero, r"\b[48][0-9AB][1-9A-HJ-NP-Za-km-z]{93}\b"),
+    ]
+    .into_iter()
+    .map(|(currency, pattern)| (currency, Regex::new(pattern).expect("valid wallet regex")))
+    .collect()
+});
+
+/// Minimal length of printable strings taken from native libraries and assets.
+const MIN_STRING_LENGTH: usize = 6;
+
+/// Signer certificate of the apk.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CertificateIndicator {
+    pub sha256: String,
+    pub serial_number: String,
+    pub subject: String,
+    pub issuer: String,
+}
+
+/// File inside the apk.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct FileIndicator {
+    pub name: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Cryptocurrency of the wallet address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Currency {
+    Bitcoin,
+    Ethereum,
+    Monero,
+    Tron,
+}
+
+/// Cryptocurrency wallet address, base58 addresses of Bitcoin and Tron have a valid checksum.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct WalletIndicator {
+    pub currency: Currency,
+    pub address: String,
+}
+
+/// Indicators of the apk, see the [module](self) docs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Indicators {
+    /// Name of the analyzed file, not known to the parser and set by the caller.
+    pub file_name: Option<String>,
+
+    /// Sha256 of the whole file.
+    pub sha256: String,
+
+    /// Size of the whole file.
+    pub size: u64,
+
+    pub package_name: Option<String>,
+    pub version_name: Option<String>,
+
+    /// Unique signer certificates of all signature schemes.
+    pub certificates: Vec<CertificateIndicator>,
+
+    /// Urls, sorted.
+    pub urls: Vec<String>,
+
+    /// IPv4 addresses from the strings and the urls except loopback and unspecified, sorted.
+    pub ips: Vec<String>,
+
+    /// Hosts of the urls, sorted.
+    ///
+    /// Bare domains aren't searched, they can't be told apart from java package names.
+    pub domains: Vec<String>,
+
+    /// Email addresses, sorted.
+    pub emails: Vec<String>,
+
+    /// Cryptocurrency wallet addresses, sorted. They aren't exported to STIX,
+    /// the specification has no object for them.
+    pub wallets: Vec<WalletIndicator>,
+
+    /// Files of the archive except `res/` and `META-INF/`, which are mostly noise.
+    pub files: Vec<FileIndicator>,
+
+    /// Names of activities, services, receivers and providers.
+    pub components: Vec<String>,
+}
+
+impl Indicators {
+    /// Collects indicators from the apk, dex files, native libraries and assets are read to
+    /// search their strings.
+    ///
+    /// Broken signatures, dex files and entries that can't be read are skipped.
+    pub fn collect(apk: &Apk) -> Result<Indicators, APKError> {
+        let data = apk.data();
+
+        let certificates: BTreeSet<CertificateIndicator> = apk
+            .get_signatures_ref()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|signature| match signature {
+                Signature::V1(certificates)
+                | Signature::V2 { certificates, .. }
+                | Signature::V3 { certificates, .. }
+                | Signature::V31 { certificates, .. } => Some(certificates),
+                _ => None,
+            })
+            .flatten()
+            .map(|certificate| CertificateIndicator {
+                sha256: certificate.sha256_fingerprint.clone(),
+                serial_number: certificate.serial_number.clone(),
+                subject: certificate.subject.clone(),
+                issuer: certificate.issuer.clone(),
+            })
+            .collect();
+
+        let mut strings = Extracted::default();
+        if let Some(arsc) = apk.get_resource_table() {
+            arsc.strings().for_each(|value| strings.scan(value));
+        }
+        let per_dex = apk.map_dex(|name| {
+            let mut extracted = Extracted::default();
+            match apk.read_dex(name) {
+                Ok(dex) => dex.strings().for_each(|value| extracted.scan(&value)),
+                Err(e) => warn!(
+                    "can't parse {name}, strings are skipped: {}",
+                    e.display_chain()
+                ),
+            }
+            extracted
+        });
+        per_dex
+            .into_iter()
+            .for_each(|extracted| strings.merge(extracted));
+
+        for name in apk.namelist() {
+            let is_native = name.starts_with("lib/") && name.ends_with(".so");
+            if !is_native && !name.starts_with("assets/") {
+                continue;
+            }
+
+            match apk.read_borrowed(name) {
+                Ok((content, _)) => {
+                    printable_strings(&content).for_each(|value| strings.scan(value))
+                }
+                Err(e) => warn!("can't read {name}, strings are skipped: {e}"),
+            }
+        }
+
+        let mut files = Vec::new();
+        for name in apk.namelist() {
+            if name.ends_with('/') || name.starts_with("res/") || name.starts_with("META-INF/") {
+                continue;
+            }
+
+            let content = match apk.read(name) {
+                Ok((content, _)) => content,
+                Err(e) => {
+                    warn!("can't read {name}, the file is skipped: {e}");
+                    continue;
+                }
+            };
+            files.push(FileIndicator {
+                name: name.to_string(),
+                sha256: hex_sha256(&content),
+                size: content.len() as u64,
+            });
+        }
+        files.sort_unstable();
+
+        let components = apk
+            .get_activities()
+            .filter_map(|activity| activity.name)
+            .chain(apk.get_services().filter_map(|service| service.name))
+            .chain(apk.get_receivers().filter_map(|receiver| receiver.name))
+            .chain(apk.get_providers().filter_map(|provider| provider.name))
+            .map(String::from)
+            .collect();
+
+        Ok(Indicators {
+            file_name: None,
+            sha256: hex_sha256(data),
+            size: data.len() as u64,
+            package_name: apk.get_package_name(),
+            version_name: apk.get_version_name(),
+            certificates: certificates.into_iter().collect(),
+            urls: strings.urls.into_iter().collect(),
+            ips: strings.ips.into_iter().collect(),
+            domains: strings.domains.into_iter().collect(),
+            emails: strings.emails.into_iter().collect(),
+            wallets: strings.wallets.into_iter().collect(),
+            files,
+            components,
+        })
+    }
+
+    /// Converts the indicators into a STIX 2.1 bundle.
+    pub fn to_stix_bundle(&self) -> Value {
+        let mut objects = Vec::new();
+
+        let inner_files: Vec<Value> = self
+            .files
+            .iter()
+            .map(|file| {
+                stix_object(
+                    "file",
+                    json!({ "hashes": { "SHA-256": file.sha256 } }),
+                    json!({ "name": file.name, "size": file.size }),
+                )
+            })
+            .collect();
+
+        let mut apk_file = json!({
+            "mime_type": APK_MIME_TYPE,
+            "size": self.size,
+        });
+        if let Some(file_name) = &self.file_name {
+            apk_file["name"] = json!(file_name);
+        }
+        if !inner_files.is_empty() {
+            apk_file["extensions"] = json!({
+                "archive-ext": {
+                    "contains_refs": inner_files.iter().map(|file| &file["id"]).collect::<Vec<_>>(),
+                },
+            });
+        }
+        objects.push(stix_object(
+            "file",
+            json!({ "hashes": { "SHA-256": self.sha256 } }),
+            apk_file,
+        ));
+
+        if let Some(package_name) = &self.package_name {
+            let mut software = json!({ "name": package_name });
+            if let Some(version) = &self.version_name {
+                software["version"] = json!(version);
+            }
+
+            let mut properties = json!({});
+            if !self.components.is_empty() {
+                properties["x_android_components"] = json!(self.components);
+            }
+
+            objects.push(stix_object("software", software, properties));
+        }
+
+        for certificate in &self.certificates {
+            objects.push(stix_object(
+                "x509-certificate",
+                json!({ "hashes": { "SHA-256": certificate.sha256 } }),
+                json!({
+                    "serial_number": certificate.serial_number,
+                    "subject": certificate.subject,
+                    "issuer": certificate.issuer,
+                }),
+            ));
+        }
+
+        for url in &self.urls {
+            objects.push(stix_object("url", json!({ "value": url }), json!({})));
+        }
+        for ip in &self.ips {
+            objects.push(stix_object("ipv4-addr", json!({ "value": ip }), json!({})));
+        }
+        for domain in &self.domains {
+            objects.push(stix_object(
+                "domain-name",
+                json!({ "value": domain }),
+                json!({}),
+            ));
+        }
+        for email in &self.emails {
+            objects.push(stix_object(
+                "email-addr",
+                json!({ "value": email }),
+                json!({}),
+            ));
+        }
+
+        objects.extend(inner_files);
+
+        json!({
+            "type": "bundle",
+            "id": format!("bundle--{}", uuid_v5(self.sha256.as_bytes())),
+            "objects": objects,
+        })
+    }
+}
+
+/// Indicators found in strings, merged from all sources.
+#[derive(Default)]
+struct Extracted {
+    urls: BTreeSet<String>,
+    ips: BTreeSet<String>,
+    domains: BTreeSet<String>,
+    emails: BTreeSet<String>,
+    wallets: BTreeSet<WalletIndicator>,
+}
+
+impl Extracted {
+    fn scan(&mut self, value: &str) {
+        for url in URL_REGEX.find_iter(value).map(|m| m.as_str()) {
+            match url_host(url) {
+                Some(host) if host.parse::<Ipv4Addr>().is_ok() => self.add_ip(host),
+                Some(host) if host.contains('.') => {
+                    self.domains.insert(host.to_ascii_lowercase());
+                }
+                _ => {}
+            }
+            self.urls.insert(url.to_string());
+        }
+
+        for ip in IPV4_REGEX.find_iter(value) {
+            // part of a longer dotted number, like the version 1.2.3.4.5
+            let before = value[..ip.start()].bytes().next_back();
+            let mut after = value[ip.end()..].bytes();
+            if before == Some(b'.')
+                || (after.next() == Some(b'.') && after.next().is_some_and(|b| b.is_ascii_digit()))
+            {
+                continue;
+            }
+
+            self.add_ip(ip.as_str());
+        }
+
+        // `https://user@host` has no email
+        self.emails.extend(
+            EMAIL_REGEX
+                .find_iter(value)
+                .filter(|m| !value[..m.start()].ends_with("://"))
+                .map(|m| m.as_str().to_string()),
+        );
+
+        for (currency, regex) in WALLET_REGEXES.iter() {
+            for address in regex.find_iter(value).map(|m| m.as_str()) {
+                let checked = match (currency, address.as_bytes()[0]) {
+                    (Currency::Bitcoin, b'1' | b'3') | (Currency::Tron, _) => {
+                        is_base58check(address)
+                    }
+                    _ => true,
+                };
+
+                if checked {
+                    self.wallets.insert(WalletIndicator {
+                        currency: *currency,
+                        address: address.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn add_ip(&mut self, value: &str) {
+        // version numbers like 1.2.300.4 aren't valid addresses
+        let Ok(ip) = value.parse::<Ipv4Addr>() else {
+            return;
+        };
+
+        if !ip.is_loopback() && !ip.is_unspecified() && !ip.is_broadcast() {
+            self.ips.insert(ip.to_string());
+        }
+    }
+
+    fn merge(&mut self, other: Extracted) {
+        self.urls.extend(other.urls);
+        self.ips.extend(other.ips);
+        self.domains.extend(other.domains);
+        self.emails.extend(other.emails);
+        self.wallets.extend(other.wallets);
+    }
+}
+
+/// `https://user@example.com:8080/path` -> `example.com`
+fn url_host(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = host.split(':').next()?;
+
+    (!host.is_empty()).then_some(host)
+}
+
+/// Runs of printable ascii characters, like `strings` does.
+fn printable_strings(data: &[u8]) -> impl Iterator<Item = &str> {
+    data.split(|b| !(b' '..=b'~').contains(b))
+        .filter(|run| run.len() >= MIN_STRING_LENGTH)
+        // the runs are ascii
+        .filter_map(|run| std::str::from_utf8(run).ok())
+}
+
+/// Checks the base58 address with the checksum: the last 4 bytes are the beginning of
+/// double sha256 of the rest.
+fn is_base58check(address: &str) -> bool {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    // big-endian number, every character multiplies it by 58
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in address.bytes() {
+        let Some(mut carry) = ALPHABET.iter().position(|&a| a == c) else {
+            return false;
+        };
+        for byte in bytes.iter_mut().rev() {
+            carry += usize::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    // leading '1' are leading zero bytes
+    let zeros = address.bytes().take_while(|&c| c == b'1').count();
+    let mut decoded = vec![0u8; zeros];
+    decoded.extend(bytes);
+
+    if decoded.len() != 25 {
+        return false;
+    }
+
+    let (payload, checksum) = decoded.split_at(21);
+    Sha256::digest(Sha256::digest(payload))[..4] == *checksum
+}
+
+/// Builds the cyber-observable object, the id is derived from `id_properties`.
+fn stix_object(kind: &str, id_properties: Value, properties: Value) -> Value {
+    let mut canonical = String::new();
+    write_canonical(&mut canonical, &id_properties);
+    let id = uuid_v5(canonical.as_bytes());
+
+    let mut object = json!({
+        "type": kind,
+        "spec_version": "2.1",
+        "id": format!("{kind}--{id}"),
+    });
+    merge(&mut object, id_properties);
+    merge(&mut object, properties);
+
+    object
+}
+
+/// Serializes the value in the JSON canonical form (RFC 8785) the specification requires
+/// for the ids: no whitespace and object keys sorted.
+///
+/// Only strings, integers and nested objects and arrays of them are used in the ids,
+/// their serde_json representation is already canonical.
+fn write_canonical(out: &mut String, value: &Value) {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::from(key.as_str()).to_string());
+                out.push(':');
+                write_canonical(out, value);
+            }
+            out.push('}');
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(out, value);
+            }
+            out.push(']');
+        }
+        value => out.push_str(&value.to_string()),
+    }
+}
+
+fn merge(object: &mut Value, properties: Value) {
+    if let (Some(object), Value::Object(properties)) = (object.as_object_mut(), properties) {
+        object.extend(properties);
+    }
+}
+
+/// Name-based uuid in the STIX namespace, see RFC 9562.
+fn uuid_v5(name: &[u8]) -> String {
+    let digest = Sha1::new()
+        .chain_update(STIX_NAMESPACE)
+        .chain_update(name)
+        .finalize();
+
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x50;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = hex(&bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, b| {
+        _ = write!(out, "{b:02x}");
+        out
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(value: &str) -> Extracted {
+        let mut extracted = Extracted::default();
+        extracted.scan(value);
+        extracted
+    }
+
+    fn wallets(value: &str) -> Vec<(Currency, String)> {
+        scan(value)
+            .wallets
+            .into_iter()
+            .map(|wallet| (wallet.currency, wallet.address))
+            .collect()
+    }
+
+    #[test]
+    fn test_urls() {
+        let extracted = scan(
+            "api https://API.Example.com/v1 ws wss://10.1.2.3:443/socket local http://localhost/x",
+        );
+
+        assert_eq!(
+            extracted.urls.into_iter().collect::<Vec<_>>(),
+            [
+                "http://localhost/x",
+                "https://API.Example.com/v1",
+                "wss://10.1.2.3:443/socket"
+            ]
+        );
+        // hosts without a dot aren't domains, ip hosts are ips
+        assert_eq!(
+            extracted.domains.into_iter().collect::<Vec<_>>(),
+            ["api.example.com"]
+        );
+        assert_eq!(extracted.ips.into_iter().collect::<Vec<_>>(), ["10.1.2.3"]);
+
+        assert!(
+            scan("com.example.app file:///sdcard/a mailto:a")
+                .urls
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://user@example.com:8080/path"),
+            Some("example.com")
+        );
+        assert_eq!(url_host("http://example.com?q=a@b"), Some("example.com"));
+        assert_eq!(url_host("ftp://host#fragment"), Some("host"));
+        assert_eq!(url_host("https:///path"), None);
+        assert_eq!(url_host("example.com"), None);
+    }
+
+    #[test]
+    fn test_ips() {
+        let extracted = scan("peers 8.8.8.8, 172.16.0.1:53 and (192.168.0.1)");
+        assert_eq!(
+            extracted.ips.into_iter().collect::<Vec<_>>(),
+            ["172.16.0.1", "192.168.0.1", "8.8.8.8"]
+        );
+
+        for value in [
+            "127.0.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "version 1.2.3.4.5",
+            "build 2.1.2.3.4",
+            "octet 256.1.1.1",
+            "padded 01.02.03.04",
+        ] {
+            assert!(scan(value).ips.is_empty(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_emails() {
+        let extracted = scan("contact Support.Team+apk@mail.example.co.uk or admin@example.org.");
+        assert_eq!(
+            extracted.emails.into_iter().collect::<Vec<_>>(),
+            ["Support.Team+apk@mail.example.co.uk", "admin@example.org"]
+        );
+
+        for value in [
+            "https://user@example.com",
+            "root@localhost",
+            "@example.com",
+            "a@b.c",
+        ] {
+            assert!(scan(value).emails.is_empty(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_wallets() {
+        let monero = format!("4A{}", "1".repeat(93));
+
+        assert_eq!(
+            wallets(&format!(
+                "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa \
+                 bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq \
+                 0x52908400098527886E0F7030069857D2E4169EE7 \
+                 TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t {monero}"
+            )),
+            [
+                (
+                    Currency::Bitcoin,
+                    "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".to_string()
+                ),
+                (
+                    Currency::Bitcoin,
+                    "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string()
+                ),
+                (
+                    Currency::Ethereum,
+                    "0x52908400098527886E0F7030069857D2E4169EE7".to_string()
+                ),
+                (Currency::Monero, monero.clone()),
+                (
+                    Currency::Tron,
+                    "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".to_string()
+                ),
+            ]
+        );
+
+        for value in [
+            // broken checksums
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb",
+            "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6u",
+            // too short, or part of a longer word
+            "0x52908400098527886E0F7030069857D2E4169E",
+            "0x52908400098527886E0F7030069857D2E4169EE7AB",
+            // `0` and `O` aren't base58
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfN0",
+        ] {
+            assert!(wallets(value).is_empty(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_is_base58check() {
+        assert!(is_base58check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"));
+        assert!(is_base58check("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"));
+        assert!(!is_base58check("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLz"));
+        assert!(!is_base58check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfN"));
+        assert!(!is_base58check("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNl"));
+        assert!(!is_base58check(""));
+    }
+
+    #[test]
+    fn test_printable_strings() {
+        let data = b"\x00\x01short\x00long enough\xff\x7fhttps://example.com\n12345";
+
+        assert_eq!(
+            printable_strings(data).collect::<Vec<_>>(),
+            ["long enough", "https://example.com"]
+        );
+    }
+}
@@ -6,6 +6,7 @@
 //! - A malware-friendly axml and arsc extractor;
 //! - A full AXML (Android Binary XML) implementation;
 //! - A full ARSC (Android Resource) implementation;
+//! - A malware-friendly DEX (Dalvik Executable) parser with hiddenapi flags support;
 //! - Support for extracting information contained in the `APK Signature Block 42`:
 //!     - [APK Signature scheme v1](https://source.android.com/docs/security/features/apksigning);
 //!     - [APK Signature scheme v2](https://source.android.com/docs/security/features/apksigning/v2);
@@ -39,11 +40,77 @@
 //! println!("{}/{}", package_name, main_activity);
 //! ```
 
+//!
+//! Most commonly used types are available from the [prelude]:
+//!
+//! ```no_run
+//! use apk_info::prelude::*;
+//! ```
+//!
+//! ## Stability
+//!
+//! - [stable] - getters, models, signatures and reports, they follow semver;
+//! - `experimental` - analysis passes and dex internals, they may change in minor releases
+//!   and require the `experimental` feature: carving, code analysis and detections, hidden api,
+//!   native libraries, performance statistics, progress reporting and the `dex` crate.
+//!
+//! Other top-level modules are kept for compatibility, new code should prefer the ones above.
+
+// fixture builders of the integration tests refer to the crate by name
+#[cfg(test)]
+extern crate self as apk_info;
+
+#[cfg(feature = "experimental")]
+pub mod analysis;
+pub mod anomaly;
 pub mod apk;
+pub mod artifact;
+#[cfg(feature = "experimental")]
+pub mod audit;
+pub mod baseline;
+#[cfg(feature = "parallel")]
+pub mod batch;
+#[cfg(feature = "experimental")]
+pub mod carve;
+#[cfg(feature = "experimental")]
+pub mod detection;
+pub mod diff;
 pub mod errors;
+#[cfg(feature = "experimental")]
+pub mod experimental;
+#[cfg(feature = "experimental")]
+pub mod hiddenapi;
+pub mod indicators;
+mod logging;
 pub mod models;
+#[cfg(feature = "experimental")]
+pub mod native;
+#[cfg(feature = "experimental")]
+pub mod perf;
+// parse timings are collected anyway, only the report is experimental
+#[cfg(not(feature = "experimental"))]
+#[allow(dead_code)]
+mod perf;
+pub mod permissions;
+pub mod prelude;
+#[cfg(feature = "experimental")]
+pub mod progress;
+pub mod splits;
+pub mod stable;
+#[cfg(test)]
+#[path = "../tests/common/mod.rs"]
+mod testing;
+pub mod xapk;
 
 pub use apk::Apk;
 pub use apk_info_axml::*;
 pub use apk_info_zip::*;
 pub use errors::APKError;
+#[cfg(feature = "experimental")]
+pub use hiddenapi::HiddenApiDatabase;
+// internal crates, so there is no need to depend on them directly
+pub use apk_info_axml as axml;
+#[cfg(feature = "experimental")]
+pub use apk_info_dex as dex;
+pub use apk_info_xml as xml;
+pub use apk_info_zip as zip;
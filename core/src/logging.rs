@@ -0,0 +1,26 @@
+//! Wrappers of the `log` macros that are compiled out with the `quiet` feature.
+//!
+//! The feature is forwarded to the parser crates, so the whole library stays silent,
+//! anomalies are still available from [Apk::get_manifest_diagnostics](crate::Apk::get_manifest_diagnostics)
+//! and [Apk::get_archive_anomalies](crate::Apk::get_archive_anomalies).
+
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        if cfg!(not(feature = "quiet")) {
+            ::log::warn!($($arg)+)
+        }
+    };
+}
+
+#[cfg(feature = "experimental")]
+macro_rules! log_debug {
+    ($($arg:tt)+) => {
+        if cfg!(not(feature = "quiet")) {
+            ::log::debug!($($arg)+)
+        }
+    };
+}
+
+#[cfg(feature = "experimental")]
+pub(crate) use log_debug as debug;
+pub(crate) use log_warn as warn;
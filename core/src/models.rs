@@ -1,12 +1,101 @@
 //! Abstractions over `manifest` tags.
 
-use serde::{Deserialize, Serialize};
+use apk_info_zip::FileType;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::apk::parse_resource_int;
+use crate::permissions::ProtectionLevel;
 
 /// Represents xapk manifest.json
-#[derive(Deserialize)]
-pub struct XAPKManifest {
+///
+/// Only `package_name` is required, stores fill the rest differently.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct XapkManifest {
     /// Defined package name
     pub package_name: String,
+
+    /// Version of the format: 1 - single apk with obb, 2 - split apks
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub xapk_version: Option<String>,
+
+    /// Application name
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub version_code: Option<String>,
+
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub version_name: Option<String>,
+
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub min_sdk_version: Option<String>,
+
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub target_sdk_version: Option<String>,
+
+    /// Requested permissions, as declared by the store
+    #[serde(default)]
+    pub permissions: Vec<String>,
+
+    /// Names of the configuration splits: `config.arm64_v8a`, `config.en`
+    #[serde(default)]
+    pub split_configs: Vec<String>,
+
+    /// Path to the icon inside the bundle
+    #[serde(default)]
+    pub icon: Option<String>,
+
+    /// Inner apk files
+    #[serde(default)]
+    pub split_apks: Vec<XapkSplit>,
+
+    /// Expansion files (obb)
+    #[serde(default)]
+    pub expansions: Vec<XapkExpansion>,
+}
+
+/// Old name of [XapkManifest].
+#[deprecated(since = "1.1.0", note = "renamed to `XapkManifest`")]
+pub type XAPKManifest = XapkManifest;
+
+/// Represents an inner apk listed in xapk manifest.json
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct XapkSplit {
+    /// Path to the apk inside the bundle
+    pub file: String,
+
+    /// Split name: `base`, `config.arm64_v8a`, feature module name
+    #[serde(default)]
+    pub id: String,
+}
+
+/// Represents an expansion file listed in xapk manifest.json
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct XapkExpansion {
+    /// Path to the file inside the bundle
+    pub file: String,
+
+    /// Where the file is installed: `EXTERNAL_STORAGE`, `INTERNAL_STORAGE`
+    #[serde(default)]
+    pub install_location: Option<String>,
+
+    /// Installation path on the device: `Android/obb/<package>/main.1.<package>.obb`
+    #[serde(default)]
+    pub install_path: Option<String>,
+}
+
+/// Stores write versions and sdk levels both as strings and numbers.
+fn string_or_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+
+    Ok(match value {
+        Some(serde_json::Value::String(s)) => Some(s),
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
 }
 
 /// Represents `<intent-filter>` in manifest
@@ -23,6 +112,111 @@ pub struct IntentFilter<'a> {
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/category-element>
     pub categories: Vec<&'a str>,
+
+    /// A list of declared data specifications
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element>
+    pub data: Vec<IntentData<'a>>,
+
+    /// Whether the system should verify the app links of the filter.
+    ///
+    /// See: <https://developer.android.com/training/app-links/verify-android-applinks>
+    pub auto_verify: Option<&'a str>,
+}
+
+impl IntentFilter<'_> {
+    /// Returns URIs accepted by the filter: `https://example.com/path`.
+    ///
+    /// All `<data>` elements of the filter are merged, as the system does: every scheme is combined
+    /// with every host and every path. Path prefixes and patterns are returned as declared,
+    /// hosts and paths without a scheme match nothing and are ignored.
+    ///
+    /// ```ignore
+    /// for activity in apk.get_activities() {
+    ///     for filter in &activity.intent_filters {
+    ///         println!("{:?} {:?}", activity.name, filter.uris());
+    ///     }
+    /// }
+    /// ```
+    pub fn uris(&self) -> Vec<String> {
+        let schemes: Vec<&str> = self.data.iter().filter_map(|data| data.scheme).collect();
+        let hosts: Vec<String> = self
+            .data
+            .iter()
+            .filter_map(|data| match (data.host, data.port) {
+                (Some(host), Some(port)) => Some(format!("{host}:{port}")),
+                (Some(host), None) => Some(host.to_string()),
+                (None, _) => None,
+            })
+            .collect();
+        let paths: Vec<&str> = self
+            .data
+            .iter()
+            .flat_map(|data| [data.path, data.path_prefix, data.path_pattern])
+            .flatten()
+            .collect();
+
+        let mut uris = Vec::new();
+        for scheme in schemes {
+            if hosts.is_empty() {
+                uris.push(format!("{scheme}:"));
+                continue;
+            }
+
+            for host in &hosts {
+                if paths.is_empty() {
+                    uris.push(format!("{scheme}://{host}"));
+                }
+
+                for path in &paths {
+                    uris.push(format!("{scheme}://{host}{path}"));
+                }
+            }
+        }
+
+        uris
+    }
+}
+
+/// Represents `<data>` inside `<intent-filter>` in manifest
+///
+/// More information: <https://developer.android.com/guide/topics/manifest/data-element>
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct IntentData<'a> {
+    /// The scheme part of the URI: `https`, `myapp`
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element#scheme>
+    pub scheme: Option<&'a str>,
+
+    /// The host part of the URI authority, may start with a `*` wildcard.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element#host>
+    pub host: Option<&'a str>,
+
+    /// The port part of the URI authority.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element#port>
+    pub port: Option<&'a str>,
+
+    /// The complete path of the URI.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element#path>
+    pub path: Option<&'a str>,
+
+    /// The initial part of the path of the URI.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element#path>
+    pub path_prefix: Option<&'a str>,
+
+    /// The pattern of the path of the URI.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element#path>
+    pub path_pattern: Option<&'a str>,
+
+    /// The MIME media type, may contain the `*` subtype wildcard: `image/*`
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/data-element#mime>
+    pub mime_type: Option<&'a str>,
 }
 
 /// Represents `<activity>` in manifest
@@ -70,12 +264,96 @@ pub struct Activity<'a> {
     /// See: <https://developer.android.com/guide/topics/manifest/activity-element#proc>
     pub process: Option<&'a str>,
 
+    /// Whether the activity can move from the task that started it to the task it has an affinity for.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#reparent>
+    pub allow_task_reparenting: Option<&'a str>,
+
+    /// Configuration changes that the activity handles itself: `orientation|screenSize`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#config>
+    pub config_changes: Option<&'a str>,
+
+    /// Whether the activity can run before the user unlocks the device.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#directBootAware>
+    pub direct_boot_aware: Option<&'a str>,
+
+    /// How a new instance of the activity is added to the recents screen.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#dlmode>
+    pub document_launch_mode: Option<&'a str>,
+
+    /// Whether the task started by the activity is hidden from the recents screen.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#exclude>
+    pub exclude_from_recents: Option<&'a str>,
+
+    /// How the activity is launched: `standard`, `singleTop`, `singleTask`, `singleInstance` or `singleInstancePerTask`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#lmode>
+    pub launch_mode: Option<&'a str>,
+
+    /// Whether the activity is removed from the back stack when the user leaves it.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#nohist>
+    pub no_history: Option<&'a str>,
+
+    /// Whether the activity supports multi-window mode.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#resizeableActivity>
+    pub resizeable_activity: Option<&'a str>,
+
+    /// The orientation of the activity on the device: `portrait`, `landscape`, `sensor`, ....
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#screen>
+    pub screen_orientation: Option<&'a str>,
+
+    /// Whether the activity is shown on top of the lock screen.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#showWhenLocked>
+    pub show_when_locked: Option<&'a str>,
+
+    /// The task that the activity has an affinity for, affinity to other packages is used for task hijacking.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#aff>
+    pub task_affinity: Option<&'a str>,
+
+    /// A reference to a style resource defining the overall theme of the activity.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#theme>
+    pub theme: Option<&'a str>,
+
+    /// How the main window of the activity interacts with the on-screen soft keyboard.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/activity-element#wsoft>
+    pub window_soft_input_mode: Option<&'a str>,
+
     /// A list of all declared `<intent-filter>` for a given activity
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/intent-filter-element>
     pub intent_filters: Vec<IntentFilter<'a>>,
 }
 
+impl Activity<'_> {
+    /// Whether the activity can be launched by other applications.
+    ///
+    /// If `android:exported` is not set, the activity is exported when it declares intent filters
+    /// (this is the behavior before Android 12, where the attribute became mandatory).
+    pub fn is_exported(&self) -> bool {
+        match self.exported {
+            Some(v) => v == "true",
+            None => !self.intent_filters.is_empty(),
+        }
+    }
+
+    /// Whether the activity can be instantiated by the system (`true` by default).
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some("false")
+    }
+}
+
 /// Represents `<activity-alias>` in manifest
 ///
 /// More information: <https://developer.android.com/guide/topics/manifest/activity-alias-element>
@@ -122,6 +400,14 @@ pub struct ActivityAlias<'a> {
     pub intent_filters: Vec<IntentFilter<'a>>,
 }
 
+impl ActivityAlias<'_> {
+    /// Whether the target activity can be instantiated by the system (`true` by default).
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some("false")
+    }
+}
+
 /// Represents `<permission>` in manifest
 ///
 /// More information: <https://developer.android.com/guide/topics/manifest/permission-element>
@@ -163,6 +449,64 @@ pub struct Permission<'a> {
     pub protection_level: Option<&'a str>,
 }
 
+/// `<permission>` with the label and description resolved from resources and the parsed
+/// protection level, see [crate::Apk::get_declared_permissions_detailed].
+///
+/// More information: <https://developer.android.com/guide/topics/manifest/permission-element>
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeclaredPermission<'a> {
+    /// The name to be used in code to refer to the permission.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/permission-element#nm>
+    pub name: Option<&'a str>,
+
+    /// A user-readable name for the permission, references are resolved to the default value.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/permission-element#label>
+    pub label: Option<String>,
+
+    /// A user-readable description of the permission, references are resolved to the default value.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/permission-element#desc>
+    pub description: Option<String>,
+
+    /// A reference to a drawable resource for an icon that represents the permission.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/permission-element#icon>
+    pub icon: Option<&'a str>,
+
+    /// The `<permission-group>` the permission belongs to.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/permission-element#pgroup>
+    pub permission_group: Option<&'a str>,
+
+    /// The base protection level, `normal` if the attribute is not set.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/permission-element#plevel>
+    pub protection_level: ProtectionLevel,
+
+    /// Additional protection flags: `privileged`, `appop`, `development`, etc.
+    pub protection_flags: Vec<&'a str>,
+}
+
+/// Requested permissions grouped by their protection level.
+///
+/// See: [ProtectionLevel](crate::permissions::ProtectionLevel)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct PermissionsByProtection<'a> {
+    /// Runtime permissions, granted by the user
+    pub dangerous: Vec<&'a str>,
+
+    /// Install-time permissions
+    pub normal: Vec<&'a str>,
+
+    /// Signature and privileged permissions
+    pub signature: Vec<&'a str>,
+
+    /// Permissions of other apps or unknown to the bundled table
+    pub unknown: Vec<&'a str>,
+}
+
 /// Represents `<provider>` in manifest.
 ///
 /// More information: <https://developer.android.com/guide/topics/manifest/provider-element>
@@ -242,6 +586,37 @@ pub struct Provider<'a> {
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/provider-element#write>
     pub write_permission: Option<&'a str>,
+
+    /// A list of all declared `<intent-filter>` for a given provider
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/intent-filter-element>
+    pub intent_filters: Vec<IntentFilter<'a>>,
+}
+
+impl Provider<'_> {
+    /// Whether the provider is available for other applications.
+    ///
+    /// The default value depends on `targetSdkVersion` (`true` before 17), here only the
+    /// explicitly declared value is taken into account.
+    #[inline]
+    pub fn is_exported(&self) -> bool {
+        self.exported == Some("true")
+    }
+
+    /// Whether the provider can be instantiated by the system (`true` by default).
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some("false")
+    }
+
+    /// Returns the list of authorities, separated by semicolons in the manifest.
+    pub fn authorities(&self) -> impl Iterator<Item = &str> {
+        self.authorities
+            .into_iter()
+            .flat_map(|v| v.split(';'))
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    }
 }
 
 /// Represents `<service>` in manifest
@@ -308,6 +683,19 @@ pub struct Service<'a> {
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/service-element#stopWithTask>
     pub stop_with_task: Option<&'a str>,
+
+    /// A list of all declared `<intent-filter>` for a given service
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/intent-filter-element>
+    pub intent_filters: Vec<IntentFilter<'a>>,
+}
+
+impl Service<'_> {
+    /// Whether the service can be instantiated by the system (`true` by default).
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some("false")
+    }
 }
 
 /// Represents `<receiver>` in manifest
@@ -354,6 +742,19 @@ pub struct Receiver<'a> {
     ///
     /// See: <https://developer.android.com/guide/topics/manifest/receiver-element#proc>
     pub process: Option<&'a str>,
+
+    /// A list of all declared `<intent-filter>` for a given receiver
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/intent-filter-element>
+    pub intent_filters: Vec<IntentFilter<'a>>,
+}
+
+impl Receiver<'_> {
+    /// Whether the receiver can be instantiated by the system (`true` by default).
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != Some("false")
+    }
 }
 
 /// This helps trace data access back to logical parts of application code.
@@ -370,3 +771,403 @@ pub struct Attribution<'a> {
     /// See: <https://developer.android.com/guide/topics/manifest/attribution-element#label>
     pub label: Option<&'a str>,
 }
+
+/// Represents `<instrumentation>` in manifest: a test runner that is loaded into the
+/// target application before any of its components.
+///
+/// More information: <https://developer.android.com/guide/topics/manifest/instrumentation-element>
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Instrumentation<'a> {
+    /// The name of the class that implements the instrumentation: `androidx.test.runner.AndroidJUnitRunner`
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/instrumentation-element#nm>
+    pub name: Option<&'a str>,
+
+    /// The package of the application that is instrumented.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/instrumentation-element#trgt>
+    pub target_package: Option<&'a str>,
+
+    /// Whether the instrumentation turns profiling on and off.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/instrumentation-element#prof>
+    pub handle_profiling: Option<&'a str>,
+}
+
+/// Represents `<supports-screens>` in manifest.
+///
+/// More information: <https://developer.android.com/guide/topics/manifest/supports-screens-element>
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct SupportsScreens<'a> {
+    /// Whether the application is resizeable for different screen sizes (deprecated).
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#resizeable>
+    pub resizeable: Option<&'a str>,
+
+    /// Whether the application supports the smaller screen form-factors.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#small>
+    pub small_screens: Option<&'a str>,
+
+    /// Whether an application supports the "normal" screen form-factors.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#normal>
+    pub normal_screens: Option<&'a str>,
+
+    /// Whether the application supports larger screen form-factors.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#large>
+    pub large_screens: Option<&'a str>,
+
+    /// Whether the application supports extra large screen form-factors.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#xlarge>
+    pub xlarge_screens: Option<&'a str>,
+
+    /// Whether the application includes resources to accommodate any screen density.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#any>
+    pub any_density: Option<&'a str>,
+
+    /// The minimum `smallestWidth` required.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#requiresSmallest>
+    pub requires_smallest_width_dp: Option<&'a str>,
+
+    /// The maximum `smallestWidth` the application is designed for, compatibility mode is offered above it.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#compatibleWidth>
+    pub compatible_width_limit_dp: Option<&'a str>,
+
+    /// The maximum `smallestWidth` the application is designed for, compatibility mode is forced above it.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/supports-screens-element#largestWidth>
+    pub largest_width_limit_dp: Option<&'a str>,
+}
+
+/// Represents `<screen>` inside `<compatible-screens>` in manifest.
+///
+/// Google Play hides the application from devices with screens that are not listed.
+///
+/// More information: <https://developer.android.com/guide/topics/manifest/compatible-screens-element>
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct CompatibleScreen<'a> {
+    /// The screen size: `small`, `normal`, `large` or `xlarge`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/compatible-screens-element#screenSize>
+    pub screen_size: Option<&'a str>,
+
+    /// The screen density: `ldpi`, `mdpi`, `hdpi`, `xhdpi`, `xxhdpi`, `xxxhdpi` or the dpi value.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/compatible-screens-element#screenDensity>
+    pub screen_density: Option<&'a str>,
+}
+
+/// Represents `<uses-feature>` in manifest.
+///
+/// More information: <https://developer.android.com/guide/topics/manifest/uses-feature-element>
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Feature<'a> {
+    /// The hardware or software feature, not set when the element only declares `glEsVersion`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/uses-feature-element#name>
+    pub name: Option<&'a str>,
+
+    /// Whether the application can't function without the feature (`true` by default).
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/uses-feature-element#required>
+    pub required: Option<&'a str>,
+
+    /// The OpenGL ES version required, the major version in the upper 16 bits: `0x00030001` is 3.1.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/uses-feature-element#glEsVersion>
+    pub gl_es_version: Option<&'a str>,
+
+    /// The minimum version of the feature.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/uses-feature-element#version>
+    pub version: Option<&'a str>,
+}
+
+impl Feature<'_> {
+    /// Whether devices without the feature are filtered out (`true` by default).
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.required != Some("false")
+    }
+
+    /// The required OpenGL ES version as `(major, minor)`, values that aren't numbers give `None`.
+    pub fn opengl_es_version(&self) -> Option<(u16, u16)> {
+        let version = parse_resource_int(self.gl_es_version?)?;
+        let version = u32::try_from(version).ok()?;
+
+        Some(((version >> 16) as u16, version as u16))
+    }
+}
+
+/// Code statistics for a single package prefix, see [crate::Apk::package_profile].
+///
+/// Many short identifiers in a group usually mean the code was processed by an
+/// obfuscator (ProGuard, R8, DexGuard).
+#[cfg(feature = "experimental")]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PackageProfile {
+    /// The first two segments of the package (`com.google`), the single segment
+    /// for top-level packages (`a`) or an empty string for the default package.
+    pub prefix: String,
+
+    /// Number of defined classes, including inner ones.
+    pub classes: usize,
+
+    /// Number of defined methods, including constructors.
+    pub methods: usize,
+
+    /// Number of defined fields.
+    pub fields: usize,
+
+    /// Average length of class, method and field names (constructors are not counted).
+    pub average_identifier_length: f64,
+}
+
+/// Usage of a restricted (non-SDK) api, see [crate::Apk::restricted_api_usage].
+#[cfg(feature = "experimental")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestrictedApiUsage {
+    /// Signature of the member: `Landroid/app/Activity;->mResumed:Z`.
+    pub signature: String,
+
+    /// Kind of the member: `method` or `field`.
+    pub kind: &'static str,
+
+    /// Restriction from the database: `unsupported`, `blocked`, `max-target-o`, etc.
+    pub restriction: String,
+
+    /// Dex files that reference the member.
+    pub dex_files: Vec<String>,
+}
+
+/// String from the dex file and methods that load it, see [crate::Apk::find_string_usages].
+#[cfg(feature = "experimental")]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StringUsage {
+    /// The string itself.
+    pub string: String,
+
+    /// Dex file that contains the string.
+    pub dex_file: String,
+
+    /// Signatures of the methods that load the string with `const-string`:
+    /// `Lcom/example/Api;->connect()V`.
+    ///
+    /// Empty if the string is used only by annotations, field initializers or not used at all.
+    pub methods: Vec<String>,
+}
+
+/// Entry of the normalized file listing, see [Apk::get_files_normalized](crate::Apk::get_files_normalized).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct NormalizedFile {
+    /// Path inside the archive, directories end with `/`.
+    pub name: String,
+
+    /// Whether the entry is a directory.
+    pub is_directory: bool,
+
+    /// Whether the directory isn't present in the archive and was added as a parent of other entries.
+    pub synthesized: bool,
+
+    /// Number of central directory records with this name, `0` for synthesized directories.
+    ///
+    /// Values above `1` mean duplicated entries, only one of them is visible to the parser.
+    pub count: usize,
+}
+
+/// Estimated on-device and network sizes of the apk.
+///
+/// The values are computed from the central directory, so they are close to the
+/// numbers shown in Play Console, but not exact.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SizeEstimate {
+    /// Size of the data transferred over network: compressed entries with
+    /// their local headers, the central directory and the end of central directory.
+    pub download_size: u64,
+
+    /// Estimated size occupied on device after installation.
+    ///
+    /// Includes the apk itself, native libraries for a single ABI if they are extracted
+    /// (`android:extractNativeLibs`) and compressed dex files, which are extracted by ART.
+    pub install_size: u64,
+
+    /// Uncompressed size of all `classes*.dex` files.
+    pub dex_size: u64,
+
+    /// Uncompressed size of native libraries for the largest ABI.
+    pub native_libs_size: u64,
+
+    /// Uncompressed size of `resources.arsc`, `res/` and `assets/`.
+    pub resources_size: u64,
+
+    /// Whether native libraries are extracted on installation.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/application-element#extractNativeLibs>
+    pub extract_native_libs: bool,
+}
+
+/// ABIs the apk can be installed on, see [Apk::get_supported_abis](crate::Apk::get_supported_abis).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SupportedAbis {
+    /// ABIs with native libraries in `lib/`, sorted.
+    ///
+    /// Empty list means the apk has no native code and runs on any device.
+    pub abis: Vec<String>,
+
+    /// Whether native libraries are extracted on installation, `android:extractNativeLibs`.
+    ///
+    /// If not, they are loaded directly from the apk, so they must be stored uncompressed
+    /// and page aligned.
+    pub extract_native_libs: bool,
+
+    /// Whether libraries of all ABIs are installed, not only the primary one, `android:multiArch`.
+    ///
+    /// See: <https://developer.android.com/guide/topics/manifest/application-element#multiarch>
+    pub multi_arch: bool,
+}
+
+impl SupportedAbis {
+    /// Preference order of the ABIs on the device, 64-bit first.
+    const PREFERENCE: [&'static str; 7] = [
+        "arm64-v8a",
+        "x86_64",
+        "riscv64",
+        "armeabi-v7a",
+        "x86",
+        "armeabi",
+        "mips",
+    ];
+
+    /// Checks if the apk contains native libraries.
+    #[inline]
+    pub fn has_native_code(&self) -> bool {
+        !self.abis.is_empty()
+    }
+
+    /// Checks if the apk runs on 64-bit only devices, which Google Play requires since 2019.
+    pub fn supports_64bit(&self) -> bool {
+        !self.has_native_code()
+            || self
+                .abis
+                .iter()
+                .any(|abi| matches!(abi.as_str(), "arm64-v8a" | "x86_64" | "riscv64" | "mips64"))
+    }
+
+    /// Returns the ABI a modern device would pick: `arm64-v8a` if present, then `x86_64`, etc.
+    pub fn primary_abi(&self) -> Option<&str> {
+        Self::PREFERENCE
+            .iter()
+            .find(|abi| self.abis.iter().any(|a| a == *abi))
+            .copied()
+            .or_else(|| self.abis.first().map(String::as_str))
+    }
+}
+
+/// What the bytes returned by [Apk::get_icon_bytes](crate::Apk::get_icon_bytes) are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(tag = "kind", content = "format", rename_all = "snake_case")]
+pub enum IconKind {
+    /// Bitmap of the whole icon: png, webp or jpeg
+    Bitmap(FileType),
+
+    /// Foreground layer of the adaptive icon, there is no bitmap of the whole icon.
+    ///
+    /// The background is usually a color, the launcher masks both layers with its own shape.
+    AdaptiveForeground(FileType),
+}
+
+impl IconKind {
+    /// Format of the bitmap.
+    #[inline]
+    pub fn format(&self) -> FileType {
+        match self {
+            IconKind::Bitmap(format) | IconKind::AdaptiveForeground(format) => *format,
+        }
+    }
+}
+
+/// Type of the manifest component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    Activity,
+    ActivityAlias,
+    Service,
+    Receiver,
+    Provider,
+}
+
+impl ComponentKind {
+    /// Name of the manifest element: `activity-alias`
+    pub fn tag(&self) -> &'static str {
+        match self {
+            ComponentKind::Activity => "activity",
+            ComponentKind::ActivityAlias => "activity-alias",
+            ComponentKind::Service => "service",
+            ComponentKind::Receiver => "receiver",
+            ComponentKind::Provider => "provider",
+        }
+    }
+}
+
+/// Why the component is available to other applications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportReason {
+    /// `android:exported="true"`
+    Explicit,
+
+    /// No `android:exported`, but the component declares intent filters.
+    ///
+    /// Android 12 refuses to install such apps with `targetSdkVersion` 31 or higher.
+    IntentFilters,
+
+    /// Provider without `android:exported` in the app with `minSdkVersion` or
+    /// `targetSdkVersion` 16 or lower.
+    LegacyProviderDefault,
+}
+
+/// Component available to other applications, see [Apk::get_exported_components](crate::Apk::get_exported_components).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct ExportedComponent<'a> {
+    pub kind: ComponentKind,
+
+    /// The name of the class that implements the component
+    pub name: Option<&'a str>,
+
+    pub reason: ExportReason,
+
+    /// The permission required from callers, `<application android:permission>` is used
+    /// when the component has none.
+    pub permission: Option<&'a str>,
+
+    /// The permission required to read from the provider.
+    pub read_permission: Option<&'a str>,
+
+    /// The permission required to write to the provider.
+    pub write_permission: Option<&'a str>,
+
+    /// Whether the component can be instantiated by the system.
+    pub enabled: bool,
+}
+
+impl ExportedComponent<'_> {
+    /// Whether callers need any permission to use the component.
+    ///
+    /// Providers are protected only when both reading and writing require a permission.
+    pub fn is_protected(&self) -> bool {
+        match self.kind {
+            ComponentKind::Provider => {
+                self.permission.is_some()
+                    || (self.read_permission.is_some() && self.write_permission.is_some())
+            }
+            _ => self.permission.is_some(),
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! Inventory of the native libraries (`lib/<abi>/*.so`).
+//!
+//! Only the headers and the dynamic section of the ELF files are parsed: architecture,
+//! required libraries and exported symbols. That is enough to spot packers (a tiny
+//! `libjiagu.so` with `JNI_OnLoad` and nothing else) and libraries that are not ELF at all.
+//!
+//! Section headers are often stripped or corrupted by packers, so the dynamic section is
+//! found through the program headers, section headers are used only for the symbol table.
+//!
+//! ```ignore
+//! for abi in apk.get_native_libraries_by_abi() {
+//!     for library in &abi.libraries {
+//!         println!("{}: {:?}", library.path, library.elf.as_ref().map(|elf| &elf.needed));
+//!     }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Parsed headers of the ELF file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ElfInfo {
+    /// Architecture: `aarch64`, `arm`, `x86`, `x86_64`, `riscv`, `mips` or the raw `e_machine`
+    pub arch: String,
+
+    /// 32 or 64
+    pub bits: u8,
+
+    /// `DT_SONAME`
+    pub soname: Option<String>,
+
+    /// Required libraries (`DT_NEEDED`): `libc.so`, `liblog.so`
+    pub needed: Vec<String>,
+
+    /// Defined global functions and objects of the dynamic symbol table, sorted
+    pub exported_symbols: Vec<String>,
+
+    /// Exports `JNI_OnLoad`, called by the runtime when the library is loaded
+    pub has_jni_onload: bool,
+}
+
+impl ElfInfo {
+    /// Returns the ABI that matches the architecture: `arm64-v8a`, `armeabi-v7a`, ...
+    ///
+    /// See: <https://developer.android.com/ndk/guides/abis>
+    pub fn abi(&self) -> Option<&'static str> {
+        match (self.arch.as_str(), self.bits) {
+            ("aarch64", 64) => Some("arm64-v8a"),
+            ("arm", 32) => Some("armeabi-v7a"),
+            ("x86", 32) => Some("x86"),
+            ("x86_64", 64) => Some("x86_64"),
+            ("riscv", 64) => Some("riscv64"),
+            ("mips", 32) => Some("mips"),
+            ("mips", 64) => Some("mips64"),
+            _ => None,
+        }
+    }
+
+    /// Returns exported JNI functions bound by name: `Java_com_example_Native_init`.
+    pub fn jni_functions(&self) -> impl Iterator<Item = &str> {
+        self.exported_symbols
+            .iter()
+            .map(String::as_str)
+            .filter(|symbol| symbol.starts_with("Java_"))
+    }
+}
+
+/// Native library of the apk.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct NativeLibrary {
+    /// Path in the apk: `lib/arm64-v8a/libnative.so`
+    pub path: String,
+
+    /// Uncompressed size
+    pub size: u64,
+
+    /// [None] if the file is not a valid ELF, e.g. an encrypted payload
+    pub elf: Option<ElfInfo>,
+}
+
+impl NativeLibrary {
+    /// Checks if the architecture of the file doesn't match the ABI directory it is in.
+    pub fn is_abi_mismatch(&self, abi: &str) -> bool {
+        self.elf
+            .as_ref()
+            .and_then(ElfInfo::abi)
+            .is_some_and(|expected| {
+                expected != abi && !(abi == "armeabi" && expected == "armeabi-v7a")
+            })
+    }
+}
+
+/// Native libraries of one ABI.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct NativeAbi {
+    /// Name of the directory: `arm64-v8a`
+    pub abi: String,
+
+    /// Libraries sorted by path
+    pub libraries: Vec<NativeLibrary>,
+}
+
+impl NativeAbi {
+    /// Total uncompressed size of the libraries.
+    pub fn size(&self) -> u64 {
+        self.libraries.iter().map(|library| library.size).sum()
+    }
+}
+
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+
+const SHT_DYNSYM: u32 = 11;
+
+const SHN_UNDEF: u16 = 0;
+const SHN_LORESERVE: u16 = 0xff00;
+
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+const DT_HASH: u64 = 4;
+const DT_STRTAB: u64 = 5;
+const DT_SYMTAB: u64 = 6;
+const DT_SONAME: u64 = 14;
+const DT_GNU_HASH: u64 = 0x6fff_fef5;
+
+/// Upper bound of the dynamic entries and symbols, malformed counts are not trusted.
+const MAX_ITEMS: usize = 1 << 20;
+
+/// Upper bound of a single name, longer strings are not names but garbage without a terminator.
+const MAX_NAME_LENGTH: usize = 4096;
+
+/// Upper bound of the total length of the exported names, the rest of the symbols is skipped.
+const MAX_SYMBOL_BYTES: usize = 16 << 20;
+
+/// Little or big endian ELF of 32 or 64 bits.
+struct Elf<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    is_le: bool,
+}
+
+impl<'a> Elf<'a> {
+    fn new(data: &'a [u8]) -> Option<Elf<'a>> {
+        if !data.starts_with(b"\x7fELF") {
+            return None;
+        }
+
+        let is_64 = match *data.get(4)? {
+            1 => false,
+            2 => true,
+            _ => return None,
+        };
+        let is_le = match *data.get(5)? {
+            1 => true,
+            2 => false,
+            _ => return None,
+        };
+
+        Some(Elf { data, is_64, is_le })
+    }
+
+    fn bytes<const N: usize>(&self, offset: u64) -> Option<[u8; N]> {
+        let offset = usize::try_from(offset).ok()?;
+        self.data
+            .get(offset..offset.checked_add(N)?)?
+            .try_into()
+            .ok()
+    }
+
+    fn u16(&self, offset: u64) -> Option<u16> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.is_le {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: u64) -> Option<u32> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.is_le {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn u64(&self, offset: u64) -> Option<u64> {
+        let bytes = self.bytes(offset)?;
+        Some(if self.is_le {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads the address or offset, their size depends on the class.
+    fn word(&self, offset: u64) -> Option<u64> {
+        if self.is_64 {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+
+    /// Reads the null-terminated string, at most [MAX_NAME_LENGTH] bytes.
+    fn c_string(&self, offset: u64) -> Option<String> {
+        let data = self.data.get(usize::try_from(offset).ok()?..)?;
+        let data = &data[..data.len().min(MAX_NAME_LENGTH + 1)];
+        let end = memchr::memchr(0, data)?;
+        Some(String::from_utf8_lossy(&data[..end]).into_owned())
+    }
+
+    fn arch(&self) -> Option<String> {
+        let machine = self.u16(18)?;
+        Some(match machine {
+            3 => "x86".to_string(),
+            8 => "mips".to_string(),
+            40 => "arm".to_string(),
+            62 => "x86_64".to_string(),
+            183 => "aarch64".to_string(),
+            243 => "riscv".to_string(),
+            other => format!("0x{other:x}"),
+        })
+    }
+
+    /// Returns `(type, offset, vaddr, filesz)` of the program headers.
+    fn program_headers(&self) -> Vec<(u32, u64, u64, u64)> {
+        let (phoff, phentsize, phnum) = if self.is_64 {
+            (self.u64(32), self.u16(54), self.u16(56))
+        } else {
+            (self.u32(28).map(u64::from), self.u16(42), self.u16(44))
+        };
+        let (Some(phoff), Some(phentsize), Some(phnum)) = (phoff, phentsize, phnum) else {
+            return Vec::new();
+        };
+
+        (0..u64::from(phnum))
+            .map_while(|i| {
+                let header = phoff.checked_add(i * u64::from(phentsize))?;
+                let field = |offset: u64| header.checked_add(offset);
+                let kind = self.u32(header)?;
+                if self.is_64 {
+                    Some((
+                        kind,
+                        self.u64(field(8)?)?,
+                        self.u64(field(16)?)?,
+                        self.u64(field(32)?)?,
+                    ))
+                } else {
+                    Some((
+                        kind,
+                        u64::from(self.u32(field(4)?)?),
+                        u64::from(self.u32(field(8)?)?),
+                        u64::from(self.u32(field(16)?)?),
+                    ))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `(type, link, offset, size, entsize)` of the section headers.
+    fn section_headers(&self) -> Vec<(u32, u32, u64, u64, u64)> {
+        let (shoff, shentsize, shnum) = if self.is_64 {
+            (self.u64(40), self.u16(58), self.u16(60))
+        } else {
+            (self.u32(32).map(u64::from), self.u16(46), self.u16(48))
+        };
+        let (Some(shoff), Some(shentsize), Some(shnum)) = (shoff, shentsize, shnum) else {
+            return Vec::new();
+        };
+        if shoff == 0 {
+            return Vec::new();
+        }
+
+        (0..u64::from(shnum))
+            .map_while(|i| {
+                let header = shoff.checked_add(i * u64::from(shentsize))?;
+                let field = |offset: u64| header.checked_add(offset);
+                let kind = self.u32(field(4)?)?;
+                if self.is_64 {
+                    Some((
+                        kind,
+                        self.u32(field(40)?)?,
+                        self.u64(field(24)?)?,
+                        self.u64(field(32)?)?,
+                        self.u64(field(56)?)?,
+                    ))
+                } else {
+                    Some((
+                        kind,
+                        self.u32(field(24)?)?,
+                        u64::from(self.u32(field(16)?)?),
+                        u64::from(self.u32(field(20)?)?),
+                        u64::from(self.u32(field(36)?)?),
+                    ))
+                }
+            })
+            .collect()
+    }
+
+    /// Parses the headers, the dynamic section and the dynamic symbols.
+    fn info(&self) -> Option<ElfInfo> {
+        let program_headers = self.program_headers();
+
+        // addresses of the dynamic section are virtual, file offsets are in the loadable segments
+        let to_offset = |address: u64| {
+            program_headers
+                .iter()
+                .find(|(kind, _, vaddr, filesz)| {
+                    *kind == PT_LOAD && address >= *vaddr && address - vaddr < *filesz
+                })
+                .and_then(|(_, offset, vaddr, _)| offset.checked_add(address - vaddr))
+        };
+
+        let mut dynamic: Vec<(u64, u64)> = Vec::new();
+        if let Some((_, offset, _, size)) = program_headers
+            .iter()
+            .find(|(kind, ..)| *kind == PT_DYNAMIC)
+        {
+            let entry_size = if self.is_64 { 16 } else { 8 };
+            let count = (size / entry_size).min(MAX_ITEMS as u64);
+            for i in 0..count {
+                let Some(entry) = offset.checked_add(i * entry_size) else {
+                    break;
+                };
+                let (Some(tag), Some(value)) = (
+                    self.word(entry),
+                    entry
+                        .checked_add(entry_size / 2)
+                        .and_then(|value| self.word(value)),
+                ) else {
+                    break;
+                };
+                if tag == DT_NULL {
+                    break;
+                }
+                dynamic.push((tag, value));
+            }
+        }
+
+        let find = |tag: u64| dynamic.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v);
+        let string_table = find(DT_STRTAB).and_then(to_offset);
+        let string = |offset: u64| self.c_string(string_table?.checked_add(offset)?);
+
+        let needed = dynamic
+            .iter()
+            .filter(|(tag, _)| *tag == DT_NEEDED)
+            .filter_map(|(_, offset)| string(*offset))
+            .collect();
+        let soname = find(DT_SONAME).and_then(string);
+
+        let mut exported_symbols = self.exported_symbols(
+            find(DT_SYMTAB).and_then(to_offset),
+            string_table,
+            find(DT_HASH).and_then(to_offset),
+            find(DT_GNU_HASH).and_then(to_offset),
+        );
+        exported_symbols.sort();
+        exported_symbols.dedup();
+
+        Some(ElfInfo {
+            arch: self.arch()?,
+            bits: if self.is_64 { 64 } else { 32 },
+            soname,
+            needed,
+            has_jni_onload: exported_symbols.iter().any(|s| s == "JNI_OnLoad"),
+            exported_symbols,
+        })
+    }
+
+    /// Reads the dynamic symbol table, from the section headers if they are intact,
+    /// otherwise the number of symbols is taken from the hash tables.
+    fn exported_symbols(
+        &self,
+        symbol_table: Option<u64>,
+        string_table: Option<u64>,
+        hash: Option<u64>,
+        gnu_hash: Option<u64>,
+    ) -> Vec<String> {
+        let entry_size = if self.is_64 { 24 } else { 16 };
+        let sections = self.section_headers();
+
+        let dynsym = sections
+            .iter()
+            .find(|(kind, ..)| *kind == SHT_DYNSYM)
+            .and_then(|&(_, link, offset, size, entsize)| {
+                let (_, _, strtab, _, _) = sections.get(link as usize)?;
+                (entsize == entry_size).then_some((offset, size / entry_size, *strtab))
+            });
+
+        let (symbol_table, count, string_table) = match dynsym {
+            Some(dynsym) => dynsym,
+            None => {
+                let (Some(symbol_table), Some(string_table)) = (symbol_table, string_table) else {
+                    return Vec::new();
+                };
+                let count = match (hash, gnu_hash) {
+                    // nchain of the sysv hash table is the number of symbols
+                    (Some(hash), _) => hash
+                        .checked_add(4)
+                        .and_then(|nchain| self.u32(nchain))
+                        .map(u64::from),
+                    (None, Some(gnu_hash)) => self.gnu_hash_symbols(gnu_hash),
+                    (None, None) => None,
+                };
+                let Some(count) = count else {
+                    return Vec::new();
+                };
+                (symbol_table, count, string_table)
+            }
+        };
+
+        let count = count.min(MAX_ITEMS as u64);
+        let mut symbols = Vec::new();
+        let mut total_length = 0;
+        for i in 0..count {
+            let Some(symbol) = symbol_table.checked_add(i * entry_size) else {
+                break;
+            };
+            let (info, shndx) = if self.is_64 { (4, 6) } else { (12, 14) };
+            let (Some(name), Some([info]), Some(shndx)) = (
+                self.u32(symbol),
+                symbol
+                    .checked_add(info)
+                    .and_then(|info| self.bytes::<1>(info)),
+                symbol.checked_add(shndx).and_then(|shndx| self.u16(shndx)),
+            ) else {
+                break;
+            };
+
+            // defined global or weak functions and objects, reserved section indexes
+            // are the absolute symbols of the version definitions
+            let (binding, kind) = (info >> 4, info & 0xf);
+            if name == 0
+                || shndx == SHN_UNDEF
+                || shndx >= SHN_LORESERVE
+                || !matches!(binding, 1 | 2)
+                || !matches!(kind, 1 | 2 | 10)
+            {
+                continue;
+            }
+
+            let Some(name) = string_table
+                .checked_add(u64::from(name))
+                .and_then(|name| self.c_string(name))
+            else {
+                continue;
+            };
+            total_length += name.len();
+            if total_length > MAX_SYMBOL_BYTES {
+                break;
+            }
+            symbols.push(name);
+        }
+
+        symbols
+    }
+
+    /// Counts the symbols of the gnu hash table: the last symbol is found by following
+    /// the chain of the largest bucket until the end marker.
+    fn gnu_hash_symbols(&self, table: u64) -> Option<u64> {
+        let buckets = u64::from(self.u32(table)?);
+        let symoffset = u64::from(self.u32(table.checked_add(4)?)?);
+        let bloom_size = u64::from(self.u32(table.checked_add(8)?)?);
+        let word_size = if self.is_64 { 8 } else { 4 };
+
+        let buckets_start = table.checked_add(16 + bloom_size * word_size)?;
+        let chains_start = buckets_start.checked_add(buckets * 4)?;
+
+        let last_bucket = (0..buckets.min(MAX_ITEMS as u64))
+            .filter_map(|i| self.u32(buckets_start.checked_add(i * 4)?))
+            .max()
+            .map(u64::from)?;
+        if last_bucket < symoffset {
+            return Some(symoffset);
+        }
+
+        let mut symbol = last_bucket;
+        while symbol - symoffset < MAX_ITEMS as u64 {
+            let hash = self.u32(chains_start.checked_add((symbol - symoffset) * 4)?)?;
+            if hash & 1 != 0 {
+                return Some(symbol + 1);
+            }
+            symbol += 1;
+        }
+
+        None
+    }
+}
+
+/// Parses the ELF headers of the native library, [None] if it's not a valid ELF.
+pub fn parse_elf(data: &[u8]) -> Option<ElfInfo> {
+    Elf::new(data)?.info()
+}
@@ -0,0 +1,170 @@
+//! Parse timing and memory statistics, see [crate::Apk::perf_stats].
+//!
+//! Durations and sizes are always collected. With the `alloc-stats` feature
+//! [CountingAllocator] can be installed as the global allocator, then every
+//! subsystem also reports how many bytes it allocated:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: apk_info::perf::CountingAllocator = apk_info::perf::CountingAllocator;
+//!
+//! let apk = Apk::new("./file.apk")?;
+//! println!("{:?}", apk.perf_stats());
+//! ```
+//!
+//! The allocation counter is process-wide, so allocations made by other threads
+//! at the same time are counted too and the numbers are an estimate.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Statistics of a single subsystem.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PhaseStats {
+    /// Time spent in the subsystem, including decompression of its input.
+    pub duration: Duration,
+
+    /// Size of the data handed to the parser.
+    ///
+    /// Zero for signatures, they are parsed in place from the archive.
+    pub bytes: u64,
+
+    /// Bytes allocated by the subsystem.
+    ///
+    /// `None` without the `alloc-stats` feature and installed [CountingAllocator].
+    pub allocated: Option<u64>,
+}
+
+impl PhaseStats {
+    /// Adds statistics of another run of the same subsystem.
+    pub(crate) fn add(&mut self, other: PhaseStats) {
+        self.duration += other.duration;
+        self.bytes += other.bytes;
+        self.allocated = match (self.allocated, other.allocated) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+/// Parse statistics of the [crate::Apk] per subsystem.
+///
+/// Signatures and dex files are parsed on demand, so they are `None` until the
+/// first access. Dex statistics are accumulated over all parsed dex files.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct PerfStats {
+    /// Reading the file and parsing the archive structure.
+    pub zip: PhaseStats,
+
+    /// Parsing `AndroidManifest.xml`.
+    pub axml: PhaseStats,
+
+    /// Parsing `resources.arsc`, `None` if the apk has no resources.
+    pub arsc: Option<PhaseStats>,
+
+    /// Parsing signatures (v1, signing block).
+    pub signatures: Option<PhaseStats>,
+
+    /// Parsing `classes*.dex` files.
+    pub dex: Option<PhaseStats>,
+}
+
+impl PerfStats {
+    fn phases(&self) -> impl Iterator<Item = &PhaseStats> {
+        [Some(&self.zip), Some(&self.axml)]
+            .into_iter()
+            .chain([&self.arsc, &self.signatures, &self.dex].map(Option::as_ref))
+            .flatten()
+    }
+
+    /// Total time spent in all subsystems.
+    pub fn total_duration(&self) -> Duration {
+        self.phases().map(|phase| phase.duration).sum()
+    }
+
+    /// Total number of bytes handed to the parsers.
+    pub fn bytes_parsed(&self) -> u64 {
+        self.phases().map(|phase| phase.bytes).sum()
+    }
+}
+
+/// Measurement of a single subsystem run.
+pub(crate) struct Measure {
+    start: Instant,
+    allocated: Option<u64>,
+}
+
+impl Measure {
+    pub(crate) fn start() -> Measure {
+        Measure {
+            start: Instant::now(),
+            allocated: allocated(),
+        }
+    }
+
+    pub(crate) fn finish(self, bytes: usize) -> PhaseStats {
+        PhaseStats {
+            duration: self.start.elapsed(),
+            bytes: bytes as u64,
+            allocated: self
+                .allocated
+                .zip(allocated())
+                .map(|(start, end)| end.saturating_sub(start)),
+        }
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+
+    /// Global allocator that counts allocated bytes, see the [module](super) docs.
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct CountingAllocator;
+
+    // SAFETY: all calls are forwarded to the system allocator as is
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size() as u64, Ordering::Relaxed);
+            unsafe { System.alloc(layout) }
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size() as u64, Ordering::Relaxed);
+            unsafe { System.alloc_zeroed(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) }
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            // only the growth is counted, the old block was counted before
+            let grown = new_size.saturating_sub(layout.size());
+            ALLOCATED.fetch_add(grown as u64, Ordering::Relaxed);
+            unsafe { System.realloc(ptr, layout, new_size) }
+        }
+    }
+
+    /// Bytes allocated since the start, `None` if the allocator is not installed.
+    pub(crate) fn allocated() -> Option<u64> {
+        match ALLOCATED.load(Ordering::Relaxed) {
+            0 => None,
+            value => Some(value),
+        }
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+pub use counting::CountingAllocator;
+#[cfg(feature = "alloc-stats")]
+use counting::allocated;
+
+#[cfg(not(feature = "alloc-stats"))]
+fn allocated() -> Option<u64> {
+    None
+}
@@ -0,0 +1,346 @@
+//! Protection levels of the Android framework permissions.
+//!
+//! The table is bundled with the crate and covers public permissions from `android.permission`
+//! and a few legacy namespaces. Permissions declared by the apk itself are resolved from
+//! their `<permission>` elements, see [Apk::get_permissions_by_protection](crate::Apk::get_permissions_by_protection).
+
+use std::fmt;
+
+use serde::Serialize;
+
+/// Base protection level of the permission, flags like `privileged` or `appop` are dropped.
+///
+/// See: <https://developer.android.com/guide/topics/manifest/permission-element#plevel>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtectionLevel {
+    /// Granted at install time without asking the user
+    Normal,
+
+    /// Runtime permission, the user has to grant it
+    Dangerous,
+
+    /// Granted only to apps signed with the same certificate or to the system
+    Signature,
+
+    /// Permission isn't known
+    Unknown,
+}
+
+impl ProtectionLevel {
+    /// Parses the decoded `protectionLevel` attribute, e.g. `signature|privileged`.
+    pub fn from_attribute(value: &str) -> ProtectionLevel {
+        value
+            .split('|')
+            .find_map(|level| match level.trim() {
+                "normal" => Some(ProtectionLevel::Normal),
+                "dangerous" => Some(ProtectionLevel::Dangerous),
+                "signature" | "signatureOrSystem" | "internal" => Some(ProtectionLevel::Signature),
+                _ => None,
+            })
+            .unwrap_or(ProtectionLevel::Unknown)
+    }
+
+    /// Returns flags of the decoded `protectionLevel` attribute without the base level:
+    /// `signature|privileged|development` -> `privileged`, `development`.
+    pub fn flags(value: &str) -> impl Iterator<Item = &str> {
+        value.split('|').map(str::trim).filter(|flag| {
+            !flag.is_empty()
+                && !matches!(
+                    *flag,
+                    "normal" | "dangerous" | "signature" | "signatureOrSystem" | "internal"
+                )
+        })
+    }
+
+    /// Returns the protection level of the framework permission from the bundled table.
+    pub fn of_framework_permission(name: &str) -> ProtectionLevel {
+        match name {
+            "com.android.voicemail.permission.ADD_VOICEMAIL" => return ProtectionLevel::Dangerous,
+            "com.android.voicemail.permission.READ_VOICEMAIL"
+            | "com.android.voicemail.permission.WRITE_VOICEMAIL" => {
+                return ProtectionLevel::Signature;
+            }
+            "com.android.alarm.permission.SET_ALARM"
+            | "com.android.launcher.permission.INSTALL_SHORTCUT"
+            | "com.android.launcher.permission.UNINSTALL_SHORTCUT" => {
+                return ProtectionLevel::Normal;
+            }
+            _ => {}
+        }
+
+        let Some(name) = name.strip_prefix("android.permission.") else {
+            return ProtectionLevel::Unknown;
+        };
+
+        if name.starts_with("FOREGROUND_SERVICE") {
+            return ProtectionLevel::Normal;
+        }
+
+        match name {
+            "ACCEPT_HANDOVER"
+            | "ACCESS_BACKGROUND_LOCATION"
+            | "ACCESS_COARSE_LOCATION"
+            | "ACCESS_FINE_LOCATION"
+            | "ACCESS_MEDIA_LOCATION"
+            | "ACTIVITY_RECOGNITION"
+            | "ANSWER_PHONE_CALLS"
+            | "BLUETOOTH_ADVERTISE"
+            | "BLUETOOTH_CONNECT"
+            | "BLUETOOTH_SCAN"
+            | "BODY_SENSORS"
+            | "BODY_SENSORS_BACKGROUND"
+            | "CALL_PHONE"
+            | "CAMERA"
+            | "GET_ACCOUNTS"
+            | "NEARBY_WIFI_DEVICES"
+            | "POST_NOTIFICATIONS"
+            | "PROCESS_OUTGOING_CALLS"
+            | "READ_CALENDAR"
+            | "READ_CALL_LOG"
+            | "READ_CONTACTS"
+            | "READ_EXTERNAL_STORAGE"
+            | "READ_MEDIA_AUDIO"
+            | "READ_MEDIA_IMAGES"
+            | "READ_MEDIA_VIDEO"
+            | "READ_MEDIA_VISUAL_USER_SELECTED"
+            | "READ_PHONE_NUMBERS"
+            | "READ_PHONE_STATE"
+            | "READ_SMS"
+            | "RECEIVE_MMS"
+            | "RECEIVE_SMS"
+            | "RECEIVE_WAP_PUSH"
+            | "RECORD_AUDIO"
+            | "SEND_SMS"
+            | "USE_SIP"
+            | "UWB_RANGING"
+            | "WRITE_CALENDAR"
+            | "WRITE_CALL_LOG"
+            | "WRITE_CONTACTS"
+            | "WRITE_EXTERNAL_STORAGE" => ProtectionLevel::Dangerous,
+
+            "ACCESS_LOCATION_EXTRA_COMMANDS"
+            | "ACCESS_NETWORK_STATE"
+            | "ACCESS_NOTIFICATION_POLICY"
+            | "ACCESS_WIFI_STATE"
+            | "BLUETOOTH"
+            | "BLUETOOTH_ADMIN"
+            | "BROADCAST_STICKY"
+            | "CALL_COMPANION_APP"
+            | "CHANGE_NETWORK_STATE"
+            | "CHANGE_WIFI_MULTICAST_STATE"
+            | "CHANGE_WIFI_STATE"
+            | "CREDENTIAL_MANAGER_QUERY_CANDIDATE_CREDENTIALS"
+            | "CREDENTIAL_MANAGER_SET_ALLOWED_PROVIDERS"
+            | "CREDENTIAL_MANAGER_SET_ORIGIN"
+            | "DELIVER_COMPANION_MESSAGES"
+            | "DETECT_SCREEN_CAPTURE"
+            | "DISABLE_KEYGUARD"
+            | "ENFORCE_UPDATE_OWNERSHIP"
+            | "EXPAND_STATUS_BAR"
+            | "GET_PACKAGE_SIZE"
+            | "GET_TASKS"
+            | "HIDE_OVERLAY_WINDOWS"
+            | "HIGH_SAMPLING_RATE_SENSORS"
+            | "INTERNET"
+            | "KILL_BACKGROUND_PROCESSES"
+            | "MANAGE_OWN_CALLS"
+            | "MODIFY_AUDIO_SETTINGS"
+            | "NFC"
+            | "NFC_PREFERRED_PAYMENT_INFO"
+            | "NFC_TRANSACTION_EVENT"
+            | "PERSISTENT_ACTIVITY"
+            | "QUERY_ALL_PACKAGES"
+            | "READ_BASIC_PHONE_STATE"
+            | "READ_SYNC_SETTINGS"
+            | "READ_SYNC_STATS"
+            | "RECEIVE_BOOT_COMPLETED"
+            | "REORDER_TASKS"
+            | "REQUEST_COMPANION_PROFILE_WATCH"
+            | "REQUEST_COMPANION_RUN_IN_BACKGROUND"
+            | "REQUEST_COMPANION_START_FOREGROUND_SERVICES_FROM_BACKGROUND"
+            | "REQUEST_COMPANION_USE_DATA_IN_BACKGROUND"
+            | "REQUEST_DELETE_PACKAGES"
+            | "REQUEST_IGNORE_BATTERY_OPTIMIZATIONS"
+            | "REQUEST_OBSERVE_COMPANION_DEVICE_PRESENCE"
+            | "REQUEST_PASSWORD_COMPLEXITY"
+            | "RESTART_PACKAGES"
+            | "RUN_USER_INITIATED_JOBS"
+            | "SET_WALLPAPER"
+            | "SET_WALLPAPER_HINTS"
+            | "TRANSMIT_IR"
+            | "UPDATE_PACKAGES_WITHOUT_USER_ACTION"
+            | "USE_BIOMETRIC"
+            | "USE_EXACT_ALARM"
+            | "USE_FINGERPRINT"
+            | "USE_FULL_SCREEN_INTENT"
+            | "VIBRATE"
+            | "WAKE_LOCK"
+            | "WRITE_SYNC_SETTINGS" => ProtectionLevel::Normal,
+
+            "ACCESS_CHECKIN_PROPERTIES"
+            | "ACCESS_SURFACE_FLINGER"
+            | "ACCOUNT_MANAGER"
+            | "BATTERY_STATS"
+            | "BIND_ACCESSIBILITY_SERVICE"
+            | "BIND_APPWIDGET"
+            | "BIND_AUTOFILL_SERVICE"
+            | "BIND_CALL_REDIRECTION_SERVICE"
+            | "BIND_CARRIER_MESSAGING_CLIENT_SERVICE"
+            | "BIND_CARRIER_MESSAGING_SERVICE"
+            | "BIND_CARRIER_SERVICES"
+            | "BIND_CHOOSER_TARGET_SERVICE"
+            | "BIND_COMPANION_DEVICE_SERVICE"
+            | "BIND_CONDITION_PROVIDER_SERVICE"
+            | "BIND_CONTROLS"
+            | "BIND_CREDENTIAL_PROVIDER_SERVICE"
+            | "BIND_DEVICE_ADMIN"
+            | "BIND_DREAM_SERVICE"
+            | "BIND_INCALL_SERVICE"
+            | "BIND_INPUT_METHOD"
+            | "BIND_JOB_SERVICE"
+            | "BIND_MIDI_DEVICE_SERVICE"
+            | "BIND_NFC_SERVICE"
+            | "BIND_NOTIFICATION_LISTENER_SERVICE"
+            | "BIND_PRINT_SERVICE"
+            | "BIND_QUICK_ACCESS_WALLET_SERVICE"
+            | "BIND_QUICK_SETTINGS_TILE"
+            | "BIND_REMOTEVIEWS"
+            | "BIND_SCREENING_SERVICE"
+            | "BIND_TELECOM_CONNECTION_SERVICE"
+            | "BIND_TEXT_SERVICE"
+            | "BIND_TV_INPUT"
+            | "BIND_VISUAL_VOICEMAIL_SERVICE"
+            | "BIND_VOICE_INTERACTION"
+            | "BIND_VPN_SERVICE"
+            | "BIND_VR_LISTENER_SERVICE"
+            | "BIND_WALLPAPER"
+            | "BROADCAST_PACKAGE_REMOVED"
+            | "BROADCAST_SMS"
+            | "BROADCAST_WAP_PUSH"
+            | "CALL_PRIVILEGED"
+            | "CAPTURE_AUDIO_OUTPUT"
+            | "CHANGE_COMPONENT_ENABLED_STATE"
+            | "CHANGE_CONFIGURATION"
+            | "CLEAR_APP_CACHE"
+            | "CONTROL_LOCATION_UPDATES"
+            | "DELETE_CACHE_FILES"
+            | "DELETE_PACKAGES"
+            | "DEVICE_POWER"
+            | "DIAGNOSTIC"
+            | "DUMP"
+            | "FACTORY_TEST"
+            | "GLOBAL_SEARCH"
+            | "INSTALL_LOCATION_PROVIDER"
+            | "INSTALL_PACKAGES"
+            | "LOADER_USAGE_STATS"
+            | "LOCATION_HARDWARE"
+            | "MANAGE_DOCUMENTS"
+            | "MANAGE_EXTERNAL_STORAGE"
+            | "MANAGE_MEDIA"
+            | "MANAGE_ONGOING_CALLS"
+            | "MASTER_CLEAR"
+            | "MEDIA_CONTENT_CONTROL"
+            | "MODIFY_PHONE_STATE"
+            | "MOUNT_FORMAT_FILESYSTEMS"
+            | "MOUNT_UNMOUNT_FILESYSTEMS"
+            | "PACKAGE_USAGE_STATS"
+            | "READ_LOGS"
+            | "READ_PRIVILEGED_PHONE_STATE"
+            | "REBOOT"
+            | "REQUEST_INSTALL_PACKAGES"
+            | "SCHEDULE_EXACT_ALARM"
+            | "SEND_RESPOND_VIA_MESSAGE"
+            | "SET_ALWAYS_FINISH"
+            | "SET_ANIMATION_SCALE"
+            | "SET_DEBUG_APP"
+            | "SET_PROCESS_LIMIT"
+            | "SET_TIME"
+            | "SET_TIME_ZONE"
+            | "SIGNAL_PERSISTENT_PROCESSES"
+            | "SMS_FINANCIAL_TRANSACTIONS"
+            | "START_VIEW_PERMISSION_USAGE"
+            | "STATUS_BAR"
+            | "SYSTEM_ALERT_WINDOW"
+            | "UPDATE_DEVICE_STATS"
+            | "WRITE_APN_SETTINGS"
+            | "WRITE_GSERVICES"
+            | "WRITE_SECURE_SETTINGS"
+            | "WRITE_SETTINGS" => ProtectionLevel::Signature,
+
+            _ => ProtectionLevel::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for ProtectionLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ProtectionLevel::Normal => "normal",
+            ProtectionLevel::Dangerous => "dangerous",
+            ProtectionLevel::Signature => "signature",
+            ProtectionLevel::Unknown => "unknown",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_protection_levels() {
+        let level = ProtectionLevel::of_framework_permission;
+        assert_eq!(
+            level("android.permission.CAMERA"),
+            ProtectionLevel::Dangerous
+        );
+        assert_eq!(
+            level("android.permission.INTERNET"),
+            ProtectionLevel::Normal
+        );
+        assert_eq!(
+            level("android.permission.FOREGROUND_SERVICE_CAMERA"),
+            ProtectionLevel::Normal
+        );
+        assert_eq!(
+            level("android.permission.INSTALL_PACKAGES"),
+            ProtectionLevel::Signature
+        );
+        assert_eq!(
+            level("com.android.launcher.permission.INSTALL_SHORTCUT"),
+            ProtectionLevel::Normal
+        );
+        assert_eq!(
+            level("com.android.voicemail.permission.ADD_VOICEMAIL"),
+            ProtectionLevel::Dangerous
+        );
+        assert_eq!(
+            level("android.permission.NOT_A_PERMISSION"),
+            ProtectionLevel::Unknown
+        );
+        assert_eq!(
+            level("com.example.permission.C2D"),
+            ProtectionLevel::Unknown
+        );
+
+        assert_eq!(
+            ProtectionLevel::from_attribute("signature|privileged"),
+            ProtectionLevel::Signature
+        );
+        assert_eq!(
+            ProtectionLevel::from_attribute("appop|dangerous"),
+            ProtectionLevel::Dangerous
+        );
+        assert_eq!(
+            ProtectionLevel::from_attribute("appop"),
+            ProtectionLevel::Unknown
+        );
+        assert_eq!(
+            ProtectionLevel::flags("signature|privileged|development").collect::<Vec<_>>(),
+            ["privileged", "development"]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Commonly used types, re-exported for convenience.
+//!
+//! Downstream users can depend only on `apk-info` and import everything from here
+//! instead of picking types from the internal `apk-info-*` crates.
+//!
+//! ```no_run
+//! use apk_info::prelude::*;
+//!
+//! let apk = Apk::new("./file.apk").expect("can't parse apk file");
+//! for signature in apk.get_signatures().unwrap_or_default() {
+//!     if let Signature::V2 { certificates, .. } = signature {
+//!         println!("{:?}", certificates);
+//!     }
+//! }
+//! ```
+
+pub use apk_info_axml::structs::Density;
+pub use apk_info_axml::{ARSC, AXML};
+#[cfg(feature = "experimental")]
+pub use apk_info_dex::Dex;
+pub use apk_info_xml::{Attribute, Element};
+pub use apk_info_zip::{
+    CertificateInfo, EntryInfo, FileCompressionType, FileType, Signature, ZipAnomaly, ZipEntry,
+};
+
+pub use crate::apk::Apk;
+pub use crate::artifact::{Artifact, ArtifactKind};
+pub use crate::diff::{ApkDiff, ApkDiffer, ApkReport};
+pub use crate::errors::APKError;
+#[cfg(feature = "experimental")]
+pub use crate::hiddenapi::HiddenApiDatabase;
+pub use crate::models::*;
+#[cfg(feature = "experimental")]
+pub use crate::perf::{PerfStats, PhaseStats};
+#[cfg(feature = "experimental")]
+pub use crate::progress::{CancellationToken, ProgressSink, Stage};
+pub use crate::splits::{SplitApkSet, SplitInfo};
+pub use crate::xapk::{SplitRole, Xapk, XapkMember};
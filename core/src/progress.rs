@@ -0,0 +1,101 @@
+//! Progress reporting and cooperative cancellation for long operations.
+//!
+//! Heavy operations accept an optional [ProgressSink] and [CancellationToken]:
+//!
+//! - [Apk::extract_to](crate::Apk::extract_to) reports [Stage::Extract] per file;
+//! - [batch::process_with_progress](crate::batch::process_with_progress) reports
+//!   [Stage::Parse] per parsed apk;
+//! - [Apk::verify_signature_v1_with_progress](crate::Apk::verify_signature_v1_with_progress)
+//!   reports [Stage::Signatures] per entry of `MANIFEST.MF`;
+//! - [Apk::package_profile](crate::Apk::package_profile) and
+//!   [Apk::find_string_usages](crate::Apk::find_string_usages) report [Stage::Dex] per dex file.
+//!
+//! ```no_run
+//! use apk_info::Apk;
+//! use apk_info::progress::{CancellationToken, Stage};
+//!
+//! let apk = Apk::new("./file.apk").expect("can't parse apk file");
+//! let token = CancellationToken::new();
+//! let progress = |stage: Stage, current: u64, total: u64| {
+//!     println!("{stage}: {current}/{total}");
+//! };
+//!
+//! apk.extract_to("./out", |_| true, Some(&progress), Some(&token))
+//!     .expect("can't extract apk");
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Stage of the long operation being reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// Parsing apk files.
+    Parse,
+
+    /// Checking the digests of the signature.
+    Signatures,
+
+    /// Scanning `classes*.dex` files.
+    Dex,
+
+    /// Extracting files from the archive.
+    Extract,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Signatures => write!(f, "signatures"),
+            Stage::Dex => write!(f, "dex"),
+            Stage::Extract => write!(f, "extract"),
+        }
+    }
+}
+
+/// Receiver of progress updates.
+///
+/// Implemented for any `Fn(Stage, u64, u64)` closure. Operations running on the thread pool
+/// (dex scanning, batch parsing) report from several threads and need a `Sync` sink,
+/// the order of the reports is not guaranteed there, but `current` never repeats.
+pub trait ProgressSink {
+    /// Called after each processed item of the `stage`.
+    fn report(&self, stage: Stage, current: u64, total: u64);
+}
+
+impl<F> ProgressSink for F
+where
+    F: Fn(Stage, u64, u64),
+{
+    #[inline]
+    fn report(&self, stage: Stage, current: u64, total: u64) {
+        self(stage, current, total)
+    }
+}
+
+/// Token for cooperative cancellation of long operations.
+///
+/// Clones share the same state, so the token can be cancelled from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new, not cancelled token.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Requests cancellation of the operations that use this token.
+    #[inline]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks whether cancellation was requested.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
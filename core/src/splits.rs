@@ -0,0 +1,311 @@
+//! Sets of split apks: `.apks` archives (bundletool, SAI) and xapk bundles.
+//!
+//! [Apk] and [Xapk](crate::xapk::Xapk) analyze only the base apk. Installed apps are
+//! often split: native libraries, densities and translations come in configuration splits,
+//! so [SplitApkSet] parses every inner apk and provides a merged view of them.
+//!
+//! ```ignore
+//! let set = SplitApkSet::new("./app.apks")?;
+//! for split in set.splits() {
+//!     println!("{} - {} {:?}", split.info.file, split.info.role, split.info.abi);
+//! }
+//! println!("{:?}", set.get_native_codes());
+//! ```
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use apk_info_zip::ZipEntry;
+use serde::Serialize;
+
+use crate::apk::Apk;
+use crate::errors::APKError;
+use crate::logging::warn;
+use crate::models::{Activity, Provider, Receiver, Service};
+use crate::xapk::{SplitRole, inner_size, read_inner_apk};
+
+/// Values of `android:abi` used in the names of configuration splits.
+const ABIS: &[&str] = &[
+    "armeabi",
+    "armeabi_v7a",
+    "arm64_v8a",
+    "x86",
+    "x86_64",
+    "mips",
+    "mips64",
+    "riscv64",
+];
+
+/// Screen densities used in the names of configuration splits.
+const DENSITIES: &[&str] = &[
+    "ldpi", "mdpi", "tvdpi", "hdpi", "xhdpi", "xxhdpi", "xxxhdpi", "nodpi", "anydpi",
+];
+
+/// Metadata of the split apk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SplitInfo {
+    /// Path to the apk inside the set
+    pub file: String,
+
+    /// Role of the apk with the split name from the manifest: `config.arm64_v8a`
+    #[serde(flatten)]
+    pub role: SplitRole,
+
+    /// ABI of the native libraries, for configuration splits: `arm64-v8a`
+    pub abi: Option<String>,
+
+    /// Screen density, for configuration splits: `xxhdpi`
+    pub density: Option<String>,
+
+    /// Language of the resources, for configuration splits: `en`
+    pub language: Option<String>,
+
+    /// Uncompressed size in bytes
+    pub size: u64,
+}
+
+/// Parsed split apk.
+#[derive(Debug)]
+pub struct Split {
+    pub info: SplitInfo,
+    pub apk: Apk,
+}
+
+/// Parsed set of split apks, see the [module](self) docs.
+#[derive(Debug)]
+pub struct SplitApkSet {
+    /// Parsed splits, the base apk goes first
+    splits: Vec<Split>,
+}
+
+impl SplitApkSet {
+    /// Opens the set and parses all inner apks.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<SplitApkSet, APKError> {
+        let path = path.as_ref();
+        if std::fs::metadata(path)?.len() == 0 {
+            return Err(APKError::InvalidInput("got empty file"));
+        }
+
+        Self::from_zip(ZipEntry::open(path)?)
+    }
+
+    /// Parses the set from the given bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<SplitApkSet, APKError> {
+        if data.is_empty() {
+            return Err(APKError::InvalidInput("got empty file"));
+        }
+
+        Self::from_zip(ZipEntry::new(data)?)
+    }
+
+    /// Inner apks are all `*.apk` files of the archive, bundletool `standalones/`
+    /// duplicate the splits and are skipped. Splits that can't be parsed are skipped too,
+    /// only the base apk is required.
+    fn from_zip(zip: ZipEntry) -> Result<SplitApkSet, APKError> {
+        let mut splits = Vec::new();
+
+        for file in zip.namelist() {
+            if !file.ends_with(".apk") || file.starts_with("standalones/") {
+                continue;
+            }
+
+            let apk = match read_inner_apk(&zip, file) {
+                Ok(apk) => apk,
+                Err(e) => {
+                    warn!("can't parse split {file}, skipped: {e}");
+                    continue;
+                }
+            };
+
+            splits.push(Split {
+                info: SplitInfo::new(file, &apk, inner_size(&zip, file)),
+                apk,
+            });
+        }
+
+        match splits
+            .iter()
+            .filter(|split| split.info.role == SplitRole::Base)
+            .count()
+        {
+            0 => return Err(APKError::InvalidInput("can't find base apk in split set")),
+            1 => {}
+            _ => {
+                return Err(APKError::InvalidInput(
+                    "more than one base apk in split set",
+                ));
+            }
+        }
+
+        splits.sort_by(|a, b| {
+            (a.info.role != SplitRole::Base, &a.info.file)
+                .cmp(&(b.info.role != SplitRole::Base, &b.info.file))
+        });
+
+        Ok(SplitApkSet { splits })
+    }
+
+    /// Returns all parsed splits, the base apk goes first.
+    #[inline]
+    pub fn splits(&self) -> &[Split] {
+        &self.splits
+    }
+
+    /// Returns the base apk.
+    #[inline]
+    pub fn base(&self) -> &Apk {
+        // checked in the constructor
+        &self.splits[0].apk
+    }
+
+    /// Returns metadata of all splits.
+    pub fn infos(&self) -> impl Iterator<Item = &SplitInfo> {
+        self.splits.iter().map(|split| &split.info)
+    }
+
+    /// Returns ABIs of the configuration splits.
+    pub fn abis(&self) -> Vec<&str> {
+        self.infos()
+            .filter_map(|info| info.abi.as_deref())
+            .collect()
+    }
+
+    /// Returns screen densities of the configuration splits.
+    pub fn densities(&self) -> Vec<&str> {
+        self.infos()
+            .filter_map(|info| info.density.as_deref())
+            .collect()
+    }
+
+    /// Returns languages of the configuration splits.
+    pub fn languages(&self) -> Vec<&str> {
+        self.infos()
+            .filter_map(|info| info.language.as_deref())
+            .collect()
+    }
+}
+
+/// Merged view of the splits.
+///
+/// Identity of the app comes from the base apk, components and permissions are collected
+/// from all splits, as feature modules declare their own.
+impl SplitApkSet {
+    /// Returns the package name of the base apk.
+    #[inline]
+    pub fn get_package_name(&self) -> Option<String> {
+        self.base().get_package_name()
+    }
+
+    /// Returns the version name of the base apk.
+    #[inline]
+    pub fn get_version_name(&self) -> Option<String> {
+        self.base().get_version_name()
+    }
+
+    /// Returns the version code of the base apk.
+    #[inline]
+    pub fn get_version_code(&self) -> Option<String> {
+        self.base().get_version_code()
+    }
+
+    /// Returns the numeric version code of the base apk.
+    #[inline]
+    pub fn get_version_code_u64(&self) -> Option<u64> {
+        self.base().get_version_code_u64()
+    }
+
+    /// Returns requested permissions of all splits, sorted and de-duplicated.
+    pub fn get_permissions(&self) -> Vec<&str> {
+        let permissions: BTreeSet<&str> = self
+            .splits
+            .iter()
+            .flat_map(|split| split.apk.get_permissions())
+            .collect();
+
+        permissions.into_iter().collect()
+    }
+
+    /// Returns ABIs of native libraries of all splits, sorted.
+    pub fn get_native_codes(&self) -> Vec<String> {
+        let native_codes: BTreeSet<String> = self
+            .splits
+            .iter()
+            .flat_map(|split| split.apk.get_native_codes())
+            .collect();
+
+        native_codes.into_iter().collect()
+    }
+
+    /// Returns activities of all splits.
+    pub fn get_activities(&self) -> impl Iterator<Item = Activity<'_>> {
+        self.splits
+            .iter()
+            .flat_map(|split| split.apk.get_activities())
+    }
+
+    /// Returns services of all splits.
+    pub fn get_services(&self) -> impl Iterator<Item = Service<'_>> {
+        self.splits
+            .iter()
+            .flat_map(|split| split.apk.get_services())
+    }
+
+    /// Returns receivers of all splits.
+    pub fn get_receivers(&self) -> impl Iterator<Item = Receiver<'_>> {
+        self.splits
+            .iter()
+            .flat_map(|split| split.apk.get_receivers())
+    }
+
+    /// Returns providers of all splits.
+    pub fn get_providers(&self) -> impl Iterator<Item = Provider<'_>> {
+        self.splits
+            .iter()
+            .flat_map(|split| split.apk.get_providers())
+    }
+
+    /// Resolves the resource reference in the base apk, then in the other splits.
+    ///
+    /// ```ignore
+    /// let app_name = set.get_resource_value("@string/app_name");
+    /// ```
+    pub fn get_resource_value(&self, name: &str) -> Option<String> {
+        self.splits
+            .iter()
+            .find_map(|split| split.apk.get_resource_value(name))
+    }
+}
+
+impl SplitInfo {
+    fn new(file: &str, apk: &Apk, size: u64) -> SplitInfo {
+        let split = apk
+            .get_attribute_value("manifest", "split")
+            .unwrap_or_default();
+        let package_name = apk.get_package_name().unwrap_or_default();
+
+        let mut info = SplitInfo {
+            file: file.to_string(),
+            role: SplitRole::from_split(&split, &package_name),
+            abi: None,
+            density: None,
+            language: None,
+            size,
+        };
+
+        // `config.arm64_v8a`, feature modules have their own: `camera.config.xxhdpi`
+        let qualifier = match &info.role {
+            SplitRole::Base => None,
+            SplitRole::Config(split) | SplitRole::Feature(split) => split
+                .rsplit_once("config.")
+                .map(|(_, qualifier)| qualifier.to_string()),
+        };
+        match qualifier {
+            Some(abi) if ABIS.contains(&abi.as_str()) => info.abi = Some(abi.replace('_', "-")),
+            Some(density) if DENSITIES.contains(&density.as_str()) => info.density = Some(density),
+            Some(language) if !language.is_empty() => info.language = Some(language),
+            _ => {}
+        }
+
+        info
+    }
+}
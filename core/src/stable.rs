@@ -0,0 +1,41 @@
+//! Stable public api.
+//!
+//! Everything re-exported here follows semver: getters of [Apk], manifest models,
+//! signatures and reports are not removed or changed incompatibly within a major
+//! version. Renamed items stay available as deprecated aliases until the next major
+//! release.
+//!
+//! ```no_run
+//! use apk_info::stable::*;
+//!
+//! let apk = Apk::new("./file.apk").expect("can't parse apk file");
+//! println!("{:?}", apk.get_package_name());
+//! ```
+
+pub use apk_info_axml::diagnostics::XmlDiagnostic;
+pub use apk_info_axml::structs::Density;
+pub use apk_info_axml::{ARSC, AXML};
+pub use apk_info_zip::{
+    CertificateInfo, DigestCheck, EntryInfo, FileCompressionType, FileType, FingerprintStyle,
+    FrostingInfo, JarManifest, ManifestSection, Signature, SignatureFileVerification,
+    StructureIssue, V1Verification, ZipAnomaly,
+};
+
+pub use crate::anomaly::{Anomaly, AnomalyReport, DexAnomaly, EntryAnomaly};
+pub use crate::apk::Apk;
+pub use crate::artifact::{Artifact, ArtifactKind};
+pub use crate::baseline::{Baseline, BaselineApp, BaselineCheck, BaselineFinding, BaselineVerdict};
+pub use crate::diff::{
+    ApkDiff, ApkDiffer, ApkReport, Change, ComponentDigest, DexSummary, EntryDigest,
+    IncrementalAnalysis,
+};
+pub use crate::errors::APKError;
+pub use crate::models::{
+    Activity, ActivityAlias, Attribution, CompatibleScreen, ComponentKind, DeclaredPermission,
+    ExportReason, ExportedComponent, Feature, IconKind, Instrumentation, IntentData, IntentFilter,
+    NormalizedFile, Permission, PermissionsByProtection, Provider, Receiver, Service, SizeEstimate,
+    SupportsScreens, XapkExpansion, XapkManifest, XapkSplit,
+};
+pub use crate::permissions::ProtectionLevel;
+pub use crate::splits::{Split, SplitApkSet, SplitInfo};
+pub use crate::xapk::{SplitRole, Xapk, XapkMember};
@@ -0,0 +1,230 @@
+//! Bundles of split apks in the xapk format (APKPure and other stores).
+//!
+//! [Apk] opens xapk files too, but it only picks the base apk. [Xapk] shows the whole
+//! bundle: inner apks with their roles, expansion files and the store manifest.
+//! To analyze all inner apks at once, see [SplitApkSet](crate::splits::SplitApkSet).
+//!
+//! ```ignore
+//! let xapk = Xapk::new("./bundle.xapk")?;
+//! for member in xapk.members() {
+//!     println!("{} - {}", member.file, member.role);
+//! }
+//! let base = xapk.base_apk()?;
+//! ```
+
+use std::fmt;
+use std::path::Path;
+
+use apk_info_zip::ZipEntry;
+use serde::Serialize;
+
+use crate::apk::Apk;
+use crate::errors::APKError;
+use crate::models::{XapkExpansion, XapkManifest};
+
+/// Path of the store manifest inside the bundle.
+const MANIFEST_PATH: &str = "manifest.json";
+
+/// Default icon path, used when the manifest doesn't declare one.
+const ICON_PATH: &str = "icon.png";
+
+/// Role of the inner apk in the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "role", content = "split", rename_all = "snake_case")]
+pub enum SplitRole {
+    /// The base apk with the main manifest and code
+    Base,
+
+    /// Configuration split with native libraries, resources for density or language
+    Config(String),
+
+    /// Dynamic feature module
+    Feature(String),
+}
+
+impl SplitRole {
+    /// Detects the role by the split name from the manifest or the file name.
+    ///
+    /// The base apk has no split name in its `AndroidManifest.xml`, stores name it
+    /// `base` or after the package.
+    pub(crate) fn from_split(split: &str, package_name: &str) -> SplitRole {
+        if split.is_empty() || split == "base" || split == package_name {
+            SplitRole::Base
+        } else if split.starts_with("config.") {
+            SplitRole::Config(split.to_string())
+        } else {
+            SplitRole::Feature(split.to_string())
+        }
+    }
+}
+
+impl fmt::Display for SplitRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplitRole::Base => write!(f, "base"),
+            SplitRole::Config(split) => write!(f, "config ({split})"),
+            SplitRole::Feature(split) => write!(f, "feature ({split})"),
+        }
+    }
+}
+
+/// Inner apk of the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct XapkMember {
+    /// Path to the apk inside the bundle
+    pub file: String,
+
+    /// Role of the apk
+    #[serde(flatten)]
+    pub role: SplitRole,
+
+    /// Uncompressed size in bytes
+    pub size: u64,
+}
+
+/// Parsed xapk bundle, see the [module](self) docs.
+#[derive(Debug)]
+pub struct Xapk {
+    zip: ZipEntry,
+    manifest: XapkManifest,
+}
+
+impl Xapk {
+    /// Reads and parses the bundle, see [Xapk::open_mmap] for multi-GB bundles.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Xapk, APKError> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Opens and parses the bundle, the file is memory-mapped so inner apks are loaded
+    /// only when they are read.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified while the bundle is alive, see [apk_info_zip::ZipData::map].
+    pub unsafe fn open_mmap<P: AsRef<Path>>(path: P) -> Result<Xapk, APKError> {
+        let path = path.as_ref();
+        if std::fs::metadata(path)?.len() == 0 {
+            return Err(APKError::InvalidInput("got empty file"));
+        }
+
+        // SAFETY: forwarded to the caller
+        Self::from_zip(unsafe { ZipEntry::open_mmap(path) }?)
+    }
+
+    /// Parses the bundle from the given bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Xapk, APKError> {
+        if data.is_empty() {
+            return Err(APKError::InvalidInput("got empty file"));
+        }
+
+        Self::from_zip(ZipEntry::new(data)?)
+    }
+
+    fn from_zip(zip: ZipEntry) -> Result<Xapk, APKError> {
+        let (manifest, _) = zip
+            .read(MANIFEST_PATH)
+            .map_err(|_| APKError::InvalidInput("can't find manifest.json, is it xapk?"))?;
+        let manifest = serde_json::from_slice(&manifest)?;
+
+        Ok(Xapk { zip, manifest })
+    }
+
+    /// Returns the parsed `manifest.json`.
+    #[inline]
+    pub fn manifest(&self) -> &XapkManifest {
+        &self.manifest
+    }
+
+    /// Returns the list of files in the bundle.
+    #[inline]
+    pub fn namelist(&self) -> impl Iterator<Item = &str> + '_ {
+        self.zip.namelist()
+    }
+
+    /// Returns inner apks with their roles, the base apk goes first.
+    ///
+    /// Apks are taken from `split_apks` of the manifest, old bundles without it
+    /// are scanned for `*.apk` files in the root.
+    pub fn members(&self) -> Vec<XapkMember> {
+        let mut members: Vec<XapkMember> = if self.manifest.split_apks.is_empty() {
+            self.zip
+                .namelist()
+                .filter(|name| !name.contains('/') && name.ends_with(".apk"))
+                .map(|name| (name, name.trim_end_matches(".apk")))
+                .map(|(file, split)| self.member(file, split))
+                .collect()
+        } else {
+            self.manifest
+                .split_apks
+                .iter()
+                .map(|split| self.member(&split.file, &split.id))
+                .collect()
+        };
+
+        members.sort_by_key(|member| member.role != SplitRole::Base);
+
+        members
+    }
+
+    fn member(&self, file: &str, split: &str) -> XapkMember {
+        XapkMember {
+            file: file.to_string(),
+            role: SplitRole::from_split(split, &self.manifest.package_name),
+            size: inner_size(&self.zip, file),
+        }
+    }
+
+    /// Returns expansion files, from the manifest or `*.obb` files in the bundle.
+    pub fn expansions(&self) -> Vec<XapkExpansion> {
+        if !self.manifest.expansions.is_empty() {
+            return self.manifest.expansions.clone();
+        }
+
+        self.zip
+            .namelist()
+            .filter(|name| name.ends_with(".obb"))
+            .map(|name| XapkExpansion {
+                file: name.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Returns the path to the icon, if it exists in the bundle.
+    pub fn icon(&self) -> Option<&str> {
+        let icon = self.manifest.icon.as_deref().unwrap_or(ICON_PATH);
+
+        self.zip.namelist().find(|name| *name == icon)
+    }
+
+    /// Reads and parses the inner apk.
+    #[inline]
+    pub fn read_apk(&self, file: &str) -> Result<Apk, APKError> {
+        read_inner_apk(&self.zip, file)
+    }
+
+    /// Reads and parses the base apk.
+    pub fn base_apk(&self) -> Result<Apk, APKError> {
+        let base = self
+            .members()
+            .into_iter()
+            .find(|member| member.role == SplitRole::Base)
+            .ok_or(APKError::InvalidInput("can't find base apk in xapk"))?;
+
+        self.read_apk(&base.file)
+    }
+}
+
+/// Reads and parses the apk inside the bundle or split set.
+pub(crate) fn read_inner_apk(zip: &ZipEntry, file: &str) -> Result<Apk, APKError> {
+    let (data, _) = zip.read(file)?;
+
+    Apk::from_bytes(data)
+}
+
+/// Uncompressed size of the file inside the bundle or split set.
+pub(crate) fn inner_size(zip: &ZipEntry, file: &str) -> u64 {
+    zip.get_file_sizes(file)
+        .map(|(_, uncompressed)| uncompressed)
+        .unwrap_or_default()
+}
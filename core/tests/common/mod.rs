@@ -0,0 +1,1211 @@
+//! Builders for synthetic fixtures used by the regression tests.
+//!
+//! Samples from the wild can't be shipped with the repository, so every fixture is built
+//! from scratch: binary manifest, resource table and the zip container, with knobs to
+//! reproduce the tricks seen in malware.
+
+#![allow(dead_code)]
+
+use std::fmt::{self, Write as _};
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use apk_info::{Apk, Signature, ZipEntry};
+use flate2::Compression;
+use flate2::write::DeflateEncoder;
+use sha1::{Digest, Sha1};
+
+pub const ANDROID_NS: &str = "http://schemas.android.com/apk/res/android";
+
+/// Identifiers of the framework attributes used by the fixtures.
+const ANDROID_ATTRS: &[(&str, u32)] = &[
+    ("label", 0x01010001),
+    ("icon", 0x01010002),
+    ("name", 0x01010003),
+    ("permission", 0x01010006),
+    ("readPermission", 0x01010007),
+    ("writePermission", 0x01010008),
+    ("protectionLevel", 0x01010009),
+    ("enabled", 0x0101000e),
+    ("debuggable", 0x0101000f),
+    ("exported", 0x01010010),
+    ("authorities", 0x01010018),
+    ("mimeType", 0x01010026),
+    ("scheme", 0x01010027),
+    ("host", 0x01010028),
+    ("port", 0x01010029),
+    ("path", 0x0101002a),
+    ("pathPrefix", 0x0101002b),
+    ("pathPattern", 0x0101002c),
+    ("targetActivity", 0x01010202),
+    ("autoVerify", 0x010104ee),
+    ("versionCodeMajor", 0x01010576),
+    ("appComponentFactory", 0x0101057a),
+    ("minSdkVersion", 0x0101020c),
+    ("versionCode", 0x0101021b),
+    ("versionName", 0x0101021c),
+    ("targetSdkVersion", 0x01010270),
+    ("smallScreens", 0x01010284),
+    ("normalScreens", 0x01010285),
+    ("largeScreens", 0x01010286),
+    ("xlargeScreens", 0x010102bf),
+    ("screenSize", 0x010102ca),
+];
+
+// ------------------------------------------------------------------------------------------------
+// AXML
+// ------------------------------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Str(String),
+    Int(u32),
+    Bool(bool),
+    /// Raw typed value, for example a reference: `(0x01, 0x7f020000)`
+    Typed(u8, u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub tag: String,
+    pub attrs: Vec<(String, Value)>,
+    pub children: Vec<Node>,
+}
+
+/// Creates an element, attributes with `android:` prefix are placed into the android namespace.
+pub fn el(tag: &str, attrs: &[(&str, Value)], children: Vec<Node>) -> Node {
+    Node {
+        tag: tag.to_string(),
+        attrs: attrs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect(),
+        children,
+    }
+}
+
+pub fn s(value: &str) -> Value {
+    Value::Str(value.to_string())
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AxmlOptions {
+    /// Replace names of the android attributes in the string pool with garbage,
+    /// only the resource map keeps the real identifiers.
+    pub obfuscate_attribute_names: bool,
+
+    /// Insert a junk chunk before the string pool.
+    pub junk_before_string_pool: bool,
+}
+
+pub fn build_axml(root: &Node, options: AxmlOptions) -> Vec<u8> {
+    // android attributes go first, so their indexes match the resource map
+    let mut attr_names: Vec<String> = Vec::new();
+    let mut others: Vec<String> = Vec::new();
+    collect_strings(root, &mut attr_names, &mut others);
+    for value in ["android", ANDROID_NS] {
+        push_unique(&mut others, value);
+    }
+
+    let mut strings: Vec<String> = attr_names.clone();
+    for value in others {
+        push_unique(&mut strings, &value);
+    }
+
+    let index = |value: &str| strings.iter().position(|s| s == value).unwrap() as u32;
+
+    let pool_strings: Vec<String> = strings
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            if options.obfuscate_attribute_names && i < attr_names.len() {
+                // same length garbage, like obfuscators do
+                "\u{1}".repeat(value.len())
+            } else {
+                value.clone()
+            }
+        })
+        .collect();
+
+    let mut body = Vec::new();
+    if options.junk_before_string_pool {
+        chunk(&mut body, 0x0042, 8, &[0xde, 0xad, 0xbe, 0xef]);
+    }
+    body.extend(string_pool(&pool_strings));
+
+    let mut resource_map = Vec::new();
+    for name in &attr_names {
+        let id = ANDROID_ATTRS
+            .iter()
+            .find(|(n, _)| n == name)
+            .unwrap_or_else(|| panic!("unknown android attribute: {name}"))
+            .1;
+        resource_map.extend(id.to_le_bytes());
+    }
+    chunk(&mut body, 0x0180, 8, &resource_map);
+
+    let ns = [index("android"), index(ANDROID_NS)];
+    xml_node(&mut body, 0x0100, &[ns[0], ns[1]]);
+    emit_node(&mut body, root, &index);
+    xml_node(&mut body, 0x0101, &[ns[0], ns[1]]);
+
+    let mut out = Vec::new();
+    chunk(&mut out, 0x0003, 8, &body);
+    out
+}
+
+fn collect_strings(node: &Node, attr_names: &mut Vec<String>, others: &mut Vec<String>) {
+    for (name, value) in &node.attrs {
+        match name.strip_prefix("android:") {
+            Some(name) => push_unique(attr_names, name),
+            None => push_unique(others, name),
+        }
+        if let Value::Str(value) = value {
+            push_unique(others, value);
+        }
+    }
+    push_unique(others, &node.tag);
+
+    for child in &node.children {
+        collect_strings(child, attr_names, others);
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: &str) {
+    if !values.iter().any(|v| v == value) {
+        values.push(value.to_string());
+    }
+}
+
+fn emit_node(out: &mut Vec<u8>, node: &Node, index: &dyn Fn(&str) -> u32) {
+    let mut attrs = Vec::new();
+    for (name, value) in &node.attrs {
+        let (ns, name) = match name.strip_prefix("android:") {
+            Some(name) => (index(ANDROID_NS), name),
+            None => (u32::MAX, name.as_str()),
+        };
+
+        let (raw, data_type, data) = match value {
+            Value::Str(v) => (index(v), 0x03, index(v)),
+            Value::Int(v) => (u32::MAX, 0x10, *v),
+            Value::Bool(v) => (u32::MAX, 0x12, if *v { u32::MAX } else { 0 }),
+            Value::Typed(t, v) => (u32::MAX, *t, *v),
+        };
+
+        for v in [ns, index(name), raw] {
+            attrs.extend(v.to_le_bytes());
+        }
+        attrs.extend(8u16.to_le_bytes());
+        attrs.extend([0, data_type]);
+        attrs.extend(data.to_le_bytes());
+    }
+
+    let mut start = Vec::new();
+    for v in [1u32, u32::MAX, u32::MAX, index(&node.tag)] {
+        start.extend(v.to_le_bytes());
+    }
+    for v in [0x14u16, 0x14, node.attrs.len() as u16, 0, 0, 0] {
+        start.extend(v.to_le_bytes());
+    }
+    start.extend(attrs);
+    chunk(out, 0x0102, 0x10, &start);
+
+    for child in &node.children {
+        emit_node(out, child, index);
+    }
+
+    xml_node(out, 0x0103, &[u32::MAX, index(&node.tag)]);
+}
+
+/// Namespace and end element nodes share the same layout.
+fn xml_node(out: &mut Vec<u8>, type_: u16, values: &[u32; 2]) {
+    let mut data = Vec::new();
+    for v in [1u32, u32::MAX, values[0], values[1]] {
+        data.extend(v.to_le_bytes());
+    }
+    chunk(out, type_, 0x10, &data);
+}
+
+/// Writes a chunk, `data` contains everything after the 8 bytes of the common header.
+fn chunk(out: &mut Vec<u8>, type_: u16, header_size: u16, data: &[u8]) {
+    out.extend(type_.to_le_bytes());
+    out.extend(header_size.to_le_bytes());
+    out.extend((8 + data.len() as u32).to_le_bytes());
+    out.extend(data);
+}
+
+/// Builds UTF-8 string pool.
+pub fn string_pool(strings: &[String]) -> Vec<u8> {
+    let mut offsets = Vec::new();
+    let mut data = Vec::new();
+
+    for value in strings {
+        offsets.extend((data.len() as u32).to_le_bytes());
+        utf8_length(&mut data, value.chars().count());
+        utf8_length(&mut data, value.len());
+        data.extend(value.as_bytes());
+        data.push(0);
+    }
+    while data.len() % 4 != 0 {
+        data.push(0);
+    }
+
+    let strings_start = 0x1c + offsets.len() as u32;
+    let mut body = Vec::new();
+    for v in [strings.len() as u32, 0, 0x100, strings_start, 0] {
+        body.extend(v.to_le_bytes());
+    }
+    body.extend(offsets);
+    body.extend(data);
+
+    let mut out = Vec::new();
+    chunk(&mut out, 0x0001, 0x1c, &body);
+    out
+}
+
+fn utf8_length(out: &mut Vec<u8>, len: usize) {
+    if len > 0x7f {
+        out.push(((len >> 8) as u8) | 0x80);
+    }
+    out.push(len as u8);
+}
+
+// ------------------------------------------------------------------------------------------------
+// ARSC
+// ------------------------------------------------------------------------------------------------
+
+/// Resource type with entries for the default config.
+pub struct ResType {
+    pub name: &'static str,
+    pub keys: Vec<&'static str>,
+    pub values: Vec<Option<String>>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ArscOptions {
+    /// Write types as sparse, the entries are looked up by index.
+    pub sparse: bool,
+
+    /// Insert a junk chunk before the global string pool (APKEditor TableConfuser).
+    pub junk_before_string_pool: bool,
+
+    /// Add a second config which claims more entry offsets than the chunk can hold.
+    pub oversized_type: bool,
+}
+
+/// Builds resource table with a single `0x7f` package, all values are strings.
+pub fn build_arsc(types: &[ResType], options: ArscOptions) -> Vec<u8> {
+    let mut global: Vec<String> = Vec::new();
+    let mut keys: Vec<String> = Vec::new();
+    for t in types {
+        for key in &t.keys {
+            push_unique(&mut keys, key);
+        }
+    }
+
+    let mut chunks = Vec::new();
+    for (type_id, t) in types.iter().enumerate() {
+        let type_id = type_id as u8 + 1;
+
+        // type spec
+        let mut spec = vec![type_id, 0, 0, 0];
+        spec.extend((t.keys.len() as u32).to_le_bytes());
+        spec.extend(vec![0u8; 4 * t.keys.len()]);
+        chunk(&mut chunks, 0x0202, 16, &spec);
+
+        let mut offsets = Vec::new();
+        let mut entries = Vec::new();
+        let mut present = 0u32;
+        for (i, (key, value)) in t.keys.iter().zip(&t.values).enumerate() {
+            let Some(value) = value else {
+                if !options.sparse {
+                    offsets.extend(u32::MAX.to_le_bytes());
+                }
+                continue;
+            };
+
+            if options.sparse {
+                offsets.extend((i as u16).to_le_bytes());
+                offsets.extend(((entries.len() / 4) as u16).to_le_bytes());
+            } else {
+                offsets.extend((entries.len() as u32).to_le_bytes());
+            }
+            present += 1;
+
+            push_unique(&mut global, value);
+            let key_index = keys.iter().position(|k| k == key).unwrap() as u32;
+            let value_index = global.iter().position(|v| v == value).unwrap() as u32;
+
+            entries.extend(8u16.to_le_bytes());
+            entries.extend(0u16.to_le_bytes());
+            entries.extend(key_index.to_le_bytes());
+            entries.extend(8u16.to_le_bytes());
+            entries.extend([0, 0x03]);
+            entries.extend(value_index.to_le_bytes());
+        }
+
+        let entry_count = if options.sparse {
+            present
+        } else {
+            t.keys.len() as u32
+        };
+        chunks.extend(res_type(
+            type_id,
+            options.sparse as u8,
+            entry_count,
+            &config(None),
+            &offsets,
+            &entries,
+        ));
+
+        if options.oversized_type {
+            // claims a million entries, but holds nothing
+            chunks.extend(res_type(
+                type_id,
+                0,
+                1_000_000,
+                &config(Some("ru")),
+                &[],
+                &[],
+            ));
+        }
+    }
+
+    let type_names: Vec<String> = types.iter().map(|t| t.name.to_string()).collect();
+    let type_pool = string_pool(&type_names);
+    let key_pool = string_pool(&keys);
+
+    let header_size = 288u32;
+    let mut package = Vec::new();
+    package.extend(0x7fu32.to_le_bytes());
+    let mut name = "com.example.regression"
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<u8>>();
+    name.resize(256, 0);
+    package.extend(name);
+    for v in [
+        header_size,
+        types.len() as u32,
+        header_size + type_pool.len() as u32,
+        keys.len() as u32,
+        0,
+    ] {
+        package.extend(v.to_le_bytes());
+    }
+    package.extend(type_pool);
+    package.extend(key_pool);
+    package.extend(chunks);
+
+    let mut body = 1u32.to_le_bytes().to_vec();
+    if options.junk_before_string_pool {
+        chunk(&mut body, 0x0042, 8, &[0u8; 16]);
+    }
+    body.extend(string_pool(&global));
+    chunk(&mut body, 0x0200, header_size as u16, &package);
+
+    let mut out = Vec::new();
+    chunk(&mut out, 0x0002, 12, &body);
+    out
+}
+
+fn res_type(
+    type_id: u8,
+    flags: u8,
+    entry_count: u32,
+    config: &[u8],
+    offsets: &[u8],
+    entries: &[u8],
+) -> Vec<u8> {
+    let header_size = 20 + config.len() as u32;
+    let entries_start = header_size + offsets.len() as u32;
+
+    let mut data = vec![type_id, flags, 0, 0];
+    data.extend(entry_count.to_le_bytes());
+    data.extend(entries_start.to_le_bytes());
+    data.extend(config);
+    data.extend(offsets);
+    data.extend(entries);
+
+    let mut out = Vec::new();
+    chunk(&mut out, 0x0201, header_size as u16, &data);
+    out
+}
+
+fn config(language: Option<&str>) -> Vec<u8> {
+    let mut out = vec![0u8; 64];
+    out[..4].copy_from_slice(&64u32.to_le_bytes());
+    if let Some(language) = language {
+        out[8..10].copy_from_slice(language.as_bytes());
+    }
+    out
+}
+
+// ------------------------------------------------------------------------------------------------
+// ZIP
+// ------------------------------------------------------------------------------------------------
+
+pub struct ZipFile {
+    pub name: String,
+    pub data: Vec<u8>,
+
+    /// Deflate the data before writing.
+    pub deflate: bool,
+
+    /// Compression method written into both headers, the real one by default.
+    pub method: Option<u16>,
+
+    /// Compression method written only into the local header, overrides [ZipFile::method].
+    pub local_method: Option<u16>,
+
+    /// Write zero sizes into the local header, only the central directory keeps them.
+    pub zero_local_sizes: bool,
+}
+
+impl ZipFile {
+    pub fn stored(name: &str, data: Vec<u8>) -> ZipFile {
+        ZipFile {
+            name: name.to_string(),
+            data,
+            deflate: false,
+            method: None,
+            local_method: None,
+            zero_local_sizes: false,
+        }
+    }
+
+    pub fn deflated(name: &str, data: Vec<u8>) -> ZipFile {
+        ZipFile {
+            deflate: true,
+            ..ZipFile::stored(name, data)
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ZipOptions<'a> {
+    /// APK Signing Block placed right before the central directory.
+    pub signing_block: Option<&'a [u8]>,
+
+    /// Data before the first local header, offsets are adjusted like `zip -A` does.
+    pub prepended: &'a [u8],
+
+    /// Archive comment in the End of Central Directory.
+    pub comment: &'a [u8],
+}
+
+/// Writes the zip archive.
+pub fn build_zip(files: &[ZipFile], options: ZipOptions) -> Vec<u8> {
+    let mut out = options.prepended.to_vec();
+    let mut central = Vec::new();
+
+    for file in files {
+        let data = if file.deflate {
+            let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&file.data).unwrap();
+            encoder.finish().unwrap()
+        } else {
+            file.data.clone()
+        };
+
+        let method = file.method.unwrap_or(if file.deflate { 8 } else { 0 });
+        let crc = crc32(&file.data);
+        let offset = out.len() as u32;
+        let (local_compressed, local_uncompressed) = if file.zero_local_sizes {
+            (0, 0)
+        } else {
+            (data.len() as u32, file.data.len() as u32)
+        };
+
+        out.extend(b"PK\x03\x04");
+        for v in [20u16, 0, file.local_method.unwrap_or(method), 0, 0] {
+            out.extend(v.to_le_bytes());
+        }
+        for v in [crc, local_compressed, local_uncompressed] {
+            out.extend(v.to_le_bytes());
+        }
+        out.extend((file.name.len() as u16).to_le_bytes());
+        out.extend(0u16.to_le_bytes());
+        out.extend(file.name.as_bytes());
+        out.extend(&data);
+
+        central.extend(b"PK\x01\x02");
+        for v in [20u16, 20, 0, method, 0, 0] {
+            central.extend(v.to_le_bytes());
+        }
+        for v in [crc, data.len() as u32, file.data.len() as u32] {
+            central.extend(v.to_le_bytes());
+        }
+        for v in [file.name.len() as u16, 0, 0, 0, 0] {
+            central.extend(v.to_le_bytes());
+        }
+        central.extend(0u32.to_le_bytes());
+        central.extend(offset.to_le_bytes());
+        central.extend(file.name.as_bytes());
+    }
+
+    if let Some(block) = options.signing_block {
+        out.extend(block);
+    }
+
+    let central_offset = out.len() as u32;
+    out.extend(&central);
+
+    out.extend(b"PK\x05\x06");
+    for v in [0u16, 0, files.len() as u16, files.len() as u16] {
+        out.extend(v.to_le_bytes());
+    }
+    out.extend((central.len() as u32).to_le_bytes());
+    out.extend(central_offset.to_le_bytes());
+    out.extend((options.comment.len() as u16).to_le_bytes());
+    out.extend(options.comment);
+
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+// ------------------------------------------------------------------------------------------------
+// APK Signing Block
+// ------------------------------------------------------------------------------------------------
+
+/// Certificates of the fixture signers, self-signed and made only for these tests.
+pub const SIGNER_1: &[u8] = include_bytes!("../fixtures/signer1.der");
+pub const SIGNER_2: &[u8] = include_bytes!("../fixtures/signer2.der");
+
+fn prefixed(data: &[u8]) -> Vec<u8> {
+    let mut out = (data.len() as u32).to_le_bytes().to_vec();
+    out.extend(data);
+    out
+}
+
+/// v2 signer, digests, signatures and the public key are left empty.
+pub fn signer_v2(certificates: &[&[u8]]) -> Vec<u8> {
+    let certificates: Vec<u8> = certificates.iter().flat_map(|c| prefixed(c)).collect();
+
+    let mut signed_data = prefixed(&[]);
+    signed_data.extend(prefixed(&certificates));
+    signed_data.extend(prefixed(&[]));
+
+    let mut signer = prefixed(&signed_data);
+    signer.extend(prefixed(&[]));
+    signer.extend(prefixed(&[]));
+    prefixed(&signer)
+}
+
+/// v3 signer, digests, signatures and the public key are left empty.
+pub fn signer_v3(certificates: &[&[u8]]) -> Vec<u8> {
+    signer_v3_with_lineage(certificates, &[])
+}
+
+/// v3 signer with the proof-of-rotation attribute from `(certificate, flags)` nodes,
+/// the signatures of the nodes are left empty.
+pub fn signer_v3_with_lineage(certificates: &[&[u8]], lineage: &[(&[u8], u32)]) -> Vec<u8> {
+    let certificates: Vec<u8> = certificates.iter().flat_map(|c| prefixed(c)).collect();
+
+    let mut attributes = Vec::new();
+    if !lineage.is_empty() {
+        let mut value = ZipEntry::PROOF_OF_ROTATION_ATTR_ID.to_le_bytes().to_vec();
+        value.extend(1u32.to_le_bytes());
+        for (i, (certificate, flags)) in lineage.iter().enumerate() {
+            // rsa pkcs1 sha256
+            let mut signed_data = prefixed(certificate);
+            signed_data.extend(0x103u32.to_le_bytes());
+
+            let mut node = prefixed(&signed_data);
+            node.extend(flags.to_le_bytes());
+            node.extend((if i == 0 { 0u32 } else { 0x103 }).to_le_bytes());
+            node.extend(prefixed(&[]));
+            value.extend(prefixed(&node));
+        }
+        attributes.extend(prefixed(&value));
+    }
+
+    let mut signed_data = prefixed(&[]);
+    signed_data.extend(prefixed(&certificates));
+    signed_data.extend(28u32.to_le_bytes());
+    signed_data.extend(u32::MAX.to_le_bytes());
+    signed_data.extend(prefixed(&attributes));
+
+    let mut signer = prefixed(&signed_data);
+    signer.extend(28u32.to_le_bytes());
+    signer.extend(u32::MAX.to_le_bytes());
+    signer.extend(prefixed(&[]));
+    signer.extend(prefixed(&[]));
+    prefixed(&signer)
+}
+
+/// Builds the APK Signing Block from `(id, value)` pairs.
+pub fn signing_block(pairs: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut pairs_data = Vec::new();
+    for (id, value) in pairs {
+        pairs_data.extend((value.len() as u64 + 4).to_le_bytes());
+        pairs_data.extend(id.to_le_bytes());
+        pairs_data.extend(value);
+    }
+
+    let size = (pairs_data.len() + 8 + 16) as u64;
+    let mut out = size.to_le_bytes().to_vec();
+    out.extend(pairs_data);
+    out.extend(size.to_le_bytes());
+    out.extend(b"APK Sig Block 42");
+    out
+}
+
+/// Block value with the list of signers.
+pub fn signers(signers: &[Vec<u8>]) -> Vec<u8> {
+    prefixed(&signers.concat())
+}
+
+// ------------------------------------------------------------------------------------------------
+// DEX
+// ------------------------------------------------------------------------------------------------
+
+/// Dex file defining empty classes, the names are java names: `com.example.MainActivity`.
+///
+/// Only the string, type and class tables are filled, there is no code and no map.
+pub fn build_dex(classes: &[&str]) -> Vec<u8> {
+    build_dex_with_methods(classes, &[])
+}
+
+/// Same as [build_dex], the methods are added to the method table without code:
+/// `("com.example.MainActivity", "onCreate", "(Landroid/os/Bundle;)V")`.
+pub fn build_dex_with_methods(classes: &[&str], methods: &[(&str, &str, &str)]) -> Vec<u8> {
+    const HEADER_SIZE: u32 = 0x70;
+    const CLASS_DEF_SIZE: u32 = 0x20;
+    const NO_INDEX: u32 = u32::MAX;
+
+    fn index_of(items: &mut Vec<String>, value: &str) -> u32 {
+        match items.iter().position(|item| item == value) {
+            Some(index) => index as u32,
+            None => {
+                items.push(value.to_string());
+                items.len() as u32 - 1
+            }
+        }
+    }
+
+    fn descriptor(class: &str) -> String {
+        format!("L{};", class.replace('.', "/"))
+    }
+
+    // the parser doesn't require the sorted order of the tables
+    let mut strings: Vec<String> = Vec::new();
+    let mut types: Vec<String> = Vec::new();
+    for class in classes {
+        index_of(&mut strings, &descriptor(class));
+        index_of(&mut types, &descriptor(class));
+    }
+
+    let mut protos: Vec<String> = Vec::new();
+    let mut proto_ids: Vec<(u32, u32, Vec<u16>)> = Vec::new();
+    let mut method_ids: Vec<(u16, u16, u32)> = Vec::new();
+    for (class, name, proto) in methods {
+        let (parameters, return_type) = proto[1..].split_once(')').expect("proto descriptor");
+
+        // L and array descriptors run until `;` or the element type, primitives are one char
+        let mut parameter_types = Vec::new();
+        let mut rest = parameters;
+        while !rest.is_empty() {
+            let dims = rest.len() - rest.trim_start_matches('[').len();
+            let end = match rest.as_bytes()[dims] {
+                b'L' => rest.find(';').expect("class descriptor") + 1,
+                _ => dims + 1,
+            };
+            parameter_types.push(&rest[..end]);
+            rest = &rest[end..];
+        }
+
+        let mut type_idx = |value: &str| {
+            index_of(&mut strings, value);
+            index_of(&mut types, value)
+        };
+        let class_idx = type_idx(&descriptor(class));
+        if !protos.iter().any(|p| p == proto) {
+            let shorty: String = std::iter::once(return_type)
+                .chain(parameter_types.iter().copied())
+                .map(|t| {
+                    if t.len() > 1 {
+                        'L'
+                    } else {
+                        t.chars().next().unwrap()
+                    }
+                })
+                .collect();
+            let return_idx = type_idx(return_type);
+            let parameters = parameter_types.iter().map(|t| type_idx(t) as u16).collect();
+            proto_ids.push((index_of(&mut strings, &shorty), return_idx, parameters));
+        }
+        let proto_idx = index_of(&mut protos, proto);
+        method_ids.push((
+            class_idx as u16,
+            proto_idx as u16,
+            index_of(&mut strings, name),
+        ));
+    }
+
+    let table = |offset: u32, count: usize| if count == 0 { 0 } else { offset };
+    let string_ids_off = HEADER_SIZE;
+    let type_ids_off = string_ids_off + strings.len() as u32 * 4;
+    let proto_ids_off = type_ids_off + types.len() as u32 * 4;
+    let method_ids_off = proto_ids_off + proto_ids.len() as u32 * 12;
+    let class_defs_off = method_ids_off + method_ids.len() as u32 * 8;
+    let data_off = class_defs_off + classes.len() as u32 * CLASS_DEF_SIZE;
+
+    // parameter lists first, they are aligned to 4 bytes
+    let mut data = Vec::new();
+    let mut parameters_off = Vec::new();
+    for (_, _, parameters) in &proto_ids {
+        if parameters.is_empty() {
+            parameters_off.push(0);
+            continue;
+        }
+        parameters_off.push(data_off + data.len() as u32);
+        data.extend_from_slice(&(parameters.len() as u32).to_le_bytes());
+        for idx in parameters {
+            data.extend_from_slice(&idx.to_le_bytes());
+        }
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    let mut string_ids = Vec::new();
+    for value in &strings {
+        string_ids.extend_from_slice(&(data_off + data.len() as u32).to_le_bytes());
+        // uleb128 length, the strings are short
+        data.push(value.len() as u8);
+        data.extend_from_slice(value.as_bytes());
+        data.push(0);
+    }
+    let file_size = data_off + data.len() as u32;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"dex\n035\0");
+    out.extend_from_slice(&[0; 24]); // checksum, signature
+    for v in [file_size, HEADER_SIZE, 0x12345678, 0, 0, 0] {
+        out.extend_from_slice(&v.to_le_bytes()); // sizes, endian tag, link and map
+    }
+    for v in [
+        strings.len() as u32,
+        string_ids_off,
+        types.len() as u32,
+        type_ids_off,
+        proto_ids.len() as u32,
+        table(proto_ids_off, proto_ids.len()),
+        0,
+        0,
+        method_ids.len() as u32,
+        table(method_ids_off, method_ids.len()),
+        classes.len() as u32,
+        class_defs_off,
+        data.len() as u32,
+        data_off,
+    ] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+
+    out.extend_from_slice(&string_ids);
+    for value in &types {
+        let idx = strings.iter().position(|s| s == value).unwrap() as u32;
+        out.extend_from_slice(&idx.to_le_bytes());
+    }
+    for ((shorty_idx, return_idx, _), parameters_off) in proto_ids.iter().zip(&parameters_off) {
+        for v in [*shorty_idx, *return_idx, *parameters_off] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    for (class_idx, proto_idx, name_idx) in &method_ids {
+        out.extend_from_slice(&class_idx.to_le_bytes());
+        out.extend_from_slice(&proto_idx.to_le_bytes());
+        out.extend_from_slice(&name_idx.to_le_bytes());
+    }
+    for index in 0..classes.len() as u32 {
+        // class, access flags, superclass, interfaces, source file, annotations, data, values
+        for v in [index, 1, NO_INDEX, 0, NO_INDEX, 0, 0, 0] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    out.extend_from_slice(&data);
+
+    out
+}
+
+/// Writes the checksum and the signature of the dex file as `d8` does, so it passes
+/// the verification.
+pub fn seal_dex(mut dex: Vec<u8>) -> Vec<u8> {
+    let signature = Sha1::digest(&dex[32..]);
+    dex[12..32].copy_from_slice(&signature);
+
+    let checksum = dex_checksum(&dex);
+    dex[8..12].copy_from_slice(&checksum.to_le_bytes());
+
+    dex
+}
+
+/// Adler-32 of the dex file after the checksum field.
+pub fn dex_checksum(dex: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in &dex[12..] {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+// ------------------------------------------------------------------------------------------------
+// ELF
+// ------------------------------------------------------------------------------------------------
+
+/// Options of the native library, 64-bit little endian aarch64.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfOptions<'a> {
+    pub soname: &'a str,
+    pub needed: &'a [&'a str],
+    /// Defined global functions, `puts` is always added as an undefined import
+    pub exports: &'a [&'a str],
+    /// Keeps the section headers, otherwise `e_shoff` is zero as in packed libraries
+    pub section_headers: bool,
+}
+
+impl Default for ElfOptions<'_> {
+    fn default() -> Self {
+        Self {
+            soname: "libnative.so",
+            needed: &["libc.so", "liblog.so"],
+            exports: &["JNI_OnLoad", "Java_com_example_Native_init"],
+            section_headers: true,
+        }
+    }
+}
+
+/// Builds the shared library with one loadable segment mapped at zero, so virtual
+/// addresses are file offsets: headers, strings, symbols, sysv hash and the dynamic section.
+pub fn build_elf(options: ElfOptions) -> Vec<u8> {
+    const HEADER_SIZE: usize = 64;
+    const PROGRAM_HEADER_SIZE: usize = 56;
+
+    let mut strings = vec![0u8];
+    let mut string = |value: &str| {
+        let offset = strings.len() as u64;
+        strings.extend(value.as_bytes());
+        strings.push(0);
+        offset
+    };
+    let soname = string(options.soname);
+    let needed: Vec<u64> = options.needed.iter().map(|name| string(name)).collect();
+
+    // null symbol, exports in the first section and the undefined import
+    let mut symbols = vec![0u8; 24];
+    let mut symbol = |name: u64, shndx: u16| {
+        symbols.extend((name as u32).to_le_bytes());
+        symbols.push(0x12);
+        symbols.push(0);
+        symbols.extend(shndx.to_le_bytes());
+        symbols.extend([0; 16]);
+    };
+    for export in options.exports {
+        symbol(string(export), 1);
+    }
+    symbol(string("puts"), 0);
+    let symbol_count = symbols.len() as u32 / 24;
+
+    let strings_offset = HEADER_SIZE + 2 * PROGRAM_HEADER_SIZE;
+    let symbols_offset = (strings_offset + strings.len()).next_multiple_of(8);
+    let hash_offset = symbols_offset + symbols.len();
+    let dynamic_offset = hash_offset + 16;
+
+    let mut dynamic = Vec::new();
+    let mut entry = |tag: u64, value: u64| {
+        dynamic.extend(tag.to_le_bytes());
+        dynamic.extend(value.to_le_bytes());
+    };
+    for name in needed {
+        entry(1, name);
+    }
+    entry(14, soname);
+    entry(4, hash_offset as u64);
+    entry(5, strings_offset as u64);
+    entry(6, symbols_offset as u64);
+    entry(0, 0);
+    let section_headers_offset = dynamic_offset + dynamic.len();
+
+    let mut out = Vec::new();
+    out.extend(b"\x7fELF\x02\x01\x01");
+    out.resize(16, 0);
+    out.extend(3u16.to_le_bytes());
+    out.extend(183u16.to_le_bytes());
+    out.extend(1u32.to_le_bytes());
+    out.extend(0u64.to_le_bytes());
+    out.extend((HEADER_SIZE as u64).to_le_bytes());
+    let shoff = if options.section_headers {
+        section_headers_offset as u64
+    } else {
+        0
+    };
+    out.extend(shoff.to_le_bytes());
+    out.extend(0u32.to_le_bytes());
+    out.extend((HEADER_SIZE as u16).to_le_bytes());
+    out.extend((PROGRAM_HEADER_SIZE as u16).to_le_bytes());
+    out.extend(2u16.to_le_bytes());
+    out.extend(64u16.to_le_bytes());
+    out.extend((if options.section_headers { 3u16 } else { 0 }).to_le_bytes());
+    out.extend(0u16.to_le_bytes());
+
+    let file_size = section_headers_offset + if options.section_headers { 3 * 64 } else { 0 };
+    let mut program_header = |kind: u32, offset: usize, size: usize| {
+        out.extend(kind.to_le_bytes());
+        out.extend(6u32.to_le_bytes());
+        out.extend((offset as u64).to_le_bytes());
+        out.extend((offset as u64).to_le_bytes());
+        out.extend((offset as u64).to_le_bytes());
+        out.extend((size as u64).to_le_bytes());
+        out.extend((size as u64).to_le_bytes());
+        out.extend(8u64.to_le_bytes());
+    };
+    program_header(1, 0, file_size);
+    program_header(2, dynamic_offset, dynamic.len());
+
+    out.extend(&strings);
+    out.resize(symbols_offset, 0);
+    out.extend(&symbols);
+    // nbucket and nchain, the buckets themselves are not needed to count the symbols
+    out.extend(1u32.to_le_bytes());
+    out.extend(symbol_count.to_le_bytes());
+    out.extend([0; 8]);
+    out.extend(&dynamic);
+
+    if options.section_headers {
+        let mut section = |kind: u32, offset: usize, size: usize, link: u32, entsize: u64| {
+            out.extend(0u32.to_le_bytes());
+            out.extend(kind.to_le_bytes());
+            out.extend(2u64.to_le_bytes());
+            out.extend((offset as u64).to_le_bytes());
+            out.extend((offset as u64).to_le_bytes());
+            out.extend((size as u64).to_le_bytes());
+            out.extend(link.to_le_bytes());
+            out.extend(0u32.to_le_bytes());
+            out.extend(8u64.to_le_bytes());
+            out.extend(entsize.to_le_bytes());
+        };
+        section(0, 0, 0, 0, 0);
+        section(11, symbols_offset, symbols.len(), 2, 24);
+        section(3, strings_offset, strings.len(), 0, 0);
+    }
+
+    out
+}
+
+// ------------------------------------------------------------------------------------------------
+// Report and snapshots
+// ------------------------------------------------------------------------------------------------
+
+/// Parses the apk with the manifest and the resource table if given.
+pub fn apk_from_manifest(manifest: &Node, resources: Option<Vec<u8>>) -> Apk {
+    let mut files = vec![ZipFile::deflated(
+        "AndroidManifest.xml",
+        build_axml(manifest, AxmlOptions::default()),
+    )];
+    if let Some(resources) = resources {
+        files.push(ZipFile::stored("resources.arsc", resources));
+    }
+
+    Apk::from_bytes(build_zip(&files, ZipOptions::default())).expect("can't parse fixture")
+}
+
+/// Writes the fixture into the temp directory and parses it.
+pub fn load(name: &str, data: &[u8]) -> Apk {
+    let dir = std::env::temp_dir().join(format!("apk-info-regression-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let path: PathBuf = dir.join(format!("{name}.apk"));
+    std::fs::write(&path, data).unwrap();
+
+    let apk = Apk::new(&path).unwrap_or_else(|e| panic!("can't parse fixture {name}: {e}"));
+    let _ = std::fs::remove_file(&path);
+    apk
+}
+
+/// Long lists are cut, so giant fixtures still produce readable snapshots.
+const LIST_LIMIT: usize = 5;
+
+fn write_list<T: fmt::Display>(out: &mut String, title: &str, items: impl IntoIterator<Item = T>) {
+    let items: Vec<T> = items.into_iter().collect();
+    writeln!(out, "{title}: {}", items.len()).unwrap();
+    for item in items.iter().take(LIST_LIMIT) {
+        writeln!(out, "  - {item}").unwrap();
+    }
+    if items.len() > LIST_LIMIT {
+        writeln!(out, "  ... {} more", items.len() - LIST_LIMIT).unwrap();
+    }
+}
+
+/// Stable textual representation of everything the library extracts from the apk.
+pub fn report(apk: &Apk) -> String {
+    let mut out = String::new();
+    let none = || "<none>".to_string();
+
+    writeln!(
+        out,
+        "package: {}",
+        apk.get_package_name().unwrap_or_else(none)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "version: {} ({})",
+        apk.get_version_name().unwrap_or_else(none),
+        apk.get_version_code().unwrap_or_else(none)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "sdk: min={} target={}",
+        apk.get_min_sdk_version().unwrap_or_else(none),
+        apk.get_target_sdk_version()
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "label: {}",
+        apk.get_application_label().unwrap_or_else(none)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "debuggable: {}",
+        apk.get_application_debuggable().unwrap_or_else(none)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "main activity: {}",
+        apk.get_main_activity().unwrap_or("<none>")
+    )
+    .unwrap();
+
+    write_list(&mut out, "permissions", apk.get_permissions());
+    write_list(
+        &mut out,
+        "activities",
+        apk.get_activities().filter_map(|a| a.name),
+    );
+    write_list(
+        &mut out,
+        "services",
+        apk.get_services().filter_map(|s| s.name),
+    );
+
+    for (title, entry) in [
+        ("manifest", apk.raw_manifest()),
+        ("resources", apk.raw_resources()),
+    ] {
+        match entry {
+            Ok((data, info)) => writeln!(
+                out,
+                "{title}: {} bytes, {:?} (central={}, local={})",
+                data.len(),
+                info.compression,
+                info.central_compression_method,
+                info.local_compression_method
+            )
+            .unwrap(),
+            Err(e) => writeln!(out, "{title}: error: {e}").unwrap(),
+        }
+    }
+
+    let mut names: Vec<&str> = apk.namelist().collect();
+    names.sort_unstable();
+    writeln!(out, "files: {}", names.len()).unwrap();
+    for name in names {
+        match apk.read(name) {
+            Ok((data, compression)) => {
+                writeln!(out, "  {name}: {} bytes, {compression:?}", data.len()).unwrap()
+            }
+            Err(e) => writeln!(out, "  {name}: error: {e}").unwrap(),
+        }
+    }
+
+    write_list(&mut out, "archive anomalies", apk.get_archive_anomalies());
+
+    match apk.get_signatures_ref() {
+        Ok(signatures) => {
+            writeln!(out, "signatures: {}", signatures.len()).unwrap();
+            for signature in signatures {
+                match signature {
+                    Signature::V1(certificates)
+                    | Signature::V2 { certificates, .. }
+                    | Signature::V3 { certificates, .. }
+                    | Signature::V31 { certificates, .. } => {
+                        writeln!(out, "  {}:", signature.name()).unwrap();
+                        for cert in certificates {
+                            writeln!(
+                                out,
+                                "    {} (serial {}, sha256 {})",
+                                cert.subject, cert.serial_number, cert.sha256_fingerprint
+                            )
+                            .unwrap();
+                        }
+                        if let Signature::V3 { lineage, .. } | Signature::V31 { lineage, .. } =
+                            signature
+                        {
+                            for node in lineage {
+                                writeln!(
+                                    out,
+                                    "    lineage: {} [{}]",
+                                    node.certificate.subject,
+                                    node.capabilities().join(", ")
+                                )
+                                .unwrap();
+                            }
+                        }
+                    }
+                    Signature::ApkChannelBlock(channel) => {
+                        writeln!(out, "  {}: {channel}", signature.name()).unwrap()
+                    }
+                    other => writeln!(out, "  {}", other.name()).unwrap(),
+                }
+            }
+        }
+        Err(e) => writeln!(out, "signatures: error: {e}").unwrap(),
+    }
+
+    out
+}
+
+/// Compares the output with `tests/snapshots/<name>.snap`.
+///
+/// Run with `UPDATE_SNAPSHOTS=1` to write the new snapshots after an intended change.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{name}.snap"));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!("missing snapshot {path:?}, run the tests with UPDATE_SNAPSHOTS=1")
+    });
+
+    assert_eq!(
+        expected, actual,
+        "snapshot {name} doesn't match, run the tests with UPDATE_SNAPSHOTS=1 if the change is intended"
+    );
+}
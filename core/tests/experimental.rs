@@ -0,0 +1,244 @@
+//! Tests of the `experimental` api on synthetic fixtures: the carver and the ELF parser.
+
+#![cfg(feature = "experimental")]
+
+mod common;
+
+use std::io::Write as _;
+use std::sync::Mutex;
+
+use apk_info::carve::carve;
+use apk_info::native::{ElfInfo, parse_elf};
+use apk_info::progress::{CancellationToken, Stage};
+use apk_info::{APKError, Apk, HiddenApiDatabase};
+use base64::Engine as _;
+use common::*;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+#[test]
+fn test_native_library() {
+    let expected = ElfInfo {
+        arch: "aarch64".to_string(),
+        bits: 64,
+        soname: Some("libnative.so".to_string()),
+        needed: vec!["libc.so".to_string(), "liblog.so".to_string()],
+        exported_symbols: vec![
+            "JNI_OnLoad".to_string(),
+            "Java_com_example_Native_init".to_string(),
+        ],
+        has_jni_onload: true,
+    };
+    assert_eq!(
+        parse_elf(&build_elf(ElfOptions::default())),
+        Some(expected.clone())
+    );
+
+    // packers strip the section headers, symbols are counted from the hash table
+    let stripped = build_elf(ElfOptions {
+        section_headers: false,
+        ..Default::default()
+    });
+    assert_eq!(parse_elf(&stripped), Some(expected));
+}
+
+#[test]
+fn test_truncated_native_library() {
+    let elf = build_elf(ElfOptions::default());
+
+    // the machine is the last field that must be present
+    assert_eq!(parse_elf(&elf[..19]), None);
+    assert_eq!(parse_elf(b"\x7fELF\x03\x01"), None);
+    for end in 20..elf.len() {
+        let info = parse_elf(&elf[..end]).expect("truncated ELF is still parsed");
+        assert_eq!(info.arch, "aarch64");
+    }
+}
+
+#[test]
+fn test_malformed_native_library() {
+    let elf = build_elf(ElfOptions {
+        section_headers: false,
+        ..Default::default()
+    });
+
+    // offsets of the program headers and the dynamic section close to the end of the address space
+    let mut overflow = elf.clone();
+    overflow[32..40].copy_from_slice(&(u64::MAX - 8).to_le_bytes());
+    assert_eq!(parse_elf(&overflow).unwrap().needed, Vec::<String>::new());
+
+    let mut overflow = elf.clone();
+    overflow[64 + 56 + 8..64 + 56 + 16].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(parse_elf(&overflow).unwrap().needed, Vec::<String>::new());
+
+    // names without the terminator are skipped instead of being read to the end of the file
+    let long_name = "A".repeat(8192);
+    let long = build_elf(ElfOptions {
+        exports: &["JNI_OnLoad", &long_name],
+        ..Default::default()
+    });
+    assert_eq!(parse_elf(&long).unwrap().exported_symbols, ["JNI_OnLoad"]);
+}
+
+#[test]
+fn test_carve_wrappers() {
+    let manifest = el("manifest", &[("package", s("com.example.carved"))], vec![]);
+    let apk = build_zip(
+        &[
+            ZipFile::deflated(
+                "AndroidManifest.xml",
+                build_axml(&manifest, AxmlOptions::default()),
+            ),
+            ZipFile::stored("classes.dex", b"dex\n035\0".to_vec()),
+        ],
+        ZipOptions::default(),
+    );
+
+    let sources = |input: &[u8]| -> Vec<String> {
+        carve(input)
+            .into_iter()
+            .map(|candidate| {
+                assert_eq!(candidate.data, apk);
+                candidate.source
+            })
+            .collect()
+    };
+
+    assert_eq!(sources(&apk), ["apk"]);
+
+    let bundle = build_zip(
+        &[
+            ZipFile::deflated("samples/app.apk", apk.clone()),
+            ZipFile::stored("readme.txt", b"password: infected".to_vec()),
+        ],
+        ZipOptions::default(),
+    );
+    assert_eq!(sources(&bundle), ["zip:samples/app.apk"]);
+
+    let mut tar = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(apk.len() as u64);
+    header.set_mode(0o644);
+    tar.append_data(&mut header, "files/app.apk", apk.as_slice())
+        .unwrap();
+    let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+    gzip.write_all(&tar.into_inner().unwrap()).unwrap();
+    assert_eq!(
+        sources(&gzip.finish().unwrap()),
+        ["gzip > tar:files/app.apk"]
+    );
+
+    let mut appended = b"\x89PNG\r\n\x1a\n".to_vec();
+    appended.extend(&apk);
+    assert_eq!(sources(&appended), ["offset:0x8"]);
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&apk);
+    let mail = format!("Content-Transfer-Encoding: base64\r\n\r\n{encoded}\r\n--boundary--\r\n");
+    assert_eq!(sources(mail.as_bytes()), ["base64:0x25"]);
+}
+
+#[test]
+fn test_carve_repeated_base64_magic() {
+    // every occurrence of the magic used to decode the rest of the run again
+    let text = "UEsDB".repeat(400_000);
+    assert_eq!(carve(text.as_bytes()), []);
+}
+
+/// Apk whose dex is only a header, the analysis passes must skip it instead of failing.
+fn apk_with_broken_dex() -> Apk {
+    let manifest = el("manifest", &[("package", s("com.example.broken"))], vec![]);
+    let data = build_zip(
+        &[
+            ZipFile::deflated(
+                "AndroidManifest.xml",
+                build_axml(&manifest, AxmlOptions::default()),
+            ),
+            ZipFile::stored("classes.dex", b"dex\n035\0".to_vec()),
+        ],
+        ZipOptions::default(),
+    );
+
+    Apk::from_bytes(data).unwrap()
+}
+
+#[test]
+fn test_string_usages_skip_broken_dex() {
+    let apk = apk_with_broken_dex();
+    assert_eq!(apk.find_string_usages("https?://", None, None).unwrap(), []);
+    assert!(apk.find_string_usages("(", None, None).is_err());
+}
+
+#[test]
+fn test_package_profile_skips_broken_dex() {
+    assert_eq!(
+        apk_with_broken_dex().package_profile(None, None).unwrap(),
+        []
+    );
+}
+
+/// Apk with two small dex files.
+fn apk_with_two_dex() -> Apk {
+    let manifest = el(
+        "manifest",
+        &[("package", s("com.example.multidex"))],
+        vec![],
+    );
+    let data = build_zip(
+        &[
+            ZipFile::deflated(
+                "AndroidManifest.xml",
+                build_axml(&manifest, AxmlOptions::default()),
+            ),
+            ZipFile::stored("classes.dex", build_dex(&["Lcom/example/a/A;"])),
+            ZipFile::stored("classes2.dex", build_dex(&["Lcom/example/b/B;"])),
+        ],
+        ZipOptions::default(),
+    );
+
+    Apk::from_bytes(data).unwrap()
+}
+
+#[test]
+fn test_dex_progress() {
+    let reports = Mutex::new(Vec::new());
+    let sink = |stage: Stage, current: u64, total: u64| {
+        reports.lock().unwrap().push((stage, current, total));
+    };
+
+    apk_with_two_dex()
+        .package_profile(Some(&sink), None)
+        .unwrap();
+
+    let mut reports = reports.into_inner().unwrap();
+    reports.sort_by_key(|&(_, current, _)| current);
+    assert_eq!(reports, [(Stage::Dex, 1, 2), (Stage::Dex, 2, 2)]);
+}
+
+#[test]
+fn test_dex_scan_cancelled() {
+    let apk = apk_with_two_dex();
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+
+    assert!(matches!(
+        apk.package_profile(None, Some(&cancel)),
+        Err(APKError::Cancelled)
+    ));
+    assert!(matches!(
+        apk.find_string_usages("example", None, Some(&cancel)),
+        Err(APKError::Cancelled)
+    ));
+}
+
+#[test]
+fn test_restricted_api_usage_skips_broken_dex() {
+    let mut database = HiddenApiDatabase::default();
+    database.add_csv("Landroid/app/Activity;->mResumed:Z,blocked\n");
+
+    assert_eq!(
+        apk_with_broken_dex()
+            .restricted_api_usage(&database)
+            .unwrap(),
+        []
+    );
+}
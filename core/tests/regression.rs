@@ -0,0 +1,666 @@
+//! Regression tests on synthetic fixtures reproducing malformed samples seen in the wild.
+//!
+//! Every test builds an apk, renders the [common::report] and compares it with the stored snapshot.
+
+mod common;
+
+use apk_info::diff::{ApkDiffer, DexSummary};
+use apk_info::indicators::{Currency, Indicators, WalletIndicator};
+use apk_info::splits::SplitApkSet;
+use apk_info::xapk::{SplitRole, Xapk};
+use apk_info::{Apk, LineageNode, Signature, ZipAnomaly, ZipEntry};
+use common::*;
+
+fn manifest(application: Vec<Node>, label: Value) -> Node {
+    el(
+        "manifest",
+        &[
+            ("package", s("com.example.regression")),
+            ("android:versionCode", Value::Int(7)),
+            ("android:versionName", s("1.0.7")),
+        ],
+        vec![
+            el(
+                "uses-sdk",
+                &[
+                    ("android:minSdkVersion", Value::Int(21)),
+                    ("android:targetSdkVersion", Value::Int(34)),
+                ],
+                vec![],
+            ),
+            el(
+                "uses-permission",
+                &[("android:name", s("android.permission.INTERNET"))],
+                vec![],
+            ),
+            el(
+                "uses-permission",
+                &[("android:name", s("android.permission.READ_SMS"))],
+                vec![],
+            ),
+            el(
+                "application",
+                &[
+                    ("android:label", label),
+                    ("android:debuggable", Value::Bool(true)),
+                ],
+                application,
+            ),
+        ],
+    )
+}
+
+fn main_activity(name: &str) -> Node {
+    el(
+        "activity",
+        &[
+            ("android:name", s(name)),
+            ("android:exported", Value::Bool(true)),
+        ],
+        vec![el(
+            "intent-filter",
+            &[],
+            vec![
+                el(
+                    "action",
+                    &[("android:name", s("android.intent.action.MAIN"))],
+                    vec![],
+                ),
+                el(
+                    "category",
+                    &[("android:name", s("android.intent.category.LAUNCHER"))],
+                    vec![],
+                ),
+            ],
+        )],
+    )
+}
+
+fn default_manifest() -> Node {
+    manifest(
+        vec![
+            main_activity("com.example.regression.MainActivity"),
+            el(
+                "service",
+                &[("android:name", s("com.example.regression.SyncService"))],
+                vec![],
+            ),
+        ],
+        s("Regression"),
+    )
+}
+
+fn strings_arsc(options: ArscOptions) -> Vec<u8> {
+    build_arsc(
+        &[ResType {
+            name: "string",
+            keys: vec!["app_name", "greeting"],
+            values: vec![Some("Resolved Label".to_string()), Some("Hi".to_string())],
+        }],
+        options,
+    )
+}
+
+fn files(manifest: Vec<u8>) -> Vec<ZipFile> {
+    vec![
+        ZipFile::deflated("AndroidManifest.xml", manifest),
+        ZipFile::stored("classes.dex", b"dex\n035\0".to_vec()),
+    ]
+}
+
+#[test]
+fn test_baseline() {
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let apk = load(
+        "baseline",
+        &build_zip(&files(manifest), ZipOptions::default()),
+    );
+
+    assert_snapshot("baseline", &report(&apk));
+}
+
+#[test]
+fn test_obfuscated_attribute_names() {
+    // attribute names are garbage, only the resource map tells what they are
+    let options = AxmlOptions {
+        obfuscate_attribute_names: true,
+        ..Default::default()
+    };
+    let manifest = build_axml(&default_manifest(), options);
+    let apk = load(
+        "obfuscated_attribute_names",
+        &build_zip(&files(manifest), ZipOptions::default()),
+    );
+
+    assert_snapshot("obfuscated_attribute_names", &report(&apk));
+}
+
+#[test]
+fn test_tampered_string_pool() {
+    // junk chunks before string pools of both manifest and resources
+    let manifest = build_axml(
+        &manifest(
+            vec![main_activity("com.example.regression.MainActivity")],
+            Value::Typed(0x01, 0x7f010000),
+        ),
+        AxmlOptions {
+            junk_before_string_pool: true,
+            ..Default::default()
+        },
+    );
+    let resources = strings_arsc(ArscOptions {
+        junk_before_string_pool: true,
+        ..Default::default()
+    });
+
+    let mut files = files(manifest);
+    files.push(ZipFile::stored("resources.arsc", resources));
+    let apk = load(
+        "tampered_string_pool",
+        &build_zip(&files, ZipOptions::default()),
+    );
+
+    assert_snapshot("tampered_string_pool", &report(&apk));
+}
+
+#[test]
+fn test_fake_compression() {
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+
+    let files = vec![
+        // unknown method, but the data is deflated
+        ZipFile {
+            method: Some(0x1337),
+            ..ZipFile::deflated("AndroidManifest.xml", manifest)
+        },
+        // unknown method, but the data is stored
+        ZipFile {
+            method: Some(0x63),
+            ..ZipFile::stored("resources.arsc", strings_arsc(ArscOptions::default()))
+        },
+        // sizes are only in the central directory
+        ZipFile {
+            zero_local_sizes: true,
+            ..ZipFile::stored("classes.dex", b"dex\n035\0".to_vec())
+        },
+    ];
+    let apk = load(
+        "fake_compression",
+        &build_zip(&files, ZipOptions::default()),
+    );
+
+    assert_snapshot("fake_compression", &report(&apk));
+}
+
+#[test]
+fn test_sparse_resources() {
+    let manifest = build_axml(
+        &manifest(
+            vec![main_activity("com.example.regression.MainActivity")],
+            Value::Typed(0x01, 0x7f010000),
+        ),
+        AxmlOptions::default(),
+    );
+    let resources = strings_arsc(ArscOptions {
+        sparse: true,
+        oversized_type: true,
+        ..Default::default()
+    });
+
+    let mut files = files(manifest);
+    files.push(ZipFile::stored("resources.arsc", resources));
+    let apk = load(
+        "sparse_resources",
+        &build_zip(&files, ZipOptions::default()),
+    );
+
+    assert_snapshot("sparse_resources", &report(&apk));
+}
+
+#[test]
+fn test_multiple_signers() {
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let block = signing_block(&[
+        (
+            ZipEntry::SIGNATURE_SCHEME_V2_BLOCK_ID,
+            signers(&[signer_v2(&[SIGNER_1]), signer_v2(&[SIGNER_2])]),
+        ),
+        (
+            ZipEntry::SIGNATURE_SCHEME_V3_BLOCK_ID,
+            signers(&[signer_v3(&[SIGNER_2, SIGNER_1])]),
+        ),
+        (ZipEntry::APK_CHANNEL_BLOCK_ID, b"regression".to_vec()),
+        // unknown blocks must not break the rest
+        (0x13371337, vec![0xaa; 32]),
+    ]);
+    let apk = load(
+        "multiple_signers",
+        &build_zip(
+            &files(manifest),
+            ZipOptions {
+                signing_block: Some(&block),
+                ..Default::default()
+            },
+        ),
+    );
+
+    assert_snapshot("multiple_signers", &report(&apk));
+}
+
+#[test]
+fn test_split_roles() {
+    let split_apk = |split: Option<&str>| {
+        let mut attrs = vec![("package", s("com.example.regression"))];
+        attrs.extend(split.map(|split| ("split", s(split))));
+        let manifest = build_axml(&el("manifest", &attrs, vec![]), AxmlOptions::default());
+
+        build_zip(&files(manifest), ZipOptions::default())
+    };
+    let apks = [
+        ("base.apk", None),
+        ("split_config.arm64_v8a.apk", Some("config.arm64_v8a")),
+        ("split_camera.apk", Some("camera")),
+    ];
+
+    // bundletool set, roles come from the manifests of the splits
+    let set = build_zip(
+        &apks
+            .iter()
+            .map(|(file, split)| ZipFile::stored(file, split_apk(*split)))
+            .collect::<Vec<_>>(),
+        ZipOptions::default(),
+    );
+    let set = SplitApkSet::from_bytes(set).unwrap();
+    let roles: Vec<_> = set
+        .infos()
+        .map(|info| (info.file.as_str(), info.role.clone()))
+        .collect();
+    assert_eq!(
+        roles,
+        [
+            ("base.apk", SplitRole::Base),
+            ("split_camera.apk", SplitRole::Feature("camera".into())),
+            (
+                "split_config.arm64_v8a.apk",
+                SplitRole::Config("config.arm64_v8a".into())
+            ),
+        ]
+    );
+    assert_eq!(set.abis(), ["arm64-v8a"]);
+
+    // xapk, roles come from manifest.json
+    let manifest = br#"{
+        "package_name": "com.example.regression",
+        "split_apks": [
+            {"file": "split_camera.apk", "id": "camera"},
+            {"file": "split_config.arm64_v8a.apk", "id": "config.arm64_v8a"},
+            {"file": "base.apk", "id": "base"}
+        ]
+    }"#;
+    let mut files: Vec<_> = apks
+        .iter()
+        .map(|(file, split)| ZipFile::stored(file, split_apk(*split)))
+        .collect();
+    files.push(ZipFile::deflated("manifest.json", manifest.to_vec()));
+    let xapk = Xapk::from_bytes(build_zip(&files, ZipOptions::default())).unwrap();
+
+    let members = xapk.members();
+    assert_eq!(members[0].file, "base.apk");
+    assert_eq!(members[0].role, SplitRole::Base);
+    assert_eq!(members[0].size, split_apk(None).len() as u64);
+    assert_eq!(members[1].role, SplitRole::Feature("camera".into()));
+    assert_eq!(
+        members[2].role,
+        SplitRole::Config("config.arm64_v8a".into())
+    );
+    assert_eq!(
+        xapk.base_apk().unwrap().get_package_name().as_deref(),
+        Some("com.example.regression")
+    );
+}
+
+#[test]
+fn test_estimated_install_size() {
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let mut files = files(manifest);
+    files.push(ZipFile::deflated("lib/arm64-v8a/libfoo.so", vec![0; 4096]));
+    files.push(ZipFile::deflated(
+        "lib/armeabi-v7a/libfoo.so",
+        vec![0; 1024],
+    ));
+
+    let data = build_zip(
+        &files,
+        ZipOptions {
+            comment: b"regression",
+            ..Default::default()
+        },
+    );
+    let size = Apk::from_bytes(data.clone())
+        .unwrap()
+        .estimated_install_size();
+
+    // without a signing block the whole archive is downloaded
+    assert_eq!(size.download_size, data.len() as u64);
+    assert_eq!(size.dex_size, 8);
+    assert_eq!(size.native_libs_size, 4096);
+    assert!(size.extract_native_libs);
+    assert_eq!(size.install_size, size.download_size + 4096);
+}
+
+#[test]
+fn test_signer_certificate() {
+    let signer_certificates = |signer: Vec<u8>| {
+        let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+        let block = signing_block(&[(ZipEntry::SIGNATURE_SCHEME_V2_BLOCK_ID, signers(&[signer]))]);
+        let data = build_zip(
+            &files(manifest),
+            ZipOptions {
+                signing_block: Some(&block),
+                ..Default::default()
+            },
+        );
+
+        let signatures = Apk::from_bytes(data).unwrap().get_signatures().unwrap();
+        let Some(Signature::V2 { certificates, .. }) = signatures
+            .into_iter()
+            .find(|signature| matches!(signature, Signature::V2 { .. }))
+        else {
+            panic!("no v2 signature");
+        };
+
+        certificates
+            .into_iter()
+            .map(|cert| (cert.sha256_fingerprint, cert.is_signer))
+            .collect::<Vec<_>>()
+    };
+    let sha256 = |der: &[u8]| signer_certificates(signer_v2(&[der]))[0].0.clone();
+
+    assert_eq!(
+        signer_certificates(signer_v2(&[SIGNER_1, SIGNER_2])),
+        [(sha256(SIGNER_1), true), (sha256(SIGNER_2), false)]
+    );
+    // the broken first certificate is the signer's one, the next one must not take its place
+    assert_eq!(
+        signer_certificates(signer_v2(&[b"not a certificate", SIGNER_2])),
+        [(sha256(SIGNER_2), false)]
+    );
+}
+
+#[test]
+fn test_key_rotation() {
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let block = signing_block(&[(
+        ZipEntry::SIGNATURE_SCHEME_V3_BLOCK_ID,
+        signers(&[signer_v3_with_lineage(
+            &[SIGNER_2],
+            &[
+                (SIGNER_1, LineageNode::CAPABILITY_INSTALLED_DATA),
+                (SIGNER_2, 0x1f),
+            ],
+        )]),
+    )]);
+    let apk = load(
+        "key_rotation",
+        &build_zip(
+            &files(manifest),
+            ZipOptions {
+                signing_block: Some(&block),
+                ..Default::default()
+            },
+        ),
+    );
+
+    assert_snapshot("key_rotation", &report(&apk));
+}
+
+#[test]
+fn test_giant_manifest() {
+    let mut application: Vec<Node> = (0..5000)
+        .map(|i| {
+            el(
+                "activity",
+                &[(
+                    "android:name",
+                    s(&format!("com.example.regression.Activity{i}")),
+                )],
+                vec![],
+            )
+        })
+        .collect();
+    application.push(main_activity("com.example.regression.MainActivity"));
+
+    let mut manifest = manifest(application, s("Giant"));
+    for i in 0..2000 {
+        manifest.children.push(el(
+            "uses-permission",
+            &[("android:name", s(&format!("com.example.permission.P{i}")))],
+            vec![],
+        ));
+    }
+
+    let manifest = build_axml(&manifest, AxmlOptions::default());
+    let apk = load(
+        "giant_manifest",
+        &build_zip(&files(manifest), ZipOptions::default()),
+    );
+
+    assert_eq!(apk.get_activities().count(), 5001);
+    assert_eq!(apk.get_permissions().count(), 2002);
+    assert_snapshot("giant_manifest", &report(&apk));
+}
+
+#[test]
+fn test_prepended_data_and_comment() {
+    // self-extracting stub before the archive and a config hidden in the comment
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let options = ZipOptions {
+        prepended: b"MZ\x90\0stub",
+        comment: b"c2=https://example.com/gate",
+        ..Default::default()
+    };
+    let apk = load(
+        "prepended_data_and_comment",
+        &build_zip(&files(manifest), options),
+    );
+
+    assert_eq!(apk.prepended_data(), options.prepended);
+    assert_eq!(apk.archive_comment(), options.comment);
+    assert_snapshot("prepended_data_and_comment", &report(&apk));
+}
+
+#[test]
+fn test_concatenated_archive() {
+    // zip payload concatenated in front of the apk, end of central directory records
+    // stored in the entries are not counted, however many there are
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let payload = build_zip(
+        &[ZipFile::stored("payload.dex", b"dex\n035\0".to_vec())],
+        ZipOptions::default(),
+    );
+
+    let mut apk_files = files(manifest);
+    apk_files
+        .extend((0..2000).map(|i| ZipFile::stored(&format!("assets/{i}.zip"), payload.repeat(4))));
+    let options = ZipOptions {
+        prepended: &payload,
+        ..Default::default()
+    };
+    let apk = load("concatenated_archive", &build_zip(&apk_files, options));
+
+    assert!(
+        apk.get_archive_anomalies()
+            .contains(&ZipAnomaly::SecondaryArchives { count: 1 })
+    );
+}
+
+#[test]
+fn test_unreferenced_files_with_broken_dex() {
+    // the dex of the fixtures is only a header, it can't be parsed
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let mut apk_files = files(manifest);
+    apk_files.push(ZipFile::stored("assets/payload.bin", vec![0; 16]));
+    let apk = load(
+        "unreferenced_files_with_broken_dex",
+        &build_zip(&apk_files, ZipOptions::default()),
+    );
+
+    assert_eq!(
+        apk.get_unreferenced_files().unwrap(),
+        ["assets/payload.bin"]
+    );
+}
+
+#[test]
+fn test_indicators() {
+    let config = b"\
+        endpoint=https://user@Example.COM:8080/path?q=1\n\
+        backup=http://192.168.1.10:80/upload\n\
+        relay 10.0.0.1 local 127.0.0.1\n\
+        version 1.2.3.4.5 build 300.1.1.1 padded 01.02.03.04\n\
+        btc 1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa broken 1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb\n\
+        trx TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t\n";
+
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let mut apk_files = files(manifest);
+    apk_files.push(ZipFile::stored("assets/config.txt", config.to_vec()));
+    let apk = load("indicators", &build_zip(&apk_files, ZipOptions::default()));
+
+    let indicators = Indicators::collect(&apk).unwrap();
+
+    assert_eq!(
+        indicators.urls,
+        [
+            "http://192.168.1.10:80/upload",
+            "https://user@Example.COM:8080/path?q=1"
+        ]
+    );
+    assert_eq!(indicators.domains, ["example.com"]);
+    assert_eq!(indicators.ips, ["10.0.0.1", "192.168.1.10"]);
+    assert!(indicators.emails.is_empty());
+    assert_eq!(
+        indicators.wallets,
+        [
+            WalletIndicator {
+                currency: Currency::Bitcoin,
+                address: "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".to_string(),
+            },
+            WalletIndicator {
+                currency: Currency::Tron,
+                address: "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_stix_ids() {
+    let indicators = Indicators {
+        sha256: "ab".repeat(32),
+        urls: vec!["https://example.com/path".to_string()],
+        ..Default::default()
+    };
+
+    // uuid5 of the canonical json of the id properties in the STIX namespace
+    let bundle = indicators.to_stix_bundle();
+    assert_eq!(bundle["id"], "bundle--94196f41-fcb3-55db-b99c-8f20bfe87261");
+    assert_eq!(
+        bundle["objects"][0]["id"],
+        "file--20432b06-75d2-54b6-80ef-6d923b5d925d"
+    );
+    assert_eq!(
+        bundle["objects"][1]["id"],
+        "url--7bb6fe7e-4255-5f0b-bb45-27a58ccd2cbc"
+    );
+
+    // the same input always produces the same bundle
+    assert_eq!(bundle, indicators.to_stix_bundle());
+}
+
+fn diff_apk(name: &str, files: Vec<ZipFile>) -> apk_info::Apk {
+    let manifest = build_axml(&default_manifest(), AxmlOptions::default());
+    let mut apk_files = vec![ZipFile::deflated("AndroidManifest.xml", manifest)];
+    apk_files.extend(files);
+    load(name, &build_zip(&apk_files, ZipOptions::default()))
+}
+
+#[test]
+fn test_diff_entries() {
+    let old = diff_apk(
+        "diff_old",
+        vec![
+            ZipFile::stored(
+                "classes.dex",
+                build_dex(&["com.example.A", "com.example.B"]),
+            ),
+            ZipFile::stored("assets/kept.txt", b"kept".to_vec()),
+            ZipFile::stored("assets/changed.txt", b"old".to_vec()),
+            ZipFile::stored("assets/removed.txt", b"removed".to_vec()),
+        ],
+    );
+    let new = diff_apk(
+        "diff_new",
+        vec![
+            ZipFile::stored(
+                "classes.dex",
+                build_dex(&["com.example.A", "com.example.C"]),
+            ),
+            ZipFile::stored("assets/kept.txt", b"kept".to_vec()),
+            ZipFile::stored("assets/changed.txt", b"new".to_vec()),
+            ZipFile::stored("assets/added.txt", b"added".to_vec()),
+        ],
+    );
+
+    let diff = ApkDiffer::compare(&old, &new).unwrap();
+
+    assert_eq!(diff.added_entries, ["assets/added.txt"]);
+    assert_eq!(diff.removed_entries, ["assets/removed.txt"]);
+    assert_eq!(diff.changed_entries, ["assets/changed.txt", "classes.dex"]);
+    assert_eq!(diff.added_classes, ["com.example.C"]);
+    assert_eq!(diff.removed_classes, ["com.example.B"]);
+    assert!(diff.package_name.is_none() && diff.certificates.is_none());
+    assert!(ApkDiffer::compare(&old, &old).unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_reuses_unchanged_dex() {
+    let old = diff_apk(
+        "diff_cache_old",
+        vec![
+            ZipFile::stored("classes.dex", build_dex(&["com.example.A"])),
+            ZipFile::stored("classes2.dex", build_dex(&["com.example.B"])),
+        ],
+    );
+    let new = diff_apk(
+        "diff_cache_new",
+        vec![
+            ZipFile::stored("classes.dex", build_dex(&["com.example.A"])),
+            ZipFile::stored("classes2.dex", build_dex(&["com.example.C"])),
+        ],
+    );
+
+    // a summary that can't come from parsing shows that the cache is used
+    let mut report = ApkDiffer::analyze(&old).unwrap();
+    report.dex.insert(
+        "classes.dex".to_string(),
+        DexSummary {
+            classes: vec!["com.example.Cached".to_string()],
+            ..Default::default()
+        },
+    );
+
+    let analysis = ApkDiffer::analyze_incremental(&report, &new).unwrap();
+
+    assert_eq!(analysis.reused_entries, ["classes.dex"]);
+    assert_eq!(
+        analysis.report.dex["classes.dex"].classes,
+        ["com.example.Cached"]
+    );
+    assert_eq!(
+        analysis.report.dex["classes2.dex"].classes,
+        ["com.example.C"]
+    );
+    assert_eq!(analysis.diff.changed_entries, ["classes2.dex"]);
+    assert_eq!(analysis.diff.added_classes, ["com.example.C"]);
+    assert_eq!(analysis.diff.removed_classes, ["com.example.B"]);
+}
@@ -111,7 +111,68 @@ fn generate_attrs_manifest() {
     fs::write(&out_path, output).unwrap();
 }
 
+/// Bits of `AttributeFormat` by the name used in `format="..."`.
+const FORMAT_BITS: [(&str, u32); 10] = [
+    ("reference", 1 << 0),
+    ("string", 1 << 1),
+    ("integer", 1 << 2),
+    ("boolean", 1 << 3),
+    ("color", 1 << 4),
+    ("float", 1 << 5),
+    ("dimension", 1 << 6),
+    ("fraction", 1 << 7),
+    ("enum", 1 << 16),
+    ("flags", 1 << 17),
+];
+
+fn generate_attrs_formats() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let out_path = out_dir.join("attrs_formats_phf.rs");
+
+    let mut formats: HashMap<String, u32> = HashMap::new();
+
+    // manifest declarations take precedence over the generic ones
+    for path in [
+        "src/assets/attrs_manifest_formats.json",
+        "src/assets/attrs_formats.json",
+    ] {
+        let json_str = fs::read_to_string(path).expect("cannot read attrs formats");
+        let json: HashMap<String, String> =
+            serde_json::from_str(&json_str).expect("invalid JSON in attrs formats");
+
+        for (attr_name, format) in json {
+            let bits = format
+                .split('|')
+                .map(|name| {
+                    FORMAT_BITS
+                        .iter()
+                        .find(|(n, _)| *n == name)
+                        .unwrap_or_else(|| panic!("unknown attribute format: {name}"))
+                        .1
+                })
+                .fold(0, |acc, bit| acc | bit);
+
+            formats.entry(attr_name).or_insert(bits);
+        }
+
+        println!("cargo:rerun-if-changed={}", path);
+    }
+
+    let mut map = Map::new();
+    for (attr_name, bits) in formats {
+        map.entry(attr_name, format!("{:#x}", bits));
+    }
+
+    let output = format!(
+        "static ATTRS_FORMATS: phf::Map<&'static str, u32> = {};\n\n",
+        map.build()
+    );
+
+    fs::write(&out_path, output).unwrap();
+}
+
 fn main() {
     generate_system_types();
     generate_attrs_manifest();
+    generate_attrs_formats();
 }
@@ -1,15 +1,40 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
-use log::warn;
-use winnow::combinator::repeat;
-use winnow::prelude::*;
+#[cfg(not(feature = "std"))]
+use spin::{Mutex, MutexGuard};
 
 use crate::errors::ARCSError;
+use crate::logging::warn;
+use crate::proto;
 use crate::structs::{
-    ResTableConfig, ResTableEntry, ResTableHeader, ResTablePackage, ResourceValueType, StringPool,
+    AttributeDefinition, ResConfigQuery, ResTableConfig, ResTableEntry, ResTableHeader,
+    ResTablePackage, ResourceValue, ResourceValueType, StringPool,
 };
 
+/// Longest chain of references resolved by [ARSC::get_resource_value_with_config].
+const MAX_REFERENCE_DEPTH: usize = 16;
+
+/// Resource of the table with its values, see [ARSC::resources].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceInfo<'a> {
+    /// Resource id: `0x7f110003`
+    pub id: u32,
+
+    /// Type and entry name: `string/app_name`
+    pub name: String,
+
+    /// Declared in `public.xml`
+    pub public: bool,
+
+    /// Raw values in every config the resource is defined for, references are not resolved
+    pub values: Vec<(&'a ResTableConfig, String)>,
+}
+
 /// Represents an Android Resource Table (ARSC) file.
 ///
 /// This struct holds the parsed global string pool and resource packages.
@@ -17,10 +42,10 @@ use crate::structs::{
 #[derive(Debug)]
 pub struct ARSC {
     global_string_pool: StringPool,
-    packages: HashMap<u8, ResTablePackage>,
+    packages: BTreeMap<u8, ResTablePackage>,
 
     /// Cache for resolved reference names to avoid repeated lookups.
-    reference_names: RefCell<HashMap<u32, String>>,
+    reference_names: Mutex<BTreeMap<u32, String>>,
 }
 
 impl ARSC {
@@ -30,6 +55,9 @@ impl ARSC {
             return Err(ARCSError::TooSmallError);
         }
 
+        // used to report offsets of the failed chunks
+        let total = input.len();
+
         let header = ResTableHeader::parse(input).map_err(|_| ARCSError::HeaderError)?;
 
         if header.package_count < 1 {
@@ -39,26 +67,31 @@ impl ARSC {
             );
         }
 
+        let offset = total - input.len();
         let global_string_pool =
-            StringPool::parse(input).map_err(|_| ARCSError::StringPoolError)?;
+            StringPool::parse(input).map_err(|_| ARCSError::StringPoolError { offset })?;
 
-        let table_packages: Vec<ResTablePackage> =
-            repeat(header.package_count as usize, ResTablePackage::parse)
-                .parse_next(input)
-                .map_err(|_| ARCSError::ResourceTableError)?;
+        // package count comes from untrusted header, so don't preallocate it
+        let mut table_packages: Vec<ResTablePackage> = Vec::new();
+        for index in 0..header.package_count as usize {
+            let offset = total - input.len();
+            let package = ResTablePackage::parse(input)
+                .map_err(|_| ARCSError::ResourceTableError { index, offset })?;
+            table_packages.push(package);
+        }
 
         // There is often a single package, so we do a little optimization (i think)
         let packages = match table_packages.len() {
-            0 => HashMap::new(),
+            0 => BTreeMap::new(),
             1 => {
                 let pkg = table_packages
                     .into_iter()
                     .next()
                     .expect("is rust broken? one element must be");
-                HashMap::from([((pkg.header.id & 0xff) as u8, pkg)])
+                BTreeMap::from([((pkg.header.id & 0xff) as u8, pkg)])
             }
             _ => {
-                let mut packages = HashMap::with_capacity(table_packages.len());
+                let mut packages = BTreeMap::new();
                 for pkg in table_packages {
                     let id = (pkg.header.id & 0xff) as u8;
                     if packages.contains_key(&id) {
@@ -78,16 +111,97 @@ impl ARSC {
         Ok(ARSC {
             global_string_pool,
             packages,
-            // preallocate some space
-            reference_names: RefCell::new(HashMap::with_capacity(32)),
+            reference_names: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Parses the protobuf resource table of the app bundle (`base/resources.pb`).
+    ///
+    /// Values of configurations that have no binary counterpart here (screen size classes,
+    /// keyboard, navigation, etc.) are skipped, compound values keep only their names.
+    pub fn from_proto(input: &[u8]) -> Result<ARSC, ARCSError> {
+        let (global_string_pool, packages) = proto::parse_resource_table(input)
+            .map_err(|e| ARCSError::ProtoError { offset: e.offset })?;
+
+        Ok(ARSC {
+            global_string_pool,
+            packages,
+            reference_names: Mutex::new(BTreeMap::new()),
         })
     }
 
+    /// Returns strings of the global string pool: values of string resources and paths
+    /// of file resources (`res/raw/config.bin`, `res/layout/main.xml`).
+    pub fn strings(&self) -> impl Iterator<Item = &str> {
+        self.global_string_pool.strings.iter().map(String::as_str)
+    }
+
+    /// Returns resource packages of the table, sorted by id.
+    pub fn packages(&self) -> impl Iterator<Item = &ResTablePackage> {
+        self.packages.values()
+    }
+
+    /// Returns all resources of the table sorted by id, similar to `aapt2 dump resources`.
+    ///
+    /// ```ignore
+    /// for resource in arsc.resources() {
+    ///     println!("0x{:08x} {}", resource.id, resource.name);
+    ///     for (config, value) in &resource.values {
+    ///         println!("  [{}] {value}", config.as_string());
+    ///     }
+    /// }
+    /// ```
+    pub fn resources(&self) -> Vec<ResourceInfo<'_>> {
+        let mut resources = Vec::new();
+
+        for (&package_id, package) in &self.packages {
+            for type_id in package.type_ids() {
+                // entry id takes 16 bits of the resource id
+                for entry_id in 0..package.entry_count(type_id).min(0x10000) {
+                    let mut name = None;
+                    let mut values = Vec::new();
+                    for (config, type_map) in &package.resources {
+                        let Some(entry) = type_map.get(&type_id).and_then(|e| e.get(entry_id))
+                        else {
+                            continue;
+                        };
+                        let Some(value) = self.format_entry(entry) else {
+                            continue;
+                        };
+
+                        if name.is_none() {
+                            name = package.get_entry_full_name(entry, type_id);
+                        }
+                        values.push((config, value));
+                    }
+
+                    if values.is_empty() {
+                        continue;
+                    }
+
+                    resources.push(ResourceInfo {
+                        id: (u32::from(package_id) << 24)
+                            | (u32::from(type_id) << 16)
+                            | entry_id as u32,
+                        name: name.unwrap_or_default(),
+                        public: package
+                            .type_specs
+                            .get(&type_id)
+                            .is_some_and(|spec| spec.is_public(entry_id as u16)),
+                        values,
+                    });
+                }
+            }
+        }
+
+        resources
+    }
+
     /// Retrieves a resource value by its numeric ID.
     ///
     /// Recursively resolves references if the value is a reference type.
+    /// Only the default config is used, see [ARSC::get_resource_value_with_config] for others.
     pub fn get_resource_value(&self, id: u32) -> Option<String> {
-        // TODO: need somehow option for dynamic config, not hardcoded
         let config = ResTableConfig::default();
 
         let (package_id, type_id, entry_id) = self.split_resource_id(id);
@@ -118,23 +232,340 @@ impl ARSC {
         }
     }
 
+    /// Retrieves a resource value for the device configuration.
+    ///
+    /// Selects the best matching config like the device would, see [ResConfigQuery].
+    /// References are resolved with the same query.
+    ///
+    /// ```ignore
+    /// let query = ResConfigQuery::from_locale("ru-RU");
+    /// let app_name = arsc.get_resource_value_with_config(0x7f110003, &query);
+    /// ```
+    pub fn get_resource_value_with_config(
+        &self,
+        id: u32,
+        query: &ResConfigQuery,
+    ) -> Option<String> {
+        self.resolve_with_config(id, query, MAX_REFERENCE_DEPTH)
+    }
+
+    fn resolve_with_config(&self, id: u32, query: &ResConfigQuery, depth: usize) -> Option<String> {
+        let (package_id, type_id, entry_id) = self.split_resource_id(id);
+
+        let (_, entry) = self
+            .packages
+            .get(&package_id)?
+            .resources
+            .iter()
+            .filter_map(|(config, type_map)| {
+                let entry = type_map.get(&type_id)?.get(entry_id as usize)?;
+                if matches!(entry, ResTableEntry::NoEntry) {
+                    return None;
+                }
+                Some((query.rank(config)?, entry))
+            })
+            // the first config wins on equal ranks
+            .rev()
+            .max_by_key(|(rank, _)| *rank)?;
+
+        match self.entry_reference(entry) {
+            // reference loops, e.g. the value references itself
+            Some(reference) if reference == id || depth == 0 => None,
+            Some(reference) => self.resolve_with_config(reference, query, depth - 1),
+            None => self.format_entry(entry),
+        }
+    }
+
+    /// Retrieves the value of the resource in every config it is defined for.
+    ///
+    /// Returns pairs of config qualifiers (for example, `ru-rRU` or `xxhdpi`, empty for the default config)
+    /// and the raw value, references are not resolved.
+    ///
+    /// ```ignore
+    /// for (config, value) in arsc.get_resource_values_all_configs(0x7f110003) {
+    ///     println!("[{config}] {value}");
+    /// }
+    /// ```
+    pub fn get_resource_values_all_configs(&self, id: u32) -> Vec<(&ResTableConfig, String)> {
+        let (package_id, type_id, entry_id) = self.split_resource_id(id);
+
+        let Some(package) = self.packages.get(&package_id) else {
+            return Vec::new();
+        };
+
+        package
+            .resources
+            .iter()
+            .filter_map(|(config, type_map)| {
+                let entry = type_map.get(&type_id)?.get(entry_id as usize)?;
+                Some((config, self.format_entry(entry)?))
+            })
+            .collect()
+    }
+
+    /// Retrieves every translation of the resource: the value in each config it is defined for.
+    ///
+    /// Returns pairs of config qualifiers (`ru-rRU`, empty for the default config) and the value.
+    /// Unlike [ARSC::get_resource_values_all_configs], references are resolved for the device
+    /// with the qualifiers of the config.
+    ///
+    /// ```ignore
+    /// for (qualifiers, value) in arsc.get_all_configs_for_resource(0x7f110003) {
+    ///     println!("[{qualifiers}] {value}");
+    /// }
+    /// ```
+    pub fn get_all_configs_for_resource(&self, id: u32) -> Vec<(String, String)> {
+        let (package_id, type_id, entry_id) = self.split_resource_id(id);
+
+        let Some(package) = self.packages.get(&package_id) else {
+            return Vec::new();
+        };
+
+        package
+            .resources
+            .iter()
+            .filter_map(|(config, type_map)| {
+                let entry = type_map.get(&type_id)?.get(entry_id as usize)?;
+                let value = match self.entry_reference(entry) {
+                    Some(reference) if reference == id => return None,
+                    Some(reference) => self.resolve_with_config(
+                        reference,
+                        &ResConfigQuery::from_config(config),
+                        MAX_REFERENCE_DEPTH,
+                    )?,
+                    None => self.format_entry(entry)?,
+                };
+
+                Some((config.as_string(), value))
+            })
+            .collect()
+    }
+
+    /// Returns the referenced resource id if the entry is a reference.
+    fn entry_reference(&self, entry: &ResTableEntry) -> Option<u32> {
+        match entry {
+            ResTableEntry::Default(e) if e.value.data_type == ResourceValueType::Reference => {
+                Some(e.value.data)
+            }
+            ResTableEntry::Compact(e)
+                if ResourceValueType::from((e.flags >> 8) as u8)
+                    == ResourceValueType::Reference =>
+            {
+                Some(e.data)
+            }
+            _ => None,
+        }
+    }
+
+    /// Formats the raw value of the entry.
+    fn format_entry(&self, entry: &ResTableEntry) -> Option<String> {
+        match entry {
+            ResTableEntry::NoEntry => None,
+            ResTableEntry::Default(e) => {
+                Some(e.value.to_string(&self.global_string_pool, Some(self)))
+            }
+            ResTableEntry::Compact(e) => {
+                // for compact entries type of the value is stored in the high byte of flags
+                let value = ResourceValue {
+                    size: 8,
+                    res: 0,
+                    data_type: ResourceValueType::from((e.flags >> 8) as u8),
+                    data: e.data,
+                };
+                Some(value.to_string(&self.global_string_pool, Some(self)))
+            }
+            ResTableEntry::Complex(e) => {
+                if let Some(definition) = e.attribute_definition() {
+                    return Some(self.format_attribute_definition(&definition));
+                }
+
+                let values: Vec<String> = e
+                    .values
+                    .iter()
+                    .map(|map| {
+                        let name = ResourceValue {
+                            size: 8,
+                            res: 0,
+                            data_type: ResourceValueType::Reference,
+                            data: map.name,
+                        };
+                        format!(
+                            "{}={}",
+                            name.to_string(&self.global_string_pool, Some(self)),
+                            map.value.to_string(&self.global_string_pool, Some(self))
+                        )
+                    })
+                    .collect();
+
+                Some(format!("{{{}}}", values.join(", ")))
+            }
+        }
+    }
+
+    /// Formats the attribute definition similar to `aapt2 dump resources`.
+    ///
+    /// For example: `(attr) enum [standard=0, singleTop=1] min=0`.
+    fn format_attribute_definition(&self, definition: &AttributeDefinition) -> String {
+        let mut out = format!("(attr) {}", definition.format);
+
+        if !definition.symbols.is_empty() {
+            let symbols: Vec<String> = definition
+                .symbols
+                .iter()
+                .map(|symbol| {
+                    let name = self.get_symbol_name(symbol.name);
+                    if definition.is_flags() {
+                        format!("{}=0x{:08x}", name, symbol.value)
+                    } else {
+                        format!("{}={}", name, symbol.value as i32)
+                    }
+                })
+                .collect();
+
+            out.push_str(&format!(" [{}]", symbols.join(", ")));
+        }
+
+        if let Some(min) = definition.min {
+            out.push_str(&format!(" min={min}"));
+        }
+
+        if let Some(max) = definition.max {
+            out.push_str(&format!(" max={max}"));
+        }
+
+        out
+    }
+
+    /// Returns the definition of the attribute resource (`attr/...`).
+    ///
+    /// Returns [None] if the resource doesn't exist or is not an attribute.
+    pub fn get_attribute_definition(&self, id: u32) -> Option<AttributeDefinition> {
+        let (package_id, type_id, entry_id) = self.split_resource_id(id);
+
+        // attributes are not configurable, so the default config is enough
+        let entry = self.packages.get(&package_id)?.find_entry(
+            &ResTableConfig::default(),
+            type_id,
+            entry_id,
+        )?;
+
+        match entry {
+            ResTableEntry::Complex(e) => e.attribute_definition(),
+            _ => None,
+        }
+    }
+
+    /// Formats the value using enum or flag symbols declared by the attribute in this table.
+    ///
+    /// Works like [crate::structs::attrs_manifest::get_attr_value], but for attributes defined
+    /// by the application itself. Returns [None] if the attribute is not an enum or flags,
+    /// or the value doesn't match any symbol.
+    ///
+    /// ```ignore
+    /// // attr/mode with `<enum name="fast" value="1"/>`
+    /// assert_eq!(arsc.format_attribute_value(0x7f040000, 1).as_deref(), Some("fast"));
+    /// ```
+    pub fn format_attribute_value(&self, id: u32, value: u32) -> Option<String> {
+        let definition = self.get_attribute_definition(id)?;
+
+        if definition.is_enum() {
+            let symbol = definition.symbols.iter().find(|s| s.value == value)?;
+            return Some(self.get_symbol_name(symbol.name));
+        }
+
+        if definition.is_flags() {
+            // exact match first, flags often have combined values (e.g. `center = 0x11`)
+            if let Some(symbol) = definition.symbols.iter().find(|s| s.value == value) {
+                return Some(self.get_symbol_name(symbol.name));
+            }
+
+            let mut rest = value;
+            let parts: Vec<String> = definition
+                .symbols
+                .iter()
+                .filter_map(|s| {
+                    if s.value != 0 && (s.value & rest) == s.value {
+                        rest &= !s.value;
+                        Some(self.get_symbol_name(s.name))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            return (!parts.is_empty()).then(|| parts.join("|"));
+        }
+
+        None
+    }
+
+    /// Returns the name of the enum/flag symbol without the `id/` prefix.
+    fn get_symbol_name(&self, id: u32) -> String {
+        match self.get_resource_name(id) {
+            Some(name) => match name.split_once('/') {
+                Some((_, short)) => short.to_string(),
+                None => name,
+            },
+            None => format!("0x{id:08x}"),
+        }
+    }
+
     /// Retrieves a resource value by its resolved name.
     pub fn get_resource_value_by_name(&self, name: &str) -> Option<String> {
-        let (&id, _) = self
-            .reference_names
-            .borrow()
-            .iter()
-            .find(|(_, v)| v == &name)?;
+        let (&id, _) = self.reference_names().iter().find(|(_, v)| v == &name)?;
 
         self.get_resource_value(id)
     }
 
+    /// Returns the ID of the resource by its name: `mipmap/ic_launcher`.
+    ///
+    /// The name may be prefixed with `@` and the package, as in the manifest: `@android:mipmap/sym_def_app_icon`.
+    pub fn get_resource_id(&self, name: &str) -> Option<u32> {
+        let name = name.strip_prefix('@').unwrap_or(name);
+        let name = name.split_once(':').map_or(name, |(_, name)| name);
+        let (type_name, _) = name.split_once('/')?;
+
+        for (&package_id, package) in &self.packages {
+            let Some(type_index) = package
+                .type_strings
+                .strings
+                .iter()
+                .position(|string| string == type_name)
+            else {
+                continue;
+            };
+            let type_id = (type_index + 1) as u8;
+
+            for type_map in package.resources.values() {
+                let Some(entries) = type_map.get(&type_id) else {
+                    continue;
+                };
+
+                let entry_id = entries.iter().position(|entry| {
+                    package
+                        .get_entry_full_name(entry, type_id)
+                        .is_some_and(|full_name| full_name == name)
+                });
+
+                if let Some(entry_id) = entry_id {
+                    return Some(
+                        (u32::from(package_id) << 24)
+                            | (u32::from(type_id) << 16)
+                            | entry_id as u32,
+                    );
+                }
+            }
+        }
+
+        None
+    }
+
     /// Returns the full resource name for a given resource ID.
     ///
     /// Uses a cache to speed up repeated lookups.
     pub fn get_resource_name(&self, id: u32) -> Option<String> {
         // fast path: if we've already have this name in cache
-        if let Some(name) = self.reference_names.borrow().get(&id) {
+        if let Some(name) = self.reference_names().get(&id) {
             return Some(name.clone());
         }
 
@@ -155,11 +586,23 @@ impl ARSC {
         let name = package.get_entry_full_name(entry, type_id)?;
 
         // save in cache
-        self.reference_names.borrow_mut().insert(id, name.clone());
+        self.reference_names().insert(id, name.clone());
 
         Some(name)
     }
 
+    #[cfg(feature = "std")]
+    fn reference_names(&self) -> MutexGuard<'_, BTreeMap<u32, String>> {
+        self.reference_names
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn reference_names(&self) -> MutexGuard<'_, BTreeMap<u32, String>> {
+        self.reference_names.lock()
+    }
+
     /// Splits a 32-bit resource ID into its package ID, type ID, and entry ID.
     #[inline(always)]
     fn split_resource_id(&self, id: u32) -> (u8, u8, u16) {
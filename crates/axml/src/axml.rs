@@ -1,13 +1,17 @@
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
 use apk_info_xml::Element;
-use log::warn;
 use winnow::error::{ContextError, ErrMode};
 use winnow::prelude::*;
 use winnow::token::take;
 
 use crate::ARSC;
+use crate::diagnostics::XmlDiagnostic;
 use crate::errors::AXMLError;
+use crate::logging::warn;
+use crate::proto;
 use crate::structs::{
     ResChunkHeader, ResourceHeaderType, StringPool, XMLHeader, XMLResourceMap, XmlCData,
     XmlEndElement, XmlNamespace, XmlParse, XmlStartElement, attrs_manifest,
@@ -25,6 +29,9 @@ pub const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android"
 #[derive(Debug)]
 pub struct AXML {
     pub root: Element,
+
+    /// Anomalies found during parsing.
+    diagnostics: Vec<XmlDiagnostic>,
 }
 
 impl AXML {
@@ -41,8 +48,13 @@ impl AXML {
             return Err(AXMLError::TooSmallError);
         }
 
+        // used to report offsets of the failed chunks
+        let total = input.len();
+
         // parse header
-        let header = ResChunkHeader::parse(input).map_err(|_| AXMLError::HeaderError)?;
+        let header = ResChunkHeader::parse(input).map_err(|_| AXMLError::HeaderError {
+            offset: total - input.len(),
+        })?;
 
         // header size must be 8 bytes, otherwise is non valid axml
         if header.header_size != 8 {
@@ -50,38 +62,76 @@ impl AXML {
         }
 
         // parse string pool
-        let string_pool = StringPool::parse(input).map_err(|_| AXMLError::StringPoolError)?;
+        let offset = total - input.len();
+        let string_pool =
+            StringPool::parse(input).map_err(|_| AXMLError::StringPoolError { offset })?;
 
         // parse resource map
-        let xml_resource = XMLResourceMap::parse(input).map_err(|_| AXMLError::ResourceMapError)?;
+        let offset = total - input.len();
+        let xml_resource =
+            XMLResourceMap::parse(input).map_err(|_| AXMLError::ResourceMapError { offset })?;
 
         // parse and get xml tree
-        let root = Self::get_xml_tree(input, arsc, &string_pool, &xml_resource)
+        let mut diagnostics = Vec::new();
+        let root = Self::get_xml_tree(
+            input,
+            total,
+            arsc,
+            &string_pool,
+            &xml_resource,
+            &mut diagnostics,
+        )?;
+
+        Ok(AXML { root, diagnostics })
+    }
+
+    /// Parses the protobuf manifest of the app bundle (`base/manifest/AndroidManifest.xml`).
+    ///
+    /// Attribute values are formatted the same way as for the binary manifest.
+    ///
+    /// ```ignore
+    /// let axml = AXML::from_proto(&manifest, Some(&arsc))?;
+    /// ```
+    pub fn from_proto(input: &[u8], arsc: Option<&ARSC>) -> Result<AXML, AXMLError> {
+        let root = proto::parse_xml(input, arsc)
+            .map_err(|e| AXMLError::ProtoError { offset: e.offset })?
             .ok_or(AXMLError::MissingRoot)?;
 
-        Ok(AXML { root })
+        Ok(AXML {
+            root,
+            diagnostics: Vec::new(),
+        })
     }
 
     fn get_xml_tree<'a>(
         input: &mut &[u8],
+        total: usize,
         arsc: Option<&ARSC>,
         string_pool: &'a StringPool,
         xml_resource: &'a XMLResourceMap,
-    ) -> Option<Element> {
+        diagnostics: &mut Vec<XmlDiagnostic>,
+    ) -> Result<Element, AXMLError> {
         let mut stack: Vec<Element> = Vec::with_capacity(16);
 
+        // the chunk on which parsing stopped, used to explain a missing root
+        let mut failed_chunk: Option<(u16, usize)> = None;
+
         loop {
+            let offset = total - input.len();
             let chunk_header = match ResChunkHeader::parse(input) {
                 Ok(v) => v,
                 Err(ErrMode::Backtrack(_)) => break,
-                Err(_) => return None,
+                // the type of the chunk is unknown if its header is broken
+                Err(_) => return Err(AXMLError::HeaderError { offset }),
             };
+            let chunk_type = u16::from(&chunk_header.type_);
 
             // Skip non-xml chunks
             if chunk_header.type_ < ResourceHeaderType::XmlStartNamespace
                 || chunk_header.type_ > ResourceHeaderType::XmlLastChunk
             {
                 warn!("not a xml resource chunk: {chunk_header:?}");
+                diagnostics.push(XmlDiagnostic::SkippedChunk { chunk_type, offset });
 
                 let _ =
                     take::<u32, &[u8], ContextError>(chunk_header.content_size()).parse_next(input);
@@ -91,6 +141,7 @@ impl AXML {
             // another malware technique
             if chunk_header.header_size != 0x10 {
                 warn!("xml resource chunk header size is not 0x10: {chunk_header:?}, skipped");
+                diagnostics.push(XmlDiagnostic::SkippedChunk { chunk_type, offset });
 
                 let _ =
                     take::<u32, &[u8], ContextError>(chunk_header.content_size()).parse_next(input);
@@ -99,7 +150,10 @@ impl AXML {
 
             let xml_header = match XMLHeader::parse(input, chunk_header) {
                 Ok(v) => v,
-                Err(_) => break,
+                Err(_) => {
+                    failed_chunk = Some((chunk_type, offset));
+                    break;
+                }
             };
 
             match xml_header.header.type_ {
@@ -112,7 +166,10 @@ impl AXML {
                 ResourceHeaderType::XmlStartElement => {
                     let node = match XmlStartElement::parse(input, xml_header) {
                         Ok(v) => v,
-                        Err(_) => break,
+                        Err(_) => {
+                            failed_chunk = Some((chunk_type, offset));
+                            break;
+                        }
                     };
 
                     let Some(name) = string_pool.get(node.name) else {
@@ -139,17 +196,41 @@ impl AXML {
                         // skip garbage strings
                         if attribute_name.contains(char::is_whitespace) {
                             warn!("skipped garbage attribute name: {:?}", attribute_name);
+                            diagnostics.push(XmlDiagnostic::GarbageAttribute {
+                                element: name.to_string(),
+                                attribute: attribute_name.to_string(),
+                            });
                             continue;
                         }
 
-                        let ns_prefix = if string_pool
-                            .get_with_resources(attribute.namespace_uri, xml_resource, false)
-                            .is_some()
-                        {
-                            Some("android")
-                        } else {
-                            None
-                        };
+                        let namespace = string_pool.get_with_resources(
+                            attribute.namespace_uri,
+                            xml_resource,
+                            false,
+                        );
+
+                        match namespace {
+                            Some(ANDROID_NAMESPACE) => {}
+                            Some(namespace) => {
+                                warn!(
+                                    "attribute {attribute_name:?} in <{name}> has unknown namespace {namespace:?}"
+                                );
+                                diagnostics.push(XmlDiagnostic::UnknownNamespace {
+                                    element: name.to_string(),
+                                    attribute: attribute_name.to_string(),
+                                    namespace: namespace.to_string(),
+                                });
+                            }
+                            None if xml_resource.get_attr(attribute.name).is_some() => {
+                                diagnostics.push(XmlDiagnostic::MissingNamespace {
+                                    element: name.to_string(),
+                                    attribute: attribute_name.to_string(),
+                                });
+                            }
+                            None => {}
+                        }
+
+                        let ns_prefix = namespace.map(|_| "android");
 
                         let value_str = attrs_manifest::get_attr_value(
                             attribute_name,
@@ -159,6 +240,38 @@ impl AXML {
                             Cow::Owned(attribute.typed_value.to_string(string_pool, arsc))
                         });
 
+                        if xml_resource.get_attr(attribute.name).is_some()
+                            && let Some(expected) = attrs_manifest::get_attr_format(attribute_name)
+                            && !expected.accepts(&attribute.typed_value.data_type)
+                        {
+                            warn!(
+                                "attribute {attribute_name:?} in <{name}> expects {expected}, got {}",
+                                attribute.typed_value.data_type
+                            );
+                            diagnostics.push(XmlDiagnostic::TypeMismatch {
+                                element: name.to_string(),
+                                attribute: attribute_name.to_string(),
+                                expected: expected.to_string(),
+                                actual: attribute.typed_value.data_type.to_string(),
+                                value: value_str.to_string(),
+                            });
+                        }
+
+                        // android takes the first occurrence, later ones are ignored
+                        if let Some(kept) = element
+                            .attributes()
+                            .find(|a| a.name() == attribute_name && a.prefix() == ns_prefix)
+                        {
+                            warn!("duplicate attribute {attribute_name:?} in <{name}>, ignored");
+                            diagnostics.push(XmlDiagnostic::DuplicateAttribute {
+                                element: name.to_string(),
+                                attribute: attribute_name.to_string(),
+                                kept: kept.value().to_string(),
+                                ignored: value_str.into_owned(),
+                            });
+                            continue;
+                        }
+
                         element.set_attribute_with_prefix(ns_prefix, attribute_name, &value_str);
                     }
 
@@ -177,11 +290,26 @@ impl AXML {
                 }
                 _ => {
                     warn!("unknown header type: {:#?}", xml_header.header.type_);
+                    diagnostics.push(XmlDiagnostic::SkippedChunk { chunk_type, offset });
                 }
             }
         }
 
-        (!stack.is_empty()).then(|| stack.remove(0))
+        if !stack.is_empty() {
+            return Ok(stack.remove(0));
+        }
+
+        match failed_chunk {
+            Some((chunk_type, offset)) => Err(AXMLError::XmlTreeError { chunk_type, offset }),
+            None => Err(AXMLError::MissingRoot),
+        }
+    }
+
+    /// Returns anomalies found during parsing (duplicate attributes, namespace tricks, type mismatches,
+    /// skipped chunks and attributes).
+    #[inline]
+    pub fn diagnostics(&self) -> &[XmlDiagnostic] {
+        &self.diagnostics
     }
 
     /// Returns the pretty-printed XML as a string.
@@ -276,44 +404,47 @@ impl AXML {
     ///
     /// See: <https://xrefandroid.com/android-16.0.0_r2/xref/frameworks/base/core/java/android/app/ApplicationPackageManager.java#310>
     pub fn get_main_activities(&self) -> impl Iterator<Item = &str> {
+        self.get_launcher_elements()
+            .filter(|activity| activity.attr("enabled") != Some("false"))
+            .filter_map(|activity| activity.attr("name"))
+    }
+
+    /// Returns `<activity>` and `<activity-alias>` elements that handle `MAIN` intent
+    /// with `LAUNCHER` or `INFO` category.
+    ///
+    /// Unlike [AXML::get_main_activities] the `enabled` state is not checked and aliases
+    /// are not resolved, so the caller can do it with the resource table at hand.
+    pub fn get_launcher_elements(&self) -> impl Iterator<Item = &Element> {
         self.root
             .childrens()
             .filter(|c| c.name() == "application")
             .flat_map(|app| app.childrens())
-            .filter_map(|activity| {
-                // check tag and enabled state
+            .filter(|activity| {
                 let tag = activity.name();
-                if (tag != "activity" && tag != "activity-alias")
-                    || activity.attr("enabled") == Some("false")
-                {
-                    return None;
+                if tag != "activity" && tag != "activity-alias" {
+                    return false;
                 }
 
-                for intent_filter in activity.childrens() {
-                    if intent_filter.name() != "intent-filter" {
-                        continue;
-                    }
-
-                    let mut has_main = false;
-                    let mut has_launcher = false;
-
-                    for child in intent_filter.childrens() {
-                        match (child.name(), child.attr("name")) {
-                            ("action", Some("android.intent.action.MAIN")) => has_main = true,
-                            ("category", Some("android.intent.category.LAUNCHER"))
-                            | ("category", Some("android.intent.category.INFO")) => {
-                                has_launcher = true
+                activity
+                    .childrens()
+                    .filter(|intent_filter| intent_filter.name() == "intent-filter")
+                    .any(|intent_filter| {
+                        let mut has_main = false;
+                        let mut has_launcher = false;
+
+                        for child in intent_filter.childrens() {
+                            match (child.name(), child.attr("name")) {
+                                ("action", Some("android.intent.action.MAIN")) => has_main = true,
+                                ("category", Some("android.intent.category.LAUNCHER"))
+                                | ("category", Some("android.intent.category.INFO")) => {
+                                    has_launcher = true
+                                }
+                                _ => {}
                             }
-                            _ => {}
                         }
-                    }
-
-                    if has_main && has_launcher {
-                        return activity.attr("name");
-                    }
-                }
 
-                None
+                        has_main && has_launcher
+                    })
             })
     }
 }
@@ -23,9 +23,9 @@ enum AttrType {
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() < 3 {
+    if args.len() < 4 {
         eprintln!(
-            "Usage: {} ./crates/axml/src/assets/attrs_manifest.xml ./crates/axml/src/assets/attrs_manifest.json",
+            "Usage: {} ./crates/axml/src/assets/attrs_manifest.xml ./crates/axml/src/assets/attrs_manifest.json ./crates/axml/src/assets/attrs_manifest_formats.json",
             args[0]
         );
         exit(1);
@@ -33,11 +33,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let path = &args[1];
     let out = &args[2];
+    let formats_out = &args[3];
     let attrs_xml = std::fs::read_to_string(path)?;
     let mut reader = Reader::from_str(&attrs_xml);
 
     let mut buf = Vec::new();
     let mut data: HashMap<String, AttrCollection> = HashMap::new();
+    // attribute name -> declared format, the first declaration wins
+    let mut formats: HashMap<String, String> = HashMap::new();
     let mut current_attr_name: Option<String> = None;
 
     loop {
@@ -45,11 +48,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Event::Start(e) => {
                 let name = e.name();
                 if name == QName(b"attr") {
-                    let mut attr_name = String::new();
-                    for a in e.attributes().flatten() {
-                        if a.key.as_ref() == b"name" {
-                            attr_name = a.unescape_value()?.to_string();
-                        }
+                    let (attr_name, format) = read_attr(&e)?;
+                    if let Some(format) = format {
+                        formats.entry(attr_name.clone()).or_insert(format);
                     }
                     current_attr_name = Some(attr_name);
                 }
@@ -57,7 +58,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             Event::Empty(e) => {
                 let name = e.name();
-                if name == QName(b"flag") || name == QName(b"enum") {
+                if name == QName(b"attr") {
+                    let (attr_name, format) = read_attr(&e)?;
+                    if let Some(format) = format {
+                        formats.entry(attr_name).or_insert(format);
+                    }
+                } else if name == QName(b"flag") || name == QName(b"enum") {
                     if let Some(attr_name) = current_attr_name.clone() {
                         let mut item_name = String::new();
                         let mut value_str = String::new();
@@ -78,14 +84,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             AttrType::Enum
                         };
 
-                        let entry = data.entry(attr_name).or_insert_with(|| AttrCollection {
-                            kind,
-                            items: HashMap::new(),
-                        });
+                        let entry =
+                            data.entry(attr_name.clone())
+                                .or_insert_with(|| AttrCollection {
+                                    kind,
+                                    items: HashMap::new(),
+                                });
 
                         if !entry.items.contains_key(&value) {
                             entry.items.insert(value, item_name);
                         }
+
+                        // symbols are declared without format, it's implied by the kind
+                        let kind = if name == QName(b"flag") {
+                            "flags"
+                        } else {
+                            "enum"
+                        };
+                        let format = formats.entry(attr_name).or_default();
+                        if !format.split('|').any(|f| f == kind) {
+                            if !format.is_empty() {
+                                format.push('|');
+                            }
+                            format.push_str(kind);
+                        }
                     }
                 }
             }
@@ -105,9 +127,31 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut out = File::create(out)?;
     out.write_all(json.as_bytes())?;
 
+    let json = serde_json::to_string(&formats)?;
+    let mut out = File::create(formats_out)?;
+    out.write_all(json.as_bytes())?;
+
     Ok(())
 }
 
+/// Returns the name and the `format` of the `<attr>` element.
+fn read_attr(
+    e: &quick_xml::events::BytesStart<'_>,
+) -> Result<(String, Option<String>), Box<dyn std::error::Error>> {
+    let mut attr_name = String::new();
+    let mut format = None;
+
+    for a in e.attributes().flatten() {
+        match a.key.as_ref() {
+            b"name" => attr_name = a.unescape_value()?.to_string(),
+            b"format" => format = Some(a.unescape_value()?.to_string()),
+            _ => {}
+        }
+    }
+
+    Ok((attr_name, format))
+}
+
 fn parse_hex_or_dec(s: &str) -> Result<u32, std::num::ParseIntError> {
     if let Some(hex) = s.strip_prefix("0x") {
         u32::from_str_radix(hex, 16)
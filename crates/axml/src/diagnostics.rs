@@ -0,0 +1,140 @@
+//! Anomalies found while parsing binary xml.
+//!
+//! Such anomalies don't break parsing, but they are often used by malware to
+//! desynchronize analysis tools from the Android OS.
+
+use alloc::string::String;
+use core::fmt;
+
+/// A single anomaly found in the binary xml.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", rename_all = "snake_case")
+)]
+pub enum XmlDiagnostic {
+    /// The same attribute is declared several times in one element.
+    ///
+    /// Android takes the first occurrence, so the first value is kept and the rest are ignored.
+    DuplicateAttribute {
+        /// Name of the element.
+        element: String,
+
+        /// Name of the duplicated attribute.
+        attribute: String,
+
+        /// The value used (first occurrence).
+        kept: String,
+
+        /// The value that was ignored.
+        ignored: String,
+    },
+
+    /// The attribute is declared with a namespace other than android.
+    ///
+    /// Android resolves system attributes by resource id, so the attribute is still treated as `android:`.
+    UnknownNamespace {
+        /// Name of the element.
+        element: String,
+
+        /// Name of the attribute.
+        attribute: String,
+
+        /// The declared namespace uri.
+        namespace: String,
+    },
+
+    /// The system attribute is declared without namespace.
+    MissingNamespace {
+        /// Name of the element.
+        element: String,
+
+        /// Name of the attribute.
+        attribute: String,
+    },
+
+    /// The value type of the system attribute doesn't match the declared format.
+    ///
+    /// For example, integer `debuggable` or string `versionCode`: aapt2 never produces them,
+    /// but lenient parsers on the device may still accept the value.
+    TypeMismatch {
+        /// Name of the element.
+        element: String,
+
+        /// Name of the attribute.
+        attribute: String,
+
+        /// Declared format of the attribute: `boolean`, `integer|string`.
+        expected: String,
+
+        /// Type of the value in the file.
+        actual: String,
+
+        /// The value itself.
+        value: String,
+    },
+
+    /// The chunk is not a xml node or its header size is tampered, it is skipped.
+    SkippedChunk {
+        /// Type of the chunk.
+        chunk_type: u16,
+
+        /// Offset of the chunk in the file.
+        offset: usize,
+    },
+
+    /// The attribute name contains whitespace, such garbage attributes are skipped.
+    GarbageAttribute {
+        /// Name of the element.
+        element: String,
+
+        /// Name of the attribute.
+        attribute: String,
+    },
+}
+
+impl fmt::Display for XmlDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlDiagnostic::DuplicateAttribute {
+                element,
+                attribute,
+                kept,
+                ignored,
+            } => write!(
+                f,
+                "duplicate attribute {attribute:?} in <{element}>: kept {kept:?}, ignored {ignored:?}"
+            ),
+            XmlDiagnostic::UnknownNamespace {
+                element,
+                attribute,
+                namespace,
+            } => write!(
+                f,
+                "attribute {attribute:?} in <{element}> has unknown namespace {namespace:?}"
+            ),
+            XmlDiagnostic::MissingNamespace { element, attribute } => write!(
+                f,
+                "system attribute {attribute:?} in <{element}> has no namespace"
+            ),
+            XmlDiagnostic::TypeMismatch {
+                element,
+                attribute,
+                expected,
+                actual,
+                value,
+            } => write!(
+                f,
+                "attribute {attribute:?} in <{element}> expects {expected}, got {actual} {value:?}"
+            ),
+            XmlDiagnostic::SkippedChunk { chunk_type, offset } => write!(
+                f,
+                "skipped chunk of type 0x{chunk_type:04x} at offset 0x{offset:x}"
+            ),
+            XmlDiagnostic::GarbageAttribute { element, attribute } => {
+                write!(f, "skipped garbage attribute {attribute:?} in <{element}>")
+            }
+        }
+    }
+}
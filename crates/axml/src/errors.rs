@@ -2,6 +2,8 @@
 //!
 //! This module contains the definitions for all error types returned by this crate.
 
+use alloc::string::String;
+
 use thiserror::Error;
 
 /// Errors that may occur while parsing an Android XML (AXML) manifest.
@@ -11,25 +13,40 @@ pub enum AXMLError {
     #[error("file size too small for manifest")]
     TooSmallError,
 
-    /// Failed to parse the header.
-    #[error("failed to parse header")]
-    HeaderError,
+    /// Failed to parse the header of the file or of a chunk.
+    #[error("failed to parse header at offset 0x{offset:x}")]
+    HeaderError {
+        /// Offset of the header from the start of the file.
+        offset: usize,
+    },
 
     /// The header size is invalid.
     #[error("invalid header size (expected 8, got {0})")]
     HeaderSizeError(u16),
 
     /// Failed to parse the resource map.
-    #[error("failed to parse resource map")]
-    ResourceMapError,
+    #[error("failed to parse resource map at offset 0x{offset:x}")]
+    ResourceMapError {
+        /// Offset of the resource map chunk from the start of the file.
+        offset: usize,
+    },
 
     /// Failed to parse the string pool.
-    #[error("failed to parse string pool")]
-    StringPoolError,
+    #[error("failed to parse string pool at offset 0x{offset:x}")]
+    StringPoolError {
+        /// Offset of the string pool chunk from the start of the file.
+        offset: usize,
+    },
 
     /// Failed to parse the XML tree.
-    #[error("failed to parse XML tree")]
-    XmlTreeError,
+    #[error("failed to parse XML tree at chunk 0x{chunk_type:x} offset 0x{offset:x}")]
+    XmlTreeError {
+        /// Type of the chunk that could not be parsed.
+        chunk_type: u16,
+
+        /// Offset of the chunk from the start of the file.
+        offset: usize,
+    },
 
     /// The XML tree does not have a root node.
     #[error("missing root node in XML tree")]
@@ -38,6 +55,13 @@ pub enum AXMLError {
     /// Failed to parse the manifest.
     #[error("failed to parse manifest")]
     ParseError,
+
+    /// The protobuf manifest of the app bundle is malformed.
+    #[error("malformed protobuf manifest at offset 0x{offset:x}")]
+    ProtoError {
+        /// Offset of the malformed field from the start of the file.
+        offset: usize,
+    },
 }
 
 /// Errors that may occur while parsing an Android resources.arsc file.
@@ -52,10 +76,31 @@ pub enum ARCSError {
     HeaderError,
 
     /// Failed to parse the string pool.
-    #[error("failed to parse string pool")]
-    StringPoolError,
+    #[error("failed to parse string pool at offset 0x{offset:x}")]
+    StringPoolError {
+        /// Offset of the string pool chunk from the start of the file.
+        offset: usize,
+    },
 
     /// Failed to parse the resource table package.
-    #[error("failed to parse resource table package")]
-    ResourceTableError,
+    #[error("failed to parse resource table package #{index} at offset 0x{offset:x}")]
+    ResourceTableError {
+        /// Index of the package in the resource table.
+        index: usize,
+
+        /// Offset of the package chunk from the start of the file.
+        offset: usize,
+    },
+
+    /// The protobuf resource table of the app bundle is malformed.
+    #[error("malformed protobuf resource table at offset 0x{offset:x}")]
+    ProtoError {
+        /// Offset of the malformed field from the start of the file.
+        offset: usize,
+    },
 }
+
+/// The attribute format name is not known, see [crate::structs::AttributeFormat].
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("unknown attribute format {0:?}")]
+pub struct AttributeFormatError(pub String);
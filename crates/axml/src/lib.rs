@@ -9,11 +9,18 @@
 //! let axml = AXML::new(input, None /* arsc */).expect("can't parse given axml file");
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 mod arsc;
 mod axml;
+pub mod diagnostics;
 pub mod errors;
+mod logging;
+mod proto;
 
 pub mod structs;
 
-pub use arsc::ARSC;
+pub use arsc::{ARSC, ResourceInfo};
 pub use axml::{ANDROID_NAMESPACE, AXML};
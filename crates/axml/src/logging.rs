@@ -0,0 +1,30 @@
+//! Wrappers of the `log` macros that are compiled out with the `quiet` feature.
+//!
+//! Anomalies that matter are also recorded in the [diagnostics](crate::diagnostics) of the
+//! parsed file, so quiet builds lose only the debug noise.
+
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        if cfg!(not(feature = "quiet")) {
+            ::log::warn!($($arg)+)
+        }
+    };
+}
+
+macro_rules! log_info {
+    ($($arg:tt)+) => {
+        if cfg!(not(feature = "quiet")) {
+            ::log::info!($($arg)+)
+        }
+    };
+}
+
+macro_rules! log_debug {
+    ($($arg:tt)+) => {
+        if cfg!(not(feature = "quiet")) {
+            ::log::debug!($($arg)+)
+        }
+    };
+}
+
+pub(crate) use {log_debug as debug, log_info as info, log_warn as warn};
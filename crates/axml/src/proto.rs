@@ -0,0 +1,908 @@
+//! Protobuf formats of Android App Bundles.
+//!
+//! Bundles keep `AndroidManifest.xml` as the `XmlNode` message and the resource table as
+//! the `ResourceTable` message (`resources.pb`) from aapt2 `Resources.proto`. Only the wire
+//! format is decoded here, the messages are converted into the same [Element] tree and
+//! resource packages as the binary formats, so everything built on top of them works as is.
+//!
+//! See: <https://android.googlesource.com/platform/frameworks/base/+/refs/heads/main/tools/aapt2/Resources.proto>
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use apk_info_xml::Element;
+
+use crate::logging::debug;
+use crate::structs::{
+    ResChunkHeader, ResTableConfig, ResTableEntry, ResTableEntryDefault, ResTableFlag,
+    ResTableMapEntry, ResTablePackage, ResTablePackageHeader, ResourceHeaderType, ResourceValue,
+    ResourceValueType, StringPool, attrs_manifest,
+};
+use crate::{ANDROID_NAMESPACE, ARSC};
+
+/// Nesting limit of xml elements, hostile files may nest them until the stack overflows.
+const MAX_DEPTH: usize = 256;
+
+/// Entry slots the resource table may allocate per byte of input, at least [MIN_ENTRY_SLOTS].
+///
+/// Entries of a configuration are stored by id, so a value with id 65535 allocates all
+/// the slots before it. Gaps are legitimate in sparse configurations, but a ~20 byte value
+/// must not allocate 65536 slots for every distinct configuration.
+const ENTRY_SLOTS_PER_BYTE: usize = 4;
+const MIN_ENTRY_SLOTS: usize = 1 << 16;
+
+/// The message is malformed, `offset` is from the start of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProtoError {
+    pub(crate) offset: usize,
+}
+
+/// Field value of the wire format.
+#[derive(Debug, Clone, Copy)]
+enum Value<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(Message<'a>),
+    Fixed32(u32),
+}
+
+impl<'a> Value<'a> {
+    /// Integer value, `int32` fields are truncated the same way as by protobuf itself.
+    fn as_u32(self) -> u32 {
+        match self {
+            Value::Varint(v) | Value::Fixed64(v) => v as u32,
+            Value::Fixed32(v) => v,
+            Value::Bytes(_) => 0,
+        }
+    }
+
+    fn as_message(self) -> Option<Message<'a>> {
+        match self {
+            Value::Bytes(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'a str {
+        self.as_message().map(|m| m.as_str()).unwrap_or_default()
+    }
+}
+
+/// Encoded message, or any other length-delimited field.
+#[derive(Debug, Clone, Copy)]
+struct Message<'a> {
+    data: &'a [u8],
+
+    /// Offset of the data from the start of the file
+    offset: usize,
+}
+
+impl<'a> Message<'a> {
+    fn fields(self) -> Fields<'a> {
+        Fields {
+            message: self,
+            pos: 0,
+        }
+    }
+
+    /// Strings are expected to be UTF-8, broken ones are treated as empty.
+    fn as_str(self) -> &'a str {
+        core::str::from_utf8(self.data).unwrap_or_default()
+    }
+
+    /// Returns the value of the first field with the given number, `0` for missing fields.
+    fn get_u32(self, number: u32) -> Result<u32, ProtoError> {
+        for field in self.fields() {
+            let (field_number, value) = field?;
+            if field_number == number {
+                return Ok(value.as_u32());
+            }
+        }
+
+        Ok(0)
+    }
+}
+
+/// Iterator over the fields of the message, stops after the first error.
+struct Fields<'a> {
+    message: Message<'a>,
+    pos: usize,
+}
+
+impl<'a> Fields<'a> {
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = *self.message.data.get(self.pos)?;
+            self.pos += 1;
+
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let data = self
+            .message
+            .data
+            .get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(data)
+    }
+
+    fn next_field(&mut self) -> Option<(u32, Value<'a>)> {
+        let key = self.varint()?;
+        let number = u32::try_from(key >> 3).ok()?;
+
+        let value = match key & 0x7 {
+            0 => Value::Varint(self.varint()?),
+            1 => Value::Fixed64(u64::from_le_bytes(self.take(8)?.try_into().ok()?)),
+            2 => {
+                let len = usize::try_from(self.varint()?).ok()?;
+                let offset = self.message.offset + self.pos;
+                Value::Bytes(Message {
+                    data: self.take(len)?,
+                    offset,
+                })
+            }
+            5 => Value::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().ok()?)),
+            // groups are deprecated and never used by aapt2
+            _ => return None,
+        };
+
+        Some((number, value))
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<(u32, Value<'a>), ProtoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.message.data.len() {
+            return None;
+        }
+
+        let start = self.message.offset + self.pos;
+        match self.next_field() {
+            Some(field) => Some(Ok(field)),
+            None => {
+                self.pos = self.message.data.len();
+                Some(Err(ProtoError { offset: start }))
+            }
+        }
+    }
+}
+
+/// Compiled value of the attribute or the resource.
+enum Item<'a> {
+    Value(ResourceValue),
+    String(&'a str),
+}
+
+fn resource_value(data_type: ResourceValueType, data: u32) -> ResourceValue {
+    ResourceValue {
+        size: 8,
+        res: 0,
+        data_type,
+        data,
+    }
+}
+
+/// Parses the `Item` message, unsupported items are [None].
+fn parse_item(message: Message<'_>) -> Result<Option<Item<'_>>, ProtoError> {
+    for field in message.fields() {
+        let (number, value) = field?;
+        let Some(message) = value.as_message() else {
+            continue;
+        };
+
+        let item = match number {
+            // ref
+            1 => parse_reference(message)?.map(Item::Value),
+            // str, raw_str, styled_str, file
+            2..=5 => Some(Item::String(
+                message
+                    .fields()
+                    .find_map(|field| match field {
+                        Ok((1, value)) => Some(value.as_str()),
+                        _ => None,
+                    })
+                    .unwrap_or_default(),
+            )),
+            // id, compiled as `false` by aapt2
+            6 => Some(Item::Value(resource_value(ResourceValueType::Boolean, 0))),
+            // prim
+            7 => parse_primitive(message)?.map(Item::Value),
+            _ => None,
+        };
+
+        return Ok(item);
+    }
+
+    Ok(None)
+}
+
+/// Parses the `Reference` message, references without ids are not compiled yet.
+fn parse_reference(message: Message<'_>) -> Result<Option<ResourceValue>, ProtoError> {
+    let (mut kind, mut id, mut dynamic) = (0, 0, false);
+
+    for field in message.fields() {
+        match field? {
+            (1, value) => kind = value.as_u32(),
+            (2, value) => id = value.as_u32(),
+            (5, Value::Bytes(boolean)) => dynamic = boolean.get_u32(1)? != 0,
+            _ => {}
+        }
+    }
+
+    if id == 0 {
+        return Ok(None);
+    }
+
+    let data_type = match (kind, dynamic) {
+        (1, false) => ResourceValueType::Attribute,
+        (1, true) => ResourceValueType::DynamicAttribute,
+        (_, false) => ResourceValueType::Reference,
+        (_, true) => ResourceValueType::DynamicReference,
+    };
+
+    Ok(Some(resource_value(data_type, id)))
+}
+
+/// Parses the `Primitive` message.
+fn parse_primitive(message: Message<'_>) -> Result<Option<ResourceValue>, ProtoError> {
+    let Some(field) = message.fields().next() else {
+        // all fields have default values, the only possible value is `0`
+        return Ok(Some(resource_value(ResourceValueType::Dec, 0)));
+    };
+    let (number, value) = field?;
+
+    let data_type = match number {
+        1 => return Ok(Some(resource_value(ResourceValueType::Null, 0))),
+        2 => return Ok(Some(resource_value(ResourceValueType::Null, 1))),
+        3 => ResourceValueType::Float,
+        13 => ResourceValueType::Dimension,
+        14 => ResourceValueType::Fraction,
+        6 => ResourceValueType::Dec,
+        7 => ResourceValueType::Hex,
+        8 => {
+            let data = if value.as_u32() != 0 { u32::MAX } else { 0 };
+            return Ok(Some(resource_value(ResourceValueType::Boolean, data)));
+        }
+        9 => ResourceValueType::ColorArgb8,
+        10 => ResourceValueType::ColorRgb8,
+        11 => ResourceValueType::ColorArgb4,
+        12 => ResourceValueType::ColorRgb4,
+        _ => return Ok(None),
+    };
+
+    Ok(Some(resource_value(data_type, value.as_u32())))
+}
+
+/// Parses the `XmlNode` message with the root element.
+pub(crate) fn parse_xml(input: &[u8], arsc: Option<&ARSC>) -> Result<Option<Element>, ProtoError> {
+    let message = Message {
+        data: input,
+        offset: 0,
+    };
+
+    for field in message.fields() {
+        if let (1, Value::Bytes(element)) = field? {
+            return parse_element(element, arsc, 0).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Parses the `XmlElement` message, text nodes are skipped like in the binary format.
+fn parse_element(
+    message: Message<'_>,
+    arsc: Option<&ARSC>,
+    depth: usize,
+) -> Result<Element, ProtoError> {
+    if depth > MAX_DEPTH {
+        return Err(ProtoError {
+            offset: message.offset,
+        });
+    }
+
+    let mut name = "";
+    let mut attributes = Vec::new();
+    let mut children = Vec::new();
+
+    for field in message.fields() {
+        match field? {
+            (3, value) => name = value.as_str(),
+            (4, Value::Bytes(attribute)) => attributes.push(attribute),
+            (5, Value::Bytes(node)) => children.push(node),
+            _ => {}
+        }
+    }
+
+    let mut element = Element::with_capacity(name, attributes.len());
+    if name == "manifest" {
+        element.set_attribute_with_prefix(Some("xlmns"), "android", ANDROID_NAMESPACE);
+    }
+
+    for attribute in attributes {
+        let (mut namespace, mut attribute_name, mut raw_value, mut item) = ("", "", "", None);
+
+        for field in attribute.fields() {
+            match field? {
+                (1, value) => namespace = value.as_str(),
+                (2, value) => attribute_name = value.as_str(),
+                (3, value) => raw_value = value.as_str(),
+                (6, Value::Bytes(compiled)) => item = parse_item(compiled)?,
+                _ => {}
+            }
+        }
+
+        if attribute_name.is_empty() {
+            continue;
+        }
+
+        let value = match item {
+            Some(Item::Value(value)) => match value.data_type {
+                ResourceValueType::Dec | ResourceValueType::Hex => {
+                    attrs_manifest::get_attr_value(attribute_name, &value.data)
+                        .map(|v| v.into_owned())
+                }
+                _ => None,
+            }
+            .unwrap_or_else(|| value.to_string(&StringPool::from_strings(Vec::new()), arsc)),
+            Some(Item::String(value)) => value.to_string(),
+            None => raw_value.to_string(),
+        };
+
+        // the binary format keeps only the android namespace as well
+        let prefix = (!namespace.is_empty()).then_some("android");
+        element.set_attribute_with_prefix(prefix, attribute_name, &value);
+    }
+
+    for node in children {
+        for field in node.fields() {
+            if let (1, Value::Bytes(child)) = field? {
+                element.append_child(parse_element(child, arsc, depth + 1)?);
+            }
+        }
+    }
+
+    Ok(element)
+}
+
+/// Parses the `ResourceTable` message into the global string pool and packages.
+pub(crate) fn parse_resource_table(
+    input: &[u8],
+) -> Result<(StringPool, BTreeMap<u8, ResTablePackage>), ProtoError> {
+    let message = Message {
+        data: input,
+        offset: 0,
+    };
+
+    let mut strings = Vec::new();
+    let mut packages = BTreeMap::new();
+    let mut slots_left = input
+        .len()
+        .saturating_mul(ENTRY_SLOTS_PER_BYTE)
+        .max(MIN_ENTRY_SLOTS);
+
+    for field in message.fields() {
+        if let (2, Value::Bytes(package)) = field? {
+            let package = parse_package(package, &mut strings, &mut slots_left)?;
+            let id = (package.header.id & 0xff) as u8;
+            packages.entry(id).or_insert(package);
+        }
+    }
+
+    Ok((StringPool::from_strings(strings), packages))
+}
+
+/// Parses the `Package` message, string values are added to the global `strings`.
+fn parse_package(
+    message: Message<'_>,
+    strings: &mut Vec<String>,
+    slots_left: &mut usize,
+) -> Result<ResTablePackage, ProtoError> {
+    let mut id = 0;
+    let mut package_name = "";
+    let mut type_names: Vec<String> = Vec::new();
+    let mut keys: BTreeMap<&str, u32> = BTreeMap::new();
+    let mut resources: BTreeMap<ResTableConfig, BTreeMap<u8, Vec<ResTableEntry>>> = BTreeMap::new();
+
+    for field in message.fields() {
+        let (number, value) = field?;
+        let Some(message) = value.as_message() else {
+            continue;
+        };
+
+        match number {
+            1 => id = message.get_u32(1)?,
+            2 => package_name = message.as_str(),
+            3 => {
+                let mut type_id = 0;
+                let mut type_name = "";
+                let mut entries = Vec::new();
+
+                for field in message.fields() {
+                    match field? {
+                        (1, Value::Bytes(type_id_message)) => {
+                            type_id = type_id_message.get_u32(1)?
+                        }
+                        (2, value) => type_name = value.as_str(),
+                        (3, Value::Bytes(entry)) => entries.push(entry),
+                        _ => {}
+                    }
+                }
+
+                let Ok(type_id @ 1..) = u8::try_from(type_id) else {
+                    debug!("resource type {type_name:?} without id, skipped");
+                    continue;
+                };
+
+                let index = usize::from(type_id - 1);
+                if type_names.len() <= index {
+                    type_names.resize(index + 1, String::new());
+                }
+                type_names[index] = type_name.to_string();
+
+                for entry in entries {
+                    parse_entry(
+                        entry,
+                        type_id,
+                        &mut keys,
+                        strings,
+                        &mut resources,
+                        slots_left,
+                    )?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut key_strings = vec![String::new(); keys.len()];
+    for (key, index) in keys {
+        key_strings[index as usize] = key.to_string();
+    }
+
+    let mut name = [0u8; 256];
+    for (chunk, unit) in name
+        .chunks_exact_mut(2)
+        .zip(package_name.encode_utf16().take(127))
+    {
+        chunk.copy_from_slice(&unit.to_le_bytes());
+    }
+
+    Ok(ResTablePackage {
+        header: ResTablePackageHeader {
+            header: ResChunkHeader {
+                type_: ResourceHeaderType::TablePackage,
+                ..ResChunkHeader::default()
+            },
+            id,
+            name,
+            type_strings: 0,
+            last_public_type: 0,
+            key_strings: 0,
+            last_public_key: 0,
+            type_id_offset: 0,
+        },
+        type_strings: StringPool::from_strings(type_names),
+        key_strings: StringPool::from_strings(key_strings),
+        resources,
+        type_specs: BTreeMap::new(),
+    })
+}
+
+/// Parses the `Entry` message and puts its values for every configuration into `resources`.
+///
+/// Slots added to the entry arrays are taken from `slots_left`, values that don't fit are skipped.
+fn parse_entry<'a>(
+    message: Message<'a>,
+    type_id: u8,
+    keys: &mut BTreeMap<&'a str, u32>,
+    strings: &mut Vec<String>,
+    resources: &mut BTreeMap<ResTableConfig, BTreeMap<u8, Vec<ResTableEntry>>>,
+    slots_left: &mut usize,
+) -> Result<(), ProtoError> {
+    let mut entry_id = None;
+    let mut name = "";
+    let mut config_values = Vec::new();
+
+    for field in message.fields() {
+        match field? {
+            (1, Value::Bytes(entry_id_message)) => entry_id = Some(entry_id_message.get_u32(1)?),
+            (2, value) => name = value.as_str(),
+            (6, Value::Bytes(config_value)) => config_values.push(config_value),
+            _ => {}
+        }
+    }
+
+    // a missing message means the default value, i.e. the first entry
+    let entry_id = entry_id.unwrap_or_default() as usize;
+    if entry_id > usize::from(u16::MAX) {
+        debug!("resource entry {name:?} with invalid id {entry_id}, skipped");
+        return Ok(());
+    }
+
+    let next_key = keys.len() as u32;
+    let key = *keys.entry(name).or_insert(next_key);
+
+    for config_value in config_values {
+        let (mut config, mut value) = (Some(ResTableConfig::default()), None);
+        for field in config_value.fields() {
+            match field? {
+                (1, Value::Bytes(config_message)) => config = parse_config(config_message)?,
+                (2, Value::Bytes(value_message)) => value = Some(value_message),
+                _ => {}
+            }
+        }
+
+        let Some(config) = config else {
+            debug!("resource {name:?} has unsupported configuration, skipped");
+            continue;
+        };
+
+        let Some(entry) = value
+            .map(|value| parse_value(value, key, strings))
+            .transpose()?
+            .flatten()
+        else {
+            continue;
+        };
+
+        let len = resources
+            .get(&config)
+            .and_then(|types| types.get(&type_id))
+            .map_or(0, Vec::len);
+        let growth = (entry_id + 1).saturating_sub(len);
+        if growth > *slots_left {
+            debug!("resource {name:?} exceeds the entry limit of the table, skipped");
+            continue;
+        }
+        *slots_left -= growth;
+
+        let entries = resources
+            .entry(config)
+            .or_default()
+            .entry(type_id)
+            .or_default();
+        if entries.len() <= entry_id {
+            entries.resize_with(entry_id + 1, || ResTableEntry::NoEntry);
+        }
+        if matches!(entries[entry_id], ResTableEntry::NoEntry) {
+            entries[entry_id] = entry;
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the `Value` message into the table entry with the `key` name.
+///
+/// Compound values (styles, arrays, plurals) keep only the parent, it's enough to name them.
+fn parse_value(
+    message: Message<'_>,
+    key: u32,
+    strings: &mut Vec<String>,
+) -> Result<Option<ResTableEntry>, ProtoError> {
+    for field in message.fields() {
+        let (number, value) = field?;
+        let Some(message) = value.as_message() else {
+            continue;
+        };
+
+        match number {
+            4 => {
+                let value = match parse_item(message)? {
+                    Some(Item::Value(value)) => value,
+                    Some(Item::String(value)) => {
+                        strings.push(value.to_string());
+                        resource_value(ResourceValueType::String, strings.len() as u32 - 1)
+                    }
+                    None => return Ok(None),
+                };
+
+                return Ok(Some(ResTableEntry::Default(ResTableEntryDefault {
+                    size: 8,
+                    flags: 0,
+                    index: key,
+                    value,
+                })));
+            }
+            5 => {
+                // style.parent
+                let mut parent = 0;
+                for field in message.fields() {
+                    if let (2, Value::Bytes(style)) = field? {
+                        for field in style.fields() {
+                            if let (1, Value::Bytes(reference)) = field? {
+                                parent = parse_reference(reference)?.map_or(0, |v| v.data);
+                            }
+                        }
+                    }
+                }
+
+                return Ok(Some(ResTableEntry::Complex(ResTableMapEntry {
+                    size: 16,
+                    flags: ResTableFlag::FLAG_COMPLEX.bits(),
+                    index: key,
+                    parent,
+                    count: 0,
+                    values: Vec::new(),
+                })));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(None)
+}
+
+/// Parses the `Configuration` message.
+///
+/// Returns [None] for qualifiers that aren't mapped (screen size classes, keyboard,
+/// navigation, etc.), values for them are skipped instead of overriding the default ones.
+fn parse_config(message: Message<'_>) -> Result<Option<ResTableConfig>, ProtoError> {
+    let mut config = ResTableConfig::default();
+
+    for field in message.fields() {
+        let (number, value) = field?;
+        let v = value.as_u32();
+
+        match number {
+            // mcc, mnc
+            1 => config.imsi |= v & 0xffff,
+            2 => config.imsi |= (v & 0xffff) << 16,
+            3 => match parse_locale(value.as_str()) {
+                Some(locale) => config.locale = locale,
+                None => return Ok(None),
+            },
+            // layout direction: ltr, rtl
+            4 => config.screen_config |= [0, 0x40, 0x80].get(v as usize).copied().unwrap_or(0),
+            // screen width, height
+            5 => config.screen_size |= v & 0xffff,
+            6 => config.screen_size |= (v & 0xffff) << 16,
+            // screen width and height in dp
+            7 => config.screen_size_dp |= v & 0xffff,
+            8 => config.screen_size_dp |= (v & 0xffff) << 16,
+            9 => config.screen_config |= (v & 0xffff) << 16,
+            // port, land, square have the same values
+            15 => config.screen_type |= v & 0xff,
+            // night, notnight
+            17 => {
+                config.screen_config |= [0, 0x20, 0x10].get(v as usize).copied().unwrap_or(0) << 8
+            }
+            18 => config.screen_type |= (v & 0xffff) << 16,
+            24 => config.version |= v & 0xffff,
+            // product isn't a part of the binary configuration
+            25 => {}
+            _ => return Ok(None),
+        }
+    }
+
+    Ok(Some(config))
+}
+
+/// Packs `en`, `en-US` and `en-rUS` locales, other forms are not supported.
+fn parse_locale(locale: &str) -> Option<u32> {
+    let mut parts = locale.split(['-', '_']);
+
+    let language = parts.next().filter(|l| l.len() == 2)?;
+    let region = match parts.next() {
+        None => "",
+        Some(region) => {
+            let region = region.strip_prefix('r').unwrap_or(region);
+            (region.len() == 2).then_some(region)?
+        }
+    };
+
+    if parts.next().is_some() || !language.is_ascii() || !region.is_ascii() {
+        return None;
+    }
+
+    let mut bytes = [0u8; 4];
+    bytes[..2].copy_from_slice(language.to_ascii_lowercase().as_bytes());
+    if !region.is_empty() {
+        bytes[2..].copy_from_slice(region.to_ascii_uppercase().as_bytes());
+    }
+
+    Some(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn uint(number: u32, value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint(u64::from(number) << 3, &mut out);
+        varint(value, &mut out);
+        out
+    }
+
+    fn bytes(number: u32, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint((u64::from(number) << 3) | 2, &mut out);
+        varint(data.len() as u64, &mut out);
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn message(fields: &[Vec<u8>]) -> Vec<u8> {
+        fields.concat()
+    }
+
+    fn attribute(name: &str, value: &str, item: Vec<u8>) -> Vec<u8> {
+        bytes(
+            4,
+            &message(&[
+                bytes(1, ANDROID_NAMESPACE.as_bytes()),
+                bytes(2, name.as_bytes()),
+                bytes(3, value.as_bytes()),
+                bytes(6, &item),
+            ]),
+        )
+    }
+
+    #[test]
+    fn test_parse_xml() {
+        let activity = message(&[
+            bytes(3, b"activity"),
+            attribute("name", ".Main", bytes(2, &bytes(1, b".Main"))),
+            attribute("enabled", "false", bytes(7, &uint(8, 0))),
+            attribute("screenOrientation", "portrait", bytes(7, &uint(6, 1))),
+            attribute("label", "@string/app_name", bytes(1, &uint(2, 0x7f01_0000))),
+        ]);
+        let manifest = message(&[
+            bytes(3, b"manifest"),
+            bytes(
+                4,
+                &message(&[bytes(2, b"package"), bytes(3, b"com.example")]),
+            ),
+            bytes(5, &bytes(2, b"text node")),
+            bytes(5, &bytes(1, &activity)),
+        ]);
+
+        let root = parse_xml(&bytes(1, &manifest), None).unwrap().unwrap();
+        assert_eq!(root.name(), "manifest");
+        assert_eq!(root.attr("package"), Some("com.example"));
+
+        let activity = root.childrens().next().unwrap();
+        assert_eq!(activity.attr("name"), Some(".Main"));
+        assert_eq!(activity.attr("enabled"), Some("false"));
+        assert_eq!(activity.attr("screenOrientation"), Some("portrait"));
+        assert_eq!(activity.attr("label"), Some("@7f010000"));
+    }
+
+    #[test]
+    fn test_parse_resource_table() {
+        let string_entry = |id: u64, name: &str, value: &str| {
+            bytes(
+                3,
+                &message(&[
+                    bytes(1, &uint(1, id)),
+                    bytes(2, name.as_bytes()),
+                    bytes(
+                        6,
+                        &bytes(2, &bytes(4, &bytes(2, &bytes(1, value.as_bytes())))),
+                    ),
+                ]),
+            )
+        };
+        let table = bytes(
+            2,
+            &message(&[
+                bytes(1, &uint(1, 0x7f)),
+                bytes(2, b"com.example"),
+                bytes(
+                    3,
+                    &message(&[
+                        bytes(1, &uint(1, 1)),
+                        bytes(2, b"string"),
+                        string_entry(0, "app_name", "Example"),
+                        string_entry(2, "hello", "Hello"),
+                    ]),
+                ),
+            ]),
+        );
+
+        let (strings, packages) = parse_resource_table(&table).unwrap();
+        assert_eq!(strings.strings, ["Example", "Hello"]);
+
+        let package = &packages[&0x7f];
+        assert_eq!(package.header.name(), "com.example");
+
+        let config = ResTableConfig::default();
+        let entry = package.find_entry(&config, 1, 2).unwrap();
+        assert_eq!(
+            package.get_entry_full_name(entry, 1).as_deref(),
+            Some("string/hello")
+        );
+        assert!(package.find_entry(&config, 1, 1).is_none());
+    }
+
+    #[test]
+    fn test_sparse_configs_are_bounded() {
+        // every value sits in its own configuration with the last possible id
+        let config_values: Vec<Vec<u8>> = (0..1000u64)
+            .map(|mcc| {
+                bytes(
+                    6,
+                    &message(&[
+                        bytes(1, &uint(1, mcc + 1)),
+                        bytes(2, &bytes(4, &bytes(7, &uint(8, 1)))),
+                    ]),
+                )
+            })
+            .collect();
+        let mut entry = vec![bytes(1, &uint(1, 0xffff)), bytes(2, b"flag")];
+        entry.extend(config_values);
+
+        let table = bytes(
+            2,
+            &message(&[
+                bytes(1, &uint(1, 0x7f)),
+                bytes(
+                    3,
+                    &message(&[
+                        bytes(1, &uint(1, 1)),
+                        bytes(2, b"bool"),
+                        bytes(3, &message(&entry)),
+                    ]),
+                ),
+            ]),
+        );
+
+        let (_, packages) = parse_resource_table(&table).unwrap();
+        let slots: usize = packages[&0x7f]
+            .resources
+            .values()
+            .flat_map(|types| types.values())
+            .map(Vec::len)
+            .sum();
+
+        assert!(slots <= MIN_ENTRY_SLOTS.max(table.len() * ENTRY_SLOTS_PER_BYTE));
+        assert_eq!(packages[&0x7f].resources.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(parse_locale("en"), Some(u32::from_le_bytes(*b"en\0\0")));
+        assert_eq!(parse_locale("en-rUS"), Some(u32::from_le_bytes(*b"enUS")));
+        assert_eq!(parse_locale("pt-BR"), Some(u32::from_le_bytes(*b"ptBR")));
+        assert_eq!(parse_locale("b+sr+Latn"), None);
+        assert_eq!(parse_locale("fil"), None);
+    }
+
+    #[test]
+    fn test_truncated_message() {
+        let mut data = bytes(1, &bytes(3, b"manifest"));
+        data.truncate(data.len() - 2);
+
+        assert_eq!(
+            parse_xml(&data, None).unwrap_err(),
+            ProtoError { offset: 0 }
+        );
+    }
+}
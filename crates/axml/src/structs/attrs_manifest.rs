@@ -1,8 +1,13 @@
 //! Wrapper around `phf` with manifest attributes.
 
-use std::borrow::Cow;
+use alloc::borrow::Cow;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::structs::AttributeFormat;
 
 include!(concat!(env!("OUT_DIR"), "/attrs_manifest_phf.rs"));
+include!(concat!(env!("OUT_DIR"), "/attrs_formats_phf.rs"));
 
 /// If AndroidManifest.xml If it contains a system attribute, then it finds the value by its name.
 ///
@@ -48,8 +53,19 @@ pub fn get_attr_value<'a>(name: &'a str, value: &'a u32) -> Option<Cow<'a, str>>
     }
 }
 
+/// Returns the declared format of the system attribute by its name (`versionCode`, `debuggable`).
+///
+/// Declarations from `attrs_manifest.xml` take precedence over `attrs.xml`.
+pub fn get_attr_format(name: &str) -> Option<AttributeFormat> {
+    ATTRS_FORMATS
+        .get(name)
+        .map(|bits| AttributeFormat::from_bits_retain(*bits))
+}
+
 #[cfg(test)]
 mod tests {
+    use alloc::borrow::ToOwned;
+
     use super::*;
 
     #[test]
@@ -86,4 +102,25 @@ mod tests {
         let value = get_attr_value("screenOrientation", &u32::MAX);
         assert_eq!(value, Some(Cow::Owned("unspecified".to_owned())))
     }
+
+    #[test]
+    fn test_attr_format() {
+        assert_eq!(
+            get_attr_format("versionCode"),
+            Some(AttributeFormat::INTEGER)
+        );
+        assert_eq!(
+            get_attr_format("debuggable"),
+            Some(AttributeFormat::BOOLEAN)
+        );
+        assert_eq!(
+            get_attr_format("minSdkVersion"),
+            Some(AttributeFormat::INTEGER | AttributeFormat::STRING)
+        );
+        assert_eq!(
+            get_attr_format("protectionLevel"),
+            Some(AttributeFormat::FLAGS)
+        );
+        assert_eq!(get_attr_format("notAnAttribute"), None);
+    }
 }
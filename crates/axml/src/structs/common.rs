@@ -1,4 +1,5 @@
-use std::fmt::Write;
+use alloc::string::String;
+use core::fmt::{self, Write};
 
 use winnow::binary::{le_u8, le_u16, le_u32};
 use winnow::prelude::*;
@@ -67,6 +68,32 @@ impl From<u16> for ResourceHeaderType {
     }
 }
 
+impl From<&ResourceHeaderType> for u16 {
+    fn from(value: &ResourceHeaderType) -> Self {
+        match value {
+            ResourceHeaderType::Null => 0x0000,
+            ResourceHeaderType::StringPool => 0x0001,
+            ResourceHeaderType::Table => 0x0002,
+            ResourceHeaderType::Xml => 0x0003,
+            ResourceHeaderType::XmlStartNamespace => 0x0100,
+            ResourceHeaderType::XmlEndNamespace => 0x0101,
+            ResourceHeaderType::XmlStartElement => 0x0102,
+            ResourceHeaderType::XmlEndElement => 0x0103,
+            ResourceHeaderType::XmlCdata => 0x0104,
+            ResourceHeaderType::XmlLastChunk => 0x017f,
+            ResourceHeaderType::XmlResourceMap => 0x0180,
+            ResourceHeaderType::TablePackage => 0x0200,
+            ResourceHeaderType::TableType => 0x0201,
+            ResourceHeaderType::TableTypeSpec => 0x0202,
+            ResourceHeaderType::TableLibrary => 0x0203,
+            ResourceHeaderType::TableOverlayable => 0x0204,
+            ResourceHeaderType::TableOverlayablePolicy => 0x0205,
+            ResourceHeaderType::TableStagedAlias => 0x0206,
+            ResourceHeaderType::Unknown(other) => *other,
+        }
+    }
+}
+
 /// Header that appears at the front of every data chunk in a resource
 ///
 /// See: <https://xrefandroid.com/android-16.0.0_r2/xref/frameworks/base/libs/androidfw/include/androidfw/ResourceTypes.h#220>
@@ -201,6 +228,31 @@ impl From<u8> for ResourceValueType {
     }
 }
 
+impl fmt::Display for ResourceValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ResourceValueType::Null => "null",
+            ResourceValueType::Reference => "reference",
+            ResourceValueType::Attribute => "attribute",
+            ResourceValueType::String => "string",
+            ResourceValueType::Float => "float",
+            ResourceValueType::Dimension => "dimension",
+            ResourceValueType::Fraction => "fraction",
+            ResourceValueType::DynamicReference => "dynamic reference",
+            ResourceValueType::DynamicAttribute => "dynamic attribute",
+            ResourceValueType::Dec | ResourceValueType::Hex => "integer",
+            ResourceValueType::Boolean => "boolean",
+            ResourceValueType::ColorArgb8
+            | ResourceValueType::ColorRgb8
+            | ResourceValueType::ColorArgb4
+            | ResourceValueType::ColorRgb4 => "color",
+            ResourceValueType::Unknown(v) => return write!(f, "unknown type 0x{v:02x}"),
+        };
+
+        f.write_str(name)
+    }
+}
+
 /// Representation of a value in a resource, supplying type information
 #[derive(Debug, PartialEq, Eq)]
 pub struct ResourceValue {
@@ -265,7 +317,7 @@ impl ResourceValue {
                 out
             }
 
-            ResourceValueType::Attribute => {
+            ResourceValueType::Attribute | ResourceValueType::DynamicAttribute => {
                 if self.is_system_type() {
                     if let Some(name) = system_types::get_type_name(&self.data) {
                         out.push_str(name);
@@ -273,6 +325,9 @@ impl ResourceValue {
                         // fallback
                         write!(&mut out, "@{:08x}", self.data).unwrap();
                     }
+                } else if let Some(name) = arsc.and_then(|arsc| arsc.get_resource_name(self.data)) {
+                    out.push('?');
+                    out.push_str(&name);
                 } else {
                     write!(&mut out, "?{:08x}", self.data).unwrap();
                 }
@@ -2,6 +2,7 @@
 
 pub mod attrs_manifest;
 mod common;
+mod res_config_query;
 mod res_string_pool;
 mod res_table_config;
 mod resource_table;
@@ -9,6 +10,7 @@ pub mod system_types;
 mod xml_elements;
 
 pub use common::*;
+pub use res_config_query::*;
 pub use res_string_pool::*;
 pub use res_table_config::*;
 pub use resource_table::*;
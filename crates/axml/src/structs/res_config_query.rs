@@ -0,0 +1,218 @@
+use alloc::string::{String, ToString};
+
+use crate::structs::{Density, ResTableConfig};
+
+/// Device configuration to select resource values for, see [crate::ARSC::get_resource_value_with_config].
+///
+/// Qualifiers that are not part of the query (orientation, night mode, screen size, etc.)
+/// are treated as absent on the device, values that require them are never selected.
+///
+/// ```ignore
+/// let query = ResConfigQuery {
+///     density: Some(Density::XXHigh),
+///     sdk: Some(34),
+///     ..ResConfigQuery::from_locale("ru-RU")
+/// };
+/// let app_name = arsc.get_resource_value_with_config(0x7f110003, &query);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResConfigQuery {
+    /// Language, lowercase: `ru`
+    pub language: Option<String>,
+
+    /// Region, uppercase: `RU`
+    pub region: Option<String>,
+
+    /// Screen density, the closest one is selected, higher densities are preferred
+    pub density: Option<Density>,
+
+    /// Api level, values for newer versions are skipped
+    pub sdk: Option<u16>,
+}
+
+impl ResConfigQuery {
+    /// Creates the query for the locale: `ru`, `ru-RU`, `ru-rRU` or `ru_RU`.
+    pub fn from_locale(locale: &str) -> ResConfigQuery {
+        let mut parts = locale.split(['-', '_']).filter(|part| !part.is_empty());
+
+        let language = parts.next().map(str::to_ascii_lowercase);
+        let region = parts.next().map(|region| {
+            // the qualifier form of the region: `rRU`
+            let region = match region.strip_prefix('r') {
+                Some(stripped) if stripped.len() == 2 => stripped,
+                _ => region,
+            };
+            region.to_ascii_uppercase()
+        });
+
+        ResConfigQuery {
+            language,
+            region,
+            ..ResConfigQuery::default()
+        }
+    }
+
+    /// Creates the query for the device with the locale, density and api level of the config.
+    pub fn from_config(config: &ResTableConfig) -> ResConfigQuery {
+        let (language, region) = config.get_language_region();
+        let (_, _, density) = config.get_orientation_touchscreen_density();
+        let (sdk, _) = config.get_sdk_minor_version();
+
+        ResConfigQuery {
+            language: (!language.is_empty()).then_some(language),
+            region: (!region.is_empty()).then_some(region),
+            density: (density != 0).then(|| Density::from(density)),
+            sdk: (sdk != 0).then_some(sdk),
+        }
+    }
+
+    /// Ranks the config for the query, higher is better, [None] if it doesn't match.
+    ///
+    /// As on the device, the locale is more important than the density,
+    /// and the density is more important than the api level.
+    pub(crate) fn rank(&self, config: &ResTableConfig) -> Option<(u8, u32, u16)> {
+        let (orientation, touchscreen, density) = config.get_orientation_touchscreen_density();
+        let (sdk, minor) = config.get_sdk_minor_version();
+        if config.imsi != 0
+            || orientation != 0
+            || touchscreen != 0
+            || config.generic_purpose_field != 0
+            || config.screen_size != 0
+            || minor != 0
+            || config.screen_config != 0
+            || config.screen_size_dp != 0
+            || config.screen_config_2 != 0
+        {
+            return None;
+        }
+
+        Some((
+            self.rank_locale(config)?,
+            self.rank_density(density),
+            self.rank_sdk(sdk)?,
+        ))
+    }
+
+    fn rank_locale(&self, config: &ResTableConfig) -> Option<u8> {
+        let (language, region) = config.get_language_region();
+        if language.is_empty() {
+            return Some(0);
+        }
+
+        if self.language.as_deref() != Some(language.as_str()) {
+            return None;
+        }
+
+        match (self.region.as_deref(), region.as_str()) {
+            (_, "") => Some(2),
+            (Some(expected), region) if expected == region => Some(3),
+            // any region of the language is better than the default value, as on android 7+
+            _ => Some(1),
+        }
+    }
+
+    fn rank_density(&self, density: u16) -> u32 {
+        let Some(target) = self.density else {
+            // without density the default value is preferred
+            return u32::from(density == 0);
+        };
+        let target = u16::from(target);
+
+        match density {
+            // anydpi is vector, it suits any density
+            0xfffe => u32::MAX,
+            // nodpi bitmaps are not scaled, the last resort
+            0xffff => 0,
+            density => {
+                // the default config is mdpi
+                let density = if density == 0 {
+                    u16::from(Density::Medium)
+                } else {
+                    density
+                };
+
+                // scaling down is better than scaling up
+                if density >= target {
+                    (2 << 16) | u32::from(u16::MAX - (density - target))
+                } else {
+                    (1 << 16) | u32::from(density)
+                }
+            }
+        }
+    }
+
+    fn rank_sdk(&self, sdk: u16) -> Option<u16> {
+        match self.sdk {
+            Some(target) if sdk > target => None,
+            _ => Some(sdk),
+        }
+    }
+}
+
+impl core::fmt::Display for ResConfigQuery {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut parts = alloc::vec::Vec::new();
+        if let Some(language) = &self.language {
+            parts.push(match &self.region {
+                Some(region) => alloc::format!("{language}-r{region}"),
+                None => language.to_string(),
+            });
+        }
+        if let Some(density) = self.density {
+            parts.push(density.to_string());
+        }
+        if let Some(sdk) = self.sdk {
+            parts.push(alloc::format!("v{sdk}"));
+        }
+
+        f.write_str(&parts.join("-"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_locale() {
+        for locale in ["ru-RU", "ru_RU", "ru-rRU", "RU-ru"] {
+            let query = ResConfigQuery::from_locale(locale);
+            assert_eq!(query.language.as_deref(), Some("ru"), "{locale}");
+            assert_eq!(query.region.as_deref(), Some("RU"), "{locale}");
+        }
+
+        let query = ResConfigQuery::from_locale("en");
+        assert_eq!(query.language.as_deref(), Some("en"));
+        assert_eq!(query.region, None);
+    }
+
+    #[test]
+    fn test_rank_density() {
+        let query = ResConfigQuery {
+            density: Some(Density::XHigh),
+            ..ResConfigQuery::default()
+        };
+
+        let xxhdpi = query.rank_density(Density::XXHigh.into());
+        let xhdpi = query.rank_density(Density::XHigh.into());
+        let hdpi = query.rank_density(Density::High.into());
+        let default = query.rank_density(0);
+
+        assert!(xhdpi > xxhdpi);
+        assert!(xxhdpi > hdpi);
+        assert!(hdpi > default);
+        assert!(query.rank_density(0xfffe) > xhdpi);
+    }
+
+    #[test]
+    fn test_rank_sdk() {
+        let query = ResConfigQuery {
+            sdk: Some(28),
+            ..ResConfigQuery::default()
+        };
+
+        assert_eq!(query.rank_sdk(0), Some(0));
+        assert_eq!(query.rank_sdk(26), Some(26));
+        assert_eq!(query.rank_sdk(31), None);
+    }
+}
@@ -1,11 +1,15 @@
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use apk_info_common::repeat_bounded;
 use bitflags::bitflags;
-use log::{info, warn};
 use winnow::binary::{le_u8, le_u16, le_u32};
-use winnow::combinator::repeat;
 use winnow::error::{ErrMode, Needed};
 use winnow::prelude::*;
 use winnow::token::take;
 
+use crate::logging::{info, warn};
 use crate::structs::{ResChunkHeader, ResourceHeaderType, XMLResourceMap};
 
 bitflags! {
@@ -96,6 +100,24 @@ pub struct StringPool {
 }
 
 impl StringPool {
+    /// Creates a pool from already decoded strings, used for formats without string pools.
+    pub(crate) fn from_strings(strings: Vec<String>) -> StringPool {
+        StringPool {
+            header: ResStringPoolHeader {
+                header: ResChunkHeader {
+                    type_: ResourceHeaderType::StringPool,
+                    ..ResChunkHeader::default()
+                },
+                string_count: strings.len() as u32,
+                style_count: 0,
+                flags: 0,
+                strings_start: 0,
+                styles_start: 0,
+            },
+            strings,
+        }
+    }
+
     pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<StringPool> {
         let mut string_header = ResStringPoolHeader::parse(input)?;
 
@@ -116,11 +138,12 @@ impl StringPool {
         }
 
         let string_offsets =
-            repeat(string_header.string_count as usize, le_u32).parse_next(input)?;
+            repeat_bounded(string_header.string_count as usize, 4, le_u32).parse_next(input)?;
 
         // style_offsets are not used, but there may be cases when this value is not equal to 0, so we need to consume input
         if string_header.style_count != 0 {
-            repeat(string_header.style_count as usize, le_u32).parse_next(input)?
+            let _: Vec<u32> =
+                repeat_bounded(string_header.style_count as usize, 4, le_u32).parse_next(input)?;
         }
 
         let strings = Self::parse_strings(input, &string_header, &string_offsets)?;
@@ -144,7 +167,7 @@ impl StringPool {
         // take just string chunk, because malware likes tampering string pool
         let (slice, rest) = input
             .split_at_checked(string_pool_size)
-            .ok_or_else(|| ErrMode::Incomplete(Needed::Unknown))?;
+            .ok_or(ErrMode::Incomplete(Needed::Unknown))?;
         *input = rest;
 
         let is_utf8 = string_header.is_utf8();
@@ -216,7 +239,7 @@ impl StringPool {
             // skip last byte
             let _ = le_u8(input)?;
 
-            let s = match std::str::from_utf8(content) {
+            let s = match core::str::from_utf8(content) {
                 Ok(s) => s.to_owned(),
                 Err(_) => String::from_utf8_lossy(content).to_string(),
             };
@@ -233,10 +256,10 @@ impl StringPool {
         // SAFETY: the axml guarantees valid utf-16?
         unsafe {
             // cast &[u8] → &[u16] directly
-            let u16_slice = std::slice::from_raw_parts(slice.as_ptr() as *const u16, len);
+            let u16_slice = core::slice::from_raw_parts(slice.as_ptr() as *const u16, len);
 
             // decode utf-16
-            std::char::decode_utf16(u16_slice.iter().map(|&x| u16::from_le(x)))
+            core::char::decode_utf16(u16_slice.iter().map(|&x| u16::from_le(x)))
                 .collect::<Result<String, _>>()
                 .unwrap_or_default()
         }
@@ -1,13 +1,16 @@
-use std::cmp::Ordering;
-use std::fmt::{Display, Write};
-use std::hash::Hash;
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+use core::fmt::{Display, Write};
+use core::hash::Hash;
 
 use bitflags::bitflags;
-use log::warn;
 use winnow::binary::{le_u32, u8};
 use winnow::prelude::*;
 use winnow::token::take;
 
+use crate::logging::warn;
+
 bitflags! {
     /// Bitmask for configuration changes and qualifiers from Android's AConfiguration.
     ///
@@ -127,7 +130,7 @@ impl From<u8> for GrammaticalGender {
 }
 
 impl Display for GrammaticalGender {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             GrammaticalGender::Any => Ok(()),
             GrammaticalGender::Neuter => write!(f, "neuter"),
@@ -164,7 +167,7 @@ impl From<u8> for LayoutDir {
 }
 
 impl Display for LayoutDir {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Ltr => write!(f, "ldltr"),
             Self::Rtl => write!(f, "ldrtl"),
@@ -208,7 +211,7 @@ impl From<u8> for ScreenSize {
 }
 
 impl Display for ScreenSize {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Small => write!(f, "small"),
             Self::Normal => write!(f, "normal"),
@@ -246,7 +249,7 @@ impl From<u8> for ScreenLong {
 }
 
 impl Display for ScreenLong {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::No => write!(f, "notlong"),
             Self::Yes => write!(f, "long"),
@@ -282,7 +285,7 @@ impl From<u8> for ScreenRound {
 }
 
 impl Display for ScreenRound {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::No => write!(f, "notround"),
             Self::Yes => write!(f, "round"),
@@ -313,7 +316,7 @@ impl From<u8> for WideColorGamut {
 }
 
 impl Display for WideColorGamut {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::No => write!(f, "nowidecg"),
             Self::Yes => write!(f, "widecg"),
@@ -344,7 +347,7 @@ impl From<u8> for Hdr {
 }
 
 impl Display for Hdr {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::No => write!(f, "lowdr"),
             Self::Yes => write!(f, "highdr"),
@@ -380,7 +383,7 @@ impl From<u8> for Orientation {
 }
 
 impl Display for Orientation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Any => Ok(()),
             Self::Port => write!(f, "port"),
@@ -426,7 +429,7 @@ impl From<u8> for UIMode {
 }
 
 impl Display for UIMode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             // original code don't handle Normal case, so as we
             Self::Any | Self::Normal => Ok(()),
@@ -466,7 +469,7 @@ impl From<u8> for UIModeNight {
 }
 
 impl Display for UIModeNight {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Any => Ok(()),
             Self::No => f.write_str("notnight"),
@@ -532,7 +535,7 @@ impl From<Density> for u16 {
 }
 
 impl Display for Density {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Default => Ok(()),
             Self::Low => write!(f, "ldpi"),
@@ -575,7 +578,7 @@ impl From<u8> for Touchscreen {
 }
 
 impl Display for Touchscreen {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Any => Ok(()),
             Self::NoTouch => write!(f, "notouch"),
@@ -612,7 +615,7 @@ impl From<u8> for KeysHidden {
 }
 
 impl Display for KeysHidden {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::No => write!(f, "keysexposed"),
             Self::Yes => write!(f, "keyshidden"),
@@ -648,7 +651,7 @@ impl From<u8> for Keyboard {
 }
 
 impl Display for Keyboard {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Any => Ok(()),
             Self::NoKeys => write!(f, "nokeys"),
@@ -683,7 +686,7 @@ impl From<u8> for NavHidden {
 }
 
 impl Display for NavHidden {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Any => Ok(()),
             Self::No => write!(f, "navexposed"),
@@ -721,7 +724,7 @@ impl From<u8> for Navigation {
 }
 
 impl Display for Navigation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Any => Ok(()),
             Self::NoNav => write!(f, "nonav"),
@@ -1086,10 +1089,18 @@ impl ResTableConfig {
         (screen_layout2, color_mode)
     }
 
+    /// Language and region of the locale: `("ru", "RU")`, empty if not set
+    pub fn get_language_region(&self) -> (String, String) {
+        let bytes = self.locale.to_le_bytes();
+        let language = self.unpack_language([bytes[0], bytes[1]]);
+        let region = self.unpack_region([bytes[2], bytes[3]]);
+        (language, region)
+    }
+
     fn unpack_language(&self, input: [u8; 2]) -> String {
         let (_, buf) = self.unpack_language_or_region(input, b'a');
 
-        std::str::from_utf8(&buf)
+        core::str::from_utf8(&buf)
             .expect("can't decode language from given configuration")
             .trim_end_matches('\0')
             .to_owned()
@@ -1098,7 +1109,7 @@ impl ResTableConfig {
     fn unpack_region(&self, input: [u8; 2]) -> String {
         let (_, buf) = self.unpack_language_or_region(input, b'0');
 
-        std::str::from_utf8(&buf)
+        core::str::from_utf8(&buf)
             .expect("can't decode region from given configuration")
             .trim_end_matches('\0')
             .to_owned()
@@ -1182,7 +1193,7 @@ impl ResTableConfig {
 
         if script_was_provided {
             result.push('+');
-            let script = std::str::from_utf8(&self.locale_script)
+            let script = core::str::from_utf8(&self.locale_script)
                 .expect("can't decode locale_script from given configuration")
                 .trim_end_matches('\0');
             result.push_str(script);
@@ -1196,7 +1207,7 @@ impl ResTableConfig {
 
         if has_variant {
             result.push('+');
-            let variant = std::str::from_utf8(&self.locale_variant)
+            let variant = core::str::from_utf8(&self.locale_variant)
                 .expect("can't decode locale_variant from given configuration")
                 .trim_end_matches('\0');
             result.push_str(variant);
@@ -1204,7 +1215,7 @@ impl ResTableConfig {
 
         if has_numbering_system {
             result.push_str("+u+nu+");
-            let numsys = std::str::from_utf8(&self.locale_numbering_system)
+            let numsys = core::str::from_utf8(&self.locale_numbering_system)
                 .expect("can't decode locale_numbering_system from given configuration")
                 .trim_end_matches('\0');
             result.push_str(numsys);
@@ -1418,7 +1429,7 @@ impl ResTableConfig {
 }
 
 impl Hash for ResTableConfig {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.imsi.hash(state);
         self.locale.hash(state);
         self.screen_type.hash(state);
@@ -1,18 +1,23 @@
-use std::collections::{BTreeMap, HashMap};
-use std::fmt;
-use std::hash::Hash;
-
-use log::{debug, info, warn};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+use core::str::FromStr;
+
+use apk_info_common::repeat_bounded;
 use winnow::binary::{le_u16, le_u32, u8};
-use winnow::combinator::repeat;
 use winnow::error::{ErrMode, Needed, StrContext, StrContextValue};
 use winnow::prelude::*;
 use winnow::stream::Stream;
 use winnow::token::take;
 
+use crate::errors::AttributeFormatError;
+use crate::logging::{debug, info, warn};
 use crate::structs::{
     ResChunkHeader, ResTableConfig, ResTableConfigFlags, ResourceHeaderType, ResourceValue,
-    StringPool,
+    ResourceValueType, StringPool,
 };
 
 /// Header for a resource table
@@ -203,6 +208,9 @@ pub struct ResTableTypeSpec {
 }
 
 impl ResTableTypeSpec {
+    /// Flag of the public entry in the configuration mask
+    const SPEC_PUBLIC: u32 = 0x4000_0000;
+
     #[inline]
     pub(crate) fn parse(
         header: ResChunkHeader,
@@ -220,9 +228,11 @@ impl ResTableTypeSpec {
         )
             .parse_next(input)?;
 
-        let type_spec_flags = repeat(
+        let type_spec_flags = repeat_bounded(
             entry_count as usize,
-            le_u32.map(ResTableConfigFlags::from_bits_truncate),
+            4,
+            // keeps `SPEC_PUBLIC` and `SPEC_STAGED_API` bits, see [ResTableTypeSpec::is_public]
+            le_u32.map(ResTableConfigFlags::from_bits_retain),
         )
         .parse_next(input)?;
 
@@ -235,6 +245,13 @@ impl ResTableTypeSpec {
             type_spec_flags,
         })
     }
+
+    /// Whether the entry is declared in `public.xml` and can be used by other packages.
+    pub fn is_public(&self, entry_id: u16) -> bool {
+        self.type_spec_flags
+            .get(entry_id as usize)
+            .is_some_and(|flags| flags.bits() & Self::SPEC_PUBLIC != 0)
+    }
 }
 
 bitflags::bitflags! {
@@ -274,6 +291,18 @@ pub struct ResTableMap {
 }
 
 impl ResTableMap {
+    /// Meta entry of an attribute resource: bit mask of allowed types, see [AttributeFormat].
+    pub const ATTR_TYPE: u32 = 0x01000000;
+
+    /// Meta entry of an attribute resource: minimum value of an integer attribute.
+    pub const ATTR_MIN: u32 = 0x01000001;
+
+    /// Meta entry of an attribute resource: maximum value of an integer attribute.
+    pub const ATTR_MAX: u32 = 0x01000002;
+
+    /// Meta entry of an attribute resource: localization of this resource can be encouraged or required.
+    pub const ATTR_L10N: u32 = 0x01000003;
+
     #[inline(always)]
     pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<ResTableMap> {
         (le_u32, ResourceValue::parse)
@@ -317,7 +346,7 @@ impl ResTableMapEntry {
         input: &mut &[u8],
     ) -> ModalResult<ResTableMapEntry> {
         let (parent, count) = (le_u32, le_u32).parse_next(input)?;
-        let values = repeat(count as usize, ResTableMap::parse).parse_next(input)?;
+        let values = repeat_bounded(count as usize, 12, ResTableMap::parse).parse_next(input)?;
 
         Ok(ResTableMapEntry {
             size,
@@ -328,6 +357,206 @@ impl ResTableMapEntry {
             values,
         })
     }
+
+    /// Decodes the attribute definition if this entry is an attribute resource.
+    ///
+    /// Returns [None] for other complex resources (styles, arrays, plurals).
+    pub fn attribute_definition(&self) -> Option<AttributeDefinition> {
+        let mut format = None;
+        let mut min = None;
+        let mut max = None;
+        let mut l10n = None;
+        let mut symbols = Vec::new();
+
+        for map in &self.values {
+            match map.name {
+                ResTableMap::ATTR_TYPE => {
+                    format = Some(AttributeFormat::from_bits_retain(map.value.data))
+                }
+                ResTableMap::ATTR_MIN => min = Some(map.value.data as i32),
+                ResTableMap::ATTR_MAX => max = Some(map.value.data as i32),
+                ResTableMap::ATTR_L10N => l10n = Some(map.value.data),
+                name => symbols.push(AttributeSymbol {
+                    name,
+                    value: map.value.data,
+                }),
+            }
+        }
+
+        Some(AttributeDefinition {
+            format: format?,
+            min,
+            max,
+            l10n,
+            symbols,
+        })
+    }
+}
+
+bitflags::bitflags! {
+    /// Types of values allowed for an attribute resource.
+    ///
+    /// See: <https://xrefandroid.com/android-16.0.0_r2/xref/frameworks/base/libs/androidfw/include/androidfw/ResourceTypes.h#1730>
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct AttributeFormat: u32 {
+        /// No type has been defined for this attribute, use generic type handling.
+        const ANY = 0x0000ffff;
+
+        /// Attribute holds a reference to another resource.
+        const REFERENCE = 1 << 0;
+
+        /// Attribute holds a generic string.
+        const STRING = 1 << 1;
+
+        /// Attribute holds an integer value, [ResTableMap::ATTR_MIN] and [ResTableMap::ATTR_MAX]
+        /// can optionally specify a constrained range of possible integer values.
+        const INTEGER = 1 << 2;
+
+        /// Attribute holds a boolean integer.
+        const BOOLEAN = 1 << 3;
+
+        /// Attribute holds a color value.
+        const COLOR = 1 << 4;
+
+        /// Attribute holds a floating point value.
+        const FLOAT = 1 << 5;
+
+        /// Attribute holds a dimension value, such as "20px".
+        const DIMENSION = 1 << 6;
+
+        /// Attribute holds a fraction value, such as "20%".
+        const FRACTION = 1 << 7;
+
+        /// Attribute holds an enumeration, the symbols are the other entries of the map.
+        const ENUM = 1 << 16;
+
+        /// Attribute holds a bitmask of flags, the symbols are the other entries of the map.
+        const FLAGS = 1 << 17;
+    }
+}
+
+impl AttributeFormat {
+    const NAMES: [(AttributeFormat, &str); 10] = [
+        (AttributeFormat::REFERENCE, "reference"),
+        (AttributeFormat::STRING, "string"),
+        (AttributeFormat::INTEGER, "integer"),
+        (AttributeFormat::BOOLEAN, "boolean"),
+        (AttributeFormat::COLOR, "color"),
+        (AttributeFormat::FLOAT, "float"),
+        (AttributeFormat::DIMENSION, "dimension"),
+        (AttributeFormat::FRACTION, "fraction"),
+        (AttributeFormat::ENUM, "enum"),
+        (AttributeFormat::FLAGS, "flags"),
+    ];
+
+    /// Checks whether a value of the given type can be assigned to the attribute.
+    ///
+    /// References are always accepted, they are resolved at runtime.
+    pub fn accepts(&self, value_type: &ResourceValueType) -> bool {
+        let required = match value_type {
+            ResourceValueType::Null
+            | ResourceValueType::Reference
+            | ResourceValueType::Attribute
+            | ResourceValueType::DynamicReference
+            | ResourceValueType::DynamicAttribute => return true,
+            ResourceValueType::String => AttributeFormat::STRING,
+            ResourceValueType::Float => AttributeFormat::FLOAT,
+            ResourceValueType::Dimension => AttributeFormat::DIMENSION,
+            ResourceValueType::Fraction => AttributeFormat::FRACTION,
+            ResourceValueType::Dec | ResourceValueType::Hex => {
+                AttributeFormat::INTEGER | AttributeFormat::ENUM | AttributeFormat::FLAGS
+            }
+            ResourceValueType::Boolean => AttributeFormat::BOOLEAN,
+            ResourceValueType::ColorArgb8
+            | ResourceValueType::ColorRgb8
+            | ResourceValueType::ColorArgb4
+            | ResourceValueType::ColorRgb4 => AttributeFormat::COLOR,
+            ResourceValueType::Unknown(_) => return false,
+        };
+
+        self.intersects(required)
+    }
+}
+
+impl FromStr for AttributeFormat {
+    type Err = AttributeFormatError;
+
+    /// Parses the `format` of the `<attr>` declaration: `any` or names joined with `|`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "any" {
+            return Ok(AttributeFormat::ANY);
+        }
+
+        s.split('|')
+            .map(|name| {
+                Self::NAMES
+                    .iter()
+                    .find(|(_, n)| *n == name.trim())
+                    .map(|(flag, _)| *flag)
+                    .ok_or_else(|| AttributeFormatError(name.to_string()))
+            })
+            .try_fold(AttributeFormat::empty(), |acc, flag| Ok(acc | flag?))
+    }
+}
+
+impl fmt::Display for AttributeFormat {
+    /// Formats the mask the same way as aapt2: `any` or names joined with `|`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.contains(AttributeFormat::ANY) {
+            return f.write_str("any");
+        }
+
+        let names: Vec<&str> = Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect();
+
+        f.write_str(&names.join("|"))
+    }
+}
+
+/// Enum or flag value declared by an attribute resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSymbol {
+    /// Resource identifier of the symbol name, usually an `id` resource (`id/singleTop`).
+    pub name: u32,
+
+    /// Value of the symbol.
+    pub value: u32,
+}
+
+/// Definition of an attribute resource (`<attr>` in `values/attrs.xml`) decoded from the meta entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeDefinition {
+    /// Types of values allowed for this attribute.
+    pub format: AttributeFormat,
+
+    /// Minimum value of an integer attribute.
+    pub min: Option<i32>,
+
+    /// Maximum value of an integer attribute.
+    pub max: Option<i32>,
+
+    /// Localization mode of the attribute.
+    pub l10n: Option<u32>,
+
+    /// Enum or flag symbols in the declaration order.
+    pub symbols: Vec<AttributeSymbol>,
+}
+
+impl AttributeDefinition {
+    /// Returns `true` if the attribute is an enumeration.
+    #[inline]
+    pub fn is_enum(&self) -> bool {
+        self.format.contains(AttributeFormat::ENUM)
+    }
+
+    /// Returns `true` if the attribute is a bitmask of flags.
+    #[inline]
+    pub fn is_flags(&self) -> bool {
+        self.format.contains(AttributeFormat::FLAGS)
+    }
 }
 
 /// A compact entry is indicated by [ResTableFlag::FLAG_COMPACT] with falgs at the same offset as normal entry.
@@ -517,8 +746,9 @@ impl ResTableType {
         // handle sparse flag based on jadx code
         // https://github.com/skylot/jadx/blob/master/jadx-core/src/main/java/jadx/core/xmlgen/ResTableBinaryParser.java#L276
         let entry_offsets: Vec<u32> = if Self::is_sparse(flags) {
-            repeat(
+            repeat_bounded(
                 entry_count as usize,
+                4,
                 (le_u16, le_u16).map(|(_, x)| {
                     if x == u16::MAX {
                         u32::MAX
@@ -529,8 +759,9 @@ impl ResTableType {
             )
             .parse_next(input)?
         } else if Self::is_offset16(flags) {
-            repeat(
+            repeat_bounded(
                 entry_count as usize,
+                2,
                 le_u16.map(|x| {
                     if x == u16::MAX {
                         u32::MAX
@@ -541,7 +772,7 @@ impl ResTableType {
             )
             .parse_next(input)?
         } else {
-            repeat(entry_count as usize, le_u32).parse_next(input)?
+            repeat_bounded(entry_count as usize, 4, le_u32).parse_next(input)?
         };
 
         // whatsapp is doing some kind of crap with offsets, so we need to make a slice on this particular piece of data
@@ -557,7 +788,7 @@ impl ResTableType {
         let entries_size = header.size.saturating_sub(entries_start) as usize;
         let (entries_slice, rest) = input
             .split_at_checked(entries_size)
-            .ok_or_else(|| ErrMode::Incomplete(Needed::Unknown))?;
+            .ok_or(ErrMode::Incomplete(Needed::Unknown))?;
 
         *input = rest;
 
@@ -672,7 +903,8 @@ impl ResTableLibrary {
     #[inline(always)]
     pub(crate) fn parse(header: ResChunkHeader, input: &mut &[u8]) -> ModalResult<ResTableLibrary> {
         let count = le_u32.parse_next(input)?;
-        let entries = repeat(count as usize, ResTableLibraryEntry::parse).parse_next(input)?;
+        let entries =
+            repeat_bounded(count as usize, 260, ResTableLibraryEntry::parse).parse_next(input)?;
 
         Ok(ResTableLibrary {
             header,
@@ -798,7 +1030,7 @@ impl ResTableOverlayblePolicy {
     ) -> ModalResult<ResTableOverlayblePolicy> {
         let (policy_flags, entry_count) =
             (le_u32.map(PolicyFlags::from_bits_truncate), le_u32).parse_next(input)?;
-        let entries = repeat(entry_count as usize, le_u32).parse_next(input)?;
+        let entries = repeat_bounded(entry_count as usize, 4, le_u32).parse_next(input)?;
 
         Ok(ResTableOverlayblePolicy {
             header,
@@ -855,7 +1087,8 @@ impl ResTableStagedAlias {
         input: &mut &[u8],
     ) -> ModalResult<ResTableStagedAlias> {
         let count = le_u32.parse_next(input)?;
-        let entries = repeat(count as usize, ResTableStagedAliasEntry::parse).parse_next(input)?;
+        let entries =
+            repeat_bounded(count as usize, 8, ResTableStagedAliasEntry::parse).parse_next(input)?;
 
         Ok(ResTableStagedAlias {
             header,
@@ -873,7 +1106,10 @@ pub struct ResTablePackage {
 
     // requires fastloop by resource id => resource
     // for example: 0x7f010000 => anim/abc_fade_in or res/anim/abc_fade_in.xml type=XML
-    pub resources: BTreeMap<ResTableConfig, HashMap<u8, Vec<ResTableEntry>>>,
+    pub resources: BTreeMap<ResTableConfig, BTreeMap<u8, Vec<ResTableEntry>>>,
+
+    /// Type specs by type id, empty for the protobuf table
+    pub type_specs: BTreeMap<u8, ResTableTypeSpec>,
 }
 
 impl ResTablePackage {
@@ -885,8 +1121,9 @@ impl ResTablePackage {
         )
             .parse_next(input)?;
 
-        let mut resources: BTreeMap<ResTableConfig, HashMap<u8, Vec<ResTableEntry>>> =
+        let mut resources: BTreeMap<ResTableConfig, BTreeMap<u8, Vec<ResTableEntry>>> =
             BTreeMap::new();
+        let mut type_specs = BTreeMap::new();
 
         loop {
             // save position before parsing header
@@ -906,8 +1143,10 @@ impl ResTablePackage {
 
             match header.type_ {
                 ResourceHeaderType::TableTypeSpec => {
-                    // idk what should i do with this value
-                    let _ = ResTableTypeSpec::parse(header, input)?;
+                    let type_spec = ResTableTypeSpec::parse(header, input)?;
+
+                    // the first one wins, same as for types
+                    type_specs.entry(type_spec.id).or_insert(type_spec);
                 }
                 ResourceHeaderType::TableType => {
                     let type_type = ResTableType::parse(header, input)?;
@@ -940,9 +1179,43 @@ impl ResTablePackage {
             type_strings,
             key_strings,
             resources,
+            type_specs,
         })
     }
 
+    /// Returns the name of the type: `string`, `drawable`.
+    #[inline]
+    pub fn type_name(&self, type_id: u8) -> Option<&str> {
+        self.type_strings
+            .get(type_id.saturating_sub(1) as u32)
+            .map(String::as_str)
+    }
+
+    /// Returns ids of the types that have entries in any configuration, sorted.
+    pub fn type_ids(&self) -> Vec<u8> {
+        let type_ids: BTreeSet<u8> = self
+            .resources
+            .values()
+            .flat_map(|type_map| type_map.keys().copied())
+            .chain(self.type_specs.keys().copied())
+            .collect();
+
+        type_ids.into_iter().collect()
+    }
+
+    /// Returns the number of entries of the type, the longest of all configurations.
+    pub fn entry_count(&self, type_id: u8) -> usize {
+        let spec_count = self
+            .type_specs
+            .get(&type_id)
+            .map_or(0, |spec| spec.entry_count as usize);
+
+        self.resources
+            .values()
+            .filter_map(|type_map| type_map.get(&type_id).map(Vec::len))
+            .fold(spec_count, usize::max)
+    }
+
     /// Searches for the specified resource in the current package
     pub fn find_entry(
         &self,
@@ -998,3 +1271,118 @@ impl ResTablePackage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn map(name: u32, data: u32) -> ResTableMap {
+        ResTableMap {
+            name,
+            value: ResourceValue {
+                size: 8,
+                res: 0,
+                data_type: ResourceValueType::Dec,
+                data,
+            },
+        }
+    }
+
+    fn map_entry(values: Vec<ResTableMap>) -> ResTableMapEntry {
+        ResTableMapEntry {
+            size: 16,
+            flags: ResTableFlag::FLAG_COMPLEX.bits(),
+            index: 0,
+            parent: 0,
+            count: values.len() as u32,
+            values,
+        }
+    }
+
+    #[test]
+    fn test_attribute_definition_enum() {
+        let entry = map_entry(vec![
+            map(ResTableMap::ATTR_TYPE, 0x00010004),
+            map(ResTableMap::ATTR_MIN, 0xffffffff),
+            map(0x7f050000, 0),
+            map(0x7f050001, 1),
+        ]);
+
+        let definition = entry.attribute_definition().unwrap();
+        assert!(definition.is_enum());
+        assert!(!definition.is_flags());
+        assert_eq!(definition.format.to_string(), "integer|enum");
+        assert_eq!(definition.min, Some(-1));
+        assert_eq!(definition.max, None);
+        assert_eq!(
+            definition.symbols,
+            vec![
+                AttributeSymbol {
+                    name: 0x7f050000,
+                    value: 0
+                },
+                AttributeSymbol {
+                    name: 0x7f050001,
+                    value: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_type_spec_public() {
+        // id, res0, types_count, entry_count, then the flags of two entries
+        let mut data = vec![0x02, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00];
+        data.extend_from_slice(&0x4000_0004u32.to_le_bytes());
+        data.extend_from_slice(&0x0000_0004u32.to_le_bytes());
+
+        let spec =
+            ResTableTypeSpec::parse(ResChunkHeader::default(), &mut data.as_slice()).unwrap();
+        assert_eq!(spec.id, 2);
+        assert!(spec.is_public(0));
+        assert!(!spec.is_public(1));
+        assert!(!spec.is_public(2));
+        assert!(spec.type_spec_flags[0].contains(ResTableConfigFlags::CONFIG_LOCALE));
+    }
+
+    #[test]
+    fn test_attribute_definition_not_attr() {
+        // style entry, names are attributes
+        let entry = map_entry(vec![map(0x01010000, 1), map(0x7f040000, 2)]);
+        assert!(entry.attribute_definition().is_none());
+    }
+
+    #[test]
+    fn test_attribute_format_any() {
+        assert_eq!(AttributeFormat::ANY.to_string(), "any");
+        assert_eq!(
+            (AttributeFormat::REFERENCE | AttributeFormat::COLOR).to_string(),
+            "reference|color"
+        );
+    }
+
+    #[test]
+    fn test_attribute_format_parse() {
+        assert_eq!("any".parse(), Ok(AttributeFormat::ANY));
+        assert_eq!(
+            "integer|string".parse(),
+            Ok(AttributeFormat::INTEGER | AttributeFormat::STRING)
+        );
+        assert_eq!(
+            "integer|str".parse::<AttributeFormat>(),
+            Err(AttributeFormatError("str".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_attribute_format_accepts() {
+        assert!(AttributeFormat::BOOLEAN.accepts(&ResourceValueType::Boolean));
+        assert!(AttributeFormat::BOOLEAN.accepts(&ResourceValueType::Reference));
+        assert!(!AttributeFormat::BOOLEAN.accepts(&ResourceValueType::Dec));
+        assert!(!AttributeFormat::INTEGER.accepts(&ResourceValueType::String));
+        assert!(AttributeFormat::FLAGS.accepts(&ResourceValueType::Hex));
+        assert!(AttributeFormat::ANY.accepts(&ResourceValueType::Float));
+    }
+}
@@ -1,9 +1,11 @@
-use log::debug;
+use alloc::vec::Vec;
+
+use apk_info_common::repeat_bounded;
 use winnow::binary::{le_u16, le_u32};
-use winnow::combinator::repeat;
 use winnow::prelude::*;
 use winnow::token::take;
 
+use crate::logging::debug;
 use crate::structs::{ResChunkHeader, ResourceValue, system_types};
 
 #[derive(Debug)]
@@ -16,8 +18,9 @@ pub struct XMLResourceMap {
 impl XMLResourceMap {
     pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<XMLResourceMap> {
         let header = ResChunkHeader::parse(input)?;
-        let resource_ids = repeat(
+        let resource_ids = repeat_bounded(
             (header.size.saturating_sub(header.header_size as u32) / 4) as usize,
+            4,
             le_u32,
         )
         .parse_next(input)?;
@@ -226,8 +229,9 @@ impl XmlParse for XmlStartElement {
                     .parse_next(input)?;
         }
 
-        let attributes = repeat(
+        let attributes = repeat_bounded(
             attribute_count as usize,
+            attribute_size.max(XmlAttributeElement::DEFAULT_ATTRIBUTE_SIZE) as usize,
             XmlAttributeElement::parse(attribute_size),
         )
         .parse_next(input)?;
@@ -0,0 +1,116 @@
+//! Parsing helpers shared by the `apk-info` crates.
+//!
+//! Headers of crafted files declare huge counts (`string_count`, `entry_count`,
+//! `insns_size`) to make the parser allocate or loop for nothing. Every element
+//! takes at least a few bytes, so a count is checked against the remaining input
+//! before anything is parsed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use thiserror::Error;
+use winnow::combinator::repeat;
+use winnow::error::{ContextError, ErrMode, FromExternalError};
+use winnow::prelude::*;
+
+/// The declared number of elements can't fit into the remaining input.
+///
+/// Stored as the cause of the [ContextError], see [ContextError::cause].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{count} elements of at least {element_size} bytes don't fit into {remaining} bytes")]
+pub struct CountError {
+    /// Declared number of elements.
+    pub count: usize,
+
+    /// Minimal size of a single element in bytes.
+    pub element_size: usize,
+
+    /// Size of the remaining input in bytes.
+    pub remaining: usize,
+}
+
+/// Checks that `count` elements of at least `element_size` bytes fit into the input.
+///
+/// Fails with [ErrMode::Backtrack] and [CountError] as the cause, the same way as running
+/// out of input in the middle of [repeat], so tolerant parsers can skip the broken chunk.
+pub fn check_count(input: &[u8], count: usize, element_size: usize) -> ModalResult<()> {
+    if count.saturating_mul(element_size) <= input.len() {
+        return Ok(());
+    }
+
+    let error = CountError {
+        count,
+        element_size,
+        remaining: input.len(),
+    };
+
+    Err(ErrMode::from_external_error(&input, error))
+}
+
+/// [repeat] for counts taken from the file, see [check_count].
+///
+/// ```ignore
+/// let offsets = repeat_bounded(string_count as usize, 4, le_u32).parse_next(input)?;
+/// ```
+pub fn repeat_bounded<'a, O, P>(
+    count: usize,
+    element_size: usize,
+    mut parser: P,
+) -> impl Parser<&'a [u8], Vec<O>, ErrMode<ContextError>>
+where
+    P: Parser<&'a [u8], O, ErrMode<ContextError>>,
+{
+    move |input: &mut &'a [u8]| {
+        check_count(input, count, element_size)?;
+
+        repeat(count, parser.by_ref()).parse_next(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use winnow::binary::le_u32;
+
+    use super::*;
+
+    #[test]
+    fn test_repeat_bounded() {
+        let data = [1, 0, 0, 0, 2, 0, 0, 0];
+        let mut input = &data[..];
+
+        let values = repeat_bounded(2, 4, le_u32).parse_next(&mut input).unwrap();
+        assert_eq!(values, vec![1, 2]);
+        assert!(input.is_empty());
+    }
+
+    // the cause of the error is kept only with `std`
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_repeat_bounded_huge_count() {
+        let data = [0u8; 8];
+        let mut input = &data[..];
+
+        let Err(ErrMode::Backtrack(error)) =
+            repeat_bounded(u32::MAX as usize, 4, le_u32).parse_next(&mut input)
+        else {
+            panic!("expected backtrack error");
+        };
+
+        let cause = error
+            .cause()
+            .and_then(|cause| cause.downcast_ref::<CountError>());
+        assert_eq!(
+            cause,
+            Some(&CountError {
+                count: u32::MAX as usize,
+                element_size: 4,
+                remaining: 8,
+            })
+        );
+    }
+}
@@ -0,0 +1,671 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::{Deref, Range};
+
+use winnow::binary::{le_u16, le_u32};
+use winnow::error::ContextError;
+use winnow::prelude::*;
+
+use crate::errors::DexError;
+use crate::logging::warn;
+use crate::structs::class_data::uleb128;
+use crate::structs::{
+    ClassData, ClassDef, CodeItem, CompactDexHeader, DexHeader, EncodedMethod, FieldId,
+    HiddenApiFlags, MapItem, MethodId, NO_INDEX, ProtoId, mutf8,
+};
+use crate::vdex::Vdex;
+
+/// Parsed dex file.
+#[derive(Debug)]
+pub struct Dex {
+    data: DexData,
+    header: DexHeader,
+
+    /// Offsets of `string_data_item`
+    string_ids: Vec<u32>,
+
+    /// Indexes into the string table
+    type_ids: Vec<u32>,
+
+    proto_ids: Vec<ProtoId>,
+    field_ids: Vec<FieldId>,
+    method_ids: Vec<MethodId>,
+    class_defs: Vec<ClassDef>,
+    map_list: Vec<MapItem>,
+}
+
+impl Dex {
+    /// Parses the dex file from the given bytes.
+    ///
+    /// Only the header and the id tables are parsed here, strings and class data
+    /// are decoded on access.
+    ///
+    /// Compact dex files (`cdex001`) are supported too, dex files of the `.vdex` container
+    /// are extracted with [Vdex::new].
+    pub fn new(data: Vec<u8>) -> Result<Dex, DexError> {
+        let range = 0..data.len();
+        Dex::from_shared(Arc::new(data), range)
+    }
+
+    /// Parses the dex file stored in the range of the buffer.
+    ///
+    /// Compact dex files of a vdex keep their data in one shared section, so they are
+    /// parsed from the same buffer instead of copying the section for every file.
+    pub(crate) fn from_shared(buffer: Arc<Vec<u8>>, range: Range<usize>) -> Result<Dex, DexError> {
+        let data = DexData { buffer, range };
+
+        if data.starts_with(Vdex::MAGIC) {
+            return Err(DexError::VdexContainer);
+        }
+
+        if data.len() < DexHeader::SIZE {
+            return Err(DexError::TooSmallError);
+        }
+
+        let header = DexHeader::parse(&mut &data[..]).map_err(|_| DexError::HeaderError)?;
+
+        if header.is_compact() {
+            if data.len() < CompactDexHeader::SIZE {
+                return Err(DexError::TooSmallError);
+            }
+
+            if header.data_off as usize > data.len() {
+                return Err(DexError::SectionOutOfBounds {
+                    section: "data",
+                    offset: header.data_off,
+                    count: header.data_size,
+                });
+            }
+        }
+        let data_base = data_base(&header);
+
+        match header.endian_tag {
+            DexHeader::ENDIAN_CONSTANT => {}
+            DexHeader::REVERSE_ENDIAN_CONSTANT => {
+                return Err(DexError::UnsupportedEndian(header.endian_tag));
+            }
+            // the runtime rejects such files, but the tables may still be readable
+            other => warn!("unexpected endian tag 0x{other:x}, assuming little-endian"),
+        }
+
+        let string_ids = parse_section(
+            &data,
+            "string_ids",
+            header.string_ids_off,
+            header.string_ids_size,
+            4,
+            parse_u32,
+        )?;
+        let type_ids = parse_section(
+            &data,
+            "type_ids",
+            header.type_ids_off,
+            header.type_ids_size,
+            4,
+            parse_u32,
+        )?;
+        let proto_ids = parse_section(
+            &data,
+            "proto_ids",
+            header.proto_ids_off,
+            header.proto_ids_size,
+            ProtoId::SIZE,
+            ProtoId::parse,
+        )?;
+        let field_ids = parse_section(
+            &data,
+            "field_ids",
+            header.field_ids_off,
+            header.field_ids_size,
+            FieldId::SIZE,
+            FieldId::parse,
+        )?;
+        let method_ids = parse_section(
+            &data,
+            "method_ids",
+            header.method_ids_off,
+            header.method_ids_size,
+            MethodId::SIZE,
+            MethodId::parse,
+        )?;
+        let class_defs = parse_section(
+            &data,
+            "class_defs",
+            header.class_defs_off,
+            header.class_defs_size,
+            ClassDef::SIZE,
+            ClassDef::parse,
+        )?;
+
+        // the map is only needed for optional sections, so a broken one is not fatal
+        let map_list = parse_map_list(&data[data_base..], header.map_off).unwrap_or_else(|e| {
+            warn!("can't parse map_list: {e}");
+            Vec::new()
+        });
+
+        Ok(Dex {
+            data,
+            header,
+            string_ids,
+            type_ids,
+            proto_ids,
+            field_ids,
+            method_ids,
+            class_defs,
+            map_list,
+        })
+    }
+
+    /// Returns the parsed header.
+    #[inline]
+    pub fn header(&self) -> &DexHeader {
+        &self.header
+    }
+
+    /// Checks if the file is a compact dex, see [DexHeader::is_compact].
+    #[inline]
+    pub fn is_compact(&self) -> bool {
+        self.header.is_compact()
+    }
+
+    /// Returns the rest of the header of the compact dex.
+    pub fn compact_header(&self) -> Option<CompactDexHeader> {
+        if !self.is_compact() {
+            return None;
+        }
+
+        CompactDexHeader::parse(&mut self.data.get(DexHeader::SIZE..)?).ok()
+    }
+
+    /// Returns the data starting at the offset of the data item.
+    fn data_at(&self, offset: u32) -> Option<&[u8]> {
+        let start = data_base(&self.header).checked_add(offset as usize)?;
+        self.data.get(start..)
+    }
+
+    /// Returns raw bytes of the dex file.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Checks the adler32 [checksum](DexHeader::checksum) of the file.
+    ///
+    /// Tools that patch dex files often don't bother to update it, the runtime
+    /// verifies it only for files loaded from disk.
+    pub fn is_checksum_valid(&self) -> bool {
+        self.compute_checksum() == Some(self.header.checksum)
+    }
+
+    /// Recomputes the adler32 checksum of everything after the `checksum` field.
+    ///
+    /// Compact dex files keep the data section outside of the file, the runtime checksums
+    /// the header, the rest of the file and the data section separately and combines them,
+    /// see `CompactDexFile::CalculateChecksum` in AOSP.
+    pub(crate) fn compute_checksum(&self) -> Option<u32> {
+        let end = (self.header.file_size as usize).min(self.data.len());
+        if !self.header.is_compact() {
+            return Some(adler32(ADLER32_INITIAL, self.data.get(12..end)?));
+        }
+
+        // checksum, data_size and data_off are excluded from the sum
+        let mut header = self.data.get(..CompactDexHeader::SIZE)?.to_vec();
+        header[8..12].fill(0);
+        header[0x68..0x70].fill(0);
+
+        let data_begin = self.header.data_off as usize;
+        let data = self
+            .data
+            .get(data_begin..data_begin.checked_add(self.header.data_size as usize)?)?;
+
+        let checksum = adler32(ADLER32_INITIAL, &header);
+        let checksum = checksum.wrapping_mul(31)
+            ^ adler32(ADLER32_INITIAL, self.data.get(CompactDexHeader::SIZE..end)?);
+        Some(checksum.wrapping_mul(31) ^ adler32(ADLER32_INITIAL, data))
+    }
+
+    /// Returns the number of strings in the string table.
+    #[inline]
+    pub fn strings_count(&self) -> usize {
+        self.string_ids.len()
+    }
+
+    /// Returns the string by its index in the string table.
+    pub fn get_string(&self, idx: u32) -> Option<String> {
+        let mut input = self.data_at(*self.string_ids.get(idx as usize)?)?;
+
+        // size in utf-16 code units, not needed for decoding
+        uleb128.parse_next(&mut input).ok()?;
+
+        Some(mutf8::decode(input))
+    }
+
+    /// Iterates over all strings in the string table.
+    ///
+    /// Strings that point outside of the file are skipped.
+    pub fn strings(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.string_ids.len() as u32).filter_map(|idx| self.get_string(idx))
+    }
+
+    /// Returns the number of types in the type table.
+    #[inline]
+    pub fn types_count(&self) -> usize {
+        self.type_ids.len()
+    }
+
+    /// Returns the type descriptor (`Landroid/app/Activity;`) by its index in the type table.
+    pub fn get_type(&self, idx: u32) -> Option<String> {
+        if idx == NO_INDEX {
+            return None;
+        }
+
+        self.get_string(*self.type_ids.get(idx as usize)?)
+    }
+
+    /// Returns the java class name (`android.app.Activity`) by its index in the type table.
+    ///
+    /// Returns [None] for primitive types and arrays.
+    pub fn get_class_name(&self, idx: u32) -> Option<String> {
+        let descriptor = self.get_type(idx)?;
+        let name = descriptor.strip_prefix('L')?.strip_suffix(';')?;
+
+        Some(name.replace('/', "."))
+    }
+
+    /// Returns the prototype table.
+    #[inline]
+    pub fn proto_ids(&self) -> &[ProtoId] {
+        &self.proto_ids
+    }
+
+    /// Returns the field table.
+    #[inline]
+    pub fn field_ids(&self) -> &[FieldId] {
+        &self.field_ids
+    }
+
+    /// Returns the name of the field by its index in the field table.
+    pub fn get_field_name(&self, idx: u32) -> Option<String> {
+        self.get_string(self.field_ids.get(idx as usize)?.name_idx)
+    }
+
+    /// Returns the method table.
+    #[inline]
+    pub fn method_ids(&self) -> &[MethodId] {
+        &self.method_ids
+    }
+
+    /// Returns the name of the method by its index in the method table.
+    pub fn get_method_name(&self, idx: u32) -> Option<String> {
+        self.get_string(self.method_ids.get(idx as usize)?.name_idx)
+    }
+
+    /// Returns the descriptor of the prototype: `(ILjava/lang/String;)V`.
+    pub fn get_proto_descriptor(&self, idx: u32) -> Option<String> {
+        let proto = self.proto_ids.get(idx as usize)?;
+        let mut descriptor = String::from("(");
+
+        if proto.parameters_off != 0 {
+            let mut input = self.data_at(proto.parameters_off)?;
+            let size = le_u32::<_, ContextError>.parse_next(&mut input).ok()?;
+
+            // every item takes 2 bytes, don't trust the size blindly
+            if size as usize > input.len() / 2 {
+                return None;
+            }
+
+            for _ in 0..size {
+                let type_idx = le_u16::<_, ContextError>.parse_next(&mut input).ok()?;
+                descriptor.push_str(&self.get_type(type_idx as u32)?);
+            }
+        }
+
+        descriptor.push(')');
+        descriptor.push_str(&self.get_type(proto.return_type_idx)?);
+
+        Some(descriptor)
+    }
+
+    /// Returns the full signature of the field: `Landroid/app/Activity;->mResumed:Z`.
+    ///
+    /// The same format is used by `hiddenapi-flags.csv` and smali.
+    pub fn get_field_signature(&self, idx: u32) -> Option<String> {
+        let field = self.field_ids.get(idx as usize)?;
+
+        Some(format!(
+            "{}->{}:{}",
+            self.get_type(field.class_idx as u32)?,
+            self.get_string(field.name_idx)?,
+            self.get_type(field.type_idx as u32)?
+        ))
+    }
+
+    /// Returns the full signature of the method: `Landroid/app/Activity;->finish()V`.
+    ///
+    /// The same format is used by `hiddenapi-flags.csv` and smali.
+    pub fn get_method_signature(&self, idx: u32) -> Option<String> {
+        let method = self.method_ids.get(idx as usize)?;
+
+        Some(format!(
+            "{}->{}{}",
+            self.get_type(method.class_idx as u32)?,
+            self.get_string(method.name_idx)?,
+            self.get_proto_descriptor(method.proto_idx as u32)?
+        ))
+    }
+
+    /// Returns all class definitions.
+    #[inline]
+    pub fn classes(&self) -> &[ClassDef] {
+        &self.class_defs
+    }
+
+    /// Parses fields and methods defined by the class.
+    ///
+    /// Returns [None] if the class has no class data.
+    pub fn get_class_data(&self, class: &ClassDef) -> Result<Option<ClassData>, DexError> {
+        if class.class_data_off == 0 {
+            return Ok(None);
+        }
+
+        let error = || DexError::ClassDataError {
+            offset: class.class_data_off,
+        };
+
+        let mut input = self.data_at(class.class_data_off).ok_or_else(error)?;
+
+        ClassData::parse(&mut input).map(Some).map_err(|_| error())
+    }
+
+    /// Parses the bytecode of the method.
+    ///
+    /// Returns [None] for `abstract` and `native` methods.
+    pub fn get_code_item(&self, method: &EncodedMethod) -> Result<Option<CodeItem>, DexError> {
+        if method.code_off == 0 {
+            return Ok(None);
+        }
+
+        let error = || DexError::CodeItemError {
+            offset: method.code_off,
+        };
+
+        if self.is_compact() {
+            let data = &self.data[data_base(&self.header)..];
+            return CodeItem::parse_compact(data, method.code_off as usize)
+                .map(Some)
+                .ok_or_else(error);
+        }
+
+        let mut input = self.data_at(method.code_off).ok_or_else(error)?;
+
+        CodeItem::parse(&mut input).map(Some).map_err(|_| error())
+    }
+
+    /// Returns indexes of the methods that load the string with `const-string`.
+    ///
+    /// The whole bytecode is scanned, use [Dex::string_references] for many strings.
+    pub fn string_xrefs(&self, string_idx: u32) -> Result<Vec<u32>, DexError> {
+        Ok(self
+            .string_references()?
+            .remove(&string_idx)
+            .unwrap_or_default())
+    }
+
+    /// Returns indexes of the methods that load each string with `const-string`.
+    ///
+    /// Methods are listed once per string in the order of class definitions.
+    pub fn string_references(&self) -> Result<BTreeMap<u32, Vec<u32>>, DexError> {
+        let mut references: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+
+        for class in &self.class_defs {
+            let Some(class_data) = self.get_class_data(class)? else {
+                continue;
+            };
+
+            for method in class_data.methods() {
+                let Some(code_item) = self.get_code_item(method)? else {
+                    continue;
+                };
+
+                for string_idx in code_item.string_refs() {
+                    let methods = references.entry(string_idx).or_default();
+                    if methods.last() != Some(&method.method_idx) {
+                        methods.push(method.method_idx);
+                    }
+                }
+            }
+        }
+
+        Ok(references)
+    }
+}
+
+/// Hidden api flags of the class members.
+impl Dex {
+    /// Returns the sections of the file described by the `map_list`.
+    #[inline]
+    pub fn map_list(&self) -> &[MapItem] {
+        &self.map_list
+    }
+
+    /// Returns `true` if the file has `hiddenapi_class_data_item`.
+    ///
+    /// Only the platform libraries (`framework.jar`, `core-oj.jar`, etc.) have such section.
+    pub fn has_hiddenapi_data(&self) -> bool {
+        self.hiddenapi_section().is_some()
+    }
+
+    fn hiddenapi_section(&self) -> Option<&MapItem> {
+        self.map_list
+            .iter()
+            .find(|item| item.type_ == MapItem::TYPE_HIDDENAPI_CLASS_DATA_ITEM)
+    }
+
+    /// Returns the hidden api flags of the class members by the index of the class definition.
+    ///
+    /// Flags follow the order of the class data: static fields, instance fields,
+    /// direct methods and virtual methods. Returns [None] if the file or the class has no flags.
+    ///
+    /// See: <https://source.android.com/docs/core/runtime/dex-format#hiddenapi-class-data-item>
+    pub fn get_hiddenapi_flags(
+        &self,
+        class_def_idx: usize,
+    ) -> Result<Option<Vec<HiddenApiFlags>>, DexError> {
+        let (Some(section), Some(class)) =
+            (self.hiddenapi_section(), self.class_defs.get(class_def_idx))
+        else {
+            return Ok(None);
+        };
+
+        let error = || DexError::HiddenApiError {
+            offset: section.offset,
+        };
+
+        // size, then offsets (relative to the section) for every class definition
+        let mut input = self
+            .data_at(section.offset)
+            .and_then(|data| data.get(4 + class_def_idx * 4..))
+            .ok_or_else(error)?;
+        let offset = le_u32::<_, ContextError>
+            .parse_next(&mut input)
+            .map_err(|_| error())?;
+
+        if offset == 0 {
+            return Ok(None);
+        }
+
+        let Some(class_data) = self.get_class_data(class)? else {
+            return Ok(None);
+        };
+
+        let mut input = self
+            .data_at(section.offset)
+            .and_then(|data| data.get(offset as usize..))
+            .ok_or_else(error)?;
+
+        let count = class_data.fields().count() + class_data.methods().count();
+        let mut flags = Vec::with_capacity(count);
+        for _ in 0..count {
+            flags.push(HiddenApiFlags(
+                uleb128.parse_next(&mut input).map_err(|_| error())?,
+            ));
+        }
+
+        Ok(Some(flags))
+    }
+
+    /// Returns signatures of all class members with their hidden api flags.
+    ///
+    /// Empty if the file has no `hiddenapi_class_data_item`.
+    ///
+    /// ```ignore
+    /// for (signature, flags) in dex.hiddenapi_members()? {
+    ///     println!("{signature},{}", flags.restriction());
+    /// }
+    /// ```
+    pub fn hiddenapi_members(&self) -> Result<Vec<(String, HiddenApiFlags)>, DexError> {
+        let mut members = Vec::new();
+        if !self.has_hiddenapi_data() {
+            return Ok(members);
+        }
+
+        for (idx, class) in self.class_defs.iter().enumerate() {
+            let (Some(flags), Some(class_data)) =
+                (self.get_hiddenapi_flags(idx)?, self.get_class_data(class)?)
+            else {
+                continue;
+            };
+
+            let signatures = class_data
+                .fields()
+                .map(|f| self.get_field_signature(f.field_idx))
+                .chain(
+                    class_data
+                        .methods()
+                        .map(|m| self.get_method_signature(m.method_idx)),
+                );
+
+            for (signature, flags) in signatures.zip(flags) {
+                if let Some(signature) = signature {
+                    members.push((signature, flags));
+                }
+            }
+        }
+
+        Ok(members)
+    }
+}
+
+/// Returns the start of the data section, offsets of the data items are relative to it.
+///
+/// Zero for the standard dex, compact dex files of the vdex share one data section.
+/// Bytes of the dex file, a range of the buffer that may be shared with other files.
+#[derive(Debug)]
+struct DexData {
+    buffer: Arc<Vec<u8>>,
+    range: Range<usize>,
+}
+
+impl Deref for DexData {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.buffer[self.range.clone()]
+    }
+}
+
+fn data_base(header: &DexHeader) -> usize {
+    if header.is_compact() {
+        header.data_off as usize
+    } else {
+        0
+    }
+}
+
+/// Parses the `map_list`, an empty list is returned if there is no map.
+fn parse_map_list(data: &[u8], offset: u32) -> Result<Vec<MapItem>, DexError> {
+    if offset == 0 {
+        return Ok(Vec::new());
+    }
+
+    let count = data
+        .get(offset as usize..)
+        .and_then(|mut input| le_u32::<_, ContextError>.parse_next(&mut input).ok())
+        .ok_or(DexError::SectionOutOfBounds {
+            section: "map_list",
+            offset,
+            count: 0,
+        })?;
+
+    parse_section(
+        data,
+        "map_list",
+        offset.saturating_add(4),
+        count,
+        MapItem::SIZE,
+        MapItem::parse,
+    )
+}
+
+/// Parses the table of fixed-size items, checking that it fits into the file.
+fn parse_section<T>(
+    data: &[u8],
+    section: &'static str,
+    offset: u32,
+    count: u32,
+    item_size: usize,
+    parser: fn(&mut &[u8]) -> ModalResult<T>,
+) -> Result<Vec<T>, DexError> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let error = || DexError::SectionOutOfBounds {
+        section,
+        offset,
+        count,
+    };
+
+    let end = (count as usize)
+        .checked_mul(item_size)
+        .and_then(|size| size.checked_add(offset as usize))
+        .ok_or_else(error)?;
+    let mut input = data.get(offset as usize..end).ok_or_else(error)?;
+
+    let mut items = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        items.push(parser(&mut input).map_err(|_| error())?);
+    }
+
+    Ok(items)
+}
+
+fn parse_u32(input: &mut &[u8]) -> ModalResult<u32> {
+    le_u32.parse_next(input)
+}
+
+/// Initial value of the Adler-32 checksum.
+pub(crate) const ADLER32_INITIAL: u32 = 1;
+
+/// Adler-32 checksum, see RFC 1950. Continues from the `checksum` of the preceding data.
+pub(crate) fn adler32(checksum: u32, data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    // the largest n such that 255n(n+1)/2 + (n+1)(MOD-1) fits into u32
+    const CHUNK: usize = 5552;
+
+    let (mut a, mut b) = (checksum & 0xffff, checksum >> 16);
+    for chunk in data.chunks(CHUNK) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+
+    (b << 16) | a
+}
@@ -0,0 +1,63 @@
+//! Errors returned by this crate.
+//!
+//! This module contains the definitions for all error types returned by this crate.
+
+use thiserror::Error;
+
+/// Errors that may occur while parsing a dex file.
+#[derive(Error, Debug)]
+pub enum DexError {
+    /// The provided file is too small to contain a valid dex header.
+    #[error("file size too small for dex header")]
+    TooSmallError,
+
+    /// Failed to parse the header (invalid magic).
+    #[error("failed to parse dex header")]
+    HeaderError,
+
+    /// The file is a `.vdex` container, see [Vdex::new](crate::Vdex::new).
+    #[error("vdex container, not a dex file")]
+    VdexContainer,
+
+    /// The version of the `.vdex` container is not supported.
+    #[error("unsupported vdex version {0}")]
+    UnsupportedVdexVersion(u32),
+
+    /// Big-endian dex files are not supported.
+    #[error("unsupported endian tag 0x{0:x}")]
+    UnsupportedEndian(u32),
+
+    /// The section points outside of the file.
+    #[error("section {section} at offset 0x{offset:x} with {count} items is out of bounds")]
+    SectionOutOfBounds {
+        /// Name of the section (`string_ids`, `type_ids`, ...).
+        section: &'static str,
+
+        /// Offset of the section from the start of the file.
+        offset: u32,
+
+        /// Number of items declared in the header.
+        count: u32,
+    },
+
+    /// Failed to parse the class data.
+    #[error("failed to parse class data at offset 0x{offset:x}")]
+    ClassDataError {
+        /// Offset of the class data from the start of the file.
+        offset: u32,
+    },
+
+    /// Failed to parse the code item.
+    #[error("failed to parse code_item at offset 0x{offset:x}")]
+    CodeItemError {
+        /// Offset of the code item from the start of the file.
+        offset: u32,
+    },
+
+    /// Failed to parse the hidden api flags.
+    #[error("failed to parse hiddenapi_class_data_item at offset 0x{offset:x}")]
+    HiddenApiError {
+        /// Offset of the section from the start of the file.
+        offset: u32,
+    },
+}
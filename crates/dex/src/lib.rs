@@ -0,0 +1,34 @@
+//! A parser for `Dalvik Executable` (DEX) files.
+//!
+//! Parses the header, the id tables (strings, types, prototypes, fields, methods) and the
+//! class definitions. Strings and class data are decoded lazily on access.
+//!
+//! Compact dex files (`cdex`) and dex files of the `.vdex` container from the devices
+//! are supported as well, see [Vdex].
+//!
+//! ## Example
+//!
+//! ```ignore
+//! let dex = Dex::new(input).expect("can't parse dex file");
+//! for class in dex.classes() {
+//!     println!("{:?}", dex.get_type(class.class_idx));
+//! }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod dex;
+pub mod errors;
+mod logging;
+
+pub mod structs;
+#[cfg(test)]
+mod testing;
+mod vdex;
+mod verify;
+
+pub use dex::Dex;
+pub use vdex::Vdex;
+pub use verify::DexVerification;
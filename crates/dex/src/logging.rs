@@ -0,0 +1,13 @@
+//! Wrapper of the `log` macro that is compiled out with the `quiet` feature.
+//!
+//! Warnings only report recoverable oddities of the header, parsing goes on either way.
+
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        if cfg!(not(feature = "quiet")) {
+            ::log::warn!($($arg)+)
+        }
+    };
+}
+
+pub(crate) use log_warn as warn;
@@ -0,0 +1,182 @@
+use alloc::vec::Vec;
+
+use apk_info_common::check_count;
+use winnow::error::{ContextError, ErrMode};
+use winnow::prelude::*;
+use winnow::token::any;
+
+/// Field defined by a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncodedField {
+    /// Index into the field table.
+    pub field_idx: u32,
+
+    /// Access flags of the field.
+    pub access_flags: u32,
+}
+
+/// Method defined by a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EncodedMethod {
+    /// Index into the method table.
+    pub method_idx: u32,
+
+    /// Access flags of the method.
+    pub access_flags: u32,
+
+    /// Offset to the code, `0` for `abstract` and `native` methods.
+    pub code_off: u32,
+}
+
+/// Fields and methods defined by a class.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#class-data-item>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassData {
+    pub static_fields: Vec<EncodedField>,
+    pub instance_fields: Vec<EncodedField>,
+    pub direct_methods: Vec<EncodedMethod>,
+    pub virtual_methods: Vec<EncodedMethod>,
+}
+
+impl ClassData {
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<ClassData> {
+        let static_fields_size = uleb128.parse_next(input)?;
+        let instance_fields_size = uleb128.parse_next(input)?;
+        let direct_methods_size = uleb128.parse_next(input)?;
+        let virtual_methods_size = uleb128.parse_next(input)?;
+
+        Ok(ClassData {
+            static_fields: parse_fields(input, static_fields_size)?,
+            instance_fields: parse_fields(input, instance_fields_size)?,
+            direct_methods: parse_methods(input, direct_methods_size)?,
+            virtual_methods: parse_methods(input, virtual_methods_size)?,
+        })
+    }
+
+    /// Iterates over all fields, static first.
+    pub fn fields(&self) -> impl Iterator<Item = &EncodedField> {
+        self.static_fields.iter().chain(&self.instance_fields)
+    }
+
+    /// Iterates over all methods, direct first.
+    pub fn methods(&self) -> impl Iterator<Item = &EncodedMethod> {
+        self.direct_methods.iter().chain(&self.virtual_methods)
+    }
+}
+
+/// Field indexes are stored as a difference from the previous one.
+fn parse_fields(input: &mut &[u8], count: u32) -> ModalResult<Vec<EncodedField>> {
+    // every field takes at least 2 bytes
+    let count = count as usize;
+    check_count(input, count, 2)?;
+
+    let mut fields = Vec::with_capacity(count);
+    let mut field_idx = 0u32;
+    for _ in 0..count {
+        let (diff, access_flags) = (uleb128, uleb128).parse_next(input)?;
+        field_idx = field_idx.wrapping_add(diff);
+        fields.push(EncodedField {
+            field_idx,
+            access_flags,
+        });
+    }
+
+    Ok(fields)
+}
+
+/// Method indexes are stored as a difference from the previous one.
+fn parse_methods(input: &mut &[u8], count: u32) -> ModalResult<Vec<EncodedMethod>> {
+    // every method takes at least 3 bytes
+    let count = count as usize;
+    check_count(input, count, 3)?;
+
+    let mut methods = Vec::with_capacity(count);
+    let mut method_idx = 0u32;
+    for _ in 0..count {
+        let (diff, access_flags, code_off) = (uleb128, uleb128, uleb128).parse_next(input)?;
+        method_idx = method_idx.wrapping_add(diff);
+        methods.push(EncodedMethod {
+            method_idx,
+            access_flags,
+            code_off,
+        });
+    }
+
+    Ok(methods)
+}
+
+/// Parses unsigned LEB128 value, at most 5 bytes.
+pub(crate) fn uleb128(input: &mut &[u8]) -> ModalResult<u32> {
+    let mut result = 0u32;
+
+    for shift in (0..35).step_by(7) {
+        let byte = any.parse_next(input)?;
+        result |= ((byte & 0x7f) as u32).wrapping_shl(shift);
+
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+
+    Err(ErrMode::Cut(ContextError::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uleb128() {
+        let cases: &[(&[u8], u32)] = &[
+            (&[0x00], 0),
+            (&[0x01], 1),
+            (&[0x7f], 127),
+            (&[0x80, 0x7f], 16256),
+            (&[0xff, 0xff, 0xff, 0xff, 0x0f], u32::MAX),
+        ];
+
+        for (data, expected) in cases {
+            let mut input = *data;
+            assert_eq!(uleb128(&mut input).unwrap(), *expected);
+            assert!(input.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_uleb128_too_long() {
+        let mut input: &[u8] = &[0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+        assert!(uleb128(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_parse_class_data() {
+        #[rustfmt::skip]
+        let data: &[u8] = &[
+            0x01, 0x00, 0x01, 0x01, // sizes
+            0x02, 0x08,             // static field #2, private static
+            0x05, 0x01, 0x00,       // direct method #5, public, no code
+            0x06, 0x01, 0x80, 0x01, // virtual method #6, public, code at 0x80
+        ];
+
+        let mut input = data;
+        let class_data = ClassData::parse(&mut input).unwrap();
+
+        assert_eq!(class_data.static_fields[0].field_idx, 2);
+        assert!(class_data.instance_fields.is_empty());
+        assert_eq!(class_data.direct_methods[0].method_idx, 5);
+        assert_eq!(class_data.virtual_methods[0].method_idx, 6);
+        assert_eq!(class_data.virtual_methods[0].code_off, 0x80);
+        assert_eq!(class_data.methods().count(), 2);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_parse_class_data_huge_count() {
+        let mut input: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0x0f, 0x00, 0x00, 0x00];
+        assert!(ClassData::parse(&mut input).is_err());
+    }
+}
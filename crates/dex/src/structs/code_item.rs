@@ -0,0 +1,305 @@
+use alloc::vec::Vec;
+
+use apk_info_common::repeat_bounded;
+use winnow::binary::{le_u16, le_u32};
+use winnow::error::ContextError;
+use winnow::prelude::*;
+
+/// Bytecode of a method.
+///
+/// Only the header and the instructions are parsed, `try_item` and handlers are skipped.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#code-item>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CodeItem {
+    /// Number of registers used by the code.
+    pub registers_size: u16,
+
+    /// Number of words of incoming arguments.
+    pub ins_size: u16,
+
+    /// Number of words of outgoing argument space required for method invocation.
+    pub outs_size: u16,
+
+    /// Number of `try_item` entries.
+    pub tries_size: u16,
+
+    /// Offset to the debug info, `0` if there is none.
+    pub debug_info_off: u32,
+
+    /// Instructions in 16-bit code units.
+    pub insns: Vec<u16>,
+}
+
+/// Identifiers of the pseudo-instructions with inline data.
+const PACKED_SWITCH_PAYLOAD: u16 = 0x0100;
+const SPARSE_SWITCH_PAYLOAD: u16 = 0x0200;
+const FILL_ARRAY_DATA_PAYLOAD: u16 = 0x0300;
+
+/// Flags of the compact code item: the size is continued in the preheader.
+const COMPACT_PREHEADER_REGISTERS_SIZE: u16 = 0x1;
+const COMPACT_PREHEADER_INS_SIZE: u16 = 0x2;
+const COMPACT_PREHEADER_OUTS_SIZE: u16 = 0x4;
+const COMPACT_PREHEADER_TRIES_SIZE: u16 = 0x8;
+const COMPACT_PREHEADER_INSNS_SIZE: u16 = 0x10;
+
+/// `const-string vAA, string@BBBB`
+const CONST_STRING: u8 = 0x1a;
+
+/// `const-string/jumbo vAA, string@BBBBBBBB`
+const CONST_STRING_JUMBO: u8 = 0x1b;
+
+impl CodeItem {
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<CodeItem> {
+        let (registers_size, ins_size, outs_size, tries_size, debug_info_off, insns_size) =
+            (le_u16, le_u16, le_u16, le_u16, le_u32, le_u32).parse_next(input)?;
+
+        let insns = repeat_bounded(insns_size as usize, 2, le_u16).parse_next(input)?;
+
+        Ok(CodeItem {
+            registers_size,
+            ins_size,
+            outs_size,
+            tries_size,
+            debug_info_off,
+            insns,
+        })
+    }
+
+    /// Parses the compact dex code item at `offset`.
+    ///
+    /// Sizes that don't fit into the 4-bit fields are stored in the preheader, 16-bit words
+    /// right before the code item, so the whole data section is needed. The debug info
+    /// is kept in a separate table, [CodeItem::debug_info_off] is always `0`.
+    pub(crate) fn parse_compact(data: &[u8], offset: usize) -> Option<CodeItem> {
+        let mut input = data.get(offset..)?;
+        let (fields, insns_count_and_flags) = (le_u16::<_, ContextError>, le_u16)
+            .parse_next(&mut input)
+            .ok()?;
+
+        // registers are stored without the incoming arguments
+        let mut registers_size = fields >> 12;
+        let mut ins_size = (fields >> 8) & 0xf;
+        let mut outs_size = (fields >> 4) & 0xf;
+        let mut tries_size = fields & 0xf;
+        let mut insns_size = u32::from(insns_count_and_flags >> 5);
+        let flags = insns_count_and_flags & 0x1f;
+
+        // the preheader is read backwards
+        let mut preheader = offset;
+        let mut previous = || -> Option<u16> {
+            preheader = preheader.checked_sub(2)?;
+            let word = data.get(preheader..preheader + 2)?;
+            Some(u16::from_le_bytes([word[0], word[1]]))
+        };
+
+        if flags & COMPACT_PREHEADER_INSNS_SIZE != 0 {
+            insns_size = insns_size.wrapping_add(u32::from(previous()?));
+            insns_size = insns_size.wrapping_add(u32::from(previous()?) << 16);
+        }
+        if flags & COMPACT_PREHEADER_REGISTERS_SIZE != 0 {
+            registers_size = registers_size.wrapping_add(previous()?);
+        }
+        if flags & COMPACT_PREHEADER_INS_SIZE != 0 {
+            ins_size = ins_size.wrapping_add(previous()?);
+        }
+        if flags & COMPACT_PREHEADER_OUTS_SIZE != 0 {
+            outs_size = outs_size.wrapping_add(previous()?);
+        }
+        if flags & COMPACT_PREHEADER_TRIES_SIZE != 0 {
+            tries_size = tries_size.wrapping_add(previous()?);
+        }
+
+        let insns = repeat_bounded(insns_size as usize, 2, le_u16)
+            .parse_next(&mut input)
+            .ok()?;
+
+        Some(CodeItem {
+            registers_size: registers_size.wrapping_add(ins_size),
+            ins_size,
+            outs_size,
+            tries_size,
+            debug_info_off: 0,
+            insns,
+        })
+    }
+
+    /// Iterates over the instructions as slices of code units, payloads included.
+    ///
+    /// Iteration stops at the truncated instruction.
+    pub fn instructions(&self) -> impl Iterator<Item = &[u16]> {
+        let mut pc = 0;
+
+        core::iter::from_fn(move || {
+            let width = instruction_width(&self.insns[pc..])?;
+            let instruction = self.insns.get(pc..pc + width)?;
+            pc += width;
+            Some(instruction)
+        })
+    }
+
+    /// Returns indexes of the strings loaded by `const-string` and `const-string/jumbo`.
+    pub fn string_refs(&self) -> impl Iterator<Item = u32> + '_ {
+        self.instructions()
+            .filter_map(|instruction| match (instruction[0] & 0xff) as u8 {
+                CONST_STRING => Some(instruction[1] as u32),
+                CONST_STRING_JUMBO => Some(instruction[1] as u32 | (instruction[2] as u32) << 16),
+                _ => None,
+            })
+    }
+}
+
+/// Returns the width of the first instruction in code units.
+///
+/// See: <https://source.android.com/docs/core/runtime/dalvik-bytecode>
+fn instruction_width(insns: &[u16]) -> Option<usize> {
+    let unit = *insns.first()?;
+
+    let width = match unit {
+        PACKED_SWITCH_PAYLOAD => 4 + *insns.get(1)? as usize * 2,
+        SPARSE_SWITCH_PAYLOAD => 2 + *insns.get(1)? as usize * 4,
+        FILL_ARRAY_DATA_PAYLOAD => {
+            let element_width = *insns.get(1)? as usize;
+            let size = *insns.get(2)? as usize | (*insns.get(3)? as usize) << 16;
+            4 + (element_width * size).div_ceil(2)
+        }
+        _ => match (unit & 0xff) as u8 {
+            0x00..=0x01 => 1,
+            0x02 => 2,
+            0x03 => 3,
+            0x04 => 1,
+            0x05 => 2,
+            0x06 => 3,
+            0x07 => 1,
+            0x08 => 2,
+            0x09 => 3,
+            0x0a..=0x12 => 1,
+            0x13 => 2,
+            0x14 => 3,
+            0x15 | 0x16 => 2,
+            0x17 => 3,
+            0x18 => 5,
+            0x19 | 0x1a => 2,
+            0x1b => 3,
+            0x1c => 2,
+            0x1d | 0x1e => 1,
+            0x1f | 0x20 => 2,
+            0x21 => 1,
+            0x22 | 0x23 => 2,
+            0x24..=0x26 => 3,
+            0x27 | 0x28 => 1,
+            0x29 => 2,
+            0x2a..=0x2c => 3,
+            0x2d..=0x3d => 2,
+            0x3e..=0x43 => 1,
+            0x44..=0x6d => 2,
+            0x6e..=0x72 => 3,
+            0x73 => 1,
+            0x74..=0x78 => 3,
+            0x79..=0x7a => 1,
+            0x7b..=0x8f => 1,
+            0x90..=0xaf => 2,
+            0xb0..=0xcf => 1,
+            0xd0..=0xe2 => 2,
+            0xe3..=0xf9 => 1,
+            0xfa | 0xfb => 4,
+            0xfc | 0xfd => 3,
+            0xfe | 0xff => 2,
+        },
+    };
+
+    Some(width)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    fn code(insns: &[u16]) -> CodeItem {
+        CodeItem {
+            insns: insns.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_code_item() {
+        #[rustfmt::skip]
+        let data: &[u8] = &[
+            0x02, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, // registers, ins, outs, tries
+            0x00, 0x00, 0x00, 0x00,                         // debug_info_off
+            0x03, 0x00, 0x00, 0x00,                         // insns_size
+            0x1a, 0x00, 0x05, 0x00,                         // const-string v0, string@5
+            0x0e, 0x00,                                     // return-void
+        ];
+
+        let mut input = data;
+        let code_item = CodeItem::parse(&mut input).unwrap();
+
+        assert_eq!(code_item.registers_size, 2);
+        assert_eq!(code_item.insns, vec![0x001a, 0x0005, 0x000e]);
+        assert_eq!(code_item.string_refs().collect::<Vec<_>>(), vec![5]);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_parse_compact_code_item() {
+        #[rustfmt::skip]
+        let data: &[u8] = &[
+            0x01, 0x00,             // preheader: registers_size
+            0x12, 0x10,             // registers 1 (+1 from preheader), ins 0, outs 1, tries 2
+            0x41, 0x00,             // 2 code units, registers_size in the preheader
+            0x1a, 0x00, 0x05, 0x00, // const-string v0, string@5
+            0x0e, 0x00,             // return-void
+        ];
+
+        let code_item = CodeItem::parse_compact(data, 2).unwrap();
+
+        assert_eq!(code_item.registers_size, 2);
+        assert_eq!(code_item.outs_size, 1);
+        assert_eq!(code_item.tries_size, 2);
+        assert_eq!(code_item.insns, vec![0x001a, 0x0005]);
+
+        // the preheader is outside of the data
+        assert!(CodeItem::parse_compact(&data[2..], 0).is_none());
+    }
+
+    #[test]
+    fn test_parse_code_item_huge_size() {
+        #[rustfmt::skip]
+        let mut input: &[u8] = &[
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            0xff, 0xff, 0xff, 0x0f, // insns_size
+            0x00, 0x00,
+        ];
+        assert!(CodeItem::parse(&mut input).is_err());
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_string_refs() {
+        let code_item = code(&[
+            0x011b, 0x0002, 0x0001, // const-string/jumbo v1, string@0x10002
+            0x0026, 0x0006, 0x0000, // fill-array-data v0, +6
+            0x0000,                 // nop (alignment)
+            0x0300, 0x0001, 0x0003, 0x0000, 0x1a1a, 0x001a, // payload looking like const-string
+            0x021a, 0x0007,         // const-string v2, string@7
+        ]);
+
+        assert_eq!(
+            code_item.string_refs().collect::<Vec<_>>(),
+            vec![0x10002, 7]
+        );
+    }
+
+    #[test]
+    fn test_truncated_instruction() {
+        // invoke-virtual without operands
+        let code_item = code(&[0x001a, 0x0001, 0x106e]);
+        assert_eq!(code_item.instructions().count(), 1);
+    }
+}
@@ -0,0 +1,261 @@
+use winnow::binary::le_u32;
+use winnow::prelude::*;
+use winnow::token::take;
+
+/// Header of the dex file.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#header-item>
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DexHeader {
+    /// Magic value, `dex\n` (`cdex` for compact dex) followed by the version and `\0`.
+    pub magic: [u8; 8],
+
+    /// Adler32 checksum of the rest of the file (everything but `magic` and this field).
+    pub checksum: u32,
+
+    /// SHA-1 signature of the rest of the file (everything but `magic`, `checksum` and this field).
+    pub signature: [u8; 20],
+
+    /// Size of the entire file in bytes.
+    pub file_size: u32,
+
+    /// Size of the header, always `0x70`.
+    pub header_size: u32,
+
+    /// Endianness tag, see [DexHeader::ENDIAN_CONSTANT].
+    pub endian_tag: u32,
+
+    pub link_size: u32,
+    pub link_off: u32,
+
+    /// Offset to the map list.
+    pub map_off: u32,
+
+    pub string_ids_size: u32,
+    pub string_ids_off: u32,
+
+    pub type_ids_size: u32,
+    pub type_ids_off: u32,
+
+    pub proto_ids_size: u32,
+    pub proto_ids_off: u32,
+
+    pub field_ids_size: u32,
+    pub field_ids_off: u32,
+
+    pub method_ids_size: u32,
+    pub method_ids_off: u32,
+
+    pub class_defs_size: u32,
+    pub class_defs_off: u32,
+
+    pub data_size: u32,
+    pub data_off: u32,
+}
+
+impl DexHeader {
+    /// Size of the header in bytes.
+    pub const SIZE: usize = 0x70;
+
+    /// Expected value of [DexHeader::endian_tag] for little-endian files.
+    pub const ENDIAN_CONSTANT: u32 = 0x12345678;
+
+    /// Expected value of [DexHeader::endian_tag] for byte-swapped files.
+    pub const REVERSE_ENDIAN_CONSTANT: u32 = 0x78563412;
+
+    /// Prefix of the magic value.
+    pub(crate) const MAGIC_PREFIX: &[u8] = b"dex\n";
+
+    /// Prefix of the magic value of compact dex files.
+    pub(crate) const COMPACT_MAGIC_PREFIX: &[u8] = b"cdex";
+
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<DexHeader> {
+        let magic: &[u8] = take(8usize)
+            .verify(|magic: &[u8]| {
+                (magic.starts_with(Self::MAGIC_PREFIX)
+                    || magic.starts_with(Self::COMPACT_MAGIC_PREFIX))
+                    && magic[7] == 0
+            })
+            .parse_next(input)?;
+        let checksum = le_u32.parse_next(input)?;
+        let signature: &[u8] = take(20usize).parse_next(input)?;
+
+        let (file_size, header_size, endian_tag, link_size, link_off, map_off) =
+            (le_u32, le_u32, le_u32, le_u32, le_u32, le_u32).parse_next(input)?;
+
+        let (
+            string_ids_size,
+            string_ids_off,
+            type_ids_size,
+            type_ids_off,
+            proto_ids_size,
+            proto_ids_off,
+        ) = (le_u32, le_u32, le_u32, le_u32, le_u32, le_u32).parse_next(input)?;
+
+        let (
+            field_ids_size,
+            field_ids_off,
+            method_ids_size,
+            method_ids_off,
+            class_defs_size,
+            class_defs_off,
+        ) = (le_u32, le_u32, le_u32, le_u32, le_u32, le_u32).parse_next(input)?;
+
+        let (data_size, data_off) = (le_u32, le_u32).parse_next(input)?;
+
+        Ok(DexHeader {
+            // length is checked by the parser above
+            magic: magic.try_into().unwrap_or_default(),
+            checksum,
+            signature: signature.try_into().unwrap_or_default(),
+            file_size,
+            header_size,
+            endian_tag,
+            link_size,
+            link_off,
+            map_off,
+            string_ids_size,
+            string_ids_off,
+            type_ids_size,
+            type_ids_off,
+            proto_ids_size,
+            proto_ids_off,
+            field_ids_size,
+            field_ids_off,
+            method_ids_size,
+            method_ids_off,
+            class_defs_size,
+            class_defs_off,
+            data_size,
+            data_off,
+        })
+    }
+
+    /// Returns the format version from the magic (`35`, `38`, `39`, ...).
+    ///
+    /// Returns [None] if the version is not a number.
+    pub fn version(&self) -> Option<u32> {
+        core::str::from_utf8(&self.magic[4..7]).ok()?.parse().ok()
+    }
+
+    /// Checks if the file is a compact dex (`cdex001`) produced by `dex2oat`.
+    #[inline]
+    pub fn is_compact(&self) -> bool {
+        self.magic.starts_with(Self::COMPACT_MAGIC_PREFIX)
+    }
+}
+
+/// Fields of the compact dex header that follow [DexHeader].
+///
+/// See: `art/libdexfile/dex/compact_dex_file.h` in AOSP
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompactDexHeader {
+    pub feature_flags: u32,
+
+    /// Position in the table of the debug info offsets.
+    pub debug_info_offsets_pos: u32,
+
+    /// Offset of the table of the debug info offsets.
+    pub debug_info_offsets_table_offset: u32,
+
+    /// Base added to the debug info offsets.
+    pub debug_info_base: u32,
+
+    /// Range of the shared data section owned by this file.
+    pub owned_data_begin: u32,
+    pub owned_data_end: u32,
+}
+
+impl CompactDexHeader {
+    /// Size of the whole compact dex header in bytes.
+    pub const SIZE: usize = 0x88;
+
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<CompactDexHeader> {
+        let (
+            feature_flags,
+            debug_info_offsets_pos,
+            debug_info_offsets_table_offset,
+            debug_info_base,
+            owned_data_begin,
+            owned_data_end,
+        ) = (le_u32, le_u32, le_u32, le_u32, le_u32, le_u32).parse_next(input)?;
+
+        Ok(CompactDexHeader {
+            feature_flags,
+            debug_info_offsets_pos,
+            debug_info_offsets_table_offset,
+            debug_info_base,
+            owned_data_begin,
+            owned_data_end,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    fn make_header(magic: &[u8; 8]) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        data.extend_from_slice(magic); // magic
+        data.extend_from_slice(&0xdeadbeefu32.to_le_bytes()); // checksum
+        data.extend_from_slice(&[0x11; 20]); // signature
+        data.extend_from_slice(&0x1000u32.to_le_bytes()); // file_size
+        data.extend_from_slice(&0x70u32.to_le_bytes()); // header_size
+        data.extend_from_slice(&DexHeader::ENDIAN_CONSTANT.to_le_bytes()); // endian_tag
+
+        // link, map and all id sections
+        for value in 0..17u32 {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        data
+    }
+
+    #[test]
+    fn test_parse_valid_header() {
+        let data = make_header(b"dex\n035\0");
+        let mut input = &data[..];
+        let header = DexHeader::parse(&mut input).unwrap();
+
+        assert_eq!(header.checksum, 0xdeadbeef);
+        assert_eq!(header.signature, [0x11; 20]);
+        assert_eq!(header.file_size, 0x1000);
+        assert_eq!(header.header_size, DexHeader::SIZE as u32);
+        assert_eq!(header.endian_tag, DexHeader::ENDIAN_CONSTANT);
+        assert_eq!(header.string_ids_size, 3);
+        assert_eq!(header.data_off, 16);
+        assert_eq!(header.version(), Some(35));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_parse_compact_header() {
+        let data = make_header(b"cdex001\0");
+        let header = DexHeader::parse(&mut &data[..]).unwrap();
+
+        assert!(header.is_compact());
+        assert_eq!(header.version(), Some(1));
+    }
+
+    #[test]
+    fn test_parse_invalid_magic() {
+        let data = make_header(b"dey\n035\0");
+        let mut input = &data[..];
+
+        assert!(DexHeader::parse(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_parse_truncated_header() {
+        let data = make_header(b"dex\n039\0");
+        let mut input = &data[..DexHeader::SIZE - 1];
+
+        assert!(DexHeader::parse(&mut input).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use core::fmt;
+
+/// Restriction of the hidden api, the lower 3 bits of the flags.
+///
+/// See: <https://cs.android.com/android/platform/superproject/main/+/main:art/libartbase/base/hiddenapi_flags.h>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum HiddenApiRestriction {
+    /// Public SDK api, no restrictions (`whitelist` in older releases).
+    Sdk,
+
+    /// Non-SDK api which can be used by apps (`greylist`).
+    Unsupported,
+
+    /// Non-SDK api which can't be used by apps (`blacklist`).
+    Blocked,
+
+    /// Allowed for apps targeting Android O and below.
+    MaxTargetO,
+
+    /// Allowed for apps targeting Android P and below.
+    MaxTargetP,
+
+    /// Allowed for apps targeting Android Q and below.
+    MaxTargetQ,
+
+    /// Allowed for apps targeting Android R and below.
+    MaxTargetR,
+
+    /// Allowed for apps targeting Android S and below.
+    MaxTargetS,
+}
+
+impl HiddenApiRestriction {
+    /// Parses the name used in `hiddenapi-flags.csv`, old names are accepted too.
+    pub fn from_name(name: &str) -> Option<HiddenApiRestriction> {
+        Some(match name {
+            "sdk" | "whitelist" | "public-api" | "system-api" | "test-api" => Self::Sdk,
+            "unsupported" | "greylist" => Self::Unsupported,
+            "blocked" | "blacklist" => Self::Blocked,
+            "max-target-o" | "greylist-max-o" => Self::MaxTargetO,
+            "max-target-p" | "greylist-max-p" => Self::MaxTargetP,
+            "max-target-q" | "greylist-max-q" => Self::MaxTargetQ,
+            "max-target-r" | "greylist-max-r" => Self::MaxTargetR,
+            "max-target-s" | "greylist-max-s" => Self::MaxTargetS,
+            _ => return None,
+        })
+    }
+
+    /// Returns the name used in `hiddenapi-flags.csv`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sdk => "sdk",
+            Self::Unsupported => "unsupported",
+            Self::Blocked => "blocked",
+            Self::MaxTargetO => "max-target-o",
+            Self::MaxTargetP => "max-target-p",
+            Self::MaxTargetQ => "max-target-q",
+            Self::MaxTargetR => "max-target-r",
+            Self::MaxTargetS => "max-target-s",
+        }
+    }
+
+    /// Returns `true` for everything except the public SDK.
+    #[inline]
+    pub fn is_restricted(&self) -> bool {
+        *self != Self::Sdk
+    }
+}
+
+impl fmt::Display for HiddenApiRestriction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Raw flags of a class member from `hiddenapi_class_data_item`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HiddenApiFlags(pub u32);
+
+impl HiddenApiFlags {
+    const RESTRICTION_MASK: u32 = 0x7;
+
+    /// Member is part of the core platform api.
+    pub const CORE_PLATFORM_API: u32 = 1 << 3;
+
+    /// Member is part of the test api.
+    pub const TEST_API: u32 = 1 << 4;
+
+    /// Returns the restriction of the member.
+    pub fn restriction(&self) -> HiddenApiRestriction {
+        match self.0 & Self::RESTRICTION_MASK {
+            0 => HiddenApiRestriction::Sdk,
+            1 => HiddenApiRestriction::Unsupported,
+            2 => HiddenApiRestriction::Blocked,
+            3 => HiddenApiRestriction::MaxTargetO,
+            4 => HiddenApiRestriction::MaxTargetP,
+            5 => HiddenApiRestriction::MaxTargetQ,
+            6 => HiddenApiRestriction::MaxTargetR,
+            // the mask leaves only 3 bits
+            _ => HiddenApiRestriction::MaxTargetS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restriction() {
+        assert_eq!(
+            HiddenApiFlags(0x1).restriction(),
+            HiddenApiRestriction::Unsupported
+        );
+        assert_eq!(
+            HiddenApiFlags(0x2 | HiddenApiFlags::TEST_API).restriction(),
+            HiddenApiRestriction::Blocked
+        );
+    }
+
+    #[test]
+    fn test_restriction_names() {
+        for name in [
+            "sdk",
+            "unsupported",
+            "blocked",
+            "max-target-o",
+            "max-target-s",
+        ] {
+            assert_eq!(HiddenApiRestriction::from_name(name).unwrap().name(), name);
+        }
+        assert_eq!(
+            HiddenApiRestriction::from_name("greylist-max-p"),
+            Some(HiddenApiRestriction::MaxTargetP)
+        );
+        assert_eq!(HiddenApiRestriction::from_name("lo-prio"), None);
+    }
+}
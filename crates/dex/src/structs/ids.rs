@@ -0,0 +1,158 @@
+use winnow::binary::{le_u16, le_u32};
+use winnow::prelude::*;
+
+/// Value used in place of an index when there is no value (for example, no superclass).
+pub const NO_INDEX: u32 = 0xffffffff;
+
+/// Method prototype.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#proto-id-item>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProtoId {
+    /// Index into the string table for the short-form descriptor (`VIL`).
+    pub shorty_idx: u32,
+
+    /// Index into the type table for the return type.
+    pub return_type_idx: u32,
+
+    /// Offset to the list of parameter types, `0` if there are no parameters.
+    pub parameters_off: u32,
+}
+
+impl ProtoId {
+    pub(crate) const SIZE: usize = 12;
+
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<ProtoId> {
+        let (shorty_idx, return_type_idx, parameters_off) =
+            (le_u32, le_u32, le_u32).parse_next(input)?;
+
+        Ok(ProtoId {
+            shorty_idx,
+            return_type_idx,
+            parameters_off,
+        })
+    }
+}
+
+/// Reference to a field.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#field-id-item>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldId {
+    /// Index into the type table for the definer of this field.
+    pub class_idx: u16,
+
+    /// Index into the type table for the type of this field.
+    pub type_idx: u16,
+
+    /// Index into the string table for the name of this field.
+    pub name_idx: u32,
+}
+
+impl FieldId {
+    pub(crate) const SIZE: usize = 8;
+
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<FieldId> {
+        let (class_idx, type_idx, name_idx) = (le_u16, le_u16, le_u32).parse_next(input)?;
+
+        Ok(FieldId {
+            class_idx,
+            type_idx,
+            name_idx,
+        })
+    }
+}
+
+/// Reference to a method.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#method-id-item>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MethodId {
+    /// Index into the type table for the definer of this method.
+    pub class_idx: u16,
+
+    /// Index into the prototype table for this method.
+    pub proto_idx: u16,
+
+    /// Index into the string table for the name of this method.
+    pub name_idx: u32,
+}
+
+impl MethodId {
+    pub(crate) const SIZE: usize = 8;
+
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<MethodId> {
+        let (class_idx, proto_idx, name_idx) = (le_u16, le_u16, le_u32).parse_next(input)?;
+
+        Ok(MethodId {
+            class_idx,
+            proto_idx,
+            name_idx,
+        })
+    }
+}
+
+/// Class definition.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#class-def-item>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassDef {
+    /// Index into the type table for this class.
+    pub class_idx: u32,
+
+    /// Access flags (`public`, `final`, etc).
+    pub access_flags: u32,
+
+    /// Index into the type table for the superclass or [NO_INDEX].
+    pub superclass_idx: u32,
+
+    /// Offset to the list of interfaces, `0` if there are none.
+    pub interfaces_off: u32,
+
+    /// Index into the string table for the source file name or [NO_INDEX].
+    pub source_file_idx: u32,
+
+    /// Offset to the annotations structure, `0` if there are none.
+    pub annotations_off: u32,
+
+    /// Offset to the class data, `0` if there is no class data (for example, a marker interface).
+    pub class_data_off: u32,
+
+    /// Offset to the initial values for static fields, `0` if there are none.
+    pub static_values_off: u32,
+}
+
+impl ClassDef {
+    pub(crate) const SIZE: usize = 32;
+
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<ClassDef> {
+        let (
+            class_idx,
+            access_flags,
+            superclass_idx,
+            interfaces_off,
+            source_file_idx,
+            annotations_off,
+            class_data_off,
+            static_values_off,
+        ) = (
+            le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32, le_u32,
+        )
+            .parse_next(input)?;
+
+        Ok(ClassDef {
+            class_idx,
+            access_flags,
+            superclass_idx,
+            interfaces_off,
+            source_file_idx,
+            annotations_off,
+            class_data_off,
+            static_values_off,
+        })
+    }
+}
@@ -0,0 +1,83 @@
+use winnow::binary::{le_u16, le_u32};
+use winnow::prelude::*;
+
+/// Item of the `map_list`, describes one section of the file.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#map-item>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MapItem {
+    /// Type of the items, see `TYPE_*` constants.
+    pub type_: u16,
+
+    /// Number of items in the section.
+    pub size: u32,
+
+    /// Offset of the section from the start of the file.
+    pub offset: u32,
+}
+
+impl MapItem {
+    pub const TYPE_HEADER_ITEM: u16 = 0x0000;
+    pub const TYPE_STRING_ID_ITEM: u16 = 0x0001;
+    pub const TYPE_TYPE_ID_ITEM: u16 = 0x0002;
+    pub const TYPE_PROTO_ID_ITEM: u16 = 0x0003;
+    pub const TYPE_FIELD_ID_ITEM: u16 = 0x0004;
+    pub const TYPE_METHOD_ID_ITEM: u16 = 0x0005;
+    pub const TYPE_CLASS_DEF_ITEM: u16 = 0x0006;
+    pub const TYPE_CALL_SITE_ID_ITEM: u16 = 0x0007;
+    pub const TYPE_METHOD_HANDLE_ITEM: u16 = 0x0008;
+    pub const TYPE_MAP_LIST: u16 = 0x1000;
+    pub const TYPE_TYPE_LIST: u16 = 0x1001;
+    pub const TYPE_ANNOTATION_SET_REF_LIST: u16 = 0x1002;
+    pub const TYPE_ANNOTATION_SET_ITEM: u16 = 0x1003;
+    pub const TYPE_CLASS_DATA_ITEM: u16 = 0x2000;
+    pub const TYPE_CODE_ITEM: u16 = 0x2001;
+    pub const TYPE_STRING_DATA_ITEM: u16 = 0x2002;
+    pub const TYPE_DEBUG_INFO_ITEM: u16 = 0x2003;
+    pub const TYPE_ANNOTATION_ITEM: u16 = 0x2004;
+    pub const TYPE_ENCODED_ARRAY_ITEM: u16 = 0x2005;
+    pub const TYPE_ANNOTATIONS_DIRECTORY_ITEM: u16 = 0x2006;
+    pub const TYPE_HIDDENAPI_CLASS_DATA_ITEM: u16 = 0xf000;
+
+    pub(crate) const SIZE: usize = 12;
+
+    /// Returns the name of the item type as in the specification, e.g. `string_id_item`.
+    pub fn type_name(&self) -> &'static str {
+        match self.type_ {
+            Self::TYPE_HEADER_ITEM => "header_item",
+            Self::TYPE_STRING_ID_ITEM => "string_id_item",
+            Self::TYPE_TYPE_ID_ITEM => "type_id_item",
+            Self::TYPE_PROTO_ID_ITEM => "proto_id_item",
+            Self::TYPE_FIELD_ID_ITEM => "field_id_item",
+            Self::TYPE_METHOD_ID_ITEM => "method_id_item",
+            Self::TYPE_CLASS_DEF_ITEM => "class_def_item",
+            Self::TYPE_CALL_SITE_ID_ITEM => "call_site_id_item",
+            Self::TYPE_METHOD_HANDLE_ITEM => "method_handle_item",
+            Self::TYPE_MAP_LIST => "map_list",
+            Self::TYPE_TYPE_LIST => "type_list",
+            Self::TYPE_ANNOTATION_SET_REF_LIST => "annotation_set_ref_list",
+            Self::TYPE_ANNOTATION_SET_ITEM => "annotation_set_item",
+            Self::TYPE_CLASS_DATA_ITEM => "class_data_item",
+            Self::TYPE_CODE_ITEM => "code_item",
+            Self::TYPE_STRING_DATA_ITEM => "string_data_item",
+            Self::TYPE_DEBUG_INFO_ITEM => "debug_info_item",
+            Self::TYPE_ANNOTATION_ITEM => "annotation_item",
+            Self::TYPE_ENCODED_ARRAY_ITEM => "encoded_array_item",
+            Self::TYPE_ANNOTATIONS_DIRECTORY_ITEM => "annotations_directory_item",
+            Self::TYPE_HIDDENAPI_CLASS_DATA_ITEM => "hiddenapi_class_data_item",
+            _ => "unknown",
+        }
+    }
+
+    pub(crate) fn parse(input: &mut &[u8]) -> ModalResult<MapItem> {
+        // type, unused, size, offset
+        let (type_, _, size, offset) = (le_u16, le_u16, le_u32, le_u32).parse_next(input)?;
+
+        Ok(MapItem {
+            type_,
+            size,
+            offset,
+        })
+    }
+}
@@ -0,0 +1,15 @@
+pub(crate) mod class_data;
+pub(crate) mod code_item;
+pub(crate) mod header;
+pub(crate) mod hiddenapi;
+pub(crate) mod ids;
+pub(crate) mod map_list;
+pub(crate) mod mutf8;
+
+// just re-export models
+pub use class_data::{ClassData, EncodedField, EncodedMethod};
+pub use code_item::CodeItem;
+pub use header::{CompactDexHeader, DexHeader};
+pub use hiddenapi::{HiddenApiFlags, HiddenApiRestriction};
+pub use ids::{ClassDef, FieldId, MethodId, NO_INDEX, ProtoId};
+pub use map_list::MapItem;
@@ -0,0 +1,72 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Decodes a null-terminated "Modified UTF-8" string used by dex files.
+///
+/// Differences from the regular UTF-8: `\0` is encoded as two bytes and supplementary
+/// characters are encoded as surrogate pairs (three bytes each). Invalid sequences
+/// are replaced with `U+FFFD`, so the strings from broken files are still readable.
+///
+/// See: <https://source.android.com/docs/core/runtime/dex-format#mutf-8>
+pub(crate) fn decode(data: &[u8]) -> String {
+    let mut units: Vec<u16> = Vec::with_capacity(data.len());
+    let mut iter = data.iter().copied();
+
+    while let Some(a) = iter.next() {
+        let unit = match a {
+            0x00 => break,
+            0x01..=0x7f => a as u16,
+            0xc0..=0xdf => match iter.next() {
+                Some(b) if b & 0xc0 == 0x80 => ((a as u16 & 0x1f) << 6) | (b as u16 & 0x3f),
+                _ => 0xfffd,
+            },
+            0xe0..=0xef => match (iter.next(), iter.next()) {
+                (Some(b), Some(c)) if b & 0xc0 == 0x80 && c & 0xc0 == 0x80 => {
+                    ((a as u16 & 0x0f) << 12) | ((b as u16 & 0x3f) << 6) | (c as u16 & 0x3f)
+                }
+                _ => 0xfffd,
+            },
+            _ => 0xfffd,
+        };
+
+        units.push(unit);
+    }
+
+    // surrogate pairs are joined here, unpaired surrogates are replaced
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ascii() {
+        assert_eq!(
+            decode(b"Landroid/app/Activity;\0garbage"),
+            "Landroid/app/Activity;"
+        );
+        assert_eq!(decode(b"no terminator"), "no terminator");
+    }
+
+    #[test]
+    fn test_decode_embedded_null() {
+        assert_eq!(decode(b"a\xc0\x80b\0"), "a\0b");
+    }
+
+    #[test]
+    fn test_decode_multibyte() {
+        assert_eq!(decode("привет\0".as_bytes()), "привет");
+    }
+
+    #[test]
+    fn test_decode_surrogate_pair() {
+        // U+1F600 encoded as surrogate pair D83D DE00
+        assert_eq!(decode(b"\xed\xa0\xbd\xed\xb8\x80\0"), "\u{1f600}");
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(decode(b"a\xffb\xc3\0"), "a\u{fffd}b\u{fffd}");
+    }
+}
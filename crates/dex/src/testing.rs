@@ -0,0 +1,45 @@
+//! Dex files built by hand for the unit tests.
+
+use alloc::vec::Vec;
+
+use sha1::{Digest, Sha1};
+
+use crate::dex::{ADLER32_INITIAL, adler32};
+use crate::structs::{CompactDexHeader, DexHeader};
+
+/// Dex file without any items and with valid checksum and signature.
+pub(crate) fn empty_dex() -> Vec<u8> {
+    let mut data = Vec::new();
+
+    data.extend_from_slice(b"dex\n039\0");
+    data.extend_from_slice(&0u32.to_le_bytes()); // checksum
+    data.extend_from_slice(&[0; 20]); // signature
+    data.extend_from_slice(&(DexHeader::SIZE as u32).to_le_bytes()); // file_size
+    data.extend_from_slice(&(DexHeader::SIZE as u32).to_le_bytes()); // header_size
+    data.extend_from_slice(&DexHeader::ENDIAN_CONSTANT.to_le_bytes());
+    data.resize(DexHeader::SIZE, 0);
+
+    let signature: [u8; 20] = Sha1::digest(&data[32..]).into();
+    data[12..32].copy_from_slice(&signature);
+    let checksum = adler32(ADLER32_INITIAL, &data[12..]);
+    data[8..12].copy_from_slice(&checksum.to_le_bytes());
+
+    data
+}
+
+/// Compact dex file without any items, its data is `data_off` bytes after its start.
+pub(crate) fn compact_dex(data_off: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+
+    data.extend_from_slice(b"cdex001\0");
+    data.extend_from_slice(&0u32.to_le_bytes()); // checksum
+    data.extend_from_slice(&[0; 20]); // signature
+    data.extend_from_slice(&(CompactDexHeader::SIZE as u32).to_le_bytes()); // file_size
+    data.extend_from_slice(&(DexHeader::SIZE as u32).to_le_bytes()); // header_size
+    data.extend_from_slice(&DexHeader::ENDIAN_CONSTANT.to_le_bytes());
+    data.resize(DexHeader::SIZE - 4, 0);
+    data.extend_from_slice(&data_off.to_le_bytes());
+    data.resize(CompactDexHeader::SIZE, 0);
+
+    data
+}
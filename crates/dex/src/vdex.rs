@@ -0,0 +1,324 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use winnow::binary::le_u32;
+use winnow::error::ContextError;
+use winnow::prelude::*;
+
+use crate::dex::Dex;
+use crate::errors::DexError;
+use crate::structs::DexHeader;
+
+/// Parsed `.vdex` container produced by `dex2oat`.
+///
+/// Apps pulled from devices often have only the vdex next to the odex, it keeps the dex files
+/// of the app (compact dex on Android 9 and 10) together with the verifier dependencies.
+/// Since Android 12 the dex files usually stay in the apk and the vdex has none.
+///
+/// Supported versions: `006`, `010` (Android 8), `019` (Android 9), `021` (Android 10, 11)
+/// and `027` (Android 12+).
+///
+/// ```ignore
+/// let vdex = Vdex::new(&std::fs::read("base.vdex")?)?;
+/// for dex in vdex.dex_files() {
+///     println!("{} classes", dex.classes().len());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Vdex {
+    version: u32,
+    checksums: Vec<u32>,
+    dex_files: Vec<Dex>,
+}
+
+/// Kinds of the sections of the vdex since version `027`.
+const SECTION_CHECKSUM: u32 = 0;
+const SECTION_DEX_FILE: u32 = 1;
+
+impl Vdex {
+    /// Magic value at the start of the file.
+    pub const MAGIC: &'static [u8] = b"vdex";
+
+    /// Parses the container and all dex files in it.
+    pub fn new(data: &[u8]) -> Result<Vdex, DexError> {
+        let version = data
+            .strip_prefix(Self::MAGIC)
+            .and_then(|rest| rest.get(..4))
+            .filter(|version| version[3] == 0)
+            .and_then(|version| core::str::from_utf8(&version[..3]).ok()?.parse().ok())
+            .ok_or(DexError::HeaderError)?;
+
+        let (checksums, dex_files) = match version {
+            6 | 10 => {
+                // number of dex files, dex size, verifier deps size, quickening info size
+                let count = read_u32(data, 8)?;
+                let dex_size = read_u32(data, 12)?;
+                let checksums = read_checksums(data, 24, count)?;
+                let dex_begin = 24 + checksums.len() * 4;
+
+                (checksums, dex_section(data, dex_begin, dex_size, 0, 0)?)
+            }
+            19 | 21 => {
+                // dex section version, `000` if there is no dex section
+                let has_dex_section = data.get(8..11) != Some(b"000".as_slice());
+                let count = read_u32(data, 12)?;
+
+                // the dex section header follows: dex size, shared data size, quickening info size
+                let checksums_begin = if has_dex_section { 32 } else { 20 };
+                let checksums = read_checksums(data, checksums_begin, count)?;
+
+                if has_dex_section {
+                    let dex_size = read_u32(data, 20)?;
+                    let shared_data_size = read_u32(data, 24)?;
+                    let dex_begin = checksums_begin + checksums.len() * 4;
+
+                    // every dex file is prefixed with the offset of its quickening table
+                    let files = dex_section(data, dex_begin, dex_size, 4, shared_data_size)?;
+                    (checksums, files)
+                } else {
+                    (checksums, Vec::new())
+                }
+            }
+            27 => {
+                let sections = read_u32(data, 8)?;
+                let mut checksums = Vec::new();
+                let mut dex_files = Vec::new();
+
+                for index in 0..sections as usize {
+                    let header = 12 + index * 12;
+                    let (kind, offset, size) = (
+                        read_u32(data, header)?,
+                        read_u32(data, header + 4)?,
+                        read_u32(data, header + 8)?,
+                    );
+
+                    match kind {
+                        SECTION_CHECKSUM => {
+                            checksums = read_checksums(data, offset as usize, size / 4)?;
+                        }
+                        SECTION_DEX_FILE if size != 0 => {
+                            dex_files = dex_section(data, offset as usize, size, 0, 0)?;
+                        }
+                        // verifier dependencies and type lookup tables
+                        _ => {}
+                    }
+                }
+
+                (checksums, dex_files)
+            }
+            version => return Err(DexError::UnsupportedVdexVersion(version)),
+        };
+
+        Ok(Vdex {
+            version,
+            checksums,
+            dex_files,
+        })
+    }
+
+    /// Returns the version of the container: `27`.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the checksums of the original dex files, the runtime checks them against the apk.
+    #[inline]
+    pub fn checksums(&self) -> &[u32] {
+        &self.checksums
+    }
+
+    /// Returns the dex files in the order they are loaded.
+    #[inline]
+    pub fn dex_files(&self) -> &[Dex] {
+        &self.dex_files
+    }
+
+    /// Takes the dex files out of the container.
+    #[inline]
+    pub fn into_dex_files(self) -> Vec<Dex> {
+        self.dex_files
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, DexError> {
+    data.get(offset..)
+        .and_then(|mut input| le_u32::<_, ContextError>.parse_next(&mut input).ok())
+        .ok_or(DexError::TooSmallError)
+}
+
+fn read_checksums(data: &[u8], offset: usize, count: u32) -> Result<Vec<u32>, DexError> {
+    let error = || DexError::SectionOutOfBounds {
+        section: "vdex checksums",
+        offset: offset as u32,
+        count,
+    };
+
+    let end = (count as usize)
+        .checked_mul(4)
+        .and_then(|size| size.checked_add(offset))
+        .ok_or_else(error)?;
+
+    Ok(data
+        .get(offset..end)
+        .ok_or_else(error)?
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect())
+}
+
+/// Parses the dex files stored one after another, each is aligned to 4 bytes.
+///
+/// `prefix` bytes are skipped before every file. Compact dex files keep their data in the
+/// shared section right after the dex section, so they get everything up to its end.
+/// The section is copied once and all files are parsed from the same buffer.
+fn dex_section(
+    data: &[u8],
+    begin: usize,
+    size: u32,
+    prefix: usize,
+    shared_data_size: u32,
+) -> Result<Vec<Dex>, DexError> {
+    let error = || DexError::SectionOutOfBounds {
+        section: "vdex dex files",
+        offset: begin as u32,
+        count: size,
+    };
+
+    let end = begin.checked_add(size as usize).ok_or_else(error)?;
+    if end > data.len() {
+        return Err(error());
+    }
+    let shared_end = end
+        .saturating_add(shared_data_size as usize)
+        .min(data.len());
+    let buffer = Arc::new(data[begin..shared_end].to_vec());
+
+    let mut dex_files = Vec::new();
+    let mut offset = begin;
+    while offset + prefix < end {
+        let start = offset + prefix;
+        let header = DexHeader::parse(&mut &data[start..end]).map_err(|_| DexError::HeaderError)?;
+
+        let file_end = if header.is_compact() {
+            shared_end
+        } else {
+            start
+                .checked_add(header.file_size as usize)
+                .filter(|&file_end| file_end <= end)
+                .ok_or_else(error)?
+        };
+        dex_files.push(Dex::from_shared(
+            Arc::clone(&buffer),
+            start - begin..file_end - begin,
+        )?);
+
+        // the size of the file itself, the shared data is not a part of it
+        let next = start.saturating_add(header.file_size as usize);
+        if next <= offset {
+            return Err(error());
+        }
+        offset = next.next_multiple_of(4);
+    }
+
+    Ok(dex_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{compact_dex, empty_dex};
+
+    #[test]
+    fn test_parse_vdex_027() {
+        let dex = empty_dex();
+
+        let mut data = Vec::new();
+        data.extend_from_slice(b"vdex027\0");
+        data.extend_from_slice(&2u32.to_le_bytes()); // number of sections
+
+        // checksum section, then two dex files
+        for value in [SECTION_CHECKSUM, 36, 8, SECTION_DEX_FILE, 44] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&(dex.len() as u32 * 2).to_le_bytes());
+        data.extend_from_slice(&0x1111u32.to_le_bytes());
+        data.extend_from_slice(&0x2222u32.to_le_bytes());
+        data.extend_from_slice(&dex);
+        data.extend_from_slice(&dex);
+
+        let vdex = Vdex::new(&data).unwrap();
+
+        assert_eq!(vdex.version(), 27);
+        assert_eq!(vdex.checksums(), &[0x1111, 0x2222]);
+        assert_eq!(vdex.dex_files().len(), 2);
+        assert!(matches!(Dex::new(data), Err(DexError::VdexContainer)));
+    }
+
+    #[test]
+    fn test_parse_vdex_compact_dex() {
+        let shared_data = b"shared";
+
+        for version in [b"vdex019\0", b"vdex021\0"] {
+            let mut data = Vec::new();
+            data.extend_from_slice(version);
+            data.extend_from_slice(b"002\0"); // dex section version
+            data.extend_from_slice(&2u32.to_le_bytes()); // number of dex files
+            data.extend_from_slice(&0u32.to_le_bytes()); // verifier deps size
+
+            // every file is prefixed with the offset of its quickening table
+            let file_size = 4 + compact_dex(0).len();
+            data.extend_from_slice(&(file_size as u32 * 2).to_le_bytes()); // dex size
+            data.extend_from_slice(&(shared_data.len() as u32).to_le_bytes());
+            data.extend_from_slice(&0u32.to_le_bytes()); // quickening info size
+            data.extend_from_slice(&0x1111u32.to_le_bytes());
+            data.extend_from_slice(&0x2222u32.to_le_bytes());
+
+            // the shared data follows the dex section, `data_off` is relative to each file
+            data.extend_from_slice(&0u32.to_le_bytes());
+            data.extend_from_slice(&compact_dex(file_size as u32 * 2 - 4));
+            data.extend_from_slice(&0u32.to_le_bytes());
+            data.extend_from_slice(&compact_dex(file_size as u32 - 4));
+            data.extend_from_slice(shared_data);
+
+            let vdex = Vdex::new(&data).unwrap();
+
+            assert_eq!(vdex.checksums(), &[0x1111, 0x2222]);
+            let [first, second] = vdex.dex_files() else {
+                panic!("expected two dex files");
+            };
+            assert!(first.is_compact() && second.is_compact());
+
+            fn shared(dex: &Dex) -> &[u8] {
+                &dex.as_bytes()[dex.header().data_off as usize..]
+            }
+            assert_eq!(shared(first), shared_data);
+            assert_eq!(shared(second), shared_data);
+            // both files are views into the same buffer
+            assert_eq!(shared(first).as_ptr(), shared(second).as_ptr());
+        }
+    }
+
+    #[test]
+    fn test_parse_vdex_021_without_dex() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"vdex021\0");
+        data.extend_from_slice(b"000\0"); // no dex section
+        data.extend_from_slice(&1u32.to_le_bytes()); // number of dex files
+        data.extend_from_slice(&0u32.to_le_bytes()); // verifier deps size
+        data.extend_from_slice(&0xabcdu32.to_le_bytes());
+
+        let vdex = Vdex::new(&data).unwrap();
+
+        assert_eq!(vdex.checksums(), &[0xabcd]);
+        assert!(vdex.dex_files().is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        assert!(matches!(
+            Vdex::new(b"vdex099\0"),
+            Err(DexError::UnsupportedVdexVersion(99))
+        ));
+    }
+}
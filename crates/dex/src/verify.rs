@@ -0,0 +1,140 @@
+use sha1::{Digest, Sha1};
+
+use crate::dex::Dex;
+
+/// Result of [Dex::verify]: header values against the recomputed ones.
+///
+/// `dx`, `d8` and `dexlayout` always write valid values, mismatches mean the file was patched
+/// after the build. The runtime checks the checksum only for files loaded from disk and never
+/// checks the signature, so packers and injectors often leave both stale.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DexVerification {
+    /// Adler-32 checksum from the header
+    pub expected_checksum: u32,
+
+    /// Recomputed checksum, [None] if the header points outside of the file
+    pub actual_checksum: Option<u32>,
+
+    /// SHA-1 signature from the header
+    pub expected_signature: [u8; 20],
+
+    /// Recomputed signature, [None] if `file_size` is smaller than the header
+    /// and for compact dex files: they keep the signature of the original dex file
+    pub actual_signature: Option<[u8; 20]>,
+
+    /// `file_size` from the header
+    pub declared_size: u32,
+
+    /// Real size of the data
+    pub actual_size: usize,
+
+    /// Whether the file is a compact dex
+    pub compact: bool,
+}
+
+impl DexVerification {
+    /// Checks if the checksum matches the content.
+    #[inline]
+    pub fn is_checksum_valid(&self) -> bool {
+        self.actual_checksum == Some(self.expected_checksum)
+    }
+
+    /// Checks if the signature matches the content, always `true` for compact dex files.
+    #[inline]
+    pub fn is_signature_valid(&self) -> bool {
+        self.compact || self.actual_signature == Some(self.expected_signature)
+    }
+
+    /// Checks if `file_size` matches the data, data appended to the file is a mismatch too.
+    ///
+    /// Compact dex files from the vdex container are followed by the shared data,
+    /// so only the truncation is a mismatch for them.
+    #[inline]
+    pub fn is_size_valid(&self) -> bool {
+        let declared = self.declared_size as usize;
+        if self.compact {
+            declared <= self.actual_size
+        } else {
+            declared == self.actual_size
+        }
+    }
+
+    /// Checks if both the checksum and the signature are valid.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.is_checksum_valid() && self.is_signature_valid()
+    }
+}
+
+impl Dex {
+    /// Recomputes the adler32 checksum and the SHA-1 signature and compares them with the header.
+    ///
+    /// ```ignore
+    /// let verification = dex.verify();
+    /// if !verification.is_signature_valid() {
+    ///     println!("dex file was modified after the build");
+    /// }
+    /// ```
+    pub fn verify(&self) -> DexVerification {
+        let header = self.header();
+        let data = self.as_bytes();
+        let end = (header.file_size as usize).min(data.len());
+
+        let actual_signature = if header.is_compact() {
+            None
+        } else {
+            data.get(32..end).map(|data| Sha1::digest(data).into())
+        };
+
+        DexVerification {
+            expected_checksum: header.checksum,
+            actual_checksum: self.compute_checksum(),
+            expected_signature: header.signature,
+            actual_signature,
+            declared_size: header.file_size,
+            actual_size: data.len(),
+            compact: header.is_compact(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::empty_dex;
+
+    #[test]
+    fn test_verify_valid() {
+        let dex = Dex::new(empty_dex()).unwrap();
+        let verification = dex.verify();
+
+        assert!(verification.is_valid());
+        assert!(verification.is_size_valid());
+        assert!(dex.is_checksum_valid());
+    }
+
+    #[test]
+    fn test_verify_patched() {
+        let mut data = empty_dex();
+        // patch the padding of the header, the checksum and the signature are stale now
+        data[0x6f] = 1;
+
+        let verification = Dex::new(data).unwrap().verify();
+
+        assert!(!verification.is_checksum_valid());
+        assert!(!verification.is_signature_valid());
+    }
+
+    #[test]
+    fn test_verify_appended_data() {
+        let mut data = empty_dex();
+        data.extend_from_slice(b"payload");
+
+        let verification = Dex::new(data).unwrap().verify();
+
+        // the checksum covers only `file_size` bytes
+        assert!(verification.is_valid());
+        assert!(!verification.is_size_valid());
+    }
+}
@@ -1,5 +1,14 @@
 //! A small library that allows you to build an XML DOM tree.
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
 /// Represents a single XML attribute, including an optional namespace prefix.
 ///
 /// This struct models attributes like `id="123"` or `android:name="..."`.
@@ -52,10 +61,16 @@ impl Attribute {
     pub fn value(&self) -> &str {
         &self.value
     }
+
+    /// Returns the namespace prefix of the attribute
+    #[inline(always)]
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
 }
 
-impl std::fmt::Display for Attribute {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Attribute {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if let Some(prefix) = &self.prefix {
             write!(f, "{}:{}=\"{}\"", prefix, self.name, self.value)
         } else {
@@ -256,9 +271,9 @@ impl Element {
 
     pub(crate) fn fmt_with_indent(
         &self,
-        f: &mut std::fmt::Formatter<'_>,
+        f: &mut core::fmt::Formatter<'_>,
         indent: usize,
-    ) -> std::fmt::Result {
+    ) -> core::fmt::Result {
         let indent_str = "  ".repeat(indent);
 
         write!(f, "{}<{}", indent_str, self.name)?;
@@ -296,8 +311,8 @@ impl Element {
     }
 }
 
-impl std::fmt::Display for Element {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Element {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         // default xml header
         writeln!(f, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
 
@@ -307,7 +322,7 @@ impl std::fmt::Display for Element {
 }
 
 pub struct Descendants<'a> {
-    stack: Vec<std::slice::Iter<'a, Element>>,
+    stack: Vec<core::slice::Iter<'a, Element>>,
 }
 
 impl<'a> Descendants<'a> {
@@ -0,0 +1,85 @@
+//! Anomalies of the zip container.
+//!
+//! They don't prevent unpacking, but the extra data is not covered by the usual
+//! analysis of the archive entries and may hide payloads or configs.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::FileType;
+
+/// A single anomaly found in the zip container.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ZipAnomaly {
+    /// There is data before the first local header, for example a self-extracting stub.
+    PrependedData {
+        /// Size of the data in bytes.
+        size: usize,
+    },
+
+    /// The archive has a non-empty comment in the End of Central Directory.
+    ArchiveComment {
+        /// Size of the comment in bytes.
+        size: usize,
+    },
+
+    /// The file contains other complete zip archives besides the one used by Android,
+    /// see [crate::ZipEntry::secondary_archives].
+    SecondaryArchives {
+        /// Number of the other archives.
+        count: usize,
+    },
+
+    /// The public key of the v2/v3 signer doesn't match its certificate,
+    /// see [crate::CertificateInfo::has_public_key_mismatch].
+    PublicKeyMismatch {
+        /// Name of the signature scheme.
+        scheme: String,
+
+        /// SHA-256 fingerprint of the certificate.
+        certificate: String,
+    },
+
+    /// The content of the entry doesn't match its extension, for example
+    /// a native library named `.png`, see [FileType::matches_extension].
+    ExtensionMismatch {
+        /// Name of the entry.
+        name: String,
+
+        /// Type detected from the content.
+        file_type: FileType,
+    },
+}
+
+impl fmt::Display for ZipAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ZipAnomaly::PrependedData { size } => {
+                write!(f, "{size} bytes of data before the first local header")
+            }
+            ZipAnomaly::ArchiveComment { size } => {
+                write!(f, "archive comment of {size} bytes")
+            }
+            ZipAnomaly::SecondaryArchives { count } => {
+                write!(
+                    f,
+                    "{count} more zip archive(s) besides the one used by android"
+                )
+            }
+            ZipAnomaly::PublicKeyMismatch {
+                scheme,
+                certificate,
+            } => {
+                write!(
+                    f,
+                    "{scheme} signer public key doesn't match certificate {certificate}"
+                )
+            }
+            ZipAnomaly::ExtensionMismatch { name, file_type } => {
+                write!(f, "{name} contains {file_type} data")
+            }
+        }
+    }
+}
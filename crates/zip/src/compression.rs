@@ -1,7 +1,9 @@
 //! Possible types of compression.
 
+use serde::{Deserialize, Serialize};
+
 /// Represents the type of compression used for a file in a ZIP archive.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum FileCompressionType {
     /// The file is stored without compression.
     Stored,
@@ -15,3 +17,16 @@ pub enum FileCompressionType {
     /// The file appears tampered but is actually compressed with `Deflate`.
     DeflatedTampered,
 }
+
+impl FileCompressionType {
+    /// Checks whether the local header declares a compression method that differs from the real one.
+    ///
+    /// Malware uses it to break the parsers that trust the header, Android ignores the method.
+    #[inline]
+    pub fn is_tampered(&self) -> bool {
+        matches!(
+            self,
+            FileCompressionType::StoredTampered | FileCompressionType::DeflatedTampered
+        )
+    }
+}
@@ -0,0 +1,74 @@
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+/// Storage of the archive data: a buffer in memory or a memory-mapped file.
+///
+/// Mapped files are read by the OS on access, so only the central directory and
+/// the entries that are actually read end up in memory, which matters for multi-GB xapk bundles.
+/// Mapping is opt-in through the unsafe [ZipData::map], archives are read into memory by default.
+#[derive(Debug)]
+pub enum ZipData {
+    /// Data owned by the archive
+    Owned(Vec<u8>),
+
+    /// Memory-mapped file
+    Mapped(Mmap),
+}
+
+impl ZipData {
+    /// Maps the file into memory.
+    ///
+    /// Fails for files that can't be mapped (pipes, character devices) and empty files,
+    /// the caller is expected to fall back to reading them.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the data is alive, otherwise
+    /// the parsed archive sees the bytes change under it and reads past the end of the mapping
+    /// raise `SIGBUS`. Use it only for files nothing else writes to.
+    pub unsafe fn map<P: AsRef<Path>>(path: P) -> io::Result<ZipData> {
+        let file = File::open(path)?;
+
+        // SAFETY: the caller guarantees the file is not modified while it is mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        Ok(ZipData::Mapped(mmap))
+    }
+
+    /// Returns the data as an owned buffer, mapped files are copied.
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            ZipData::Owned(data) => data,
+            ZipData::Mapped(mmap) => mmap.to_vec(),
+        }
+    }
+
+    /// Whether the data is a memory-mapped file.
+    #[inline]
+    pub fn is_mapped(&self) -> bool {
+        matches!(self, ZipData::Mapped(_))
+    }
+}
+
+impl Deref for ZipData {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        match self {
+            ZipData::Owned(data) => data,
+            ZipData::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+impl From<Vec<u8>> for ZipData {
+    #[inline]
+    fn from(data: Vec<u8>) -> Self {
+        ZipData::Owned(data)
+    }
+}
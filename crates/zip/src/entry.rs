@@ -1,14 +1,16 @@
 //! Describes a `zip` archive
 
+use std::borrow::Cow;
 use std::fmt::Write;
-use std::sync::Arc;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
 use ahash::AHashMap;
 use cms::cert::CertificateChoices;
 use cms::content_info::ContentInfo;
-use cms::signed_data::SignedData;
-use flate2::{Decompress, FlushDecompress, Status};
-use log::warn;
+use cms::signed_data::{SignedData, SignerIdentifier};
+use flate2::{Crc, Decompress, FlushDecompress, Status};
 use md5::{Digest, Md5};
 use sha1::Sha1;
 use sha2::Sha256;
@@ -18,37 +20,68 @@ use winnow::error::ContextError;
 use winnow::prelude::*;
 use winnow::token::take;
 use x509_cert::Certificate;
+use x509_cert::der::asn1::{ObjectIdentifier, PrintableStringRef, UintRef};
 use x509_cert::der::oid::db::DB;
-use x509_cert::der::{Decode, Encode};
-
-use crate::signature::{CertificateInfo, Signature};
-use crate::structs::{CentralDirectory, EndOfCentralDirectory, LocalFileHeader};
-use crate::{CertificateError, FileCompressionType, ZipError};
+use x509_cert::der::oid::db::{rfc4519, rfc5912, rfc8410};
+use x509_cert::der::{Decode, Encode, Reader, SliceReader};
+use x509_cert::name::Name;
+use x509_cert::spki::SubjectPublicKeyInfoOwned;
+
+use crate::integrity::{DigestCheck, StructureIssue, content_digest};
+use crate::jar::{JAR_MANIFEST_NAME, JarManifest, V1Verification, verify_signature_file};
+use crate::logging::warn;
+use crate::signature::{
+    CertificateInfo, ContentDigest, LineageNode, Signature, SignatureAlgorithm,
+};
+use crate::structs::{
+    CentralDirectory, CentralDirectoryEntry, EndOfCentralDirectory, LocalFileHeader,
+};
+use crate::{
+    CertificateError, EntryHashes, EntryInfo, FileCompressionType, FileType, FrostingInfo,
+    HashAlgorithm, ZipAnomaly, ZipData, ZipError,
+};
 
 /// Represents a parsed ZIP archive.
 #[derive(Debug)]
 pub struct ZipEntry {
-    /// Owned zip data
-    input: Vec<u8>,
+    /// Zip data, owned or memory-mapped
+    input: ZipData,
 
     /// EOCD structure
     eocd: EndOfCentralDirectory,
 
+    /// Offset of the EOCD from the start of the input
+    eocd_offset: usize,
+
     /// Central directory structure
     central_directory: CentralDirectory,
 
     /// Information about local headers
     local_headers: AHashMap<Arc<str>, LocalFileHeader>,
+
+    /// Size of the data before the first local header
+    prepended_size: usize,
+
+    /// Other archives in the data, see [ZipEntry::secondary_archives],
+    /// found on first use since the whole data is scanned
+    secondary_archives: OnceLock<Vec<Range<usize>>>,
 }
 
 /// Implementation of basic methods
 impl ZipEntry {
+    const LOCAL_HEADER_MAGIC: &[u8] = b"PK\x03\x04";
+
+    /// Maximum compression ratio of deflate, the declared uncompressed size is never
+    /// trusted beyond it when allocating the output.
+    const MAX_DEFLATE_RATIO: usize = 1032;
+
     /// Creates a new `ZipEntry` from raw ZIP data.
     ///
     /// # Errors
     ///
     /// Returns a [ZipError] if:
-    /// - The input does not start with a valid ZIP signature [ZipError::InvalidHeader];
+    /// - The input does not start with a valid ZIP signature and the archive can't be found
+    ///   from the end [ZipError::InvalidHeader];
     /// - The End of Central Directory cannot be found [ZipError::NotFoundEOCD];
     /// - Parsing of the EOCD or central directory fails [ZipError::ParseError].
     ///
@@ -60,19 +93,85 @@ impl ZipEntry {
     /// let zip = ZipEntry::new(data).expect("failed to parse ZIP archive");
     /// ```
     pub fn new(input: Vec<u8>) -> Result<ZipEntry, ZipError> {
-        // perform basic sanity check
-        if !input.starts_with(b"PK\x03\x04") {
-            return Err(ZipError::InvalidHeader);
-        }
+        Self::from_data(ZipData::Owned(input))
+    }
 
-        let eocd_offset =
-            EndOfCentralDirectory::find_eocd(&input, 4096).ok_or(ZipError::NotFoundEOCD)?;
+    /// Reads the archive file into memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// let zip = ZipEntry::open("bundle.xapk").expect("failed to open ZIP archive");
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<ZipEntry, ZipError> {
+        Self::from_data(ZipData::Owned(std::fs::read(path)?))
+    }
+
+    /// Opens the archive as a memory-mapped file, see [ZipData].
+    ///
+    /// Files that can't be mapped are read into memory.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified while the archive is alive, see [ZipData::map].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// // SAFETY: nothing writes to the bundle while it is analyzed
+    /// let zip = unsafe { ZipEntry::open_mmap("bundle.xapk") }.expect("failed to open ZIP archive");
+    /// ```
+    pub unsafe fn open_mmap<P: AsRef<Path>>(path: P) -> Result<ZipEntry, ZipError> {
+        let path = path.as_ref();
+        // SAFETY: forwarded to the caller
+        let data = match unsafe { ZipData::map(path) } {
+            Ok(data) => data,
+            Err(_) => ZipData::Owned(std::fs::read(path)?),
+        };
+
+        Self::from_data(data)
+    }
+
+    /// Creates a new `ZipEntry` from owned or memory-mapped data, see [ZipEntry::new].
+    pub fn from_data(input: ZipData) -> Result<ZipEntry, ZipError> {
+        // perform basic sanity check, data before the first local header is allowed,
+        // but then the archive must be found from the end
+        let has_header = input.starts_with(Self::LOCAL_HEADER_MAGIC);
+        let not_found = |error| {
+            if has_header {
+                error
+            } else {
+                ZipError::InvalidHeader
+            }
+        };
+
+        let eocd_offset = EndOfCentralDirectory::find_eocd(&input, 4096)
+            .ok_or_else(|| not_found(ZipError::NotFoundEOCD))?;
 
         let eocd = EndOfCentralDirectory::parse(&mut &input[eocd_offset..])
-            .map_err(|_| ZipError::ParseError)?;
+            .map_err(|_| not_found(ZipError::ParseError))?;
 
         let central_directory =
-            CentralDirectory::parse(&input, &eocd).map_err(|_| ZipError::ParseError)?;
+            CentralDirectory::parse(&input, &eocd).map_err(|_| not_found(ZipError::ParseError))?;
+
+        // self-extracting stubs and hidden payloads, offsets in the central directory are absolute
+        let prepended_size = if has_header {
+            0
+        } else {
+            central_directory
+                .entries
+                .values()
+                .map(|entry| entry.local_header_offset as usize)
+                .min()
+                .filter(|&offset| {
+                    input
+                        .get(offset..)
+                        .is_some_and(|data| data.starts_with(Self::LOCAL_HEADER_MAGIC))
+                })
+                .ok_or(ZipError::InvalidHeader)?
+        };
 
         let local_headers = central_directory
             .entries
@@ -87,11 +186,172 @@ impl ZipEntry {
         Ok(ZipEntry {
             input,
             eocd,
+            eocd_offset,
             central_directory,
             local_headers,
+            prepended_size,
+            secondary_archives: OnceLock::new(),
         })
     }
 
+    /// Ranges of the secondary archives, see [ZipEntry::find_secondary_archives].
+    fn secondary_archive_ranges(&self) -> &[Range<usize>] {
+        self.secondary_archives.get_or_init(|| {
+            Self::find_secondary_archives(
+                &self.input,
+                self.eocd_offset..self.eocd_offset + self.eocd.size(),
+                &self.central_directory,
+            )
+        })
+    }
+
+    /// Looks for other complete archives in the data: an apk with appended zip payload,
+    /// or a payload concatenated in front of the apk.
+    ///
+    /// Archives stored inside the entries or the comment of the main one are not counted.
+    fn find_secondary_archives(
+        input: &[u8],
+        eocd: Range<usize>,
+        central_directory: &CentralDirectory,
+    ) -> Vec<Range<usize>> {
+        // entries may overlap, merged ranges sorted by start are binary-searched per candidate
+        let mut entries: Vec<Range<usize>> = central_directory
+            .entries
+            .values()
+            .map(|entry| {
+                let start = entry.local_header_offset as usize;
+                let end = start
+                    .saturating_add(30)
+                    .saturating_add(entry.file_name_length as usize)
+                    .saturating_add(entry.extra_field_length as usize)
+                    .saturating_add(entry.compressed_size as usize);
+
+                start..end
+            })
+            .collect();
+        entries.sort_unstable_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(entries.len());
+        for range in entries {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        let inside_entry = |offset: usize| {
+            let idx = merged.partition_point(|range| range.start <= offset);
+            idx > 0 && merged[idx - 1].contains(&offset)
+        };
+
+        EndOfCentralDirectory::find_all(input)
+            .filter(|offset| !eocd.contains(offset) && !inside_entry(*offset))
+            .filter_map(|offset| {
+                let eocd = EndOfCentralDirectory::parse(&mut &input[offset..]).ok()?;
+                let end = offset + eocd.size();
+
+                // offsets are relative to the start of the archive if it was simply concatenated,
+                // and absolute if they were adjusted (`zip -A`)
+                let cd_end = eocd.central_dir_offset as usize + eocd.central_dir_size as usize;
+                let relative = offset.checked_sub(cd_end);
+
+                [relative, Some(0)].into_iter().flatten().find_map(|start| {
+                    let cd_offset = start + eocd.central_dir_offset as usize;
+                    input
+                        .get(cd_offset..)
+                        .filter(|data| {
+                            data.starts_with(&CentralDirectoryEntry::MAGIC.to_le_bytes())
+                        })
+                        .map(|_| start..end)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the raw data the archive was created from.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.input
+    }
+
+    /// Consumes the archive and returns the raw data it was created from.
+    #[inline]
+    pub fn into_data(self) -> Vec<u8> {
+        self.input.into_vec()
+    }
+
+    /// Returns the data placed before the first local header.
+    ///
+    /// Usually empty, but may contain a self-extracting stub or a hidden payload.
+    #[inline]
+    pub fn prepended_data(&self) -> &[u8] {
+        &self.input[..self.prepended_size]
+    }
+
+    /// Parses other archives found in the data.
+    ///
+    /// Android uses the last End of Central Directory record, so the archive described
+    /// by it is the main one, and everything else (for example, a zip payload glued to the
+    /// apk) is returned here in the order of appearance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// # let zip = ZipEntry::new(std::fs::read("archive.zip").unwrap()).unwrap();
+    /// for archive in zip.secondary_archives() {
+    ///     println!("{:?}", archive.namelist().collect::<Vec<_>>());
+    /// }
+    /// ```
+    pub fn secondary_archives(&self) -> Vec<ZipEntry> {
+        self.secondary_archive_ranges()
+            .iter()
+            .filter_map(|range| ZipEntry::new(self.input[range.clone()].to_vec()).ok())
+            .collect()
+    }
+
+    /// Returns the comment from the End of Central Directory record.
+    #[inline]
+    pub fn archive_comment(&self) -> &[u8] {
+        &self.eocd.comment
+    }
+
+    /// Returns anomalies of the container: prepended data, archive comment, secondary archives.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// # let zip = ZipEntry::new(std::fs::read("archive.zip").unwrap()).unwrap();
+    /// for anomaly in zip.anomalies() {
+    ///     println!("{anomaly}");
+    /// }
+    /// ```
+    pub fn anomalies(&self) -> Vec<ZipAnomaly> {
+        let mut anomalies = Vec::new();
+
+        if self.prepended_size != 0 {
+            anomalies.push(ZipAnomaly::PrependedData {
+                size: self.prepended_size,
+            });
+        }
+
+        if !self.eocd.comment.is_empty() {
+            anomalies.push(ZipAnomaly::ArchiveComment {
+                size: self.eocd.comment.len(),
+            });
+        }
+
+        let secondary_archives = self.secondary_archive_ranges();
+        if !secondary_archives.is_empty() {
+            anomalies.push(ZipAnomaly::SecondaryArchives {
+                count: secondary_archives.len(),
+            });
+        }
+
+        anomalies
+    }
+
     /// Returns an iterator over the names of all files in the ZIP archive.
     ///
     /// # Examples
@@ -108,6 +368,77 @@ impl ZipEntry {
         self.central_directory.entries.keys().map(|x| x.as_ref())
     }
 
+    /// Returns names of all central directory records in their original order.
+    ///
+    /// Unlike [ZipEntry::namelist], duplicated names are listed as many times as they occur.
+    pub fn raw_namelist(&self) -> impl Iterator<Item = &str> + '_ {
+        self.central_directory.names.iter().map(|x| x.as_ref())
+    }
+
+    /// Returns the compressed and uncompressed sizes of a file as recorded in the central directory.
+    ///
+    /// The values are taken as is, so for tampered archives they may not match the real data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// # let zip_data = std::fs::read("archive.zip").unwrap();
+    /// # let zip = ZipEntry::new(zip_data).unwrap();
+    /// if let Some((compressed, uncompressed)) = zip.get_file_sizes("classes.dex") {
+    ///     println!("{compressed} -> {uncompressed}");
+    /// }
+    /// ```
+    pub fn get_file_sizes(&self, filename: &str) -> Option<(u64, u64)> {
+        self.central_directory
+            .entries
+            .get(filename)
+            .map(|entry| (entry.compressed_size as u64, entry.uncompressed_size as u64))
+    }
+
+    /// Returns the total size of the zip structures: local file headers, central directory
+    /// records and the End of Central Directory with the archive comment.
+    ///
+    /// Together with the compressed sizes of the files it gives the size of the archive
+    /// without the signing block and data hidden between the entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// # let zip = ZipEntry::new(std::fs::read("archive.zip").unwrap()).unwrap();
+    /// println!("{} bytes of headers", zip.get_headers_size());
+    /// ```
+    pub fn get_headers_size(&self) -> u64 {
+        let local_headers: usize = self.local_headers.values().map(|h| h.size()).sum();
+        let central_directory: usize = self
+            .central_directory
+            .entries
+            .values()
+            .map(|entry| entry.size())
+            .sum();
+
+        (local_headers + central_directory + self.eocd.size()) as u64
+    }
+
+    /// Returns the CRC-32 of the file declared in the central directory.
+    ///
+    /// The value is not verified, the data is not read.
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// # let zip = ZipEntry::new(std::fs::read("archive.zip").unwrap()).unwrap();
+    /// if let Some(crc32) = zip.get_file_crc32("classes.dex") {
+    ///     println!("{crc32:08x}");
+    /// }
+    /// ```
+    pub fn get_file_crc32(&self, filename: &str) -> Option<u32> {
+        self.central_directory
+            .entries
+            .get(filename)
+            .map(|entry| entry.crc32)
+    }
+
     /// Reads the contents of a file from the ZIP archive.
     ///
     /// This method handles both normally compressed files and tampered files
@@ -136,16 +467,85 @@ impl ZipEntry {
     /// }
     /// ```
     pub fn read(&self, filename: &str) -> Result<(Vec<u8>, FileCompressionType), ZipError> {
+        self.read_with_info(filename)
+            .map(|(data, info)| (data, info.compression))
+    }
+
+    /// Same as [ZipEntry::read], but also returns metadata of the entry that was used for reading.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use apk_info_zip::ZipEntry;
+    /// # let zip_data = std::fs::read("archive.zip").unwrap();
+    /// # let zip = ZipEntry::new(zip_data).unwrap();
+    /// let (data, info) = zip.read_with_info("classes.dex").expect("failed to read file");
+    /// println!("{} bytes at 0x{:x}, crc32 {:08x}", data.len(), info.data_offset, info.crc32);
+    /// ```
+    pub fn read_with_info(&self, filename: &str) -> Result<(Vec<u8>, EntryInfo), ZipError> {
+        self.read_limited(filename, None)
+            .map(|(data, info)| (data.into_owned(), info))
+    }
+
+    /// Same as [ZipEntry::read], but doesn't copy the data of stored entries.
+    ///
+    /// Stored entries are borrowed from the archive, only compressed entries are
+    /// decompressed into a new buffer. `resources.arsc` and native libraries are
+    /// usually stored, so this saves an allocation for the most of large files.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::borrow::Cow;
+    /// # use apk_info_zip::ZipEntry;
+    /// # let zip_data = std::fs::read("archive.zip").unwrap();
+    /// # let zip = ZipEntry::new(zip_data).unwrap();
+    /// let (data, _) = zip.read_borrowed("resources.arsc").expect("failed to read file");
+    /// if let Cow::Borrowed(_) = data {
+    ///     println!("no copy was made");
+    /// }
+    /// ```
+    pub fn read_borrowed(
+        &self,
+        filename: &str,
+    ) -> Result<(Cow<'_, [u8]>, FileCompressionType), ZipError> {
+        self.read_limited(filename, None)
+            .map(|(data, info)| (data, info.compression))
+    }
+
+    /// Returns metadata of the entry without reading the whole data.
+    ///
+    /// Only the first bytes are decompressed to fill [EntryInfo::file_type], so this is
+    /// cheap enough to be called for every entry of the archive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use apk_info_zip::{FileType, ZipEntry};
+    /// # let zip_data = std::fs::read("archive.zip").unwrap();
+    /// # let zip = ZipEntry::new(zip_data).unwrap();
+    /// let info = zip.entry_info("assets/data.bin").expect("failed to read file");
+    /// if info.file_type == Some(FileType::Dex) {
+    ///     println!("hidden dex file");
+    /// }
+    /// ```
+    pub fn entry_info(&self, filename: &str) -> Result<EntryInfo, ZipError> {
+        self.read_limited(filename, Some(FileType::HEADER_SIZE))
+            .map(|(_, info)| info)
+    }
+
+    /// Finds the headers of the entry and the sizes used for reading.
+    fn locate(&self, filename: &str) -> Result<EntryLocation<'_>, ZipError> {
         let local_header = self
             .local_headers
             .get(filename)
-            .ok_or(ZipError::FileNotFound)?;
+            .ok_or_else(|| ZipError::FileNotFound(filename.to_string()))?;
 
         let central_directory_entry = self
             .central_directory
             .entries
             .get(filename)
-            .ok_or(ZipError::FileNotFound)?;
+            .ok_or_else(|| ZipError::FileNotFound(filename.to_string()))?;
 
         let (compressed_size, uncompressed_size) =
             if local_header.compressed_size == 0 || local_header.uncompressed_size == 0 {
@@ -160,9 +560,60 @@ impl ZipEntry {
                 )
             };
 
-        let offset = central_directory_entry.local_header_offset as usize + local_header.size();
-        // helper to safely get a slice from input
-        let get_slice = |start: usize, end: usize| self.input.get(start..end).ok_or(ZipError::EOF);
+        Ok(EntryLocation {
+            local_header,
+            central_directory_entry,
+            offset: central_directory_entry.local_header_offset as usize + local_header.size(),
+            compressed_size,
+            uncompressed_size,
+        })
+    }
+
+    /// Safely gets a slice of the input.
+    fn get_slice(&self, filename: &str, start: usize, end: usize) -> Result<&[u8], ZipError> {
+        self.input.get(start..end).ok_or_else(|| ZipError::EOF {
+            entry: filename.to_string(),
+            offset: start,
+            size: end.saturating_sub(start),
+        })
+    }
+
+    /// Reads the entry, at most `limit` bytes of the uncompressed data if it is given.
+    ///
+    /// Data of stored entries is borrowed from the archive.
+    fn read_limited(
+        &self,
+        filename: &str,
+        limit: Option<usize>,
+    ) -> Result<(Cow<'_, [u8]>, EntryInfo), ZipError> {
+        let EntryLocation {
+            local_header,
+            central_directory_entry,
+            offset,
+            compressed_size,
+            uncompressed_size,
+        } = self.locate(filename)?;
+        let get_slice = |start: usize, end: usize| self.get_slice(filename, start, end);
+
+        let output_size = limit.map_or(uncompressed_size, |limit| limit.min(uncompressed_size));
+        let flush = match limit {
+            Some(_) => FlushDecompress::None,
+            None => FlushDecompress::Finish,
+        };
+
+        let info = |compression, data: &[u8]| EntryInfo {
+            name: filename.to_string(),
+            compression,
+            central_compression_method: central_directory_entry.compression_method,
+            local_compression_method: local_header.compression_method,
+            flags: central_directory_entry.general_purpose,
+            crc32: central_directory_entry.crc32,
+            compressed_size: compressed_size as u64,
+            uncompressed_size: uncompressed_size as u64,
+            local_header_offset: central_directory_entry.local_header_offset as u64,
+            data_offset: offset as u64,
+            file_type: FileType::detect(data),
+        };
 
         match (
             local_header.compression_method,
@@ -170,51 +621,63 @@ impl ZipEntry {
         ) {
             (0, _) => {
                 // stored (no compression)
-                let slice = get_slice(offset, offset + uncompressed_size)?;
-                Ok((slice.to_vec(), FileCompressionType::Stored))
+                let slice = get_slice(offset, offset + output_size)?;
+                let info = info(FileCompressionType::Stored, slice);
+                Ok((Cow::Borrowed(slice), info))
             }
             (8, _) => {
                 // deflate default
                 let compressed_data = get_slice(offset, offset + compressed_size)?;
-                let mut uncompressed_data = Vec::with_capacity(uncompressed_size);
+                let mut uncompressed_data = Vec::with_capacity(
+                    output_size.min(
+                        compressed_data
+                            .len()
+                            .saturating_mul(Self::MAX_DEFLATE_RATIO),
+                    ),
+                );
 
                 Decompress::new(false)
-                    .decompress_vec(
-                        compressed_data,
-                        &mut uncompressed_data,
-                        FlushDecompress::Finish,
-                    )
-                    .map_err(|_| ZipError::DecompressionError)?;
-
-                Ok((uncompressed_data, FileCompressionType::Deflated))
+                    .decompress_vec(compressed_data, &mut uncompressed_data, flush)
+                    .map_err(|_| ZipError::DecompressionError(filename.to_string()))?;
+
+                let info = info(FileCompressionType::Deflated, &uncompressed_data);
+                Ok((Cow::Owned(uncompressed_data), info))
             }
             (_, true) => {
                 // stored tampered
-                let slice = get_slice(offset, offset + uncompressed_size)?;
-                Ok((slice.to_vec(), FileCompressionType::StoredTampered))
+                let slice = get_slice(offset, offset + output_size)?;
+                let info = info(FileCompressionType::StoredTampered, slice);
+                Ok((Cow::Borrowed(slice), info))
             }
             (_, false) => {
                 // deflate tampered
                 let compressed_data = get_slice(offset, offset + compressed_size)?;
-                let mut uncompressed_data = Vec::with_capacity(uncompressed_size);
+                let mut uncompressed_data = Vec::with_capacity(
+                    output_size.min(
+                        compressed_data
+                            .len()
+                            .saturating_mul(Self::MAX_DEFLATE_RATIO),
+                    ),
+                );
                 let mut decompressor = Decompress::new(false);
 
-                let status = decompressor.decompress_vec(
-                    compressed_data,
-                    &mut uncompressed_data,
-                    FlushDecompress::Finish,
-                );
+                let status =
+                    decompressor.decompress_vec(compressed_data, &mut uncompressed_data, flush);
 
-                // check if decompression was actually successfull
-                let is_valid = decompressor.total_in() == compressed_data.len() as u64;
+                // check if decompression was actually successfull,
+                // the whole input is consumed only when the output isn't limited
+                let is_valid =
+                    limit.is_some() || decompressor.total_in() == compressed_data.len() as u64;
                 match status {
                     Ok(Status::Ok) | Ok(Status::StreamEnd) if is_valid => {
-                        Ok((uncompressed_data, FileCompressionType::DeflatedTampered))
+                        let info = info(FileCompressionType::DeflatedTampered, &uncompressed_data);
+                        Ok((Cow::Owned(uncompressed_data), info))
                     }
                     _ => {
                         // fallback to stored tampered
-                        let slice = get_slice(offset, offset + uncompressed_size)?;
-                        Ok((slice.to_vec(), FileCompressionType::StoredTampered))
+                        let slice = get_slice(offset, offset + output_size)?;
+                        let info = info(FileCompressionType::StoredTampered, slice);
+                        Ok((Cow::Borrowed(slice), info))
                     }
                 }
             }
@@ -222,6 +685,241 @@ impl ZipEntry {
     }
 }
 
+/// Headers of the entry and where its data is.
+struct EntryLocation<'a> {
+    local_header: &'a LocalFileHeader,
+    central_directory_entry: &'a CentralDirectoryEntry,
+
+    /// Offset of the data from the start of the input
+    offset: usize,
+
+    compressed_size: usize,
+    uncompressed_size: usize,
+}
+
+/// Implementation for hashing
+///
+/// The data is hashed as it is decompressed, the whole file is never kept in memory.
+impl ZipEntry {
+    /// Size of the buffer the compressed entries are decompressed into while hashing.
+    const HASH_CHUNK_SIZE: usize = 64 * 1024;
+
+    /// Computes the digest of the uncompressed data of the file, lowercase hex.
+    ///
+    /// The data is the same as returned by [ZipEntry::read], tampered entries included.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use apk_info_zip::{HashAlgorithm, ZipEntry};
+    /// # let zip = ZipEntry::new(std::fs::read("archive.zip").unwrap()).unwrap();
+    /// let sha256 = zip.hash_entry("classes.dex", HashAlgorithm::Sha256).expect("failed to read file");
+    /// println!("{sha256}");
+    /// ```
+    pub fn hash_entry(&self, filename: &str, algorithm: HashAlgorithm) -> Result<String, ZipError> {
+        match algorithm {
+            HashAlgorithm::Md5 => self.digest_entry::<Md5>(filename),
+            HashAlgorithm::Sha1 => self.digest_entry::<Sha1>(filename),
+            HashAlgorithm::Sha256 => self.digest_entry::<Sha256>(filename),
+        }
+    }
+
+    /// Computes the digest of the whole archive as it was given, lowercase hex.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use apk_info_zip::{HashAlgorithm, ZipEntry};
+    /// # let zip = ZipEntry::new(std::fs::read("archive.zip").unwrap()).unwrap();
+    /// println!("{}", zip.hash(HashAlgorithm::Sha256));
+    /// ```
+    pub fn hash(&self, algorithm: HashAlgorithm) -> String {
+        match algorithm {
+            HashAlgorithm::Md5 => hex_digest::<Md5>(&self.input),
+            HashAlgorithm::Sha1 => hex_digest::<Sha1>(&self.input),
+            HashAlgorithm::Sha256 => hex_digest::<Sha256>(&self.input),
+        }
+    }
+
+    /// Same as [ZipEntry::hash_entry], but computes all digests in one pass over the data.
+    pub fn hash_entry_all(&self, filename: &str) -> Result<EntryHashes, ZipError> {
+        let (mut md5, mut sha1, mut sha256) = (Md5::new(), Sha1::new(), Sha256::new());
+        let size = self.for_each_chunk(filename, &mut |chunk| {
+            md5.update(chunk);
+            sha1.update(chunk);
+            sha256.update(chunk);
+        })?;
+
+        Ok(EntryHashes {
+            name: filename.to_string(),
+            size,
+            md5: to_hex(&md5.finalize()),
+            sha1: to_hex(&sha1.finalize()),
+            sha256: to_hex(&sha256.finalize()),
+        })
+    }
+
+    fn digest_entry<D: Digest>(&self, filename: &str) -> Result<String, ZipError> {
+        let mut hasher = D::new();
+        self.for_each_chunk(filename, &mut |chunk| hasher.update(chunk))?;
+        Ok(to_hex(&hasher.finalize()))
+    }
+
+    /// Passes the uncompressed data of the entry to `f` in chunks, returns its size.
+    ///
+    /// Follows the same rules as [ZipEntry::read_limited] for tampered entries.
+    fn for_each_chunk(&self, filename: &str, f: &mut dyn FnMut(&[u8])) -> Result<u64, ZipError> {
+        let location = self.locate(filename)?;
+        let (offset, compressed_size, uncompressed_size) = (
+            location.offset,
+            location.compressed_size,
+            location.uncompressed_size,
+        );
+
+        let stored = |f: &mut dyn FnMut(&[u8])| {
+            let slice = self.get_slice(filename, offset, offset + uncompressed_size)?;
+            f(slice);
+            Ok(slice.len() as u64)
+        };
+        let max_size =
+            uncompressed_size.min(compressed_size.saturating_mul(Self::MAX_DEFLATE_RATIO));
+
+        match (
+            location.local_header.compression_method,
+            compressed_size == uncompressed_size,
+        ) {
+            (0, _) | (_, true) => stored(f),
+            (8, _) => {
+                let compressed_data = self.get_slice(filename, offset, offset + compressed_size)?;
+                inflate_chunks(compressed_data, max_size, f)
+                    .map(|(size, _)| size)
+                    .map_err(|_| ZipError::DecompressionError(filename.to_string()))
+            }
+            (_, false) => {
+                // the data is deflated only if the whole input is consumed,
+                // checked before anything is passed to `f`
+                let compressed_data = self.get_slice(filename, offset, offset + compressed_size)?;
+                match inflate_chunks(compressed_data, max_size, &mut |_| {}) {
+                    Ok((_, true)) => inflate_chunks(compressed_data, max_size, f)
+                        .map(|(size, _)| size)
+                        .map_err(|_| ZipError::DecompressionError(filename.to_string())),
+                    _ => stored(f),
+                }
+            }
+        }
+    }
+}
+
+/// Decompresses the raw deflate stream into a fixed buffer, passing every chunk to `f`.
+///
+/// Returns the size of the output and whether the whole input was consumed.
+fn inflate_chunks(
+    input: &[u8],
+    max_size: usize,
+    f: &mut dyn FnMut(&[u8]),
+) -> Result<(u64, bool), flate2::DecompressError> {
+    let mut decompressor = Decompress::new(false);
+    let mut buffer = vec![0; ZipEntry::HASH_CHUNK_SIZE.min(max_size.max(1))];
+
+    loop {
+        let (total_in, total_out) = (
+            decompressor.total_in() as usize,
+            decompressor.total_out() as usize,
+        );
+        let remaining = max_size - total_out;
+        if remaining == 0 {
+            break;
+        }
+
+        let output = &mut buffer[..remaining.min(ZipEntry::HASH_CHUNK_SIZE)];
+        let status = decompressor.decompress(&input[total_in..], output, FlushDecompress::None)?;
+
+        let produced = decompressor.total_out() as usize - total_out;
+        f(&output[..produced]);
+
+        // truncated streams stop making progress
+        if status == Status::StreamEnd
+            || (produced == 0 && decompressor.total_in() as usize == total_in)
+        {
+            break;
+        }
+    }
+
+    Ok((
+        decompressor.total_out(),
+        decompressor.total_in() == input.len() as u64,
+    ))
+}
+
+/// Formats the digest as lowercase hex.
+pub(crate) fn to_hex(digest: &[u8]) -> String {
+    digest.iter().fold(String::new(), |mut out, x| {
+        _ = write!(out, "{x:02x}");
+        out
+    })
+}
+
+/// Signer of the v2+ signing schemes.
+#[derive(Default)]
+struct ParsedSigner {
+    certificates: Vec<CertificateInfo>,
+    lineage: Vec<LineageNode>,
+    algorithms: Vec<SignatureAlgorithm>,
+    digests: Vec<ContentDigest>,
+}
+
+impl ParsedSigner {
+    fn new(
+        certificates: Vec<CertificateInfo>,
+        lineage: Vec<LineageNode>,
+        signatures: &[(u32, &[u8])],
+        digests: &[(u32, &[u8])],
+    ) -> ParsedSigner {
+        ParsedSigner {
+            certificates,
+            lineage,
+            algorithms: signatures
+                .iter()
+                .map(|(id, _)| SignatureAlgorithm::from_id(*id))
+                .collect(),
+            digests: digests
+                .iter()
+                .map(|(id, digest)| ContentDigest {
+                    algorithm: SignatureAlgorithm::from_id(*id),
+                    digest: to_hex(digest),
+                })
+                .collect(),
+        }
+    }
+
+    /// Merges the signers of the block: certificates are flattened, algorithms and digests
+    /// are deduplicated.
+    ///
+    /// Signers for different sdk ranges usually share the lineage, the first one is taken.
+    fn merge(signers: Vec<ParsedSigner>) -> ParsedSigner {
+        let mut merged = ParsedSigner::default();
+
+        for signer in signers {
+            merged.certificates.extend(signer.certificates);
+            if merged.lineage.is_empty() {
+                merged.lineage = signer.lineage;
+            }
+            for algorithm in signer.algorithms {
+                if !merged.algorithms.contains(&algorithm) {
+                    merged.algorithms.push(algorithm);
+                }
+            }
+            for digest in signer.digests {
+                if !merged.digests.contains(&digest) {
+                    merged.digests.push(digest);
+                }
+            }
+        }
+
+        merged
+    }
+}
+
 /// Implementation for certificate parsing
 ///
 /// Very cool research about signature blocks: <https://goa2023.nullcon.net/doc/goa-2023/Android-SigMorph-Covert-Communication-Exploiting-Android-Signing-Schemes.pdf>
@@ -260,6 +958,11 @@ impl ZipEntry {
     /// See: <https://xrefandroid.com/android-16.0.0_r2/xref/tools/apksig/src/main/java/com/android/apksig/internal/apk/stamp/SourceStampConstants.java#24>
     pub const V2_SOURCE_STAMP_BLOCK_ID: u32 = 0x6dff800d;
 
+    /// Proof-of-rotation attribute of the v3 signer, contains the signing certificate lineage
+    ///
+    /// See: <https://xrefandroid.com/android-16.0.0_r2/xref/tools/apksig/src/main/java/com/android/apksig/internal/apk/v3/V3SchemeConstants.java#31>
+    pub const PROOF_OF_ROTATION_ATTR_ID: u32 = 0x3ba06f8c;
+
     /// Used to increase the size of the signing block (including the length and magic) to a mulitple 4096
     ///
     /// See: <https://xrefandroid.com/android-16.0.0_r2/xref/tools/apksig/src/main/java/com/android/apksig/internal/apk/ApkSigningBlockUtils.java#100>
@@ -279,80 +982,347 @@ impl ZipEntry {
     /// Alsow known as `MEITAN_APK_CHANNEL_BLOCK`
     pub const APK_CHANNEL_BLOCK_ID: u32 = 0x71777777;
 
-    /// Google Play Frosting ID
-    pub const GOOGLE_PLAY_FROSTING_ID: u32 = 0x2146444e;
+    /// Google Play Frosting ID
+    pub const GOOGLE_PLAY_FROSTING_ID: u32 = 0x2146444e;
+
+    /// Zero block ID
+    pub const ZERO_BLOCK_ID: u32 = 0xff3b5998;
+
+    /// The signature of some Chinese packer
+    ///
+    /// See: <https://github.com/mcxiaoke/packer-ng-plugin/blob/ffbe05a2d27406f3aea574d083cded27f0742160/common/src/main/java/com/mcxiaoke/packer/common/PackerCommon.java#L29>
+    pub const PACKER_NG_SIG_V2: u32 = 0x7a786b21;
+
+    /// Some apk protector/parser, idk, seen in the wild
+    ///
+    /// The channel information in the ID-Value pair
+    ///
+    /// See: <https://edgeone.ai/document/58005>
+    pub const VASDOLLY_V2: u32 = 0x881155ff;
+
+    /// Extracts information from a v1 (APK-style) signature in the ZIP archive.
+    ///
+    /// This method searches for signature files in the `META-INF/` directory
+    /// with extensions `.DSA`, `.EC`, or `.RSA`, reads the PKCS#7 data,
+    /// and returns the associated certificates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use apk_info_zip::{ZipEntry, Signature};
+    /// # let archive = ZipEntry::new(zip_data).unwrap();
+    /// match archive.get_signature_v1() {
+    ///     Ok(Signature::V1(certs)) => println!("Found {} certificates", certs.len()),
+    ///     Ok(Signature::Unknown) => println!("No v1 signature found"),
+    ///     Err(err) => eprintln!("Error parsing signature: {:?}", err),
+    /// }
+    /// ```
+    pub fn get_signature_v1(&self) -> Result<Signature, CertificateError> {
+        let signature_file = match self.namelist().find(|name| {
+            name.starts_with("META-INF/")
+                && (name.ends_with(".DSA") || name.ends_with(".EC") || name.ends_with(".RSA"))
+        }) {
+            Some(v) => v,
+            // just apk without signatures
+            None => return Ok(Signature::Unknown),
+        };
+
+        let (data, _) = self
+            .read(signature_file)
+            .map_err(|_| CertificateError::ParseError)?;
+
+        let info = ContentInfo::from_der(&data).map_err(|_| CertificateError::ParseError)?;
+        let content = info
+            .content
+            .to_der()
+            .map_err(|_| CertificateError::ParseError)?;
+
+        let signed_data =
+            SignedData::from_der(&content).map_err(|_| CertificateError::ParseError)?;
+
+        // jarsigner and apksigner refer to the signer by issuer and serial number,
+        // the rest of the bag is not covered by the signature
+        let signers: Vec<_> = signed_data
+            .signer_infos
+            .0
+            .iter()
+            .filter_map(|signer| match &signer.sid {
+                SignerIdentifier::IssuerAndSerialNumber(id) => Some(id),
+                SignerIdentifier::SubjectKeyIdentifier(_) => None,
+            })
+            .collect();
+
+        let certs = signed_data
+            .certificates
+            .as_ref()
+            .ok_or(CertificateError::ParseError)?
+            .0
+            .iter()
+            .filter_map(|cert| {
+                let CertificateChoices::Certificate(cert) = cert else {
+                    return None;
+                };
+                let is_signer = signers.iter().any(|id| {
+                    id.issuer == cert.tbs_certificate.issuer
+                        && id.serial_number == cert.tbs_certificate.serial_number
+                });
+
+                let mut info = CertificateInfo::from(cert.clone());
+                info.is_signer = is_signer;
+                Some(info)
+            })
+            .collect();
+
+        Ok(Signature::V1(certs))
+    }
+
+    /// Parses `META-INF/MANIFEST.MF`, [None] if the archive has none.
+    pub fn get_jar_manifest(&self) -> Option<JarManifest> {
+        let (data, _) = self.read(JAR_MANIFEST_NAME).ok()?;
+        Some(JarManifest::parse(&data))
+    }
+
+    /// Verifies the digests of the v1 signature: entries against `MANIFEST.MF`
+    /// and `MANIFEST.MF` against every `.SF` file, see [crate::jar].
+    ///
+    /// The signature block itself is not verified. Returns [None] if the archive has no manifest.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// # let archive = ZipEntry::new(zip_data).unwrap();
+    /// if let Some(verification) = archive.verify_signature_v1() {
+    ///     for name in &verification.modified {
+    ///         println!("{name} was modified after signing");
+    ///     }
+    /// }
+    /// ```
+    pub fn verify_signature_v1(&self) -> Option<V1Verification> {
+        self.verify_signature_v1_with(|_, _| true)
+    }
+
+    /// Same as [ZipEntry::verify_signature_v1], `on_entry` is called before every section
+    /// of `MANIFEST.MF` and at the end with the number of checked and all sections.
+    ///
+    /// Returning `false` from `on_entry` stops the check, the result is incomplete then.
+    pub fn verify_signature_v1_with<F>(&self, mut on_entry: F) -> Option<V1Verification>
+    where
+        F: FnMut(usize, usize) -> bool,
+    {
+        let (manifest_data, _) = self.read(JAR_MANIFEST_NAME).ok()?;
+        let manifest = JarManifest::parse(&manifest_data);
+
+        let mut verification = V1Verification::default();
+        let total = manifest.entries.len();
+        for (index, section) in manifest.entries.iter().enumerate() {
+            if !on_entry(index, total) {
+                return Some(verification);
+            }
+
+            let Some(name) = section.name.as_deref() else {
+                continue;
+            };
+            if !self.local_headers.contains_key(name) {
+                verification.missing.push(name.to_string());
+                continue;
+            }
+
+            let digests = section.digests("-Digest");
+            if digests.is_empty() {
+                verification.unverified.push(name.to_string());
+                continue;
+            }
+
+            let valid = digests.iter().all(|(algorithm, expected)| {
+                self.hash_entry(name, *algorithm)
+                    .is_ok_and(|actual| actual == *expected)
+            });
+            if valid {
+                verification.verified += 1;
+            } else {
+                verification.modified.push(name.to_string());
+            }
+        }
+        if !on_entry(total, total) {
+            return Some(verification);
+        }
+
+        let mut names: Vec<&str> = self.namelist().collect();
+        names.sort_unstable();
+
+        verification.extra = names
+            .iter()
+            .filter(|name| !name.starts_with("META-INF/") && !name.ends_with('/'))
+            .filter(|name| manifest.get(name).is_none())
+            .map(|name| name.to_string())
+            .collect();
+
+        for name in names {
+            if !(name.starts_with("META-INF/") && name.ends_with(".SF")) {
+                continue;
+            }
+            let Ok((data, _)) = self.read(name) else {
+                continue;
+            };
+
+            verification.signature_files.push(verify_signature_file(
+                name,
+                &JarManifest::parse(&data),
+                &manifest_data,
+                &manifest,
+            ));
+        }
+
+        Some(verification)
+    }
+
+    /// Checks that the local headers match the central directory and the data matches
+    /// the declared CRC-32, see [crate::integrity].
+    ///
+    /// Every entry is read completely, so it is as expensive as hashing the archive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use apk_info_zip::ZipEntry;
+    /// # let archive = ZipEntry::new(zip_data).unwrap();
+    /// for issue in archive.verify_structure() {
+    ///     println!("{issue}");
+    /// }
+    /// ```
+    pub fn verify_structure(&self) -> Vec<StructureIssue> {
+        let mut entries: Vec<_> = self.central_directory.entries.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        let mut issues = Vec::new();
+        for (name, entry) in entries {
+            let Some(local) = self.local_headers.get(name) else {
+                issues.push(StructureIssue::MissingLocalHeader {
+                    name: name.to_string(),
+                });
+                continue;
+            };
+
+            if *local.file_name != *name.as_bytes() {
+                issues.push(StructureIssue::NameMismatch {
+                    name: name.to_string(),
+                    local_name: String::from_utf8_lossy(&local.file_name).into_owned(),
+                });
+            }
+            if local.compression_method != entry.compression_method {
+                issues.push(StructureIssue::CompressionMismatch {
+                    name: name.to_string(),
+                    central: entry.compression_method,
+                    local: local.compression_method,
+                });
+            }
 
-    /// Zero block ID
-    pub const ZERO_BLOCK_ID: u32 = 0xff3b5998;
+            let central = (entry.compressed_size as u64, entry.uncompressed_size as u64);
+            let local = (local.compressed_size as u64, local.uncompressed_size as u64);
+            if local != (0, 0) && local != central {
+                issues.push(StructureIssue::SizeMismatch {
+                    name: name.to_string(),
+                    central,
+                    local,
+                });
+            }
 
-    /// The signature of some Chinese packer
-    ///
-    /// See: <https://github.com/mcxiaoke/packer-ng-plugin/blob/ffbe05a2d27406f3aea574d083cded27f0742160/common/src/main/java/com/mcxiaoke/packer/common/PackerCommon.java#L29>
-    pub const PACKER_NG_SIG_V2: u32 = 0x7a786b21;
+            if name.ends_with('/') {
+                continue;
+            }
+            match self.read_borrowed(name) {
+                Ok((data, _)) => {
+                    let mut crc = Crc::new();
+                    crc.update(&data);
+                    if crc.sum() != entry.crc32 {
+                        issues.push(StructureIssue::CrcMismatch {
+                            name: name.to_string(),
+                            declared: entry.crc32,
+                            actual: crc.sum(),
+                        });
+                    }
+                }
+                Err(e) => issues.push(StructureIssue::Unreadable {
+                    name: name.to_string(),
+                    error: e.to_string(),
+                }),
+            }
+        }
 
-    /// Some apk protector/parser, idk, seen in the wild
-    ///
-    /// The channel information in the ID-Value pair
-    ///
-    /// See: <https://edgeone.ai/document/58005>
-    pub const VASDOLLY_V2: u32 = 0x881155ff;
+        issues
+    }
 
-    /// Extracts information from a v1 (APK-style) signature in the ZIP archive.
+    /// Checks the content digests of the v2, v3 and v3.1 signers against the archive,
+    /// see [crate::integrity].
     ///
-    /// This method searches for signature files in the `META-INF/` directory
-    /// with extensions `.DSA`, `.EC`, or `.RSA`, reads the PKCS#7 data,
-    /// and returns the associated certificates.
+    /// The signatures themselves are not verified. Returns an empty list if the archive
+    /// has no signing block.
     ///
     /// # Example
     ///
     /// ```
-    /// # use apk_info_zip::{ZipEntry, Signature};
+    /// # use apk_info_zip::ZipEntry;
     /// # let archive = ZipEntry::new(zip_data).unwrap();
-    /// match archive.get_signature_v1() {
-    ///     Ok(Signature::V1(certs)) => println!("Found {} certificates", certs.len()),
-    ///     Ok(Signature::Unknown) => println!("No v1 signature found"),
-    ///     Err(err) => eprintln!("Error parsing signature: {:?}", err),
+    /// for check in archive.verify_content_digests().unwrap() {
+    ///     if check.is_valid() == Some(false) {
+    ///         println!("{} digest doesn't match, the apk was modified", check.scheme);
+    ///     }
     /// }
     /// ```
-    pub fn get_signature_v1(&self) -> Result<Signature, CertificateError> {
-        let signature_file = match self.namelist().find(|name| {
-            name.starts_with("META-INF/")
-                && (name.ends_with(".DSA") || name.ends_with(".EC") || name.ends_with(".RSA"))
-        }) {
-            Some(v) => v,
-            // just apk without signatures
-            None => return Ok(Signature::Unknown),
+    pub fn verify_content_digests(&self) -> Result<Vec<DigestCheck>, CertificateError> {
+        let Some((block_offset, _)) = self.signing_block_with_offset()? else {
+            return Ok(Vec::new());
         };
 
-        let (data, _) = self
-            .read(signature_file)
-            .map_err(|_| CertificateError::ParseError)?;
-
-        let info = ContentInfo::from_der(&data).map_err(|_| CertificateError::ParseError)?;
-        let content = info
-            .content
-            .to_der()
-            .map_err(|_| CertificateError::ParseError)?;
+        let central_dir_offset = self.eocd.central_dir_offset as usize;
+        let before_block = self.input.get(..block_offset).unwrap_or_default();
+        let central_directory = self
+            .input
+            .get(central_dir_offset..self.eocd_offset)
+            .unwrap_or_default();
 
-        let signed_data =
-            SignedData::from_der(&content).map_err(|_| CertificateError::ParseError)?;
+        // the offset of the central directory is replaced with the offset of the signing block
+        let mut eocd = self
+            .input
+            .get(self.eocd_offset..)
+            .unwrap_or_default()
+            .to_vec();
+        if let Some(field) = eocd.get_mut(16..20) {
+            field.copy_from_slice(&(block_offset as u32).to_le_bytes());
+        }
+        let sections = [before_block, central_directory, &eocd];
+
+        let mut checks = Vec::new();
+        let mut computed: Vec<(SignatureAlgorithm, Option<String>)> = Vec::new();
+        for signature in self.get_signatures_other()? {
+            let (Signature::V2 { digests, .. }
+            | Signature::V3 { digests, .. }
+            | Signature::V31 { digests, .. }) = &signature
+            else {
+                continue;
+            };
 
-        let certs = signed_data
-            .certificates
-            .ok_or(CertificateError::ParseError)?
-            .0
-            .into_vec()
-            .into_iter()
-            .filter_map(|cert| {
-                if let CertificateChoices::Certificate(cert) = cert {
-                    Some(cert.into())
-                } else {
-                    None
-                }
-            })
-            .collect();
+            for digest in digests {
+                let actual = match computed.iter().find(|(a, _)| *a == digest.algorithm) {
+                    Some((_, actual)) => actual.clone(),
+                    None => {
+                        let actual = content_digest(digest.algorithm, &sections);
+                        computed.push((digest.algorithm, actual.clone()));
+                        actual
+                    }
+                };
+
+                checks.push(DigestCheck {
+                    scheme: signature.name(),
+                    algorithm: digest.algorithm,
+                    expected: digest.digest.clone(),
+                    actual,
+                });
+            }
+        }
 
-        Ok(Signature::V1(certs))
+        Ok(checks)
     }
 
     /// Parses the APK Signature Block and extracts useful information.
@@ -369,10 +1339,72 @@ impl ZipEntry {
     ///
     /// </div>
     pub fn get_signatures_other(&self) -> Result<Vec<Signature>, CertificateError> {
+        let mut slice = match self.signing_block()? {
+            Some(v) => v,
+            None => return Ok(Vec::new()),
+        };
+
+        let signatures: Vec<Signature> =
+            repeat::<&[u8], Signature, Vec<Signature>, ContextError, _>(
+                0..,
+                self.parse_apk_signatures(),
+            )
+            .parse_next(&mut slice)
+            .map_err(|_| CertificateError::ParseError)?
+            .into_iter()
+            .filter(|signature| signature != &Signature::Unknown)
+            .collect();
+
+        Ok(signatures)
+    }
+
+    /// Returns the raw ID-value pairs of the APK Signing Block in their order in the file.
+    ///
+    /// Gives access to the blocks that [ZipEntry::get_signatures_other] doesn't know
+    /// or reports only as present: Frosting, padding, channel blocks of Chinese markets.
+    /// Parsing stops at the first malformed pair.
+    ///
+    /// ```ignore
+    /// for (id, data) in zip.get_signing_block_entries() {
+    ///     println!("0x{id:08x}: {} bytes", data.len());
+    /// }
+    /// ```
+    pub fn get_signing_block_entries(&self) -> Vec<(u32, &[u8])> {
+        let Ok(Some(mut slice)) = self.signing_block() else {
+            return Vec::new();
+        };
+
+        let mut entries = Vec::new();
+        while !slice.is_empty() {
+            let Ok((size, id)) = (le_u64::<&[u8], ContextError>, le_u32).parse_next(&mut slice)
+            else {
+                break;
+            };
+            let Ok(data) = take::<usize, &[u8], ContextError>(size.saturating_sub(4) as usize)
+                .parse_next(&mut slice)
+            else {
+                break;
+            };
+
+            entries.push((id, data));
+        }
+
+        entries
+    }
+
+    /// Returns the ID-value pairs of the APK Signing Block, without the size fields and the magic.
+    ///
+    /// Returns [None] if the archive has no signing block.
+    fn signing_block(&self) -> Result<Option<&[u8]>, CertificateError> {
+        Ok(self.signing_block_with_offset()?.map(|(_, slice)| slice))
+    }
+
+    /// Same as [ZipEntry::signing_block], also returns the offset of the whole block.
+    fn signing_block_with_offset(&self) -> Result<Option<(usize, &[u8])>, CertificateError> {
         let offset = self.eocd.central_dir_offset as usize;
         let mut slice = match self.input.get(offset.saturating_sub(24)..offset) {
             Some(v) => v,
-            None => return Ok(Vec::new()),
+            None => return Ok(None),
         };
 
         let size_of_block = le_u64::<&[u8], ContextError>
@@ -385,16 +1417,14 @@ impl ZipEntry {
 
         // if the magic does not match, then assume that there is no v2+ block with signatures
         if magic != Self::APK_SIGNATURE_MAGIC {
-            return Ok(Vec::new());
+            return Ok(None);
         }
 
         // size of block (full) - 8 bytes (size of block - start) - 24 (end signature)
-        slice = match self
-            .input
-            .get(offset.saturating_sub((size_of_block + 8) as usize)..offset.saturating_sub(24))
-        {
+        let block_offset = offset.saturating_sub((size_of_block + 8) as usize);
+        slice = match self.input.get(block_offset..offset.saturating_sub(24)) {
             Some(v) => v,
-            None => return Ok(Vec::new()),
+            None => return Ok(None),
         };
 
         let size_of_block_start = le_u64::<&[u8], ContextError>
@@ -408,21 +1438,9 @@ impl ZipEntry {
             ));
         }
 
-        let signatures: Vec<Signature> =
-            repeat::<&[u8], Signature, Vec<Signature>, ContextError, _>(
-                0..,
-                self.parse_apk_signatures(),
-            )
-            .parse_next(&mut slice)
-            .map_err(|_| CertificateError::ParseError)?
-            .into_iter()
-            .filter(|signature| signature != &Signature::Unknown)
-            .collect();
-
-        Ok(signatures)
+        Ok(Some((block_offset, slice)))
     }
 
-    #[allow(unused)]
     fn parse_digest<'a>() -> impl Parser<&'a [u8], (u32, &'a [u8]), ContextError> {
         move |input: &mut &'a [u8]| {
             // digest_block_length, signature_algorith_id, digest_length, digest
@@ -451,19 +1469,50 @@ impl ZipEntry {
         }
     }
 
-    #[allow(unused)]
     fn parse_attribute_v3<'a>() -> impl Parser<&'a [u8], (u32, &'a [u8]), ContextError> {
         move |input: &mut &'a [u8]| {
-            let (attribute_length, id) = (le_u32, le_u32).parse_next(input)?;
-            let value = take(attribute_length.saturating_sub(4)).parse_next(input)?;
-            let _const_id = le_u32.parse_next(input)?;
-            // also should be somekind of Proof-of-rotation struct, but skip for now
+            let mut attribute = length_take(le_u32).parse_next(input)?;
+            let id = le_u32.parse_next(&mut attribute)?;
 
-            Ok((id, value))
+            Ok((id, attribute))
+        }
+    }
+
+    /// Parses the value of the proof-of-rotation attribute.
+    ///
+    /// See: <https://xrefandroid.com/android-16.0.0_r2/xref/tools/apksig/src/main/java/com/android/apksig/internal/apk/v3/V3SigningCertificateLineage.java#62>
+    fn parse_lineage<'a>() -> impl Parser<&'a [u8], Vec<LineageNode>, ContextError> {
+        move |input: &mut &'a [u8]| {
+            let _version = le_u32.parse_next(input)?;
+
+            let nodes: Vec<Option<LineageNode>> =
+                repeat(0.., Self::parse_lineage_node()).parse_next(input)?;
+
+            Ok(nodes.into_iter().flatten().collect())
+        }
+    }
+
+    fn parse_lineage_node<'a>() -> impl Parser<&'a [u8], Option<LineageNode>, ContextError> {
+        move |input: &mut &'a [u8]| {
+            let mut node_data = length_take(le_u32).parse_next(input)?;
+
+            // signed by the previous key: certificate and the algorithm of the next node
+            let mut signed_data = length_take(le_u32).parse_next(&mut node_data)?;
+            let certificate = Self::parse_certificate().parse_next(&mut signed_data)?;
+            let signed_signature_algorithm_id = le_u32.parse_next(&mut signed_data)?;
+
+            let (flags, signature_algorithm_id) = (le_u32, le_u32).parse_next(&mut node_data)?;
+            let _signature = length_take(le_u32).parse_next(&mut node_data)?;
+
+            Ok(certificate.map(|certificate| LineageNode {
+                certificate,
+                flags,
+                signature_algorithm_id,
+                signed_signature_algorithm_id,
+            }))
         }
     }
 
-    #[allow(unused)]
     fn parse_signature<'a>() -> impl Parser<&'a [u8], (u32, &'a [u8]), ContextError> {
         move |input: &mut &'a [u8]| {
             // signature_block_length, signature_algorithm_id, signature_length, signature
@@ -474,7 +1523,26 @@ impl ZipEntry {
         }
     }
 
-    fn parse_signer_v2<'a>() -> impl Parser<&'a [u8], Vec<CertificateInfo>, ContextError> {
+    /// Marks the first certificate as the signer's certificate, attaches the signer's
+    /// public key to it and drops unparsed certificates.
+    ///
+    /// The signer's certificate is the first one in the block, when it can't be parsed
+    /// no certificate is marked. An empty key is treated as missing.
+    fn with_signer_public_key(
+        mut certificates: Vec<Option<CertificateInfo>>,
+        public_key: &[u8],
+    ) -> Vec<CertificateInfo> {
+        if let Some(Some(certificate)) = certificates.first_mut() {
+            certificate.is_signer = true;
+            if !public_key.is_empty() {
+                certificate.signer_public_key_sha256 = Some(hex_digest::<Sha256>(public_key));
+            }
+        }
+
+        certificates.into_iter().flatten().collect()
+    }
+
+    fn parse_signer_v2<'a>() -> impl Parser<&'a [u8], ParsedSigner, ContextError> {
         move |input: &mut &'a [u8]| {
             // 1 - parse signer
             let mut signer_data = length_take(le_u32).parse_next(input)?;
@@ -483,10 +1551,9 @@ impl ZipEntry {
             let mut signed_data = length_take(le_u32).parse_next(&mut signer_data)?;
 
             // 1.1.1 - parse digests
-            let mut _digests_data = length_take(le_u32).parse_next(&mut signed_data)?;
-            // uncomment this block if actually need parse digests
-            // let digests: Vec<(u32, &[u8])> =
-            //     repeat(0.., Self::parse_digest()).parse_next(&mut digests_data)?;
+            let mut digests_data = length_take(le_u32).parse_next(&mut signed_data)?;
+            let digests: Vec<(u32, &[u8])> =
+                repeat(0.., Self::parse_digest()).parse_next(&mut digests_data)?;
 
             // 1.1.2 - parse certificates
             let mut certificates_data = length_take(le_u32).parse_next(&mut signed_data)?;
@@ -500,19 +1567,23 @@ impl ZipEntry {
             //     repeat(0.., Self::parse_attribute_v2()).parse_next(&mut attributes_data)?;
 
             // 1.2 - parse signatures
-            let mut _signatures_data = length_take(le_u32).parse_next(&mut signer_data)?;
-            // uncomment this block if actually need parse signatures
-            // let signatures: Vec<(u32, &[u8])> =
-            //     repeat(0.., Self::parse_signature()).parse_next(&mut signatures_data)?;
+            let mut signatures_data = length_take(le_u32).parse_next(&mut signer_data)?;
+            let signatures: Vec<(u32, &[u8])> =
+                repeat(0.., Self::parse_signature()).parse_next(&mut signatures_data)?;
 
             // 1.3 - parse public key
-            let _public_key = length_take(le_u32).parse_next(&mut signer_data)?;
-
-            Ok(certificates.into_iter().flatten().collect())
+            let public_key = length_take(le_u32).parse_next(&mut signer_data)?;
+
+            Ok(ParsedSigner::new(
+                Self::with_signer_public_key(certificates, public_key),
+                Vec::new(),
+                &signatures,
+                &digests,
+            ))
         }
     }
 
-    fn parse_signer_v3<'a>() -> impl Parser<&'a [u8], Vec<CertificateInfo>, ContextError> {
+    fn parse_signer_v3<'a>() -> impl Parser<&'a [u8], ParsedSigner, ContextError> {
         move |input: &mut &'a [u8]| {
             // 1 - parse signer
             let mut signer_data = length_take(le_u32).parse_next(input)?;
@@ -521,10 +1592,9 @@ impl ZipEntry {
             let mut signed_data = length_take(le_u32).parse_next(&mut signer_data)?;
 
             // 1.1.1 - parse digests
-            let mut _digests_data = length_take(le_u32).parse_next(&mut signed_data)?;
-            // uncomment this block if actually need parse digests
-            // let digets: Vec<(u32, &[u8])> =
-            //     repeat(0.., Self::parse_digest()).parse_next(&mut digests_data)?;
+            let mut digests_data = length_take(le_u32).parse_next(&mut signed_data)?;
+            let digests: Vec<(u32, &[u8])> =
+                repeat(0.., Self::parse_digest()).parse_next(&mut digests_data)?;
 
             // 1.1.2 - parse certificates
             let mut certificates_data = length_take(le_u32).parse_next(&mut signed_data)?;
@@ -535,25 +1605,35 @@ impl ZipEntry {
             let (_min_sdk, _max_sdk) = (le_u32, le_u32).parse_next(&mut signed_data)?;
 
             // 1.1.4 - parse attributes
-            let mut _attributes_data = length_take(le_u32).parse_next(&mut signed_data)?;
-            // uncomment this block if actually need parse attributes
-            // let attributes: Vec<(u32, &[u8])> =
-            //     repeat(0.., Self::parse_attribute_v3()).parse_next(&mut attributes_data)?;
+            let mut attributes_data = length_take(le_u32).parse_next(&mut signed_data)?;
+            let attributes: Vec<(u32, &[u8])> =
+                repeat(0.., Self::parse_attribute_v3()).parse_next(&mut attributes_data)?;
+
+            // a broken lineage shouldn't hide the certificates
+            let lineage = attributes
+                .into_iter()
+                .find(|(id, _)| *id == Self::PROOF_OF_ROTATION_ATTR_ID)
+                .and_then(|(_, mut value)| Self::parse_lineage().parse_next(&mut value).ok())
+                .unwrap_or_default();
 
             // 1.2 - parse duplicates sdk
             let (_duplicate_min_sdk, _duplicate_max_sdk) =
                 (le_u32, le_u32).parse_next(&mut signer_data)?;
 
             // 1.3 - parse signatures
-            let mut _signatures_data = length_take(le_u32).parse_next(&mut signer_data)?;
-            // uncomment this block if actually need parse signatures
-            // let signatures: Vec<(u32, &[u8])> =
-            //     repeat(0.., Self::parse_signature()).parse_next(&mut signatures_data)?;
+            let mut signatures_data = length_take(le_u32).parse_next(&mut signer_data)?;
+            let signatures: Vec<(u32, &[u8])> =
+                repeat(0.., Self::parse_signature()).parse_next(&mut signatures_data)?;
 
             // 1.4 - parse public key
-            let _public_key = length_take(le_u32).parse_next(&mut signer_data)?;
-
-            Ok(certificates.into_iter().flatten().collect())
+            let public_key = length_take(le_u32).parse_next(&mut signer_data)?;
+
+            Ok(ParsedSigner::new(
+                Self::with_signer_public_key(certificates, public_key),
+                lineage,
+                &signatures,
+                &digests,
+            ))
         }
     }
 
@@ -564,48 +1644,41 @@ impl ZipEntry {
             match id {
                 Self::SIGNATURE_SCHEME_V2_BLOCK_ID => {
                     let mut signers_data = length_take(le_u32).parse_next(input)?;
-
-                    let certificates =
-                        repeat::<_, Vec<CertificateInfo>, Vec<Vec<CertificateInfo>>, _, _>(
-                            1..,
-                            Self::parse_signer_v2(),
-                        )
-                        .parse_next(&mut signers_data)?
-                        .into_iter()
-                        .flatten()
-                        .collect();
-
-                    Ok(Signature::V2(certificates))
+                    let signers: Vec<_> =
+                        repeat(1.., Self::parse_signer_v2()).parse_next(&mut signers_data)?;
+                    let signer = ParsedSigner::merge(signers);
+
+                    Ok(Signature::V2 {
+                        certificates: signer.certificates,
+                        algorithms: signer.algorithms,
+                        digests: signer.digests,
+                    })
                 }
                 Self::SIGNATURE_SCHEME_V3_BLOCK_ID => {
                     let mut signers_data = length_take(le_u32).parse_next(input)?;
-
-                    let certificates =
-                        repeat::<_, Vec<CertificateInfo>, Vec<Vec<CertificateInfo>>, _, _>(
-                            1..,
-                            Self::parse_signer_v3(),
-                        )
-                        .parse_next(&mut signers_data)?
-                        .into_iter()
-                        .flatten()
-                        .collect();
-
-                    Ok(Signature::V3(certificates))
+                    let signers: Vec<_> =
+                        repeat(1.., Self::parse_signer_v3()).parse_next(&mut signers_data)?;
+                    let signer = ParsedSigner::merge(signers);
+
+                    Ok(Signature::V3 {
+                        certificates: signer.certificates,
+                        lineage: signer.lineage,
+                        algorithms: signer.algorithms,
+                        digests: signer.digests,
+                    })
                 }
                 Self::SIGNATURE_SCHEME_V31_BLOCK_ID => {
                     let mut signers_data = length_take(le_u32).parse_next(input)?;
-
-                    let certificates =
-                        repeat::<_, Vec<CertificateInfo>, Vec<Vec<CertificateInfo>>, _, _>(
-                            1..,
-                            Self::parse_signer_v3(),
-                        )
-                        .parse_next(&mut signers_data)?
-                        .into_iter()
-                        .flatten()
-                        .collect();
-
-                    Ok(Signature::V31(certificates))
+                    let signers: Vec<_> =
+                        repeat(1.., Self::parse_signer_v3()).parse_next(&mut signers_data)?;
+                    let signer = ParsedSigner::merge(signers);
+
+                    Ok(Signature::V31 {
+                        certificates: signer.certificates,
+                        lineage: signer.lineage,
+                        algorithms: signer.algorithms,
+                        digests: signer.digests,
+                    })
                 }
                 Self::APK_CHANNEL_BLOCK_ID => {
                     let data = take(size.saturating_sub(4) as usize).parse_next(input)?;
@@ -652,8 +1725,8 @@ impl ZipEntry {
                     Ok(Signature::PackerNextGenV2(data.to_vec()))
                 }
                 Self::GOOGLE_PLAY_FROSTING_ID => {
-                    let _ = take(size.saturating_sub(4) as usize).parse_next(input)?;
-                    Ok(Signature::GooglePlayFrosting)
+                    let data = take(size.saturating_sub(4) as usize).parse_next(input)?;
+                    Ok(Signature::Frosting(FrostingInfo::parse(data)))
                 }
                 Self::VASDOLLY_V2 => {
                     let data = take(size.saturating_sub(4) as usize).parse_next(input)?;
@@ -675,9 +1748,12 @@ impl ZipEntry {
                         id, size
                     );
 
-                    let _ = take(size.saturating_sub(4) as usize).parse_next(input)?;
+                    let data = take(size.saturating_sub(4) as usize).parse_next(input)?;
 
-                    Ok(Signature::Unknown)
+                    Ok(Signature::UnknownBlock {
+                        id,
+                        data: data.to_vec(),
+                    })
                 }
             }
         }
@@ -690,6 +1766,9 @@ impl From<Certificate> for CertificateInfo {
         _ = value.encode_to_vec(&mut cert_data);
         let cert = value.tbs_certificate;
 
+        let mut public_key = Vec::new();
+        _ = cert.subject_public_key_info.encode_to_vec(&mut public_key);
+
         CertificateInfo {
             serial_number: cert.serial_number.as_bytes().iter().fold(
                 String::new(),
@@ -706,25 +1785,370 @@ impl From<Certificate> for CertificateInfo {
                 .by_oid(&cert.signature.oid)
                 .unwrap_or_default()
                 .to_string(),
-            md5_fingerprint: Md5::digest(&cert_data)
-                .iter()
-                .fold(String::new(), |mut out, x| {
-                    _ = write!(out, "{x:02x}");
-                    out
-                }),
-            sha1_fingerprint: Sha1::digest(&cert_data)
+            md5_fingerprint: hex_digest::<Md5>(&cert_data),
+            sha1_fingerprint: hex_digest::<Sha1>(&cert_data),
+            sha256_fingerprint: hex_digest::<Sha256>(&cert_data),
+            public_key_sha256: hex_digest::<Sha256>(&public_key),
+            signer_public_key_sha256: None,
+            version: cert.version as u8 + 1,
+            key_algorithm: key_algorithm(&cert.subject_public_key_info.algorithm.oid),
+            key_size: key_size(&cert.subject_public_key_info),
+            extensions: cert
+                .extensions
                 .iter()
-                .fold(String::new(), |mut out, x| {
-                    _ = write!(out, "{x:02x}");
-                    out
-                }),
-            sha256_fingerprint: Sha256::digest(&cert_data).iter().fold(
-                String::new(),
-                |mut out, x| {
-                    _ = write!(out, "{x:02x}");
-                    out
-                },
-            ),
+                .flatten()
+                .map(|extension| oid_name(&extension.extn_id))
+                .collect(),
+            is_self_signed: cert.subject == cert.issuer,
+            is_debug_cert: is_debug_subject(&cert.subject),
+            is_signer: false,
+            der: cert_data,
+        }
+    }
+}
+
+fn oid_name(oid: &ObjectIdentifier) -> String {
+    DB.by_oid(oid)
+        .map(str::to_string)
+        .unwrap_or_else(|| oid.to_string())
+}
+
+fn key_algorithm(oid: &ObjectIdentifier) -> String {
+    match *oid {
+        rfc5912::RSA_ENCRYPTION => "RSA".to_string(),
+        rfc5912::ID_EC_PUBLIC_KEY => "EC".to_string(),
+        rfc5912::ID_DSA => "DSA".to_string(),
+        rfc8410::ID_ED_25519 => "Ed25519".to_string(),
+        _ => oid_name(oid),
+    }
+}
+
+/// Number of significant bits of the big-endian unsigned integer.
+fn bit_length(value: UintRef<'_>) -> u32 {
+    let bytes = value.as_bytes();
+    match bytes.first() {
+        Some(first) => bytes.len() as u32 * 8 - first.leading_zeros(),
+        None => 0,
+    }
+}
+
+fn key_size(info: &SubjectPublicKeyInfoOwned) -> Option<u32> {
+    match info.algorithm.oid {
+        rfc5912::RSA_ENCRYPTION => {
+            // RSAPublicKey ::= SEQUENCE { modulus INTEGER, publicExponent INTEGER }
+            let mut reader = SliceReader::new(info.subject_public_key.as_bytes()?).ok()?;
+            reader
+                .sequence(|reader| {
+                    let modulus = UintRef::decode(reader)?;
+                    let _exponent = UintRef::decode(reader)?;
+                    Ok(bit_length(modulus))
+                })
+                .ok()
+        }
+        rfc5912::ID_DSA => {
+            // Dss-Parms ::= SEQUENCE { p INTEGER, q INTEGER, g INTEGER }
+            let parameters = info.algorithm.parameters.as_ref()?;
+            let mut reader = SliceReader::new(parameters.value()).ok()?;
+            UintRef::decode(&mut reader).ok().map(bit_length)
+        }
+        rfc5912::ID_EC_PUBLIC_KEY => {
+            let curve: ObjectIdentifier = info.algorithm.parameters.as_ref()?.decode_as().ok()?;
+            match curve {
+                rfc5912::SECP_224_R_1 => Some(224),
+                rfc5912::SECP_256_R_1 => Some(256),
+                rfc5912::SECP_384_R_1 => Some(384),
+                rfc5912::SECP_521_R_1 => Some(521),
+                _ => None,
+            }
+        }
+        rfc8410::ID_ED_25519 => Some(256),
+        _ => None,
+    }
+}
+
+/// Checks the subject against the debug keystore of the Android SDK
+/// (`CN=Android Debug,O=Android,C=US`) and the test keys of AOSP
+/// (`CN=Android,OU=Android,O=Android,...`, testkey, platform, shared and media).
+fn is_debug_subject(subject: &Name) -> bool {
+    let attribute = |oid: ObjectIdentifier| {
+        subject
+            .0
+            .iter()
+            .flat_map(|rdn| rdn.0.iter())
+            .find(|attribute| attribute.oid == oid)
+            .and_then(|attribute| {
+                attribute.value.decode_as::<String>().ok().or_else(|| {
+                    attribute
+                        .value
+                        .decode_as::<PrintableStringRef<'_>>()
+                        .ok()
+                        .map(|value| value.to_string())
+                })
+            })
+    };
+
+    match attribute(rfc4519::CN).as_deref() {
+        Some("Android Debug") => true,
+        Some("Android") => {
+            attribute(rfc4519::O).as_deref() == Some("Android")
+                && attribute(rfc4519::OU).as_deref() == Some("Android")
+        }
+        _ => false,
+    }
+}
+
+/// Hashes the data and returns the lowercase hex digest.
+pub(crate) fn hex_digest<D: Digest>(data: &[u8]) -> String {
+    to_hex(&D::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefixed(data: &[u8]) -> Vec<u8> {
+        let mut out = (data.len() as u32).to_le_bytes().to_vec();
+        out.extend(data);
+        out
+    }
+
+    /// Digests and signatures share the layout: length-prefixed `(algorithm id, value)`.
+    fn algorithm_values(values: &[(u32, &[u8])]) -> Vec<u8> {
+        let items: Vec<u8> = values
+            .iter()
+            .flat_map(|(id, value)| {
+                let mut item = id.to_le_bytes().to_vec();
+                item.extend(prefixed(value));
+                prefixed(&item)
+            })
+            .collect();
+        prefixed(&items)
+    }
+
+    /// v2 signer without certificates, attributes and the public key.
+    fn signer_v2(digests: &[(u32, &[u8])], signatures: &[(u32, &[u8])]) -> Vec<u8> {
+        let mut signed_data = algorithm_values(digests);
+        signed_data.extend(prefixed(&[]));
+        signed_data.extend(prefixed(&[]));
+
+        let mut signer = prefixed(&signed_data);
+        signer.extend(algorithm_values(signatures));
+        signer.extend(prefixed(&[]));
+        prefixed(&signer)
+    }
+
+    /// v3 signer without certificates, attributes and the public key.
+    fn signer_v3(digests: &[(u32, &[u8])], signatures: &[(u32, &[u8])]) -> Vec<u8> {
+        let sdk: Vec<u8> = [28u32, u32::MAX]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+
+        let mut signed_data = algorithm_values(digests);
+        signed_data.extend(prefixed(&[]));
+        signed_data.extend(&sdk);
+        signed_data.extend(prefixed(&[]));
+
+        let mut signer = prefixed(&signed_data);
+        signer.extend(&sdk);
+        signer.extend(algorithm_values(signatures));
+        signer.extend(prefixed(&[]));
+        prefixed(&signer)
+    }
+
+    fn digest(id: u32, hex: &str) -> ContentDigest {
+        ContentDigest {
+            algorithm: SignatureAlgorithm::from_id(id),
+            digest: hex.to_string(),
         }
     }
+
+    #[test]
+    fn test_signer_v2_algorithms() {
+        let data = signer_v2(
+            &[(0x0103, &[0xab, 0xcd]), (0x0421, &[0x01, 0x02, 0x03])],
+            &[(0x0103, b"signature"), (0x0421, b"verity signature")],
+        );
+
+        let signer = ZipEntry::parse_signer_v2()
+            .parse_next(&mut data.as_slice())
+            .unwrap();
+
+        assert_eq!(
+            signer.algorithms,
+            [
+                SignatureAlgorithm::RsaPkcs1Sha256,
+                SignatureAlgorithm::VerityRsaPkcs1Sha256
+            ]
+        );
+        assert_eq!(
+            signer.digests,
+            [digest(0x0103, "abcd"), digest(0x0421, "010203")]
+        );
+        assert!(signer.certificates.is_empty() && signer.lineage.is_empty());
+    }
+
+    #[test]
+    fn test_signer_v3_algorithms() {
+        let data = signer_v3(&[(0x0201, &[0xff; 4])], &[(0x0201, b"signature")]);
+
+        let signer = ZipEntry::parse_signer_v3()
+            .parse_next(&mut data.as_slice())
+            .unwrap();
+
+        assert_eq!(signer.algorithms, [SignatureAlgorithm::EcdsaSha256]);
+        assert_eq!(signer.digests, [digest(0x0201, "ffffffff")]);
+    }
+
+    #[test]
+    fn test_signer_unknown_algorithm() {
+        let data = signer_v2(&[(0x9999, &[0x00])], &[(0x9999, b"signature")]);
+
+        let signer = ZipEntry::parse_signer_v2()
+            .parse_next(&mut data.as_slice())
+            .unwrap();
+
+        // kept, the caller decides what an unknown algorithm means
+        assert_eq!(signer.algorithms, [SignatureAlgorithm::Unknown(0x9999)]);
+        assert_eq!(signer.digests, [digest(0x9999, "00")]);
+    }
+
+    #[test]
+    fn test_signer_truncated() {
+        let mut data = signer_v2(&[(0x0103, &[0xab, 0xcd])], &[(0x0103, b"signature")]);
+        data.truncate(data.len() - 1);
+
+        assert!(
+            ZipEntry::parse_signer_v2()
+                .parse_next(&mut data.as_slice())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_merge_signers() {
+        let parse = |digests: &[(u32, &[u8])], signatures: &[(u32, &[u8])]| {
+            let data = signer_v2(digests, signatures);
+            ZipEntry::parse_signer_v2()
+                .parse_next(&mut data.as_slice())
+                .unwrap()
+        };
+
+        let merged = ParsedSigner::merge(vec![
+            parse(&[(0x0103, &[0x01])], &[(0x0103, b"first")]),
+            parse(
+                &[(0x0103, &[0x01]), (0x0104, &[0x02])],
+                &[(0x0104, b"second"), (0x0103, b"second")],
+            ),
+        ]);
+
+        // deduplicated in the order of appearance
+        assert_eq!(
+            merged.algorithms,
+            [
+                SignatureAlgorithm::RsaPkcs1Sha256,
+                SignatureAlgorithm::RsaPkcs1Sha512
+            ]
+        );
+        assert_eq!(merged.digests, [digest(0x0103, "01"), digest(0x0104, "02")]);
+    }
+
+    /// `CN=Android Debug`, RSA 2048, self-signed
+    const DEBUG_CERT: &[u8] = include_bytes!("../tests/fixtures/debug.der");
+
+    /// Subject of the AOSP test keys, DSA 2048, self-signed
+    const TESTKEY_CERT: &[u8] = include_bytes!("../tests/fixtures/testkey.der");
+
+    /// `CN=Android,O=Android` issued by `CN=Test CA`, Ed25519, no extensions
+    const ISSUED_CERT: &[u8] = include_bytes!("../tests/fixtures/issued.der");
+
+    fn certificate(der: &[u8]) -> CertificateInfo {
+        Certificate::from_der(der).unwrap().into()
+    }
+
+    #[test]
+    fn test_certificate_debug() {
+        let info = certificate(DEBUG_CERT);
+
+        assert_eq!(info.subject, "CN=Android Debug,O=Android,C=US");
+        assert_eq!(info.version, 3);
+        assert_eq!(info.key_algorithm, "RSA");
+        assert_eq!(info.key_size, Some(2048));
+        assert_eq!(
+            info.extensions,
+            [
+                "id-ce-subjectKeyIdentifier",
+                "id-ce-authorityKeyIdentifier",
+                "id-ce-basicConstraints"
+            ]
+        );
+        assert!(info.is_self_signed);
+        assert!(info.is_debug_cert);
+        assert!(!info.is_signer);
+        assert_eq!(info.der, DEBUG_CERT);
+    }
+
+    #[test]
+    fn test_certificate_testkey() {
+        let info = certificate(TESTKEY_CERT);
+
+        assert_eq!(info.key_algorithm, "DSA");
+        assert_eq!(info.key_size, Some(2048));
+        assert!(info.is_self_signed);
+        assert!(info.is_debug_cert);
+    }
+
+    #[test]
+    fn test_certificate_issued() {
+        let info = certificate(ISSUED_CERT);
+
+        assert_eq!(info.issuer, "CN=Test CA,O=apk-info");
+        assert_eq!(info.key_algorithm, "Ed25519");
+        assert_eq!(info.key_size, Some(256));
+        assert!(info.extensions.is_empty());
+        assert!(!info.is_self_signed);
+        // `CN=Android` is a test key only with `OU=Android`
+        assert!(!info.is_debug_cert);
+    }
+
+    #[test]
+    fn test_signer_public_key() {
+        let certificates = vec![
+            None,
+            Some(certificate(DEBUG_CERT)),
+            Some(certificate(ISSUED_CERT)),
+        ];
+        // the first certificate can't be parsed, none is the signer's
+        let certificates = ZipEntry::with_signer_public_key(certificates, b"key");
+        assert_eq!(certificates.len(), 2);
+        assert!(certificates.iter().all(|c| !c.is_signer));
+
+        let certificates = vec![
+            Some(certificate(DEBUG_CERT)),
+            Some(certificate(ISSUED_CERT)),
+        ];
+        let certificates = ZipEntry::with_signer_public_key(certificates, b"key");
+        assert!(certificates[0].is_signer);
+        assert_eq!(
+            certificates[0].signer_public_key_sha256.as_deref(),
+            Some(hex_digest::<Sha256>(b"key").as_str())
+        );
+        assert!(!certificates[1].is_signer);
+        assert_eq!(certificates[1].signer_public_key_sha256, None);
+
+        // an empty key is missing
+        let certificates =
+            ZipEntry::with_signer_public_key(vec![Some(certificate(DEBUG_CERT))], b"");
+        assert!(certificates[0].is_signer);
+        assert_eq!(certificates[0].signer_public_key_sha256, None);
+    }
+
+    #[test]
+    fn test_bit_length() {
+        let uint = |bytes: &'static [u8]| UintRef::new(bytes).unwrap();
+
+        assert_eq!(bit_length(uint(&[0x01])), 1);
+        assert_eq!(bit_length(uint(&[0x80, 0x00])), 16);
+        assert_eq!(bit_length(uint(&[0x7f, 0xff, 0xff])), 23);
+    }
 }
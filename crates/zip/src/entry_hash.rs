@@ -0,0 +1,86 @@
+//! Digests of the files inside the archive.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Hash algorithm for [ZipEntry::hash_entry](crate::ZipEntry::hash_entry).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HashAlgorithm::Md5 => "md5",
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha256 => "sha256",
+        })
+    }
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('-', "").as_str() {
+            "md5" => Ok(HashAlgorithm::Md5),
+            "sha1" => Ok(HashAlgorithm::Sha1),
+            "sha256" => Ok(HashAlgorithm::Sha256),
+            _ => Err(format!("unknown hash algorithm: {s}")),
+        }
+    }
+}
+
+/// Digests of the uncompressed data of the file, lowercase hex.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct EntryHashes {
+    /// Name of the file inside the archive.
+    pub name: String,
+
+    /// Size of the hashed data.
+    pub size: u64,
+
+    pub md5: String,
+
+    pub sha1: String,
+
+    pub sha256: String,
+}
+
+impl EntryHashes {
+    /// Returns the digest computed with the algorithm.
+    pub fn get(&self, algorithm: HashAlgorithm) -> &str {
+        match algorithm {
+            HashAlgorithm::Md5 => &self.md5,
+            HashAlgorithm::Sha1 => &self.sha1,
+            HashAlgorithm::Sha256 => &self.sha256,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_algorithm() {
+        assert_eq!("SHA-256".parse(), Ok(HashAlgorithm::Sha256));
+        assert_eq!("sha1".parse(), Ok(HashAlgorithm::Sha1));
+        assert_eq!("md5".parse(), Ok(HashAlgorithm::Md5));
+        assert!("crc32".parse::<HashAlgorithm>().is_err());
+
+        for algorithm in [
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha1,
+            HashAlgorithm::Sha256,
+        ] {
+            assert_eq!(algorithm.to_string().parse(), Ok(algorithm));
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Metadata of a single file inside the archive.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{FileCompressionType, FileType};
+
+/// Metadata of the file inside the archive, as it was used for reading.
+///
+/// Headers of tampered archives often disagree with each other, so both the central
+/// directory and the local header values are kept where it matters.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EntryInfo {
+    /// Name of the file inside the archive.
+    pub name: String,
+
+    /// How the data was actually read.
+    pub compression: FileCompressionType,
+
+    /// Compression method declared in the central directory.
+    pub central_compression_method: u16,
+
+    /// Compression method declared in the local file header.
+    pub local_compression_method: u16,
+
+    /// General purpose bit flag from the central directory.
+    pub flags: u16,
+
+    /// CRC-32 declared in the central directory.
+    pub crc32: u32,
+
+    /// Compressed size used for reading.
+    pub compressed_size: u64,
+
+    /// Uncompressed size used for reading.
+    pub uncompressed_size: u64,
+
+    /// Offset of the local file header from the start of the archive.
+    pub local_header_offset: u64,
+
+    /// Offset of the file data from the start of the archive.
+    pub data_offset: u64,
+
+    /// Type detected from the content, see [FileType::detect].
+    pub file_type: Option<FileType>,
+}
@@ -12,16 +12,25 @@ pub enum ZipError {
     InvalidHeader,
 
     /// An error occurred while decompressing a file entry.
-    #[error("got error while decompressing object")]
-    DecompressionError,
+    #[error("got error while decompressing {0}")]
+    DecompressionError(String),
 
     /// Unexpected end-of-file (EOF) was reached while reading the ZIP archive.
-    #[error("got EOF while parsing zip")]
-    EOF,
+    #[error("got EOF while reading {entry} (offset 0x{offset:x}, size {size})")]
+    EOF {
+        /// Name of the entry that was being read.
+        entry: String,
+
+        /// Offset of the entry data inside the archive.
+        offset: usize,
+
+        /// Expected size of the entry data.
+        size: usize,
+    },
 
     /// The requested file does not exist inside the ZIP archive.
-    #[error("file not exist in zip")]
-    FileNotFound,
+    #[error("file {0} not exist in zip")]
+    FileNotFound(String),
 
     /// The End of Central Directory (EOCD) record could not be found, preventing operations.
     #[error("can't find EOCD in zip")]
@@ -30,6 +39,10 @@ pub enum ZipError {
     /// A general error occurred while parsing the ZIP archive.
     #[error("got error while parsing zip archive")]
     ParseError,
+
+    /// The archive file can't be opened or read.
+    #[error("can't read zip archive: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 /// Represents all errors that can occur while handling certificates.
@@ -40,7 +53,7 @@ pub enum CertificateError {
     ParseError,
 
     /// An error occurred while parsing a ZIP archive within the certificate context.
-    #[error("got zip error while parsing certificate: {0}")]
+    #[error("got zip error while parsing certificate")]
     ZipError(#[from] ZipError),
 
     /// A signing error occurred (e.g., invalid signer or signature verification failed).
@@ -50,4 +63,8 @@ pub enum CertificateError {
     /// The certificate format is invalid because the block sizes do not match the expected values.
     #[error("size of blocks not equals (required by format) - (start - {0}, end - {1})")]
     InvalidFormat(u64, u64),
+
+    /// The fingerprint style name is not known, see [crate::FingerprintStyle].
+    #[error("unknown fingerprint style {0:?}, expected hex, colon or base64")]
+    UnknownFingerprintStyle(String),
 }
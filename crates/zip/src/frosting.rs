@@ -0,0 +1,180 @@
+//! Google Play Frosting block of the APK Signing Block.
+//!
+//! Google Play adds the block to the apks it delivers. The block starts with a varint-prefixed
+//! protobuf message with the metadata, followed by the signature of Google Play. The schema
+//! of the message isn't public, so only the wire format is decoded.
+//!
+//! See: <https://github.com/avast/apkverifier/blob/master/signingblock/frosting.go>
+
+use serde::{Deserialize, Serialize};
+
+/// Value of a protobuf field, nested messages and strings are [ProtobufValue::Bytes].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtobufValue {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(Vec<u8>),
+    Fixed32(u32),
+}
+
+/// Top-level field of a protobuf message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ProtobufField {
+    /// Field number from the schema
+    pub number: u32,
+
+    pub value: ProtobufValue,
+}
+
+impl ProtobufField {
+    /// Decodes the top-level fields of the message.
+    ///
+    /// Returns [None] if the message is malformed or uses the deprecated groups.
+    pub fn parse_message(mut data: &[u8]) -> Option<Vec<ProtobufField>> {
+        let mut fields = Vec::new();
+
+        while !data.is_empty() {
+            let key = read_varint(&mut data)?;
+            let number = u32::try_from(key >> 3).ok()?;
+            let value = match key & 0x7 {
+                0 => ProtobufValue::Varint(read_varint(&mut data)?),
+                1 => {
+                    ProtobufValue::Fixed64(u64::from_le_bytes(take(&mut data, 8)?.try_into().ok()?))
+                }
+                2 => {
+                    let size = usize::try_from(read_varint(&mut data)?).ok()?;
+                    ProtobufValue::Bytes(take(&mut data, size)?.to_vec())
+                }
+                5 => {
+                    ProtobufValue::Fixed32(u32::from_le_bytes(take(&mut data, 4)?.try_into().ok()?))
+                }
+                _ => return None,
+            };
+
+            fields.push(ProtobufField { number, value });
+        }
+
+        Some(fields)
+    }
+}
+
+/// Parsed Google Play Frosting block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct FrostingInfo {
+    /// Raw protobuf message with the metadata
+    pub metadata: Vec<u8>,
+
+    /// Top-level fields of the metadata, empty if the message is malformed
+    pub fields: Vec<ProtobufField>,
+
+    /// The rest of the block after the metadata: the signature of Google Play,
+    /// its layout isn't documented
+    pub signature_data: Vec<u8>,
+}
+
+impl FrostingInfo {
+    /// Parses the value of the block, never fails: if the size of the metadata is invalid,
+    /// the whole block goes to [FrostingInfo::signature_data].
+    pub fn parse(data: &[u8]) -> FrostingInfo {
+        let mut rest = data;
+        let metadata = read_varint(&mut rest)
+            .and_then(|size| usize::try_from(size).ok())
+            .and_then(|size| take(&mut rest, size));
+
+        let Some(metadata) = metadata else {
+            return FrostingInfo {
+                signature_data: data.to_vec(),
+                ..Default::default()
+            };
+        };
+
+        FrostingInfo {
+            metadata: metadata.to_vec(),
+            fields: ProtobufField::parse_message(metadata).unwrap_or_default(),
+            signature_data: rest.to_vec(),
+        }
+    }
+
+    /// Returns the values of the top-level field.
+    pub fn get(&self, number: u32) -> impl Iterator<Item = &ProtobufValue> {
+        self.fields
+            .iter()
+            .filter(move |field| field.number == number)
+            .map(|field| &field.value)
+    }
+}
+
+fn read_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *data = &data[i + 1..];
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+fn take<'a>(data: &mut &'a [u8], size: usize) -> Option<&'a [u8]> {
+    if data.len() < size {
+        return None;
+    }
+
+    let (head, tail) = data.split_at(size);
+    *data = tail;
+    Some(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frosting() {
+        // field 1: varint 150, field 2: "abc", field 3: fixed32 1
+        let message = [
+            0x08, 0x96, 0x01, 0x12, 0x03, b'a', b'b', b'c', 0x1d, 1, 0, 0, 0,
+        ];
+        let mut data = vec![message.len() as u8];
+        data.extend_from_slice(&message);
+        data.extend_from_slice(b"signature");
+
+        let info = FrostingInfo::parse(&data);
+
+        assert_eq!(info.metadata, message);
+        assert_eq!(info.signature_data, b"signature");
+        assert_eq!(
+            info.get(1).collect::<Vec<_>>(),
+            [&ProtobufValue::Varint(150)]
+        );
+        assert_eq!(
+            info.get(2).collect::<Vec<_>>(),
+            [&ProtobufValue::Bytes(b"abc".to_vec())]
+        );
+        assert_eq!(
+            info.get(3).collect::<Vec<_>>(),
+            [&ProtobufValue::Fixed32(1)]
+        );
+    }
+
+    #[test]
+    fn test_parse_frosting_truncated() {
+        let info = FrostingInfo::parse(&[0x20, 0x08, 0x01]);
+
+        assert!(info.metadata.is_empty());
+        assert!(info.fields.is_empty());
+        assert_eq!(info.signature_data, [0x20, 0x08, 0x01]);
+    }
+
+    #[test]
+    fn test_parse_message_malformed() {
+        // length-delimited field longer than the message
+        assert_eq!(ProtobufField::parse_message(&[0x12, 0x05, b'a']), None);
+        // group
+        assert_eq!(ProtobufField::parse_message(&[0x0b]), None);
+    }
+}
@@ -0,0 +1,189 @@
+//! Integrity checks of the archive beyond the v1 signature, see [crate::jar] for it.
+//!
+//! - [StructureIssue]: the central directory and the local headers must describe the same
+//!   entries, otherwise tools that read one or the other see different files;
+//! - [DigestCheck]: content digests of the v2/v3 signers must match the archive, Android
+//!   computes them over the entries, the central directory and the End of Central Directory.
+//!
+//! See: <https://source.android.com/docs/security/features/apksigning/v2#integrity-protected-contents>
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::SignatureAlgorithm;
+use crate::entry::to_hex;
+
+/// Mismatch between the central directory and the local header of the entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StructureIssue {
+    /// The entry is listed in the central directory, but its local header can't be parsed.
+    MissingLocalHeader { name: String },
+
+    /// The local header has a different name.
+    NameMismatch { name: String, local_name: String },
+
+    /// The local header declares a different compression method.
+    CompressionMismatch {
+        name: String,
+        central: u16,
+        local: u16,
+    },
+
+    /// The local header declares different sizes, zero sizes of data descriptors are allowed.
+    SizeMismatch {
+        name: String,
+        central: (u64, u64),
+        local: (u64, u64),
+    },
+
+    /// CRC-32 of the data doesn't match the central directory.
+    CrcMismatch {
+        name: String,
+        declared: u32,
+        actual: u32,
+    },
+
+    /// The data can't be read.
+    Unreadable { name: String, error: String },
+}
+
+impl fmt::Display for StructureIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StructureIssue::MissingLocalHeader { name } => {
+                write!(f, "{name}: local header is missing")
+            }
+            StructureIssue::NameMismatch { name, local_name } => {
+                write!(f, "{name}: local header has name {local_name:?}")
+            }
+            StructureIssue::CompressionMismatch {
+                name,
+                central,
+                local,
+            } => write!(
+                f,
+                "{name}: compression method {central} in central directory, {local} in local header"
+            ),
+            StructureIssue::SizeMismatch {
+                name,
+                central,
+                local,
+            } => write!(
+                f,
+                "{name}: sizes {}/{} in central directory, {}/{} in local header",
+                central.0, central.1, local.0, local.1
+            ),
+            StructureIssue::CrcMismatch {
+                name,
+                declared,
+                actual,
+            } => write!(f, "{name}: crc32 {actual:08x}, expected {declared:08x}"),
+            StructureIssue::Unreadable { name, error } => write!(f, "{name}: {error}"),
+        }
+    }
+}
+
+/// Content digest of a v2/v3 signer checked against the archive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct DigestCheck {
+    /// Name of the signature scheme: `v2`, `v3`, `v3.1`.
+    pub scheme: String,
+
+    pub algorithm: SignatureAlgorithm,
+
+    /// Digest declared by the signer, lowercase hex.
+    pub expected: String,
+
+    /// Digest of the archive, [None] if the algorithm isn't supported (verity).
+    pub actual: Option<String>,
+}
+
+impl DigestCheck {
+    /// Whether the digests match, [None] if the digest wasn't computed.
+    pub fn is_valid(&self) -> Option<bool> {
+        self.actual.as_ref().map(|actual| *actual == self.expected)
+    }
+}
+
+/// Size of the chunks the sections are split into.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Computes the content digest of the sections, [None] if the algorithm isn't supported.
+///
+/// Every section is split into 1 MiB chunks, the digest of a chunk covers `0xa5`,
+/// its size and the data; the top-level digest covers `0x5a`, the number of chunks
+/// and the digests of all chunks.
+pub(crate) fn content_digest(algorithm: SignatureAlgorithm, sections: &[&[u8]]) -> Option<String> {
+    match algorithm {
+        SignatureAlgorithm::RsaPssSha256
+        | SignatureAlgorithm::RsaPkcs1Sha256
+        | SignatureAlgorithm::EcdsaSha256
+        | SignatureAlgorithm::DsaSha256 => Some(chunked_digest::<Sha256>(sections)),
+        SignatureAlgorithm::RsaPssSha512
+        | SignatureAlgorithm::RsaPkcs1Sha512
+        | SignatureAlgorithm::EcdsaSha512 => Some(chunked_digest::<Sha512>(sections)),
+        _ => None,
+    }
+}
+
+fn chunked_digest<D: Digest>(sections: &[&[u8]]) -> String {
+    let chunks = sections
+        .iter()
+        .flat_map(|section| section.chunks(CHUNK_SIZE))
+        .filter(|chunk| !chunk.is_empty());
+
+    let mut count = 0u32;
+    let mut digests = Vec::new();
+    for chunk in chunks {
+        let mut hasher = D::new();
+        hasher.update([0xa5]);
+        hasher.update((chunk.len() as u32).to_le_bytes());
+        hasher.update(chunk);
+        digests.extend_from_slice(&hasher.finalize());
+        count += 1;
+    }
+
+    let mut hasher = D::new();
+    hasher.update([0x5a]);
+    hasher.update(count.to_le_bytes());
+    hasher.update(&digests);
+
+    to_hex(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_digest_chunks() {
+        let data = vec![7u8; CHUNK_SIZE + 10];
+
+        let mut top = Sha256::new();
+        top.update([0x5a]);
+        top.update(3u32.to_le_bytes());
+        for chunk in [&data[..CHUNK_SIZE], &data[CHUNK_SIZE..], b"eocd"] {
+            let mut hasher = Sha256::new();
+            hasher.update([0xa5]);
+            hasher.update((chunk.len() as u32).to_le_bytes());
+            hasher.update(chunk);
+            top.update(hasher.finalize());
+        }
+
+        assert_eq!(
+            content_digest(SignatureAlgorithm::EcdsaSha256, &[&data, b"", b"eocd"]),
+            Some(to_hex(&top.finalize()))
+        );
+    }
+
+    #[test]
+    fn test_content_digest_verity() {
+        assert_eq!(
+            content_digest(SignatureAlgorithm::VerityEcdsaSha256, &[b"data"]),
+            None
+        );
+    }
+}
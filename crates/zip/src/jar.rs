@@ -0,0 +1,437 @@
+//! `MANIFEST.MF` and `.SF` files of the v1 (jar) signature.
+//!
+//! `MANIFEST.MF` lists the digest of every file in the archive, every `.SF` file lists the
+//! digest of the whole manifest and of its sections, the `.RSA` / `.DSA` / `.EC` block signs
+//! the `.SF` file. Only the block is covered by [ZipEntry::get_signature_v1](crate::ZipEntry::get_signature_v1),
+//! [ZipEntry::verify_signature_v1](crate::ZipEntry::verify_signature_v1) checks the rest of the chain.
+//!
+//! Android ignores all files in `META-INF/` when checking the manifest, so they can be
+//! added to a signed apk without breaking the v1 signature.
+
+use std::str::FromStr;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::Sha256;
+
+use crate::HashAlgorithm;
+use crate::entry::{hex_digest, to_hex};
+
+/// Name of the manifest inside the archive.
+pub const JAR_MANIFEST_NAME: &str = "META-INF/MANIFEST.MF";
+
+/// A section of the manifest: the main attributes or the attributes of a single entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManifestSection {
+    /// Value of the `Name` attribute, [None] for the main section.
+    pub name: Option<String>,
+
+    /// Attributes in the order they are declared, continuation lines are joined.
+    pub attributes: Vec<(String, String)>,
+
+    /// Raw bytes of the section with the empty line that ends it, `.SF` digests cover them.
+    pub raw: Vec<u8>,
+}
+
+impl ManifestSection {
+    /// Returns the value of the attribute, names are case-insensitive.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the declared digests with the suffix in the attribute name, lowercase hex.
+    ///
+    /// The suffix is `-Digest` for the entries of `MANIFEST.MF` (`SHA-256-Digest`) and
+    /// `-Digest-Manifest` for the main section of `.SF` (`SHA-256-Digest-Manifest`).
+    /// Digests of unsupported algorithms and invalid base64 are skipped.
+    pub fn digests(&self, suffix: &str) -> Vec<(HashAlgorithm, String)> {
+        self.attributes
+            .iter()
+            .filter_map(|(key, value)| {
+                let algorithm = key
+                    .len()
+                    .checked_sub(suffix.len())
+                    .filter(|&end| key.is_char_boundary(end))
+                    .filter(|&end| key[end..].eq_ignore_ascii_case(suffix))
+                    .and_then(|end| HashAlgorithm::from_str(&key[..end]).ok())?;
+                let digest = STANDARD.decode(value.trim()).ok()?;
+
+                Some((algorithm, to_hex(&digest)))
+            })
+            .collect()
+    }
+}
+
+/// Parsed `MANIFEST.MF` or `.SF` file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JarManifest {
+    /// The main section, it is always present, even if empty.
+    pub main: ManifestSection,
+
+    /// Sections of the entries, those without `Name` are skipped.
+    pub entries: Vec<ManifestSection>,
+}
+
+impl JarManifest {
+    /// Parses the manifest, malformed lines are skipped.
+    ///
+    /// Lines end with `\r\n`, `\n` or `\r`, a line starting with a space continues the previous
+    /// one, an empty line ends the section.
+    pub fn parse(data: &[u8]) -> JarManifest {
+        let mut sections = Vec::new();
+        let mut current = ManifestSection::default();
+        let mut start = 0;
+        let mut offset = 0;
+
+        while offset < data.len() {
+            let (line, next) = split_line(data, offset);
+
+            if line.is_empty() {
+                if offset > start {
+                    current.raw = data[start..next].to_vec();
+                    sections.push(std::mem::take(&mut current));
+                } else if sections.is_empty() {
+                    // the main section ends with the empty line even if there are no attributes
+                    current.raw = data[start..next].to_vec();
+                    sections.push(std::mem::take(&mut current));
+                }
+                start = next;
+            } else if let Some(continuation) = line.strip_prefix(b" ") {
+                if let Some((_, value)) = current.attributes.last_mut() {
+                    value.push_str(&String::from_utf8_lossy(continuation));
+                }
+            } else if let Some(colon) = line.iter().position(|&b| b == b':') {
+                let key = String::from_utf8_lossy(&line[..colon]).trim().to_string();
+                let value = String::from_utf8_lossy(&line[colon + 1..]);
+                let value = value.strip_prefix(' ').unwrap_or(&value).to_string();
+                current.attributes.push((key, value));
+            }
+
+            offset = next;
+        }
+        if start < data.len() {
+            current.raw = data[start..].to_vec();
+            sections.push(current);
+        }
+
+        let mut sections = sections.into_iter();
+        let mut main = sections.next().unwrap_or_default();
+        main.name = None;
+
+        let entries = sections
+            .filter_map(|mut section| {
+                section.name = Some(section.get("Name")?.to_string());
+                Some(section)
+            })
+            .collect();
+
+        JarManifest { main, entries }
+    }
+
+    /// Returns the section of the entry.
+    pub fn get(&self, name: &str) -> Option<&ManifestSection> {
+        self.entries
+            .iter()
+            .find(|section| section.name.as_deref() == Some(name))
+    }
+}
+
+/// Returns the line without the line break and the offset of the next line.
+fn split_line(data: &[u8], offset: usize) -> (&[u8], usize) {
+    let rest = &data[offset..];
+    match rest.iter().position(|&b| b == b'\r' || b == b'\n') {
+        Some(end) if rest[end] == b'\r' && rest.get(end + 1) == Some(&b'\n') => {
+            (&rest[..end], offset + end + 2)
+        }
+        Some(end) => (&rest[..end], offset + end + 1),
+        None => (rest, data.len()),
+    }
+}
+
+/// Computes the digest of the data, lowercase hex.
+fn digest(algorithm: HashAlgorithm, data: &[u8]) -> String {
+    match algorithm {
+        HashAlgorithm::Md5 => hex_digest::<Md5>(data),
+        HashAlgorithm::Sha1 => hex_digest::<Sha1>(data),
+        HashAlgorithm::Sha256 => hex_digest::<Sha256>(data),
+    }
+}
+
+/// Result of checking a single `.SF` file against `MANIFEST.MF`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct SignatureFileVerification {
+    /// Name of the `.SF` file.
+    pub name: String,
+
+    /// Whether the digest of the whole manifest matches, [None] if it isn't declared.
+    pub manifest_digest_valid: Option<bool>,
+
+    /// Whether the digest of the main section of the manifest matches, [None] if it isn't
+    /// declared or the whole manifest matches.
+    pub main_attributes_valid: Option<bool>,
+
+    /// Entries whose section digest doesn't match the manifest or is missing from it,
+    /// checked only when the digest of the whole manifest doesn't match.
+    pub mismatched_sections: Vec<String>,
+
+    /// Entries of the manifest without a section in the `.SF` file, checked only when the
+    /// digest of the whole manifest doesn't match: no signer covers them.
+    pub unsigned_sections: Vec<String>,
+
+    /// Value of `X-Android-APK-Signed`: ids of the newer schemes the apk is also signed with.
+    ///
+    /// Android rejects the apk if they are declared but the apk has no such signatures,
+    /// it protects from stripping the newer signatures.
+    pub apk_signed: Option<String>,
+}
+
+impl SignatureFileVerification {
+    /// Checks if the `.SF` file matches the manifest.
+    ///
+    /// If the digest of the whole manifest doesn't match, every entry of the manifest must
+    /// have a matching section in the `.SF` file, as Android requires.
+    pub fn is_valid(&self) -> bool {
+        self.manifest_digest_valid == Some(true)
+            || (self.main_attributes_valid != Some(false)
+                && self.mismatched_sections.is_empty()
+                && self.unsigned_sections.is_empty())
+    }
+}
+
+/// Result of [ZipEntry::verify_signature_v1](crate::ZipEntry::verify_signature_v1).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct V1Verification {
+    /// Number of entries whose digests match.
+    pub verified: usize,
+
+    /// Entries listed in the manifest, but missing from the archive.
+    pub missing: Vec<String>,
+
+    /// Entries of the archive not listed in the manifest, `META-INF/` is ignored as Android does.
+    pub extra: Vec<String>,
+
+    /// Entries whose content doesn't match the declared digest.
+    pub modified: Vec<String>,
+
+    /// Entries without a digest of a supported algorithm (SHA-384 and SHA-512 are not),
+    /// the verification fails since their content can't be checked.
+    pub unverified: Vec<String>,
+
+    /// `.SF` files checked against the manifest.
+    pub signature_files: Vec<SignatureFileVerification>,
+}
+
+impl V1Verification {
+    /// Checks if all entries and all `.SF` files match.
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.modified.is_empty()
+            && self.unverified.is_empty()
+            && !self.signature_files.is_empty()
+            && self
+                .signature_files
+                .iter()
+                .all(SignatureFileVerification::is_valid)
+    }
+}
+
+/// Checks the `.SF` file against the raw manifest and its sections.
+pub(crate) fn verify_signature_file(
+    name: &str,
+    signature_file: &JarManifest,
+    manifest_data: &[u8],
+    manifest: &JarManifest,
+) -> SignatureFileVerification {
+    let matches = |digests: Vec<(HashAlgorithm, String)>, data: &[u8]| {
+        (!digests.is_empty()).then(|| {
+            digests
+                .iter()
+                .all(|(algorithm, expected)| digest(*algorithm, data) == *expected)
+        })
+    };
+
+    let manifest_digest_valid = matches(
+        signature_file.main.digests("-Digest-Manifest"),
+        manifest_data,
+    );
+
+    let mut main_attributes_valid = None;
+    let mut mismatched_sections = Vec::new();
+    let mut unsigned_sections = Vec::new();
+    if manifest_digest_valid != Some(true) {
+        main_attributes_valid = matches(
+            signature_file
+                .main
+                .digests("-Digest-Manifest-Main-Attributes"),
+            &manifest.main.raw,
+        );
+
+        for section in &signature_file.entries {
+            let Some(entry_name) = &section.name else {
+                continue;
+            };
+            let valid = manifest
+                .get(entry_name)
+                .and_then(|entry| matches(section.digests("-Digest"), &entry.raw));
+            if valid != Some(true) {
+                mismatched_sections.push(entry_name.clone());
+            }
+        }
+
+        // an entry added to the manifest together with its digest isn't signed by anyone
+        unsigned_sections = manifest
+            .entries
+            .iter()
+            .filter_map(|entry| entry.name.as_ref())
+            .filter(|entry_name| signature_file.get(entry_name).is_none())
+            .cloned()
+            .collect();
+    }
+
+    SignatureFileVerification {
+        name: name.to_string(),
+        manifest_digest_valid,
+        main_attributes_valid,
+        mismatched_sections,
+        unsigned_sections,
+        apk_signed: signature_file
+            .main
+            .get("X-Android-APK-Signed")
+            .map(String::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::Digest;
+
+    use super::*;
+
+    const MANIFEST: &[u8] = b"Manifest-Version: 1.0\r\n\
+Created-By: 1.0 (Android)\r\n\
+\r\n\
+Name: res/layout/a_very_long_name_of_the_layout_file_that_does_not_fit_i\r\n \
+nto_one_line.xml\r\n\
+SHA-256-Digest: n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=\r\n\
+\r\n\
+Name: classes.dex\r\n\
+SHA1-Digest: qZk+NkcGgWq6PiVxeFDCbJzQ2J0=\r\n\
+\r\n";
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = JarManifest::parse(MANIFEST);
+
+        assert_eq!(manifest.main.get("manifest-version"), Some("1.0"));
+        assert_eq!(manifest.entries.len(), 2);
+        assert_eq!(
+            manifest.entries[0].name.as_deref(),
+            Some(
+                "res/layout/a_very_long_name_of_the_layout_file_that_does_not_fit_into_one_line.xml"
+            )
+        );
+
+        let dex = manifest.get("classes.dex").unwrap();
+        assert_eq!(
+            dex.digests("-Digest"),
+            vec![(
+                HashAlgorithm::Sha1,
+                "a9993e364706816aba3e25717850c26c9cd0d89d".to_string()
+            )]
+        );
+        assert_eq!(
+            dex.raw,
+            b"Name: classes.dex\r\nSHA1-Digest: qZk+NkcGgWq6PiVxeFDCbJzQ2J0=\r\n\r\n"
+        );
+        assert_eq!(
+            manifest.main.raw,
+            b"Manifest-Version: 1.0\r\nCreated-By: 1.0 (Android)\r\n\r\n"
+        );
+    }
+
+    /// `.SF` file with a wrong digest of the whole manifest and sections of the given entries.
+    fn signature_file(manifest: &JarManifest, names: &[&str]) -> JarManifest {
+        let mut data = "Signature-Version: 1.0\r\n\
+SHA-256-Digest-Manifest: AAAA\r\n\
+X-Android-APK-Signed: 2, 3\r\n\
+\r\n"
+            .to_string();
+        for name in names {
+            let section_digest = STANDARD.encode(Sha256::digest(&manifest.get(name).unwrap().raw));
+            data.push_str(&format!(
+                "Name: {name}\r\nSHA-256-Digest: {section_digest}\r\n\r\n"
+            ));
+        }
+
+        JarManifest::parse(data.as_bytes())
+    }
+
+    #[test]
+    fn test_verify_signature_file() {
+        let manifest = JarManifest::parse(MANIFEST);
+        let names: Vec<&str> = manifest
+            .entries
+            .iter()
+            .filter_map(|entry| entry.name.as_deref())
+            .collect();
+        let signature_file = signature_file(&manifest, &names);
+
+        let verification =
+            verify_signature_file("META-INF/CERT.SF", &signature_file, MANIFEST, &manifest);
+
+        // the whole manifest doesn't match, but every section does
+        assert_eq!(verification.manifest_digest_valid, Some(false));
+        assert!(verification.mismatched_sections.is_empty());
+        assert!(verification.unsigned_sections.is_empty());
+        assert!(verification.is_valid());
+        assert_eq!(verification.apk_signed.as_deref(), Some("2, 3"));
+    }
+
+    #[test]
+    fn test_verify_signature_file_added_entry() {
+        let mut data = MANIFEST.to_vec();
+        data.extend_from_slice(
+            b"Name: evil.dex\r\nSHA-256-Digest: n4bQgYhMfWWaL+qgxVrQFaO/TxsrC4Is0V1sFbDwCgg=\r\n\r\n",
+        );
+        let manifest = JarManifest::parse(&data);
+        let signature_file = signature_file(&manifest, &["classes.dex"]);
+
+        let verification =
+            verify_signature_file("META-INF/CERT.SF", &signature_file, &data, &manifest);
+
+        assert!(verification.mismatched_sections.is_empty());
+        assert!(
+            verification
+                .unsigned_sections
+                .iter()
+                .any(|name| name == "evil.dex")
+        );
+        assert!(!verification.is_valid());
+    }
+
+    #[test]
+    fn test_unverified_entries_fail() {
+        let verification = V1Verification {
+            verified: 1,
+            unverified: vec!["classes.dex".to_string()],
+            signature_files: vec![SignatureFileVerification {
+                name: "META-INF/CERT.SF".to_string(),
+                manifest_digest_valid: Some(true),
+                main_attributes_valid: None,
+                mismatched_sections: Vec::new(),
+                unsigned_sections: Vec::new(),
+                apk_signed: None,
+            }],
+            ..Default::default()
+        };
+
+        assert!(!verification.is_valid());
+    }
+}
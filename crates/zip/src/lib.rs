@@ -9,13 +9,32 @@
 //! let (data, compression_method) = zip.read("AndroidManifest.xml");
 //! ```
 
+pub mod anomaly;
 pub mod compression;
+pub mod data;
 pub mod entry;
+pub mod entry_hash;
+pub mod entry_info;
 pub mod errors;
+pub mod frosting;
+pub mod integrity;
+pub mod jar;
+pub mod magic;
+pub mod path;
 pub mod signature;
 
+mod logging;
 mod structs;
+pub use anomaly::*;
 pub use compression::*;
+pub use data::*;
 pub use entry::*;
+pub use entry_hash::*;
+pub use entry_info::*;
 pub use errors::*;
+pub use frosting::*;
+pub use integrity::*;
+pub use jar::*;
+pub use magic::*;
+pub use path::*;
 pub use signature::*;
@@ -0,0 +1,14 @@
+//! Wrapper of the `log` macro that is compiled out with the `quiet` feature.
+//!
+//! Anomalies of the container are reported by [ZipEntry::anomalies](crate::ZipEntry::anomalies)
+//! regardless of the feature.
+
+macro_rules! log_warn {
+    ($($arg:tt)+) => {
+        if cfg!(not(feature = "quiet")) {
+            ::log::warn!($($arg)+)
+        }
+    };
+}
+
+pub(crate) use log_warn as warn;
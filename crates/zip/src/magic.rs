@@ -0,0 +1,288 @@
+//! Detection of the file type by the leading magic bytes.
+//!
+//! Only formats that are interesting inside an apk are known, there is no external
+//! database: native code, dex and archives that are often hidden under other extensions,
+//! media files and the compiled android resources.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Type of the file detected from its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileType {
+    /// ELF executable or shared library
+    Elf,
+
+    /// Dalvik executable
+    Dex,
+
+    /// Zip archive, also apk, jar and aar
+    Zip,
+
+    /// Android binary xml
+    Axml,
+
+    /// Android resource table
+    Arsc,
+
+    /// Java class file
+    Class,
+
+    /// Windows PE executable
+    Pe,
+
+    /// Mach-O executable
+    MachO,
+
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+    Pdf,
+    Gzip,
+    #[serde(rename = "7z")]
+    SevenZip,
+    Rar,
+    Xz,
+    Sqlite,
+    Ogg,
+    Mp3,
+    Mp4,
+    Ttf,
+    Otf,
+
+    /// PEM encoded key or certificate
+    Pem,
+
+    /// Text xml
+    Xml,
+}
+
+impl FileType {
+    /// All known types.
+    pub const ALL: &[FileType] = &[
+        FileType::Elf,
+        FileType::Dex,
+        FileType::Zip,
+        FileType::Axml,
+        FileType::Arsc,
+        FileType::Class,
+        FileType::Pe,
+        FileType::MachO,
+        FileType::Png,
+        FileType::Jpeg,
+        FileType::Gif,
+        FileType::Webp,
+        FileType::Pdf,
+        FileType::Gzip,
+        FileType::SevenZip,
+        FileType::Rar,
+        FileType::Xz,
+        FileType::Sqlite,
+        FileType::Ogg,
+        FileType::Mp3,
+        FileType::Mp4,
+        FileType::Ttf,
+        FileType::Otf,
+        FileType::Pem,
+        FileType::Xml,
+    ];
+
+    /// Number of leading bytes that is enough for detection.
+    pub const HEADER_SIZE: usize = 16;
+
+    /// Detects the type by the first [FileType::HEADER_SIZE] bytes of the data.
+    ///
+    /// ```ignore
+    /// assert_eq!(FileType::detect(b"\x7fELF\x02\x01\x01"), Some(FileType::Elf));
+    /// ```
+    pub fn detect(data: &[u8]) -> Option<FileType> {
+        let file_type = match data {
+            [0x7f, b'E', b'L', b'F', ..] => FileType::Elf,
+            [b'd', b'e', b'x', b'\n', _, _, _, 0, ..] => FileType::Dex,
+            [b'P', b'K', 0x03, 0x04, ..] | [b'P', b'K', 0x05, 0x06, ..] => FileType::Zip,
+            [0x03, 0x00, 0x08, 0x00, ..] => FileType::Axml,
+            [0x02, 0x00, 0x0c, 0x00, ..] => FileType::Arsc,
+            // also the fat Mach-O header, but it doesn't occur in apk
+            [0xca, 0xfe, 0xba, 0xbe, ..] => FileType::Class,
+            [b'M', b'Z', ..] => FileType::Pe,
+            [0xfe, 0xed, 0xfa, 0xce | 0xcf, ..] | [0xce | 0xcf, 0xfa, 0xed, 0xfe, ..] => {
+                FileType::MachO
+            }
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => FileType::Png,
+            [0xff, 0xd8, 0xff, ..] => FileType::Jpeg,
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => FileType::Gif,
+            [b'R', b'I', b'F', b'F', _, _, _, _, rest @ ..] if rest.starts_with(b"WEBP") => {
+                FileType::Webp
+            }
+            [b'%', b'P', b'D', b'F', b'-', ..] => FileType::Pdf,
+            [0x1f, 0x8b, ..] => FileType::Gzip,
+            [b'7', b'z', 0xbc, 0xaf, 0x27, 0x1c, ..] => FileType::SevenZip,
+            [b'R', b'a', b'r', b'!', 0x1a, 0x07, ..] => FileType::Rar,
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => FileType::Xz,
+            _ if data.starts_with(b"SQLite format 3\0") => FileType::Sqlite,
+            [b'O', b'g', b'g', b'S', ..] => FileType::Ogg,
+            [b'I', b'D', b'3', ..] => FileType::Mp3,
+            [_, _, _, _, b'f', b't', b'y', b'p', ..] => FileType::Mp4,
+            [0x00, 0x01, 0x00, 0x00, 0x00, ..] => FileType::Ttf,
+            [b'O', b'T', b'T', b'O', ..] => FileType::Otf,
+            _ if data.starts_with(b"-----BEGIN ") => FileType::Pem,
+            _ if data.starts_with(b"<?xml") || data.starts_with(b"\xef\xbb\xbf<?xml") => {
+                FileType::Xml
+            }
+            _ => return None,
+        };
+
+        Some(file_type)
+    }
+
+    /// Parses the name returned by [FileType::name].
+    pub fn from_name(name: &str) -> Option<FileType> {
+        FileType::ALL
+            .iter()
+            .copied()
+            .find(|file_type| file_type.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Short lowercase name of the type, e.g. `elf`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileType::Elf => "elf",
+            FileType::Dex => "dex",
+            FileType::Zip => "zip",
+            FileType::Axml => "axml",
+            FileType::Arsc => "arsc",
+            FileType::Class => "class",
+            FileType::Pe => "pe",
+            FileType::MachO => "macho",
+            FileType::Png => "png",
+            FileType::Jpeg => "jpeg",
+            FileType::Gif => "gif",
+            FileType::Webp => "webp",
+            FileType::Pdf => "pdf",
+            FileType::Gzip => "gzip",
+            FileType::SevenZip => "7z",
+            FileType::Rar => "rar",
+            FileType::Xz => "xz",
+            FileType::Sqlite => "sqlite",
+            FileType::Ogg => "ogg",
+            FileType::Mp3 => "mp3",
+            FileType::Mp4 => "mp4",
+            FileType::Ttf => "ttf",
+            FileType::Otf => "otf",
+            FileType::Pem => "pem",
+            FileType::Xml => "xml",
+        }
+    }
+
+    /// MIME type of the content.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            FileType::Elf => "application/x-elf",
+            FileType::Dex => "application/vnd.android.dex",
+            FileType::Zip => "application/zip",
+            FileType::Axml | FileType::Arsc => "application/octet-stream",
+            FileType::Class => "application/java-vm",
+            FileType::Pe => "application/vnd.microsoft.portable-executable",
+            FileType::MachO => "application/x-mach-binary",
+            FileType::Png => "image/png",
+            FileType::Jpeg => "image/jpeg",
+            FileType::Gif => "image/gif",
+            FileType::Webp => "image/webp",
+            FileType::Pdf => "application/pdf",
+            FileType::Gzip => "application/gzip",
+            FileType::SevenZip => "application/x-7z-compressed",
+            FileType::Rar => "application/vnd.rar",
+            FileType::Xz => "application/x-xz",
+            FileType::Sqlite => "application/vnd.sqlite3",
+            FileType::Ogg => "audio/ogg",
+            FileType::Mp3 => "audio/mpeg",
+            FileType::Mp4 => "video/mp4",
+            FileType::Ttf => "font/ttf",
+            FileType::Otf => "font/otf",
+            FileType::Pem => "application/x-pem-file",
+            FileType::Xml => "text/xml",
+        }
+    }
+
+    /// Whether the content is expected for the extension of the file name.
+    ///
+    /// Unknown extensions and files without them match any content. Images are
+    /// interchangeable, the android decoder doesn't look at the extension.
+    pub fn matches_extension(&self, name: &str) -> bool {
+        let file_name = name.rsplit('/').next().unwrap_or(name);
+        let Some((_, extension)) = file_name.rsplit_once('.') else {
+            return true;
+        };
+
+        let expected: &[FileType] = match extension.to_ascii_lowercase().as_str() {
+            "so" => &[FileType::Elf],
+            "dex" => &[FileType::Dex],
+            "apk" | "jar" | "zip" | "aar" => &[FileType::Zip],
+            "arsc" => &[FileType::Arsc],
+            "xml" => &[FileType::Axml, FileType::Xml],
+            "png" | "jpg" | "jpeg" | "gif" | "webp" => {
+                &[FileType::Png, FileType::Jpeg, FileType::Gif, FileType::Webp]
+            }
+            "ogg" | "mp3" | "mp4" | "m4a" => &[FileType::Ogg, FileType::Mp3, FileType::Mp4],
+            "ttf" | "otf" => &[FileType::Ttf, FileType::Otf],
+            "pdf" => &[FileType::Pdf],
+            "db" | "sqlite" => &[FileType::Sqlite],
+            _ => return true,
+        };
+
+        expected.contains(self)
+    }
+}
+
+impl fmt::Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let cases: &[(&[u8], Option<FileType>)] = &[
+            (b"\x7fELF\x02\x01\x01\x00", Some(FileType::Elf)),
+            (b"dex\n035\0\x00\x00\x00\x00", Some(FileType::Dex)),
+            (b"PK\x03\x04\x14\x00", Some(FileType::Zip)),
+            (b"\x03\x00\x08\x00\x10\x00", Some(FileType::Axml)),
+            (b"\x89PNG\r\n\x1a\n\x00\x00", Some(FileType::Png)),
+            (b"RIFF\x10\x00\x00\x00WEBPVP8 ", Some(FileType::Webp)),
+            (b"SQLite format 3\0\x10\x00", Some(FileType::Sqlite)),
+            (b"\x00\x00\x00\x18ftypmp42", Some(FileType::Mp4)),
+            (b"<?xml version=\"1.0\"?>", Some(FileType::Xml)),
+            (b"dex\n", None),
+            (b"hello world", None),
+            (b"", None),
+        ];
+
+        for (data, expected) in cases {
+            assert_eq!(FileType::detect(data), *expected, "data: {data:?}");
+        }
+    }
+
+    #[test]
+    fn test_matches_extension() {
+        assert!(FileType::Elf.matches_extension("lib/arm64-v8a/libfoo.so"));
+        assert!(!FileType::Elf.matches_extension("assets/logo.PNG"));
+        assert!(FileType::Jpeg.matches_extension("res/drawable/logo.png"));
+        assert!(!FileType::Dex.matches_extension("assets/data.jar"));
+        assert!(FileType::Dex.matches_extension("assets/payload"));
+        assert!(FileType::Dex.matches_extension("assets/payload.bin"));
+    }
+
+    #[test]
+    fn test_names() {
+        for file_type in FileType::ALL {
+            assert_eq!(FileType::from_name(file_type.name()), Some(*file_type));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Safe paths for extracting entries to the disk.
+//!
+//! Entry names come from the archive as is: backslashes, absolute paths, drive letters,
+//! `..`, reserved Windows names and the same name in NFC and NFD forms. Names are turned
+//! into relative paths that are written the same way on Linux, macOS and Windows.
+
+use std::collections::HashSet;
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Names reserved by Windows, with or without an extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Characters that can't be used in file names on Windows.
+const INVALID_CHARS: &[char] = &['<', '>', ':', '"', '|', '?', '*'];
+
+/// Entry with the path it should be extracted to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractPath {
+    /// Name of the entry in the archive
+    pub name: String,
+
+    /// Relative path with `/` separators
+    pub path: String,
+}
+
+impl ExtractPath {
+    /// Whether the path differs from the entry name.
+    #[inline]
+    pub fn is_renamed(&self) -> bool {
+        self.name != self.path
+    }
+}
+
+/// Turns the entry name into a safe relative path.
+///
+/// - `\` is treated as a separator, drive letters and leading separators are dropped;
+/// - `.` is dropped, `..` removes the previous component and never leaves the root;
+/// - the name is converted to NFC;
+/// - characters invalid on Windows and control characters are replaced with `_`,
+///   as well as trailing dots and spaces;
+/// - reserved Windows names (`CON`, `NUL`, `COM1`, ...) are prefixed with `_`.
+///
+/// Returns [None] for directories and names without any component left.
+pub fn normalize_entry_name(name: &str) -> Option<String> {
+    let name: String = name.replace('\\', "/").nfc().collect();
+    if name.ends_with('/') {
+        return None;
+    }
+
+    let name = strip_drive(&name);
+
+    let mut components: Vec<String> = Vec::new();
+    for component in name.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            component => components.push(sanitize_component(component)),
+        }
+    }
+
+    if components.is_empty() {
+        return None;
+    }
+
+    Some(components.join("/"))
+}
+
+/// Returns paths for extracting the given entries.
+///
+/// Directories and empty names are skipped. Names that end up with the same path,
+/// compared case-insensitively as on Windows and macOS, get a `~N` suffix.
+pub fn extract_paths<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<ExtractPath> {
+    let mut taken = HashSet::new();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let path = normalize_entry_name(name)?;
+            let path = (1..)
+                .map(|i| match i {
+                    1 => path.clone(),
+                    i => with_suffix(&path, i),
+                })
+                .find(|path| taken.insert(path.to_lowercase()))?;
+
+            Some(ExtractPath {
+                name: name.to_string(),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Strips the `C:` drive prefix, leading separators are dropped later.
+fn strip_drive(name: &str) -> &str {
+    match name.as_bytes() {
+        [letter, b':', ..] if letter.is_ascii_alphabetic() => &name[2..],
+        _ => name,
+    }
+}
+
+fn sanitize_component(component: &str) -> String {
+    let mut component: String = component
+        .chars()
+        .map(|c| {
+            if c.is_control() || INVALID_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    // windows silently strips them, so `a.` and `a` are the same file
+    let trimmed = component.trim_end_matches(['.', ' ']).len();
+    if trimmed != component.len() {
+        component.truncate(trimmed);
+        component.push('_');
+    }
+
+    let stem = component.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+    {
+        component.insert(0, '_');
+    }
+
+    component
+}
+
+/// `dir/name.ext` -> `dir/name~2.ext`
+fn with_suffix(path: &str, i: usize) -> String {
+    let file_start = path.rfind('/').map_or(0, |pos| pos + 1);
+
+    match path[file_start..].rfind('.') {
+        Some(dot) if dot > 0 => {
+            let dot = file_start + dot;
+            format!("{}~{i}{}", &path[..dot], &path[dot..])
+        }
+        _ => format!("{path}~{i}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_entry_name() {
+        let cases = [
+            ("res/layout/main.xml", Some("res/layout/main.xml")),
+            ("res\\raw\\a.bin", Some("res/raw/a.bin")),
+            ("/etc/passwd", Some("etc/passwd")),
+            ("C:\\Windows\\evil.dll", Some("Windows/evil.dll")),
+            ("../../a/./b", Some("a/b")),
+            ("a/../../../b", Some("b")),
+            ("assets/CON", Some("assets/_CON")),
+            ("assets/nul.txt", Some("assets/_nul.txt")),
+            ("assets/console.txt", Some("assets/console.txt")),
+            ("assets/a:b?.txt", Some("assets/a_b_.txt")),
+            ("assets/name. ", Some("assets/name_")),
+            ("assets/\u{0}x", Some("assets/_x")),
+            ("assets/", None),
+            ("..", None),
+            ("", None),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(
+                normalize_entry_name(name).as_deref(),
+                expected,
+                "name: {name:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_unicode() {
+        // "é" precomposed and decomposed
+        assert_eq!(
+            normalize_entry_name("assets/caf\u{e9}"),
+            normalize_entry_name("assets/cafe\u{301}")
+        );
+    }
+
+    #[test]
+    fn test_extract_paths_collisions() {
+        let paths = extract_paths([
+            "assets/cafe\u{301}.txt",
+            "assets/caf\u{e9}.txt",
+            "assets/CAFÉ.txt",
+            "res/",
+            "res\\a",
+        ]);
+
+        let paths: Vec<&str> = paths.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "assets/caf\u{e9}.txt",
+                "assets/caf\u{e9}~2.txt",
+                "assets/CAFÉ~3.txt",
+                "res/a",
+            ]
+        );
+    }
+}
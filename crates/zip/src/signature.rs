@@ -1,11 +1,18 @@
 //! Describes signatures contained in the `APK Signature Block`.
 
-use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+use crate::{CertificateError, FrostingInfo};
 
 /// Describe used signature scheme in APK
 ///
 /// Basic overview: <https://source.android.com/docs/security/features/apksigning>
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Signature {
     /// Default signature scheme based on JAR signing
     ///
@@ -17,19 +24,49 @@ pub enum Signature {
     ///
     /// See: <https://source.android.com/docs/security/features/apksigning/v2>
     #[serde(rename = "v2")]
-    V2(Vec<CertificateInfo>),
+    V2 {
+        certificates: Vec<CertificateInfo>,
+
+        /// Algorithms of the signatures of all signers
+        algorithms: Vec<SignatureAlgorithm>,
+
+        /// Digests of the apk contents signed by the signers
+        digests: Vec<ContentDigest>,
+    },
 
     /// APK signature scheme v3
     ///
     /// See: <https://source.android.com/docs/security/features/apksigning/v3>
     #[serde(rename = "v3")]
-    V3(Vec<CertificateInfo>),
+    V3 {
+        certificates: Vec<CertificateInfo>,
+
+        /// Signing certificate lineage, empty if the key was never rotated
+        lineage: Vec<LineageNode>,
+
+        /// Algorithms of the signatures of all signers
+        algorithms: Vec<SignatureAlgorithm>,
+
+        /// Digests of the apk contents signed by the signers
+        digests: Vec<ContentDigest>,
+    },
 
     /// APK signature scheme v3.1
     ///
     /// See: <https://source.android.com/docs/security/features/apksigning/v3-1>
     #[serde(rename = "v31")]
-    V31(Vec<CertificateInfo>),
+    V31 {
+        certificates: Vec<CertificateInfo>,
+
+        /// Signing certificate lineage, empty if the key was never rotated
+        lineage: Vec<LineageNode>,
+
+        /// Algorithms of the signatures of all signers
+        algorithms: Vec<SignatureAlgorithm>,
+
+        /// Digests of the apk contents signed by the signers
+        digests: Vec<ContentDigest>,
+    },
 
     /// APK signature scheme v4
     ///
@@ -70,11 +107,12 @@ pub enum Signature {
 
     /// Google Play Frosting Metadata
     ///
-    /// We just highlight the presence of the block, because the full structure is unknown to anyone in public space
+    /// Added by Google Play to the apks it delivers. The schema of the metadata is unknown to anyone
+    /// in public space, so only its protobuf fields are decoded, see [FrostingInfo].
     ///
     /// For more details you can inspect: <https://github.com/avast/apkverifier/blob/master/signingblock/frosting.go#L23>
     #[serde(rename = "google_play_frosting")]
-    GooglePlayFrosting,
+    Frosting(FrostingInfo),
 
     /// Some apk protector/parser, idk, seen in the wild
     ///
@@ -84,6 +122,13 @@ pub enum Signature {
     #[serde(rename = "vasdolly_v2")]
     VasDollyV2(String),
 
+    /// Block of the APK Signing Block with an ID we don't know yet
+    ///
+    /// See [ZipEntry::get_signing_block_entries](crate::ZipEntry::get_signing_block_entries)
+    /// for all blocks, including the known ones.
+    #[serde(rename = "unknown_block")]
+    UnknownBlock { id: u32, data: Vec<u8> },
+
     /// Got something that we don't know yet
     #[serde(rename = "unknown")]
     Unknown,
@@ -93,23 +138,211 @@ impl Signature {
     pub fn name(&self) -> String {
         match &self {
             Signature::V1(_) => "v1".to_owned(),
-            Signature::V2(_) => "v2".to_owned(),
-            Signature::V3(_) => "v3".to_owned(),
-            Signature::V31(_) => "v3.1".to_owned(),
+            Signature::V2 { .. } => "v2".to_owned(),
+            Signature::V3 { .. } => "v3".to_owned(),
+            Signature::V31 { .. } => "v3.1".to_owned(),
             Signature::V4 => "v4".to_owned(),
             Signature::ApkChannelBlock(_) => "APK Channel block".to_owned(),
             Signature::StampBlockV1(_) => "Stamp Block v1".to_owned(),
             Signature::StampBlockV2(_) => "Stamp Block v2".to_owned(),
             Signature::PackerNextGenV2(_) => "Packer NG v2".to_owned(),
-            Signature::GooglePlayFrosting => "Google Play Frosting".to_owned(),
+            Signature::Frosting(_) => "Google Play Frosting".to_owned(),
             Signature::VasDollyV2(_) => "v2-VasDolly".to_owned(),
+            Signature::UnknownBlock { id, .. } => format!("Unknown block 0x{id:08x}"),
             Signature::Unknown => "unknown".to_owned(),
         }
     }
 }
 
+/// Signature algorithm of the v2+ signing schemes, the digest algorithm is derived from it.
+///
+/// See: <https://xrefandroid.com/android-16.0.0_r2/xref/tools/apksig/src/main/java/com/android/apksig/internal/apk/SignatureAlgorithm.java>
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum SignatureAlgorithm {
+    #[serde(rename = "RSASSA-PSS-SHA256")]
+    RsaPssSha256,
+
+    #[serde(rename = "RSASSA-PSS-SHA512")]
+    RsaPssSha512,
+
+    #[serde(rename = "RSASSA-PKCS1-v1_5-SHA256")]
+    RsaPkcs1Sha256,
+
+    #[serde(rename = "RSASSA-PKCS1-v1_5-SHA512")]
+    RsaPkcs1Sha512,
+
+    #[serde(rename = "ECDSA-SHA256")]
+    EcdsaSha256,
+
+    #[serde(rename = "ECDSA-SHA512")]
+    EcdsaSha512,
+
+    #[serde(rename = "DSA-SHA256")]
+    DsaSha256,
+
+    /// Same as [SignatureAlgorithm::RsaPkcs1Sha256], but over the verity tree of the apk
+    #[serde(rename = "VERITY-RSASSA-PKCS1-v1_5-SHA256")]
+    VerityRsaPkcs1Sha256,
+
+    /// Same as [SignatureAlgorithm::EcdsaSha256], but over the verity tree of the apk
+    #[serde(rename = "VERITY-ECDSA-SHA256")]
+    VerityEcdsaSha256,
+
+    /// Same as [SignatureAlgorithm::DsaSha256], but over the verity tree of the apk
+    #[serde(rename = "VERITY-DSA-SHA256")]
+    VerityDsaSha256,
+
+    /// ID that Android doesn't know, such signatures are ignored by the verifier
+    #[serde(rename = "unknown")]
+    Unknown(u32),
+}
+
+impl SignatureAlgorithm {
+    /// Returns the algorithm by its ID in the signing block.
+    pub fn from_id(id: u32) -> SignatureAlgorithm {
+        match id {
+            0x0101 => SignatureAlgorithm::RsaPssSha256,
+            0x0102 => SignatureAlgorithm::RsaPssSha512,
+            0x0103 => SignatureAlgorithm::RsaPkcs1Sha256,
+            0x0104 => SignatureAlgorithm::RsaPkcs1Sha512,
+            0x0201 => SignatureAlgorithm::EcdsaSha256,
+            0x0202 => SignatureAlgorithm::EcdsaSha512,
+            0x0301 => SignatureAlgorithm::DsaSha256,
+            0x0421 => SignatureAlgorithm::VerityRsaPkcs1Sha256,
+            0x0423 => SignatureAlgorithm::VerityEcdsaSha256,
+            0x0425 => SignatureAlgorithm::VerityDsaSha256,
+            id => SignatureAlgorithm::Unknown(id),
+        }
+    }
+
+    /// Returns the ID of the algorithm in the signing block.
+    pub fn id(&self) -> u32 {
+        match self {
+            SignatureAlgorithm::RsaPssSha256 => 0x0101,
+            SignatureAlgorithm::RsaPssSha512 => 0x0102,
+            SignatureAlgorithm::RsaPkcs1Sha256 => 0x0103,
+            SignatureAlgorithm::RsaPkcs1Sha512 => 0x0104,
+            SignatureAlgorithm::EcdsaSha256 => 0x0201,
+            SignatureAlgorithm::EcdsaSha512 => 0x0202,
+            SignatureAlgorithm::DsaSha256 => 0x0301,
+            SignatureAlgorithm::VerityRsaPkcs1Sha256 => 0x0421,
+            SignatureAlgorithm::VerityEcdsaSha256 => 0x0423,
+            SignatureAlgorithm::VerityDsaSha256 => 0x0425,
+            SignatureAlgorithm::Unknown(id) => *id,
+        }
+    }
+
+    /// Human-readable name, as apksigner shows it.
+    pub fn name(&self) -> String {
+        match self {
+            SignatureAlgorithm::RsaPssSha256 => "RSASSA-PSS-SHA256".to_owned(),
+            SignatureAlgorithm::RsaPssSha512 => "RSASSA-PSS-SHA512".to_owned(),
+            SignatureAlgorithm::RsaPkcs1Sha256 => "RSASSA-PKCS1-v1_5-SHA256".to_owned(),
+            SignatureAlgorithm::RsaPkcs1Sha512 => "RSASSA-PKCS1-v1_5-SHA512".to_owned(),
+            SignatureAlgorithm::EcdsaSha256 => "ECDSA-SHA256".to_owned(),
+            SignatureAlgorithm::EcdsaSha512 => "ECDSA-SHA512".to_owned(),
+            SignatureAlgorithm::DsaSha256 => "DSA-SHA256".to_owned(),
+            SignatureAlgorithm::VerityRsaPkcs1Sha256 => {
+                "VERITY-RSASSA-PKCS1-v1_5-SHA256".to_owned()
+            }
+            SignatureAlgorithm::VerityEcdsaSha256 => "VERITY-ECDSA-SHA256".to_owned(),
+            SignatureAlgorithm::VerityDsaSha256 => "VERITY-DSA-SHA256".to_owned(),
+            SignatureAlgorithm::Unknown(id) => format!("unknown (0x{id:04x})"),
+        }
+    }
+
+    /// Checks if the algorithm is weak: DSA keys of Android are limited to 2048 bits
+    /// and apksigner no longer signs with them by default.
+    pub fn is_weak(&self) -> bool {
+        matches!(
+            self,
+            SignatureAlgorithm::DsaSha256 | SignatureAlgorithm::VerityDsaSha256
+        )
+    }
+}
+
+impl fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// Digest of the apk contents from the signed data of a v2+ signer.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ContentDigest {
+    pub algorithm: SignatureAlgorithm,
+
+    /// Lowercase hex
+    pub digest: String,
+}
+
+/// A signing certificate of the proof-of-rotation attribute of v3 and v3.1 signatures.
+///
+/// Nodes are ordered from the oldest certificate to the current one, each node is signed
+/// by the key of the previous one. The flags are the capabilities the newer keys grant to
+/// this one, so an app can still trust data or permissions of the rotated key.
+///
+/// See: <https://xrefandroid.com/android-16.0.0_r2/xref/tools/apksig/src/main/java/com/android/apksig/SigningCertificateLineage.java>
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LineageNode {
+    pub certificate: CertificateInfo,
+
+    /// Capability flags, see the `CAPABILITY_*` constants
+    pub flags: u32,
+
+    /// Algorithm the previous key signed this node with, `0` for the first node
+    pub signature_algorithm_id: u32,
+
+    /// Algorithm the key of this node signs the next node with
+    pub signed_signature_algorithm_id: u32,
+}
+
+impl LineageNode {
+    /// The data of the app signed by this key is still trusted (`installed_data`)
+    pub const CAPABILITY_INSTALLED_DATA: u32 = 1;
+
+    /// Apps signed by this key may share the user id (`shared_uid`)
+    pub const CAPABILITY_SHARED_USER_ID: u32 = 2;
+
+    /// Signature permissions granted to this key still apply (`permission`)
+    pub const CAPABILITY_PERMISSION: u32 = 4;
+
+    /// Updates signed by this key are allowed (`rollback`)
+    pub const CAPABILITY_ROLLBACK: u32 = 8;
+
+    /// Authentication of this key for other apps (`auth`)
+    pub const CAPABILITY_AUTH: u32 = 16;
+
+    /// Checks if the capability flag is set.
+    #[inline]
+    pub fn has_capability(&self, capability: u32) -> bool {
+        self.flags & capability == capability
+    }
+
+    /// Names of the set capabilities, as `apksigner lineage --print-certs` shows them.
+    #[inline]
+    pub fn capabilities(&self) -> Vec<&'static str> {
+        Self::capability_names(self.flags)
+    }
+
+    /// Names of the capabilities set in the flags.
+    pub fn capability_names(flags: u32) -> Vec<&'static str> {
+        [
+            (Self::CAPABILITY_INSTALLED_DATA, "installed_data"),
+            (Self::CAPABILITY_SHARED_USER_ID, "shared_uid"),
+            (Self::CAPABILITY_PERMISSION, "permission"),
+            (Self::CAPABILITY_ROLLBACK, "rollback"),
+            (Self::CAPABILITY_AUTH, "auth"),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| flags & flag == *flag)
+        .map(|(_, name)| name)
+        .collect()
+    }
+}
+
 /// Represents detailed information about an APK signing certificate.
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
 pub struct CertificateInfo {
     /// The serial number of the certificate.
     pub serial_number: String,
@@ -137,4 +370,308 @@ pub struct CertificateInfo {
 
     /// SHA-256 fingerprint of the certificate.
     pub sha256_fingerprint: String,
+
+    /// SHA-256 of the certificate's `SubjectPublicKeyInfo`, stays the same when the key
+    /// is reissued in a new certificate.
+    pub public_key_sha256: String,
+
+    /// SHA-256 of the public key stored in the v2/v3 signer next to the certificates.
+    ///
+    /// Android verifies the signature with this key and requires it to match the first
+    /// certificate, so it is set only for the first certificate of the signer.
+    pub signer_public_key_sha256: Option<String>,
+
+    /// X.509 version: 1, 2 or 3.
+    #[serde(default)]
+    pub version: u8,
+
+    /// Algorithm of the public key: `RSA`, `EC`, `DSA`, `Ed25519` or the OID for the others.
+    #[serde(default)]
+    pub key_algorithm: String,
+
+    /// Size of the public key in bits: modulus for RSA and DSA, curve for EC.
+    #[serde(default)]
+    pub key_size: Option<u32>,
+
+    /// Names of the X.509 v3 extensions, OIDs for the unknown ones.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+
+    /// Whether the subject and the issuer are the same, the signature itself isn't checked.
+    ///
+    /// Android doesn't use certificate authorities, so nearly all apks are self-signed.
+    #[serde(default)]
+    pub is_self_signed: bool,
+
+    /// Whether it is a well-known debug or test certificate: `CN=Android Debug` generated by
+    /// the Android SDK or the public test keys of AOSP.
+    ///
+    /// Anyone can sign an apk with them, Google Play rejects such apks.
+    #[serde(default)]
+    pub is_debug_cert: bool,
+
+    /// Whether it is the certificate of a signer: the first certificate of a v2/v3 signer
+    /// or the one a `SignerInfo` of the v1 signature refers to.
+    ///
+    /// The others are the rest of the chain or extra certificates of the PKCS#7 bag,
+    /// they aren't used to verify anything and can be added by anyone.
+    #[serde(default)]
+    pub is_signer: bool,
+
+    /// DER encoding of the certificate, not serialized to keep reports small.
+    #[serde(skip)]
+    pub der: Vec<u8>,
+}
+
+impl CertificateInfo {
+    /// Returns `true` if the public key of the signer doesn't match the key of the certificate.
+    pub fn has_public_key_mismatch(&self) -> bool {
+        self.signer_public_key_sha256
+            .as_ref()
+            .is_some_and(|key| *key != self.public_key_sha256)
+    }
+
+    /// MD5 fingerprint of the certificate in the given style.
+    ///
+    /// Fingerprints that are not hex (certificates deserialized from elsewhere)
+    /// are returned as is, see [FingerprintStyle::format].
+    pub fn fingerprint_md5_formatted(&self, style: FingerprintStyle) -> String {
+        style
+            .format(&self.md5_fingerprint)
+            .unwrap_or_else(|| self.md5_fingerprint.clone())
+    }
+
+    /// SHA-1 fingerprint of the certificate in the given style, see
+    /// [CertificateInfo::fingerprint_md5_formatted].
+    pub fn fingerprint_sha1_formatted(&self, style: FingerprintStyle) -> String {
+        style
+            .format(&self.sha1_fingerprint)
+            .unwrap_or_else(|| self.sha1_fingerprint.clone())
+    }
+
+    /// SHA-256 fingerprint of the certificate in the given style, see
+    /// [CertificateInfo::fingerprint_md5_formatted].
+    pub fn fingerprint_sha256_formatted(&self, style: FingerprintStyle) -> String {
+        style
+            .format(&self.sha256_fingerprint)
+            .unwrap_or_else(|| self.sha256_fingerprint.clone())
+    }
+
+    /// PEM encoding of the certificate, as written by `openssl x509`.
+    pub fn to_pem(&self) -> String {
+        let encoded = STANDARD.encode(&self.der);
+        let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+        // base64 is ascii, so the chunks are valid utf-8
+        for line in encoded.as_bytes().chunks(64) {
+            pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+            pem.push('\n');
+        }
+        pem.push_str("-----END CERTIFICATE-----\n");
+        pem
+    }
+}
+
+/// Text encoding of certificate fingerprints, different tools expect different ones.
+#[derive(Debug, Default, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FingerprintStyle {
+    /// Lowercase hex: `a40da80a59d1...` (apksigner, Firebase)
+    #[default]
+    Hex,
+
+    /// Uppercase hex separated by colons: `A4:0D:A8:0A:59:D1:...` (keytool, Play Console)
+    Colon,
+
+    /// Standard base64 of the digest: `pA2oClnR...` (certificate pinning)
+    Base64,
+}
+
+impl FingerprintStyle {
+    /// Names of the styles, as accepted by [FingerprintStyle::from_str].
+    pub const NAMES: [&str; 3] = ["hex", "colon", "base64"];
+
+    /// Formats the hex fingerprint as stored in [CertificateInfo].
+    ///
+    /// Returns [None] if the input is not a hex string of whole bytes.
+    pub fn format(&self, hex: &str) -> Option<String> {
+        if !hex.len().is_multiple_of(2) || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let formatted = match self {
+            FingerprintStyle::Hex => hex.to_ascii_lowercase(),
+            FingerprintStyle::Colon => hex
+                .to_ascii_uppercase()
+                .as_bytes()
+                .chunks(2)
+                .map(|pair| std::str::from_utf8(pair).ok())
+                .collect::<Option<Vec<_>>>()?
+                .join(":"),
+            FingerprintStyle::Base64 => {
+                let digest = hex
+                    .as_bytes()
+                    .chunks(2)
+                    .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+                    .collect::<Option<Vec<u8>>>()?;
+
+                STANDARD.encode(digest)
+            }
+        };
+
+        Some(formatted)
+    }
+}
+
+impl FromStr for FingerprintStyle {
+    type Err = CertificateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hex" => Ok(FingerprintStyle::Hex),
+            "colon" => Ok(FingerprintStyle::Colon),
+            "base64" => Ok(FingerprintStyle::Base64),
+            _ => Err(CertificateError::UnknownFingerprintStyle(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA1: &str = "a40da80a59d170caa950cf15c18c454d47a39b26";
+
+    #[test]
+    fn test_fingerprint_hex() {
+        assert_eq!(
+            FingerprintStyle::Hex.format("A40DA80A").as_deref(),
+            Some("a40da80a")
+        );
+        assert_eq!(FingerprintStyle::Hex.format(SHA1).as_deref(), Some(SHA1));
+    }
+
+    #[test]
+    fn test_fingerprint_colon() {
+        assert_eq!(
+            FingerprintStyle::Colon.format("a40da80a").as_deref(),
+            Some("A4:0D:A8:0A")
+        );
+        assert_eq!(FingerprintStyle::Colon.format("").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_fingerprint_base64() {
+        assert_eq!(
+            FingerprintStyle::Base64.format(SHA1).as_deref(),
+            Some("pA2oClnRcMqpUM8VwYxFTUejmyY=")
+        );
+        assert_eq!(
+            FingerprintStyle::Base64.format("A40DA80A").as_deref(),
+            Some("pA2oCg==")
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_not_hex() {
+        for style in [
+            FingerprintStyle::Hex,
+            FingerprintStyle::Colon,
+            FingerprintStyle::Base64,
+        ] {
+            assert_eq!(style.format("a40"), None);
+            assert_eq!(style.format("a4zz"), None);
+            assert_eq!(style.format("a4:0d"), None);
+        }
+    }
+
+    const ALGORITHMS: &[(u32, SignatureAlgorithm, &str)] = &[
+        (
+            0x0101,
+            SignatureAlgorithm::RsaPssSha256,
+            "RSASSA-PSS-SHA256",
+        ),
+        (
+            0x0102,
+            SignatureAlgorithm::RsaPssSha512,
+            "RSASSA-PSS-SHA512",
+        ),
+        (
+            0x0103,
+            SignatureAlgorithm::RsaPkcs1Sha256,
+            "RSASSA-PKCS1-v1_5-SHA256",
+        ),
+        (
+            0x0104,
+            SignatureAlgorithm::RsaPkcs1Sha512,
+            "RSASSA-PKCS1-v1_5-SHA512",
+        ),
+        (0x0201, SignatureAlgorithm::EcdsaSha256, "ECDSA-SHA256"),
+        (0x0202, SignatureAlgorithm::EcdsaSha512, "ECDSA-SHA512"),
+        (0x0301, SignatureAlgorithm::DsaSha256, "DSA-SHA256"),
+        (
+            0x0421,
+            SignatureAlgorithm::VerityRsaPkcs1Sha256,
+            "VERITY-RSASSA-PKCS1-v1_5-SHA256",
+        ),
+        (
+            0x0423,
+            SignatureAlgorithm::VerityEcdsaSha256,
+            "VERITY-ECDSA-SHA256",
+        ),
+        (
+            0x0425,
+            SignatureAlgorithm::VerityDsaSha256,
+            "VERITY-DSA-SHA256",
+        ),
+    ];
+
+    #[test]
+    fn test_signature_algorithm_ids() {
+        for (id, algorithm, name) in ALGORITHMS {
+            assert_eq!(SignatureAlgorithm::from_id(*id), *algorithm);
+            assert_eq!(algorithm.id(), *id);
+            assert_eq!(algorithm.name(), *name);
+            assert_eq!(algorithm.to_string(), *name);
+        }
+    }
+
+    #[test]
+    fn test_signature_algorithm_unknown() {
+        // the removed verity rsa pss id and a made up one
+        for id in [0x0422, 0x9999] {
+            let algorithm = SignatureAlgorithm::from_id(id);
+            assert_eq!(algorithm, SignatureAlgorithm::Unknown(id));
+            assert_eq!(algorithm.id(), id);
+            assert!(!algorithm.is_weak());
+        }
+        assert_eq!(
+            SignatureAlgorithm::Unknown(0x0422).name(),
+            "unknown (0x0422)"
+        );
+    }
+
+    #[test]
+    fn test_signature_algorithm_weak() {
+        let weak: Vec<SignatureAlgorithm> = ALGORITHMS
+            .iter()
+            .map(|(_, algorithm, _)| *algorithm)
+            .filter(SignatureAlgorithm::is_weak)
+            .collect();
+
+        assert_eq!(
+            weak,
+            [
+                SignatureAlgorithm::DsaSha256,
+                SignatureAlgorithm::VerityDsaSha256
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_style_names() {
+        for name in FingerprintStyle::NAMES {
+            assert!(name.parse::<FingerprintStyle>().is_ok());
+        }
+        assert!("sha256".parse::<FingerprintStyle>().is_err());
+    }
 }
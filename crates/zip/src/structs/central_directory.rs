@@ -58,15 +58,21 @@ pub(crate) struct CentralDirectoryEntry {
 
     pub(crate) file_name: Arc<str>,
 
-    #[allow(unused)]
     pub(crate) extra_field: Arc<[u8]>,
 
-    #[allow(unused)]
     pub(crate) file_comment: Arc<[u8]>,
 }
 
 impl CentralDirectoryEntry {
-    const MAGIC: u32 = 0x02014b50;
+    pub(crate) const MAGIC: u32 = 0x02014b50;
+
+    /// Get structure size
+    ///
+    /// 4 (MAGIC) + 42 (DATA) + file_name length + extra field length + file comment length
+    #[inline]
+    pub(crate) fn size(&self) -> usize {
+        46 + self.file_name.len() + self.extra_field.len() + self.file_comment.len()
+    }
 
     #[inline(always)]
     fn parse(input: &mut &[u8]) -> ModalResult<CentralDirectoryEntry> {
@@ -145,6 +151,9 @@ impl CentralDirectoryEntry {
 #[derive(Debug)]
 pub(crate) struct CentralDirectory {
     pub(crate) entries: AHashMap<Arc<str>, CentralDirectoryEntry>,
+
+    /// Names of all records in the order of the central directory, duplicates included
+    pub(crate) names: Vec<Arc<str>>,
 }
 
 impl CentralDirectory {
@@ -157,16 +166,22 @@ impl CentralDirectory {
             .get(eocd.central_dir_offset as usize..)
             .ok_or(ErrMode::Incomplete(Needed::Unknown))?;
 
-        let entries = repeat::<_, CentralDirectoryEntry, Vec<CentralDirectoryEntry>, _, _>(
+        let records = repeat::<_, CentralDirectoryEntry, Vec<CentralDirectoryEntry>, _, _>(
             0..,
             CentralDirectoryEntry::parse,
         )
-        .parse_next(&mut input)?
-        .into_iter()
-        .map(|entry| (Arc::clone(&entry.file_name), entry))
-        .collect();
-
-        Ok(CentralDirectory { entries })
+        .parse_next(&mut input)?;
+
+        let names = records
+            .iter()
+            .map(|entry| Arc::clone(&entry.file_name))
+            .collect();
+        let entries = records
+            .into_iter()
+            .map(|entry| (Arc::clone(&entry.file_name), entry))
+            .collect();
+
+        Ok(CentralDirectory { entries, names })
     }
 }
 
@@ -286,6 +301,31 @@ mod tests {
         assert_eq!(b.file_comment.as_ref(), b"comment field");
     }
 
+    #[test]
+    fn test_parse_duplicate_entries() {
+        let mut data = Vec::new();
+        for name in ["a.txt", "b.txt", "a.txt"] {
+            data.extend_from_slice(&make_cde_record(name, b"", b"", 10, 20, 30));
+        }
+
+        let eocd = EndOfCentralDirectory {
+            disk_number: 0,
+            central_dir_start_disk: 0,
+            entries_on_this_disk: 0,
+            total_entries: 0,
+            central_dir_size: data.len() as u32,
+            central_dir_offset: 0,
+            comment_length: 0,
+            comment: Arc::from([]),
+        };
+
+        let cd = CentralDirectory::parse(&data, &eocd).unwrap();
+        assert_eq!(cd.entries.len(), 2);
+
+        let names: Vec<&str> = cd.names.iter().map(|name| name.as_ref()).collect();
+        assert_eq!(names, vec!["a.txt", "b.txt", "a.txt"]);
+    }
+
     #[test]
     fn test_parse_central_directory_with_offset() {
         let entry = make_cde_record("offset.txt", b"", b"", 100, 200, 300);
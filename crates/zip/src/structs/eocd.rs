@@ -27,7 +27,6 @@ pub(crate) struct EndOfCentralDirectory {
     #[allow(unused)]
     pub(crate) comment_length: u16,
 
-    #[allow(unused)]
     pub(crate) comment: Arc<[u8]>,
 }
 
@@ -76,6 +75,17 @@ impl EndOfCentralDirectory {
         })
     }
 
+    /// Size of the record with the comment.
+    #[inline]
+    pub(crate) fn size(&self) -> usize {
+        22 + self.comment.len()
+    }
+
+    /// Offsets of all EOCD magics in the file, from the start.
+    pub(crate) fn find_all(input: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        memmem::find_iter(input, &Self::MAGIC)
+    }
+
     /// Search EOCD magic from the end of the file
     pub(crate) fn find_eocd(input: &[u8], chunk_size: usize) -> Option<usize> {
         let mut end = input.len();
@@ -213,4 +223,20 @@ mod tests {
             "expected parse error for bad comment length"
         );
     }
+
+    #[test]
+    fn test_find_all_eocd() {
+        let mut data = make_eocd(b"first");
+        data.extend_from_slice(b"payload");
+        let second = data.len();
+        data.extend_from_slice(&make_eocd(&[]));
+
+        assert_eq!(
+            EndOfCentralDirectory::find_all(&data).collect::<Vec<_>>(),
+            vec![0, second]
+        );
+
+        let eocd = EndOfCentralDirectory::parse(&mut &data[..]).unwrap();
+        assert_eq!(eocd.size(), 22 + 5);
+    }
 }
@@ -0,0 +1,54 @@
+/*
+ * C ABI for the apk-info parser.
+ *
+ * - functions that can fail return NULL, the reason is available with apk_last_error();
+ * - returned strings are owned by the caller and must be released with apk_string_free();
+ * - the handle returned by apk_open() must be released with apk_free().
+ *
+ * A handle can be shared between threads.
+ *
+ * Build with `cargo build --profile release-ffi`: it keeps unwinding, so an internal panic
+ * is reported by apk_last_error(), other profiles abort the process.
+ */
+
+#ifndef APK_INFO_H
+#define APK_INFO_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct Apk Apk;
+
+/* Opens and parses the apk file, path is UTF-8. */
+Apk *apk_open(const char *path);
+
+/* Parses the apk from memory, the data is copied. */
+Apk *apk_open_memory(const uint8_t *data, size_t size);
+
+/* Releases the handle, NULL is ignored. */
+void apk_free(Apk *apk);
+
+/* Returns the package name, NULL if the manifest has no package name or on error. */
+char *apk_get_package_name(const Apk *apk);
+
+/* Returns the requested permissions as a JSON array of strings. */
+char *apk_get_permissions_json(const Apk *apk);
+
+/*
+ * Returns the message of the last error of the calling thread, NULL if the last call succeeded.
+ * The string stays valid until the next call on the same thread and must not be freed.
+ */
+const char *apk_last_error(void);
+
+/* Releases a string returned by the library, NULL is ignored. */
+void apk_string_free(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* APK_INFO_H */
@@ -0,0 +1,202 @@
+//! C ABI for the apk-info parser.
+//!
+//! Lets C, C++, Go or C# pipelines link against the parser without Python.
+//! The declarations are in `include/apk_info.h`.
+//!
+//! Conventions:
+//! - functions that can fail return `NULL`, the reason is available with [apk_last_error];
+//! - returned strings are owned by the caller and must be released with [apk_string_free];
+//! - the handle returned by [apk_open] must be released with [apk_free].
+//!
+//! A handle can be shared between threads, the parser caches results internally.
+//!
+//! Panics are caught only when the library is built with unwinding: the `release-ffi`
+//! profile, the `release` one aborts the process.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+use apk_info::Apk;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl Into<String>) {
+    // interior nul bytes can't be represented in a C string
+    let message = message.into().replace('\0', " ");
+    LAST_ERROR.with(|error| *error.borrow_mut() = CString::new(message).ok());
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|error| *error.borrow_mut() = None);
+}
+
+/// Runs the closure, a panic is reported as an error instead of unwinding into C.
+///
+/// Has effect only with `panic = "unwind"`, see the `release-ffi` profile.
+fn guard<T>(f: impl FnOnce() -> Result<T, String>) -> Option<T> {
+    clear_last_error();
+
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            set_last_error(e);
+            None
+        }
+        Err(_) => {
+            set_last_error("internal error: parser panicked");
+            None
+        }
+    }
+}
+
+fn into_c_string(value: String) -> Result<*mut c_char, String> {
+    CString::new(value)
+        .map(CString::into_raw)
+        .map_err(|e| e.to_string())
+}
+
+/// # Safety
+///
+/// `apk` must be `NULL` or a handle returned by [apk_open] that wasn't freed yet.
+unsafe fn handle<'a>(apk: *const Apk) -> Result<&'a Apk, String> {
+    // SAFETY: guaranteed by the caller
+    unsafe { apk.as_ref() }.ok_or_else(|| "apk handle is NULL".to_string())
+}
+
+/// Opens and parses the apk file.
+///
+/// Returns `NULL` if the file can't be parsed, see [apk_last_error].
+///
+/// # Safety
+///
+/// `path` must be `NULL` or a valid nul-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apk_open(path: *const c_char) -> *mut Apk {
+    guard(|| {
+        if path.is_null() {
+            return Err("path is NULL".to_string());
+        }
+
+        // SAFETY: guaranteed by the caller
+        let path = unsafe { CStr::from_ptr(path) }
+            .to_str()
+            .map_err(|e| format!("path is not valid UTF-8: {e}"))?;
+
+        Apk::new(path)
+            .map(|apk| Box::into_raw(Box::new(apk)))
+            .map_err(|e| e.display_chain())
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Parses the apk from memory, the data is copied.
+///
+/// Returns `NULL` if the data can't be parsed, see [apk_last_error].
+///
+/// # Safety
+///
+/// `data` must point to `size` readable bytes, it may be `NULL` only if `size` is `0`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apk_open_memory(data: *const u8, size: usize) -> *mut Apk {
+    guard(|| {
+        let data = if size == 0 {
+            Vec::new()
+        } else if data.is_null() {
+            return Err("data is NULL".to_string());
+        } else {
+            // SAFETY: guaranteed by the caller
+            unsafe { std::slice::from_raw_parts(data, size) }.to_vec()
+        };
+
+        Apk::from_bytes(data)
+            .map(|apk| Box::into_raw(Box::new(apk)))
+            .map_err(|e| e.display_chain())
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Releases the handle, `NULL` is ignored.
+///
+/// # Safety
+///
+/// `apk` must be `NULL` or a handle returned by [apk_open] that wasn't freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apk_free(apk: *mut Apk) {
+    if !apk.is_null() {
+        // SAFETY: guaranteed by the caller
+        drop(unsafe { Box::from_raw(apk) });
+    }
+}
+
+/// Returns the package name.
+///
+/// Returns `NULL` if the manifest has no package name or on error, see [apk_last_error].
+///
+/// # Safety
+///
+/// `apk` must be `NULL` or a handle returned by [apk_open] that wasn't freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apk_get_package_name(apk: *const Apk) -> *mut c_char {
+    guard(|| {
+        // SAFETY: guaranteed by the caller
+        let apk = unsafe { handle(apk) }?;
+
+        match apk.get_package_name() {
+            Some(package_name) => into_c_string(package_name),
+            None => Ok(ptr::null_mut()),
+        }
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Returns the requested permissions as a JSON array of strings: `["android.permission.INTERNET"]`.
+///
+/// Returns `NULL` on error, see [apk_last_error].
+///
+/// # Safety
+///
+/// `apk` must be `NULL` or a handle returned by [apk_open] that wasn't freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apk_get_permissions_json(apk: *const Apk) -> *mut c_char {
+    guard(|| {
+        // SAFETY: guaranteed by the caller
+        let apk = unsafe { handle(apk) }?;
+        let permissions: Vec<&str> = apk.get_permissions().collect();
+
+        serde_json::to_string(&permissions)
+            .map_err(|e| e.to_string())
+            .and_then(into_c_string)
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// Returns the message of the last error of the calling thread, `NULL` if the last call succeeded.
+///
+/// The string is owned by the library and stays valid until the next call on the same thread,
+/// it must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn apk_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Releases a string returned by the library, `NULL` is ignored.
+///
+/// # Safety
+///
+/// `value` must be `NULL` or a string returned by the library that wasn't freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn apk_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        // SAFETY: guaranteed by the caller
+        drop(unsafe { CString::from_raw(value) });
+    }
+}
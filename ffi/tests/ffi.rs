@@ -0,0 +1,48 @@
+//! Calls the C ABI the way a C consumer would.
+
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use apk_info_ffi::*;
+
+fn last_error() -> Option<String> {
+    let error = apk_last_error();
+    (!error.is_null()).then(|| {
+        unsafe { CStr::from_ptr(error) }
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+#[test]
+fn test_open_missing_file() {
+    let path = CString::new("/nonexistent/file.apk").unwrap();
+    let apk = unsafe { apk_open(path.as_ptr()) };
+
+    assert!(apk.is_null());
+    assert!(last_error().is_some());
+}
+
+#[test]
+fn test_open_invalid_data() {
+    let data = b"not a zip archive";
+    let apk = unsafe { apk_open_memory(data.as_ptr(), data.len()) };
+
+    assert!(apk.is_null());
+    assert!(last_error().is_some());
+}
+
+#[test]
+fn test_null_arguments() {
+    assert!(unsafe { apk_open(ptr::null()) }.is_null());
+    assert_eq!(last_error().as_deref(), Some("path is NULL"));
+
+    assert!(unsafe { apk_get_package_name(ptr::null()) }.is_null());
+    assert_eq!(last_error().as_deref(), Some("apk handle is NULL"));
+
+    // must be no-ops
+    unsafe {
+        apk_free(ptr::null_mut());
+        apk_string_free(ptr::null_mut());
+    }
+}
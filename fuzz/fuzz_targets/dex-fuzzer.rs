@@ -0,0 +1,17 @@
+#![no_main]
+
+use apk_info_dex::Dex;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(dex) = Dex::new(data.to_vec()) else {
+        return;
+    };
+
+    for _ in dex.strings() {}
+
+    for class in dex.classes() {
+        let _ = dex.get_class_name(class.class_idx);
+        let _ = dex.get_class_data(class);
+    }
+});
@@ -1,6 +1,7 @@
+from collections.abc import Callable, Iterator
 from dataclasses import dataclass
-from pathlib import PurePath
-from typing import Literal
+from pathlib import Path, PurePath
+from typing import Any, Literal
 
 __version__: str
 """
@@ -17,9 +18,14 @@ class APKError(Exception):
 class APK:
     """
     APK class, the main entrypoint to use `apk-info` library.
+
+    Instances are thread-safe: the GIL is released while the file, signatures and dex
+    files are parsed, so several APKs can be processed concurrently from a thread pool,
+    and a single instance can be shared between threads. Closing an instance while
+    another thread is still using it raises `RuntimeError`.
     """
 
-    def __init__(self, path: str | PurePath) -> None:
+    def __init__(self, path: str | PurePath, mmap: bool = False) -> None:
         """
         Create a new APK instance
 
@@ -27,6 +33,10 @@ class APK:
         ----------
         path : str | PurePath
             Path to the APK file on disk
+        mmap : bool
+            Map the file into memory instead of reading it, only the entries that are read
+            are loaded from disk. The file must not be modified or truncated while the
+            instance is open, otherwise the process may crash
 
         Raises
         ------
@@ -41,6 +51,66 @@ class APK:
         """
         ...
 
+    @staticmethod
+    def from_bytes(data: bytes) -> APK:
+        """
+        Create a new APK instance from the data already in memory
+
+        Useful when the APK doesn't come from disk, for example from object storage.
+
+        Parameters
+        ----------
+        data : bytes
+            Contents of the APK file
+
+        Raises
+        ------
+        APKError
+            If the parsing failed
+
+        Examples
+        --------
+
+        ```python
+        body = s3.get_object(Bucket="samples", Key=key)["Body"].read()
+        apk = APK.from_bytes(body)
+        ```
+        """
+        ...
+
+    def close(self) -> None:
+        """
+        Release the file, the instance can't be used afterwards
+
+        Signatures and dex files are parsed only on first access, so a closed instance
+        doesn't keep them in memory either. Calling any other method of a closed
+        instance raises `ValueError`, closing it again does nothing.
+        """
+        ...
+
+    @property
+    def closed(self) -> bool:
+        """
+        Whether the instance was closed
+        """
+        ...
+
+    def __enter__(self) -> APK:
+        """
+        Use the instance as a context manager, it is closed on exit
+
+        Examples
+        --------
+
+        ```python
+        with APK("./file.apk") as apk:
+            print(apk.get_package_name())
+        ```
+        """
+        ...
+
+    def __exit__(self, exc_type: object, exc_value: object, traceback: object) -> bool: ...
+
     def read(self, filename: str) -> tuple[bytes, FileCompressionType]:
         """
         Read raw data for the filename in the zip archive
@@ -63,13 +133,58 @@ class APK:
         ```python
         apk = APK("./file")
         data, compression = apk.read("AndroidManifest.xml")
-        print(compression)
+        if compression.is_tampered:
+            print(f"compression method is tampered: {compression}")
         with open("AndroidManifest.xml", "wb") as fd:
             fd.write(data)
         ```
         """
         ...
 
+    def get_sha256(self) -> str:
+        """
+        SHA256 of the APK file, lowercase hex, as VirusTotal and most feeds identify it
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        print(f"https://www.virustotal.com/gui/file/{apk.get_sha256()}")
+        ```
+        """
+        ...
+
+    def get_sha1(self) -> str:
+        """
+        SHA1 of the APK file, lowercase hex
+        """
+        ...
+
+    def get_md5(self) -> str:
+        """
+        MD5 of the APK file, lowercase hex
+        """
+        ...
+
+    def iter_files(self) -> Iterator[tuple[str, int, FileCompressionType]]:
+        """
+        Iterate over files of the APK without reading their contents
+
+        Yields `(name, uncompressed_size, compression)` for every file, directories are
+        skipped. Metadata of an entry is read only when the iterator reaches it.
+
+        Examples
+        --------
+
+        ```python
+        for name, size, compression in apk.iter_files():
+            if compression.is_tampered:
+                print(f"{name} ({size} bytes) has tampered headers")
+        ```
+        """
+        ...
+
     def namelist(self) -> list[str]:
         """
         The list of files contained in the APK, obtained from the central directory (zip)
@@ -85,6 +200,25 @@ class APK:
         """
         ...
 
+    def get_files_normalized(self) -> list[NormalizedFile]:
+        """
+        Sorted and de-duplicated file listing with all parent directories
+
+        Directories missing from the archive are synthesized, every entry carries
+        the number of central directory records with its name.
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        for file in apk.get_files_normalized():
+            if file.count > 1:
+                print(f"{file.name} is duplicated {file.count} times")
+        ```
+        """
+        ...
+
     def is_multidex(self) -> bool:
         """
         Checks if the APK has multiple `classes.dex` files or not
@@ -98,6 +232,58 @@ class APK:
         """
         ...
 
+    def dex_summaries(self) -> list[DexSummary]:
+        """
+        Header values and table sizes of every `classes*.dex` file
+
+        Raises
+        ------
+        APKError
+            If any dex file can't be parsed
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        for dex in apk.dex_summaries():
+            if not dex.checksum_valid:
+                print(f"{dex.name} was modified after build")
+            print(dex.name, dex.map_items.get("code_item", 0))
+        ```
+        """
+        ...
+
+    def get_dex_strings(self) -> list[str]:
+        """
+        Strings of all `classes*.dex` files, dex files that can't be parsed are skipped
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        urls = [s for s in apk.get_dex_strings() if s.startswith("http")]
+        ```
+        """
+        ...
+
+    def get_dex_classes(self) -> list[str]:
+        """
+        Java names of the classes defined in all `classes*.dex` files: `com.example.MainActivity`
+
+        Dex files that can't be parsed are skipped.
+        """
+        ...
+
+    def get_dex_methods(self) -> list[str]:
+        """
+        Signatures of the methods defined or called in all `classes*.dex` files: `Landroid/app/Activity;->finish()V`
+
+        Dex files that can't be parsed are skipped.
+        """
+        ...
+
     def get_xml_string(self) -> str:
         """
         Converts the internal xml representation of the `AndroidManifest.xml` to a human readable format
@@ -109,6 +295,40 @@ class APK:
         """
         ...
 
+    def get_manifest_xml(self) -> str:
+        """
+        Decoded `AndroidManifest.xml` as text, same as `get_xml_string`
+
+        Resource references in attribute values are resolved against `resources.arsc`.
+
+        Examples
+        --------
+
+        ```python
+        with open("AndroidManifest.xml", "w") as fd:
+            fd.write(apk.get_manifest_xml())
+        ```
+        """
+        ...
+
+    def get_manifest_dict(self) -> dict[str, Any]:
+        """
+        Decoded `AndroidManifest.xml` as nested dicts
+
+        Every element is `{"tag": str, "attributes": dict[str, str], "children": list[dict]}`,
+        attribute names keep their namespace prefix: `android:name`.
+
+        Examples
+        --------
+
+        ```python
+        manifest = apk.get_manifest_dict()
+        application = next(c for c in manifest["children"] if c["tag"] == "application")
+        print(application["attributes"].get("android:debuggable"))
+        ```
+        """
+        ...
+
     def get_resource_value(self, name: str) -> str | None:
         """
         An auxiliary method that allows you to get a value from a reference to a resource
@@ -244,7 +464,8 @@ class APK:
         Notes
         -----
         The automatic conversion to `int` was not done on purpose,
-        because there is no certainty that malware will not try to insert random values there
+        because there is no certainty that malware will not try to insert random values there,
+        use `get_version_code_u64` for the parsed value
 
         Returns
         -------
@@ -253,6 +474,28 @@ class APK:
         """
         ...
 
+    def get_version_code_u64(self) -> int | None:
+        """
+        Retrieves the version code as a number, `versionCodeMajor` makes up the upper 32 bits.
+
+        See: <a href="https://developer.android.com/reference/android/content/pm/PackageInfo#getLongVersionCode()" target="_blank">https://developer.android.com/reference/android/content/pm/PackageInfo#getLongVersionCode()</a>
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        print(apk.get_version_code_u64())
+        2025101912
+        ```
+
+        Returns
+        -------
+        int | None
+            The version code if it is a decimal or hex number, otherwise None
+        """
+        ...
+
     def get_version_name(self) -> str | None:
         """
         Retrieves the human-readable application version name.
@@ -434,6 +677,30 @@ class APK:
         """
         ...
 
+    def is_debuggable(self) -> bool | None:
+        """
+        Parses the `android:debuggable` attribute from `<application>`.
+
+        Returns
+        -------
+        bool | None
+            The value if it is `true`/`false` in any case or `1`/`0`, otherwise None.
+        """
+        ...
+
+    def get_application_test_only(self) -> Literal["true", "false"] | None:
+        """
+        Extracts the `android:testOnly` attribute from `<application>`.
+
+        See: <a href="https://developer.android.com/guide/topics/manifest/application-element#testOnly" target="_blank">https://developer.android.com/guide/topics/manifest/application-element#testOnly</a>
+
+        Returns
+        -------
+        str | None
+            "true" or "false" if declared, otherwise None.
+        """
+        ...
+
     def get_application_description(self) -> str | None:
         """
         Extracts and resolve the `android:description` attribute from `<application>`.
@@ -459,7 +726,7 @@ class APK:
 
         Notes
         ----
-        There is no way to choose a resolution yet, it will be implemented in the future.
+        Returns the path for the default configuration, use `get_icon_bytes` to choose a density.
 
         Examples
         --------
@@ -480,6 +747,37 @@ class APK:
         """
         ...
 
+    def get_icon_bytes(self, density: int | None = None) -> tuple[bytes, IconKind] | None:
+        """
+        Resolves the application icon to a png, webp or jpeg bitmap
+
+        Adaptive icons have no bitmap of the whole icon, their foreground layer is returned instead.
+
+        Parameters
+        ----------
+        density : int | None
+            Screen density in dpi (`480` for xxhdpi). The closest bitmap at or above it is chosen,
+            the largest one if not given.
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        icon = apk.get_icon_bytes(480)
+        if icon:
+            data, kind = icon
+            with open(f"icon.{kind.format}", "wb") as fd:
+                fd.write(data)
+        ```
+
+        Returns
+        -------
+        tuple[bytes, IconKind] | None
+            The content of the bitmap and what it is, if available
+        """
+        ...
+
     def get_application_label(self) -> str | None:
         """
         Extracts and resolves the `android:label` attribute from `<application>`.
@@ -497,6 +795,52 @@ class APK:
         """
         ...
 
+    def get_application_label_for_locale(self, locale: str) -> str | None:
+        """
+        Resolves the `android:label` attribute from `<application>` for the locale
+
+        Falls back to the default value if the label is not translated.
+
+        Parameters
+        ----------
+        locale : str
+            Language with optional region: `ru`, `ru-RU` or `ru-rRU`
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        label = apk.get_application_label_for_locale("ru-RU")
+        ```
+
+        Returns
+        -------
+        str | None
+            The translated label or literal value, if available
+        """
+        ...
+
+    def get_application_labels(self) -> dict[str, str]:
+        """
+        Returns every translation of the application label
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        for locale, label in apk.get_application_labels().items():
+            print(f"{locale or 'default'}: {label}")
+        ```
+
+        Returns
+        -------
+        dict[str, str]
+            Labels by locale (`ru`, `ru-RU`), the default value has an empty key
+        """
+        ...
+
     def get_application_logo(self) -> str | None:
         """
         Extracts and resolves the `android:logo` attribute from `<application>`
@@ -556,6 +900,19 @@ class APK:
         """
         ...
 
+    def get_instrumentations(self) -> list[Instrumentation]:
+        """
+        Retrieves all `<instrumentation>` declarations from `<manifest>`
+
+        See: <a href="https://developer.android.com/guide/topics/manifest/instrumentation-element" target="_blank">https://developer.android.com/guide/topics/manifest/instrumentation-element</a>
+
+        Returns
+        -------
+        list[Instrumentation]
+            All found instrumentation tags
+        """
+        ...
+
     def get_permissions(self) -> set[str]:
         """
         Retrieves all permissions names from `<uses-permission>`
@@ -603,6 +960,25 @@ class APK:
         """
         ...
 
+    def get_min_sdk(self) -> int | None:
+        """
+        Parses the minimum supported SDK version (`minSdkVersion`) as a number.
+
+        Examples
+        --------
+
+        ```python
+        print(apk.get_min_sdk())
+        26
+        ```
+
+        Returns
+        -------
+        int | None
+            The minimum SDK version, or None if not specified or it is a codename of a preview release.
+        """
+        ...
+
     def get_target_sdk_version(self) -> int:
         """
         Extracts the target SDK version (`targetSdkVersion`) from the `<uses-sdk>` element.
@@ -675,20 +1051,43 @@ class APK:
         """
         ...
 
-    def is_automotive(self) -> bool:
+    def get_features_detailed(self) -> list[Feature]:
         """
-        Checks whether the app is designed to display its user interface on multiple screens inside the vehicle.
+        Retrieves all `<uses-feature>` declarations with their `required`, `glEsVersion` and `version`.
 
-        See: <a href="https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features" target="_blank">https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features</a>
-        """
-        ...
+        Google Play hides the application from devices without the required features.
 
-    def is_leanback(self) -> bool:
-        """
-        Checks whether the app is designed to show its UI on a television.
+        See: <a href="https://developer.android.com/guide/topics/manifest/uses-feature-element" target="_blank">https://developer.android.com/guide/topics/manifest/uses-feature-element</a>
 
-        See: <a href="https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features" target="_blank">https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features</a>
-        """
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        required = [f.name for f in apk.get_features_detailed() if f.is_required and f.name]
+        ```
+
+        Returns
+        -------
+        list[Feature]
+            Declared features in manifest order
+        """
+        ...
+
+    def is_automotive(self) -> bool:
+        """
+        Checks whether the app is designed to display its user interface on multiple screens inside the vehicle.
+
+        See: <a href="https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features" target="_blank">https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features</a>
+        """
+        ...
+
+    def is_leanback(self) -> bool:
+        """
+        Checks whether the app is designed to show its UI on a television.
+
+        See: <a href="https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features" target="_blank">https://developer.android.com/guide/topics/manifest/uses-feature-element#device-ui-hw-features</a>
+        """
         ...
 
     def is_wearable(self) -> bool:
@@ -707,6 +1106,63 @@ class APK:
         """
         ...
 
+    def is_test_artifact(self) -> bool:
+        """
+        Checks whether the apk looks like a test build that should not be shipped.
+
+        Any of the following is enough: `android:testOnly="true"`, `<instrumentation>` declarations,
+        the `.test` package suffix of `androidTest` apks or `androidx.test` classes in the dex files.
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        if apk.is_test_artifact():
+            raise SystemExit("refusing to publish a test build")
+        ```
+        """
+        ...
+
+    def get_supports_screens(self) -> SupportsScreens | None:
+        """
+        Retrieves `<supports-screens>` from the manifest.
+
+        See: <a href="https://developer.android.com/guide/topics/manifest/supports-screens-element" target="_blank">https://developer.android.com/guide/topics/manifest/supports-screens-element</a>
+
+        Returns
+        -------
+        SupportsScreens | None
+            Supported screen sizes, if the element is present
+        """
+        ...
+
+    def get_compatible_screens(self) -> list[CompatibleScreen]:
+        """
+        Retrieves screens listed in `<compatible-screens>`.
+
+        See: <a href="https://developer.android.com/guide/topics/manifest/compatible-screens-element" target="_blank">https://developer.android.com/guide/topics/manifest/compatible-screens-element</a>
+
+        Returns
+        -------
+        list[CompatibleScreen]
+            Screen configurations Google Play allows the application on
+        """
+        ...
+
+    def get_min_supported_screens(self) -> str | None:
+        """
+        Returns the smallest screen size the application can be installed on.
+
+        `<compatible-screens>` takes precedence, otherwise `<supports-screens>` flags are used.
+
+        Returns
+        -------
+        str | None
+            One of `small`, `normal`, `large`, `xlarge` or None if every size is excluded
+        """
+        ...
+
     def get_declared_permissions(self) -> set[Permission]:
         """
         Retrieves all user defines permissions.
@@ -720,6 +1176,45 @@ class APK:
         """
         ...
 
+    def get_declared_permissions_detailed(self) -> list[DeclaredPermission]:
+        """
+        Retrieves all user defined permissions with the label and description resolved from
+        `resources.arsc` and the parsed protection level.
+
+        See: <a href="https://developer.android.com/guide/topics/manifest/permission-element" target="_blank">https://developer.android.com/guide/topics/manifest/permission-element</a>
+
+        Examples
+        --------
+
+        ```python
+        apk = APK("./file")
+        for permission in apk.get_declared_permissions_detailed():
+            if permission.protection_level == "dangerous":
+                print(permission.name, permission.label)
+        ```
+
+        Returns
+        -------
+        list[DeclaredPermission]
+            Permissions declared by the application in manifest order
+        """
+        ...
+
+    def permissions_by_protection(self) -> dict[str, list[str]]:
+        """
+        Groups requested permissions by protection level
+
+        Permissions declared by the application take the level from their `<permission>` element,
+        others are looked up in the bundled table of framework permissions.
+
+        Returns
+        -------
+        dict[str, list[str]]
+            Sorted permission names under the `dangerous`, `normal`, `signature` and `unknown` keys,
+            all keys are always present
+        """
+        ...
+
     def get_main_activity(self) -> str | None:
         """
         Retrieves first main (launchable) activity defined in the manifest.
@@ -759,6 +1254,9 @@ class APK:
 
         A main activity is typically one that has an intent filter with actions `MAIN` and categories `LAUNCHER` or `INFO`.
 
+        `<activity-alias>` is resolved to its `android:targetActivity`, so only names of the declared
+        activities are returned, each one once. Disabled components are skipped.
+
         See: <a href="https://developer.android.com/guide/topics/manifest/activity-element" target="_blank">https://developer.android.com/guide/topics/manifest/activity-element</a>
 
         Resolve logic: <a href="https://xrefandroid.com/android-16.0.0_r2/xref/frameworks/base/core/java/android/app/ApplicationPackageManager.java#310" target="_blank">https://xrefandroid.com/android-16.0.0_r2/xref/frameworks/base/core/java/android/app/ApplicationPackageManager.java#310</a>
@@ -770,6 +1268,91 @@ class APK:
         """
         ...
 
+    def get_manifest_diagnostics(self) -> list[str]:
+        """
+        Retrieves anomalies found while parsing `AndroidManifest.xml`
+
+        For example, duplicate attributes (Android takes the first one), attributes
+        declared with unexpected namespaces or values that don't match the attribute
+        format (integer `debuggable`).
+
+        Returns
+        -------
+        list[str]
+            Human-readable descriptions of anomalies
+        """
+        ...
+
+    def get_archive_anomalies(self) -> list[str]:
+        """
+        Retrieves anomalies of the zip container
+
+        For example, data before the first local header (self-extracting stubs, hidden payloads)
+        or a non-empty archive comment.
+
+        Returns
+        -------
+        list[str]
+            Human-readable descriptions of anomalies
+        """
+        ...
+
+    def get_anomalies(self) -> list[str]:
+        """
+        Retrieves all tampering and anomalies found in the APK
+
+        Combines the manifest diagnostics and archive anomalies with tampered compression
+        methods of the entries and dex files whose checksum, signature or size don't match
+        their headers. Parses all dex files, so it is slower than the other getters.
+
+        Returns
+        -------
+        list[str]
+            Human-readable descriptions of anomalies: `entry: classes.dex at 0x0 declares ...`
+        """
+        ...
+
+    def get_unreferenced_files(self) -> list[str]:
+        """
+        Retrieves files that are not referenced by resources, the manifest or dex strings
+
+        Such files are read only by code that builds the path at runtime,
+        typical for second-stage payloads in `assets/` and `res/raw/`.
+
+        Returns
+        -------
+        list[str]
+            Sorted names of the files
+
+        Raises
+        ------
+        APKError
+            If a dex file can't be parsed
+        """
+        ...
+
+    def prepended_data(self) -> bytes:
+        """
+        Retrieves the data placed before the first local header of the archive
+
+        Returns
+        -------
+        bytes
+            Raw data, empty for regular archives
+        """
+        ...
+
+    def archive_comment(self) -> bytes:
+        """
+        Retrieves the comment of the archive (End of Central Directory)
+
+        Returns
+        -------
+        bytes
+            Raw comment, empty if there is no comment
+        """
+        ...
+
     def get_activities(self) -> list[Activity]:
         """
         Retrieves all `<activity>` components declared in the manifest.
@@ -838,6 +1421,7 @@ class APK:
         Retrieves all APK signing signatures (v1, v2, v3, v3.1, etc).
 
         Combines results from multiple signature blocks within the APK file.
+        Signatures are parsed on the first call and cached, repeated calls are cheap.
 
         Raises
         ------
@@ -851,6 +1435,19 @@ class APK:
         """
         ...
 
+    def get_signing_block_entries(self) -> list[tuple[int, bytes]]:
+        """
+        Returns the raw ID-value pairs of the APK Signing Block in their order in the file.
+
+        Gives access to the blocks that `get_signatures` doesn't know or reports only as present.
+
+        Returns
+        -------
+        list[tuple[int, bytes]]
+            Pairs of block ID and its value, empty if the apk has no signing block
+        """
+        ...
+
     def get_native_codes(self) -> list[str]:
         """
         Retrieves all available native platforms: `arm64-v8a`, `armeabi-v7a`, `x86`, `x86_64`
@@ -862,6 +1459,144 @@ class APK:
         """
         ...
 
+    def get_supported_abis(self) -> SupportedAbis:
+        """
+        Retrieves ABIs of the native libraries together with `extractNativeLibs` and `multiArch`
+
+        Returns
+        -------
+        SupportedAbis
+            Supported ABIs and how the libraries are installed
+        """
+        ...
+
+    def extract(self, member: str, path: str | PurePath) -> Path:
+        """
+        Extracts a single file from the APK into the given directory
+
+        The entry name is turned into a safe relative path: absolute paths and `..`
+        components can't escape the directory.
+
+        Parameters
+        ----------
+        member : str
+            The path to the file inside the APK archive
+        path : str | PurePath
+            Output directory
+
+        Returns
+        -------
+        Path
+            Path of the extracted file
+
+        Raises
+        ------
+        PyValueError
+            If the name doesn't point to a file
+        APKError
+            If the file is missing or got error while reading or writing it
+
+        Examples
+        --------
+
+        ```python
+        dex_path = apk.extract("classes.dex", "./out")
+        ```
+        """
+        ...
+
+    def extract_all(
+        self,
+        output: str | PurePath,
+        pattern: str | None = None,
+        progress: Callable[[str, int, int], None] | None = None,
+    ) -> int:
+        """
+        Extracts files from the APK into the given directory
+
+        Entries with unsafe paths (absolute, `..`) are turned into safe relative paths.
+
+        Parameters
+        ----------
+        output : str | PurePath
+            Output directory
+        pattern : str | None
+            Regular expression, only files whose names match it are extracted
+        progress : Callable[[str, int, int], None] | None
+            Callback called after each file with `(stage, current, total)`.
+            Raise an exception inside the callback to cancel the extraction.
+
+        Returns
+        -------
+        int
+            Number of extracted files
+
+        Raises
+        ------
+        PyValueError
+            If the pattern is not a valid regular expression
+        APKError
+            If got error while reading or writing files
+
+        Examples
+        --------
+
+        ```python
+        apk.extract_all("./out", pattern=r"^(classes\d*\.dex|lib/.*\.so)$")
+        ```
+        """
+        ...
+
+    def estimated_install_size(self) -> SizeEstimate:
+        """
+        Estimates install and download size of the APK, similar to Play Console
+
+        Returns
+        -------
+        SizeEstimate
+            Estimated sizes in bytes
+        """
+        ...
+
+    def to_dict(self) -> dict[str, Any]:
+        """
+        Collect all extracted metadata into a nested dict
+
+        The dict has the manifest fields, requested and declared permissions, features,
+        libraries, native ABIs, components with their intent filters, signatures and
+        hashes of the file. Values are plain JSON types, so the dict can be stored as is.
+
+        Raises
+        ------
+        APKError
+            If the signatures could not be parsed
+
+        Examples
+        --------
+
+        ```python
+        report = APK("./file.apk").to_dict()
+        print(report["package_name"], len(report["activities"]))
+        ```
+        """
+        ...
+
+    def to_json(self, indent: int | None = None) -> str:
+        """
+        Same as `to_dict`, serialized to JSON
+
+        Parameters
+        ----------
+        indent : int | None
+            Number of spaces to pretty-print with, compact output if None
+
+        Raises
+        ------
+        APKError
+            If the signatures could not be parsed
+        """
+        ...
+
 @dataclass(frozen=True)
 class CertificateInfo:
     serial_number: str
@@ -909,21 +1644,139 @@ class CertificateInfo:
     SHA256 fingerprint of the certificate
     """
 
-@dataclass(frozen=True)
-class Signature:
-    @dataclass(frozen=True)
-    class V1:
-        """
-        Default signature scheme based on JAR signing
+    public_key_sha256: str
+    """
+    SHA256 of the certificate's SubjectPublicKeyInfo
+    """
 
-        See: <a href="https://source.android.com/docs/security/features/apksigning/v2#v1-verification" target="_blank">https://source.android.com/docs/security/features/apksigning/v2#v1-verification</a>
-        """
+    signer_public_key_sha256: str | None
+    """
+    SHA256 of the public key stored in the v2/v3 signer, set only for the first certificate of the signer
+    """
 
-        certificates: list[CertificateInfo]
+    version: int
+    """
+    X.509 version: 1, 2 or 3
+    """
 
-    @dataclass(frozen=True)
-    class V2:
-        """
+    key_algorithm: str
+    """
+    Algorithm of the public key: `RSA`, `EC`, `DSA`, `Ed25519` or the OID for the others
+    """
+
+    key_size: int | None
+    """
+    Size of the public key in bits: modulus for RSA and DSA, curve for EC
+    """
+
+    extensions: list[str]
+    """
+    Names of the X.509 v3 extensions, OIDs for the unknown ones
+    """
+
+    is_self_signed: bool
+    """
+    Whether the subject and the issuer are the same, the signature itself isn't checked
+    """
+
+    is_debug_cert: bool
+    """
+    Whether it is a well-known debug or test certificate: `CN=Android Debug` or the public test keys of AOSP
+    """
+
+    is_signer: bool
+    """
+    Whether it is the certificate of a signer, the others are the rest of the chain or extra certificates of the PKCS#7 bag
+    """
+
+    def has_public_key_mismatch(self) -> bool:
+        """
+        Returns `True` if the public key of the signer doesn't match the key of the certificate
+        """
+        ...
+
+    def fingerprint_md5_formatted(self, style: Literal["hex", "colon", "base64"] = "hex") -> str:
+        """
+        MD5 fingerprint of the certificate in the given style
+
+        See `fingerprint_sha256_formatted` for the styles.
+        """
+        ...
+
+    def fingerprint_sha1_formatted(self, style: Literal["hex", "colon", "base64"] = "hex") -> str:
+        """
+        SHA1 fingerprint of the certificate in the given style
+
+        See `fingerprint_sha256_formatted` for the styles.
+        """
+        ...
+
+    def fingerprint_sha256_formatted(self, style: Literal["hex", "colon", "base64"] = "hex") -> str:
+        """
+        SHA256 fingerprint of the certificate in the given style
+
+        Parameters
+        ----------
+        style : str
+            - `hex` - lowercase hex (apksigner, Firebase)
+            - `colon` - uppercase hex separated by colons (keytool, Play Console)
+            - `base64` - base64 of the digest (certificate pinning)
+
+        Raises
+        ------
+        ValueError
+            If the style is unknown or the fingerprint is not hex
+        """
+        ...
+
+@dataclass(frozen=True)
+class LineageNode:
+    """
+    A signing certificate of the proof-of-rotation attribute of v3 and v3.1 signatures
+
+    Nodes are ordered from the oldest certificate to the current one
+
+    See: <a href="https://source.android.com/docs/security/features/apksigning/v3#key-rotation" target="_blank">https://source.android.com/docs/security/features/apksigning/v3#key-rotation</a>
+    """
+
+    certificate: CertificateInfo
+
+    flags: int
+    """
+    Capability flags granted to this certificate by the newer ones
+    """
+
+    signature_algorithm_id: int
+    """
+    Algorithm the previous key signed this node with, `0` for the first node
+    """
+
+    signed_signature_algorithm_id: int
+    """
+    Algorithm the key of this node signs the next node with
+    """
+
+    def capabilities(self) -> list[str]:
+        """
+        Names of the set capabilities: `installed_data`, `shared_uid`, `permission`, `rollback`, `auth`
+        """
+        ...
+
+@dataclass(frozen=True)
+class Signature:
+    @dataclass(frozen=True)
+    class V1:
+        """
+        Default signature scheme based on JAR signing
+
+        See: <a href="https://source.android.com/docs/security/features/apksigning/v2#v1-verification" target="_blank">https://source.android.com/docs/security/features/apksigning/v2#v1-verification</a>
+        """
+
+        certificates: list[CertificateInfo]
+
+    @dataclass(frozen=True)
+    class V2:
+        """
         APK signature scheme v2
 
         See: <a href="https://source.android.com/docs/security/features/apksigning/v2" target="_blank">https://source.android.com/docs/security/features/apksigning/v2</a>
@@ -931,6 +1784,16 @@ class Signature:
 
         certificates: list[CertificateInfo]
 
+        algorithms: list[str]
+        """
+        Signature algorithms of the signers, e.g. `RSASSA-PKCS1-v1_5-SHA256`
+        """
+
+        digests: list[tuple[str, str]]
+        """
+        Pairs of the algorithm name and the hex digest of the apk contents
+        """
+
     @dataclass(frozen=True)
     class V3:
         """
@@ -940,6 +1803,17 @@ class Signature:
         """
 
         certificates: list[CertificateInfo]
+        lineage: list[LineageNode]
+
+        algorithms: list[str]
+        """
+        Signature algorithms of the signers, e.g. `RSASSA-PKCS1-v1_5-SHA256`
+        """
+
+        digests: list[tuple[str, str]]
+        """
+        Pairs of the algorithm name and the hex digest of the apk contents
+        """
 
     @dataclass(frozen=True)
     class V31:
@@ -950,6 +1824,17 @@ class Signature:
         """
 
         certificates: list[CertificateInfo]
+        lineage: list[LineageNode]
+
+        algorithms: list[str]
+        """
+        Signature algorithms of the signers, e.g. `RSASSA-PKCS1-v1_5-SHA256`
+        """
+
+        digests: list[tuple[str, str]]
+        """
+        Pairs of the algorithm name and the hex digest of the apk contents
+        """
 
     @dataclass(frozen=True)
     class ApkChannelBlock:
@@ -981,217 +1866,687 @@ class Signature:
         See: <a href="https://android.googlesource.com/platform/frameworks/base/+/master/core/java/android/util/apk/SourceStampVerifier.java#75" target="_blank">https://android.googlesource.com/platform/frameworks/base/+/master/core/java/android/util/apk/SourceStampVerifier.java#75</a>
         """
 
-        certificate: CertificateInfo
+        certificate: CertificateInfo
+
+    @dataclass
+    class PackerNextGenV2:
+        """
+        Some Chinese packer
+
+        See: <a href="https://github.com/mcxiaoke/packer-ng-plugin/blob/ffbe05a2d27406f3aea574d083cded27f0742160/common/src/main/java/com/mcxiaoke/packer/common/PackerCommon.java#L20" target="_blank">https://github.com/mcxiaoke/packer-ng-plugin/blob/ffbe05a2d27406f3aea574d083cded27f0742160/common/src/main/java/com/mcxiaoke/packer/common/PackerCommon.java#L20</a>
+        """
+
+        value: bytes
+
+    @dataclass
+    class GooglePlayFrosting:
+        """
+        Google Play Frosting Metadata
+
+        Added by Google Play to the apks it delivers. The schema of the metadata is unknown to anyone in public space,
+        so it is returned as the raw protobuf message
+
+        For more details you can inspect: <https://github.com/avast/apkverifier/blob/master/signingblock/frosting.go#L23>
+        """
+
+        metadata: bytes
+        signature_data: bytes
+
+    @dataclass
+    class VasDolleyV2:
+        """
+        Some apk protector/parser, idk, seen in the wild
+
+        The channel information in the ID-Value pair
+
+        See: <a href="https://edgeone.ai/document/58005" target="_blank">https://edgeone.ai/document/58005</a>
+        """
+
+        value: str
+
+    @dataclass
+    class UnknownBlock:
+        """
+        Block of the APK Signing Block with an ID that is not known yet
+
+        See `APK.get_signing_block_entries` for all blocks, including the known ones.
+        """
+
+        id: int
+        data: bytes
+
+type SignatureType = (
+    Signature.ApkChannelBlock
+    | Signature.GooglePlayFrosting
+    | Signature.PackerNextGenV2
+    | Signature.StampBlockV1
+    | Signature.StampBlockV2
+    | Signature.UnknownBlock
+    | Signature.V1
+    | Signature.V2
+    | Signature.V3
+    | Signature.V31
+    | Signature.VasDolleyV2
+)
+"""
+Represents all available signatures
+"""
+
+@dataclass(frozen=True)
+class IntentFilter:
+    """
+    Represents `<intent-filter>` in manifest
+
+    More information:
+    <a href="https://developer.android.com/guide/topics/manifest/intent-filter-element" target="_blank">https://developer.android.com/guide/topics/manifest/intent-filter-element</a>
+    """
+
+    actions: list[str]
+    """
+    A list of declared actions
+
+    See: https://developer.android.com/guide/topics/manifest/action-element
+    """
+
+    categories: list[str]
+    """
+    A list of declared categories
+
+    See: https://developer.android.com/guide/topics/manifest/category-element
+    """
+
+    data: list[IntentData]
+    """
+    A list of declared data specifications
+
+    See: https://developer.android.com/guide/topics/manifest/data-element
+    """
+
+    auto_verify: str | None
+    """
+    Whether the system should verify the app links of the filter
+
+    See: https://developer.android.com/training/app-links/verify-android-applinks
+    """
+
+    uris: list[str]
+    """
+    URIs accepted by the filter: `https://example.com/path`
+
+    All `<data>` elements are merged, as the system does: every scheme is combined with every host
+    and every path. Path prefixes and patterns are returned as declared.
+    """
+
+@dataclass(frozen=True)
+class IntentData:
+    """
+    Represents `<data>` inside `<intent-filter>` in manifest
+
+    More information:
+    <a href="https://developer.android.com/guide/topics/manifest/data-element" target="_blank">https://developer.android.com/guide/topics/manifest/data-element</a>
+    """
+
+    scheme: str | None
+    """
+    The scheme part of the URI: `https`, `myapp`
+    """
+
+    host: str | None
+    """
+    The host part of the URI authority, may start with a `*` wildcard
+    """
+
+    port: str | None
+    """
+    The port part of the URI authority
+    """
+
+    path: str | None
+    """
+    The complete path of the URI
+    """
+
+    path_prefix: str | None
+    """
+    The initial part of the path of the URI
+    """
+
+    path_pattern: str | None
+    """
+    The pattern of the path of the URI
+    """
+
+    mime_type: str | None
+    """
+    The MIME media type, may contain the `*` subtype wildcard: `image/*`
+    """
+
+@dataclass(frozen=True)
+class Activity:
+    """
+    Represents an Android activity defined in an app's manifest.
+
+    More information:
+    <a href="https://developer.android.com/guide/topics/manifest/activity-element" target="_blank">https://developer.android.com/guide/topics/manifest/activity-element</a>
+    """
+
+    enabled: str | None
+    """
+    Whether the activity can be instantiated by the system.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#enabled
+    """
+
+    exported: str | None
+    """
+    Whether the activity can be launched by components of other applications.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#exported
+    """
+
+    icon: str | None
+    """
+    An icon representing the activity.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#icon
+    """
+
+    label: str | None
+    """
+    A user-readable label for the activity.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#label
+    """
+
+    name: str | None
+    """
+    The name of the class that implements the activity, a subclass of `Activity`.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#nm
+    """
+
+    parent_activity_name: str | None
+    """
+    The class name of the logical parent of the activity.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#parent
+    """
+
+    permission: str | None
+    """
+    The name of a permission that clients must have to launch the activity or otherwise
+    get it to respond to an intent.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#prmsn
+    """
+
+    process: str | None
+    """
+    The name of the process in which the activity runs.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#proc
+    """
+
+    allow_task_reparenting: str | None
+    """
+    Whether the activity can move from the task that started it to the task it has an affinity for.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#reparent
+    """
+
+    config_changes: str | None
+    """
+    Configuration changes that the activity handles itself: `orientation|screenSize`.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#config
+    """
+
+    direct_boot_aware: str | None
+    """
+    Whether the activity can run before the user unlocks the device.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#directBootAware
+    """
+
+    document_launch_mode: str | None
+    """
+    How a new instance of the activity is added to the recents screen.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#dlmode
+    """
+
+    exclude_from_recents: str | None
+    """
+    Whether the task started by the activity is hidden from the recents screen.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#exclude
+    """
+
+    launch_mode: str | None
+    """
+    How the activity is launched: `standard`, `singleTop`, `singleTask`, `singleInstance` or `singleInstancePerTask`.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#lmode
+    """
+
+    no_history: str | None
+    """
+    Whether the activity is removed from the back stack when the user leaves it.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#nohist
+    """
+
+    resizeable_activity: str | None
+    """
+    Whether the activity supports multi-window mode.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#resizeableActivity
+    """
+
+    screen_orientation: str | None
+    """
+    The orientation of the activity on the device: `portrait`, `landscape`, `sensor`, ....
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#screen
+    """
+
+    show_when_locked: str | None
+    """
+    Whether the activity is shown on top of the lock screen.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#showWhenLocked
+    """
+
+    task_affinity: str | None
+    """
+    The task that the activity has an affinity for, affinity to other packages is used for task hijacking.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#aff
+    """
+
+    theme: str | None
+    """
+    A reference to a style resource defining the overall theme of the activity.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#theme
+    """
+
+    window_soft_input_mode: str | None
+    """
+    How the main window of the activity interacts with the on-screen soft keyboard.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-element#wsoft
+    """
+
+    intent_filters: list[IntentFilter]
+    """
+    A list of all declared `<intent-filter>` for a given activity
+
+    See: https://developer.android.com/guide/topics/manifest/intent-filter-element
+    """
+
+    is_exported: bool
+    """
+    Whether the activity can be launched by other applications.
+
+    If `exported` is not set, the activity is exported when it declares intent filters.
+    """
+
+    is_enabled: bool
+    """
+    Whether the activity can be instantiated by the system (`True` by default).
+    """
+
+@dataclass(frozen=True)
+class ActivityAlias:
+    """
+    Represents `<activity-alias>` in manifest
+
+    More information:
+    <a href="https://developer.android.com/guide/topics/manifest/activity-alias-element">https://developer.android.com/guide/topics/manifest/activity-alias-element</a>
+    """
+
+    enabled: str | None
+    """
+    Whether the target activity can be instantiated by the system through this alias.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#enabled
+    """
+
+    exported: str | None
+    """
+    Whether the components of other applications can launch the target activity through this alias.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#exported
+    """
+
+    icon: str | None
+    """
+    An icon for the target activity when presented to users through the alias.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#icon
+    """
+
+    label: str | None
+    """
+    A user-readable label for the alias when presented to users through the alias.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#label
+    """
+
+    name: str | None
+    """
+    A unique name for the alias.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#nm
+    """
+
+    permission: str | None
+    """
+    The name of a permission that clients must have to launch the target activity or get it to do something using the alias.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#prmsn
+    """
+
+    target_activity: str | None
+    """
+    The name of the activity that can be activated through the alias.
+
+    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#trgt
+    """
+
+    intent_filters: list[IntentFilter]
+    """
+    A list of all declared `<intent-filter>` for a given activity
+
+    See: https://developer.android.com/guide/topics/manifest/intent-filter-element
+    """
+
+@dataclass(frozen=True)
+class NormalizedFile:
+    """
+    Entry of the normalized file listing, see `APK.get_files_normalized`
+    """
+
+    name: str
+    """
+    Path inside the archive, directories end with `/`
+    """
+
+    is_directory: bool
+    """
+    Whether the entry is a directory
+    """
+
+    synthesized: bool
+    """
+    Whether the directory isn't present in the archive and was added as a parent of other entries
+    """
+
+    count: int
+    """
+    Number of central directory records with this name, `0` for synthesized directories
+    """
+
+@dataclass(frozen=True)
+class IconKind:
+    """
+    What the bytes returned by `APK.get_icon_bytes` are
+    """
 
-    @dataclass
-    class PackerNextGenV2:
-        """
-        Some Chinese packer
+    format: str
+    """
+    Format of the bitmap: `png`, `webp` or `jpeg`
+    """
 
-        See: <a href="https://github.com/mcxiaoke/packer-ng-plugin/blob/ffbe05a2d27406f3aea574d083cded27f0742160/common/src/main/java/com/mcxiaoke/packer/common/PackerCommon.java#L20" target="_blank">https://github.com/mcxiaoke/packer-ng-plugin/blob/ffbe05a2d27406f3aea574d083cded27f0742160/common/src/main/java/com/mcxiaoke/packer/common/PackerCommon.java#L20</a>
-        """
+    adaptive_foreground: bool
+    """
+    Whether it is only the foreground layer of the adaptive icon
+    """
 
-        value: bytes
+@dataclass(frozen=True)
+class DexSummary:
+    """
+    Header values and table sizes of a single dex file, see `APK.dex_summaries`
+    """
 
-    @dataclass
-    class GooglePlayFrosting:
-        """
-        Google Play Frosting Metadata
+    name: str
+    """
+    Name of the dex file inside the archive
+    """
 
-        We just highlight the presence of the block, because the full structure is unknown to anyone in public space
+    version: int | None
+    """
+    Format version from the magic (`35`, `38`, `39`, ...)
+    """
 
-        For more details you can inspect: <https://github.com/avast/apkverifier/blob/master/signingblock/frosting.go#L23>
-        """
+    file_size: int
+    """
+    Size of the file declared in the header
+    """
 
-        value: bytes
+    checksum: int
+    """
+    Adler32 checksum declared in the header
+    """
 
-    @dataclass
-    class VasDolleyV2:
-        """
-        Some apk protector/parser, idk, seen in the wild
+    checksum_valid: bool
+    """
+    Whether the checksum matches the content of the file
+    """
 
-        The channel information in the ID-Value pair
+    signature_valid: bool
+    """
+    Whether the SHA-1 signature matches the content of the file, always `True` for compact dex
+    """
 
-        See: <a href="https://edgeone.ai/document/58005" target="_blank">https://edgeone.ai/document/58005</a>
-        """
+    strings: int
+    types: int
+    protos: int
+    fields: int
+    methods: int
+    classes: int
 
-        value: str
+    map_items: dict[str, int]
+    """
+    Number of items in each section from the `map_list`: `{"code_item": 1234, ...}`
 
-type SignatureType = (
-    Signature.ApkChannelBlock
-    | Signature.GooglePlayFrosting
-    | Signature.PackerNextGenV2
-    | Signature.StampBlockV1
-    | Signature.StampBlockV2
-    | Signature.V1
-    | Signature.V2
-    | Signature.V3
-    | Signature.V31
-    | Signature.VasDolleyV2
-)
-"""
-Represents all available signatures
-"""
+    See: https://source.android.com/docs/core/runtime/dex-format#map-item
+    """
 
 @dataclass(frozen=True)
-class IntentFilter:
+class SupportedAbis:
     """
-    Represents `<intent-filter>` in manifest
-
-    More information:
-    <a href="https://developer.android.com/guide/topics/manifest/intent-filter-element" target="_blank">https://developer.android.com/guide/topics/manifest/intent-filter-element</a>
+    ABIs the APK can be installed on.
     """
 
-    actions: list[str]
+    abis: list[str]
+    """
+    ABIs with native libraries in `lib/`, sorted. Empty if the APK has no native code
     """
-    A list of declared actions
 
-    See: https://developer.android.com/guide/topics/manifest/action-element
+    extract_native_libs: bool
+    """
+    Whether native libraries are extracted on installation, `android:extractNativeLibs`
     """
 
-    categories: list[str]
+    multi_arch: bool
     """
-    A list of declared categories
+    Whether libraries of all ABIs are installed, not only the primary one, `android:multiArch`
 
-    See: https://developer.android.com/guide/topics/manifest/category-element
+    See: https://developer.android.com/guide/topics/manifest/application-element#multiarch
     """
 
+    def has_native_code(self) -> bool:
+        """
+        Checks if the APK contains native libraries
+        """
+        ...
+
+    def supports_64bit(self) -> bool:
+        """
+        Checks if the APK runs on 64-bit only devices
+        """
+        ...
+
+    def primary_abi(self) -> str | None:
+        """
+        Returns the ABI a modern device would pick: `arm64-v8a` if present, then `x86_64`, etc.
+        """
+        ...
+
 @dataclass(frozen=True)
-class Activity:
+class SizeEstimate:
     """
-    Represents an Android activity defined in an app's manifest.
+    Estimated on-device and network sizes of the APK.
 
-    More information:
-    <a href="https://developer.android.com/guide/topics/manifest/activity-element" target="_blank">https://developer.android.com/guide/topics/manifest/activity-element</a>
+    The values are computed from the central directory, so they are close to the
+    numbers shown in Play Console, but not exact.
     """
 
-    enabled: str | None
+    download_size: int
+    """
+    Size of the data transferred over network: compressed entries with their
+    local headers, the central directory and the end of central directory
     """
-    Whether the activity can be instantiated by the system.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#enabled
+    install_size: int
+    """
+    Estimated size occupied on device after installation
     """
 
-    exported: str | None
+    dex_size: int
+    """
+    Uncompressed size of all `classes*.dex` files
     """
-    Whether the activity can be launched by components of other applications.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#exported
+    native_libs_size: int
+    """
+    Uncompressed size of native libraries for the largest ABI
     """
 
-    icon: str | None
+    resources_size: int
+    """
+    Uncompressed size of `resources.arsc`, `res/` and `assets/`
     """
-    An icon representing the activity.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#icon
+    extract_native_libs: bool
     """
+    Whether native libraries are extracted on installation
 
-    label: str | None
+    See: https://developer.android.com/guide/topics/manifest/application-element#extractNativeLibs
     """
-    A user-readable label for the activity.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#label
+@dataclass(frozen=True)
+class SupportsScreens:
     """
+    Represents `<supports-screens>` in the manifest.
 
-    name: str | None
+    More information:
+    <a href="https://developer.android.com/guide/topics/manifest/supports-screens-element" target="_blank">https://developer.android.com/guide/topics/manifest/supports-screens-element</a>
     """
-    The name of the class that implements the activity, a subclass of `Activity`.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#nm
+    resizeable: str | None
+    """
+    Whether the application is resizeable for different screen sizes (deprecated).
     """
 
-    parent_activity_name: str | None
+    small_screens: str | None
+    """
+    Whether the application supports the smaller screen form-factors.
     """
-    The class name of the logical parent of the activity.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#parent
+    normal_screens: str | None
+    """
+    Whether the application supports the "normal" screen form-factors.
     """
 
-    permission: str | None
+    large_screens: str | None
+    """
+    Whether the application supports larger screen form-factors.
     """
-    The name of a permission that clients must have to launch the activity or otherwise
-    get it to respond to an intent.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#prmsn
+    xlarge_screens: str | None
+    """
+    Whether the application supports extra large screen form-factors.
     """
 
-    process: str | None
+    any_density: str | None
+    """
+    Whether the application includes resources to accommodate any screen density.
     """
-    The name of the process in which the activity runs.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-element#proc
+    requires_smallest_width_dp: str | None
+    """
+    The minimum `smallestWidth` required.
     """
 
-    intent_filters: list[IntentFilter]
+    compatible_width_limit_dp: str | None
+    """
+    The maximum `smallestWidth` the application is designed for, compatibility mode is offered above it.
     """
-    A list of all declared `<intent-filter>` for a given activity
 
-    See: https://developer.android.com/guide/topics/manifest/intent-filter-element
+    largest_width_limit_dp: str | None
+    """
+    The maximum `smallestWidth` the application is designed for, compatibility mode is forced above it.
     """
 
 @dataclass(frozen=True)
-class ActivityAlias:
+class CompatibleScreen:
     """
-    Represents `<activity-alias>` in manifest
+    Represents `<screen>` inside `<compatible-screens>` in the manifest.
 
     More information:
-    <a href="https://developer.android.com/guide/topics/manifest/activity-alias-element">https://developer.android.com/guide/topics/manifest/activity-alias-element</a>
+    <a href="https://developer.android.com/guide/topics/manifest/compatible-screens-element" target="_blank">https://developer.android.com/guide/topics/manifest/compatible-screens-element</a>
     """
 
-    enabled: str | None
+    screen_size: str | None
     """
-    Whether the target activity can be instantiated by the system through this alias.
-
-    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#enabled
+    The screen size: `small`, `normal`, `large` or `xlarge`.
     """
 
-    exported: str | None
+    screen_density: str | None
     """
-    Whether the components of other applications can launch the target activity through this alias.
-
-    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#exported
+    The screen density: `ldpi`, `mdpi`, `hdpi`, `xhdpi`, `xxhdpi`, `xxxhdpi` or the dpi value.
     """
 
-    icon: str | None
+@dataclass(frozen=True)
+class Feature:
     """
-    An icon for the target activity when presented to users through the alias.
+    Represents `<uses-feature>` in the manifest.
 
-    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#icon
+    More information:
+    <a href="https://developer.android.com/guide/topics/manifest/uses-feature-element" target="_blank">https://developer.android.com/guide/topics/manifest/uses-feature-element</a>
     """
 
-    label: str | None
+    name: str | None
     """
-    A user-readable label for the alias when presented to users through the alias.
-
-    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#label
+    The hardware or software feature, not set when the element only declares `glEsVersion`.
     """
 
-    name: str | None
+    required: str | None
     """
-    A unique name for the alias.
-
-    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#nm
+    Whether the application can't function without the feature.
     """
 
-    permission: str | None
+    gl_es_version: str | None
     """
-    The name of a permission that clients must have to launch the target activity or get it to do something using the alias.
-
-    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#prmsn
+    The OpenGL ES version required, the major version in the upper 16 bits: `0x00030001` is 3.1.
     """
 
-    target_activity: str | None
+    version: str | None
     """
-    The name of the activity that can be activated through the alias.
-
-    See: https://developer.android.com/guide/topics/manifest/activity-alias-element#trgt
+    The minimum version of the feature.
     """
 
-    intent_filters: list[IntentFilter]
+    is_required: bool
+    """
+    Whether devices without the feature are filtered out (`True` by default).
     """
-    A list of all declared `<intent-filter>` for a given activity
 
-    See: https://developer.android.com/guide/topics/manifest/intent-filter-element
+    opengl_es_version: tuple[int, int] | None
+    """
+    The required OpenGL ES version as `(major, minor)`.
     """
 
 @dataclass(frozen=True)
@@ -1248,6 +2603,51 @@ class Permission:
     See: https://developer.android.com/guide/topics/manifest/permission-element#plevel
     """
 
+@dataclass(frozen=True)
+class DeclaredPermission:
+    """
+    Represents `<permission>` with the label and description resolved from resources
+    and the parsed protection level.
+
+    More information:
+    <a href="https://developer.android.com/guide/topics/manifest/permission-element" target="_blank">https://developer.android.com/guide/topics/manifest/permission-element</a>
+    """
+
+    name: str | None
+    """
+    The name to be used in code to refer to the permission.
+    """
+
+    label: str | None
+    """
+    A user-readable name for the permission, references are resolved to the default value.
+    """
+
+    description: str | None
+    """
+    A user-readable description of the permission, references are resolved to the default value.
+    """
+
+    icon: str | None
+    """
+    A reference to a drawable resource for an icon that represents the permission.
+    """
+
+    permission_group: str | None
+    """
+    The `<permission-group>` the permission belongs to.
+    """
+
+    protection_level: Literal["normal", "dangerous", "signature", "unknown"]
+    """
+    The base protection level, `normal` if the attribute is not set.
+    """
+
+    protection_flags: list[str]
+    """
+    Additional protection flags: `privileged`, `appop`, `development`, etc.
+    """
+
 @dataclass(frozen=True)
 class Provider:
     """
@@ -1362,6 +2762,28 @@ class Provider:
     See: https://developer.android.com/guide/topics/manifest/provider-element#write
     """
 
+    intent_filters: list[IntentFilter]
+    """
+    A list of all declared `<intent-filter>` for a given provider
+
+    See: https://developer.android.com/guide/topics/manifest/intent-filter-element
+    """
+
+    authority_list: list[str]
+    """
+    Authorities split by `;`.
+    """
+
+    is_exported: bool
+    """
+    Whether the provider is explicitly exported for other applications.
+    """
+
+    is_enabled: bool
+    """
+    Whether the provider can be instantiated by the system (`True` by default).
+    """
+
 @dataclass(frozen=True)
 class Service:
     """
@@ -1455,6 +2877,13 @@ class Service:
     See: https://developer.android.com/guide/topics/manifest/service-element#stopWithTask
     """
 
+    intent_filters: list[IntentFilter]
+    """
+    A list of all declared `<intent-filter>` for a given service
+
+    See: https://developer.android.com/guide/topics/manifest/intent-filter-element
+    """
+
 @dataclass(frozen=True)
 class Receiver:
     """
@@ -1520,6 +2949,13 @@ class Receiver:
     See: https://developer.android.com/guide/topics/manifest/receiver-element#proc
     """
 
+    intent_filters: list[IntentFilter]
+    """
+    A list of all declared `<intent-filter>` for a given receiver
+
+    See: https://developer.android.com/guide/topics/manifest/intent-filter-element
+    """
+
 class Attribution:
     """
     This helps trace data access back to logical parts of application code.
@@ -1541,6 +2977,35 @@ class Attribution:
     See: https://developer.android.com/guide/topics/manifest/attribution-element#label
     """
 
+@dataclass(frozen=True)
+class Instrumentation:
+    """
+    A test runner that is loaded into the target application before any of its components.
+
+    More information: <a href="https://developer.android.com/guide/topics/manifest/instrumentation-element">https://developer.android.com/guide/topics/manifest/instrumentation-element</a>
+    """
+
+    name: str | None
+    """
+    The name of the class that implements the instrumentation.
+
+    See: https://developer.android.com/guide/topics/manifest/instrumentation-element#nm
+    """
+
+    target_package: str | None
+    """
+    The package of the application that is instrumented.
+
+    See: https://developer.android.com/guide/topics/manifest/instrumentation-element#trgt
+    """
+
+    handle_profiling: str | None
+    """
+    Whether the instrumentation turns profiling on and off.
+
+    See: https://developer.android.com/guide/topics/manifest/instrumentation-element#prof
+    """
+
 class FileCompressionType:
     """
     Compression mode used for a zip entry
@@ -1565,3 +3030,10 @@ class FileCompressionType:
     """
     The file appears tampered but is actually compressed with `Deflate`.
     """
+
+    @property
+    def is_tampered(self) -> bool:
+        """
+        Whether the declared compression method differs from the real one
+        """
+        ...
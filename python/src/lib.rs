@@ -1,21 +1,33 @@
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::PathBuf;
 
-use ::apk_info::Apk as ApkRust;
+use ::apk_info::dex::Dex as ApkDex;
 use ::apk_info::models::{
     Activity as ApkActivity, ActivityAlias as ApkActivityAlias, Attribution as ApkAttribution,
-    IntentFilter as ApkIntentFilter, Permission as ApkPermission, Provider as ApkProvider,
-    Receiver as ApkReceiver, Service as ApkService,
+    CompatibleScreen as ApkCompatibleScreen, DeclaredPermission as ApkDeclaredPermission,
+    Feature as ApkFeature, IconKind as ApkIconKind, Instrumentation as ApkInstrumentation,
+    IntentData as ApkIntentData, IntentFilter as ApkIntentFilter,
+    NormalizedFile as ApkNormalizedFile, Permission as ApkPermission, Provider as ApkProvider,
+    Receiver as ApkReceiver, Service as ApkService, SizeEstimate as ApkSizeEstimate,
+    SupportedAbis as ApkSupportedAbis, SupportsScreens as ApkSupportsScreens,
 };
-use ::apk_info_zip::{
-    CertificateInfo as ZipCertificateInfo, FileCompressionType as ZipFileCompressionType,
-    Signature as ZipSignature,
+use ::apk_info::progress::{CancellationToken, Stage};
+use ::apk_info::structs::Density;
+use ::apk_info::xml::Element as XmlElement;
+use ::apk_info::zip::{
+    CertificateInfo as ZipCertificateInfo, ContentDigest as ZipContentDigest,
+    FileCompressionType as ZipFileCompressionType, FingerprintStyle, LineageNode as ZipLineageNode,
+    Signature as ZipSignature, SignatureAlgorithm as ZipSignatureAlgorithm, extract_paths,
 };
+use ::apk_info::{APKError as ApkRustError, Apk as ApkRust};
 use pyo3::conversion::IntoPyObject;
 use pyo3::exceptions::{PyException, PyFileNotFoundError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyString;
+use pyo3::types::{PyDict, PyList, PyString};
 use pyo3::{Bound, PyAny, PyResult, create_exception, pyclass, pymethods};
+use regex::Regex;
+use serde::Serialize;
 
 create_exception!(m, APKError, PyException, "Got error while parsing apk");
 
@@ -48,6 +60,33 @@ pub struct CertificateInfo {
 
     #[pyo3(get)]
     pub sha256_fingerprint: String,
+
+    #[pyo3(get)]
+    pub public_key_sha256: String,
+
+    #[pyo3(get)]
+    pub signer_public_key_sha256: Option<String>,
+
+    #[pyo3(get)]
+    pub version: u8,
+
+    #[pyo3(get)]
+    pub key_algorithm: String,
+
+    #[pyo3(get)]
+    pub key_size: Option<u32>,
+
+    #[pyo3(get)]
+    pub extensions: Vec<String>,
+
+    #[pyo3(get)]
+    pub is_self_signed: bool,
+
+    #[pyo3(get)]
+    pub is_debug_cert: bool,
+
+    #[pyo3(get)]
+    pub is_signer: bool,
 }
 
 impl From<ZipCertificateInfo> for CertificateInfo {
@@ -62,6 +101,15 @@ impl From<ZipCertificateInfo> for CertificateInfo {
             md5_fingerprint: certificate.md5_fingerprint,
             sha1_fingerprint: certificate.sha1_fingerprint,
             sha256_fingerprint: certificate.sha256_fingerprint,
+            public_key_sha256: certificate.public_key_sha256,
+            signer_public_key_sha256: certificate.signer_public_key_sha256,
+            version: certificate.version,
+            key_algorithm: certificate.key_algorithm,
+            key_size: certificate.key_size,
+            extensions: certificate.extensions,
+            is_self_signed: certificate.is_self_signed,
+            is_debug_cert: certificate.is_debug_cert,
+            is_signer: certificate.is_signer,
         }
     }
 }
@@ -70,7 +118,7 @@ impl From<ZipCertificateInfo> for CertificateInfo {
 impl CertificateInfo {
     fn __repr__(&self) -> String {
         format!(
-            "CertificateInfo(serial_number='{}', subject='{}', issuer='{}' valid_from='{}', valid_until='{}', signature_type='{}', md5_fingerprint='{}', sha1_fingerprint='{}', sha256_fingerprint='{}')",
+            "CertificateInfo(serial_number='{}', subject='{}', issuer='{}' valid_from='{}', valid_until='{}', signature_type='{}', md5_fingerprint='{}', sha1_fingerprint='{}', sha256_fingerprint='{}', public_key_sha256='{}')",
             self.serial_number,
             self.subject,
             self.issuer,
@@ -79,24 +127,146 @@ impl CertificateInfo {
             self.signature_type,
             self.md5_fingerprint,
             self.sha1_fingerprint,
-            self.sha256_fingerprint
+            self.sha256_fingerprint,
+            self.public_key_sha256
+        )
+    }
+
+    fn has_public_key_mismatch(&self) -> bool {
+        self.signer_public_key_sha256
+            .as_ref()
+            .is_some_and(|key| *key != self.public_key_sha256)
+    }
+
+    #[pyo3(signature = (style="hex"))]
+    fn fingerprint_md5_formatted(&self, style: &str) -> PyResult<String> {
+        format_fingerprint(style, &self.md5_fingerprint)
+    }
+
+    #[pyo3(signature = (style="hex"))]
+    fn fingerprint_sha1_formatted(&self, style: &str) -> PyResult<String> {
+        format_fingerprint(style, &self.sha1_fingerprint)
+    }
+
+    #[pyo3(signature = (style="hex"))]
+    fn fingerprint_sha256_formatted(&self, style: &str) -> PyResult<String> {
+        format_fingerprint(style, &self.sha256_fingerprint)
+    }
+}
+
+#[pyclass(eq, frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct LineageNode {
+    #[pyo3(get)]
+    pub certificate: CertificateInfo,
+
+    #[pyo3(get)]
+    pub flags: u32,
+
+    #[pyo3(get)]
+    pub signature_algorithm_id: u32,
+
+    #[pyo3(get)]
+    pub signed_signature_algorithm_id: u32,
+}
+
+impl From<ZipLineageNode> for LineageNode {
+    fn from(node: ZipLineageNode) -> Self {
+        Self {
+            certificate: node.certificate.into(),
+            flags: node.flags,
+            signature_algorithm_id: node.signature_algorithm_id,
+            signed_signature_algorithm_id: node.signed_signature_algorithm_id,
+        }
+    }
+}
+
+#[pymethods]
+impl LineageNode {
+    fn __repr__(&self) -> String {
+        format!(
+            "LineageNode(certificate={}, flags={}, signature_algorithm_id={}, signed_signature_algorithm_id={})",
+            self.certificate.__repr__(),
+            self.flags,
+            self.signature_algorithm_id,
+            self.signed_signature_algorithm_id
         )
     }
+
+    fn capabilities(&self) -> Vec<&'static str> {
+        ZipLineageNode::capability_names(self.flags)
+    }
+}
+
+fn algorithm_names(algorithms: &[ZipSignatureAlgorithm]) -> Vec<String> {
+    algorithms.iter().map(ZipSignatureAlgorithm::name).collect()
+}
+
+fn digest_pairs(digests: Vec<ZipContentDigest>) -> Vec<(String, String)> {
+    digests
+        .into_iter()
+        .map(|digest| (digest.algorithm.name(), digest.digest))
+        .collect()
+}
+
+fn fingerprint_style(style: &str) -> PyResult<FingerprintStyle> {
+    style
+        .parse()
+        .map_err(|e: ::apk_info::zip::CertificateError| PyValueError::new_err(e.to_string()))
+}
+
+fn format_fingerprint(style: &str, fingerprint: &str) -> PyResult<String> {
+    fingerprint_style(style)?
+        .format(fingerprint)
+        .ok_or_else(|| PyValueError::new_err(format!("fingerprint is not hex: {fingerprint:?}")))
 }
 
 #[pyclass(eq, frozen, module = "apk_info._apk_info")]
 #[derive(PartialEq, Eq, Hash)]
 enum Signature {
-    V1 { certificates: Vec<CertificateInfo> },
-    V2 { certificates: Vec<CertificateInfo> },
-    V3 { certificates: Vec<CertificateInfo> },
-    V31 { certificates: Vec<CertificateInfo> },
-    StampBlockV1 { certificate: CertificateInfo },
-    StampBlockV2 { certificate: CertificateInfo },
-    ApkChannelBlock { value: String },
-    PackerNextGenV2 { value: Vec<u8> },
-    GooglePlayFrosting {},
-    VasDollyV2 { value: String },
+    V1 {
+        certificates: Vec<CertificateInfo>,
+    },
+    V2 {
+        certificates: Vec<CertificateInfo>,
+        algorithms: Vec<String>,
+        digests: Vec<(String, String)>,
+    },
+    V3 {
+        certificates: Vec<CertificateInfo>,
+        lineage: Vec<LineageNode>,
+        algorithms: Vec<String>,
+        digests: Vec<(String, String)>,
+    },
+    V31 {
+        certificates: Vec<CertificateInfo>,
+        lineage: Vec<LineageNode>,
+        algorithms: Vec<String>,
+        digests: Vec<(String, String)>,
+    },
+    StampBlockV1 {
+        certificate: CertificateInfo,
+    },
+    StampBlockV2 {
+        certificate: CertificateInfo,
+    },
+    ApkChannelBlock {
+        value: String,
+    },
+    PackerNextGenV2 {
+        value: Vec<u8>,
+    },
+    GooglePlayFrosting {
+        metadata: Vec<u8>,
+        signature_data: Vec<u8>,
+    },
+    VasDollyV2 {
+        value: String,
+    },
+    UnknownBlock {
+        id: u32,
+        data: Vec<u8>,
+    },
 }
 
 impl Signature {
@@ -107,18 +277,49 @@ impl Signature {
             }
             .into_pyobject(py)
             .ok(),
-            ZipSignature::V2(v) => Signature::V2 {
-                certificates: v.into_iter().map(CertificateInfo::from).collect(),
+            ZipSignature::V2 {
+                certificates,
+                algorithms,
+                digests,
+            } => Signature::V2 {
+                certificates: certificates
+                    .into_iter()
+                    .map(CertificateInfo::from)
+                    .collect(),
+                algorithms: algorithm_names(&algorithms),
+                digests: digest_pairs(digests),
             }
             .into_pyobject(py)
             .ok(),
-            ZipSignature::V3(v) => Signature::V3 {
-                certificates: v.into_iter().map(CertificateInfo::from).collect(),
+            ZipSignature::V3 {
+                certificates,
+                lineage,
+                algorithms,
+                digests,
+            } => Signature::V3 {
+                certificates: certificates
+                    .into_iter()
+                    .map(CertificateInfo::from)
+                    .collect(),
+                lineage: lineage.into_iter().map(LineageNode::from).collect(),
+                algorithms: algorithm_names(&algorithms),
+                digests: digest_pairs(digests),
             }
             .into_pyobject(py)
             .ok(),
-            ZipSignature::V31(v) => Signature::V31 {
-                certificates: v.into_iter().map(CertificateInfo::from).collect(),
+            ZipSignature::V31 {
+                certificates,
+                lineage,
+                algorithms,
+                digests,
+            } => Signature::V31 {
+                certificates: certificates
+                    .into_iter()
+                    .map(CertificateInfo::from)
+                    .collect(),
+                lineage: lineage.into_iter().map(LineageNode::from).collect(),
+                algorithms: algorithm_names(&algorithms),
+                digests: digest_pairs(digests),
             }
             .into_pyobject(py)
             .ok(),
@@ -138,12 +339,18 @@ impl Signature {
             ZipSignature::PackerNextGenV2(value) => {
                 Signature::PackerNextGenV2 { value }.into_pyobject(py).ok()
             }
-            ZipSignature::GooglePlayFrosting => {
-                Signature::GooglePlayFrosting {}.into_pyobject(py).ok()
+            ZipSignature::Frosting(frosting) => Signature::GooglePlayFrosting {
+                metadata: frosting.metadata,
+                signature_data: frosting.signature_data,
             }
+            .into_pyobject(py)
+            .ok(),
             ZipSignature::VasDollyV2(v) => {
                 Signature::VasDollyV2 { value: v }.into_pyobject(py).ok()
             }
+            ZipSignature::UnknownBlock { id, data } => {
+                Signature::UnknownBlock { id, data }.into_pyobject(py).ok()
+            }
             _ => None,
         }
     }
@@ -156,14 +363,37 @@ impl Signature {
             Signature::V1 { certificates } => {
                 format!("Signature.V1(certificates={:?})", certificates)
             }
-            Signature::V2 { certificates } => {
-                format!("Signature.V2(certificates={:?})", certificates)
+            Signature::V2 {
+                certificates,
+                algorithms,
+                ..
+            } => {
+                format!(
+                    "Signature.V2(certificates={:?}, algorithms={:?})",
+                    certificates, algorithms
+                )
             }
-            Signature::V3 { certificates } => {
-                format!("Signature.V3(certificates={:?})", certificates)
+            Signature::V3 {
+                certificates,
+                lineage,
+                algorithms,
+                ..
+            } => {
+                format!(
+                    "Signature.V3(certificates={:?}, lineage={:?}, algorithms={:?})",
+                    certificates, lineage, algorithms
+                )
             }
-            Signature::V31 { certificates } => {
-                format!("Signature.V31(certificates={:?})", certificates)
+            Signature::V31 {
+                certificates,
+                lineage,
+                algorithms,
+                ..
+            } => {
+                format!(
+                    "Signature.V31(certificates={:?}, lineage={:?}, algorithms={:?})",
+                    certificates, lineage, algorithms
+                )
             }
             Signature::StampBlockV1 { certificate } => {
                 format!("Signature.StampBlockV1(certificate={:?})", certificate)
@@ -182,10 +412,24 @@ impl Signature {
                     .join("");
                 format!("Signature.PackerNextGenV2(channel='{}')", hex_string)
             }
-            Signature::GooglePlayFrosting {} => "Signature.GooglePlayFrosting()".to_string(),
+            Signature::GooglePlayFrosting {
+                metadata,
+                signature_data,
+            } => format!(
+                "Signature.GooglePlayFrosting(metadata_size={}, signature_size={})",
+                metadata.len(),
+                signature_data.len()
+            ),
             Signature::VasDollyV2 { value } => {
                 format!("Signature.VasDollyV2(value='{}')", value)
             }
+            Signature::UnknownBlock { id, data } => {
+                format!(
+                    "Signature.UnknownBlock(id=0x{:08x}, size={})",
+                    id,
+                    data.len()
+                )
+            }
         }
     }
 }
@@ -215,6 +459,14 @@ impl FileCompressionType {
             FileCompressionType::DeflatedTampered => "deflated_tampered",
         }
     }
+
+    #[getter]
+    fn is_tampered(&self) -> bool {
+        matches!(
+            self,
+            FileCompressionType::StoredTampered | FileCompressionType::DeflatedTampered
+        )
+    }
 }
 
 impl From<ZipFileCompressionType> for FileCompressionType {
@@ -228,6 +480,62 @@ impl From<ZipFileCompressionType> for FileCompressionType {
     }
 }
 
+#[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct IntentData {
+    #[pyo3(get)]
+    scheme: Option<String>,
+    #[pyo3(get)]
+    host: Option<String>,
+    #[pyo3(get)]
+    port: Option<String>,
+    #[pyo3(get)]
+    path: Option<String>,
+    #[pyo3(get)]
+    path_prefix: Option<String>,
+    #[pyo3(get)]
+    path_pattern: Option<String>,
+    #[pyo3(get)]
+    mime_type: Option<String>,
+}
+
+impl<'a> From<ApkIntentData<'a>> for IntentData {
+    fn from(data: ApkIntentData<'a>) -> Self {
+        IntentData {
+            scheme: data.scheme.map(String::from),
+            host: data.host.map(String::from),
+            port: data.port.map(String::from),
+            path: data.path.map(String::from),
+            path_prefix: data.path_prefix.map(String::from),
+            path_pattern: data.path_pattern.map(String::from),
+            mime_type: data.mime_type.map(String::from),
+        }
+    }
+}
+
+#[pymethods]
+impl IntentData {
+    fn __repr__(&self) -> String {
+        let mut parts = Vec::with_capacity(7);
+        macro_rules! push_field {
+            ($field:ident) => {
+                if let Some(ref v) = self.$field {
+                    parts.push(format!(concat!(stringify!($field), "={:?}"), v));
+                }
+            };
+        }
+        push_field!(scheme);
+        push_field!(host);
+        push_field!(port);
+        push_field!(path);
+        push_field!(path_prefix);
+        push_field!(path_pattern);
+        push_field!(mime_type);
+
+        format!("IntentData({})", parts.join(", "))
+    }
+}
+
 #[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct IntentFilter {
@@ -236,13 +544,25 @@ struct IntentFilter {
 
     #[pyo3(get)]
     categories: Vec<String>,
+
+    #[pyo3(get)]
+    data: Vec<IntentData>,
+
+    #[pyo3(get)]
+    auto_verify: Option<String>,
+
+    #[pyo3(get)]
+    uris: Vec<String>,
 }
 
 impl<'a> From<ApkIntentFilter<'a>> for IntentFilter {
     fn from(intent: ApkIntentFilter<'a>) -> Self {
         IntentFilter {
+            uris: intent.uris(),
             actions: intent.actions.into_iter().map(String::from).collect(),
             categories: intent.categories.into_iter().map(String::from).collect(),
+            data: intent.data.into_iter().map(IntentData::from).collect(),
+            auto_verify: intent.auto_verify.map(String::from),
         }
     }
 }
@@ -274,6 +594,8 @@ impl IntentFilter {
 
         push_field!(vec actions);
         push_field!(vec categories);
+        push_field!(vec data);
+        push_field!(opt auto_verify);
 
         format!("IntentFilter({})", parts.join(", "))
     }
@@ -299,12 +621,44 @@ struct Activity {
     #[pyo3(get)]
     process: Option<String>,
     #[pyo3(get)]
+    allow_task_reparenting: Option<String>,
+    #[pyo3(get)]
+    config_changes: Option<String>,
+    #[pyo3(get)]
+    direct_boot_aware: Option<String>,
+    #[pyo3(get)]
+    document_launch_mode: Option<String>,
+    #[pyo3(get)]
+    exclude_from_recents: Option<String>,
+    #[pyo3(get)]
+    launch_mode: Option<String>,
+    #[pyo3(get)]
+    no_history: Option<String>,
+    #[pyo3(get)]
+    resizeable_activity: Option<String>,
+    #[pyo3(get)]
+    screen_orientation: Option<String>,
+    #[pyo3(get)]
+    show_when_locked: Option<String>,
+    #[pyo3(get)]
+    task_affinity: Option<String>,
+    #[pyo3(get)]
+    theme: Option<String>,
+    #[pyo3(get)]
+    window_soft_input_mode: Option<String>,
+    #[pyo3(get)]
     intent_filters: Vec<IntentFilter>,
+    #[pyo3(get)]
+    is_exported: bool,
+    #[pyo3(get)]
+    is_enabled: bool,
 }
 
 impl<'a> From<ApkActivity<'a>> for Activity {
     fn from(activity: ApkActivity<'a>) -> Self {
         Activity {
+            is_exported: activity.is_exported(),
+            is_enabled: activity.is_enabled(),
             enabled: activity.enabled.map(String::from),
             exported: activity.exported.map(String::from),
             icon: activity.icon.map(String::from),
@@ -313,6 +667,19 @@ impl<'a> From<ApkActivity<'a>> for Activity {
             parent_activity_name: activity.parent_activity_name.map(String::from),
             permission: activity.permission.map(String::from),
             process: activity.process.map(String::from),
+            allow_task_reparenting: activity.allow_task_reparenting.map(String::from),
+            config_changes: activity.config_changes.map(String::from),
+            direct_boot_aware: activity.direct_boot_aware.map(String::from),
+            document_launch_mode: activity.document_launch_mode.map(String::from),
+            exclude_from_recents: activity.exclude_from_recents.map(String::from),
+            launch_mode: activity.launch_mode.map(String::from),
+            no_history: activity.no_history.map(String::from),
+            resizeable_activity: activity.resizeable_activity.map(String::from),
+            screen_orientation: activity.screen_orientation.map(String::from),
+            show_when_locked: activity.show_when_locked.map(String::from),
+            task_affinity: activity.task_affinity.map(String::from),
+            theme: activity.theme.map(String::from),
+            window_soft_input_mode: activity.window_soft_input_mode.map(String::from),
             intent_filters: activity
                 .intent_filters
                 .into_iter()
@@ -355,6 +722,19 @@ impl Activity {
         push_field!(opt parent_activity_name);
         push_field!(opt permission);
         push_field!(opt process);
+        push_field!(opt allow_task_reparenting);
+        push_field!(opt config_changes);
+        push_field!(opt direct_boot_aware);
+        push_field!(opt document_launch_mode);
+        push_field!(opt exclude_from_recents);
+        push_field!(opt launch_mode);
+        push_field!(opt no_history);
+        push_field!(opt resizeable_activity);
+        push_field!(opt screen_orientation);
+        push_field!(opt show_when_locked);
+        push_field!(opt task_affinity);
+        push_field!(opt theme);
+        push_field!(opt window_soft_input_mode);
         push_field!(vec intent_filters);
 
         format!("Activity({})", parts.join(", "))
@@ -493,63 +873,43 @@ impl Permission {
 
 #[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-pub struct Provider {
-    #[pyo3(get)]
-    pub authorities: Option<String>,
-    #[pyo3(get)]
-    pub enabled: Option<String>,
-    #[pyo3(get)]
-    pub direct_boot_aware: Option<String>,
-    #[pyo3(get)]
-    pub exported: Option<String>,
+struct DeclaredPermission {
     #[pyo3(get)]
-    pub grant_uri_permissions: Option<String>,
-    #[pyo3(get)]
-    pub icon: Option<String>,
-    #[pyo3(get)]
-    pub init_order: Option<String>,
-    #[pyo3(get)]
-    pub label: Option<String>,
-    #[pyo3(get)]
-    pub multiprocess: Option<String>,
+    name: Option<String>,
     #[pyo3(get)]
-    pub name: Option<String>,
+    label: Option<String>,
     #[pyo3(get)]
-    pub permission: Option<String>,
+    description: Option<String>,
     #[pyo3(get)]
-    pub process: Option<String>,
+    icon: Option<String>,
     #[pyo3(get)]
-    pub read_permission: Option<String>,
+    permission_group: Option<String>,
     #[pyo3(get)]
-    pub syncable: Option<String>,
+    protection_level: String,
     #[pyo3(get)]
-    pub write_permission: Option<String>,
+    protection_flags: Vec<String>,
 }
 
-impl<'a> From<ApkProvider<'a>> for Provider {
-    fn from(provider: ApkProvider<'a>) -> Self {
-        Provider {
-            authorities: provider.authorities.map(String::from),
-            enabled: provider.enabled.map(String::from),
-            direct_boot_aware: provider.direct_boot_aware.map(String::from),
-            exported: provider.exported.map(String::from),
-            grant_uri_permissions: provider.grant_uri_permissions.map(String::from),
-            icon: provider.icon.map(String::from),
-            init_order: provider.init_order.map(String::from),
-            label: provider.label.map(String::from),
-            multiprocess: provider.multiprocess.map(String::from),
-            name: provider.name.map(String::from),
-            permission: provider.permission.map(String::from),
-            process: provider.process.map(String::from),
-            read_permission: provider.read_permission.map(String::from),
-            syncable: provider.syncable.map(String::from),
-            write_permission: provider.write_permission.map(String::from),
+impl<'a> From<ApkDeclaredPermission<'a>> for DeclaredPermission {
+    fn from(permission: ApkDeclaredPermission<'a>) -> Self {
+        DeclaredPermission {
+            name: permission.name.map(String::from),
+            label: permission.label,
+            description: permission.description,
+            icon: permission.icon.map(String::from),
+            permission_group: permission.permission_group.map(String::from),
+            protection_level: permission.protection_level.to_string(),
+            protection_flags: permission
+                .protection_flags
+                .into_iter()
+                .map(String::from)
+                .collect(),
         }
     }
 }
 
 #[pymethods]
-impl Provider {
+impl DeclaredPermission {
     fn __repr__(&self) -> String {
         let mut parts = Vec::with_capacity(16);
         macro_rules! push_field {
@@ -559,77 +919,61 @@ impl Provider {
                 }
             };
         }
-
-        push_field!(authorities);
-        push_field!(enabled);
-        push_field!(direct_boot_aware);
-        push_field!(exported);
-        push_field!(grant_uri_permissions);
-        push_field!(icon);
-        push_field!(init_order);
-        push_field!(label);
-        push_field!(multiprocess);
         push_field!(name);
-        push_field!(permission);
-        push_field!(process);
-        push_field!(read_permission);
-        push_field!(syncable);
-        push_field!(write_permission);
+        push_field!(label);
+        push_field!(description);
+        push_field!(icon);
+        push_field!(permission_group);
+        parts.push(format!("protection_level={:?}", self.protection_level));
+        if !self.protection_flags.is_empty() {
+            parts.push(format!("protection_flags={:?}", self.protection_flags));
+        }
 
-        format!("Provider({})", parts.join(", "))
+        format!("DeclaredPermission({})", parts.join(", "))
     }
 }
 
 #[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Service {
-    #[pyo3(get)]
-    description: Option<String>,
-    #[pyo3(get)]
-    direct_boot_aware: Option<String>,
-    #[pyo3(get)]
-    enabled: Option<String>,
+struct SupportsScreens {
     #[pyo3(get)]
-    exported: Option<String>,
+    resizeable: Option<String>,
     #[pyo3(get)]
-    foreground_service_type: Option<String>,
+    small_screens: Option<String>,
     #[pyo3(get)]
-    icon: Option<String>,
+    normal_screens: Option<String>,
     #[pyo3(get)]
-    isolated_process: Option<String>,
+    large_screens: Option<String>,
     #[pyo3(get)]
-    label: Option<String>,
+    xlarge_screens: Option<String>,
     #[pyo3(get)]
-    name: Option<String>,
+    any_density: Option<String>,
     #[pyo3(get)]
-    permission: Option<String>,
+    requires_smallest_width_dp: Option<String>,
     #[pyo3(get)]
-    process: Option<String>,
+    compatible_width_limit_dp: Option<String>,
     #[pyo3(get)]
-    stop_with_task: Option<String>,
+    largest_width_limit_dp: Option<String>,
 }
 
-impl<'a> From<ApkService<'a>> for Service {
-    fn from(service: ApkService<'a>) -> Self {
-        Service {
-            description: service.description.map(String::from),
-            direct_boot_aware: service.direct_boot_aware.map(String::from),
-            enabled: service.enabled.map(String::from),
-            exported: service.exported.map(String::from),
-            foreground_service_type: service.foreground_service_type.map(String::from),
-            icon: service.icon.map(String::from),
-            isolated_process: service.isolated_process.map(String::from),
-            label: service.label.map(String::from),
-            name: service.name.map(String::from),
-            permission: service.permission.map(String::from),
-            process: service.process.map(String::from),
-            stop_with_task: service.stop_with_task.map(String::from),
+impl<'a> From<ApkSupportsScreens<'a>> for SupportsScreens {
+    fn from(screens: ApkSupportsScreens<'a>) -> Self {
+        SupportsScreens {
+            resizeable: screens.resizeable.map(String::from),
+            small_screens: screens.small_screens.map(String::from),
+            normal_screens: screens.normal_screens.map(String::from),
+            large_screens: screens.large_screens.map(String::from),
+            xlarge_screens: screens.xlarge_screens.map(String::from),
+            any_density: screens.any_density.map(String::from),
+            requires_smallest_width_dp: screens.requires_smallest_width_dp.map(String::from),
+            compatible_width_limit_dp: screens.compatible_width_limit_dp.map(String::from),
+            largest_width_limit_dp: screens.largest_width_limit_dp.map(String::from),
         }
     }
 }
 
 #[pymethods]
-impl Service {
+impl SupportsScreens {
     fn __repr__(&self) -> String {
         let mut parts = Vec::with_capacity(16);
         macro_rules! push_field {
@@ -639,47 +983,576 @@ impl Service {
                 }
             };
         }
-        push_field!(description);
-        push_field!(direct_boot_aware);
-        push_field!(enabled);
-        push_field!(exported);
-        push_field!(foreground_service_type);
-        push_field!(isolated_process);
-        push_field!(name);
-        push_field!(permission);
-        push_field!(process);
-        push_field!(stop_with_task);
-
-        format!("Service({})", parts.join(", "))
+        push_field!(resizeable);
+        push_field!(small_screens);
+        push_field!(normal_screens);
+        push_field!(large_screens);
+        push_field!(xlarge_screens);
+        push_field!(any_density);
+        push_field!(requires_smallest_width_dp);
+        push_field!(compatible_width_limit_dp);
+        push_field!(largest_width_limit_dp);
+
+        format!("SupportsScreens({})", parts.join(", "))
     }
 }
 
 #[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Receiver {
-    #[pyo3(get)]
-    pub direct_boot_aware: Option<String>,
-
-    #[pyo3(get)]
-    pub enabled: Option<String>,
-
+struct CompatibleScreen {
     #[pyo3(get)]
-    pub exported: Option<String>,
-
-    #[pyo3(get)]
-    pub icon: Option<String>,
-
+    screen_size: Option<String>,
     #[pyo3(get)]
-    pub label: Option<String>,
+    screen_density: Option<String>,
+}
 
-    #[pyo3(get)]
-    pub name: Option<String>,
+impl<'a> From<ApkCompatibleScreen<'a>> for CompatibleScreen {
+    fn from(screen: ApkCompatibleScreen<'a>) -> Self {
+        CompatibleScreen {
+            screen_size: screen.screen_size.map(String::from),
+            screen_density: screen.screen_density.map(String::from),
+        }
+    }
+}
+
+#[pymethods]
+impl CompatibleScreen {
+    fn __repr__(&self) -> String {
+        let mut parts = Vec::with_capacity(16);
+        macro_rules! push_field {
+            ($field:ident) => {
+                if let Some(ref v) = self.$field {
+                    parts.push(format!(concat!(stringify!($field), "={:?}"), v));
+                }
+            };
+        }
+        push_field!(screen_size);
+        push_field!(screen_density);
+
+        format!("CompatibleScreen({})", parts.join(", "))
+    }
+}
+
+#[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct Feature {
+    #[pyo3(get)]
+    name: Option<String>,
+    #[pyo3(get)]
+    required: Option<String>,
+    #[pyo3(get)]
+    gl_es_version: Option<String>,
+    #[pyo3(get)]
+    version: Option<String>,
+    #[pyo3(get)]
+    is_required: bool,
+    #[pyo3(get)]
+    opengl_es_version: Option<(u16, u16)>,
+}
+
+impl<'a> From<ApkFeature<'a>> for Feature {
+    fn from(feature: ApkFeature<'a>) -> Self {
+        Feature {
+            is_required: feature.is_required(),
+            opengl_es_version: feature.opengl_es_version(),
+            name: feature.name.map(String::from),
+            required: feature.required.map(String::from),
+            gl_es_version: feature.gl_es_version.map(String::from),
+            version: feature.version.map(String::from),
+        }
+    }
+}
+
+#[pymethods]
+impl Feature {
+    fn __repr__(&self) -> String {
+        let mut parts = Vec::with_capacity(16);
+        macro_rules! push_field {
+            ($field:ident) => {
+                if let Some(ref v) = self.$field {
+                    parts.push(format!(concat!(stringify!($field), "={:?}"), v));
+                }
+            };
+        }
+        push_field!(name);
+        push_field!(required);
+        push_field!(gl_es_version);
+        push_field!(version);
+
+        format!("Feature({})", parts.join(", "))
+    }
+}
+
+#[pyclass(eq, frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct SupportedAbis {
+    inner: ApkSupportedAbis,
+}
+
+#[pymethods]
+impl SupportedAbis {
+    #[getter]
+    fn abis(&self) -> Vec<String> {
+        self.inner.abis.clone()
+    }
+
+    #[getter]
+    fn extract_native_libs(&self) -> bool {
+        self.inner.extract_native_libs
+    }
+
+    #[getter]
+    fn multi_arch(&self) -> bool {
+        self.inner.multi_arch
+    }
+
+    fn has_native_code(&self) -> bool {
+        self.inner.has_native_code()
+    }
+
+    fn supports_64bit(&self) -> bool {
+        self.inner.supports_64bit()
+    }
+
+    fn primary_abi(&self) -> Option<&str> {
+        self.inner.primary_abi()
+    }
+
+    fn __repr__(&self) -> String {
+        let py_bool = |value: bool| if value { "True" } else { "False" };
+
+        format!(
+            "SupportedAbis(abis={:?}, extract_native_libs={}, multi_arch={})",
+            self.inner.abis,
+            py_bool(self.inner.extract_native_libs),
+            py_bool(self.inner.multi_arch),
+        )
+    }
+}
+
+#[pyclass(eq, frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct SizeEstimate {
+    #[pyo3(get)]
+    download_size: u64,
+    #[pyo3(get)]
+    install_size: u64,
+    #[pyo3(get)]
+    dex_size: u64,
+    #[pyo3(get)]
+    native_libs_size: u64,
+    #[pyo3(get)]
+    resources_size: u64,
+    #[pyo3(get)]
+    extract_native_libs: bool,
+}
+
+impl From<ApkSizeEstimate> for SizeEstimate {
+    fn from(size: ApkSizeEstimate) -> Self {
+        SizeEstimate {
+            download_size: size.download_size,
+            install_size: size.install_size,
+            dex_size: size.dex_size,
+            native_libs_size: size.native_libs_size,
+            resources_size: size.resources_size,
+            extract_native_libs: size.extract_native_libs,
+        }
+    }
+}
+
+#[pymethods]
+impl SizeEstimate {
+    fn __repr__(&self) -> String {
+        format!(
+            "SizeEstimate(download_size={}, install_size={}, dex_size={}, native_libs_size={}, resources_size={}, extract_native_libs={})",
+            self.download_size,
+            self.install_size,
+            self.dex_size,
+            self.native_libs_size,
+            self.resources_size,
+            if self.extract_native_libs {
+                "True"
+            } else {
+                "False"
+            },
+        )
+    }
+}
+
+#[pyclass(eq, frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct NormalizedFile {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    is_directory: bool,
+    #[pyo3(get)]
+    synthesized: bool,
+    #[pyo3(get)]
+    count: usize,
+}
+
+impl From<ApkNormalizedFile> for NormalizedFile {
+    fn from(file: ApkNormalizedFile) -> Self {
+        NormalizedFile {
+            name: file.name,
+            is_directory: file.is_directory,
+            synthesized: file.synthesized,
+            count: file.count,
+        }
+    }
+}
+
+#[pymethods]
+impl NormalizedFile {
+    fn __repr__(&self) -> String {
+        let py_bool = |value: bool| if value { "True" } else { "False" };
+
+        format!(
+            "NormalizedFile(name={:?}, is_directory={}, synthesized={}, count={})",
+            self.name,
+            py_bool(self.is_directory),
+            py_bool(self.synthesized),
+            self.count,
+        )
+    }
+}
+
+#[pyclass(eq, frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct IconKind {
+    #[pyo3(get)]
+    format: &'static str,
+    #[pyo3(get)]
+    adaptive_foreground: bool,
+}
+
+impl From<ApkIconKind> for IconKind {
+    fn from(kind: ApkIconKind) -> Self {
+        IconKind {
+            format: kind.format().name(),
+            adaptive_foreground: matches!(kind, ApkIconKind::AdaptiveForeground(_)),
+        }
+    }
+}
+
+#[pymethods]
+impl IconKind {
+    fn __repr__(&self) -> String {
+        format!(
+            "IconKind(format={:?}, adaptive_foreground={})",
+            self.format,
+            if self.adaptive_foreground {
+                "True"
+            } else {
+                "False"
+            },
+        )
+    }
+}
+
+#[pyclass(eq, frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct DexSummary {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    version: Option<u32>,
+    #[pyo3(get)]
+    file_size: u32,
+    #[pyo3(get)]
+    checksum: u32,
+    #[pyo3(get)]
+    checksum_valid: bool,
+    #[pyo3(get)]
+    signature_valid: bool,
+    #[pyo3(get)]
+    strings: usize,
+    #[pyo3(get)]
+    types: usize,
+    #[pyo3(get)]
+    protos: usize,
+    #[pyo3(get)]
+    fields: usize,
+    #[pyo3(get)]
+    methods: usize,
+    #[pyo3(get)]
+    classes: usize,
+    #[pyo3(get)]
+    map_items: BTreeMap<&'static str, u32>,
+}
+
+impl DexSummary {
+    fn new(name: &str, dex: &ApkDex) -> Self {
+        let header = dex.header();
+        let verification = dex.verify();
+
+        let mut map_items = BTreeMap::new();
+        for item in dex.map_list() {
+            *map_items.entry(item.type_name()).or_default() += item.size;
+        }
+
+        DexSummary {
+            name: name.to_string(),
+            version: header.version(),
+            file_size: header.file_size,
+            checksum: header.checksum,
+            checksum_valid: verification.is_checksum_valid(),
+            signature_valid: verification.is_signature_valid(),
+            strings: dex.strings_count(),
+            types: dex.types_count(),
+            protos: dex.proto_ids().len(),
+            fields: dex.field_ids().len(),
+            methods: dex.method_ids().len(),
+            classes: dex.classes().len(),
+            map_items,
+        }
+    }
+}
+
+#[pymethods]
+impl DexSummary {
+    fn __repr__(&self) -> String {
+        format!(
+            "DexSummary(name={:?}, version={}, checksum_valid={}, signature_valid={}, strings={}, types={}, methods={}, classes={})",
+            self.name,
+            self.version
+                .map_or_else(|| "None".to_string(), |v| v.to_string()),
+            if self.checksum_valid { "True" } else { "False" },
+            if self.signature_valid {
+                "True"
+            } else {
+                "False"
+            },
+            self.strings,
+            self.types,
+            self.methods,
+            self.classes,
+        )
+    }
+}
+
+#[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Provider {
+    #[pyo3(get)]
+    pub authorities: Option<String>,
+    #[pyo3(get)]
+    pub enabled: Option<String>,
+    #[pyo3(get)]
+    pub direct_boot_aware: Option<String>,
+    #[pyo3(get)]
+    pub exported: Option<String>,
+    #[pyo3(get)]
+    pub grant_uri_permissions: Option<String>,
+    #[pyo3(get)]
+    pub icon: Option<String>,
+    #[pyo3(get)]
+    pub init_order: Option<String>,
+    #[pyo3(get)]
+    pub label: Option<String>,
+    #[pyo3(get)]
+    pub multiprocess: Option<String>,
+    #[pyo3(get)]
+    pub name: Option<String>,
+    #[pyo3(get)]
+    pub permission: Option<String>,
+    #[pyo3(get)]
+    pub process: Option<String>,
+    #[pyo3(get)]
+    pub read_permission: Option<String>,
+    #[pyo3(get)]
+    pub syncable: Option<String>,
+    #[pyo3(get)]
+    pub write_permission: Option<String>,
+    #[pyo3(get)]
+    intent_filters: Vec<IntentFilter>,
+    #[pyo3(get)]
+    pub authority_list: Vec<String>,
+    #[pyo3(get)]
+    pub is_exported: bool,
+    #[pyo3(get)]
+    pub is_enabled: bool,
+}
+
+impl<'a> From<ApkProvider<'a>> for Provider {
+    fn from(provider: ApkProvider<'a>) -> Self {
+        Provider {
+            authority_list: provider.authorities().map(String::from).collect(),
+            is_exported: provider.is_exported(),
+            is_enabled: provider.is_enabled(),
+            authorities: provider.authorities.map(String::from),
+            enabled: provider.enabled.map(String::from),
+            direct_boot_aware: provider.direct_boot_aware.map(String::from),
+            exported: provider.exported.map(String::from),
+            grant_uri_permissions: provider.grant_uri_permissions.map(String::from),
+            icon: provider.icon.map(String::from),
+            init_order: provider.init_order.map(String::from),
+            label: provider.label.map(String::from),
+            multiprocess: provider.multiprocess.map(String::from),
+            name: provider.name.map(String::from),
+            permission: provider.permission.map(String::from),
+            process: provider.process.map(String::from),
+            read_permission: provider.read_permission.map(String::from),
+            syncable: provider.syncable.map(String::from),
+            write_permission: provider.write_permission.map(String::from),
+            intent_filters: provider
+                .intent_filters
+                .into_iter()
+                .map(IntentFilter::from)
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl Provider {
+    fn __repr__(&self) -> String {
+        let mut parts = Vec::with_capacity(16);
+        macro_rules! push_field {
+            ($field:ident) => {
+                if let Some(ref v) = self.$field {
+                    parts.push(format!(concat!(stringify!($field), "={:?}"), v));
+                }
+            };
+        }
+
+        push_field!(authorities);
+        push_field!(enabled);
+        push_field!(direct_boot_aware);
+        push_field!(exported);
+        push_field!(grant_uri_permissions);
+        push_field!(icon);
+        push_field!(init_order);
+        push_field!(label);
+        push_field!(multiprocess);
+        push_field!(name);
+        push_field!(permission);
+        push_field!(process);
+        push_field!(read_permission);
+        push_field!(syncable);
+        push_field!(write_permission);
+        if !self.intent_filters.is_empty() {
+            parts.push(format!("intent_filters={:?}", self.intent_filters));
+        }
+
+        format!("Provider({})", parts.join(", "))
+    }
+}
+
+#[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct Service {
+    #[pyo3(get)]
+    description: Option<String>,
+    #[pyo3(get)]
+    direct_boot_aware: Option<String>,
+    #[pyo3(get)]
+    enabled: Option<String>,
+    #[pyo3(get)]
+    exported: Option<String>,
+    #[pyo3(get)]
+    foreground_service_type: Option<String>,
+    #[pyo3(get)]
+    icon: Option<String>,
+    #[pyo3(get)]
+    isolated_process: Option<String>,
+    #[pyo3(get)]
+    label: Option<String>,
+    #[pyo3(get)]
+    name: Option<String>,
+    #[pyo3(get)]
+    permission: Option<String>,
+    #[pyo3(get)]
+    process: Option<String>,
+    #[pyo3(get)]
+    stop_with_task: Option<String>,
+    #[pyo3(get)]
+    intent_filters: Vec<IntentFilter>,
+}
+
+impl<'a> From<ApkService<'a>> for Service {
+    fn from(service: ApkService<'a>) -> Self {
+        Service {
+            description: service.description.map(String::from),
+            direct_boot_aware: service.direct_boot_aware.map(String::from),
+            enabled: service.enabled.map(String::from),
+            exported: service.exported.map(String::from),
+            foreground_service_type: service.foreground_service_type.map(String::from),
+            icon: service.icon.map(String::from),
+            isolated_process: service.isolated_process.map(String::from),
+            label: service.label.map(String::from),
+            name: service.name.map(String::from),
+            permission: service.permission.map(String::from),
+            process: service.process.map(String::from),
+            stop_with_task: service.stop_with_task.map(String::from),
+            intent_filters: service
+                .intent_filters
+                .into_iter()
+                .map(IntentFilter::from)
+                .collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl Service {
+    fn __repr__(&self) -> String {
+        let mut parts = Vec::with_capacity(16);
+        macro_rules! push_field {
+            ($field:ident) => {
+                if let Some(ref v) = self.$field {
+                    parts.push(format!(concat!(stringify!($field), "={:?}"), v));
+                }
+            };
+        }
+        push_field!(description);
+        push_field!(direct_boot_aware);
+        push_field!(enabled);
+        push_field!(exported);
+        push_field!(foreground_service_type);
+        push_field!(isolated_process);
+        push_field!(name);
+        push_field!(permission);
+        push_field!(process);
+        push_field!(stop_with_task);
+        if !self.intent_filters.is_empty() {
+            parts.push(format!("intent_filters={:?}", self.intent_filters));
+        }
+
+        format!("Service({})", parts.join(", "))
+    }
+}
+
+#[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct Receiver {
+    #[pyo3(get)]
+    pub direct_boot_aware: Option<String>,
+
+    #[pyo3(get)]
+    pub enabled: Option<String>,
+
+    #[pyo3(get)]
+    pub exported: Option<String>,
+
+    #[pyo3(get)]
+    pub icon: Option<String>,
+
+    #[pyo3(get)]
+    pub label: Option<String>,
+
+    #[pyo3(get)]
+    pub name: Option<String>,
 
     #[pyo3(get)]
     pub permission: Option<String>,
 
     #[pyo3(get)]
     pub process: Option<String>,
+
+    #[pyo3(get)]
+    pub intent_filters: Vec<IntentFilter>,
 }
 
 impl<'a> From<ApkReceiver<'a>> for Receiver {
@@ -693,6 +1566,11 @@ impl<'a> From<ApkReceiver<'a>> for Receiver {
             name: receiver.name.map(String::from),
             permission: receiver.permission.map(String::from),
             process: receiver.process.map(String::from),
+            intent_filters: receiver
+                .intent_filters
+                .into_iter()
+                .map(IntentFilter::from)
+                .collect(),
         }
     }
 }
@@ -716,11 +1594,56 @@ impl Receiver {
         push_field!(name);
         push_field!(permission);
         push_field!(process);
+        if !self.intent_filters.is_empty() {
+            parts.push(format!("intent_filters={:?}", self.intent_filters));
+        }
 
         format!("Receiver({})", parts.join(", "))
     }
 }
 
+#[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct Instrumentation {
+    #[pyo3(get)]
+    pub name: Option<String>,
+
+    #[pyo3(get)]
+    pub target_package: Option<String>,
+
+    #[pyo3(get)]
+    pub handle_profiling: Option<String>,
+}
+
+impl<'a> From<ApkInstrumentation<'a>> for Instrumentation {
+    fn from(instrumentation: ApkInstrumentation<'a>) -> Self {
+        Instrumentation {
+            name: instrumentation.name.map(String::from),
+            target_package: instrumentation.target_package.map(String::from),
+            handle_profiling: instrumentation.handle_profiling.map(String::from),
+        }
+    }
+}
+
+#[pymethods]
+impl Instrumentation {
+    fn __repr__(&self) -> String {
+        let mut parts = Vec::with_capacity(3);
+        macro_rules! push_field {
+            ($field:ident) => {
+                if let Some(ref v) = self.$field {
+                    parts.push(format!(concat!(stringify!($field), "={:?}"), v));
+                }
+            };
+        }
+        push_field!(name);
+        push_field!(target_package);
+        push_field!(handle_profiling);
+
+        format!("Instrumentation({})", parts.join(", "))
+    }
+}
+
 #[pyclass(frozen, from_py_object, module = "apk_info._apk_info")]
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct Attribution {
@@ -758,15 +1681,140 @@ impl Attribution {
     }
 }
 
-#[pyclass(name = "APK", unsendable, module = "apk_info._apk_info")]
+#[pyclass(module = "apk_info._apk_info")]
+struct FileIterator {
+    apk: Py<Apk>,
+    names: std::vec::IntoIter<String>,
+}
+
+#[pymethods]
+impl FileIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<(String, u64, FileCompressionType)>> {
+        let apk = self.apk.borrow(py);
+        let apkrs = apk.inner()?;
+
+        // entries that can't be read are skipped, the same as in `get_files_normalized`
+        for name in self.names.by_ref() {
+            if let Ok(info) = apkrs.get_file_info(&name) {
+                let compression = FileCompressionType::from(info.compression);
+                return Ok(Some((name, info.uncompressed_size, compression)));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Converts the xml element to `{"tag": ..., "attributes": {...}, "children": [...]}`
+fn element_to_dict<'py>(py: Python<'py>, element: &XmlElement) -> PyResult<Bound<'py, PyDict>> {
+    let attributes = PyDict::new(py);
+    for attribute in element.attributes() {
+        match attribute.prefix() {
+            Some(prefix) => {
+                attributes.set_item(format!("{prefix}:{}", attribute.name()), attribute.value())?
+            }
+            None => attributes.set_item(attribute.name(), attribute.value())?,
+        }
+    }
+
+    let children = PyList::empty(py);
+    for child in element.childrens() {
+        children.append(element_to_dict(py, child)?)?;
+    }
+
+    let dict = PyDict::new(py);
+    dict.set_item("tag", element.name())?;
+    dict.set_item("attributes", attributes)?;
+    dict.set_item("children", children)?;
+
+    Ok(dict)
+}
+
+/// Everything extracted from the apk, for `APK.to_dict` and `APK.to_json`
+#[derive(Serialize)]
+struct ApkDict<'a> {
+    package_name: Option<String>,
+    version_code: Option<String>,
+    version_name: Option<String>,
+    min_sdk_version: Option<String>,
+    target_sdk_version: u32,
+    max_sdk_version: Option<String>,
+    application_label: Option<String>,
+    application_name: Option<String>,
+    main_activity: Option<&'a str>,
+    md5: String,
+    sha1: String,
+    sha256: String,
+    permissions: BTreeSet<&'a str>,
+    declared_permissions: Vec<ApkPermission<'a>>,
+    features: BTreeSet<&'a str>,
+    libraries: BTreeSet<&'a str>,
+    supported_abis: ApkSupportedAbis,
+    activities: Vec<ApkActivity<'a>>,
+    activity_aliases: Vec<ApkActivityAlias<'a>>,
+    services: Vec<ApkService<'a>>,
+    receivers: Vec<ApkReceiver<'a>>,
+    providers: Vec<ApkProvider<'a>>,
+    signatures: Vec<&'a ZipSignature>,
+}
+
+impl<'a> ApkDict<'a> {
+    fn collect(apk: &'a ApkRust) -> Result<ApkDict<'a>, ApkRustError> {
+        Ok(ApkDict {
+            package_name: apk.get_package_name(),
+            version_code: apk.get_version_code(),
+            version_name: apk.get_version_name(),
+            min_sdk_version: apk.get_min_sdk_version(),
+            target_sdk_version: apk.get_target_sdk_version(),
+            max_sdk_version: apk.get_max_sdk_version(),
+            application_label: apk.get_application_label(),
+            application_name: apk.get_application_name(),
+            main_activity: apk.get_main_activity(),
+            md5: apk.get_md5(),
+            sha1: apk.get_sha1(),
+            sha256: apk.get_sha256(),
+            permissions: apk.get_permissions().collect(),
+            declared_permissions: apk.get_declared_permissions().collect(),
+            features: apk.get_features().collect(),
+            libraries: apk.get_libraries().collect(),
+            supported_abis: apk.get_supported_abis(),
+            activities: apk.get_activities().collect(),
+            activity_aliases: apk.get_activity_aliases().collect(),
+            services: apk.get_services().collect(),
+            receivers: apk.get_receivers().collect(),
+            providers: apk.get_providers().collect(),
+            signatures: apk
+                .get_signatures_ref()?
+                .iter()
+                .filter(|signature| !matches!(signature, ZipSignature::Unknown))
+                .collect(),
+        })
+    }
+}
+
+#[pyclass(name = "APK", module = "apk_info._apk_info")]
 struct Apk {
-    apkrs: ApkRust,
+    /// Parsed apk, [None] once the instance is closed
+    apkrs: Option<ApkRust>,
+}
+
+impl Apk {
+    fn inner(&self) -> PyResult<&ApkRust> {
+        self.apkrs
+            .as_ref()
+            .ok_or_else(|| PyValueError::new_err("I/O operation on closed APK"))
+    }
 }
 
 #[pymethods]
 impl Apk {
     #[new]
-    pub fn new(path: &Bound<'_, PyAny>) -> PyResult<Apk> {
+    #[pyo3(signature = (path, mmap=false))]
+    pub fn new(py: Python<'_>, path: &Bound<'_, PyAny>, mmap: bool) -> PyResult<Apk> {
         let resolved: Option<PathBuf> = if let Ok(s) = path.extract::<&str>() {
             Some(PathBuf::from(s))
         } else {
@@ -781,9 +1829,50 @@ impl Apk {
             )));
         }
 
-        let apkrs = ApkRust::new(&path).map_err(|e| APKError::new_err(e.to_string()))?;
+        let apkrs = py
+            .detach(|| {
+                if mmap {
+                    // SAFETY: opt-in, the caller promises the file is not modified while the APK is open
+                    unsafe { ApkRust::open_mmap(&path) }
+                } else {
+                    ApkRust::new(&path)
+                }
+            })
+            .map_err(|e| APKError::new_err(e.display_chain()))?;
+
+        Ok(Apk { apkrs: Some(apkrs) })
+    }
+
+    #[staticmethod]
+    pub fn from_bytes(py: Python<'_>, data: Vec<u8>) -> PyResult<Apk> {
+        let apkrs = py
+            .detach(|| ApkRust::from_bytes(data))
+            .map_err(|e| APKError::new_err(e.display_chain()))?;
+
+        Ok(Apk { apkrs: Some(apkrs) })
+    }
+
+    pub fn close(&mut self) {
+        self.apkrs = None;
+    }
+
+    #[getter]
+    pub fn closed(&self) -> bool {
+        self.apkrs.is_none()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
 
-        Ok(Apk { apkrs })
+    fn __exit__(
+        &mut self,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> bool {
+        self.close();
+        false
     }
 
     pub fn read(&self, filename: &Bound<'_, PyString>) -> PyResult<(Vec<u8>, FileCompressionType)> {
@@ -792,225 +1881,554 @@ impl Apk {
             Err(_) => return Err(PyValueError::new_err("bad filename")),
         };
 
-        match self.apkrs.read(filename) {
+        match self.inner()?.read(filename) {
             Ok((data, compression)) => Ok((data, FileCompressionType::from(compression))),
-            Err(e) => Err(APKError::new_err(e.to_string())),
+            Err(e) => Err(APKError::new_err(ApkRustError::from(e).display_chain())),
         }
     }
 
-    pub fn namelist(&self) -> Vec<&str> {
-        self.apkrs.namelist().collect()
+    pub fn iter_files(slf: Bound<'_, Self>) -> PyResult<FileIterator> {
+        let names = slf
+            .borrow()
+            .inner()?
+            .namelist()
+            .filter(|name| !name.ends_with('/'))
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        Ok(FileIterator {
+            apk: slf.unbind(),
+            names: names.into_iter(),
+        })
+    }
+
+    pub fn namelist(&self) -> PyResult<Vec<&str>> {
+        Ok(self.inner()?.namelist().collect())
     }
 
-    pub fn is_multidex(&self) -> bool {
-        self.apkrs.is_multidex()
+    pub fn get_sha256(&self) -> PyResult<String> {
+        Ok(self.inner()?.get_sha256())
     }
 
-    pub fn get_xml_string(&self) -> String {
-        self.apkrs.get_xml_string()
+    pub fn get_sha1(&self) -> PyResult<String> {
+        Ok(self.inner()?.get_sha1())
     }
 
-    pub fn get_resource_value(&self, name: &str) -> Option<String> {
-        self.apkrs.get_resource_value(name)
+    pub fn get_md5(&self) -> PyResult<String> {
+        Ok(self.inner()?.get_md5())
     }
 
-    pub fn get_attribute_value(&self, tag: &str, name: &str) -> Option<String> {
-        self.apkrs.get_attribute_value(tag, name)
+    pub fn get_files_normalized(&self) -> PyResult<Vec<NormalizedFile>> {
+        Ok(self
+            .inner()?
+            .get_files_normalized()
+            .into_iter()
+            .map(NormalizedFile::from)
+            .collect())
+    }
+
+    pub fn is_multidex(&self) -> PyResult<bool> {
+        Ok(self.inner()?.is_multidex())
+    }
+
+    pub fn dex_summaries(&self, py: Python<'_>) -> PyResult<Vec<DexSummary>> {
+        let apkrs = self.inner()?;
+
+        py.detach(|| {
+            apkrs
+                .dex_names()
+                .into_iter()
+                .map(|name| {
+                    let dex = apkrs.read_dex(name).map_err(|e| {
+                        APKError::new_err(format!("failed to parse {name}: {}", e.display_chain()))
+                    })?;
+
+                    Ok(DexSummary::new(name, &dex))
+                })
+                .collect()
+        })
     }
 
-    pub fn get_all_attribute_values<'a>(&'a self, tag: &'a str, name: &'a str) -> Vec<&'a str> {
-        self.apkrs.get_all_attribute_values(tag, name).collect()
+    pub fn get_dex_strings(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        let apkrs = self.inner()?;
+
+        Ok(py.detach(|| apkrs.get_dex_strings().collect()))
     }
 
-    pub fn get_package_name(&self) -> Option<String> {
-        self.apkrs.get_package_name()
+    pub fn get_dex_classes(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        let apkrs = self.inner()?;
+
+        Ok(py.detach(|| apkrs.get_dex_classes().collect()))
     }
 
-    pub fn get_shared_user_id(&self) -> Option<String> {
-        self.apkrs.get_shared_user_id()
+    pub fn get_dex_methods(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        let apkrs = self.inner()?;
+
+        Ok(py.detach(|| apkrs.get_dex_methods().collect()))
     }
 
-    pub fn get_shared_user_label(&self) -> Option<String> {
-        self.apkrs.get_shared_user_label()
+    pub fn get_xml_string(&self) -> PyResult<String> {
+        Ok(self.inner()?.get_xml_string())
     }
 
-    pub fn get_shared_user_max_sdk_version(&self) -> Option<String> {
-        self.apkrs.get_shared_user_max_sdk_version()
+    pub fn get_manifest_xml(&self) -> PyResult<String> {
+        Ok(self.inner()?.get_xml_string())
     }
 
-    pub fn get_version_code(&self) -> Option<String> {
-        self.apkrs.get_version_code()
+    pub fn get_manifest_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        element_to_dict(py, self.inner()?.get_manifest_root())
     }
 
-    pub fn get_version_name(&self) -> Option<String> {
-        self.apkrs.get_version_name()
+    pub fn get_resource_value(&self, name: &str) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_resource_value(name))
     }
 
-    pub fn get_build_version_code(&self) -> Option<String> {
-        self.apkrs.get_build_version_code()
+    pub fn get_attribute_value(&self, tag: &str, name: &str) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_attribute_value(tag, name))
     }
 
-    pub fn get_build_version_name(&self) -> Option<String> {
-        self.apkrs.get_build_version_name()
+    pub fn get_all_attribute_values<'a>(
+        &'a self,
+        tag: &'a str,
+        name: &'a str,
+    ) -> PyResult<Vec<&'a str>> {
+        Ok(self.inner()?.get_all_attribute_values(tag, name).collect())
     }
-    pub fn get_compile_sdk_version(&self) -> Option<String> {
-        self.apkrs.get_compile_sdk_version()
+
+    pub fn get_package_name(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_package_name())
     }
 
-    pub fn get_compile_sdk_version_codename(&self) -> Option<String> {
-        self.apkrs.get_compile_sdk_version_codename()
+    pub fn get_shared_user_id(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_shared_user_id())
     }
 
-    pub fn get_install_location(&self) -> Option<String> {
-        self.apkrs.get_install_location()
+    pub fn get_shared_user_label(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_shared_user_label())
     }
 
-    pub fn get_application_task_reparenting(&self) -> Option<String> {
-        self.apkrs.get_application_task_reparenting()
+    pub fn get_shared_user_max_sdk_version(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_shared_user_max_sdk_version())
     }
 
-    pub fn get_application_allow_backup(&self) -> Option<String> {
-        self.apkrs.get_application_allow_backup()
+    pub fn get_version_code(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_version_code())
     }
 
-    pub fn get_application_category(&self) -> Option<String> {
-        self.apkrs.get_application_category()
+    pub fn get_version_code_u64(&self) -> PyResult<Option<u64>> {
+        Ok(self.inner()?.get_version_code_u64())
     }
 
-    pub fn get_application_backup_agent(&self) -> Option<String> {
-        self.apkrs.get_application_backup_agent()
+    pub fn get_version_name(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_version_name())
     }
 
-    pub fn get_application_debuggable(&self) -> Option<String> {
-        self.apkrs.get_application_debuggable()
+    pub fn get_build_version_code(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_build_version_code())
     }
 
-    pub fn get_application_description(&self) -> Option<String> {
-        self.apkrs.get_application_description()
+    pub fn get_build_version_name(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_build_version_name())
+    }
+    pub fn get_compile_sdk_version(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_compile_sdk_version())
     }
 
-    pub fn get_application_icon(&self) -> Option<String> {
-        self.apkrs.get_application_icon()
+    pub fn get_compile_sdk_version_codename(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_compile_sdk_version_codename())
     }
 
-    pub fn get_application_logo(&self) -> Option<String> {
-        self.apkrs.get_application_logo()
+    pub fn get_install_location(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_install_location())
     }
 
-    pub fn get_application_label(&self) -> Option<String> {
-        self.apkrs.get_application_label()
+    pub fn get_application_task_reparenting(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_task_reparenting())
     }
 
-    pub fn get_application_name(&self) -> Option<String> {
-        self.apkrs.get_application_name()
+    pub fn get_application_allow_backup(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_allow_backup())
     }
 
-    pub fn get_attributions(&self) -> HashSet<Attribution> {
-        self.apkrs
+    pub fn get_application_category(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_category())
+    }
+
+    pub fn get_application_backup_agent(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_backup_agent())
+    }
+
+    pub fn get_application_debuggable(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_debuggable())
+    }
+
+    pub fn is_debuggable(&self) -> PyResult<Option<bool>> {
+        Ok(self.inner()?.is_debuggable())
+    }
+
+    pub fn get_application_test_only(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_test_only())
+    }
+
+    pub fn get_application_description(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_description())
+    }
+
+    pub fn get_application_icon(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_icon())
+    }
+
+    #[pyo3(signature = (density=None))]
+    pub fn get_icon_bytes(&self, density: Option<u16>) -> PyResult<Option<(Vec<u8>, IconKind)>> {
+        Ok(self
+            .inner()?
+            .get_icon_bytes(density.map(Density::from))
+            .map(|(data, kind)| (data, IconKind::from(kind))))
+    }
+
+    pub fn get_application_logo(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_logo())
+    }
+
+    pub fn get_application_label(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_label())
+    }
+
+    pub fn get_application_label_for_locale(&self, locale: &str) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_label_for_locale(locale))
+    }
+
+    pub fn get_application_labels(&self) -> PyResult<BTreeMap<String, String>> {
+        Ok(self.inner()?.get_application_labels())
+    }
+
+    pub fn get_application_name(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_application_name())
+    }
+
+    pub fn get_attributions(&self) -> PyResult<HashSet<Attribution>> {
+        Ok(self
+            .inner()?
             .get_attributions()
             .map(Attribution::from)
-            .collect()
+            .collect())
+    }
+
+    pub fn get_instrumentations(&self) -> PyResult<Vec<Instrumentation>> {
+        Ok(self
+            .inner()?
+            .get_instrumentations()
+            .map(Instrumentation::from)
+            .collect())
     }
 
-    pub fn get_permissions(&self) -> HashSet<&str> {
-        self.apkrs.get_permissions().collect()
+    pub fn get_permissions(&self) -> PyResult<HashSet<&str>> {
+        Ok(self.inner()?.get_permissions().collect())
     }
 
-    pub fn get_permissions_sdk23(&self) -> HashSet<&str> {
-        self.apkrs.get_permissions_sdk23().collect()
+    pub fn get_permissions_sdk23(&self) -> PyResult<HashSet<&str>> {
+        Ok(self.inner()?.get_permissions_sdk23().collect())
     }
 
-    pub fn get_min_sdk_version(&self) -> Option<String> {
-        self.apkrs.get_min_sdk_version()
+    pub fn get_min_sdk_version(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_min_sdk_version())
     }
 
-    pub fn get_target_sdk_version(&self) -> u32 {
-        self.apkrs.get_target_sdk_version()
+    pub fn get_min_sdk(&self) -> PyResult<Option<u32>> {
+        Ok(self.inner()?.get_min_sdk())
     }
 
-    pub fn get_max_sdk_version(&self) -> Option<String> {
-        self.apkrs.get_max_sdk_version()
+    pub fn get_target_sdk_version(&self) -> PyResult<u32> {
+        Ok(self.inner()?.get_target_sdk_version())
     }
 
-    pub fn get_libraries(&self) -> HashSet<&str> {
-        self.apkrs.get_libraries().collect()
+    pub fn get_max_sdk_version(&self) -> PyResult<Option<String>> {
+        Ok(self.inner()?.get_max_sdk_version())
     }
 
-    pub fn get_native_libraries(&self) -> HashSet<&str> {
-        self.apkrs.get_native_libraries().collect()
+    pub fn get_libraries(&self) -> PyResult<HashSet<&str>> {
+        Ok(self.inner()?.get_libraries().collect())
     }
 
-    pub fn get_features(&self) -> HashSet<&str> {
-        self.apkrs.get_features().collect()
+    pub fn get_native_libraries(&self) -> PyResult<HashSet<&str>> {
+        Ok(self.inner()?.get_native_libraries().collect())
     }
 
-    pub fn is_automotive(&self) -> bool {
-        self.apkrs.is_automotive()
+    pub fn get_features(&self) -> PyResult<HashSet<&str>> {
+        Ok(self.inner()?.get_features().collect())
     }
 
-    pub fn is_leanback(&self) -> bool {
-        self.apkrs.is_leanback()
+    pub fn get_features_detailed(&self) -> PyResult<Vec<Feature>> {
+        Ok(self
+            .inner()?
+            .get_features_detailed()
+            .map(Feature::from)
+            .collect())
     }
 
-    pub fn is_wearable(&self) -> bool {
-        self.apkrs.is_wearable()
+    pub fn is_automotive(&self) -> PyResult<bool> {
+        Ok(self.inner()?.is_automotive())
     }
 
-    pub fn is_chromebook(&self) -> bool {
-        self.apkrs.is_chromebook()
+    pub fn is_leanback(&self) -> PyResult<bool> {
+        Ok(self.inner()?.is_leanback())
     }
 
-    pub fn get_declared_permissions(&self) -> HashSet<Permission> {
-        self.apkrs
+    pub fn is_wearable(&self) -> PyResult<bool> {
+        Ok(self.inner()?.is_wearable())
+    }
+
+    pub fn is_chromebook(&self) -> PyResult<bool> {
+        Ok(self.inner()?.is_chromebook())
+    }
+
+    pub fn is_test_artifact(&self) -> PyResult<bool> {
+        Ok(self.inner()?.is_test_artifact())
+    }
+
+    pub fn get_supports_screens(&self) -> PyResult<Option<SupportsScreens>> {
+        Ok(self
+            .inner()?
+            .get_supports_screens()
+            .map(SupportsScreens::from))
+    }
+
+    pub fn get_compatible_screens(&self) -> PyResult<Vec<CompatibleScreen>> {
+        Ok(self
+            .inner()?
+            .get_compatible_screens()
+            .map(CompatibleScreen::from)
+            .collect())
+    }
+
+    pub fn get_min_supported_screens(&self) -> PyResult<Option<&'static str>> {
+        Ok(self.inner()?.get_min_supported_screens())
+    }
+
+    pub fn get_declared_permissions(&self) -> PyResult<HashSet<Permission>> {
+        Ok(self
+            .inner()?
             .get_declared_permissions()
             .map(Permission::from)
-            .collect()
+            .collect())
+    }
+
+    pub fn get_declared_permissions_detailed(&self) -> PyResult<Vec<DeclaredPermission>> {
+        Ok(self
+            .inner()?
+            .get_declared_permissions_detailed()
+            .map(DeclaredPermission::from)
+            .collect())
+    }
+
+    pub fn permissions_by_protection(&self) -> PyResult<HashMap<&'static str, Vec<&str>>> {
+        let permissions = self.inner()?.get_permissions_by_protection();
+
+        Ok(HashMap::from([
+            ("dangerous", permissions.dangerous),
+            ("normal", permissions.normal),
+            ("signature", permissions.signature),
+            ("unknown", permissions.unknown),
+        ]))
     }
 
-    pub fn get_main_activity(&self) -> Option<&str> {
-        self.apkrs.get_main_activity()
+    pub fn get_main_activity(&self) -> PyResult<Option<&str>> {
+        Ok(self.inner()?.get_main_activity())
     }
 
     // Use a vector instead of a hashset to preserve the order of the found activities
-    pub fn get_main_activities(&self) -> Vec<&str> {
-        self.apkrs.get_main_activities().collect()
+    pub fn get_main_activities(&self) -> PyResult<Vec<&str>> {
+        Ok(self.inner()?.get_main_activities().collect())
     }
 
-    pub fn get_activities(&self) -> Vec<Activity> {
-        self.apkrs.get_activities().map(Activity::from).collect()
+    pub fn get_manifest_diagnostics(&self) -> PyResult<Vec<String>> {
+        Ok(self
+            .inner()?
+            .get_manifest_diagnostics()
+            .iter()
+            .map(|d| d.to_string())
+            .collect())
     }
 
-    pub fn get_activity_aliases(&self) -> Vec<ActivityAlias> {
-        self.apkrs
+    pub fn get_archive_anomalies(&self) -> PyResult<Vec<String>> {
+        Ok(self
+            .inner()?
+            .get_archive_anomalies()
+            .iter()
+            .map(|a| a.to_string())
+            .collect())
+    }
+
+    pub fn get_anomalies(&self) -> PyResult<Vec<String>> {
+        Ok(self
+            .inner()?
+            .get_anomalies()
+            .iter()
+            .map(|a| a.to_string())
+            .collect())
+    }
+
+    pub fn get_unreferenced_files(&self, py: Python<'_>) -> PyResult<Vec<&str>> {
+        let apkrs = self.inner()?;
+
+        py.detach(|| apkrs.get_unreferenced_files())
+            .map_err(|e| APKError::new_err(e.display_chain()))
+    }
+
+    pub fn prepended_data(&self) -> PyResult<&[u8]> {
+        Ok(self.inner()?.prepended_data())
+    }
+
+    pub fn archive_comment(&self) -> PyResult<&[u8]> {
+        Ok(self.inner()?.archive_comment())
+    }
+
+    pub fn get_activities(&self) -> PyResult<Vec<Activity>> {
+        Ok(self.inner()?.get_activities().map(Activity::from).collect())
+    }
+
+    pub fn get_activity_aliases(&self) -> PyResult<Vec<ActivityAlias>> {
+        Ok(self
+            .inner()?
             .get_activity_aliases()
             .map(ActivityAlias::from)
-            .collect()
+            .collect())
     }
 
-    pub fn get_services(&self) -> Vec<Service> {
-        self.apkrs.get_services().map(Service::from).collect()
+    pub fn get_services(&self) -> PyResult<Vec<Service>> {
+        Ok(self.inner()?.get_services().map(Service::from).collect())
     }
 
-    pub fn get_receivers(&self) -> Vec<Receiver> {
-        self.apkrs.get_receivers().map(Receiver::from).collect()
+    pub fn get_receivers(&self) -> PyResult<Vec<Receiver>> {
+        Ok(self.inner()?.get_receivers().map(Receiver::from).collect())
     }
 
-    pub fn get_providers(&self) -> Vec<Provider> {
-        self.apkrs.get_providers().map(Provider::from).collect()
+    pub fn get_providers(&self) -> PyResult<Vec<Provider>> {
+        Ok(self.inner()?.get_providers().map(Provider::from).collect())
     }
 
     pub fn get_signatures<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, Signature>>> {
-        Ok(self
-            .apkrs
-            .get_signatures()
-            .map_err(|e| APKError::new_err(format!("failed to get signatures: {:?}", e)))?
-            .into_iter()
-            .filter_map(|x| Signature::from(py, x))
+        let apkrs = self.inner()?;
+
+        Ok(py
+            .detach(|| apkrs.get_signatures_ref())
+            .map_err(|e| {
+                APKError::new_err(format!("failed to get signatures: {}", e.display_chain()))
+            })?
+            .iter()
+            .filter_map(|x| Signature::from(py, x.clone()))
             .collect())
     }
 
-    pub fn get_native_codes(&self) -> Vec<String> {
-        self.apkrs.get_native_codes()
+    pub fn get_signing_block_entries(&self) -> PyResult<Vec<(u32, &[u8])>> {
+        Ok(self.inner()?.get_signing_block_entries())
+    }
+
+    pub fn get_native_codes(&self) -> PyResult<Vec<String>> {
+        Ok(self.inner()?.get_native_codes())
+    }
+
+    pub fn get_supported_abis(&self) -> PyResult<SupportedAbis> {
+        Ok(SupportedAbis {
+            inner: self.inner()?.get_supported_abis(),
+        })
+    }
+
+    pub fn extract(&self, py: Python<'_>, member: &str, path: PathBuf) -> PyResult<PathBuf> {
+        let apkrs = self.inner()?;
+        let Some(file) = extract_paths([member]).pop() else {
+            return Err(PyValueError::new_err(format!(
+                "can't extract {member:?}: not a file"
+            )));
+        };
+
+        let count = py
+            .detach(|| apkrs.extract_to(&path, |name| name == member, None, None))
+            .map_err(|e| APKError::new_err(e.display_chain()))?;
+        if count == 0 {
+            return Err(APKError::new_err(format!("file not found: {member:?}")));
+        }
+
+        Ok(path.join(file.path))
+    }
+
+    #[pyo3(signature = (output, pattern=None, progress=None))]
+    pub fn extract_all(
+        &self,
+        output: PathBuf,
+        pattern: Option<&str>,
+        progress: Option<Bound<'_, PyAny>>,
+    ) -> PyResult<usize> {
+        let pattern = pattern
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(ApkRustError::from(e).display_chain()))?;
+        let token = CancellationToken::new();
+        let callback_error: RefCell<Option<PyErr>> = RefCell::new(None);
+
+        // exception raised from the callback cancels the extraction
+        let sink = |stage: Stage, current: u64, total: u64| {
+            if let Some(callback) = &progress
+                && let Err(e) = callback.call1((stage.to_string(), current, total))
+            {
+                callback_error.borrow_mut().replace(e);
+                token.cancel();
+            }
+        };
+
+        match self.inner()?.extract_to(
+            &output,
+            |name| {
+                pattern
+                    .as_ref()
+                    .is_none_or(|pattern| pattern.is_match(name))
+            },
+            Some(&sink),
+            Some(&token),
+        ) {
+            Ok(count) => Ok(count),
+            Err(ApkRustError::Cancelled) if callback_error.borrow().is_some() => {
+                Err(callback_error.take().expect("checked above"))
+            }
+            Err(e) => Err(APKError::new_err(e.display_chain())),
+        }
+    }
+
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let json = self.to_json(py, None)?;
+
+        py.import("json")?.call_method1("loads", (json,))
+    }
+
+    #[pyo3(signature = (indent=None))]
+    pub fn to_json(&self, py: Python<'_>, indent: Option<usize>) -> PyResult<String> {
+        let apkrs = self.inner()?;
+
+        py.detach(|| {
+            let dict = ApkDict::collect(apkrs).map_err(|e| {
+                APKError::new_err(format!("failed to get signatures: {}", e.display_chain()))
+            })?;
+
+            let result = match indent {
+                Some(indent) => {
+                    let indent = " ".repeat(indent);
+                    let formatter =
+                        serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+                    let mut out = Vec::new();
+                    let mut serializer =
+                        serde_json::Serializer::with_formatter(&mut out, formatter);
+                    dict.serialize(&mut serializer)
+                        .map(|_| String::from_utf8(out).expect("serde_json writes utf-8"))
+                }
+                None => serde_json::to_string(&dict),
+            };
+
+            result.map_err(|e| APKError::new_err(format!("failed to serialize apk: {e}")))
+        })
+    }
+
+    pub fn estimated_install_size(&self) -> PyResult<SizeEstimate> {
+        Ok(SizeEstimate::from(self.inner()?.estimated_install_size()))
     }
 }
 
@@ -1021,6 +2439,8 @@ fn apk_info(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("APKError", m.py().get_type::<APKError>())?;
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add_class::<CertificateInfo>()?;
+    m.add_class::<LineageNode>()?;
+    m.add_class::<IntentData>()?;
     m.add_class::<IntentFilter>()?;
     m.add_class::<Activity>()?;
     m.add_class::<ActivityAlias>()?;
@@ -1030,6 +2450,17 @@ fn apk_info(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<Service>()?;
     m.add_class::<Signature>()?;
     m.add_class::<FileCompressionType>()?;
+    m.add_class::<SizeEstimate>()?;
+    m.add_class::<SupportedAbis>()?;
+    m.add_class::<NormalizedFile>()?;
+    m.add_class::<FileIterator>()?;
+    m.add_class::<DexSummary>()?;
+    m.add_class::<IconKind>()?;
+    m.add_class::<Instrumentation>()?;
+    m.add_class::<SupportsScreens>()?;
+    m.add_class::<CompatibleScreen>()?;
+    m.add_class::<Feature>()?;
+    m.add_class::<DeclaredPermission>()?;
 
     m.add_class::<Apk>()?;
     Ok(())